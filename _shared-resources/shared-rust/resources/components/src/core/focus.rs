@@ -0,0 +1,178 @@
+// Focus Management
+// Tracks which widget owns keyboard focus and routes key events to it
+//
+// Usage:
+//   let mut focus = FocusManager::new(vec![Pane::List, Pane::Preview]);
+//   match focus.route_key(&key) {
+//       KeyRoute::Deliver(Pane::List) => list.handle_key(&key),
+//       KeyRoute::Traversed(_) => {} // Tab/Shift-Tab moved focus
+//       ...
+//   }
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{style::Style, widgets::BorderType};
+
+use crate::utilities::get_border_style;
+
+/// Where a key event should go
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyRoute<T> {
+    /// Tab/Shift-Tab moved focus to this widget
+    Traversed(T),
+    /// Deliver the key to this widget
+    Deliver(T),
+    /// Nothing can receive focus
+    Unrouted,
+}
+
+/// Focus ring with a stack of modal focus owners (popups, dialogs)
+/// While a modal is open it receives every key and ring traversal is suspended
+#[derive(Debug, Clone)]
+pub struct FocusManager<T> {
+    ring: Vec<T>,
+    index: usize,
+    modals: Vec<T>,
+}
+
+impl<T: Copy + PartialEq> FocusManager<T> {
+    /// Create a focus ring; the first widget starts focused
+    pub fn new(ring: Vec<T>) -> Self {
+        Self {
+            ring,
+            index: 0,
+            modals: Vec::new(),
+        }
+    }
+
+    /// Replace the focus ring, keeping the focused widget if it is still present
+    pub fn set_ring(&mut self, ring: Vec<T>) {
+        let current = self.ring.get(self.index).copied();
+        self.ring = ring;
+        self.index = current
+            .and_then(|id| self.ring.iter().position(|r| *r == id))
+            .unwrap_or(0);
+    }
+
+    /// Widgets in traversal order
+    pub fn ring(&self) -> &[T] {
+        &self.ring
+    }
+
+    /// Widget that currently receives keys (top modal, else the ring selection)
+    pub fn focused(&self) -> Option<T> {
+        self.modals.last().copied().or_else(|| self.ring.get(self.index).copied())
+    }
+
+    pub fn is_focused(&self, id: T) -> bool {
+        self.focused() == Some(id)
+    }
+
+    /// Focus a widget in the ring; returns false if it is not part of the ring
+    pub fn focus(&mut self, id: T) -> bool {
+        match self.ring.iter().position(|r| *r == id) {
+            Some(index) => {
+                self.index = index;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn focus_next(&mut self) -> Option<T> {
+        if !self.ring.is_empty() {
+            self.index = (self.index + 1) % self.ring.len();
+        }
+        self.focused()
+    }
+
+    pub fn focus_prev(&mut self) -> Option<T> {
+        if !self.ring.is_empty() {
+            self.index = (self.index + self.ring.len() - 1) % self.ring.len();
+        }
+        self.focused()
+    }
+
+    /// Open a modal focus owner on top of the ring
+    pub fn push_modal(&mut self, id: T) {
+        self.modals.push(id);
+    }
+
+    /// Close the top modal
+    pub fn pop_modal(&mut self) -> Option<T> {
+        self.modals.pop()
+    }
+
+    /// Close a specific modal wherever it is in the stack
+    pub fn remove_modal(&mut self, id: T) {
+        self.modals.retain(|m| *m != id);
+    }
+
+    pub fn has_modal(&self) -> bool {
+        !self.modals.is_empty()
+    }
+
+    /// Route a key: Tab/Shift-Tab traverse the ring (unless a modal is open),
+    /// everything else goes to the focused widget
+    pub fn route_key(&mut self, key: &KeyEvent) -> KeyRoute<T> {
+        if !self.has_modal() {
+            let traversed = match key.code {
+                KeyCode::Tab => Some(self.focus_next()),
+                KeyCode::BackTab => Some(self.focus_prev()),
+                _ => None,
+            };
+            if let Some(target) = traversed {
+                return target.map(KeyRoute::Traversed).unwrap_or(KeyRoute::Unrouted);
+            }
+        }
+
+        self.focused().map(KeyRoute::Deliver).unwrap_or(KeyRoute::Unrouted)
+    }
+
+    /// Border style for a widget: highlighted when focused, dimmed behind a modal
+    pub fn border_style(&self, id: T) -> (Style, BorderType) {
+        let is_focused = self.is_focused(id);
+        let dimmed = self.has_modal() && !is_focused;
+        get_border_style(is_focused, dimmed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Pane {
+        Left,
+        Right,
+        Popup,
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_tab_traversal_and_modal_capture() {
+        let mut focus = FocusManager::new(vec![Pane::Left, Pane::Right]);
+        assert_eq!(focus.route_key(&key(KeyCode::Tab)), KeyRoute::Traversed(Pane::Right));
+        assert_eq!(focus.route_key(&key(KeyCode::BackTab)), KeyRoute::Traversed(Pane::Left));
+
+        focus.push_modal(Pane::Popup);
+        assert_eq!(focus.route_key(&key(KeyCode::Tab)), KeyRoute::Deliver(Pane::Popup));
+        assert!(!focus.is_focused(Pane::Left));
+
+        focus.pop_modal();
+        assert_eq!(focus.route_key(&key(KeyCode::Char('x'))), KeyRoute::Deliver(Pane::Left));
+    }
+
+    #[test]
+    fn test_set_ring_keeps_focus() {
+        let mut focus = FocusManager::new(vec![Pane::Left, Pane::Right]);
+        focus.focus(Pane::Right);
+        focus.set_ring(vec![Pane::Popup, Pane::Right]);
+        assert_eq!(focus.focused(), Some(Pane::Right));
+        focus.set_ring(vec![Pane::Left]);
+        assert_eq!(focus.focused(), Some(Pane::Left));
+    }
+}
@@ -1,8 +1,10 @@
 // Core infrastructure module
 // Provides foundational systems that other modules depend on
 
+pub mod focus;
 pub mod rect_handle;
 
+pub use focus::{FocusManager, KeyRoute};
 pub use rect_handle::{
     RectHandle, RectRegistry, RectMetrics,
     TabBarState, TabConfigData, TabBarConfigData,
@@ -117,6 +117,8 @@ pub struct TabBarConfigData {
     pub min_tab_width: u16,
     /// Show tooltips
     pub tab_tooltips: bool,
+    /// Wrap around when navigating past the first/last tab
+    pub wrap: bool,
 }
 
 /// Alignment configuration data
@@ -153,6 +155,10 @@ pub struct TabConfigData {
     pub active: bool,
     /// State for state-based coloring (for tab bars with type: state)
     pub state: TabState,
+    /// Disabled tabs are skipped by navigation and cannot be activated
+    pub disabled: bool,
+    /// Optional badge rendered after the name, e.g. a change count: "DIFFS (12)"
+    pub badge: Option<String>,
 }
 
 /// Registry for tracking rendered rectangles with handles
@@ -166,6 +172,10 @@ pub struct RectRegistry {
     next_id: u64,
     /// Tab bar state storage (keyed by handle ID)
     tab_bar_states: HashMap<u64, TabBarState>,
+    /// Overlay rects (popups, dialogs) registered this frame, with their z-order
+    layers: Vec<(u16, Rect)>,
+    /// Overlay rects from the previous frame (read before this frame's overlays are drawn)
+    previous_layers: Vec<(u16, Rect)>,
 }
 
 impl RectRegistry {
@@ -176,9 +186,43 @@ impl RectRegistry {
             name_to_handle: HashMap::new(),
             next_id: 1, // Start at 1, 0 can be used as invalid handle
             tab_bar_states: HashMap::new(),
+            layers: Vec::new(),
+            previous_layers: Vec::new(),
         }
     }
 
+    /// Start a new frame: this frame's overlays become the previous frame's
+    /// Call once per frame before rendering
+    pub fn begin_frame(&mut self) {
+        self.previous_layers = std::mem::take(&mut self.layers);
+    }
+
+    /// Register an overlay rectangle at a z-order above the base layer (z = 0)
+    /// Everything outside the topmost layer is dimmed by `apply_dimming`
+    pub fn register_layer(&mut self, name: Option<&str>, rect: Rect, z: u16) -> RectHandle {
+        self.push_layer(rect, z);
+        self.register(name, rect)
+    }
+
+    /// Record an overlay rectangle for this frame without giving it a handle
+    pub fn push_layer(&mut self, rect: Rect, z: u16) {
+        self.layers.push((z.max(1), rect));
+    }
+
+    /// Rects of the highest z-order registered this frame (empty when no overlay is open)
+    pub fn top_layer(&self) -> Vec<Rect> {
+        let Some(top) = self.layers.iter().map(|(z, _)| *z).max() else {
+            return Vec::new();
+        };
+        self.layers.iter().filter(|(z, _)| *z == top).map(|(_, rect)| *rect).collect()
+    }
+
+    /// Whether an overlay is open this frame or was open in the previous one
+    /// Lets widgets rendered before the overlay dim themselves without knowing about it
+    pub fn has_overlay(&self) -> bool {
+        !self.layers.is_empty() || !self.previous_layers.is_empty()
+    }
+
     /// Register a rectangle and return a handle
     /// If a name is provided and a rectangle with that name already exists, it will be updated
     pub fn register(&mut self, name: Option<&str>, rect: Rect) -> RectHandle {
@@ -427,10 +471,11 @@ impl RectRegistry {
         self.tab_bar_states.get_mut(&handle.0)
     }
 
-    /// Update active tab index for a tab bar handle
+    /// Update active tab index for a tab bar handle (disabled tabs cannot be activated)
     pub fn set_active_tab(&mut self, handle: RectHandle, active_index: usize) -> bool {
         if let Some(state) = self.tab_bar_states.get_mut(&handle.0) {
-            if active_index < state.tab_count {
+            let disabled = state.tab_configs.get(active_index).is_some_and(|t| t.disabled);
+            if active_index < state.tab_count && !disabled {
                 state.active_tab_index = active_index;
                 return true;
             }
@@ -440,7 +485,7 @@ impl RectRegistry {
 
     /// Navigate to a tab with minimal debouncing to prevent hardware bounce
     /// Key repeat events are filtered at the event handler level, so this is just for hardware safety
-    /// Returns true if navigation occurred, false if debounced
+    /// Returns true if navigation occurred, false if debounced or no enabled tab lies in that direction
     pub fn navigate_tab(&mut self, handle: RectHandle, direction: i32) -> bool {
         const DEBOUNCE_DURATION: Duration = Duration::from_millis(50); // Reduced from 150ms since key repeats are filtered at event level
         
@@ -453,22 +498,32 @@ impl RectRegistry {
                 }
             }
             
-            // Calculate new index
-            let current_index = state.active_tab_index;
-            let new_index = if direction < 0 {
-                // Navigate left/previous
-                if current_index > 0 {
-                    current_index - 1
+            // Step to the next enabled tab, wrapping around only if configured
+            let count = state.tab_count;
+            let mut candidate = state.active_tab_index;
+            let mut next_index = None;
+            for _ in 1..count {
+                candidate = if direction < 0 {
+                    match candidate.checked_sub(1) {
+                        Some(index) => index,
+                        None if state.config.wrap => count - 1,
+                        None => break,
+                    }
+                } else if candidate + 1 < count {
+                    candidate + 1
+                } else if state.config.wrap {
+                    0
                 } else {
-                    state.tab_count - 1
+                    break;
+                };
+                if !state.tab_configs.get(candidate).is_some_and(|t| t.disabled) {
+                    next_index = Some(candidate);
+                    break;
                 }
-            } else {
-                // Navigate right/next
-                (current_index + 1) % state.tab_count
-            };
+            }
             
             // Update tab and record navigation time
-            if new_index < state.tab_count {
+            if let Some(new_index) = next_index {
                 state.active_tab_index = new_index;
                 state.last_navigation_time = Some(now);
                 return true;
@@ -489,6 +544,45 @@ impl RectRegistry {
         false
     }
 
+    /// Enable or disable a tab; disabling the active tab moves to the next enabled one
+    /// Returns true if the tab exists
+    pub fn set_tab_disabled(&mut self, handle: RectHandle, tab_index: usize, disabled: bool) -> bool {
+        let Some(state) = self.tab_bar_states.get_mut(&handle.0) else {
+            return false;
+        };
+        let Some(tab) = state.tab_configs.get_mut(tab_index) else {
+            return false;
+        };
+        tab.disabled = disabled;
+
+        if disabled && state.active_tab_index == tab_index {
+            let count = state.tab_configs.len();
+            if let Some(next) = (1..count)
+                .map(|offset| (tab_index + offset) % count)
+                .find(|&index| !state.tab_configs[index].disabled)
+            {
+                state.active_tab_index = next;
+            }
+        }
+        true
+    }
+
+    /// Set or clear the badge shown after a tab's name
+    /// Returns true if the tab exists
+    pub fn set_tab_badge(&mut self, handle: RectHandle, tab_index: usize, badge: Option<String>) -> bool {
+        match self
+            .tab_bar_states
+            .get_mut(&handle.0)
+            .and_then(|state| state.tab_configs.get_mut(tab_index))
+        {
+            Some(tab) => {
+                tab.badge = badge;
+                true
+            }
+            None => false,
+        }
+    }
+
     /// Get state for a specific tab
     pub fn get_tab_state(&self, handle: RectHandle, tab_index: usize) -> Option<TabState> {
         if let Some(tab_bar_state) = self.tab_bar_states.get(&handle.0) {
@@ -509,6 +603,8 @@ impl RectRegistry {
         self.handles.clear();
         self.name_to_handle.clear();
         self.tab_bar_states.clear();
+        self.layers.clear();
+        self.previous_layers.clear();
         self.next_id = 1; // Reset ID counter
     }
 
@@ -637,6 +733,63 @@ mod tests {
         let metrics = registry.get_metrics(handle).unwrap();
         assert_eq!(metrics.x, 12); // 15 - 3
     }
+
+    #[test]
+    fn test_tab_navigation_skips_disabled_and_respects_wrap() {
+        let mut registry = RectRegistry::new();
+        let handle = registry.register(Some("tabs"), Rect::default());
+        let tab = |name: &str| TabConfigData {
+            id: name.to_string(),
+            name: name.to_string(),
+            active: false,
+            state: TabState::Default,
+            disabled: false,
+            badge: None,
+        };
+        registry.set_tab_bar_state(handle, TabBarState {
+            active_tab_index: 0,
+            tab_count: 3,
+            tab_names: vec!["A".into(), "B".into(), "C".into()],
+            tab_configs: vec![tab("A"), tab("B"), tab("C")],
+            config: TabBarConfigData {
+                hwnd: "tabs".into(),
+                anchor: "content".into(),
+                style: "tab".into(),
+                color: "cyan".into(),
+                tab_bar_type: None,
+                state_colors: None,
+                alignment: AlignmentConfigData {
+                    vertical: "top".into(),
+                    horizontal: "left".into(),
+                    offset_x: 0,
+                    offset_y: 0,
+                },
+                min_tab_width: 8,
+                tab_tooltips: true,
+                wrap: false,
+            },
+            last_navigation_time: None,
+        });
+        let navigate = |registry: &mut RectRegistry, direction: i32| {
+            registry.get_tab_bar_state_mut(handle).unwrap().last_navigation_time = None;
+            registry.navigate_tab(handle, direction)
+        };
+
+        registry.set_tab_disabled(handle, 1, true);
+        assert!(!registry.set_active_tab(handle, 1));
+        assert!(navigate(&mut registry, 1));
+        assert_eq!(registry.get_active_tab(handle), Some(2));
+
+        // Without wrap, the last tab stays put
+        assert!(!navigate(&mut registry, 1));
+        registry.get_tab_bar_state_mut(handle).unwrap().config.wrap = true;
+        assert!(navigate(&mut registry, 1));
+        assert_eq!(registry.get_active_tab(handle), Some(0));
+
+        // Disabling the active tab moves to the next enabled one
+        registry.set_tab_disabled(handle, 0, true);
+        assert_eq!(registry.get_active_tab(handle), Some(2));
+    }
 }
 
 /// Helper function to render a widget and register its rectangle
@@ -18,6 +18,69 @@ pub struct BaseLayoutConfig {
     pub tabs: Vec<TabConfig>,
     pub global_bindings: Vec<BindingConfig>,
     pub status_bar: StatusBarConfig,
+    /// Regions carved out of the main container when visible (sidebars, log panes)
+    pub regions: Vec<RegionConfig>,
+}
+
+impl BaseLayoutConfig {
+    /// Show or hide an optional region; returns false if no region has that id
+    pub fn set_region_visible(&mut self, id: &str, visible: bool) -> bool {
+        match self.regions.iter_mut().find(|r| r.id == id) {
+            Some(region) => {
+                region.visible = visible;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Toggle an optional region; returns its new visibility (false if unknown)
+    pub fn toggle_region(&mut self, id: &str) -> bool {
+        let visible = !self.is_region_visible(id);
+        self.set_region_visible(id, visible) && visible
+    }
+
+    pub fn is_region_visible(&self, id: &str) -> bool {
+        self.regions.iter().any(|r| r.id == id && r.visible)
+    }
+}
+
+/// Edge of the main container an optional region is docked to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegionPlacement {
+    Left,
+    Right,
+    Bottom,
+}
+
+/// Configuration for an optional, collapsible region
+#[derive(Debug, Clone)]
+pub struct RegionConfig {
+    pub id: String,
+    /// Registry handle name (HWND); hidden regions are registered with an empty rect
+    pub hwnd: String,
+    pub placement: RegionPlacement,
+    /// Width in columns (Left/Right) or height in rows (Bottom)
+    pub size: u16,
+    pub visible: bool,
+}
+
+impl RegionConfig {
+    pub fn new(id: &str, hwnd: &str, placement: RegionPlacement, size: u16) -> Self {
+        Self {
+            id: id.to_string(),
+            hwnd: hwnd.to_string(),
+            placement,
+            size,
+            visible: true,
+        }
+    }
+
+    /// Start collapsed
+    pub fn hidden(mut self) -> Self {
+        self.visible = false;
+        self
+    }
 }
 
 /// Configuration for a tab
@@ -55,6 +118,15 @@ impl Default for StatusBarConfig {
 #[derive(Debug, Clone)]
 pub struct BaseLayoutResult {
     pub content_area: Rect, // The area where content panels should be rendered
+    /// Areas of the visible optional regions, by region id
+    pub regions: Vec<(String, Rect)>,
+}
+
+impl BaseLayoutResult {
+    /// Area of a visible region
+    pub fn region(&self, id: &str) -> Option<Rect> {
+        self.regions.iter().find(|(region_id, _)| region_id == id).map(|(_, rect)| *rect)
+    }
 }
 
 /// Base layout component that renders the standard TUI frame structure
@@ -93,7 +165,7 @@ impl<'a> BaseLayout<'a> {
                 height: area.height.max(1),
             };
             registry.register(Some("hwndMainContainer"), safe_rect);
-            return BaseLayoutResult { content_area: safe_rect };
+            return BaseLayoutResult { content_area: safe_rect, regions: Vec::new() };
         }
         
         // 1. Title Banner (hwndTitleBanner): x=0, y=0, h=3, w=100% - anchored to top at y=0
@@ -179,6 +251,18 @@ impl<'a> BaseLayout<'a> {
             width: area.width, // Full terminal width
             height: main_container_height.max(1).min(area.height.saturating_sub(main_container_y)),
         };
+
+        // Carve visible optional regions out of the main container; hidden regions get an
+        // empty rect so anything positioned relative to them collapses too
+        let (main_container, regions) = split_regions(main_container, &self.config.regions);
+        for region in &self.config.regions {
+            let rect = regions
+                .iter()
+                .find(|(id, _)| *id == region.id)
+                .map(|(_, rect)| *rect)
+                .unwrap_or_default();
+            registry.register(Some(&region.hwnd), rect);
+        }
         registry.register(Some("hwndMainContainer"), main_container);
 
         // Render tab bar on top of main container's top border (if tabs exist)
@@ -191,7 +275,7 @@ impl<'a> BaseLayout<'a> {
             self.render_status_bar(f, status_bar);
         }
 
-        BaseLayoutResult { content_area: main_container }
+        BaseLayoutResult { content_area: main_container, regions }
     }
 
     /// Render the title header with borders
@@ -220,6 +304,8 @@ impl<'a> BaseLayout<'a> {
                 name: tab.name.clone(),
                 active: self.active_tab_id.map_or(false, |id| id == tab.id),
                 state: None,
+                badge: None,
+                disabled: false,
             })
             .collect();
 
@@ -284,6 +370,37 @@ impl<'a> BaseLayout<'a> {
     }
 }
 
+/// Split visible regions off the container: Bottom regions take full-width rows first,
+/// then Left/Right regions take columns from what remains. Each region is clamped so the
+/// content keeps at least half of the space it is carved from
+fn split_regions(container: Rect, regions: &[RegionConfig]) -> (Rect, Vec<(String, Rect)>) {
+    let mut content = container;
+    let mut areas = Vec::new();
+
+    let visible = |placement: RegionPlacement| {
+        regions.iter().filter(move |r| r.visible && r.placement == placement)
+    };
+
+    for region in visible(RegionPlacement::Bottom) {
+        let height = region.size.min(content.height / 2);
+        content.height -= height;
+        areas.push((region.id.clone(), Rect { y: content.y + content.height, height, ..content }));
+    }
+    for region in visible(RegionPlacement::Left) {
+        let width = region.size.min(content.width / 2);
+        areas.push((region.id.clone(), Rect { width, ..content }));
+        content.x += width;
+        content.width -= width;
+    }
+    for region in visible(RegionPlacement::Right) {
+        let width = region.size.min(content.width / 2);
+        content.width -= width;
+        areas.push((region.id.clone(), Rect { x: content.x + content.width, width, ..content }));
+    }
+
+    (content, areas)
+}
+
 /// Render global bindings box
 /// This can be called from within content views to show global keyboard shortcuts
 pub fn render_global_bindings(
@@ -324,3 +441,32 @@ pub fn render_global_bindings(
     f.render_widget(paragraph, area);
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_regions_recomputes_content_area() {
+        let container = Rect { x: 0, y: 3, width: 100, height: 30 };
+        let mut config = BaseLayoutConfig {
+            title: String::new(),
+            tabs: vec![],
+            global_bindings: vec![],
+            status_bar: StatusBarConfig::default(),
+            regions: vec![
+                RegionConfig::new("sidebar", "hwndSidebar", RegionPlacement::Left, 20),
+                RegionConfig::new("log", "hwndLogPane", RegionPlacement::Bottom, 8).hidden(),
+            ],
+        };
+
+        let (content, areas) = split_regions(container, &config.regions);
+        assert_eq!(content, Rect { x: 20, y: 3, width: 80, height: 30 });
+        assert_eq!(areas, vec![("sidebar".to_string(), Rect { x: 0, y: 3, width: 20, height: 30 })]);
+
+        assert!(config.toggle_region("log"));
+        assert!(!config.toggle_region("sidebar"));
+        let (content, areas) = split_regions(container, &config.regions);
+        assert_eq!(content, Rect { x: 0, y: 3, width: 100, height: 22 });
+        assert_eq!(areas, vec![("log".to_string(), Rect { x: 0, y: 25, width: 100, height: 8 })]);
+    }
+}
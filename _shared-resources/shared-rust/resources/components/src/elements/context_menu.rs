@@ -0,0 +1,336 @@
+// Context menu component
+// Small menu of actions anchored at a cell (the selected row, or where the mouse was clicked)
+// Mouse clicks are hit-tested against the rect the menu registered in the RectRegistry
+//
+// Usage:
+//   let mut menu = ContextMenu::new("notes.md", (x, y))
+//       .with_item(MenuItem::new("Sync", Action::Sync).with_key('s'))
+//       .with_item(MenuItem::new("Pin", Action::Pin).with_enabled(false));
+//   menu.render(f, f.area(), &mut registry);
+//   match menu.handle_key(&key) {          // or menu.handle_mouse(&mouse, &registry)
+//       MenuResult::Selected(Action::Sync) => sync(),
+//       MenuResult::Closed => close_menu(),
+//       _ => {}
+//   }
+
+use crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::{
+    layout::{Position, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use crate::core::RectRegistry;
+use crate::utilities::display_width;
+
+/// Registry handle name (HWND) of the open context menu
+pub const HWND_CONTEXT_MENU: &str = "hwndContextMenu";
+
+/// Outcome of an event delivered to a context menu
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MenuResult<T> {
+    /// The event was handled and the menu stays open
+    Consumed,
+    /// The event isn't for the menu
+    Ignored,
+    /// An enabled item was chosen; the caller closes the menu
+    Selected(T),
+    /// The menu was dismissed (Esc or a click outside it)
+    Closed,
+}
+
+/// One entry of a context menu
+#[derive(Debug, Clone)]
+pub struct MenuItem<T> {
+    pub label: String,
+    /// Shortcut that picks the item while the menu is open
+    pub key: Option<char>,
+    pub action: T,
+    /// Disabled items are shown dimmed and skipped by navigation
+    pub enabled: bool,
+}
+
+impl<T> MenuItem<T> {
+    pub fn new(label: impl Into<String>, action: T) -> Self {
+        Self {
+            label: label.into(),
+            key: None,
+            action,
+            enabled: true,
+        }
+    }
+
+    /// Builder: Shortcut key
+    pub fn with_key(mut self, key: char) -> Self {
+        self.key = Some(key);
+        self
+    }
+
+    /// Builder: Enable or disable the item
+    pub fn with_enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+}
+
+/// Styles used by the context menu
+#[derive(Debug, Clone)]
+pub struct ContextMenuStyles {
+    pub border: Style,
+    pub title: Style,
+    pub item: Style,
+    pub selected: Style,
+    pub key: Style,
+    pub disabled: Style,
+}
+
+impl Default for ContextMenuStyles {
+    fn default() -> Self {
+        Self {
+            border: Style::default().fg(Color::Cyan),
+            title: Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
+            item: Style::default().fg(Color::White),
+            selected: Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD | Modifier::REVERSED),
+            key: Style::default().fg(Color::Yellow),
+            disabled: Style::default().fg(Color::DarkGray),
+        }
+    }
+}
+
+/// Context menu state
+#[derive(Debug, Clone)]
+pub struct ContextMenu<T> {
+    pub title: String,
+    pub items: Vec<MenuItem<T>>,
+    pub selected: usize,
+    /// Cell the menu opens at (it is moved left/up when it would leave the screen)
+    pub anchor: (u16, u16),
+    pub styles: ContextMenuStyles,
+}
+
+impl<T: Clone> ContextMenu<T> {
+    pub fn new(title: impl Into<String>, anchor: (u16, u16)) -> Self {
+        Self {
+            title: title.into(),
+            items: Vec::new(),
+            selected: 0,
+            anchor,
+            styles: ContextMenuStyles::default(),
+        }
+    }
+
+    /// Builder: Append an item (the first enabled item starts selected)
+    pub fn with_item(mut self, item: MenuItem<T>) -> Self {
+        let enabled = item.enabled;
+        self.items.push(item);
+        if enabled && !self.items[self.selected].enabled {
+            self.selected = self.items.len() - 1;
+        }
+        self
+    }
+
+    /// Builder: Set the styles
+    pub fn with_styles(mut self, styles: ContextMenuStyles) -> Self {
+        self.styles = styles;
+        self
+    }
+
+    /// Move the selection to the next (or previous) enabled item, wrapping around
+    fn step(&mut self, forward: bool) {
+        let count = self.items.len();
+        for offset in 1..=count {
+            let index = if forward {
+                (self.selected + offset) % count
+            } else {
+                (self.selected + count - offset) % count
+            };
+            if self.items[index].enabled {
+                self.selected = index;
+                return;
+            }
+        }
+    }
+
+    /// Pick an item; disabled items keep the menu open
+    fn activate(&mut self, index: usize) -> MenuResult<T> {
+        match self.items.get(index) {
+            Some(item) if item.enabled => MenuResult::Selected(item.action.clone()),
+            _ => MenuResult::Consumed,
+        }
+    }
+
+    /// Deliver a key; every key is consumed while the menu is open
+    pub fn handle_key(&mut self, key: &KeyEvent) -> MenuResult<T> {
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') | KeyCode::BackTab => self.step(false),
+            KeyCode::Down | KeyCode::Char('j') | KeyCode::Tab => self.step(true),
+            KeyCode::Enter | KeyCode::Char(' ') => return self.activate(self.selected),
+            KeyCode::Esc => return MenuResult::Closed,
+            KeyCode::Char(c) => {
+                if let Some(index) = self.items.iter().position(|item| item.key == Some(c)) {
+                    return self.activate(index);
+                }
+            }
+            _ => {}
+        }
+        MenuResult::Consumed
+    }
+
+    /// Deliver a mouse event, hit-tested against the rect registered by the last `render`
+    /// Hovering selects, a left click picks, and a click outside the menu closes it
+    pub fn handle_mouse(&mut self, mouse: &MouseEvent, registry: &RectRegistry) -> MenuResult<T> {
+        let Some(rect) = registry.get_metrics_by_name(HWND_CONTEXT_MENU).map(Rect::from) else {
+            return MenuResult::Ignored;
+        };
+        let inside = rect.contains(Position::new(mouse.column, mouse.row));
+        let item = self.item_at(rect, mouse.column, mouse.row);
+
+        match mouse.kind {
+            MouseEventKind::Moved => {
+                if let Some(index) = item.filter(|&index| self.items[index].enabled) {
+                    self.selected = index;
+                }
+                MenuResult::Consumed
+            }
+            MouseEventKind::Down(_) if !inside => MenuResult::Closed,
+            MouseEventKind::Down(MouseButton::Left) => match item {
+                Some(index) => self.activate(index),
+                None => MenuResult::Consumed,
+            },
+            _ => MenuResult::Consumed,
+        }
+    }
+
+    /// Item under a cell of the menu drawn at `rect`
+    fn item_at(&self, rect: Rect, column: u16, row: u16) -> Option<usize> {
+        let inner = Block::default().borders(Borders::ALL).inner(rect);
+        if !inner.contains(Position::new(column, row)) {
+            return None;
+        }
+        Some((row - inner.y) as usize).filter(|&index| index < self.items.len())
+    }
+
+    /// Rect of the menu inside `bounds`: below-right of the anchor, flipped to stay on screen
+    pub fn area(&self, bounds: Rect) -> Rect {
+        let label_width = self
+            .items
+            .iter()
+            .map(|item| display_width(&item.label) + if item.key.is_some() { 4 } else { 0 })
+            .max()
+            .unwrap_or(0);
+        let width = (label_width.max(display_width(&self.title) + 2) as u16 + 4).min(bounds.width);
+        let height = (self.items.len() as u16 + 2).min(bounds.height);
+
+        let (x, y) = self.anchor;
+        let x = if x + width > bounds.right() { x.saturating_sub(width) } else { x };
+        let y = if y + height > bounds.bottom() { y.saturating_sub(height) } else { y };
+        Rect {
+            x: x.clamp(bounds.x, bounds.right().saturating_sub(width)),
+            y: y.clamp(bounds.y, bounds.bottom().saturating_sub(height)),
+            width,
+            height,
+        }
+    }
+
+    /// Render the menu and register its rect under `HWND_CONTEXT_MENU` for mouse hit-testing
+    pub fn render(&self, f: &mut Frame, bounds: Rect, registry: &mut RectRegistry) -> Rect {
+        let area = self.area(bounds);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(self.styles.border)
+            .title(Span::styled(format!(" {} ", self.title), self.styles.title));
+        let inner = block.inner(area);
+
+        let lines: Vec<Line> = self
+            .items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| {
+                let style = if !item.enabled {
+                    self.styles.disabled
+                } else if index == self.selected {
+                    self.styles.selected
+                } else {
+                    self.styles.item
+                };
+                let key = item.key.map(|c| format!(" {} ", c)).unwrap_or_default();
+                let gap = (inner.width as usize).saturating_sub(display_width(&item.label) + 1 + key.len());
+                let key_style = if item.enabled { style.patch(self.styles.key) } else { style };
+                Line::from(vec![
+                    Span::styled(format!(" {}{}", item.label, " ".repeat(gap)), style),
+                    Span::styled(key, key_style),
+                ])
+            })
+            .collect();
+
+        f.render_widget(Clear, area);
+        f.render_widget(Paragraph::new(lines).block(block), area);
+        registry.register(Some(HWND_CONTEXT_MENU), area);
+        area
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+    use ratatui::{backend::TestBackend, Terminal};
+
+    fn menu() -> ContextMenu<&'static str> {
+        ContextMenu::new("file.rs", (10, 5))
+            .with_item(MenuItem::new("Sync", "sync").with_key('s'))
+            .with_item(MenuItem::new("Pin", "pin").with_enabled(false))
+            .with_item(MenuItem::new("Copy path", "copy").with_key('c'))
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn click(column: u16, row: u16) -> MouseEvent {
+        MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+        }
+    }
+
+    #[test]
+    fn test_keys_skip_disabled_items() {
+        let mut menu = menu();
+        menu.handle_key(&key(KeyCode::Down));
+        assert_eq!(menu.selected, 2);
+        menu.handle_key(&key(KeyCode::Down));
+        assert_eq!(menu.selected, 0);
+
+        assert_eq!(menu.handle_key(&key(KeyCode::Char('c'))), MenuResult::Selected("copy"));
+        assert_eq!(menu.handle_key(&key(KeyCode::Esc)), MenuResult::Closed);
+    }
+
+    #[test]
+    fn test_mouse_hit_testing() {
+        let mut menu = menu();
+        let mut registry = RectRegistry::new();
+        let mut terminal = Terminal::new(TestBackend::new(40, 12)).unwrap();
+        let mut area = Rect::default();
+        terminal.draw(|f| area = menu.render(f, f.area(), &mut registry)).unwrap();
+
+        // Rows inside the border map to items; disabled items don't fire
+        assert_eq!(menu.handle_mouse(&click(area.x + 2, area.y + 1), &registry), MenuResult::Selected("sync"));
+        assert_eq!(menu.handle_mouse(&click(area.x + 2, area.y + 2), &registry), MenuResult::Consumed);
+        assert_eq!(menu.handle_mouse(&click(area.x, area.y), &registry), MenuResult::Consumed);
+        assert_eq!(menu.handle_mouse(&click(0, 0), &registry), MenuResult::Closed);
+    }
+
+    #[test]
+    fn test_area_stays_on_screen() {
+        let mut menu = menu();
+        menu.anchor = (38, 11);
+        let area = menu.area(Rect::new(0, 0, 40, 12));
+        assert!(area.right() <= 40 && area.bottom() <= 12);
+        assert_eq!(area.height, 5);
+    }
+}
@@ -1,11 +1,20 @@
 // File browser component
+// Directory picker with keyboard navigation, hidden-file toggle, glob filter and preview
+use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
-    layout::Rect,
+    layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Style},
+    text::{Line, Span},
     widgets::{Block, Borders, List, ListItem, Paragraph},
     Frame,
 };
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+/// Minimum browser width before a preview pane is shown
+const PREVIEW_MIN_WIDTH: u16 = 60;
+
+/// Bytes read from a file for the preview pane
+const PREVIEW_MAX_BYTES: usize = 4096;
 
 #[derive(Debug, Clone)]
 pub struct FileEntry {
@@ -15,89 +24,145 @@ pub struct FileEntry {
     pub size: u64,
 }
 
+/// What the browser is picking
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PickMode {
+    /// Space picks the highlighted directory (or the current one)
+    #[default]
+    Directory,
+    /// Enter/Space on a file picks it
+    File,
+}
+
+/// Outcome of a key press handled by the browser
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BrowserResult {
+    /// Key was used for navigation
+    Consumed,
+    /// Key is not handled by the browser
+    Ignored,
+    /// A path was chosen
+    Picked(PathBuf),
+    /// Esc was pressed
+    Cancelled,
+}
+
+#[derive(Debug, Clone)]
 pub struct FileBrowser {
     pub current_dir: PathBuf,
     pub entries: Vec<FileEntry>,
     pub selected_index: usize,
     pub scroll_offset: usize,
     pub visible_height: usize,
+    pub mode: PickMode,
+    pub show_hidden: bool,
+    /// Glob applied to file names (directories are always listed)
+    pub filter: Option<String>,
+    /// Whether key presses currently edit the filter
+    editing_filter: bool,
+    /// Preview lines for the highlighted entry (cached by path)
+    preview: Option<(PathBuf, Vec<String>)>,
 }
 
 impl FileBrowser {
     pub fn new(initial_path: PathBuf) -> Self {
         let mut browser = Self {
-            current_dir: initial_path.clone(),
+            current_dir: initial_path,
             entries: Vec::new(),
             selected_index: 0,
             scroll_offset: 0,
             visible_height: 0,
+            mode: PickMode::default(),
+            show_hidden: false,
+            filter: None,
+            editing_filter: false,
+            preview: None,
         };
         browser.load_directory();
         browser
     }
 
+    /// Builder: Set the pick mode
+    pub fn with_mode(mut self, mode: PickMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Builder: Set the file name glob filter
+    pub fn with_filter(mut self, pattern: impl Into<String>) -> Self {
+        self.set_filter(Some(pattern.into()));
+        self
+    }
+
     pub fn load_directory(&mut self) {
+        use crate::utilities::glob_match;
+
         self.entries.clear();
-        
+
         if let Ok(entries) = std::fs::read_dir(&self.current_dir) {
             let mut dirs = Vec::new();
             let mut files = Vec::new();
-            
+
             for entry in entries.flatten() {
-                let path = entry.path();
+                let name = entry.file_name().to_string_lossy().to_string();
+                if !self.show_hidden && name.starts_with('.') {
+                    continue;
+                }
+
                 let metadata = entry.metadata().ok();
                 let size = metadata.as_ref().map(|m| m.len()).unwrap_or(0);
                 let is_dir = metadata.map(|m| m.is_dir()).unwrap_or(false);
-                
+
+                if !is_dir {
+                    if let Some(pattern) = self.filter.as_deref().filter(|p| !p.is_empty()) {
+                        if !glob_match(pattern, &name) {
+                            continue;
+                        }
+                    }
+                }
+
                 let file_entry = FileEntry {
-                    name: entry.file_name().to_string_lossy().to_string(),
-                    path: path.clone(),
+                    name,
+                    path: entry.path(),
                     is_dir,
                     size,
                 };
-                
+
                 if is_dir {
                     dirs.push(file_entry);
                 } else {
                     files.push(file_entry);
                 }
             }
-            
+
             // Sort: directories first, then files, both alphabetically
             dirs.sort_by(|a, b| a.name.cmp(&b.name));
             files.sort_by(|a, b| a.name.cmp(&b.name));
-            
+
             self.entries = dirs;
             self.entries.append(&mut files);
-            
-            // Maintain selection context when navigating
-            self.adjust_selection_after_load();
         }
-        
+
         self.selected_index = self.selected_index.min(self.entries.len().saturating_sub(1));
         self.adjust_scroll_to_selection();
     }
 
-    fn adjust_selection_after_load(&mut self) {
-        // When navigating to parent, maintain selection context
-        // This is a placeholder - full implementation would track previous selection
+    /// Reload, keeping the entry with `name` selected if it is still listed
+    fn reload_selecting(&mut self, name: &str) {
+        self.load_directory();
+        if let Some(i) = self.entries.iter().position(|e| e.name == name) {
+            self.selected_index = i;
+            self.adjust_scroll_to_selection();
+        }
     }
 
     pub fn navigate_into(&mut self) {
         if let Some(entry) = self.entries.get(self.selected_index) {
             if entry.is_dir {
-                let current_name = entry.name.clone();
                 self.current_dir = entry.path.clone();
+                self.selected_index = 0;
+                self.scroll_offset = 0;
                 self.load_directory();
-                
-                // Find and select the directory we just came from (if navigating back)
-                for (i, e) in self.entries.iter().enumerate() {
-                    if e.name == current_name {
-                        self.selected_index = i;
-                        self.adjust_scroll_to_selection();
-                        break;
-                    }
-                }
             }
         }
     }
@@ -109,18 +174,9 @@ impl FileBrowser {
                 .and_then(|n| n.to_str())
                 .map(|s| s.to_string())
                 .unwrap_or_default();
-            
+
             self.current_dir = parent.to_path_buf();
-            self.load_directory();
-            
-            // Find and select the directory we just came from
-            for (i, entry) in self.entries.iter().enumerate() {
-                if entry.name == current_name {
-                    self.selected_index = i;
-                    self.adjust_scroll_to_selection();
-                    break;
-                }
-            }
+            self.reload_selecting(&current_name);
         }
     }
 
@@ -138,11 +194,124 @@ impl FileBrowser {
         }
     }
 
+    /// Show or hide dot-files
+    pub fn toggle_hidden(&mut self) {
+        self.show_hidden = !self.show_hidden;
+        let name = self.selected_entry().map(|e| e.name.clone()).unwrap_or_default();
+        self.reload_selecting(&name);
+    }
+
+    /// Set (or clear) the file name glob filter
+    pub fn set_filter(&mut self, pattern: Option<String>) {
+        self.filter = pattern;
+        let name = self.selected_entry().map(|e| e.name.clone()).unwrap_or_default();
+        self.reload_selecting(&name);
+    }
+
+    /// Currently highlighted entry
+    pub fn selected_entry(&self) -> Option<&FileEntry> {
+        self.entries.get(self.selected_index)
+    }
+
+    /// Current directory as "a › b › c"
+    pub fn breadcrumb(&self) -> String {
+        let parts: Vec<String> = self
+            .current_dir
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .filter(|s| !s.is_empty() && s != "/" && s != "\\")
+            .collect();
+
+        if parts.is_empty() {
+            "/".to_string()
+        } else {
+            parts.join(" › ")
+        }
+    }
+
+    /// Handle a key press
+    pub fn handle_key(&mut self, key: &KeyEvent) -> BrowserResult {
+        if self.editing_filter {
+            match key.code {
+                KeyCode::Char(c) => {
+                    let mut pattern = self.filter.clone().unwrap_or_default();
+                    pattern.push(c);
+                    self.set_filter(Some(pattern));
+                }
+                KeyCode::Backspace => {
+                    let mut pattern = self.filter.clone().unwrap_or_default();
+                    pattern.pop();
+                    self.set_filter(Some(pattern).filter(|p| !p.is_empty()));
+                }
+                KeyCode::Enter => self.editing_filter = false,
+                KeyCode::Esc => {
+                    self.editing_filter = false;
+                    self.set_filter(None);
+                }
+                _ => return BrowserResult::Ignored,
+            }
+            return BrowserResult::Consumed;
+        }
+
+        match key.code {
+            KeyCode::Esc => return BrowserResult::Cancelled,
+            KeyCode::Up | KeyCode::Char('k') => self.move_up(),
+            KeyCode::Down | KeyCode::Char('j') => self.move_down(),
+            KeyCode::PageUp => {
+                for _ in 0..self.visible_height.max(1) {
+                    self.move_up();
+                }
+            }
+            KeyCode::PageDown => {
+                for _ in 0..self.visible_height.max(1) {
+                    self.move_down();
+                }
+            }
+            KeyCode::Home => {
+                self.selected_index = 0;
+                self.adjust_scroll_to_selection();
+            }
+            KeyCode::End => {
+                self.selected_index = self.entries.len().saturating_sub(1);
+                self.adjust_scroll_to_selection();
+            }
+            KeyCode::Backspace | KeyCode::Left | KeyCode::Char('h') => self.navigate_parent(),
+            KeyCode::Enter | KeyCode::Right | KeyCode::Char('l') => match self.selected_entry() {
+                Some(entry) if entry.is_dir => self.navigate_into(),
+                Some(entry) if self.mode == PickMode::File => return BrowserResult::Picked(entry.path.clone()),
+                _ => {}
+            },
+            KeyCode::Char(' ') => {
+                if let Some(path) = self.pick_target() {
+                    return BrowserResult::Picked(path);
+                }
+            }
+            KeyCode::Char('.') => self.toggle_hidden(),
+            KeyCode::Char('/') => self.editing_filter = true,
+            _ => return BrowserResult::Ignored,
+        }
+        BrowserResult::Consumed
+    }
+
+    /// Path chosen by Space in the current mode
+    fn pick_target(&self) -> Option<PathBuf> {
+        let entry = self.selected_entry();
+        match self.mode {
+            PickMode::Directory => Some(
+                entry
+                    .filter(|e| e.is_dir)
+                    .map(|e| e.path.clone())
+                    .unwrap_or_else(|| self.current_dir.clone()),
+            ),
+            PickMode::File => entry.filter(|e| !e.is_dir).map(|e| e.path.clone()),
+        }
+    }
+
     fn adjust_scroll_to_selection(&mut self) {
         if self.selected_index < self.scroll_offset {
             self.scroll_offset = self.selected_index;
-        } else if self.selected_index >= self.scroll_offset + self.visible_height {
-            self.scroll_offset = self.selected_index.saturating_sub(self.visible_height - 1);
+        } else if self.selected_index >= self.scroll_offset + self.visible_height.max(1) {
+            self.scroll_offset = self.selected_index + 1 - self.visible_height.max(1);
         }
     }
 
@@ -156,19 +325,45 @@ impl FileBrowser {
     ) {
         use crate::utilities::{get_border_style, get_selection_style, get_selection_style_modal, get_text_color};
         use ratatui::widgets::ListState;
-        
-        self.visible_height = area.height.saturating_sub(2) as usize; // Account for borders
-        
+
         let (border_style, border_type) = get_border_style(is_active, modal_visible);
         let text_color = get_text_color(is_active, modal_visible);
-        
+
+        let block = Block::default()
+            .title(format!(" {} ", title))
+            .borders(Borders::ALL)
+            .border_type(border_type)
+            .border_style(border_style);
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        // Breadcrumb (and filter) header, then list | preview
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Min(0)])
+            .split(inner);
+        let (list_area, preview_area) = if area.width >= PREVIEW_MIN_WIDTH {
+            let cols = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .split(rows[1]);
+            (cols[0], Some(cols[1]))
+        } else {
+            (rows[1], None)
+        };
+
+        self.render_header(f, rows[0]);
+
+        self.visible_height = list_area.height as usize;
+        self.adjust_scroll_to_selection();
+
         // Build list items
         let visible_entries: Vec<_> = self.entries
             .iter()
             .skip(self.scroll_offset)
             .take(self.visible_height)
             .collect();
-        
+
         let items: Vec<ListItem> = if visible_entries.is_empty() {
             vec![ListItem::new(" No files")
                 .style(Style::default().fg(Color::DarkGray))]
@@ -176,60 +371,134 @@ impl FileBrowser {
             visible_entries.iter().map(|entry| {
                 let prefix = if entry.is_dir { "📁 " } else { "📄 " };
                 let display = format!("{}{}", prefix, entry.name);
-                ListItem::new(display).style(Style::default().fg(text_color))
+                // Files can't be picked in directory mode, so they are dimmed
+                let color = if !entry.is_dir && self.mode == PickMode::Directory {
+                    Color::DarkGray
+                } else {
+                    text_color
+                };
+                ListItem::new(display).style(Style::default().fg(color))
             }).collect()
         };
-        
+
         let highlight_style = if modal_visible {
             get_selection_style_modal()
         } else {
             get_selection_style(is_active)
         };
-        
-        let list = List::new(items)
-            .block(Block::default()
-                .title(format!(" {} ", title))
-                .borders(Borders::ALL)
-                .border_type(border_type)
-                .border_style(border_style))
-            .highlight_style(highlight_style);
-        
+
+        let list = List::new(items).highlight_style(highlight_style);
+
         // Create stateful list state
         let mut state = ListState::default();
         let relative_index = self.selected_index.saturating_sub(self.scroll_offset);
         state.select(Some(relative_index));
-        
-        f.render_stateful_widget(list, area, &mut state);
-        
+
+        f.render_stateful_widget(list, list_area, &mut state);
+
         // Render scrollbar if needed
         if self.entries.len() > self.visible_height {
-            self.render_scrollbar(f, area);
+            self.render_scrollbar(f, list_area);
+        }
+
+        if let Some(preview_area) = preview_area {
+            self.render_preview(f, preview_area);
         }
     }
 
+    fn render_header(&self, f: &mut Frame, area: Rect) {
+        let mut spans = vec![Span::styled(self.breadcrumb(), Style::default().fg(Color::Cyan))];
+
+        if self.editing_filter || self.filter.is_some() {
+            let filter = self.filter.as_deref().unwrap_or("");
+            let cursor = if self.editing_filter { "█" } else { "" };
+            spans.push(Span::styled(
+                format!("  filter: {}{}", filter, cursor),
+                Style::default().fg(Color::Yellow),
+            ));
+        }
+        if self.show_hidden {
+            spans.push(Span::styled("  [hidden shown]", Style::default().fg(Color::DarkGray)));
+        }
+
+        f.render_widget(Paragraph::new(Line::from(spans)), area);
+    }
+
+    fn render_preview(&mut self, f: &mut Frame, area: Rect) {
+        let Some(entry) = self.selected_entry() else {
+            return;
+        };
+        let path = entry.path.clone();
+
+        let cached = matches!(&self.preview, Some((cached_path, _)) if *cached_path == path);
+        if !cached {
+            let lines = Self::load_preview(&path, entry.is_dir);
+            self.preview = Some((path, lines));
+        }
+
+        let lines: Vec<Line> = self
+            .preview
+            .as_ref()
+            .map(|(_, lines)| lines.iter().take(area.height as usize).map(|l| Line::from(l.as_str())).collect())
+            .unwrap_or_default();
+
+        let preview = Paragraph::new(lines)
+            .style(Style::default().fg(Color::Gray))
+            .block(Block::default().borders(Borders::LEFT).border_style(Style::default().fg(Color::DarkGray)));
+        f.render_widget(preview, area);
+    }
+
+    /// Preview text: directory listing or the start of a text file
+    fn load_preview(path: &Path, is_dir: bool) -> Vec<String> {
+        if is_dir {
+            let mut names: Vec<String> = std::fs::read_dir(path)
+                .map(|entries| {
+                    entries
+                        .flatten()
+                        .map(|e| e.file_name().to_string_lossy().to_string())
+                        .collect()
+                })
+                .unwrap_or_default();
+            names.sort();
+            let mut lines = vec![format!("{} entries", names.len())];
+            lines.extend(names);
+            return lines;
+        }
+
+        let bytes = match std::fs::read(path) {
+            Ok(bytes) => bytes,
+            Err(err) => return vec![format!("Cannot read: {}", err)],
+        };
+        let head = &bytes[..bytes.len().min(PREVIEW_MAX_BYTES)];
+        if head.contains(&0) {
+            return vec!["Binary file".to_string()];
+        }
+        String::from_utf8_lossy(head).lines().map(|l| l.replace('\t', "    ")).collect()
+    }
+
     fn render_scrollbar(&self, f: &mut Frame, area: Rect) {
         use crate::utilities::hex_color;
-        
-        let scrollbar_x = area.x + area.width - 1;
-        let scrollbar_height = area.height.saturating_sub(2);
+
+        let scrollbar_x = area.x + area.width.saturating_sub(1);
+        let scrollbar_height = area.height;
         let total_items = self.entries.len();
-        
+
         if total_items == 0 {
             return;
         }
-        
+
         let scrollbar_position = (self.scroll_offset * scrollbar_height as usize) / total_items;
         let scrollbar_size = ((scrollbar_height as usize * scrollbar_height as usize) / total_items.max(1)).max(1);
-        
+
         for i in 0..scrollbar_height {
-            let y = area.y + 1 + i;
+            let y = area.y + i;
             let is_scrollbar = (i as usize) >= scrollbar_position && (i as usize) < (scrollbar_position + scrollbar_size);
             let symbol = if is_scrollbar { "█" } else { "│" };
             let color = if is_scrollbar { Color::Cyan } else { hex_color(0x333333) };
-            
+
             let scrollbar_widget = Paragraph::new(symbol)
                 .style(Style::default().fg(color));
-            
+
             f.render_widget(scrollbar_widget, Rect {
                 x: scrollbar_x,
                 y,
@@ -239,3 +508,34 @@ impl FileBrowser {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+
+    #[test]
+    fn test_hidden_toggle_and_filter() {
+        let dir = std::env::temp_dir().join(format!("tui-file-browser-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("sub")).unwrap();
+        for name in [".hidden", "a.rs", "b.md"] {
+            std::fs::write(dir.join(name), "x").unwrap();
+        }
+
+        let names = |b: &FileBrowser| b.entries.iter().map(|e| e.name.clone()).collect::<Vec<_>>();
+
+        let mut browser = FileBrowser::new(dir.clone());
+        assert_eq!(names(&browser), vec!["sub", "a.rs", "b.md"]);
+
+        browser.handle_key(&KeyEvent::new(KeyCode::Char('.'), KeyModifiers::NONE));
+        assert_eq!(names(&browser), vec!["sub", ".hidden", "a.rs", "b.md"]);
+
+        browser.set_filter(Some("*.RS".to_string()));
+        assert_eq!(names(&browser), vec!["sub", "a.rs"]);
+
+        // Directory mode: Space on a directory picks it
+        let result = browser.handle_key(&KeyEvent::new(KeyCode::Char(' '), KeyModifiers::NONE));
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(result, BrowserResult::Picked(dir.join("sub")));
+    }
+}
@@ -1,11 +1,527 @@
 // Form panel component
-// TODO: Implement shared form panel component
+// Bordered panel of typed, validated fields with keyboard editing and submit/cancel results
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
 
-pub struct FormPanel;
+use super::file_browser::{BrowserResult, FileBrowser, PickMode};
+
+/// Field validator: returns an error message for invalid values
+pub type Validator = fn(&str) -> Result<(), String>;
+
+/// Height of the path browser popup (including borders)
+const BROWSER_HEIGHT: u16 = 16;
+
+/// Validator: value must not be blank
+pub fn required(value: &str) -> Result<(), String> {
+    if value.trim().is_empty() {
+        Err("Required".to_string())
+    } else {
+        Ok(())
+    }
+}
+
+/// Kind of input a field accepts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldKind {
+    /// Free text
+    Text,
+    /// Filesystem path (free text, plus a directory browser)
+    Path,
+    /// Boolean toggle
+    Checkbox { checked: bool },
+    /// One of a fixed list of options
+    Select { options: Vec<String>, selected: usize },
+}
+
+/// A single labelled form field
+#[derive(Debug, Clone)]
+pub struct FormField {
+    pub label: String,
+    pub kind: FieldKind,
+    /// Text value (Text and Path fields)
+    pub value: String,
+    /// Hint shown (dimmed) below the field while it is focused
+    pub hint: Option<String>,
+    /// Cursor position in characters (Text and Path fields)
+    pub cursor: usize,
+    pub validators: Vec<Validator>,
+    /// Error from the last failed validation, shown inline
+    pub error: Option<String>,
+}
+
+impl FormField {
+    fn with_kind(label: impl Into<String>, kind: FieldKind, initial: String) -> Self {
+        let cursor = initial.chars().count();
+        Self {
+            label: label.into(),
+            kind,
+            value: initial,
+            hint: None,
+            cursor,
+            validators: Vec::new(),
+            error: None,
+        }
+    }
+
+    /// Free text field
+    pub fn text(label: impl Into<String>, initial: impl Into<String>) -> Self {
+        Self::with_kind(label, FieldKind::Text, initial.into())
+    }
+
+    /// Path field with a directory browser
+    pub fn path(label: impl Into<String>, initial: impl Into<String>) -> Self {
+        Self::with_kind(label, FieldKind::Path, initial.into())
+    }
+
+    /// Checkbox field
+    pub fn checkbox(label: impl Into<String>, checked: bool) -> Self {
+        Self::with_kind(label, FieldKind::Checkbox { checked }, String::new())
+    }
+
+    /// Select field; `selected` is clamped to the option list
+    pub fn select(label: impl Into<String>, options: Vec<String>, selected: usize) -> Self {
+        let selected = selected.min(options.len().saturating_sub(1));
+        Self::with_kind(label, FieldKind::Select { options, selected }, String::new())
+    }
+
+    /// Builder: Set the hint line
+    pub fn with_hint(mut self, hint: impl Into<String>) -> Self {
+        self.hint = Some(hint.into());
+        self
+    }
+
+    /// Builder: Add a validator (run in order, first error wins)
+    pub fn with_validator(mut self, validator: Validator) -> Self {
+        self.validators.push(validator);
+        self
+    }
+
+    /// Current value as text
+    /// Checkboxes yield "true"/"false", selects yield the selected option
+    pub fn display_value(&self) -> &str {
+        match &self.kind {
+            FieldKind::Text | FieldKind::Path => &self.value,
+            FieldKind::Checkbox { checked } => if *checked { "true" } else { "false" },
+            FieldKind::Select { options, selected } => options.get(*selected).map(String::as_str).unwrap_or(""),
+        }
+    }
+
+    /// Run validators and record the first error
+    pub fn validate(&mut self) -> bool {
+        let value = self.display_value();
+        self.error = self.validators.iter().find_map(|v| v(value).err());
+        self.error.is_none()
+    }
+
+    /// Replace the text value and move the cursor to the end
+    pub fn set_value(&mut self, value: impl Into<String>) {
+        self.value = value.into();
+        self.cursor = self.value.chars().count();
+    }
+
+    fn byte_index(&self, char_idx: usize) -> usize {
+        self.value
+            .char_indices()
+            .nth(char_idx)
+            .map(|(i, _)| i)
+            .unwrap_or(self.value.len())
+    }
+
+    fn insert_char(&mut self, c: char) {
+        let idx = self.byte_index(self.cursor);
+        self.value.insert(idx, c);
+        self.cursor += 1;
+    }
+
+    fn backspace(&mut self) {
+        if self.cursor > 0 {
+            self.cursor -= 1;
+            let idx = self.byte_index(self.cursor);
+            self.value.remove(idx);
+        }
+    }
+
+    fn delete(&mut self) {
+        if self.cursor < self.value.chars().count() {
+            let idx = self.byte_index(self.cursor);
+            self.value.remove(idx);
+        }
+    }
+
+    /// Move a select field to the next or previous option
+    fn cycle(&mut self, forward: bool) {
+        if let FieldKind::Select { options, selected } = &mut self.kind {
+            if !options.is_empty() {
+                *selected = if forward {
+                    (*selected + 1) % options.len()
+                } else {
+                    (*selected + options.len() - 1) % options.len()
+                };
+            }
+        }
+    }
+
+    /// Handle an editing key; returns true if consumed
+    fn handle_edit_key(&mut self, key: &KeyEvent) -> bool {
+        match &mut self.kind {
+            FieldKind::Checkbox { checked } => match key.code {
+                KeyCode::Char(' ') => *checked = !*checked,
+                _ => return false,
+            },
+            FieldKind::Select { .. } => match key.code {
+                KeyCode::Left => self.cycle(false),
+                KeyCode::Right | KeyCode::Char(' ') => self.cycle(true),
+                _ => return false,
+            },
+            FieldKind::Text | FieldKind::Path => match key.code {
+                KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => self.insert_char(c),
+                KeyCode::Backspace => self.backspace(),
+                KeyCode::Delete => self.delete(),
+                KeyCode::Left => self.cursor = self.cursor.saturating_sub(1),
+                KeyCode::Right => self.cursor = (self.cursor + 1).min(self.value.chars().count()),
+                KeyCode::Home => self.cursor = 0,
+                KeyCode::End => self.cursor = self.value.chars().count(),
+                _ => return false,
+            },
+        }
+        true
+    }
+}
+
+/// Outcome of a key press handled by the form
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormResult {
+    /// Key was used for navigation or editing
+    Consumed,
+    /// Key is not handled by the form (host may use it)
+    Ignored,
+    /// Enter was pressed and every field validated
+    Submitted,
+    /// Esc was pressed
+    Cancelled,
+}
+
+/// Form panel holding a list of fields and the focused field index
+#[derive(Debug, Clone, Default)]
+pub struct FormPanel {
+    pub title: String,
+    pub fields: Vec<FormField>,
+    pub focused: usize,
+    /// Base directory for path fields (browsed paths are made relative to it)
+    pub browse_root: Option<PathBuf>,
+    /// Open directory browser for the focused path field
+    /// Kept in a RefCell so rendering can update its scroll and preview state
+    browser: RefCell<Option<FileBrowser>>,
+}
 
 impl FormPanel {
     pub fn new() -> Self {
-        Self
+        Self::default()
+    }
+
+    /// Builder: Set the panel title
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    /// Builder: Append a field (focus order follows insertion order)
+    pub fn with_field(mut self, field: FormField) -> Self {
+        self.fields.push(field);
+        self
+    }
+
+    /// Builder: Set the base directory for path fields
+    pub fn with_browse_root(mut self, root: impl Into<PathBuf>) -> Self {
+        self.browse_root = Some(root.into());
+        self
+    }
+
+    /// Value of the field at `index` as text (empty if out of range)
+    pub fn value(&self, index: usize) -> &str {
+        self.fields.get(index).map(FormField::display_value).unwrap_or("")
+    }
+
+    /// Whether the checkbox at `index` is checked
+    pub fn checked(&self, index: usize) -> bool {
+        matches!(self.fields.get(index).map(|f| &f.kind), Some(FieldKind::Checkbox { checked: true }))
+    }
+
+    /// Whether the directory browser is open
+    pub fn is_browsing(&self) -> bool {
+        self.browser.borrow().is_some()
+    }
+
+    pub fn focus_next(&mut self) {
+        if !self.fields.is_empty() {
+            self.focused = (self.focused + 1) % self.fields.len();
+        }
+    }
+
+    pub fn focus_prev(&mut self) {
+        if !self.fields.is_empty() {
+            self.focused = (self.focused + self.fields.len() - 1) % self.fields.len();
+        }
+    }
+
+    /// Validate every field; focuses the first invalid one
+    pub fn validate(&mut self) -> bool {
+        let mut first_invalid = None;
+        for (idx, field) in self.fields.iter_mut().enumerate() {
+            if !field.validate() && first_invalid.is_none() {
+                first_invalid = Some(idx);
+            }
+        }
+
+        if let Some(idx) = first_invalid {
+            self.focused = idx;
+            false
+        } else {
+            true
+        }
+    }
+
+    /// Handle a key press
+    pub fn handle_key(&mut self, key: &KeyEvent) -> FormResult {
+        if self.browser.get_mut().is_some() {
+            self.handle_browser_key(key);
+            return FormResult::Consumed;
+        }
+
+        match key.code {
+            KeyCode::Esc => return FormResult::Cancelled,
+            KeyCode::Enter => {
+                return if self.validate() {
+                    FormResult::Submitted
+                } else {
+                    FormResult::Consumed
+                };
+            }
+            KeyCode::Tab | KeyCode::Down => self.focus_next(),
+            KeyCode::BackTab | KeyCode::Up => self.focus_prev(),
+            KeyCode::Char('o') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                if !self.open_browser() {
+                    return FormResult::Ignored;
+                }
+            }
+            _ => {
+                let Some(field) = self.fields.get_mut(self.focused) else {
+                    return FormResult::Ignored;
+                };
+                if !field.handle_edit_key(key) {
+                    return FormResult::Ignored;
+                }
+                field.error = None;
+            }
+        }
+        FormResult::Consumed
+    }
+
+    /// Open the directory browser for the focused path field
+    fn open_browser(&mut self) -> bool {
+        let Some(field) = self.fields.get(self.focused) else {
+            return false;
+        };
+        if field.kind != FieldKind::Path {
+            return false;
+        }
+
+        let root = self.browse_root.clone().unwrap_or_else(|| PathBuf::from("."));
+        let current = root.join(field.value.trim());
+        let start = if current.is_dir() { current } else { root };
+
+        let mut browser = FileBrowser::new(start).with_mode(PickMode::Directory);
+        // Borders and breadcrumb row
+        browser.visible_height = BROWSER_HEIGHT.saturating_sub(3) as usize;
+        *self.browser.get_mut() = Some(browser);
+        true
+    }
+
+    fn handle_browser_key(&mut self, key: &KeyEvent) {
+        let Some(browser) = self.browser.get_mut() else {
+            return;
+        };
+
+        match browser.handle_key(key) {
+            BrowserResult::Picked(picked) => {
+                let value = self.relative_to_root(&picked);
+                if let Some(field) = self.fields.get_mut(self.focused) {
+                    field.set_value(value);
+                    field.error = None;
+                }
+                *self.browser.get_mut() = None;
+            }
+            BrowserResult::Cancelled => *self.browser.get_mut() = None,
+            BrowserResult::Consumed | BrowserResult::Ignored => {}
+        }
+    }
+
+    fn relative_to_root(&self, path: &Path) -> String {
+        let relative = self
+            .browse_root
+            .as_ref()
+            .and_then(|root| path.strip_prefix(root).ok())
+            .unwrap_or(path);
+        relative.to_string_lossy().replace('\\', "/")
+    }
+
+    /// Render the form into `area`
+    pub fn render(&self, f: &mut Frame, area: Rect, is_active: bool, modal_visible: bool) {
+        use crate::utilities::{display_width, get_border_style, get_text_color};
+
+        let (border_style, border_type) = get_border_style(is_active, modal_visible);
+        let text_color = get_text_color(is_active, modal_visible);
+        let label_width = self.fields.iter().map(|f| display_width(&f.label)).max().unwrap_or(0);
+        let indent = " ".repeat(label_width + 3);
+
+        let mut lines = Vec::new();
+        for (idx, field) in self.fields.iter().enumerate() {
+            let is_focused = idx == self.focused && is_active;
+            let label_style = if is_focused {
+                Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(text_color)
+            };
+            let value_style = Style::default().fg(if is_focused { Color::White } else { text_color });
+
+            let mut spans = vec![
+                Span::styled(
+                    format!(" {}{}: ", " ".repeat(label_width - display_width(&field.label)), field.label),
+                    label_style,
+                ),
+            ];
+
+            match &field.kind {
+                FieldKind::Checkbox { checked } => {
+                    let mark = if *checked { "[x]" } else { "[ ]" };
+                    spans.push(Span::styled(mark, value_style));
+                }
+                FieldKind::Select { .. } => {
+                    spans.push(Span::styled(format!("◂ {} ▸", field.display_value()), value_style));
+                }
+                FieldKind::Text | FieldKind::Path if is_focused => {
+                    let (head, tail) = field.value.split_at(field.byte_index(field.cursor));
+                    spans.push(Span::styled(head.to_string(), value_style));
+                    spans.push(Span::styled("█", Style::default().fg(Color::Yellow)));
+                    spans.push(Span::styled(tail.to_string(), value_style));
+                }
+                FieldKind::Text | FieldKind::Path => {
+                    spans.push(Span::styled(field.value.clone(), value_style));
+                }
+            }
+            lines.push(Line::from(spans));
+
+            if let Some(error) = &field.error {
+                lines.push(Line::from(Span::styled(
+                    format!("{}✗ {}", indent, error),
+                    Style::default().fg(Color::Red),
+                )));
+            } else if is_focused {
+                let hint = match (&field.kind, &field.hint) {
+                    (FieldKind::Path, Some(hint)) => Some(format!("{} (Ctrl+O: Browse)", hint)),
+                    (FieldKind::Path, None) => Some("Ctrl+O: Browse".to_string()),
+                    (_, hint) => hint.clone(),
+                };
+                if let Some(hint) = hint {
+                    lines.push(Line::from(Span::styled(
+                        format!("{}{}", indent, hint),
+                        Style::default().fg(Color::DarkGray),
+                    )));
+                }
+            }
+            lines.push(Line::from(""));
+        }
+
+        f.render_widget(Clear, area);
+        let paragraph = Paragraph::new(lines).block(
+            Block::default()
+                .title(format!(" {} ", self.title))
+                .borders(Borders::ALL)
+                .border_type(border_type)
+                .border_style(border_style),
+        );
+        f.render_widget(paragraph, area);
+
+        if let Some(browser) = self.browser.borrow_mut().as_mut() {
+            let popup = crate::utilities::centered_rect(70, 100, area);
+            let height = BROWSER_HEIGHT.min(popup.height);
+            let popup = Rect {
+                y: popup.y + (popup.height - height) / 2,
+                height,
+                ..popup
+            };
+            f.render_widget(Clear, popup);
+            browser.render(f, popup, "Browse (Space: Pick, .: Hidden, /: Filter, Esc: Close)", true, false);
+        }
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_submit_runs_validators() {
+        let mut form = FormPanel::new()
+            .with_field(FormField::text("Name", ""))
+            .with_field(FormField::text("Path", "").with_validator(required));
+        form.focused = 0;
+
+        assert_eq!(form.handle_key(&press(KeyCode::Enter)), FormResult::Consumed);
+        assert_eq!(form.focused, 1);
+        assert_eq!(form.fields[1].error.as_deref(), Some("Required"));
+
+        form.handle_key(&press(KeyCode::Char('x')));
+        assert!(form.fields[1].error.is_none());
+        assert_eq!(form.handle_key(&press(KeyCode::Enter)), FormResult::Submitted);
+        assert_eq!(form.handle_key(&press(KeyCode::Esc)), FormResult::Cancelled);
+    }
+
+    #[test]
+    fn test_checkbox_and_select() {
+        let mut form = FormPanel::new()
+            .with_field(FormField::checkbox("Enabled", false))
+            .with_field(FormField::select("Mode", vec!["a".into(), "b".into()], 0));
+
+        form.handle_key(&press(KeyCode::Char(' ')));
+        assert!(form.checked(0));
+
+        form.handle_key(&KeyEvent::new(KeyCode::BackTab, KeyModifiers::SHIFT));
+        form.handle_key(&press(KeyCode::Right));
+        assert_eq!(form.value(1), "b");
+        form.handle_key(&press(KeyCode::Right));
+        assert_eq!(form.value(1), "a");
+    }
+
+    #[test]
+    fn test_browser_state_survives_render() {
+        let dir = std::env::temp_dir().join(format!("tui-form-browser-{}", std::process::id()));
+        for i in 0..10 {
+            std::fs::create_dir_all(dir.join(format!("dir{}", i))).unwrap();
+        }
+
+        let mut form = FormPanel::new()
+            .with_browse_root(dir.clone())
+            .with_field(FormField::path("Path", ""));
+        form.handle_key(&KeyEvent::new(KeyCode::Char('o'), KeyModifiers::CONTROL));
+        assert!(form.is_browsing());
+
+        // A short screen leaves room for fewer rows than the browser opened with
+        crate::utilities::snapshot::render_to_text(40, 8, |f| form.render(f, f.area(), true, false));
+        let browser = form.browser.get_mut().clone().unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(browser.visible_height, 5);
+    }
+}
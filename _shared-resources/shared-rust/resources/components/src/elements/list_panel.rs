@@ -1,11 +1,793 @@
 // List panel component
-// TODO: Implement shared list panel component
+// Virtualized multi-column list with selection, multi-select markers, sort indicators, a sticky header
+// and collapsible section headers
+use ratatui::{
+    layout::{Position, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+use std::cell::Cell;
+use std::collections::BTreeSet;
+use unicode_segmentation::UnicodeSegmentation;
 
-pub struct ListPanel;
+use crate::utilities::display_width;
+
+/// Gap between columns
+const COLUMN_GAP: u16 = 1;
+
+/// Width of the multi-select marker column
+const MARKER_WIDTH: u16 = 2;
+
+/// Column width policy
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnWidth {
+    /// Exact width in cells
+    Fixed(u16),
+    /// Share of the remaining width (split evenly between Fill columns)
+    Fill,
+}
+
+/// Horizontal alignment of cell text
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColumnAlign {
+    #[default]
+    Left,
+    Right,
+}
+
+/// Column definition
+#[derive(Debug, Clone)]
+pub struct ListColumn {
+    pub title: String,
+    pub width: ColumnWidth,
+    pub align: ColumnAlign,
+}
+
+impl ListColumn {
+    pub fn new(title: impl Into<String>, width: ColumnWidth) -> Self {
+        Self {
+            title: title.into(),
+            width,
+            align: ColumnAlign::Left,
+        }
+    }
+
+    /// Builder: Right-align this column
+    pub fn align_right(mut self) -> Self {
+        self.align = ColumnAlign::Right;
+        self
+    }
+}
+
+/// Sort direction shown in the header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortDirection {
+    Ascending,
+    Descending,
+}
+
+impl SortDirection {
+    pub fn reversed(self) -> Self {
+        match self {
+            SortDirection::Ascending => SortDirection::Descending,
+            SortDirection::Descending => SortDirection::Ascending,
+        }
+    }
+
+    fn indicator(self) -> &'static str {
+        match self {
+            SortDirection::Ascending => "▲",
+            SortDirection::Descending => "▼",
+        }
+    }
+}
+
+/// A single styled cell
+#[derive(Debug, Clone, Default)]
+pub struct ListCell {
+    pub text: String,
+    pub style: Style,
+}
+
+impl ListCell {
+    pub fn styled(text: impl Into<String>, style: Style) -> Self {
+        Self {
+            text: text.into(),
+            style,
+        }
+    }
+}
+
+impl From<String> for ListCell {
+    fn from(text: String) -> Self {
+        Self::styled(text, Style::default())
+    }
+}
+
+impl From<&str> for ListCell {
+    fn from(text: &str) -> Self {
+        Self::styled(text, Style::default())
+    }
+}
+
+/// One row of cells (one per column)
+#[derive(Debug, Clone, Default)]
+pub struct ListRow {
+    pub cells: Vec<ListCell>,
+}
+
+impl ListRow {
+    pub fn new(cells: Vec<ListCell>) -> Self {
+        Self { cells }
+    }
+}
+
+/// Collapsible header over a run of consecutive rows
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListSection {
+    /// Title shown with the row count, e.g. "Added (12)"
+    pub title: String,
+    pub style: Style,
+    /// First row of the section
+    pub start: usize,
+    /// Rows in the section
+    pub len: usize,
+    pub collapsed: bool,
+}
+
+impl ListSection {
+    pub fn new(title: impl Into<String>, start: usize, len: usize) -> Self {
+        Self {
+            title: title.into(),
+            style: Style::default().add_modifier(Modifier::BOLD),
+            start,
+            len,
+            collapsed: false,
+        }
+    }
+
+    /// Builder: Set the header style
+    pub fn with_style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn contains(&self, index: usize) -> bool {
+        (self.start..self.start + self.len).contains(&index)
+    }
+
+    /// Display lines taken: the header, plus the rows unless collapsed
+    fn line_count(&self) -> usize {
+        if self.collapsed {
+            1
+        } else {
+            1 + self.len
+        }
+    }
+}
+
+/// What a display line of the list shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ListLine {
+    /// Header of the section at this index
+    Header(usize),
+    Row(usize),
+}
+
+/// Styles used by the list panel (focused/unfocused variants)
+#[derive(Debug, Clone)]
+pub struct ListPanelStyles {
+    pub border_focused: Style,
+    pub border_unfocused: Style,
+    pub title_focused: Style,
+    pub title_unfocused: Style,
+    pub selected_focused: Style,
+    pub selected_unfocused: Style,
+    pub header: Style,
+    pub marker: Style,
+}
+
+impl Default for ListPanelStyles {
+    fn default() -> Self {
+        Self {
+            border_focused: Style::default().fg(Color::White),
+            border_unfocused: Style::default().fg(Color::DarkGray),
+            title_focused: Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
+            title_unfocused: Style::default().fg(Color::Gray),
+            selected_focused: Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD | Modifier::REVERSED),
+            selected_unfocused: Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+            header: Style::default().fg(Color::Gray).add_modifier(Modifier::UNDERLINED),
+            marker: Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+        }
+    }
+}
+
+/// List panel view state
+/// Rows are supplied at render time through a callback, so only visible rows are built
+#[derive(Debug, Clone, Default)]
+pub struct ListPanel {
+    pub title: String,
+    pub columns: Vec<ListColumn>,
+    pub selected: usize,
+    /// Whether rows can be marked for multi-select
+    pub multi_select: bool,
+    pub marked: BTreeSet<usize>,
+    /// Sorted column and direction (drives the header indicator only)
+    pub sort: Option<(usize, SortDirection)>,
+    pub show_header: bool,
+    pub empty_message: String,
+    pub styles: ListPanelStyles,
+    /// Section headers, covering the rows in order without gaps (none: a flat list)
+    /// A collapsed section shows only its header, which stands for its first row when selected
+    pub sections: Vec<ListSection>,
+    /// First visible display line (updated during render to keep the selection visible)
+    scroll_offset: Cell<usize>,
+    /// Display lines that fit in the last rendered area
+    visible_height: Cell<usize>,
+}
 
 impl ListPanel {
     pub fn new() -> Self {
-        Self
+        Self {
+            show_header: true,
+            ..Self::default()
+        }
+    }
+
+    /// Builder: Set the panel title
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    /// Builder: Set the columns
+    pub fn with_columns(mut self, columns: Vec<ListColumn>) -> Self {
+        self.columns = columns;
+        self
+    }
+
+    /// Builder: Enable multi-select markers
+    pub fn with_multi_select(mut self, enabled: bool) -> Self {
+        self.multi_select = enabled;
+        self
+    }
+
+    /// Builder: Show or hide the sticky header row
+    pub fn with_header(mut self, show: bool) -> Self {
+        self.show_header = show;
+        self
+    }
+
+    /// Builder: Message shown when there are no rows
+    pub fn with_empty_message(mut self, message: impl Into<String>) -> Self {
+        self.empty_message = message.into();
+        self
+    }
+
+    /// Builder: Set the styles
+    pub fn with_styles(mut self, styles: ListPanelStyles) -> Self {
+        self.styles = styles;
+        self
+    }
+
+    /// First visible row
+    pub fn scroll_offset(&self) -> usize {
+        self.scroll_offset.get()
+    }
+
+    /// Rows visible in the last rendered frame (at least 1)
+    pub fn visible_height(&self) -> usize {
+        self.visible_height.get().max(1)
+    }
+
+    /// Select a row, clamped to `row_count`
+    pub fn select(&mut self, index: usize, row_count: usize) {
+        self.selected = index.min(row_count.saturating_sub(1));
+    }
+
+    pub fn select_previous(&mut self) {
+        if self.sections.is_empty() {
+            self.selected = self.selected.saturating_sub(1);
+        } else {
+            self.move_by_lines(-1);
+        }
+    }
+
+    pub fn select_next(&mut self, row_count: usize) {
+        if self.sections.is_empty() {
+            self.select(self.selected + 1, row_count);
+        } else {
+            self.move_by_lines(1);
+        }
+    }
+
+    pub fn page_up(&mut self) {
+        if self.sections.is_empty() {
+            self.selected = self.selected.saturating_sub(self.visible_height());
+        } else {
+            self.move_by_lines(-(self.visible_height() as isize));
+        }
+    }
+
+    pub fn page_down(&mut self, row_count: usize) {
+        if self.sections.is_empty() {
+            self.select(self.selected + self.visible_height(), row_count);
+        } else {
+            self.move_by_lines(self.visible_height() as isize);
+        }
+    }
+
+    pub fn select_first(&mut self) {
+        if self.sections.is_empty() {
+            self.selected = 0;
+        } else {
+            self.move_by_lines(isize::MIN);
+        }
+    }
+
+    pub fn select_last(&mut self, row_count: usize) {
+        if self.sections.is_empty() {
+            self.select(row_count.saturating_sub(1), row_count);
+        } else {
+            self.move_by_lines(isize::MAX);
+        }
+    }
+
+    /// Replace the sections; sections with the title of a collapsed one stay collapsed
+    /// A selection inside a collapsed section moves to its header
+    pub fn set_sections(&mut self, mut sections: Vec<ListSection>) {
+        for section in &mut sections {
+            section.collapsed = self.sections.iter().any(|old| old.collapsed && old.title == section.title);
+        }
+        self.sections = sections;
+        if let Some(section) = self.sections.iter().find(|s| s.collapsed && s.contains(self.selected)) {
+            self.selected = section.start;
+        }
+    }
+
+    /// Collapse or expand the section of the selected row
+    /// Returns the section, or None when the list has no sections
+    pub fn toggle_section(&mut self) -> Option<&ListSection> {
+        let section = self.sections.iter_mut().find(|s| s.contains(self.selected))?;
+        section.collapsed = !section.collapsed;
+        if section.collapsed {
+            self.selected = section.start;
+        }
+        Some(section)
+    }
+
+    /// Display lines of `row_count` rows: the rows, plus section headers, minus collapsed rows
+    fn line_count(&self, row_count: usize) -> usize {
+        if self.sections.is_empty() {
+            row_count
+        } else {
+            self.sections.iter().map(ListSection::line_count).sum()
+        }
+    }
+
+    /// Display line of a row (the header of its section when collapsed)
+    fn line_of(&self, index: usize) -> usize {
+        if self.sections.is_empty() {
+            return index;
+        }
+        let mut line = 0;
+        for section in &self.sections {
+            if section.contains(index) {
+                return if section.collapsed { line } else { line + 1 + index - section.start };
+            }
+            line += section.line_count();
+        }
+        line
+    }
+
+    /// What a display line shows (not bounds-checked for a flat list)
+    fn line_at(&self, line: usize) -> Option<ListLine> {
+        if self.sections.is_empty() {
+            return Some(ListLine::Row(line));
+        }
+        let mut first = 0;
+        for (idx, section) in self.sections.iter().enumerate() {
+            if line < first + section.line_count() {
+                return Some(match line - first {
+                    0 => ListLine::Header(idx),
+                    offset => ListLine::Row(section.start + offset - 1),
+                });
+            }
+            first += section.line_count();
+        }
+        None
+    }
+
+    /// Row selected at a display line; only collapsed headers can be selected (as their first row)
+    fn selectable_at(&self, line: usize) -> Option<usize> {
+        match self.line_at(line)? {
+            ListLine::Row(index) => Some(index),
+            ListLine::Header(idx) => {
+                let section = &self.sections[idx];
+                (section.collapsed && section.len > 0).then_some(section.start)
+            }
+        }
+    }
+
+    /// Select the selectable line nearest to `distance` display lines away,
+    /// looking further in the same direction first
+    fn move_by_lines(&mut self, distance: isize) {
+        let total = self.line_count(0);
+        if total == 0 {
+            return;
+        }
+        let current = self.line_of(self.selected).min(total - 1);
+        let target = current.saturating_add_signed(distance).min(total - 1);
+        let selectable = |line: usize| self.selectable_at(line);
+        let found = if distance >= 0 {
+            let ahead = (target..total).find_map(selectable);
+            ahead.or_else(|| (current..target).rev().find_map(selectable))
+        } else {
+            let ahead = (0..=target).rev().find_map(selectable);
+            ahead.or_else(|| (target + 1..=current).find_map(selectable))
+        };
+        if let Some(index) = found {
+            self.selected = index;
+        }
+    }
+
+    /// Toggle the multi-select marker on the selected row
+    pub fn toggle_mark(&mut self) {
+        if self.multi_select && !self.marked.remove(&self.selected) {
+            self.marked.insert(self.selected);
+        }
+    }
+
+    pub fn is_marked(&self, index: usize) -> bool {
+        self.marked.contains(&index)
+    }
+
+    pub fn clear_marks(&mut self) {
+        self.marked.clear();
+    }
+
+    /// Sort by `column`; re-selecting the sorted column reverses the direction
+    pub fn toggle_sort(&mut self, column: usize) {
+        self.sort = match self.sort {
+            Some((current, direction)) if current == column => Some((column, direction.reversed())),
+            _ => Some((column, SortDirection::Ascending)),
+        };
+    }
+
+    /// Screen rect of the rows when the panel was rendered at `area` (inside the border, below the header)
+    fn body_area(&self, area: Rect) -> Rect {
+        let inner = Block::default().borders(Borders::ALL).inner(area);
+        let header = u16::from(self.show_header).min(inner.height);
+        Rect {
+            y: inner.y + header,
+            height: inner.height - header,
+            ..inner
+        }
+    }
+
+    /// Row under a screen cell of the panel last rendered at `area`, for mouse hit-testing
+    pub fn row_at(&self, area: Rect, column: u16, row: u16, row_count: usize) -> Option<usize> {
+        let body = self.body_area(area);
+        if !body.contains(Position::new(column, row)) {
+            return None;
+        }
+        let line = self.scroll_offset.get() + (row - body.y) as usize;
+        if self.sections.is_empty() {
+            return Some(line).filter(|&index| index < row_count);
+        }
+        self.selectable_at(line)
+    }
+
+    /// Screen line of a row of the panel last rendered at `area` (None when scrolled out of view)
+    pub fn row_y(&self, area: Rect, index: usize) -> Option<u16> {
+        let body = self.body_area(area);
+        let offset = self.line_of(index).checked_sub(self.scroll_offset.get())?;
+        (offset < body.height as usize).then(|| body.y + offset as u16)
+    }
+
+    /// Compute column widths for the available row width
+    fn column_widths(&self, available: u16) -> Vec<u16> {
+        let gaps = COLUMN_GAP * self.columns.len().saturating_sub(1) as u16;
+        let fixed: u16 = self
+            .columns
+            .iter()
+            .map(|c| match c.width {
+                ColumnWidth::Fixed(w) => w,
+                ColumnWidth::Fill => 0,
+            })
+            .sum();
+        let fill_count = self.columns.iter().filter(|c| c.width == ColumnWidth::Fill).count() as u16;
+        let remaining = available.saturating_sub(fixed + gaps);
+
+        let mut fill_left = fill_count;
+        let mut remaining_left = remaining;
+        self.columns
+            .iter()
+            .map(|c| match c.width {
+                ColumnWidth::Fixed(w) => w,
+                ColumnWidth::Fill => {
+                    // Last Fill column takes the rounding remainder
+                    let w = if fill_left == 1 { remaining_left } else { remaining / fill_count };
+                    fill_left -= 1;
+                    remaining_left -= w;
+                    w
+                }
+            })
+            .collect()
+    }
+
+    /// Keep the selection inside the visible window
+    fn update_scroll(&self, row_count: usize, height: usize) {
+        let selected = self.line_of(self.selected);
+        let mut offset = self.scroll_offset.get();
+        if selected < offset {
+            offset = selected;
+        } else if height > 0 && selected >= offset + height {
+            offset = selected + 1 - height;
+        }
+        offset = offset.min(self.line_count(row_count).saturating_sub(height));
+        self.scroll_offset.set(offset);
+        self.visible_height.set(height);
+    }
+
+    /// Render the list
+    /// `row_at` is only called for visible rows, so large lists stay cheap
+    pub fn render<F>(&self, f: &mut Frame, area: Rect, row_count: usize, row_at: F, is_active: bool)
+    where
+        F: Fn(usize) -> ListRow,
+    {
+        let (border_style, title_style, selected_style) = if is_active {
+            (self.styles.border_focused, self.styles.title_focused, self.styles.selected_focused)
+        } else {
+            (self.styles.border_unfocused, self.styles.title_unfocused, self.styles.selected_unfocused)
+        };
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(border_style)
+            .title(Span::styled(self.title.clone(), title_style));
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        let marker_width = if self.multi_select { MARKER_WIDTH } else { 0 };
+        let widths = self.column_widths(inner.width.saturating_sub(marker_width));
+        let header_height = usize::from(self.show_header);
+        let body_height = (inner.height as usize).saturating_sub(header_height);
+        self.update_scroll(row_count, body_height);
+
+        let mut lines = Vec::with_capacity(body_height + header_height);
+
+        if self.show_header {
+            let mut spans = vec![Span::raw(" ".repeat(marker_width as usize))];
+            for (idx, (column, width)) in self.columns.iter().zip(&widths).enumerate() {
+                let title = match self.sort {
+                    Some((sorted, direction)) if sorted == idx => format!("{} {}", column.title, direction.indicator()),
+                    _ => column.title.clone(),
+                };
+                if idx > 0 {
+                    spans.push(Span::raw(" ".repeat(COLUMN_GAP as usize)));
+                }
+                spans.push(Span::styled(fit(&title, *width as usize, column.align), self.styles.header));
+            }
+            lines.push(Line::from(spans));
+        }
+
+        if row_count == 0 {
+            lines.push(Line::from(Span::styled(
+                format!(" {}", self.empty_message),
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+
+        let start = self.scroll_offset.get();
+        let end = (start + body_height).min(self.line_count(row_count));
+        for line in start..end {
+            let index = match self.line_at(line) {
+                Some(ListLine::Row(index)) => index,
+                Some(ListLine::Header(idx)) => {
+                    let section = &self.sections[idx];
+                    let arrow = if section.collapsed { "▸" } else { "▾" };
+                    let title = format!("{} {} ({})", arrow, section.title, section.len);
+                    let style = if section.collapsed && section.contains(self.selected) {
+                        section.style.patch(selected_style)
+                    } else {
+                        section.style
+                    };
+                    lines.push(Line::from(Span::styled(fit(&title, inner.width as usize, ColumnAlign::Left), style)));
+                    continue;
+                }
+                None => break,
+            };
+            let row = row_at(index);
+            let is_selected = index == self.selected;
+            let patch = |style: Style| if is_selected { style.patch(selected_style) } else { style };
+
+            let mut spans = Vec::with_capacity(self.columns.len() * 2 + 1);
+            if self.multi_select {
+                let marker = if self.is_marked(index) { "● " } else { "  " };
+                spans.push(Span::styled(marker, patch(self.styles.marker)));
+            }
+            for (idx, (column, width)) in self.columns.iter().zip(&widths).enumerate() {
+                if idx > 0 {
+                    spans.push(Span::styled(" ".repeat(COLUMN_GAP as usize), patch(Style::default())));
+                }
+                let cell = row.cells.get(idx).cloned().unwrap_or_default();
+                spans.push(Span::styled(fit(&cell.text, *width as usize, column.align), patch(cell.style)));
+            }
+            lines.push(Line::from(spans));
+        }
+
+        f.render_widget(Paragraph::new(lines), inner);
     }
 }
 
+/// Truncate (with an ellipsis) or pad text to exactly `width` terminal columns
+fn fit(text: &str, width: usize, align: ColumnAlign) -> String {
+    let text_width = display_width(text);
+    if text_width > width {
+        if width == 0 {
+            return String::new();
+        }
+        // Keep the end of the text for right-aligned columns, the start otherwise
+        let right = matches!(align, ColumnAlign::Right);
+        let mut graphemes: Vec<&str> = text.graphemes(true).collect();
+        if right {
+            graphemes.reverse();
+        }
+        let mut used = 0;
+        let mut kept: Vec<&str> = graphemes
+            .into_iter()
+            .take_while(|grapheme| {
+                used += display_width(grapheme);
+                used < width
+            })
+            .collect();
+        let kept_width = display_width(&kept.concat());
+        // A wide character may leave one column unfilled
+        let filler = " ".repeat(width - 1 - kept_width);
+        return if right {
+            kept.reverse();
+            format!("{}…{}", filler, kept.concat())
+        } else {
+            format!("{}…{}", kept.concat(), filler)
+        };
+    }
+
+    let padding = " ".repeat(width - text_width);
+    match align {
+        ColumnAlign::Left => format!("{}{}", text, padding),
+        ColumnAlign::Right => format!("{}{}", padding, text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_column_widths_fill_remaining() {
+        let panel = ListPanel::new().with_columns(vec![
+            ListColumn::new("S", ColumnWidth::Fixed(1)),
+            ListColumn::new("Path", ColumnWidth::Fill),
+            ListColumn::new("Size", ColumnWidth::Fixed(6)),
+        ]);
+        // 40 - 1 - 6 - 2 gaps = 31
+        assert_eq!(panel.column_widths(40), vec![1, 31, 6]);
+    }
+
+    #[test]
+    fn test_scroll_keeps_selection_visible() {
+        let mut panel = ListPanel::new();
+        panel.select(50, 100);
+        panel.update_scroll(100, 10);
+        assert_eq!(panel.scroll_offset(), 41);
+
+        panel.select(3, 100);
+        panel.update_scroll(100, 10);
+        assert_eq!(panel.scroll_offset(), 3);
+    }
+
+    #[test]
+    fn test_row_hit_testing() {
+        let mut panel = ListPanel::new();
+        let area = Rect::new(0, 0, 20, 12);
+        panel.select(30, 100);
+        // Border + header leave 9 body rows (y = 2..11)
+        panel.update_scroll(100, 9);
+        assert_eq!(panel.scroll_offset(), 22);
+
+        assert_eq!(panel.row_at(area, 5, 2, 100), Some(22));
+        assert_eq!(panel.row_at(area, 5, 1, 100), None);
+        assert_eq!(panel.row_at(area, 0, 2, 100), None);
+        assert_eq!(panel.row_y(area, 30), Some(10));
+        assert_eq!(panel.row_y(area, 31), None);
+    }
+
+    #[test]
+    fn test_sections_collapse_and_navigate() {
+        let mut panel = ListPanel::new();
+        panel.set_sections(vec![
+            ListSection::new("Added", 0, 3),
+            ListSection::new("Modified", 3, 2),
+            ListSection::new("Deleted", 5, 4),
+        ]);
+        let area = Rect::new(0, 0, 20, 12);
+        assert_eq!(panel.line_count(9), 12);
+        assert_eq!(panel.line_of(3), 5);
+
+        // Headers of expanded sections are skipped
+        panel.select(2, 9);
+        panel.select_next(9);
+        assert_eq!(panel.selected, 3);
+        panel.select_previous();
+        assert_eq!(panel.selected, 2);
+
+        // A collapsed section is selected on its header as its first row
+        panel.select(4, 9);
+        assert_eq!(panel.toggle_section().map(|s| s.collapsed), Some(true));
+        assert_eq!(panel.selected, 3);
+        assert_eq!(panel.line_count(9), 10);
+        panel.select_next(9);
+        assert_eq!(panel.selected, 5);
+        panel.select_previous();
+        assert_eq!(panel.selected, 3);
+        panel.select_first();
+        assert_eq!(panel.selected, 0);
+        panel.select_last(9);
+        assert_eq!(panel.selected, 8);
+        panel.select_first();
+
+        // Lines: Added header (y=2), rows 0-2, Modified header (y=6), Deleted header, rows 5-8
+        panel.update_scroll(9, 9);
+        assert_eq!(panel.row_at(area, 5, 2, 9), None);
+        assert_eq!(panel.row_at(area, 5, 6, 9), Some(3));
+        assert_eq!(panel.row_y(area, 5), Some(8));
+
+        // Collapsed state survives new sections with the same titles
+        panel.set_sections(vec![ListSection::new("Modified", 0, 2), ListSection::new("Deleted", 2, 1)]);
+        assert!(panel.sections[0].collapsed && !panel.sections[1].collapsed);
+    }
+
+    #[test]
+    fn test_list_render_snapshot() {
+        use crate::utilities::snapshot::{assert_snapshot, render_to_text};
+
+        let mut panel = ListPanel::new()
+            .with_title(" Shared → Project ")
+            .with_columns(vec![
+                ListColumn::new("S", ColumnWidth::Fixed(1)),
+                ListColumn::new("Path", ColumnWidth::Fill),
+                ListColumn::new("Size", ColumnWidth::Fixed(6)).align_right(),
+            ])
+            .with_multi_select(true);
+        panel.set_sections(vec![ListSection::new("Added", 0, 2), ListSection::new("Modified", 2, 2)]);
+        panel.select(2, 4);
+        panel.toggle_mark();
+        let rows = [
+            ("A", "docs/setup.md", "1.2K"),
+            ("A", "scripts/a-rather-long-file-name.sh", "880"),
+            ("M", "README.md", "14K"),
+            ("M", "src/日本語.txt", "3"),
+        ];
+        let screen = render_to_text(40, 9, |f| {
+            let row_at = |index: usize| {
+                let (status, path, size) = rows[index];
+                ListRow::new(vec![status.into(), path.into(), size.into()])
+            };
+            panel.render(f, f.area(), rows.len(), row_at, true);
+        });
+        assert_snapshot("list_panel", &screen);
+    }
+
+    #[test]
+    fn test_fit_truncates_and_pads() {
+        assert_eq!(fit("abcdef", 4, ColumnAlign::Left), "abc…");
+        assert_eq!(fit("abcdef", 4, ColumnAlign::Right), "…def");
+        assert_eq!(fit("ab", 4, ColumnAlign::Right), "  ab");
+        // Wide characters take two columns; a leftover column is padded
+        assert_eq!(fit("日本語", 4, ColumnAlign::Left), "日… ");
+        assert_eq!(fit("日本", 5, ColumnAlign::Left), "日本 ");
+    }
+}
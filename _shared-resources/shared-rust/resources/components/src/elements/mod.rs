@@ -2,6 +2,7 @@
 // Visual components that render UI elements
 
 pub mod base_layout;
+pub mod context_menu;
 pub mod file_browser;
 pub mod form_panel;
 pub mod list_panel;
@@ -11,6 +12,7 @@ pub mod tab_bar;
 pub mod toast;
 
 pub use base_layout::*;
+pub use context_menu::*;
 pub use file_browser::*;
 pub use form_panel::*;
 pub use list_panel::*;
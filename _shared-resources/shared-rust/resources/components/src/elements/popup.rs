@@ -6,7 +6,7 @@ use ratatui::{
     widgets::{Clear, Paragraph},
     Frame,
 };
-use crate::utilities::{centered_rect, hex_color, wrap_text};
+use crate::utilities::{centered_rect, display_width, hex_color, wrap_text};
 
 #[derive(Debug, Clone)]
 pub enum PopupType {
@@ -35,6 +35,7 @@ pub enum PopupType {
     },
 }
 
+#[derive(Debug, Clone)]
 pub struct Popup {
     pub popup_type: PopupType,
     pub visible: bool,
@@ -80,9 +81,10 @@ impl Popup {
 
 /// Render popup with proper dimming
 /// Everything behind the popup should be dimmed to very dim grey
-pub fn render_popup(f: &mut Frame, area: Rect, popup: &Popup) {
+/// Returns the dialog's own area (None if the popup is hidden)
+pub fn render_popup(f: &mut Frame, area: Rect, popup: &Popup) -> Option<Rect> {
     if !popup.visible {
-        return;
+        return None;
     }
 
     // Dim the entire background to very dim grey
@@ -91,36 +93,37 @@ pub fn render_popup(f: &mut Frame, area: Rect, popup: &Popup) {
         .style(Style::default().bg(hex_color(0x0A0A0A))); // Very dim grey background
     f.render_widget(dim_block, area);
 
-    match &popup.popup_type {
+    let popup_area = match &popup.popup_type {
         PopupType::Confirm { title, message, selected } => {
-            render_confirm_popup(f, area, title, message, *selected);
+            render_confirm_popup(f, area, title, message, *selected)
         }
         PopupType::Input { title, prompt, input, cursor_pos } => {
-            render_input_popup(f, area, title, prompt, input, *cursor_pos);
+            render_input_popup(f, area, title, prompt, input, *cursor_pos)
         }
         PopupType::Error { title, message } => {
-            render_error_popup(f, area, title, message);
+            render_error_popup(f, area, title, message)
         }
         PopupType::Info { title, message } => {
-            render_info_popup(f, area, title, message);
+            render_info_popup(f, area, title, message)
         }
         PopupType::Warning { title, message } => {
-            render_warning_popup(f, area, title, message);
+            render_warning_popup(f, area, title, message)
         }
-    }
+    };
+    Some(popup_area)
 }
 
-fn render_confirm_popup(f: &mut Frame, area: Rect, title: &str, message: &str, selected: usize) {
+fn render_confirm_popup(f: &mut Frame, area: Rect, title: &str, message: &str, selected: usize) -> Rect {
     // Wrap message text
     let max_text_width = 50;
     let wrapped_lines = wrap_text(message, max_text_width);
     
     // Calculate popup dimensions
     let max_line_len = wrapped_lines.iter()
-        .map(|l| l.len())
+        .map(|l| display_width(l))
         .max()
-        .unwrap_or(title.len())
-        .max(title.len())
+        .unwrap_or(display_width(title))
+        .max(display_width(title))
         .max(30);
     
     let popup_width = (max_line_len as u16 + 8)
@@ -159,7 +162,7 @@ fn render_confirm_popup(f: &mut Frame, area: Rect, title: &str, message: &str, s
     )));
     
     // Title line - use actual width
-    let title_padding = (actual_width - 2).saturating_sub(title.len());
+    let title_padding = (actual_width - 2).saturating_sub(display_width(title));
     let title_left_pad = title_padding / 2;
     let title_right_pad = title_padding - title_left_pad;
     popup_lines.push(Line::from(Span::styled(
@@ -175,7 +178,7 @@ fn render_confirm_popup(f: &mut Frame, area: Rect, title: &str, message: &str, s
     
     // Content lines (centered) - use actual width
     for line in &wrapped_lines {
-        let padding = (actual_width - 2).saturating_sub(line.len());
+        let padding = (actual_width - 2).saturating_sub(display_width(line));
         let left_pad = padding / 2;
         let right_pad = padding - left_pad;
         let centered = format!("┃{}{}{}┃", " ".repeat(left_pad), line, " ".repeat(right_pad));
@@ -205,7 +208,7 @@ fn render_confirm_popup(f: &mut Frame, area: Rect, title: &str, message: &str, s
     let no_text = "No";
     let buttons = format!("{}  {}", yes_text, no_text);
     
-    let button_padding = (actual_width - 2).saturating_sub(buttons.len());
+    let button_padding = (actual_width - 2).saturating_sub(display_width(&buttons));
     let left_pad = button_padding / 2;
     let right_pad = button_padding - left_pad;
     
@@ -244,11 +247,12 @@ fn render_confirm_popup(f: &mut Frame, area: Rect, title: &str, message: &str, s
         .style(Style::default().bg(hex_color(0x141420))); // Panel background color
     
     f.render_widget(popup_widget, final_popup_area);
+    final_popup_area
 }
 
-fn render_input_popup(f: &mut Frame, area: Rect, title: &str, prompt: &str, input: &str, cursor_pos: usize) {
+fn render_input_popup(f: &mut Frame, area: Rect, title: &str, prompt: &str, input: &str, cursor_pos: usize) -> Rect {
     // Calculate popup dimensions
-    let max_line_len = prompt.len().max(title.len()).max(30);
+    let max_line_len = display_width(prompt).max(display_width(title)).max(30);
     let popup_width = (max_line_len as u16 + 8)
         .max(40)
         .min((area.width as f32 * 0.60) as u16)
@@ -279,7 +283,7 @@ fn render_input_popup(f: &mut Frame, area: Rect, title: &str, prompt: &str, inpu
     )));
     
     // Title line - use actual width
-    let title_padding = (actual_width - 2).saturating_sub(title.len());
+    let title_padding = (actual_width - 2).saturating_sub(display_width(title));
     let title_left_pad = title_padding / 2;
     let title_right_pad = title_padding - title_left_pad;
     popup_lines.push(Line::from(Span::styled(
@@ -294,7 +298,7 @@ fn render_input_popup(f: &mut Frame, area: Rect, title: &str, prompt: &str, inpu
     )));
     
     // Prompt line - use actual width
-    let prompt_padding = (actual_width - 2).saturating_sub(prompt.len());
+    let prompt_padding = (actual_width - 2).saturating_sub(display_width(prompt));
     let prompt_left_pad = prompt_padding / 2;
     let prompt_right_pad = prompt_padding - prompt_left_pad;
     popup_lines.push(Line::from(Span::styled(
@@ -306,7 +310,7 @@ fn render_input_popup(f: &mut Frame, area: Rect, title: &str, prompt: &str, inpu
     let cursor = cursor_pos.min(input.len());
     let (head, tail) = input.split_at(cursor);
     let input_display = format!("{}{}{}", head, "█", tail);
-    let input_padding = (actual_width - 2).saturating_sub(input_display.len());
+    let input_padding = (actual_width - 2).saturating_sub(display_width(&input_display));
     let input_left_pad = input_padding / 2;
     let input_right_pad = input_padding - input_left_pad;
     
@@ -345,16 +349,17 @@ fn render_input_popup(f: &mut Frame, area: Rect, title: &str, prompt: &str, inpu
         .style(Style::default().bg(hex_color(0x141420)));
     
     f.render_widget(popup_widget, final_popup_area);
+    final_popup_area
 }
 
-fn render_error_popup(f: &mut Frame, area: Rect, title: &str, message: &str) {
+fn render_error_popup(f: &mut Frame, area: Rect, title: &str, message: &str) -> Rect {
     // Similar to confirm but with error styling
     let wrapped_lines = wrap_text(message, 50);
     let max_line_len = wrapped_lines.iter()
-        .map(|l| l.len())
+        .map(|l| display_width(l))
         .max()
-        .unwrap_or(title.len())
-        .max(title.len())
+        .unwrap_or(display_width(title))
+        .max(display_width(title))
         .max(30);
     
     let popup_width = (max_line_len as u16 + 8)
@@ -384,7 +389,7 @@ fn render_error_popup(f: &mut Frame, area: Rect, title: &str, message: &str) {
         Style::default().fg(Color::Red),
     )));
     
-    let title_padding = (actual_width - 2).saturating_sub(title.len());
+    let title_padding = (actual_width - 2).saturating_sub(display_width(title));
     let title_left_pad = title_padding / 2;
     let title_right_pad = title_padding - title_left_pad;
     popup_lines.push(Line::from(Span::styled(
@@ -398,7 +403,7 @@ fn render_error_popup(f: &mut Frame, area: Rect, title: &str, message: &str) {
     )));
     
     for line in &wrapped_lines {
-        let padding = (actual_width - 2).saturating_sub(line.len());
+        let padding = (actual_width - 2).saturating_sub(display_width(line));
         let left_pad = padding / 2;
         let right_pad = padding - left_pad;
         // Border characters should be red, text should be white
@@ -435,16 +440,17 @@ fn render_error_popup(f: &mut Frame, area: Rect, title: &str, message: &str) {
         .style(Style::default().bg(hex_color(0x141420)));
     
     f.render_widget(popup_widget, final_popup_area);
+    final_popup_area
 }
 
-fn render_info_popup(f: &mut Frame, area: Rect, title: &str, message: &str) {
+fn render_info_popup(f: &mut Frame, area: Rect, title: &str, message: &str) -> Rect {
     // Similar to confirm but with info styling
     let wrapped_lines = wrap_text(message, 50);
     let max_line_len = wrapped_lines.iter()
-        .map(|l| l.len())
+        .map(|l| display_width(l))
         .max()
-        .unwrap_or(title.len())
-        .max(title.len())
+        .unwrap_or(display_width(title))
+        .max(display_width(title))
         .max(30);
     
     let popup_width = (max_line_len as u16 + 8)
@@ -474,7 +480,7 @@ fn render_info_popup(f: &mut Frame, area: Rect, title: &str, message: &str) {
         Style::default().fg(Color::Cyan),
     )));
     
-    let title_padding = (actual_width - 2).saturating_sub(title.len());
+    let title_padding = (actual_width - 2).saturating_sub(display_width(title));
     let title_left_pad = title_padding / 2;
     let title_right_pad = title_padding - title_left_pad;
     popup_lines.push(Line::from(Span::styled(
@@ -488,7 +494,7 @@ fn render_info_popup(f: &mut Frame, area: Rect, title: &str, message: &str) {
     )));
     
     for line in &wrapped_lines {
-        let padding = (actual_width - 2).saturating_sub(line.len());
+        let padding = (actual_width - 2).saturating_sub(display_width(line));
         let left_pad = padding / 2;
         let right_pad = padding - left_pad;
         // Border characters should be cyan, text should be white
@@ -525,17 +531,18 @@ fn render_info_popup(f: &mut Frame, area: Rect, title: &str, message: &str) {
         .style(Style::default().bg(hex_color(0x141420)));
     
     f.render_widget(popup_widget, final_popup_area);
+    final_popup_area
 }
 
 
-fn render_warning_popup(f: &mut Frame, area: Rect, title: &str, message: &str) {
+fn render_warning_popup(f: &mut Frame, area: Rect, title: &str, message: &str) -> Rect {
     // Similar to info but with warning styling (yellow/orange)
     let wrapped_lines = wrap_text(message, 50);
     let max_line_len = wrapped_lines.iter()
-        .map(|l| l.len())
+        .map(|l| display_width(l))
         .max()
-        .unwrap_or(title.len())
-        .max(title.len())
+        .unwrap_or(display_width(title))
+        .max(display_width(title))
         .max(30);
     
     let popup_width = (max_line_len as u16 + 8)
@@ -565,7 +572,7 @@ fn render_warning_popup(f: &mut Frame, area: Rect, title: &str, message: &str) {
         Style::default().fg(Color::Yellow),
     )));
     
-    let title_padding = (actual_width - 2).saturating_sub(title.len());
+    let title_padding = (actual_width - 2).saturating_sub(display_width(title));
     let title_left_pad = title_padding / 2;
     let title_right_pad = title_padding - title_left_pad;
     popup_lines.push(Line::from(Span::styled(
@@ -579,7 +586,7 @@ fn render_warning_popup(f: &mut Frame, area: Rect, title: &str, message: &str) {
     )));
     
     for line in &wrapped_lines {
-        let padding = (actual_width - 2).saturating_sub(line.len());
+        let padding = (actual_width - 2).saturating_sub(display_width(line));
         let left_pad = padding / 2;
         let right_pad = padding - left_pad;
         // Border characters should be yellow, text should be white
@@ -616,4 +623,5 @@ fn render_warning_popup(f: &mut Frame, area: Rect, title: &str, message: &str) {
         .style(Style::default().bg(hex_color(0x141420)));
     
     f.render_widget(popup_widget, final_popup_area);
+    final_popup_area
 }
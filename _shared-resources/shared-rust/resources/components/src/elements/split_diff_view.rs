@@ -14,6 +14,7 @@ use ratatui::{
     Frame,
 };
 use crate::core::RectRegistry;
+use crate::managers::split_diff::{minimap, AlignmentCache, FoldState, RowCache, WrapMarker, DEFAULT_SIMILARITY_THRESHOLD};
 use crate::managers::{BoundingBox, SplitDiffManager};
 use crate::utilities::LayoutCalculator;
 
@@ -161,9 +162,15 @@ pub const DEFAULT_LAYOUT_CONSTANTS: LayoutConstants = {
     make_constants(1, 2, 2, 1, 1)
 };
 
+/// Unchanged lines kept visible around each change when folding (default)
+pub const DEFAULT_CONTEXT_LINES: usize = 3;
+
+/// Columns between tab stops when expanding tabs (default)
+pub const DEFAULT_TAB_WIDTH: usize = 4;
+
 /// Configuration for the split diff view
 /// Contains only immutable configuration (titles)
-/// Runtime state (scroll_offset, fold_unchanged) is stored in SplitDiffViewState
+/// Runtime state (scroll_offset, folds) is stored in SplitDiffViewState
 #[derive(Debug, Clone)]
 pub struct SplitDiffViewConfig {
     pub source_title: String,
@@ -172,6 +179,21 @@ pub struct SplitDiffViewConfig {
     /// File extension for syntax highlighting (e.g., "rs", "py", "js")
     /// If None, no syntax highlighting will be applied
     pub file_extension: Option<String>,
+    /// Show a change-overview strip to the right of the destination panel
+    pub show_minimap: bool,
+    /// Unchanged lines kept visible around each change when folding
+    pub context_lines: usize,
+    /// Word overlap above which two differing lines are paired as one modified line (0.0–1.0)
+    pub similarity_threshold: f64,
+    /// Show the source and destination line numbers of each row in both gutters (old/new)
+    pub dual_line_numbers: bool,
+    /// Characters of a line shown before the rest is cut off behind a "line continues" marker
+    /// (0 shows every line whole)
+    pub long_line_chars: usize,
+    /// Gutter of the continuation rows of wrapped lines
+    pub wrap_marker: WrapMarker,
+    /// Columns between tab stops when tabs are expanded (0 renders tabs as they are)
+    pub tab_width: usize,
 }
 
 impl Default for SplitDiffViewConfig {
@@ -181,6 +203,13 @@ impl Default for SplitDiffViewConfig {
             dest_title: "Destination".to_string(),
             layout_constants: DEFAULT_LAYOUT_CONSTANTS,
             file_extension: None,
+            show_minimap: false,
+            context_lines: DEFAULT_CONTEXT_LINES,
+            similarity_threshold: DEFAULT_SIMILARITY_THRESHOLD,
+            dual_line_numbers: false,
+            long_line_chars: 0,
+            wrap_marker: WrapMarker::default(),
+            tab_width: DEFAULT_TAB_WIDTH,
         }
     }
 }
@@ -218,6 +247,52 @@ impl SplitDiffViewConfig {
         self
     }
 
+    /// Builder: Show or hide the minimap strip
+    pub fn with_minimap(mut self, show: bool) -> Self {
+        self.show_minimap = show;
+        self
+    }
+
+    /// Builder: Set the number of context lines shown around changes in folded regions
+    pub fn with_context_lines(mut self, lines: usize) -> Self {
+        self.context_lines = lines;
+        self
+    }
+
+    /// Builder: Set how much word overlap pairs two differing lines as one modified line
+    /// Higher values show more changes as separate removals and additions
+    pub fn with_similarity_threshold(mut self, threshold: f64) -> Self {
+        self.similarity_threshold = threshold;
+        self
+    }
+
+    /// Builder: Show both line numbers (source and destination) in the gutters
+    /// Helps cross-referencing with an editor where aligned regions are shifted
+    pub fn with_dual_line_numbers(mut self, dual: bool) -> Self {
+        self.dual_line_numbers = dual;
+        self
+    }
+
+    /// Builder: Truncate lines longer than `chars` characters (0 disables truncation)
+    /// Keeps minified files from wrapping into thousands of rows
+    pub fn with_long_line_chars(mut self, chars: usize) -> Self {
+        self.long_line_chars = chars;
+        self
+    }
+
+    /// Builder: Set what the gutter shows on the continuation rows of wrapped lines
+    pub fn with_wrap_marker(mut self, marker: WrapMarker) -> Self {
+        self.wrap_marker = marker;
+        self
+    }
+
+    /// Builder: Expand tabs to stops every `width` columns (0 renders tabs as they are)
+    /// Raw tabs take one column, so indentation wouldn't line up with the other panel
+    pub fn with_tab_width(mut self, width: usize) -> Self {
+        self.tab_width = width;
+        self
+    }
+
     /// Builder: Set file extension from file path (extracts extension automatically)
     pub fn with_file_path(mut self, file_path: &str) -> Self {
         use crate::utilities::get_file_extension;
@@ -227,24 +302,25 @@ impl SplitDiffViewConfig {
 }
 
 /// State for the split diff view
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct SplitDiffViewState {
     pub scroll_offset: usize,
-    pub fold_unchanged: bool,
+    /// Which unchanged regions are folded; keep one per file to remember it across files
+    pub folds: FoldState,
+    /// Regions of the fold indicators visible after the last render (top to bottom)
+    pub visible_folds: Vec<usize>,
+    /// Alignment indices of the truncated lines whose marker is visible after the last render
+    pub visible_long_lines: Vec<usize>,
+    /// Rows in the last rendered diff (before scrolling); used for minimap jumps
+    pub total_rows: usize,
+    /// Alignment of the current file pair, recomputed only when either side changes
+    pub(crate) alignment: AlignmentCache,
+    /// Wrapped rows of the whole diff; scrolling slices these instead of re-wrapping
+    pub(crate) rows: RowCache,
     /// Cached gutter width calculation (invalidated when line count changes)
     cached_gutter_width: Option<(usize, usize, usize, usize)>, // (source_lines, dest_lines, gutter_width, max_line_digits)
 }
 
-impl Default for SplitDiffViewState {
-    fn default() -> Self {
-        Self {
-            scroll_offset: 0,
-            fold_unchanged: true,
-            cached_gutter_width: None,
-        }
-    }
-}
-
 impl SplitDiffViewState {
     /// Calculate the number of digits needed to represent a number (integer log10)
     /// Faster than floating point log10 calculation
@@ -261,6 +337,34 @@ impl SplitDiffViewState {
         digits
     }
 
+    /// Expand the topmost folded region on screen, else the topmost truncated line
+    /// Returns false if neither is visible
+    pub fn expand_visible_fold(&mut self) -> bool {
+        if let Some(&region) = self.visible_folds.first() {
+            return self.folds.expand(region);
+        }
+        match self.visible_long_lines.first() {
+            Some(&index) => self.folds.expand_line(index),
+            None => false,
+        }
+    }
+
+    /// Scroll to the part of the diff under a click on the minimap
+    /// Returns false if the click is outside the minimap (or it isn't shown)
+    pub fn jump_to_minimap(&mut self, registry: &RectRegistry, x: u16, y: u16) -> bool {
+        let Some(metrics) = registry.get_metrics_by_name(HWND_MINIMAP) else {
+            return false;
+        };
+        let rect = Rect::new(metrics.x, metrics.y, metrics.width, metrics.height);
+        if !rect.contains((x, y).into()) {
+            return false;
+        }
+
+        let cell = (y - rect.y) as usize;
+        self.scroll_offset = minimap::scroll_offset_for_cell(cell, rect.height as usize, self.total_rows);
+        true
+    }
+
     /// Get cached gutter width if line counts match, otherwise calculate and cache
     pub fn get_gutter_width(
         &mut self,
@@ -303,6 +407,10 @@ pub enum SplitDiffViewError {
 /// Window handle names for nested bounding boxes
 const HWND_SOURCE_CONTENT: &str = "hwndSourceContent";
 const HWND_DEST_CONTENT: &str = "hwndDestContent";
+const HWND_MINIMAP: &str = "hwndSplitDiffMinimap";
+
+/// Columns reserved for the minimap strip
+const MINIMAP_WIDTH: u16 = 1;
 
 /// Split diff view component
 pub struct SplitDiffView<'a> {
@@ -361,14 +469,21 @@ impl<'a> SplitDiffView<'a> {
             .prepare(registry)
             .ok_or(SplitDiffViewError::BoxPreparationFailed)?;
 
+        // Keep the rightmost column(s) free for the minimap
+        let panels_area = if self.config.show_minimap {
+            Rect { width: area.width.saturating_sub(MINIMAP_WIDTH), ..area }
+        } else {
+            area
+        };
+
         // Calculate source panel area: positioned at (x+offset, y+offset) with half width minus borders
-        let source_content_area = self.layout_calculator.calculate_source_area(area);
+        let source_content_area = self.layout_calculator.calculate_source_area(panels_area);
         let source_box = Self::get_or_create_box(registry, HWND_SOURCE_CONTENT, source_content_area)?;
 
         // Calculate destination panel area: positioned to the right of source panel
         let dest_content_area = self
             .layout_calculator
-            .calculate_dest_area(source_content_area, area.width);
+            .calculate_dest_area(source_content_area, panels_area.width);
         let dest_box = Self::get_or_create_box(registry, HWND_DEST_CONTENT, dest_content_area)?;
 
         // Render using the nested bounding boxes
@@ -413,6 +528,12 @@ impl<'a> SplitDiffView<'a> {
         let (gutter_width, max_line_digits) = self
             .state
             .get_gutter_width(source_line_count, dest_line_count, &self.config.layout_constants);
+        // A dual gutter adds the other side's number and a space between the two
+        let gutter_width = if self.config.dual_line_numbers {
+            gutter_width + max_line_digits + 1
+        } else {
+            gutter_width
+        };
 
         // Calculate text width: available width minus gutter and separator space
         let text_width = self
@@ -434,23 +555,68 @@ impl<'a> SplitDiffView<'a> {
         );
         let render_data = SplitDiffManager::compute_render_data_static(params);
 
+        if self.config.show_minimap {
+            self.render_minimap(f, area, dest_content_area, available_height, &render_data.row_kinds, registry);
+        } else {
+            registry.register(Some(HWND_MINIMAP), Rect::default());
+        }
+
+        // Titles carry the file's line counts, GitHub-style (destination as the newer side)
+        let (mut source_title, mut dest_title) = (self.config.source_title.clone(), self.config.dest_title.clone());
+        if !render_data.stats.is_empty() {
+            source_title.push_str(&format!(" −{}", render_data.stats.source_changes()));
+            dest_title.push_str(&format!(" +{}", render_data.stats.dest_changes()));
+        }
+
         // Render the pre-computed lines (all business logic is now in the manager)
         let source_widget = Paragraph::new(render_data.source_lines).block(
             Block::default()
                 .borders(Borders::ALL)
-                .title(self.config.source_title.as_str()),
+                .title(source_title),
         );
         f.render_widget(source_widget, source_content_area);
 
         let dest_widget = Paragraph::new(render_data.dest_lines).block(
             Block::default()
                 .borders(Borders::ALL)
-                .title(self.config.dest_title.as_str()),
+                .title(dest_title),
         );
         f.render_widget(dest_widget, dest_content_area);
 
         Ok(())
     }
+
+    /// Render the minimap strip between the destination panel and the right edge of `area`,
+    /// aligned with the panels' text rows
+    fn render_minimap(
+        &self,
+        f: &mut Frame,
+        area: Rect,
+        dest_content_area: Rect,
+        available_height: usize,
+        row_kinds: &[minimap::RowKind],
+        registry: &mut RectRegistry,
+    ) {
+        let x = dest_content_area.right();
+        let minimap_area = Rect {
+            x,
+            y: dest_content_area.y.saturating_add(1),
+            width: MINIMAP_WIDTH.min(area.right().saturating_sub(x)),
+            height: available_height as u16,
+        };
+        registry.register(Some(HWND_MINIMAP), minimap_area);
+        if minimap_area.width == 0 {
+            return;
+        }
+
+        let lines = minimap::create_minimap_lines(
+            row_kinds,
+            available_height,
+            self.state.scroll_offset,
+            available_height,
+        );
+        f.render_widget(Paragraph::new(lines), minimap_area);
+    }
 }
 
 #[cfg(test)]
@@ -478,12 +644,35 @@ mod tests {
         assert_eq!(width3, 5); // 4 + 1
     }
 
+    #[test]
+    fn test_split_diff_render_snapshot() {
+        use crate::utilities::snapshot::{assert_snapshot, render_to_text};
+
+        let lines = |text: &str| -> Vec<String> { text.lines().map(str::to_string).collect() };
+        let source = lines("fn main() {\n    let a = 1;\n    old();\n}\n");
+        let dest = lines("fn main() {\n    let a = 2;\n}\n// added\n");
+        let config = SplitDiffViewConfig::new()
+            .with_source_title("Source")
+            .with_dest_title("Destination");
+        let mut state = SplitDiffViewState::default();
+        let mut registry = RectRegistry::new();
+        let screen = render_to_text(64, 8, |f| {
+            let bounding_box = BoundingBox::create(&mut registry, "hwndSplitDiff", f.area());
+            SplitDiffView::new(&config, &mut state, &source, &dest)
+                .render(f, &bounding_box, &mut registry)
+                .unwrap();
+        });
+        assert_snapshot("split_diff_view", &screen);
+    }
+
     #[test]
     fn test_config_builder_pattern() {
         let config = SplitDiffViewConfig::new()
             .with_source_title("Source File")
-            .with_dest_title("Dest File");
+            .with_dest_title("Dest File")
+            .with_dual_line_numbers(true);
         assert_eq!(config.source_title, "Source File");
         assert_eq!(config.dest_title, "Dest File");
+        assert!(config.dual_line_numbers);
     }
 }
@@ -9,7 +9,7 @@ use ratatui::{
     Frame,
 };
 use crate::core::{RectHandle, RectRegistry, AlignmentConfigData};
-use crate::utilities::DimmingContext;
+use crate::utilities::{display_width, DimmingContext};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum TabBarStyle {
@@ -50,6 +50,20 @@ pub struct TabBarItem {
     pub active: bool,
     /// State for state-based coloring (for tab bars with type: state)
     pub state: Option<crate::core::TabState>,
+    /// Badge rendered after the name, e.g. "12" → "DIFFS (12)"
+    pub badge: Option<String>,
+    /// Disabled tabs are drawn dimmed
+    pub disabled: bool,
+}
+
+impl TabBarItem {
+    /// Display text: the name followed by the badge, if any
+    pub fn label(&self) -> String {
+        match &self.badge {
+            Some(badge) => format!("{} ({})", self.name, badge),
+            None => self.name.clone(),
+        }
+    }
 }
 
 /// Bounding box for a tab (for click detection)
@@ -68,6 +82,37 @@ impl TabBounds {
     }
 }
 
+/// Width of an overflow chevron including its padding ("‹ " / " ›")
+const CHEVRON_WIDTH: u16 = 2;
+
+/// Scroll chevron shown at either end of a tab bar whose tabs do not fit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TabBarChevron {
+    Left,
+    Right,
+}
+
+/// Window of tabs shown when the bar overflows its area
+struct OverflowView {
+    /// Tab bar holding only the visible tabs
+    bar: TabBar,
+    /// Index of the first visible tab in the full bar
+    first: usize,
+    /// Hidden tabs exist to the left / right
+    left: bool,
+    right: bool,
+}
+
+impl OverflowView {
+    fn left_width(&self) -> u16 {
+        if self.left { CHEVRON_WIDTH } else { 0 }
+    }
+
+    fn right_width(&self) -> u16 {
+        if self.right { CHEVRON_WIDTH } else { 0 }
+    }
+}
+
 pub struct TabBar {
     pub items: Vec<TabBarItem>,
     pub style: TabBarStyle,
@@ -78,6 +123,17 @@ pub struct TabBar {
     pub state_colors: Option<crate::core::TabBarStateColors>,
 }
 
+/// Result of preparing a tab bar from the registry
+pub struct PreparedTabBar {
+    pub tab_bar: TabBar,
+    /// Handle of the anchor container
+    pub anchor: RectHandle,
+    pub state: crate::core::TabBarState,
+    /// Anchor area left for content after the space the tab bar reserves
+    /// (Tab style on the top edge takes the first row for its decorative line)
+    pub content_area: Rect,
+}
+
 impl TabBar {
     pub fn new(items: Vec<TabBarItem>, style: TabBarStyle, alignment: TabBarAlignment) -> Self {
         Self {
@@ -103,15 +159,97 @@ impl TabBar {
     /// Calculate the bounds of each tab based on the tab bar's current position and style
     /// Returns a vector of TabBounds for click detection
     /// Call this after determining the tab bar's area (for click handling)
+    /// Tabs scrolled out of an overflowing bar get zero-sized bounds
     pub fn calculate_tab_bounds(&self, registry: Option<&RectRegistry>) -> Vec<TabBounds> {
         let area = self.calculate_area_with_registry(Rect::default(), registry);
         if area.width == 0 || area.height == 0 {
             return Vec::new();
         }
 
+        let Some(view) = self.overflow_view(area.width) else {
+            return self.tab_bounds_from(area.x, area.y);
+        };
+
+        let hidden = TabBounds { x: 0, y: 0, width: 0, height: 0 };
+        let mut bounds = vec![hidden; self.items.len()];
+        for (offset, b) in view.bar.tab_bounds_from(area.x + view.left_width(), area.y).into_iter().enumerate() {
+            bounds[view.first + offset] = b;
+        }
+        bounds
+    }
+
+    /// Get the scroll chevron at the given coordinates, if the bar overflows
+    pub fn get_chevron_at(&self, x: u16, y: u16, registry: Option<&RectRegistry>) -> Option<TabBarChevron> {
+        let area = self.calculate_area_with_registry(Rect::default(), registry);
+        let view = self.overflow_view(area.width)?;
+        if y != area.y {
+            return None;
+        }
+        if view.left && x >= area.x && x < area.x + CHEVRON_WIDTH {
+            Some(TabBarChevron::Left)
+        } else if view.right && x < area.x + area.width && x >= (area.x + area.width).saturating_sub(CHEVRON_WIDTH) {
+            Some(TabBarChevron::Right)
+        } else {
+            None
+        }
+    }
+
+    /// Tab bar containing only the tabs in `first..last`
+    fn windowed(&self, first: usize, last: usize) -> TabBar {
+        TabBar {
+            items: self.items[first..last].to_vec(),
+            style: self.style,
+            alignment: self.alignment,
+            position: self.position.clone(),
+            color: self.color,
+            state_colors: self.state_colors.clone(),
+        }
+    }
+
+    /// Choose the window of tabs that fits `width` (including chevrons), keeping the active tab in view
+    /// Returns None when every tab fits
+    fn overflow_view(&self, width: u16) -> Option<OverflowView> {
+        if self.items.is_empty() || self.estimate_width() <= width {
+            return None;
+        }
+
+        let count = self.items.len();
+        let fits = |first: usize, last: usize| {
+            let chevrons = (if first > 0 { CHEVRON_WIDTH } else { 0 })
+                + (if last < count { CHEVRON_WIDTH } else { 0 });
+            self.windowed(first, last).estimate_width() + chevrons <= width
+        };
+
+        // Grow the window around the active tab, alternating right and left
+        let active = self.items.iter().position(|item| item.active).unwrap_or(0);
+        let (mut first, mut last) = (active, active + 1);
+        loop {
+            let grow_right = last < count && fits(first, last + 1);
+            if grow_right {
+                last += 1;
+            }
+            let grow_left = first > 0 && fits(first - 1, last);
+            if grow_left {
+                first -= 1;
+            }
+            if !grow_right && !grow_left {
+                break;
+            }
+        }
+
+        Some(OverflowView {
+            bar: self.windowed(first, last),
+            first,
+            left: first > 0,
+            right: last < count,
+        })
+    }
+
+    /// Bounds of each tab when the bar's text starts at (x, y)
+    fn tab_bounds_from(&self, x: u16, y: u16) -> Vec<TabBounds> {
         let mut bounds = Vec::new();
-        let mut current_x = area.x;
-        let tab_y = area.y;
+        let mut current_x = x;
+        let tab_y = y;
 
         // Calculate leading separator width
         let leading_width = match self.style {
@@ -125,20 +263,20 @@ impl TabBar {
             let tab_width = match self.style {
                 TabBarStyle::Tab => {
                     if item.active {
-                        item.name.len() as u16 + 4 // "╯ NAME ╰"
+                        display_width(&item.label()) as u16 + 4 // "╯ NAME ╰"
                     } else {
-                        item.name.len() as u16
+                        display_width(&item.label()) as u16
                     }
                 }
                 TabBarStyle::Boxed => {
                     if item.active {
-                        item.name.len() as u16 + 4 // "[ NAME ]"
+                        display_width(&item.label()) as u16 + 4 // "[ NAME ]"
                     } else {
-                        item.name.len() as u16
+                        display_width(&item.label()) as u16
                     }
                 }
-                TabBarStyle::BoxStatic => item.name.len() as u16 + 4, // "[ NAME ]"
-                TabBarStyle::Text | TabBarStyle::TextStatic => item.name.len() as u16,
+                TabBarStyle::BoxStatic => display_width(&item.label()) as u16 + 4, // "[ NAME ]"
+                TabBarStyle::Text | TabBarStyle::TextStatic => display_width(&item.label()) as u16,
             };
 
             bounds.push(TabBounds {
@@ -184,42 +322,65 @@ impl TabBar {
     }
 
     pub fn render_with_registry_and_handle(&self, f: &mut Frame, mut registry: Option<&mut RectRegistry>, handle_name: Option<&str>, dimming: Option<&DimmingContext>) {
-        // Note: Tab style anchor box adjustment (x+1, height-1) is now handled in from_registry()
-        // during prepare(), so the box is adjusted before rendering, allowing content to render
-        // with the adjusted position and other elements to calculate relative positions correctly
-        
-        // Calculate area using the registry (box may have been adjusted for Tab style during prepare)
+        // Calculate area using the registry (anchor boxes are never modified by the tab bar)
         let area = self.calculate_area_with_registry(f.area(), registry.as_deref());
         if area.width == 0 || area.height == 0 {
             return;
         }
 
+        // When the tabs don't fit, render a window around the active tab with scroll chevrons
+        let overflow = self.overflow_view(area.width);
+        let (bar, tabs_area) = match &overflow {
+            Some(view) => {
+                let chevron_color = dimming.map(|d| d.dim_color(self.color)).unwrap_or(self.color);
+                if view.left {
+                    let chevron_area = Rect { width: CHEVRON_WIDTH, ..area };
+                    f.render_widget(Paragraph::new(Span::styled("‹ ", Style::default().fg(chevron_color))), chevron_area);
+                }
+                if view.right {
+                    let chevron_area = Rect {
+                        x: (area.x + area.width).saturating_sub(CHEVRON_WIDTH),
+                        width: CHEVRON_WIDTH,
+                        ..area
+                    };
+                    f.render_widget(Paragraph::new(Span::styled(" ›", Style::default().fg(chevron_color))), chevron_area);
+                }
+                let tabs_area = Rect {
+                    x: area.x + view.left_width(),
+                    width: area.width.saturating_sub(view.left_width() + view.right_width()),
+                    ..area
+                };
+                (&view.bar, tabs_area)
+            }
+            None => (self, area),
+        };
+
         // Render the decorative line above the tab bar (only for Tab style)
         // Tab bar text is at rect.y (on the border), top decorative line is at rect.y - 1 (one line above)
         if self.style == TabBarStyle::Tab {
-            if let Some(active_tab) = self.items.iter().find(|item| item.active) {
+            if let Some(active_tab) = bar.items.iter().find(|item| item.active) {
                 // Top line is one line above the tab bar text
                 // Tab bar is at area.y (which is rect.y), so top line is at area.y - 1 = rect.y - 1
                 let top_line_area = Rect {
-                    x: area.x,
-                    y: area.y.saturating_sub(1), // One line above tab bar text
-                    width: area.width,
+                    x: tabs_area.x,
+                    y: tabs_area.y.saturating_sub(1), // One line above tab bar text
+                    width: tabs_area.width,
                     height: 1,
                 };
                 
                 if top_line_area.y < f.area().height {
-                    let top_line = self.build_top_line(area, active_tab, dimming);
+                    let top_line = bar.build_top_line(tabs_area, active_tab, dimming);
                     let paragraph = Paragraph::new(top_line);
                     f.render_widget(paragraph, top_line_area);
                 }
             }
         }
 
-        // Use the estimated width, not the area width, to ensure all tabs are shown
-        let estimated_width = self.estimate_width();
-        let line = self.build_tab_line(estimated_width.max(area.width), dimming);
+        // Use the estimated width, not the area width, to ensure all (windowed) tabs are shown
+        let estimated_width = bar.estimate_width();
+        let line = bar.build_tab_line(estimated_width.max(tabs_area.width), dimming);
         let paragraph = Paragraph::new(line);
-        f.render_widget(paragraph, area);
+        f.render_widget(paragraph, tabs_area);
         
         // Register the tab bar with its handle name if provided
         if let (Some(registry), Some(handle_name)) = (registry.as_mut(), handle_name) {
@@ -242,7 +403,7 @@ impl TabBar {
         } else {
             "── " // Space after for inactive tabs
         };
-        current_x += leading_sep.chars().count() as u16;
+        current_x += display_width(leading_sep) as u16;
         
         let mut active_tab_start = 0;
         let mut active_tab_width = 0;
@@ -259,18 +420,18 @@ impl TabBar {
                     } else {
                         " ─ " // Space before and after for inactive tabs
                     };
-                    current_x += separator.chars().count() as u16;
+                    current_x += display_width(separator) as u16;
                 }
             }
             
             if item.active {
                 active_tab_start = current_x;
-                let text = format!("╯ {} ╰", item.name);
-                active_tab_width = text.chars().count() as u16;
+                let text = format!("╯ {} ╰", item.label());
+                active_tab_width = display_width(&text) as u16;
                 break;
             } else {
                 // Inactive tab: add its width
-                current_x += item.name.chars().count() as u16;
+                current_x += display_width(&item.label()) as u16;
                 // Check if previous tab (at idx-1) was active
                 if idx > 0 {
                     prev_was_active = self.items[idx - 1].active && self.style == TabBarStyle::Tab;
@@ -401,9 +562,8 @@ impl TabBar {
                     if let Some(metrics) = registry.get_metrics(*handle) {
                         let rect: Rect = metrics.into();
                         
-                        // For Tab style, the anchor box has already been adjusted (y+1, height-1) in from_registry()
-                        // So rect.y is already the adjusted position - attach directly at rect.y
-                        // For Text/Boxed styles, use the container as-is (no adjustment was made)
+                        // The anchor is the unmodified container; Tab style sits one row lower
+                        // (see PreparedTabBar::content_area), other styles attach to its top border
                         
                         // Use the same logic as TopOf
                         let tab_bar_width = self.estimate_width();
@@ -422,10 +582,12 @@ impl TabBar {
                         let x = x.max(rect.x + 1);
                         let right_edge = rect.x + rect.width - 1;
                         let available_width = right_edge.saturating_sub(x) + 1;
-                        // For Tab style, the anchor box is already adjusted (moved down by 1 row) in from_registry()
-                        // So attach directly at rect.y (the adjusted position)
-                        // For other styles, attach at rect.y as well (no adjustment was made)
-                        let y = rect.y; // Attach at the container's top border (already adjusted if Tab style)
+                        // For Tab style, attach one row down so the decorative line fits in the container's first row
+                        let y = if self.style == TabBarStyle::Tab {
+                            rect.y.saturating_add(1)
+                        } else {
+                            rect.y
+                        };
                         Rect {
                             x,
                             y,
@@ -484,7 +646,7 @@ impl TabBar {
     }
 
     pub fn estimate_width(&self) -> u16 {
-        // Calculate based on actual tab text and dividers (using character count)
+        // Calculate based on actual tab text and dividers (using display width)
         // Leading separator depends on if first tab is active (only for Tab style)
         let first_is_active = self.items.first().map(|item| item.active && self.style == TabBarStyle::Tab).unwrap_or(false);
         let leading = match self.style {
@@ -499,7 +661,7 @@ impl TabBar {
                 "── " // Text, Boxed, and static styles always have space after leading separator
             }
         };
-        let mut width = leading.chars().count() as u16;
+        let mut width = display_width(leading) as u16;
         
         let mut prev_was_active = false;
         for (idx, item) in self.items.iter().enumerate() {
@@ -534,40 +696,40 @@ impl TabBar {
                             "─" // Just dash, connects to [ for static boxed style
                         }
                     };
-                    width += separator.chars().count() as u16;
+                    width += display_width(separator) as u16;
                 }
             }
             
-            // Tab text width (using character count)
+            // Tab text width (using display width)
             match self.style {
                 TabBarStyle::Tab => {
                     if item.active {
                         // Active tab: "╯ BASELINES ╰"
-                        let text = format!("╯ {} ╰", item.name);
-                        width += text.chars().count() as u16;
+                        let text = format!("╯ {} ╰", item.label());
+                        width += display_width(&text) as u16;
                     } else {
                         // Inactive tab: just the name
-                        width += item.name.chars().count() as u16;
+                        width += display_width(&item.label()) as u16;
                     }
                 }
                 TabBarStyle::Boxed => {
                     if item.active {
                         // Active tab: "[ BASELINES ]"
-                        let text = format!("[ {} ]", item.name);
-                        width += text.chars().count() as u16;
+                        let text = format!("[ {} ]", item.label());
+                        width += display_width(&text) as u16;
                     } else {
                         // Inactive tab: just the name
-                        width += item.name.chars().count() as u16;
+                        width += display_width(&item.label()) as u16;
                     }
                 }
                 TabBarStyle::Text | TabBarStyle::TextStatic => {
                     // Plain text: just the name
-                    width += item.name.chars().count() as u16;
+                    width += display_width(&item.label()) as u16;
                 }
                 TabBarStyle::BoxStatic => {
                     // Static boxed style: all tabs in brackets [ TAB ]
-                    let text = format!("[ {} ]", item.name);
-                    width += text.chars().count() as u16;
+                    let text = format!("[ {} ]", item.label());
+                    width += display_width(&text) as u16;
                 }
             }
             
@@ -587,16 +749,16 @@ impl TabBar {
             if should_add_sep {
                 match self.style {
                     TabBarStyle::Tab => {
-                        width += "─ ".chars().count() as u16; // Dash-space, connects to ╰ then space before next tab
+                        width += display_width("─ ") as u16; // Dash-space, connects to ╰ then space before next tab
                     }
                     TabBarStyle::Boxed => {
-                        width += "─ ".chars().count() as u16; // Dash-space, creates gap after ] before next tab
+                        width += display_width("─ ") as u16; // Dash-space, creates gap after ] before next tab
                     }
                     TabBarStyle::BoxStatic => {
-                        width += "─".chars().count() as u16; // Just dash, connects to ] then next [
+                        width += display_width("─") as u16; // Just dash, connects to ] then next [
                     }
                     TabBarStyle::TextStatic => {
-                        width += " ─ ".chars().count() as u16; // Space-dash-space for text static
+                        width += display_width(" ─ ") as u16; // Space-dash-space for text static
                     }
                     _ => {}
                 }
@@ -622,7 +784,7 @@ impl TabBar {
         } else {
             " ──" // Add space before trailing separator if last tab is inactive
         };
-        width += trailing_sep.chars().count() as u16;
+        width += display_width(trailing_sep) as u16;
         
         width
     }
@@ -668,7 +830,7 @@ impl TabBar {
             }
         };
         spans.push(Span::styled(leading_sep, Style::default().fg(dim_color(Color::White))));
-        current_width += leading_sep.chars().count() as u16;
+        current_width += display_width(leading_sep) as u16;
 
         // Track if previous tab was active to skip separator before next tab (only for Tab and Boxed styles)
         // Static variants don't have active states, so always add separators
@@ -707,7 +869,7 @@ impl TabBar {
                             "─" // Just dash, connects to [ for static boxed style
                         }
                     };
-                    let sep_width = separator.chars().count() as u16; // Use char count, not byte length
+                    let sep_width = display_width(separator) as u16; // Use display width, not byte length
                     
                     if current_width + sep_width <= max_width {
                         spans.push(Span::styled(separator, Style::default().fg(dim_color(Color::White))));
@@ -724,28 +886,28 @@ impl TabBar {
                 TabBarStyle::Tab => {
                     if item.active {
                         // Active tab with curved brackets: ╯ BASELINES ╰
-                        format!("╯ {} ╰", item.name).chars().count() as u16
+                        display_width(&format!("╯ {} ╰", item.label())) as u16
                     } else {
                         // Inactive tab: plain text
-                        item.name.chars().count() as u16
+                        display_width(&item.label()) as u16
                     }
                 }
                 TabBarStyle::Boxed => {
                     if item.active {
                         // Active tab with square brackets: [ BASELINES ]
-                        format!("[ {} ]", item.name).chars().count() as u16
+                        display_width(&format!("[ {} ]", item.label())) as u16
                     } else {
                         // Inactive tab: plain text
-                        item.name.chars().count() as u16
+                        display_width(&item.label()) as u16
                     }
                 }
                 TabBarStyle::Text | TabBarStyle::TextStatic => {
                     // Plain text style
-                    item.name.chars().count() as u16
+                    display_width(&item.label()) as u16
                 }
                 TabBarStyle::BoxStatic => {
                     // Static boxed style: all tabs in brackets [ TAB ]
-                    format!("[ {} ]", item.name).chars().count() as u16
+                    display_width(&format!("[ {} ]", item.label())) as u16
                 }
             };
 
@@ -755,7 +917,11 @@ impl TabBar {
 
             // Render tab text - for active Tab and Boxed styles, split into spans to color only the name
             // Use state color if available, otherwise use default color logic
-            let state_color = get_state_color(item);
+            let state_color = if item.disabled {
+                Some(crate::utilities::hex_color(0x555555))
+            } else {
+                get_state_color(item)
+            };
             let text_color = state_color.unwrap_or_else(|| {
                 if item.active && (self.style == TabBarStyle::Tab || self.style == TabBarStyle::Boxed || self.style == TabBarStyle::Text) {
                     self.color
@@ -770,7 +936,7 @@ impl TabBar {
                         // Active tab: split into ╯ (white), name (colored), ╰ (white)
                         spans.push(Span::styled("╯ ", Style::default().fg(dim_color(Color::White))));
                         spans.push(Span::styled(
-                            item.name.clone(),
+                            item.label(),
                             Style::default()
                                 .fg(dim_color(text_color))
                                 .add_modifier(Modifier::BOLD)
@@ -778,7 +944,7 @@ impl TabBar {
                         spans.push(Span::styled(" ╰", Style::default().fg(dim_color(Color::White))));
                     } else {
                         // Inactive tab: use state color if available, otherwise white
-                        spans.push(Span::styled(item.name.clone(), Style::default().fg(dim_color(text_color))));
+                        spans.push(Span::styled(item.label(), Style::default().fg(dim_color(text_color))));
                     }
                 }
                 TabBarStyle::Boxed => {
@@ -786,7 +952,7 @@ impl TabBar {
                         // Active tab: split into [ (white), name (colored), ] (white)
                         spans.push(Span::styled("[ ", Style::default().fg(dim_color(Color::White))));
                         spans.push(Span::styled(
-                            item.name.clone(),
+                            item.label(),
                             Style::default()
                                 .fg(dim_color(text_color))
                                 .add_modifier(Modifier::BOLD)
@@ -794,7 +960,7 @@ impl TabBar {
                         spans.push(Span::styled(" ]", Style::default().fg(dim_color(Color::White))));
                     } else {
                         // Inactive tab: use state color if available, otherwise white
-                        spans.push(Span::styled(item.name.clone(), Style::default().fg(dim_color(text_color))));
+                        spans.push(Span::styled(item.label(), Style::default().fg(dim_color(text_color))));
                     }
                 }
                 TabBarStyle::Text => {
@@ -806,17 +972,17 @@ impl TabBar {
                     } else {
                         Style::default().fg(dim_color(Color::White))
                     };
-                    spans.push(Span::styled(item.name.clone(), style));
+                    spans.push(Span::styled(item.label(), style));
                 }
                 TabBarStyle::BoxStatic => {
                     // Static boxed style: use state color if available, otherwise white
                     spans.push(Span::styled("[ ", Style::default().fg(dim_color(Color::White))));
-                    spans.push(Span::styled(item.name.clone(), Style::default().fg(dim_color(text_color))));
+                    spans.push(Span::styled(item.label(), Style::default().fg(dim_color(text_color))));
                     spans.push(Span::styled(" ]", Style::default().fg(dim_color(Color::White))));
                 }
                 TabBarStyle::TextStatic => {
                     // Static text style: use state color if available, otherwise white
-                    spans.push(Span::styled(item.name.clone(), Style::default().fg(dim_color(text_color))));
+                    spans.push(Span::styled(item.label(), Style::default().fg(dim_color(text_color))));
                 }
             }
             current_width += tab_width;
@@ -842,7 +1008,7 @@ impl TabBar {
                     TabBarStyle::TextStatic => " ─ ", // Space-dash-space for text static
                     _ => "",
                 };
-                let next_sep_width = next_sep.chars().count() as u16;
+                let next_sep_width = display_width(next_sep) as u16;
                 if current_width + next_sep_width <= max_width {
                     spans.push(Span::styled(next_sep, Style::default().fg(dim_color(Color::White))));
                     current_width += next_sep_width;
@@ -867,7 +1033,7 @@ impl TabBar {
         } else {
             " ──" // Add space before trailing separator if last tab is inactive
         };
-        let trailing_sep_width = trailing_sep.chars().count() as u16;
+        let trailing_sep_width = display_width(trailing_sep) as u16;
         if max_width >= current_width + trailing_sep_width {
             spans.push(Span::styled(trailing_sep, Style::default().fg(dim_color(Color::White))));
         }
@@ -876,12 +1042,12 @@ impl TabBar {
     }
 
     /// Prepare tab bar from registry state - creates TabBar but does NOT render
-    /// Returns (TabBar, anchor_handle, tab_bar_state) if successful
+    /// The registry is only read; space reserved by the tab bar is reported in `content_area`
     pub fn from_registry(
-        registry: &mut RectRegistry,
+        registry: &RectRegistry,
         tab_bar_handle: RectHandle,
         tab_style_override: Option<TabBarStyle>,
-    ) -> Option<(Self, RectHandle, crate::core::TabBarState)> {
+    ) -> Option<PreparedTabBar> {
         
         // Clone state to avoid borrow checker issues
         let tab_bar_state = registry.get_tab_bar_state(tab_bar_handle)?.clone();
@@ -914,22 +1080,29 @@ impl TabBar {
                 name: tab_config.name.clone(),
                 active: idx == active_tab_index && tab_style != TabBarStyle::BoxStatic && tab_style != TabBarStyle::TextStatic,
                 state: if include_state { Some(tab_config.state) } else { None },
+                badge: tab_config.badge.clone(),
+                disabled: tab_config.disabled,
             })
             .collect();
         
         // Create TabBarPosition based on parsed alignment
-        // For Tab style with handle-based positioning, adjust the anchor box: y+1 and height-1
-        // This adjustment happens before creating the position so other elements can calculate relative positions correctly
-        let tab_position = if parsed_alignment.offset_x == 0 && parsed_alignment.offset_y == 0 {
-            // Handle-based positioning (TopOfHandle or BottomOfHandle) - adjust anchor box for Tab style
-            if tab_style == TabBarStyle::Tab {
-                if let Some(metrics) = registry.get_metrics(anchor_handle) {
-                    let mut updated_metrics = metrics;
-                    updated_metrics.y = updated_metrics.y.saturating_add(1); // Move box down 1 line
-                    updated_metrics.height = updated_metrics.height.saturating_sub(1).max(1); // Reduce height by 1
-                    registry.update(anchor_handle, updated_metrics.into());
-                }
+        // Tab style on the top edge reserves the anchor's first row for its decorative line;
+        // the content below it is reported through PreparedTabBar::content_area
+        let handle_based = parsed_alignment.offset_x == 0 && parsed_alignment.offset_y == 0;
+        let reserves_top_row = handle_based
+            && tab_style == TabBarStyle::Tab
+            && parsed_alignment.vertical == VerticalPosition::Top;
+        let content_area = if reserves_top_row {
+            Rect {
+                y: anchor_rect.y.saturating_add(1),
+                height: anchor_rect.height.saturating_sub(1).max(1),
+                ..anchor_rect
             }
+        } else {
+            anchor_rect
+        };
+        
+        let tab_position = if handle_based {
             // No offsets: use handle-based positioning
             match parsed_alignment.vertical {
                 VerticalPosition::Top => TabBarPosition::TopOfHandle(anchor_handle),
@@ -938,7 +1111,7 @@ impl TabBar {
         } else {
             // Offsets specified: calculate coordinates from anchor rect
             let estimated_tab_width: u16 = tab_bar_state.tab_configs.iter()
-                .map(|t| t.name.len() as u16 + 4)
+                .map(|t| display_width(&t.name) as u16 + t.badge.as_deref().map_or(0, |b| display_width(b) as u16 + 3) + 4)
                 .sum::<u16>() + 10;
             
             let y = match parsed_alignment.vertical {
@@ -988,7 +1161,12 @@ impl TabBar {
         // Set state colors if tab bar type is "state"
         tab_bar.state_colors = tab_bar_state.config.state_colors.clone();
         
-        Some((tab_bar, anchor_handle, tab_bar_state))
+        Some(PreparedTabBar {
+            tab_bar,
+            anchor: anchor_handle,
+            state: tab_bar_state,
+            content_area,
+        })
     }
 
     /// Render tab bar with registry and handle name
@@ -1021,6 +1199,7 @@ impl TabBar {
         // Find initial active tab index
         let initial_active_tab_index = tab_configs.iter()
             .position(|t| t.active)
+            .or_else(|| tab_configs.iter().position(|t| !t.disabled))
             .unwrap_or(0);
         
         // Create and store tab bar state with all configuration
@@ -1116,3 +1295,75 @@ pub fn parse_alignment_from_config(alignment: &AlignmentConfigData) -> ParsedAli
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bar(count: usize, active: usize, width: u16) -> TabBar {
+        let items = (0..count)
+            .map(|idx| TabBarItem {
+                name: format!("TAB{}", idx),
+                active: idx == active,
+                state: None,
+                badge: None,
+                disabled: false,
+            })
+            .collect();
+        TabBar::new(items, TabBarStyle::Text, TabBarAlignment::Left)
+            .with_position(TabBarPosition::Coords { x1: 0, x2: width, y: 0 })
+    }
+
+    #[test]
+    fn test_overflow_keeps_active_tab_visible() {
+        let tab_bar = bar(8, 4, 30);
+        assert!(tab_bar.estimate_width() > 30);
+
+        let bounds = tab_bar.calculate_tab_bounds(None);
+        assert_eq!(bounds.len(), 8);
+        assert!(bounds[4].width > 0);
+        assert_eq!(bounds[0].width, 0);
+        assert!(bounds.iter().all(|b| b.width == 0 || b.x + b.width <= 30));
+
+        assert_eq!(tab_bar.get_chevron_at(0, 0, None), Some(TabBarChevron::Left));
+        assert_eq!(tab_bar.get_chevron_at(29, 0, None), Some(TabBarChevron::Right));
+        assert_eq!(tab_bar.get_tab_at(bounds[4].x, 0, None), Some(4));
+    }
+
+    #[test]
+    fn test_no_chevrons_when_tabs_fit() {
+        let tab_bar = bar(2, 0, 80);
+        assert_eq!(tab_bar.get_chevron_at(0, 0, None), None);
+        assert!(tab_bar.calculate_tab_bounds(None).iter().all(|b| b.width > 0));
+    }
+
+    #[test]
+    fn test_tab_bar_render_snapshots() {
+        use crate::utilities::snapshot::{assert_snapshot, render_to_text};
+        use ratatui::widgets::{Block, Borders};
+
+        // Boxed tabs on the bottom border of a header, as in an app's title bar
+        let screen = render_to_text(48, 3, |f| {
+            let area = f.area();
+            f.render_widget(Block::default().borders(Borders::ALL), area);
+            let items = ["Sync", "Snapshots", "Settings"]
+                .iter()
+                .enumerate()
+                .map(|(idx, name)| TabBarItem {
+                    name: format!("{} {}", idx + 1, name),
+                    active: idx == 0,
+                    state: None,
+                    badge: (idx == 0).then(|| "W".to_string()),
+                    disabled: false,
+                })
+                .collect();
+            TabBar::new(items, TabBarStyle::Boxed, TabBarAlignment::Right)
+                .with_position(TabBarPosition::BottomOf(area))
+                .render(f);
+        });
+        assert_snapshot("tab_bar_boxed", &screen);
+
+        // Overflowing text tabs scroll around the active one between chevrons
+        let screen = render_to_text(30, 1, |f| bar(8, 4, 30).render(f));
+        assert_snapshot("tab_bar_overflow", &screen);
+    }
+}
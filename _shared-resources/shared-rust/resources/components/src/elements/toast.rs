@@ -7,7 +7,7 @@ use ratatui::{
 };
 use std::time::SystemTime;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ToastType {
     Success,
     Error,
@@ -19,6 +19,7 @@ pub struct Toast {
     pub message: String,
     pub toast_type: ToastType,
     pub shown_at: SystemTime,  // MUST use SystemTime, not Instant
+    pub count: u32,            // Number of identical toasts merged into this one
 }
 
 impl Toast {
@@ -27,6 +28,7 @@ impl Toast {
             message,
             toast_type,
             shown_at: SystemTime::now(),
+            count: 1,
         }
     }
 
@@ -45,7 +47,7 @@ impl Toast {
 
 /// Render toasts in bottom-left corner, stacked upward, inset by 1 from left and bottom
 pub fn render_toasts(f: &mut Frame, area: Rect, toasts: &[Toast]) {
-    use crate::utilities::hex_color;
+    use crate::utilities::{display_width, hex_color};
     
     if toasts.is_empty() {
         return;
@@ -62,8 +64,12 @@ pub fn render_toasts(f: &mut Frame, area: Rect, toasts: &[Toast]) {
             ToastType::Info => ("ℹ", Color::Cyan),
         };
 
-        let content = format!("{} {}", icon, toast.message);
-        max_width = max_width.max(content.len());
+        let content = if toast.count > 1 {
+            format!("{} {} (×{})", icon, toast.message, toast.count)
+        } else {
+            format!("{} {}", icon, toast.message)
+        };
+        max_width = max_width.max(display_width(&content));
         toast_data.push((content, fg_color, icon.to_string()));
     }
 
@@ -79,18 +85,12 @@ pub fn render_toasts(f: &mut Frame, area: Rect, toasts: &[Toast]) {
 
     for (content, fg_color, _) in toast_data.iter().rev() {
         // Left-pad content to match max width
-        let content_len = content.len();
-        let left_padding = max_width.saturating_sub(content_len).saturating_sub(1).max(2);
-
-        let mut padded_text = format!("{}{} ", " ".repeat(left_padding), content);
+        let content_width = display_width(content);
+        let left_padding = max_width.saturating_sub(content_width).saturating_sub(1).max(2);
 
-        // Pad to exact width if needed
-        while padded_text.len() < max_width {
-            padded_text.push(' ');
-        }
-        if padded_text.len() > max_width {
-            padded_text.truncate(max_width);
-        }
+        // Pad to exact width if needed (content never exceeds max_width - 3)
+        let right_padding = max_width.saturating_sub(left_padding + content_width).max(1);
+        let padded_text = format!("{}{}{}", " ".repeat(left_padding), content, " ".repeat(right_padding));
 
         let toast_height = 1u16;
 
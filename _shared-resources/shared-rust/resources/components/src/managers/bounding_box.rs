@@ -19,7 +19,7 @@ use crate::utilities::DimmingContext;
 ///
 /// # Usage Examples
 ///
-/// ```rust
+/// ```rust,ignore
 /// // Create a bounding box with a handle name
 /// const HWND_MAIN_CONTENT_BOX: &str = "hwndMainContentBox";
 /// let main_box = BoundingBox::create(&mut registry, HWND_MAIN_CONTENT_BOX, rect);
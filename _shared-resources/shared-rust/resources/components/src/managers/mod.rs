@@ -4,12 +4,21 @@
 pub mod tab_bar;
 pub mod bounding_box;
 pub mod split_diff;
+pub mod popup;
+pub mod toast;
 
 pub use tab_bar::TabBarManager;
 pub use bounding_box::{BoundingBox, get_box_by_name, list_all_boxes};
 pub use split_diff::SplitDiffManager;
+pub use popup::{PopupManager, PopupResult, HWND_POPUP};
+pub use toast::{ToastManager, ToastPolicy};
 // Re-export split diff types
-pub use split_diff::{LineAlignment, SplitDiffRenderData};
+pub use split_diff::{
+    align_lines, align_lines_with, compute_word_diff_dest, compute_word_diff_source, equal_ignoring_regions, expand_tabs,
+    ignored_lines, keep_ignored_regions, long_line_window, moved_lines, AlignmentCache, DiffStats, FoldState, GutterNumbers, LineAlignment,
+    RowKind, SplitDiffRenderData, WrapMarker, MIN_MOVED_ALNUM,
+    DEFAULT_SIMILARITY_THRESHOLD, IGNORE_END_MARKER, IGNORE_START_MARKER,
+};
 
 // Re-export YAML configuration types from tab_bar module
 pub use tab_bar::{
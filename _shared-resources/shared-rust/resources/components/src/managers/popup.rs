@@ -0,0 +1,278 @@
+// Popup Manager
+// Owns a stack of popups, handles their key interaction and reports typed results
+//
+// Usage:
+//   let mut popups = PopupManager::new();
+//   popups.push(Action::Delete, Popup::confirm("Delete".into(), "Delete file?".into()));
+//   match popups.handle_key(&key) {
+//       PopupResult::Confirmed(Action::Delete, true) => delete(),
+//       PopupResult::Submitted(Action::Rename, name) => rename(name),
+//       _ => {}
+//   }
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{layout::Rect, Frame};
+
+use crate::core::RectRegistry;
+use crate::elements::popup::{render_popup, Popup, PopupType};
+
+/// Registry handle name (HWND) of the topmost popup
+pub const HWND_POPUP: &str = "hwndPopup";
+
+/// Outcome of a key event delivered to the popup stack
+/// The tag passed to `push` identifies which popup produced the result
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PopupResult<T> {
+    /// The key was handled and the popup stays open
+    Consumed,
+    /// No popup is open
+    Ignored,
+    /// A confirm popup was answered (true = Yes)
+    Confirmed(T, bool),
+    /// An input popup was submitted with its text
+    Submitted(T, String),
+    /// An error/info/warning popup was acknowledged
+    Dismissed(T),
+    /// The popup was closed with Esc
+    Cancelled(T),
+}
+
+/// Stack of open popups (e.g. a confirm on top of a form)
+/// Only the topmost receives keys; a result or Esc closes it and reveals the one below
+#[derive(Debug)]
+pub struct PopupManager<T> {
+    stack: Vec<(T, Popup)>,
+}
+
+impl<T> Default for PopupManager<T> {
+    fn default() -> Self {
+        Self { stack: Vec::new() }
+    }
+}
+
+impl<T: Clone> PopupManager<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Open a popup on top of the stack
+    pub fn push(&mut self, tag: T, mut popup: Popup) {
+        // Start input popups with the cursor after the initial text
+        if let PopupType::Input { input, cursor_pos, .. } = &mut popup.popup_type {
+            *cursor_pos = input.len();
+        }
+        popup.visible = true;
+        self.stack.push((tag, popup));
+    }
+
+    /// Close the topmost popup without producing a result
+    pub fn pop(&mut self) -> Option<(T, Popup)> {
+        self.stack.pop()
+    }
+
+    pub fn clear(&mut self) {
+        self.stack.clear();
+    }
+
+    pub fn is_open(&self) -> bool {
+        !self.stack.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.stack.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stack.is_empty()
+    }
+
+    /// Topmost popup and its tag
+    pub fn top(&self) -> Option<(&T, &Popup)> {
+        self.stack.last().map(|(tag, popup)| (tag, popup))
+    }
+
+    /// Deliver a key to the topmost popup
+    /// Popups that produce a result are removed from the stack
+    pub fn handle_key(&mut self, key: &KeyEvent) -> PopupResult<T> {
+        let Some((tag, popup)) = self.stack.last_mut() else {
+            return PopupResult::Ignored;
+        };
+
+        let result = match &mut popup.popup_type {
+            PopupType::Confirm { selected, .. } => match key.code {
+                KeyCode::Char('y') | KeyCode::Char('Y') => PopupResult::Confirmed(tag.clone(), true),
+                KeyCode::Char('n') | KeyCode::Char('N') => PopupResult::Confirmed(tag.clone(), false),
+                KeyCode::Left | KeyCode::Right | KeyCode::Tab | KeyCode::BackTab | KeyCode::Char('h') | KeyCode::Char('l') => {
+                    *selected = 1 - (*selected).min(1);
+                    PopupResult::Consumed
+                }
+                KeyCode::Enter => PopupResult::Confirmed(tag.clone(), *selected == 0),
+                KeyCode::Esc => PopupResult::Cancelled(tag.clone()),
+                _ => PopupResult::Consumed,
+            },
+            PopupType::Input { input, cursor_pos, .. } => match key.code {
+                KeyCode::Enter => PopupResult::Submitted(tag.clone(), input.clone()),
+                KeyCode::Esc => PopupResult::Cancelled(tag.clone()),
+                _ => {
+                    edit_input(input, cursor_pos, key);
+                    PopupResult::Consumed
+                }
+            },
+            PopupType::Error { .. } | PopupType::Info { .. } | PopupType::Warning { .. } => match key.code {
+                KeyCode::Enter | KeyCode::Char(' ') => PopupResult::Dismissed(tag.clone()),
+                KeyCode::Esc => PopupResult::Cancelled(tag.clone()),
+                _ => PopupResult::Consumed,
+            },
+        };
+
+        if !matches!(result, PopupResult::Consumed) {
+            self.stack.pop();
+        }
+        result
+    }
+
+    /// Render the popup stack over `area`, bottom to top
+    /// Each popup draws its own backdrop, so the ones underneath appear dimmed
+    pub fn render(&self, f: &mut Frame, area: Rect) {
+        for (_, popup) in &self.stack {
+            render_popup(f, area, popup);
+        }
+    }
+
+    /// Render the popup stack and register each popup as an overlay layer (z = depth),
+    /// so `apply_dimming` leaves only the topmost popup undimmed
+    /// The topmost popup is registered under `HWND_POPUP`
+    pub fn render_with_registry(&self, f: &mut Frame, area: Rect, registry: &mut RectRegistry) {
+        let top = self.stack.len();
+        for (depth, (_, popup)) in self.stack.iter().enumerate().map(|(i, entry)| (i + 1, entry)) {
+            let Some(rect) = render_popup(f, area, popup) else {
+                continue;
+            };
+            if depth == top {
+                registry.register_layer(Some(HWND_POPUP), rect, depth as u16);
+            } else {
+                registry.push_layer(rect, depth as u16);
+            }
+        }
+    }
+}
+
+/// Apply a line-editing key to an input buffer (cursor is a byte offset on a char boundary)
+fn edit_input(input: &mut String, cursor_pos: &mut usize, key: &KeyEvent) {
+    let cursor = (*cursor_pos).min(input.len());
+    let prev_boundary = input[..cursor].char_indices().next_back().map(|(i, _)| i);
+    let next_boundary = input[cursor..].chars().next().map(|c| cursor + c.len_utf8());
+
+    *cursor_pos = match key.code {
+        KeyCode::Char('a') if key.modifiers.contains(KeyModifiers::CONTROL) => 0,
+        KeyCode::Char('e') if key.modifiers.contains(KeyModifiers::CONTROL) => input.len(),
+        KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            input.replace_range(..cursor, "");
+            0
+        }
+        KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+            input.insert(cursor, c);
+            cursor + c.len_utf8()
+        }
+        KeyCode::Backspace => match prev_boundary {
+            Some(prev) => {
+                input.replace_range(prev..cursor, "");
+                prev
+            }
+            None => cursor,
+        },
+        KeyCode::Delete => {
+            if let Some(next) = next_boundary {
+                input.replace_range(cursor..next, "");
+            }
+            cursor
+        }
+        KeyCode::Left => prev_boundary.unwrap_or(cursor),
+        KeyCode::Right => next_boundary.unwrap_or(cursor),
+        KeyCode::Home => 0,
+        KeyCode::End => input.len(),
+        _ => cursor,
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_input_editing_and_submit() {
+        let mut popups = PopupManager::new();
+        popups.push("rename", Popup::input("Rename".into(), "New name:".into(), "ab".into()));
+
+        popups.handle_key(&key(KeyCode::Left));
+        popups.handle_key(&key(KeyCode::Char('é')));
+        popups.handle_key(&key(KeyCode::End));
+        popups.handle_key(&key(KeyCode::Backspace));
+        popups.handle_key(&key(KeyCode::Home));
+        popups.handle_key(&key(KeyCode::Delete));
+
+        assert_eq!(
+            popups.handle_key(&key(KeyCode::Enter)),
+            PopupResult::Submitted("rename", "é".to_string())
+        );
+        assert!(!popups.is_open());
+    }
+
+    #[test]
+    fn test_confirm_stack() {
+        let mut popups = PopupManager::new();
+        popups.push(1, Popup::confirm("Sync".into(), "Sync all?".into()));
+        popups.push(2, Popup::confirm("Delete".into(), "Delete file?".into()));
+
+        // Defaults to No; arrows toggle the selected button
+        popups.handle_key(&key(KeyCode::Right));
+        assert_eq!(popups.handle_key(&key(KeyCode::Enter)), PopupResult::Confirmed(2, true));
+        assert_eq!(popups.handle_key(&key(KeyCode::Char('n'))), PopupResult::Confirmed(1, false));
+        assert_eq!(popups.handle_key(&key(KeyCode::Esc)), PopupResult::Ignored);
+    }
+
+    #[test]
+    fn test_popup_render_snapshot() {
+        use crate::utilities::snapshot::{assert_snapshot, render_to_text};
+
+        let mut popups = PopupManager::new();
+        popups.push("sync", Popup::confirm("Sync".into(), "Sync 3 files to the project?".into()));
+        popups.handle_key(&key(KeyCode::Right));
+        let screen = render_to_text(80, 14, |f| popups.render(f, f.area()));
+        assert_snapshot("popup_confirm", &screen);
+    }
+
+    #[test]
+    fn test_nested_popups_layer_and_unwind() {
+        use crate::core::RectMetrics;
+        use ratatui::{backend::TestBackend, Terminal};
+
+        let mut popups = PopupManager::new();
+        popups.push("sync", Popup::confirm("Sync".into(), "Sync all files?".into()));
+        popups.push("warn", Popup::warning("Newer files".into(), "2 files are newer".into()));
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+        let mut registry = RectRegistry::new();
+        terminal
+            .draw(|f| {
+                registry.begin_frame();
+                popups.render_with_registry(f, f.area(), &mut registry);
+            })
+            .unwrap();
+
+        // Only the warning is on the top layer; the confirm below it gets dimmed
+        let top = registry.get_metrics_by_name(HWND_POPUP).unwrap();
+        assert_eq!(registry.top_layer().len(), 1);
+        assert_eq!(RectMetrics::from(registry.top_layer()[0]), top);
+
+        // Esc unwinds one level at a time
+        assert_eq!(popups.handle_key(&key(KeyCode::Esc)), PopupResult::Cancelled("warn"));
+        assert_eq!(popups.top().map(|(tag, _)| *tag), Some("sync"));
+        assert_eq!(popups.handle_key(&key(KeyCode::Esc)), PopupResult::Cancelled("sync"));
+        assert!(popups.is_empty());
+    }
+}
@@ -0,0 +1,693 @@
+// Line Alignment
+// Aligns source and destination lines for side-by-side display
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::ops::Range;
+
+use super::moved::moved_lines;
+
+/// Word overlap (Jaccard) above which two differing lines are shown as one modified line
+pub const DEFAULT_SIMILARITY_THRESHOLD: f64 = 0.3;
+
+/// Lines between a line containing this marker and the next line containing `IGNORE_END_MARKER`
+/// are never reported as changes (e.g. a generated header that differs per project)
+pub const IGNORE_START_MARKER: &str = "sync-ignore-start";
+/// Ends an ignore region started by `IGNORE_START_MARKER`
+pub const IGNORE_END_MARKER: &str = "sync-ignore-end";
+
+/// Stand-in for a whole ignore region while aligning; regions pair with each other in order
+const IGNORED_REGION: &str = "\u{0}sync-ignore-region";
+
+/// Gaps between anchors up to this many DP cells (source × destination lines) are aligned exactly
+const MAX_DP_CELLS: usize = 250_000;
+
+/// How lines are aligned between source and destination
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LineAlignment {
+    /// Lines exist in both files at given indices
+    Both(usize, usize),
+    /// Line only exists in source (removed)
+    SourceOnly(usize),
+    /// Line only exists in destination (added)
+    DestOnly(usize),
+}
+
+/// Line counts of an alignment (like a per-file "+X −Y" summary)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DiffStats {
+    /// Lines only in the source
+    pub source_only: usize,
+    /// Lines only in the destination
+    pub dest_only: usize,
+    /// Lines paired as modified (present on both sides with different content)
+    pub modified: usize,
+}
+
+impl DiffStats {
+    /// Count the changes of an alignment; lines inside ignore regions don't count
+    pub fn from_alignment(aligned: &[LineAlignment], source: &[String], dest: &[String]) -> Self {
+        let (source_ignored, dest_ignored) = (ignored_lines(source), ignored_lines(dest));
+        let mut stats = Self::default();
+        for entry in aligned {
+            match *entry {
+                LineAlignment::Both(s, d) if source[s] != dest[d] && !(source_ignored[s] && dest_ignored[d]) => {
+                    stats.modified += 1
+                }
+                LineAlignment::Both(..) => {}
+                LineAlignment::SourceOnly(s) if !source_ignored[s] => stats.source_only += 1,
+                LineAlignment::DestOnly(d) if !dest_ignored[d] => stats.dest_only += 1,
+                LineAlignment::SourceOnly(_) | LineAlignment::DestOnly(_) => {}
+            }
+        }
+        stats
+    }
+
+    /// Source lines that differ (GitHub's "−" count when the destination is the newer side)
+    pub fn source_changes(&self) -> usize {
+        self.source_only + self.modified
+    }
+
+    /// Destination lines that differ (GitHub's "+" count when the destination is the newer side)
+    pub fn dest_changes(&self) -> usize {
+        self.dest_only + self.modified
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Which lines lie inside an ignore region (the marker lines themselves are compared normally)
+/// A start marker without an end ignores the rest of the file
+pub fn ignored_lines(lines: &[String]) -> Vec<bool> {
+    let mut inside = false;
+    lines
+        .iter()
+        .map(|line| {
+            if inside && line.contains(IGNORE_END_MARKER) {
+                inside = false;
+            } else if !inside && line.contains(IGNORE_START_MARKER) {
+                inside = true;
+                return false;
+            }
+            inside
+        })
+        .collect()
+}
+
+/// Whether two texts are equal once the contents of their ignore regions are left out
+pub fn equal_ignoring_regions(a: &str, b: &str) -> bool {
+    let kept = |text: &str| -> Vec<String> {
+        let lines: Vec<String> = text.lines().map(str::to_string).collect();
+        let ignored = ignored_lines(&lines);
+        lines.into_iter().zip(ignored).filter(|(_, ignored)| !ignored).map(|(line, _)| line).collect()
+    };
+    kept(a) == kept(b)
+}
+
+/// `source` with the contents of its ignore regions taken from `dest`, pairing regions in order
+/// Used when writing `source` over `dest`, so per-file regions (generated headers) survive;
+/// source regions without a counterpart in `dest` keep their own contents
+pub fn keep_ignored_regions(source: &str, dest: &str) -> String {
+    let source_lines: Vec<&str> = source.split_inclusive('\n').collect();
+    let dest_lines: Vec<&str> = dest.split_inclusive('\n').collect();
+
+    let mut merged = String::with_capacity(source.len());
+    let mut next = 0;
+    for (region, kept) in ignore_regions(&source_lines).into_iter().zip(ignore_regions(&dest_lines)) {
+        merged.extend(source_lines[next..region.start].iter().copied());
+        merged.extend(dest_lines[kept].iter().copied());
+        // A region that ran to the end of `dest` may lack its final newline
+        if region.end < source_lines.len() && !merged.ends_with('\n') {
+            merged.push('\n');
+        }
+        next = region.end;
+    }
+    merged.extend(source_lines[next..].iter().copied());
+    merged
+}
+
+/// Line ranges inside ignore regions, markers excluded (same rules as `ignored_lines`)
+fn ignore_regions(lines: &[&str]) -> Vec<Range<usize>> {
+    let mut regions = Vec::new();
+    let mut start = None;
+    for (idx, line) in lines.iter().enumerate() {
+        match start {
+            Some(first) if line.contains(IGNORE_END_MARKER) => {
+                regions.push(first..idx);
+                start = None;
+            }
+            None if line.contains(IGNORE_START_MARKER) => start = Some(idx + 1),
+            _ => {}
+        }
+    }
+    if let Some(first) = start {
+        regions.push(first..lines.len());
+    }
+    regions
+}
+
+/// Check if two lines are similar enough to show as modified (Both) vs separate (SourceOnly/DestOnly)
+///
+/// Lines differing only in whitespace are always similar. Otherwise the overlap of their
+/// significant words (those containing a letter or digit) must exceed `threshold`; operators and
+/// braces are ignored so lines like `let a = 1;` and `let b = foo();` don't pair up through `=`
+pub fn lines_are_similar(line1: &str, line2: &str, threshold: f64) -> bool {
+    if line1.trim() == line2.trim() {
+        return true;
+    }
+
+    let words1 = significant_words(line1);
+    let words2 = significant_words(line2);
+    if words1.is_empty() || words2.is_empty() {
+        // Blank or punctuation-only lines only pair with themselves
+        return false;
+    }
+
+    let intersection = words1.intersection(&words2).count();
+    let union = words1.union(&words2).count();
+    intersection as f64 / union as f64 > threshold
+}
+
+fn significant_words(line: &str) -> HashSet<&str> {
+    line.split_whitespace()
+        .filter(|word| word.chars().any(char::is_alphanumeric))
+        .collect()
+}
+
+/// Align lines between source and destination with the default similarity threshold
+pub fn align_lines(source: &[String], dest: &[String]) -> Vec<LineAlignment> {
+    align_lines_with(source, dest, DEFAULT_SIMILARITY_THRESHOLD)
+}
+
+/// Align lines between source and destination
+///
+/// Patience-style: strips the common prefix/suffix, anchors on lines that occur exactly once on
+/// both sides (longest increasing run of them) and recurses into the gaps between anchors.
+/// Small gaps get the exact LCS alignment; large gaps with nothing to anchor on are paired in
+/// order, so time and memory stay bounded on files with thousands of lines.
+///
+/// Each ignore region is aligned as a single line that matches the other file's regions, so
+/// its contents pair up line by line regardless of what they are.
+///
+/// Within every run of unpaired lines, removals (SourceOnly) come before additions (DestOnly).
+pub fn align_lines_with(source: &[String], dest: &[String], similarity_threshold: f64) -> Vec<LineAlignment> {
+    let (source_collapsed, source_map) = collapse_ignored(source);
+    let (dest_collapsed, dest_map) = collapse_ignored(dest);
+
+    let aligner = Aligner {
+        source: &source_collapsed,
+        dest: &dest_collapsed,
+        similarity_threshold,
+    };
+    let mut collapsed = Vec::with_capacity(source_collapsed.len().max(dest_collapsed.len()));
+    aligner.align_range(0..source_collapsed.len(), 0..dest_collapsed.len(), &mut collapsed);
+
+    // Expand collapsed indices back to file lines
+    let mut aligned = Vec::with_capacity(source.len().max(dest.len()));
+    for entry in collapsed {
+        match entry {
+            LineAlignment::Both(s, d) if source_collapsed[s] == IGNORED_REGION && dest_collapsed[d] == IGNORED_REGION => {
+                let (src, dst) = (source_map[s].clone(), dest_map[d].clone());
+                let paired = src.len().min(dst.len());
+                aligned.extend((0..paired).map(|k| LineAlignment::Both(src.start + k, dst.start + k)));
+                aligned.extend((src.start + paired..src.end).map(LineAlignment::SourceOnly));
+                aligned.extend((dst.start + paired..dst.end).map(LineAlignment::DestOnly));
+            }
+            LineAlignment::Both(s, d) if source_collapsed[s] != IGNORED_REGION && dest_collapsed[d] != IGNORED_REGION => {
+                aligned.push(LineAlignment::Both(source_map[s].start, dest_map[d].start));
+            }
+            LineAlignment::Both(s, d) => {
+                aligned.extend(source_map[s].clone().map(LineAlignment::SourceOnly));
+                aligned.extend(dest_map[d].clone().map(LineAlignment::DestOnly));
+            }
+            LineAlignment::SourceOnly(s) => aligned.extend(source_map[s].clone().map(LineAlignment::SourceOnly)),
+            LineAlignment::DestOnly(d) => aligned.extend(dest_map[d].clone().map(LineAlignment::DestOnly)),
+        }
+    }
+
+    order_changes(&mut aligned);
+    aligned
+}
+
+/// Replace every ignore region by a single `IGNORED_REGION` line
+/// Returns the collapsed lines and the file lines each of them stands for
+fn collapse_ignored(lines: &[String]) -> (Vec<String>, Vec<Range<usize>>) {
+    let ignored = ignored_lines(lines);
+    let mut collapsed = Vec::with_capacity(lines.len());
+    let mut map: Vec<Range<usize>> = Vec::with_capacity(lines.len());
+
+    for (i, line) in lines.iter().enumerate() {
+        if !ignored[i] {
+            collapsed.push(line.clone());
+            map.push(i..i + 1);
+        } else if i > 0 && ignored[i - 1] {
+            map.last_mut().expect("region started on an earlier line").end = i + 1;
+        } else {
+            collapsed.push(IGNORED_REGION.to_string());
+            map.push(i..i + 1);
+        }
+    }
+    (collapsed, map)
+}
+
+/// Put removals before additions within each run of unpaired lines (order inside each kind kept)
+fn order_changes(aligned: &mut [LineAlignment]) {
+    let is_unpaired = |entry: &LineAlignment| !matches!(entry, LineAlignment::Both(..));
+    let mut start = 0;
+    while start < aligned.len() {
+        if !is_unpaired(&aligned[start]) {
+            start += 1;
+            continue;
+        }
+        let len = aligned[start..].iter().take_while(|entry| is_unpaired(entry)).count();
+        aligned[start..start + len].sort_by_key(|entry| matches!(entry, LineAlignment::DestOnly(_)));
+        start += len;
+    }
+}
+
+struct Aligner<'a> {
+    source: &'a [String],
+    dest: &'a [String],
+    similarity_threshold: f64,
+}
+
+impl Aligner<'_> {
+    fn similar(&self, i: usize, j: usize) -> bool {
+        lines_are_similar(&self.source[i], &self.dest[j], self.similarity_threshold)
+    }
+
+    fn align_range(&self, mut src: Range<usize>, mut dst: Range<usize>, out: &mut Vec<LineAlignment>) {
+        let (source, dest) = (self.source, self.dest);
+
+        // Common prefix
+        while !src.is_empty() && !dst.is_empty() && source[src.start] == dest[dst.start] {
+            out.push(LineAlignment::Both(src.start, dst.start));
+            src.start += 1;
+            dst.start += 1;
+        }
+
+        // Common suffix (emitted after the middle)
+        let mut suffix = 0;
+        while suffix < src.len().min(dst.len()) && source[src.end - 1 - suffix] == dest[dst.end - 1 - suffix] {
+            suffix += 1;
+        }
+        src.end -= suffix;
+        dst.end -= suffix;
+
+        let anchors = self.unique_anchors(src.clone(), dst.clone());
+        if anchors.is_empty() {
+            self.align_gap(src.clone(), dst.clone(), out);
+        } else {
+            let (mut s, mut d) = (src.start, dst.start);
+            for (anchor_s, anchor_d) in anchors {
+                self.align_range(s..anchor_s, d..anchor_d, out);
+                out.push(LineAlignment::Both(anchor_s, anchor_d));
+                s = anchor_s + 1;
+                d = anchor_d + 1;
+            }
+            self.align_range(s..src.end, d..dst.end, out);
+        }
+
+        for k in 0..suffix {
+            out.push(LineAlignment::Both(src.end + k, dst.end + k));
+        }
+    }
+
+    /// Lines occurring exactly once in both ranges, reduced to the longest run that keeps
+    /// source and destination order
+    fn unique_anchors(&self, src: Range<usize>, dst: Range<usize>) -> Vec<(usize, usize)> {
+        // line -> (count in source, count in dest, index in dest)
+        let mut counts: HashMap<&str, (usize, usize, usize)> = HashMap::new();
+        for i in src.clone() {
+            counts.entry(self.source[i].as_str()).or_default().0 += 1;
+        }
+        for j in dst {
+            let entry = counts.entry(self.dest[j].as_str()).or_default();
+            entry.1 += 1;
+            entry.2 = j;
+        }
+
+        let candidates: Vec<(usize, usize)> = src
+            .filter_map(|i| match counts[self.source[i].as_str()] {
+                (1, 1, j) => Some((i, j)),
+                _ => None,
+            })
+            .collect();
+        longest_increasing(&candidates)
+    }
+
+    /// Align a gap without unique lines: exact LCS when small enough, otherwise pair lines in order
+    fn align_gap(&self, src: Range<usize>, dst: Range<usize>, out: &mut Vec<LineAlignment>) {
+        if src.len().saturating_mul(dst.len()) <= MAX_DP_CELLS {
+            self.align_lcs(src, dst, out);
+            return;
+        }
+
+        let paired = src.len().min(dst.len());
+        for k in 0..paired {
+            let (i, j) = (src.start + k, dst.start + k);
+            if self.source[i] == self.dest[j] || self.similar(i, j) {
+                out.push(LineAlignment::Both(i, j));
+            } else {
+                out.push(LineAlignment::SourceOnly(i));
+                out.push(LineAlignment::DestOnly(j));
+            }
+        }
+        out.extend((src.start + paired..src.end).map(LineAlignment::SourceOnly));
+        out.extend((dst.start + paired..dst.end).map(LineAlignment::DestOnly));
+    }
+
+    /// Exact alignment of a (small) gap using LCS (Longest Common Subsequence)
+    fn align_lcs(&self, src: Range<usize>, dst: Range<usize>, out: &mut Vec<LineAlignment>) {
+        let (source, dest) = (&self.source[src.clone()], &self.dest[dst.clone()]);
+        let n = source.len();
+        let m = dest.len();
+
+        // dp[i][j] = length of LCS of source[0..i] and dest[0..j]
+        let mut dp = vec![vec![0u32; m + 1]; n + 1];
+
+        for i in 1..=n {
+            for j in 1..=m {
+                if source[i - 1] == dest[j - 1] {
+                    dp[i][j] = dp[i - 1][j - 1] + 1;
+                } else {
+                    dp[i][j] = dp[i - 1][j].max(dp[i][j - 1]);
+                }
+            }
+        }
+
+        // Backtrack to build alignment, preferring exact matches, then similar lines
+        let mut aligned = Vec::new();
+        let mut i = n;
+        let mut j = m;
+
+        while i > 0 || j > 0 {
+            if i > 0 && j > 0 && source[i - 1] == dest[j - 1] {
+                aligned.push(LineAlignment::Both(src.start + i - 1, dst.start + j - 1));
+                i -= 1;
+                j -= 1;
+            } else if i > 0 && j > 0 {
+                if self.similar(src.start + i - 1, dst.start + j - 1) {
+                    // Similar lines are shown as modified for word-level highlighting
+                    aligned.push(LineAlignment::Both(src.start + i - 1, dst.start + j - 1));
+                    i -= 1;
+                    j -= 1;
+                } else if dp[i - 1][j] > dp[i][j - 1] {
+                    aligned.push(LineAlignment::SourceOnly(src.start + i - 1));
+                    i -= 1;
+                } else if dp[i][j - 1] > dp[i - 1][j] {
+                    aligned.push(LineAlignment::DestOnly(dst.start + j - 1));
+                    j -= 1;
+                } else {
+                    // Tie - show as separate changes (ordered afterwards by order_changes)
+                    aligned.push(LineAlignment::SourceOnly(src.start + i - 1));
+                    i -= 1;
+                }
+            } else if i > 0 {
+                aligned.push(LineAlignment::SourceOnly(src.start + i - 1));
+                i -= 1;
+            } else {
+                aligned.push(LineAlignment::DestOnly(dst.start + j - 1));
+                j -= 1;
+            }
+        }
+
+        // Reverse because we built backwards
+        out.extend(aligned.into_iter().rev());
+    }
+}
+
+/// Longest subsequence of `pairs` (sorted by source index) with increasing dest index
+/// (patience sorting)
+fn longest_increasing(pairs: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    // tails[len] = index into pairs of the smallest dest index ending a run of len + 1
+    let mut tails: Vec<usize> = Vec::new();
+    let mut prev: Vec<Option<usize>> = vec![None; pairs.len()];
+
+    for (k, &(_, d)) in pairs.iter().enumerate() {
+        let pos = tails.partition_point(|&t| pairs[t].1 < d);
+        if pos > 0 {
+            prev[k] = Some(tails[pos - 1]);
+        }
+        if pos == tails.len() {
+            tails.push(k);
+        } else {
+            tails[pos] = k;
+        }
+    }
+
+    let mut run = Vec::with_capacity(tails.len());
+    let mut next = tails.last().copied();
+    while let Some(k) = next {
+        run.push(pairs[k]);
+        next = prev[k];
+    }
+    run.reverse();
+    run
+}
+
+/// Alignment of the last (source, destination, threshold) inputs, reused until any of them changes
+#[derive(Debug, Default)]
+pub struct AlignmentCache {
+    /// (source hash, dest hash, threshold bits) of the cached alignment
+    key: Option<(u64, u64, u64)>,
+    aligned: Vec<LineAlignment>,
+    stats: DiffStats,
+    source_ignored: Vec<bool>,
+    dest_ignored: Vec<bool>,
+    source_moved: Vec<bool>,
+    dest_moved: Vec<bool>,
+}
+
+impl AlignmentCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Key of the inputs aligned last (None before the first call)
+    pub fn key(&self) -> Option<(u64, u64, u64)> {
+        self.key
+    }
+
+    /// Alignment computed by the last `align` call
+    pub fn aligned(&self) -> &[LineAlignment] {
+        &self.aligned
+    }
+
+    /// Lines inside ignore regions of the (source, dest) pair aligned last
+    pub fn ignored(&self) -> (&[bool], &[bool]) {
+        (&self.source_ignored, &self.dest_ignored)
+    }
+
+    /// Lines of the (source, dest) pair aligned last that belong to a moved block
+    pub fn moved(&self) -> (&[bool], &[bool]) {
+        (&self.source_moved, &self.dest_moved)
+    }
+
+    /// Line counts of the alignment computed by the last `align` call
+    pub fn stats(&self) -> DiffStats {
+        self.stats
+    }
+
+    /// Alignment of `source` and `dest`, computed only when the inputs differ from the last call
+    pub fn align(&mut self, source: &[String], dest: &[String], similarity_threshold: f64) -> &[LineAlignment] {
+        let key = (hash_lines(source), hash_lines(dest), similarity_threshold.to_bits());
+        if self.key != Some(key) {
+            self.aligned = align_lines_with(source, dest, similarity_threshold);
+            self.stats = DiffStats::from_alignment(&self.aligned, source, dest);
+            self.source_ignored = ignored_lines(source);
+            self.dest_ignored = ignored_lines(dest);
+            (self.source_moved, self.dest_moved) =
+                moved_lines(&self.aligned, source, dest, (&self.source_ignored, &self.dest_ignored));
+            self.key = Some(key);
+        }
+        &self.aligned
+    }
+}
+
+fn hash_lines(lines: &[String]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    lines.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn lines(text: &[&str]) -> Vec<String> {
+        text.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_identical_inputs_align_as_both() {
+        let source = lines(&["a", "b", "c"]);
+        let aligned = align_lines(&source, &source);
+        assert_eq!(
+            aligned,
+            vec![LineAlignment::Both(0, 0), LineAlignment::Both(1, 1), LineAlignment::Both(2, 2)]
+        );
+    }
+
+    #[test]
+    fn test_insertion_and_removal() {
+        let source = lines(&["keep", "removed line here"]);
+        let dest = lines(&["keep", "totally different"]);
+        let aligned = align_lines(&source, &dest);
+        assert_eq!(aligned[0], LineAlignment::Both(0, 0));
+        assert!(aligned.contains(&LineAlignment::SourceOnly(1)));
+        assert!(aligned.contains(&LineAlignment::DestOnly(1)));
+
+        let stats = DiffStats::from_alignment(&aligned, &source, &dest);
+        assert_eq!(stats, DiffStats { source_only: 1, dest_only: 1, modified: 0 });
+    }
+
+    #[test]
+    fn test_large_files_anchor_on_unique_lines() {
+        // Far beyond what a full DP table could handle (40k × 40k cells)
+        let source: Vec<String> = (0..40_000).map(|i| format!("line {}", i)).collect();
+        let mut dest = source.clone();
+        dest.insert(20_000, "inserted".to_string());
+        dest[30_000] = "line 29999 edited".to_string();
+
+        let aligned = align_lines(&source, &dest);
+        assert!(aligned.contains(&LineAlignment::DestOnly(20_000)));
+        assert!(aligned.contains(&LineAlignment::Both(29_999, 30_000)));
+        let unchanged = aligned
+            .iter()
+            .filter(|entry| matches!(entry, LineAlignment::Both(s, d) if source[*s] == dest[*d]))
+            .count();
+        assert_eq!(unchanged, 39_999);
+
+        // Cached until either side changes
+        let mut cache = AlignmentCache::new();
+        assert_eq!(cache.align(&source, &dest, DEFAULT_SIMILARITY_THRESHOLD), aligned.as_slice());
+        dest.pop();
+        assert!(cache.align(&source, &dest, DEFAULT_SIMILARITY_THRESHOLD).contains(&LineAlignment::SourceOnly(39_999)));
+    }
+
+    #[test]
+    fn test_similarity_ignores_punctuation_and_honors_threshold() {
+        // Only the operator is shared, so these aren't one modified line
+        assert!(!lines_are_similar("let a = 1;", "let b = foo();", DEFAULT_SIMILARITY_THRESHOLD));
+        assert!(!lines_are_similar("}", "{", DEFAULT_SIMILARITY_THRESHOLD));
+        assert!(!lines_are_similar("", "text", DEFAULT_SIMILARITY_THRESHOLD));
+        // Re-indentation is a modification, not a removal plus an addition
+        assert!(lines_are_similar("    }", "}", DEFAULT_SIMILARITY_THRESHOLD));
+
+        assert!(lines_are_similar("return total;", "return sum;", DEFAULT_SIMILARITY_THRESHOLD));
+        assert!(!lines_are_similar("return total;", "return sum;", 0.5));
+    }
+
+    #[test]
+    fn test_removals_come_before_additions() {
+        let source = lines(&["keep", "old one", "old two", "end"]);
+        let dest = lines(&["keep", "new alpha", "new beta", "new gamma", "end"]);
+        let aligned = align_lines_with(&source, &dest, 1.0);
+        assert_eq!(
+            aligned,
+            vec![
+                LineAlignment::Both(0, 0),
+                LineAlignment::SourceOnly(1),
+                LineAlignment::SourceOnly(2),
+                LineAlignment::DestOnly(1),
+                LineAlignment::DestOnly(2),
+                LineAlignment::DestOnly(3),
+                LineAlignment::Both(3, 4),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ignore_regions_pair_regardless_of_content() {
+        let source = lines(&["a", "// sync-ignore-start", "project: one", "id: 1", "// sync-ignore-end", "b"]);
+        let dest = lines(&["a", "// sync-ignore-start", "project: two", "// sync-ignore-end", "b"]);
+        let aligned = align_lines(&source, &dest);
+        assert!(aligned.contains(&LineAlignment::Both(2, 2)));
+        assert!(aligned.contains(&LineAlignment::SourceOnly(3)));
+        assert!(aligned.contains(&LineAlignment::Both(5, 4)));
+        assert!(DiffStats::from_alignment(&aligned, &source, &dest).is_empty());
+
+        assert!(equal_ignoring_regions(&source.join("\n"), &dest.join("\n")));
+        assert!(!equal_ignoring_regions("x\n// sync-ignore-start\n1", "y\n// sync-ignore-start\n2"));
+    }
+
+    #[test]
+    fn test_keep_ignored_regions_takes_destination_contents() {
+        let source = "a\n# sync-ignore-start\nproject: shared\n# sync-ignore-end\nb2\n# sync-ignore-start\n# sync-ignore-end\n";
+        let dest = "a\n# sync-ignore-start\nproject: alpha\nid: 7\n# sync-ignore-end\nb1\n# sync-ignore-start\nlocal\n# sync-ignore-end\n";
+        assert_eq!(
+            keep_ignored_regions(source, dest),
+            "a\n# sync-ignore-start\nproject: alpha\nid: 7\n# sync-ignore-end\nb2\n# sync-ignore-start\nlocal\n# sync-ignore-end\n"
+        );
+
+        // Extra source regions keep their contents; a destination without regions changes nothing
+        let source = "# sync-ignore-start\nx\n# sync-ignore-end\n# sync-ignore-start\ny\n# sync-ignore-end";
+        assert_eq!(
+            keep_ignored_regions(source, "# sync-ignore-start\nz"),
+            "# sync-ignore-start\nz\n# sync-ignore-end\n# sync-ignore-start\ny\n# sync-ignore-end"
+        );
+        assert_eq!(keep_ignored_regions(source, "plain\n"), source);
+    }
+    /// Lines drawn from a small vocabulary so inputs share lines, near-duplicates and ignore markers
+    fn arb_lines(max_len: usize) -> impl Strategy<Value = Vec<String>> {
+        let line = prop_oneof![
+            8 => "(let|return|fn|x|y) [a-c]{0,2}",
+            2 => Just(String::new()),
+            1 => Just("}".to_string()),
+            1 => Just(format!("// {}", IGNORE_START_MARKER)),
+            1 => Just(format!("// {}", IGNORE_END_MARKER)),
+        ];
+        prop::collection::vec(line, 0..max_len)
+    }
+
+    /// Source and destination indices in the order the alignment shows them
+    fn sides(aligned: &[LineAlignment]) -> (Vec<usize>, Vec<usize>) {
+        let (mut source, mut dest) = (Vec::new(), Vec::new());
+        for entry in aligned {
+            match *entry {
+                LineAlignment::Both(s, d) => {
+                    source.push(s);
+                    dest.push(d);
+                }
+                LineAlignment::SourceOnly(s) => source.push(s),
+                LineAlignment::DestOnly(d) => dest.push(d),
+            }
+        }
+        (source, dest)
+    }
+
+    proptest! {
+        #[test]
+        fn prop_every_line_appears_once_in_order(
+            source in arb_lines(40),
+            dest in arb_lines(40),
+            threshold in 0.0..=1.0f64,
+        ) {
+            let aligned = align_lines_with(&source, &dest, threshold);
+            let (source_seen, dest_seen) = sides(&aligned);
+            // Each index exactly once and increasing means the sequence is exactly 0..len
+            prop_assert_eq!(source_seen, (0..source.len()).collect::<Vec<_>>());
+            prop_assert_eq!(dest_seen, (0..dest.len()).collect::<Vec<_>>());
+        }
+
+        #[test]
+        fn prop_identical_inputs_align_as_both(source in arb_lines(60)) {
+            let aligned = align_lines(&source, &source);
+            let expected: Vec<LineAlignment> = (0..source.len()).map(|i| LineAlignment::Both(i, i)).collect();
+            prop_assert_eq!(&aligned, &expected);
+            prop_assert!(DiffStats::from_alignment(&aligned, &source, &source).is_empty());
+        }
+
+        #[test]
+        fn prop_cache_matches_direct_alignment(source in arb_lines(30), dest in arb_lines(30)) {
+            let mut cache = AlignmentCache::new();
+            let cached = cache.align(&source, &dest, DEFAULT_SIMILARITY_THRESHOLD).to_vec();
+            prop_assert_eq!(cached, align_lines(&source, &dest));
+        }
+    }
+}
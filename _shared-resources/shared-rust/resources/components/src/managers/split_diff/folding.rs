@@ -0,0 +1,92 @@
+// Folding
+// Per-region fold state for the split diff: unchanged regions fold by default and can be
+// expanded one at a time
+//
+// A region is identified by the alignment index of its first unchanged line, which stays
+// stable for as long as the file contents don't change; truncated long lines are expanded the
+// same way, by their own alignment index
+
+use std::collections::BTreeSet;
+
+/// Fold state of one file's split diff
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FoldState {
+    /// Fold long unchanged regions (except the expanded ones)
+    pub fold_unchanged: bool,
+    /// Regions shown in full despite `fold_unchanged`
+    expanded: BTreeSet<usize>,
+    /// Long lines shown whole instead of truncated
+    expanded_lines: BTreeSet<usize>,
+}
+
+impl Default for FoldState {
+    fn default() -> Self {
+        Self::new(true)
+    }
+}
+
+impl FoldState {
+    pub fn new(fold_unchanged: bool) -> Self {
+        Self {
+            fold_unchanged,
+            expanded: BTreeSet::new(),
+            expanded_lines: BTreeSet::new(),
+        }
+    }
+
+    /// Whether the region starting at `region` is currently folded (if it is long enough)
+    pub fn is_folded(&self, region: usize) -> bool {
+        self.fold_unchanged && !self.expanded.contains(&region)
+    }
+
+    /// Show a single region in full; returns false if it was already expanded
+    pub fn expand(&mut self, region: usize) -> bool {
+        self.expanded.insert(region)
+    }
+
+    /// Fold a previously expanded region again
+    pub fn collapse(&mut self, region: usize) -> bool {
+        self.expanded.remove(&region)
+    }
+
+    /// Flip folding for the whole file, forgetting individually expanded regions
+    pub fn toggle_all(&mut self) {
+        self.fold_unchanged = !self.fold_unchanged;
+        self.expanded.clear();
+    }
+
+    /// Show a truncated long line whole; returns false if it already was
+    pub fn expand_line(&mut self, index: usize) -> bool {
+        self.expanded_lines.insert(index)
+    }
+
+    /// Whether the line at alignment `index` is shown whole even if it is long
+    pub fn is_line_expanded(&self, index: usize) -> bool {
+        self.expanded_lines.contains(&index)
+    }
+
+    /// Number of individually expanded regions
+    pub fn expanded_count(&self) -> usize {
+        self.expanded.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_single_region_and_toggle_all() {
+        let mut folds = FoldState::default();
+        assert!(folds.expand(12));
+        assert!(!folds.expand(12));
+        assert!(!folds.is_folded(12));
+        assert!(folds.is_folded(40));
+
+        folds.toggle_all();
+        assert!(!folds.is_folded(40));
+        folds.toggle_all();
+        assert!(folds.is_folded(12));
+        assert_eq!(folds.expanded_count(), 0);
+    }
+}
@@ -0,0 +1,388 @@
+// Line Wrapping
+// Builds gutter + word-wrapped, highlighted lines for the split diff panels
+
+use std::borrow::Cow;
+
+use ratatui::{
+    style::{Modifier, Style},
+    text::{Line, Span},
+};
+
+use crate::utilities::hex_color;
+
+/// Background for modified source lines (dim red)
+pub fn source_modified_bg() -> Style {
+    Style::default().bg(hex_color(0x370404))
+}
+
+/// Highlight for changed parts in source (bright red)
+pub fn source_highlight() -> Style {
+    Style::default().bg(hex_color(0x5F0303))
+}
+
+/// Background for modified destination lines (dim green)
+pub fn dest_modified_bg() -> Style {
+    Style::default().bg(hex_color(0x232915))
+}
+
+/// Highlight for changed parts in destination (bright green)
+pub fn dest_highlight() -> Style {
+    Style::default().bg(hex_color(0x3B491B))
+}
+
+/// Background for lines of a moved block, on either side (dim purple)
+pub fn moved_bg() -> Style {
+    Style::default().bg(hex_color(0x2A2140))
+}
+
+/// Gutter (line numbers) style
+pub fn gutter_style() -> Style {
+    Style::default().fg(hex_color(0x444444))
+}
+
+/// Gutter style of the continuation rows of a wrapped line (dimmer than the line numbers)
+pub fn wrap_marker_style() -> Style {
+    Style::default().fg(hex_color(0x303030))
+}
+
+/// Fold indicator style
+pub fn fold_indicator_style() -> Style {
+    Style::default()
+        .fg(hex_color(0x969696))
+        .add_modifier(Modifier::ITALIC)
+}
+
+/// Split text into "word+whitespace" units where whitespace is attached to the preceding word
+pub fn split_into_word_units(text: &str) -> Vec<String> {
+    let mut units = Vec::new();
+    let mut current_unit = String::new();
+    let mut in_word = false;
+
+    for c in text.chars() {
+        if c.is_whitespace() {
+            current_unit.push(c);
+            if in_word {
+                units.push(std::mem::take(&mut current_unit));
+                in_word = false;
+            }
+        } else {
+            if !in_word && !current_unit.is_empty() {
+                // Leading whitespace forms its own unit
+                units.push(std::mem::take(&mut current_unit));
+            }
+            current_unit.push(c);
+            in_word = true;
+        }
+    }
+
+    if !current_unit.is_empty() {
+        units.push(current_unit);
+    }
+
+    units
+}
+
+/// Segments with every tab replaced by spaces up to the next multiple of `tab_width` columns
+/// Columns are counted across segments, so changed parts keep their place and both panels line
+/// up; a `tab_width` of 0 leaves tabs as they are
+pub fn expand_tabs(diffs: &[(String, bool)], tab_width: usize) -> Cow<'_, [(String, bool)]> {
+    if tab_width == 0 || !diffs.iter().any(|(text, _)| text.contains('\t')) {
+        return Cow::Borrowed(diffs);
+    }
+    let mut column = 0;
+    let expanded = diffs
+        .iter()
+        .map(|(text, is_changed)| {
+            let mut out = String::with_capacity(text.len());
+            for c in text.chars() {
+                if c == '\t' {
+                    let spaces = tab_width - column % tab_width;
+                    out.extend(std::iter::repeat_n(' ', spaces));
+                    column += spaces;
+                } else {
+                    out.push(c);
+                    column += 1;
+                }
+            }
+            (out, *is_changed)
+        })
+        .collect();
+    Cow::Owned(expanded)
+}
+
+/// Line numbers shown in the gutter of a row
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GutterNumbers {
+    /// The panel's own line number
+    Single(usize),
+    /// Source and destination line numbers of the row; None where that side has no line
+    Dual(Option<usize>, Option<usize>),
+}
+
+impl GutterNumbers {
+    /// Gutter text: each number right-aligned to `max_line_digits`, then a space
+    pub fn format(self, max_line_digits: usize) -> String {
+        let number = |n: Option<usize>| match n {
+            Some(n) => format!("{:width$}", n, width = max_line_digits),
+            None => " ".repeat(max_line_digits),
+        };
+        match self {
+            Self::Single(n) => format!("{} ", number(Some(n))),
+            Self::Dual(source, dest) => format!("{} {} ", number(source), number(dest)),
+        }
+    }
+}
+
+/// What the gutter shows on the continuation rows of a wrapped line, so they don't read as
+/// lines of their own
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WrapMarker {
+    /// `↳` in place of the line number
+    #[default]
+    Arrow,
+    /// An empty gutter
+    Blank,
+}
+
+impl WrapMarker {
+    /// Marker by its config name ("arrow" or "blank"); other names get the default
+    pub fn named(name: &str) -> Self {
+        match name.trim() {
+            "blank" => Self::Blank,
+            _ => Self::Arrow,
+        }
+    }
+
+    /// Gutter text of a continuation row, right-aligned like the line numbers
+    pub fn gutter(self, gutter_width: usize) -> String {
+        match self {
+            Self::Arrow if gutter_width > 1 => format!("{:>width$} ", "↳", width = gutter_width - 1),
+            _ => " ".repeat(gutter_width),
+        }
+    }
+}
+
+/// Accumulates spans and flushes them into padded, gutter-prefixed lines
+struct LineBuilder {
+    gutter: String,
+    continuation_gutter: String,
+    text_width: usize,
+    base_style: Style,
+    lines: Vec<Line<'static>>,
+    spans: Vec<Span<'static>>,
+    width: usize,
+}
+
+impl LineBuilder {
+    fn flush(&mut self) {
+        let gutter = if self.lines.is_empty() {
+            Span::styled(self.gutter.clone(), gutter_style())
+        } else {
+            Span::styled(self.continuation_gutter.clone(), wrap_marker_style())
+        };
+
+        let mut line_spans = vec![gutter];
+        line_spans.append(&mut self.spans);
+
+        // Pad to the full text width so backgrounds span the panel
+        let padding_len = self.text_width.saturating_sub(self.width);
+        if padding_len > 0 {
+            line_spans.push(Span::styled(" ".repeat(padding_len), self.base_style));
+        }
+
+        // Right margin
+        line_spans.push(Span::styled(" ", self.base_style));
+
+        self.lines.push(Line::from(line_spans));
+        self.width = 0;
+    }
+
+    fn push(&mut self, text: String, width: usize, style: Style) {
+        self.spans.push(Span::styled(text, style));
+        self.width += width;
+    }
+}
+
+/// Create one or more wrapped lines for a single source line
+/// `diffs` holds (text, is_changed) segments; changed segments use `highlight_style`
+/// Rows after the first carry `wrap_marker` in the gutter instead of the line number; tabs are
+/// expanded to `tab_width` columns first (see `expand_tabs`)
+#[allow(clippy::too_many_arguments)]
+pub fn create_highlighted_lines(
+    numbers: GutterNumbers,
+    diffs: &[(String, bool)],
+    text_width: usize,
+    gutter_width: usize,
+    max_line_digits: usize,
+    wrap_marker: WrapMarker,
+    tab_width: usize,
+    base_style: Style,
+    highlight_style: Style,
+) -> Vec<Line<'static>> {
+    let mut builder = LineBuilder {
+        gutter: numbers.format(max_line_digits),
+        continuation_gutter: wrap_marker.gutter(gutter_width),
+        text_width,
+        base_style,
+        lines: Vec::new(),
+        spans: Vec::new(),
+        width: 0,
+    };
+
+    for (text, is_changed) in expand_tabs(diffs, tab_width).iter() {
+        let style = if *is_changed { highlight_style } else { base_style };
+
+        for unit in split_into_word_units(text) {
+            let unit_width = unit.chars().count();
+
+            // Wrap before a unit that doesn't fit on a partially filled line
+            if unit_width > text_width.saturating_sub(builder.width) && !builder.spans.is_empty() {
+                builder.flush();
+            }
+
+            if unit_width <= text_width.saturating_sub(builder.width) {
+                builder.push(unit, unit_width, style);
+                continue;
+            }
+
+            // Unit is longer than a whole line, break it character by character
+            let chars: Vec<char> = unit.chars().collect();
+            let mut char_idx = 0;
+            while char_idx < chars.len() {
+                let remaining = text_width.saturating_sub(builder.width);
+                if remaining == 0 {
+                    if builder.spans.is_empty() {
+                        // Zero-width panel: nothing can be placed
+                        break;
+                    }
+                    builder.flush();
+                    continue;
+                }
+
+                let take_count = remaining.min(chars.len() - char_idx);
+                let segment: String = chars[char_idx..char_idx + take_count].iter().collect();
+                builder.push(segment, take_count, style);
+                char_idx += take_count;
+            }
+        }
+    }
+
+    // Final (or only) line; empty lines still get a gutter and background
+    if !builder.spans.is_empty() || builder.lines.is_empty() {
+        builder.flush();
+    }
+
+    builder.lines
+}
+
+/// Create a blank filler line used to keep both panels aligned
+pub fn create_blank_line(text_width: usize, gutter_width: usize) -> Line<'static> {
+    Line::from(vec![
+        Span::styled(" ".repeat(gutter_width), gutter_style()),
+        Span::raw(" ".repeat(text_width)),
+        Span::raw(" "), // Right margin
+    ])
+}
+
+/// Create the "N lines hidden" indicator shown in place of a folded region
+pub fn create_fold_indicator(hidden_count: usize, text_width: usize, gutter_width: usize) -> Line<'static> {
+    let text = format!("{} lines hidden", hidden_count);
+    let padding_len = text_width.saturating_sub(text.len());
+
+    Line::from(vec![
+        Span::styled(" ".repeat(gutter_width), gutter_style()),
+        Span::styled(text, fold_indicator_style()),
+        Span::raw(" ".repeat(padding_len)),
+        Span::raw(" "), // Right margin
+    ])
+}
+
+/// Visible part of a line cut at `limit` characters and the number of characters hidden past it
+/// A `limit` of 0 keeps every line whole
+pub fn long_line_window(line: &str, limit: usize) -> (&str, usize) {
+    if limit == 0 {
+        return (line, 0);
+    }
+    match line.char_indices().nth(limit) {
+        Some((end, _)) => (&line[..end], line[end..].chars().count()),
+        None => (line, 0),
+    }
+}
+
+/// Create the "line continues" marker shown below a truncated line
+pub fn create_continuation_marker(hidden_chars: usize, text_width: usize, gutter_width: usize) -> Line<'static> {
+    let text = format!("… line continues ({} chars)", hidden_chars);
+    let padding_len = text_width.saturating_sub(text.chars().count());
+
+    Line::from(vec![
+        Span::styled(" ".repeat(gutter_width), gutter_style()),
+        Span::styled(text, fold_indicator_style()),
+        Span::raw(" ".repeat(padding_len)),
+        Span::raw(" "), // Right margin
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_into_word_units() {
+        assert_eq!(split_into_word_units("  foo bar"), vec!["  ", "foo ", "bar"]);
+    }
+
+    #[test]
+    fn test_long_line_wraps_to_text_width() {
+        let lines = create_highlighted_lines(
+            GutterNumbers::Single(1),
+            &[("alpha beta gamma".to_string(), false)],
+            6,
+            2,
+            1,
+            WrapMarker::Arrow,
+            4,
+            Style::default(),
+            Style::default(),
+        );
+        assert_eq!(lines.len(), 3);
+        // gutter + text + right margin
+        assert!(lines.iter().all(|l| l.width() == 2 + 6 + 1));
+        // Only the first row is numbered; the others are marked as its continuation
+        assert_eq!(lines[0].spans[0].content, "1 ");
+        assert_eq!(lines[1].spans[0].content, "↳ ");
+        assert_eq!(lines[2].spans[0].style, wrap_marker_style());
+    }
+
+    #[test]
+    fn test_tabs_expand_to_tab_stops_across_segments() {
+        let diffs = [("a\tb".to_string(), false), ("\tc".to_string(), true)];
+        let expanded = expand_tabs(&diffs, 4);
+        assert_eq!(expanded[0], ("a   b".to_string(), false));
+        assert_eq!(expanded[1], ("   c".to_string(), true));
+        assert_eq!(expand_tabs(&diffs, 0)[0].0, "a\tb");
+    }
+
+    #[test]
+    fn test_wrap_marker_gutter() {
+        assert_eq!(WrapMarker::Arrow.gutter(4), "  ↳ ");
+        assert_eq!(WrapMarker::Blank.gutter(4), "    ");
+        assert_eq!(WrapMarker::Arrow.gutter(1), " ");
+        assert_eq!(WrapMarker::named("blank"), WrapMarker::Blank);
+        assert_eq!(WrapMarker::named("dots"), WrapMarker::Arrow);
+    }
+
+    #[test]
+    fn test_dual_gutter_numbers() {
+        assert_eq!(GutterNumbers::Single(7).format(3), "  7 ");
+        assert_eq!(GutterNumbers::Dual(Some(7), Some(12)).format(3), "  7  12 ");
+        assert_eq!(GutterNumbers::Dual(None, Some(12)).format(2), "   12 ");
+        assert_eq!(GutterNumbers::Dual(Some(7), None).format(2), " 7    ");
+    }
+
+    #[test]
+    fn test_long_line_window_cuts_on_char_boundaries() {
+        assert_eq!(long_line_window("héllo wörld", 4), ("héll", 7));
+        assert_eq!(long_line_window("short", 5), ("short", 0));
+        assert_eq!(long_line_window("no limit", 0), ("no limit", 0));
+    }
+}
@@ -0,0 +1,147 @@
+// Minimap
+// Condenses the kind of every rendered row into a narrow strip beside the split diff
+// (like the VSCode overview ruler) and maps strip positions back to scroll offsets
+
+use ratatui::{
+    style::Style,
+    text::{Line, Span},
+};
+
+use crate::utilities::hex_color;
+
+/// What a rendered row of the split diff shows
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RowKind {
+    Unchanged,
+    /// Line exists on both sides with different content
+    Modified,
+    /// Line only exists in the destination
+    Added,
+    /// Line only exists in the source
+    Removed,
+    /// Line of a block that moved within the file
+    Moved,
+    /// "N lines hidden" indicator
+    Folded,
+}
+
+impl RowKind {
+    fn is_change(self) -> bool {
+        matches!(self, RowKind::Modified | RowKind::Added | RowKind::Removed | RowKind::Moved)
+    }
+
+    fn color(self) -> u32 {
+        match self {
+            RowKind::Unchanged => 0x2A2A2A,
+            RowKind::Modified => 0xD7BA7D,
+            RowKind::Added => 0x6A9955,
+            RowKind::Removed => 0xC24040,
+            RowKind::Moved => 0x9C7BD6,
+            RowKind::Folded => 0x555555,
+        }
+    }
+}
+
+/// Range of rows `[start, end)` summarized by minimap cell `cell`
+fn cell_rows(cell: usize, height: usize, total_rows: usize) -> (usize, usize) {
+    if total_rows <= height {
+        return (cell.min(total_rows), (cell + 1).min(total_rows));
+    }
+    (cell * total_rows / height, (cell + 1) * total_rows / height)
+}
+
+/// Reduce the row kinds to `height` cells; a cell shows its most frequent change,
+/// so a single changed line in a long unchanged stretch stays visible
+pub fn summarize(row_kinds: &[RowKind], height: usize) -> Vec<Option<RowKind>> {
+    (0..height)
+        .map(|cell| {
+            let (start, end) = cell_rows(cell, height, row_kinds.len());
+            let rows = &row_kinds[start..end];
+            if rows.is_empty() {
+                return None;
+            }
+
+            let count = |kind: RowKind| rows.iter().filter(|k| **k == kind).count();
+            let change = [RowKind::Modified, RowKind::Added, RowKind::Removed, RowKind::Moved]
+                .into_iter()
+                .map(|kind| (count(kind), kind))
+                .filter(|(n, _)| *n > 0)
+                .max_by_key(|(n, _)| *n)
+                .map(|(_, kind)| kind);
+
+            Some(change.unwrap_or(if rows.contains(&RowKind::Folded) {
+                RowKind::Folded
+            } else {
+                RowKind::Unchanged
+            }))
+        })
+        .collect()
+}
+
+/// Scroll offset that centers the rows under minimap cell `cell` in a viewport of `height` rows
+pub fn scroll_offset_for_cell(cell: usize, height: usize, total_rows: usize) -> usize {
+    let (start, _) = cell_rows(cell.min(height.saturating_sub(1)), height, total_rows);
+    start.saturating_sub(height / 2)
+}
+
+/// Build the minimap strip; cells covering the visible rows get a lighter background
+pub fn create_minimap_lines(
+    row_kinds: &[RowKind],
+    height: usize,
+    scroll_offset: usize,
+    visible_rows: usize,
+) -> Vec<Line<'static>> {
+    let viewport_end = scroll_offset + visible_rows;
+
+    summarize(row_kinds, height)
+        .into_iter()
+        .enumerate()
+        .map(|(cell, kind)| {
+            let (start, end) = cell_rows(cell, height, row_kinds.len());
+            let in_viewport = start < viewport_end && end.max(start + 1) > scroll_offset;
+
+            let mut style = Style::default();
+            if in_viewport {
+                style = style.bg(hex_color(0x303030));
+            }
+            let symbol = match kind {
+                Some(kind) if kind.is_change() => {
+                    style = style.fg(hex_color(kind.color()));
+                    "▐"
+                }
+                Some(kind) => {
+                    style = style.fg(hex_color(kind.color()));
+                    "│"
+                }
+                None => " ",
+            };
+            Line::from(Span::styled(symbol, style))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summarize_keeps_isolated_changes_and_maps_clicks() {
+        let mut rows = vec![RowKind::Unchanged; 100];
+        rows[55] = RowKind::Added;
+        rows[90] = RowKind::Modified;
+        rows[91] = RowKind::Removed;
+        rows[92] = RowKind::Removed;
+
+        let cells = summarize(&rows, 10);
+        assert_eq!(cells[0], Some(RowKind::Unchanged));
+        assert_eq!(cells[5], Some(RowKind::Added));
+        assert_eq!(cells[9], Some(RowKind::Removed));
+
+        // Short files map one row per cell and leave the rest blank
+        assert_eq!(summarize(&rows[..3], 5)[3], None);
+
+        // Clicking the last cell centers row 90 in a 10-row viewport
+        assert_eq!(scroll_offset_for_cell(9, 10, 100), 85);
+        assert_eq!(scroll_offset_for_cell(0, 10, 100), 0);
+    }
+}
@@ -0,0 +1,41 @@
+// Split Diff Manager
+// Business logic for the split diff view: line alignment, word-level diffs, folding and wrapping
+//
+// SplitDiffView (elements) only lays out bounding boxes and renders the pre-computed lines;
+// everything that decides *what* is shown lives here so it can be tested without a Frame.
+
+pub mod alignment;
+pub mod folding;
+pub mod line_wrapping;
+pub mod minimap;
+pub mod moved;
+pub mod rendering;
+pub mod word_diff;
+
+pub use alignment::{
+    align_lines, align_lines_with, equal_ignoring_regions, ignored_lines, keep_ignored_regions, AlignmentCache, DiffStats, LineAlignment,
+    DEFAULT_SIMILARITY_THRESHOLD, IGNORE_END_MARKER, IGNORE_START_MARKER,
+};
+pub use folding::FoldState;
+pub use line_wrapping::{expand_tabs, long_line_window, GutterNumbers, WrapMarker};
+pub use minimap::RowKind;
+pub use moved::{moved_lines, MIN_MOVED_ALNUM};
+pub use rendering::{RenderParams, SplitDiffRenderData};
+pub(crate) use rendering::RowCache;
+pub use word_diff::{compute_word_diff_dest, compute_word_diff_source};
+
+// ┌────────────────────────────────────────────────────────────────────────────────────────────────┐
+// │                            Split Diff Manager - Render Data Computation                        │
+// └────────────────────────────────────────────────────────────────────────────────────────────────┘
+
+/// Split diff manager
+/// Stateless entry point that turns source/destination lines into renderable panel lines
+pub struct SplitDiffManager;
+
+impl SplitDiffManager {
+    /// Compute the visible lines for both panels
+    /// Applies alignment, folding, wrapping, scrolling and height truncation
+    pub fn compute_render_data_static(params: RenderParams) -> SplitDiffRenderData {
+        rendering::compute_render_data(params)
+    }
+}
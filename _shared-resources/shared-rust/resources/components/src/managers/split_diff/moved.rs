@@ -0,0 +1,124 @@
+// Moved Blocks
+// Finds removed and added runs with the same content (like `git diff --color-moved`), so a
+// block that only changed place is shown as moved instead of as a removal plus an addition
+
+use std::collections::HashMap;
+
+use super::alignment::LineAlignment;
+
+/// Alphanumeric characters a block needs to count as moved (git's default), so a lone `}`
+/// or blank line that happens to reappear elsewhere isn't reported as a move
+pub const MIN_MOVED_ALNUM: usize = 20;
+
+/// Which (source, destination) lines belong to a block that moved within the file
+/// Only one-sided lines outside ignore regions can be part of a move; each removed block is
+/// matched with the longest unclaimed added block starting with the same line
+pub fn moved_lines(
+    aligned: &[LineAlignment],
+    source: &[String],
+    dest: &[String],
+    (source_ignored, dest_ignored): (&[bool], &[bool]),
+) -> (Vec<bool>, Vec<bool>) {
+    let mut removed = vec![false; source.len()];
+    let mut added = vec![false; dest.len()];
+    for entry in aligned {
+        match *entry {
+            LineAlignment::SourceOnly(s) if !source_ignored[s] => removed[s] = true,
+            LineAlignment::DestOnly(d) if !dest_ignored[d] => added[d] = true,
+            _ => {}
+        }
+    }
+
+    // Added lines by content, in file order
+    let mut candidates: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (d, line) in dest.iter().enumerate().filter(|(d, _)| added[*d]) {
+        candidates.entry(line.as_str()).or_default().push(d);
+    }
+
+    let mut source_moved = vec![false; source.len()];
+    let mut dest_moved = vec![false; dest.len()];
+    let mut s = 0;
+    while s < source.len() {
+        if !removed[s] {
+            s += 1;
+            continue;
+        }
+        let block_len = |d: usize| {
+            (0..)
+                .take_while(|&k| {
+                    s + k < source.len()
+                        && d + k < dest.len()
+                        && removed[s + k]
+                        && added[d + k]
+                        && !dest_moved[d + k]
+                        && source[s + k] == dest[d + k]
+                })
+                .count()
+        };
+        let best = candidates
+            .get(source[s].as_str())
+            .into_iter()
+            .flatten()
+            .filter(|&&d| !dest_moved[d])
+            .map(|&d| (block_len(d), d))
+            .max_by_key(|&(len, d)| (len, std::cmp::Reverse(d)));
+
+        match best {
+            Some((len, d)) if alnum_count(&source[s..s + len]) >= MIN_MOVED_ALNUM => {
+                source_moved[s..s + len].fill(true);
+                dest_moved[d..d + len].fill(true);
+                s += len;
+            }
+            _ => s += 1,
+        }
+    }
+    (source_moved, dest_moved)
+}
+
+fn alnum_count(lines: &[String]) -> usize {
+    lines.iter().flat_map(|line| line.chars()).filter(|c| c.is_alphanumeric()).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::managers::split_diff::alignment::{align_lines, ignored_lines};
+
+    fn lines(text: &[&str]) -> Vec<String> {
+        text.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_moved_block_is_found_and_short_lines_are_not() {
+        let source = lines(&[
+            "fn helper() {",
+            "    compute_the_answer()",
+            "}",
+            "fn main() {",
+            "    run();",
+            "}",
+            "x",
+        ]);
+        let dest = lines(&["fn main() {", "    run();", "}", "fn helper() {", "    compute_the_answer()", "}", "y", "x"]);
+        let aligned = align_lines(&source, &dest);
+        let ignored = (ignored_lines(&source), ignored_lines(&dest));
+        let (source_moved, dest_moved) = moved_lines(&aligned, &source, &dest, (&ignored.0, &ignored.1));
+
+        // Whichever function the alignment keeps in place, the other one is a moved block
+        let moved_source: Vec<usize> = (0..source.len()).filter(|&s| source_moved[s]).collect();
+        let moved_dest: Vec<usize> = (0..dest.len()).filter(|&d| dest_moved[d]).collect();
+        assert_eq!(moved_source.len(), 3);
+        assert_eq!(moved_dest.len(), 3);
+        for (s, d) in moved_source.iter().zip(&moved_dest) {
+            assert_eq!(source[*s], dest[*d]);
+        }
+
+        // An added line below the threshold stays a plain addition
+        let source = lines(&["}", "a"]);
+        let dest = lines(&["a", "}"]);
+        let aligned = align_lines(&source, &dest);
+        let ignored = (ignored_lines(&source), ignored_lines(&dest));
+        let (source_moved, dest_moved) = moved_lines(&aligned, &source, &dest, (&ignored.0, &ignored.1));
+        assert!(!source_moved.contains(&true) && !dest_moved.contains(&true));
+    }
+}
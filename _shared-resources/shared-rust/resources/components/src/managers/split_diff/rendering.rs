@@ -0,0 +1,558 @@
+// Rendering
+// Turns aligned lines into the visible rows of both panels (folding, scrolling, truncation)
+
+use ratatui::{style::Style, text::Line};
+
+use super::alignment::{DiffStats, LineAlignment};
+use super::folding::FoldState;
+use super::minimap::RowKind;
+use super::line_wrapping::{
+    create_blank_line, create_continuation_marker, create_fold_indicator, create_highlighted_lines,
+    dest_highlight, dest_modified_bg, long_line_window, moved_bg, source_highlight, source_modified_bg,
+    GutterNumbers, WrapMarker,
+};
+use super::word_diff::{compute_word_diff_dest, compute_word_diff_source};
+use crate::elements::{SplitDiffViewConfig, SplitDiffViewState};
+
+/// Inputs for computing split diff render data
+pub struct RenderParams<'a> {
+    pub config: &'a SplitDiffViewConfig,
+    pub state: &'a mut SplitDiffViewState,
+    pub source_lines: &'a [String],
+    pub dest_lines: &'a [String],
+    pub text_width: usize,
+    pub gutter_width: usize,
+    pub max_line_digits: usize,
+    pub available_height: usize,
+}
+
+impl<'a> RenderParams<'a> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        config: &'a SplitDiffViewConfig,
+        state: &'a mut SplitDiffViewState,
+        source_lines: &'a [String],
+        dest_lines: &'a [String],
+        text_width: usize,
+        gutter_width: usize,
+        max_line_digits: usize,
+        available_height: usize,
+    ) -> Self {
+        Self {
+            config,
+            state,
+            source_lines,
+            dest_lines,
+            text_width,
+            gutter_width,
+            max_line_digits,
+            available_height,
+        }
+    }
+}
+
+/// Pre-computed lines for both panels, already scrolled and truncated
+#[derive(Debug, Clone, Default)]
+pub struct SplitDiffRenderData {
+    pub source_lines: Vec<Line<'static>>,
+    pub dest_lines: Vec<Line<'static>>,
+    /// Total number of rows before scrolling/truncation
+    pub total_rows: usize,
+    /// Kind of every row before scrolling/truncation (drives the minimap)
+    pub row_kinds: Vec<RowKind>,
+    /// Line counts of the whole diff
+    pub stats: DiffStats,
+}
+
+/// Inputs that determine the built rows; any change invalidates the row cache
+/// (colors are compile-time constants, so they aren't part of the key)
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RowCacheKey {
+    /// Key of the alignment the rows were built from (contents and similarity threshold)
+    alignment: Option<(u64, u64, u64)>,
+    text_width: usize,
+    gutter_width: usize,
+    max_line_digits: usize,
+    dual_line_numbers: bool,
+    context_lines: usize,
+    long_line_chars: usize,
+    wrap_marker: WrapMarker,
+    tab_width: usize,
+    folds: FoldState,
+}
+
+/// Wrapped and highlighted rows of the whole diff from the last build
+/// Scrolling only slices these; they are rebuilt when the text width, folds or contents change
+#[derive(Debug, Default)]
+pub(crate) struct RowCache {
+    key: Option<RowCacheKey>,
+    source_rows: Vec<Line<'static>>,
+    dest_rows: Vec<Line<'static>>,
+    row_kinds: Vec<RowKind>,
+    /// (row, region) of every fold indicator
+    fold_rows: Vec<(usize, usize)>,
+    /// (row, alignment index) of every "line continues" marker
+    long_rows: Vec<(usize, usize)>,
+}
+
+/// Compute the render data for both panels
+pub(super) fn compute_render_data(params: RenderParams) -> SplitDiffRenderData {
+    let state = params.state;
+    // Realigns only if either side changed since the last frame
+    state
+        .alignment
+        .align(params.source_lines, params.dest_lines, params.config.similarity_threshold);
+    let key = RowCacheKey {
+        alignment: state.alignment.key(),
+        text_width: params.text_width,
+        gutter_width: params.gutter_width,
+        max_line_digits: params.max_line_digits,
+        dual_line_numbers: params.config.dual_line_numbers,
+        context_lines: params.config.context_lines,
+        long_line_chars: params.config.long_line_chars,
+        wrap_marker: params.config.wrap_marker,
+        tab_width: params.config.tab_width,
+        folds: state.folds.clone(),
+    };
+
+    if state.rows.key.as_ref() != Some(&key) {
+        let (source_ignored, dest_ignored) = state.alignment.ignored();
+        let (source_moved, dest_moved) = state.alignment.moved();
+        let mut builder = PanelBuilder {
+            source_lines: params.source_lines,
+            dest_lines: params.dest_lines,
+            source_ignored,
+            dest_ignored,
+            source_moved,
+            dest_moved,
+            text_width: params.text_width,
+            gutter_width: params.gutter_width,
+            max_line_digits: params.max_line_digits,
+            dual_line_numbers: params.config.dual_line_numbers,
+            context_lines: params.config.context_lines,
+            long_line_chars: params.config.long_line_chars,
+            wrap_marker: params.config.wrap_marker,
+            tab_width: params.config.tab_width,
+            folds: &state.folds,
+            source_visible: Vec::new(),
+            dest_visible: Vec::new(),
+            row_kinds: Vec::new(),
+            fold_rows: Vec::new(),
+            long_rows: Vec::new(),
+        };
+        builder.build(state.alignment.aligned());
+        state.rows = RowCache {
+            key: Some(key),
+            source_rows: builder.source_visible,
+            dest_rows: builder.dest_visible,
+            row_kinds: builder.row_kinds,
+            fold_rows: builder.fold_rows,
+            long_rows: builder.long_rows,
+        };
+    }
+
+    let rows = &state.rows;
+    let total_rows = rows.source_rows.len();
+    state.total_rows = total_rows;
+
+    // Clamp the scroll offset and slice out the viewport
+    state.scroll_offset = state.scroll_offset.min(total_rows.saturating_sub(1));
+    let viewport = state.scroll_offset..(state.scroll_offset + params.available_height).min(total_rows);
+    state.visible_folds = rows
+        .fold_rows
+        .iter()
+        .filter(|(row, _)| viewport.contains(row))
+        .map(|&(_, region)| region)
+        .collect();
+    state.visible_long_lines = rows
+        .long_rows
+        .iter()
+        .filter(|(row, _)| viewport.contains(row))
+        .map(|&(_, index)| index)
+        .collect();
+    state.visible_long_lines.dedup();
+
+    SplitDiffRenderData {
+        source_lines: rows.source_rows[viewport.clone()].to_vec(),
+        dest_lines: rows.dest_rows[viewport].to_vec(),
+        total_rows,
+        row_kinds: rows.row_kinds.clone(),
+        stats: state.alignment.stats(),
+    }
+}
+
+/// Builds the two panels row by row, keeping them the same height
+struct PanelBuilder<'a> {
+    source_lines: &'a [String],
+    dest_lines: &'a [String],
+    /// Lines inside ignore regions, shown as unchanged
+    source_ignored: &'a [bool],
+    dest_ignored: &'a [bool],
+    /// Lines of blocks that moved within the file, shown as moved rather than removed/added
+    source_moved: &'a [bool],
+    dest_moved: &'a [bool],
+    text_width: usize,
+    gutter_width: usize,
+    max_line_digits: usize,
+    /// Gutters show the source and destination line numbers of each row
+    dual_line_numbers: bool,
+    /// Unchanged lines kept visible around a change when folding
+    context_lines: usize,
+    /// Characters of a line shown before it is truncated (0 = never)
+    long_line_chars: usize,
+    /// Gutter of the continuation rows of wrapped lines
+    wrap_marker: WrapMarker,
+    /// Columns between tab stops (0 = tabs as they are)
+    tab_width: usize,
+    folds: &'a FoldState,
+    source_visible: Vec<Line<'static>>,
+    dest_visible: Vec<Line<'static>>,
+    row_kinds: Vec<RowKind>,
+    /// (row, region) of every fold indicator
+    fold_rows: Vec<(usize, usize)>,
+    /// (row, alignment index) of every "line continues" marker
+    long_rows: Vec<(usize, usize)>,
+}
+
+impl<'a> PanelBuilder<'a> {
+    fn build(&mut self, aligned: &[LineAlignment]) {
+        let mut i = 0;
+        while i < aligned.len() {
+            // Unchanged runs are handled whole so an expanded region can't re-fold from its middle
+            let unchanged_count = aligned[i..]
+                .iter()
+                .take_while(|entry| self.is_unchanged(entry))
+                .count();
+            if unchanged_count == 0 {
+                self.add_entry(i, aligned[i]);
+                i += 1;
+                continue;
+            }
+
+            if !(self.folds.is_folded(i) && self.try_fold(aligned, i, unchanged_count)) {
+                for (offset, entry) in aligned[i..i + unchanged_count].iter().enumerate() {
+                    self.add_entry(i + offset, *entry);
+                }
+            }
+            i += unchanged_count;
+        }
+    }
+
+    fn is_unchanged(&self, entry: &LineAlignment) -> bool {
+        match *entry {
+            LineAlignment::Both(s, d) => {
+                self.source_lines[s] == self.dest_lines[d] || (self.source_ignored[s] && self.dest_ignored[d])
+            }
+            LineAlignment::SourceOnly(s) => self.source_ignored[s],
+            LineAlignment::DestOnly(d) => self.dest_ignored[d],
+        }
+    }
+
+    /// Fold the unchanged run of `unchanged_count` entries starting at `start` if it is long enough
+    /// Returns false (and adds nothing) if the run is too short to fold
+    fn try_fold(&mut self, aligned: &[LineAlignment], start: usize, unchanged_count: usize) -> bool {
+        let has_change_before = start > 0 && !self.is_unchanged(&aligned[start - 1]);
+        let end = start + unchanged_count;
+        let has_change_after = end < aligned.len() && !self.is_unchanged(&aligned[end]);
+
+        let min_lines_for_fold = match (has_change_before, has_change_after) {
+            (true, true) => self.context_lines * 2 + 1,
+            (true, false) | (false, true) => self.context_lines + 1,
+            (false, false) => usize::MAX,
+        };
+
+        if unchanged_count <= min_lines_for_fold {
+            return false;
+        }
+
+        let context_before = if has_change_before { self.context_lines.min(unchanged_count) } else { 0 };
+        let context_after = if has_change_after {
+            self.context_lines.min(unchanged_count - context_before)
+        } else {
+            0
+        };
+        let hidden_count = unchanged_count - context_before - context_after;
+
+        for (offset, entry) in aligned[start..start + context_before].iter().enumerate() {
+            self.add_entry(start + offset, *entry);
+        }
+
+        if hidden_count > 0 {
+            self.fold_rows.push((self.source_visible.len(), start));
+            let indicator = create_fold_indicator(hidden_count, self.text_width, self.gutter_width);
+            self.source_visible.push(indicator.clone());
+            self.dest_visible.push(indicator);
+            self.row_kinds.push(RowKind::Folded);
+        }
+
+        for (offset, entry) in aligned[end - context_after..end].iter().enumerate() {
+            self.add_entry(end - context_after + offset, *entry);
+        }
+
+        true
+    }
+
+    /// Add the row(s) of the entry at alignment `index`
+    fn add_entry(&mut self, index: usize, entry: LineAlignment) {
+        let plain = (Style::default(), Style::default());
+        let unchanged = self.is_unchanged(&entry);
+        match entry {
+            LineAlignment::Both(src_idx, dest_idx) => {
+                let (src_line, src_hidden) = self.window(index, &self.source_lines[src_idx]);
+                let (dest_line, dest_hidden) = self.window(index, &self.dest_lines[dest_idx]);
+
+                let kind = if unchanged { RowKind::Unchanged } else { RowKind::Modified };
+                // Word diffs cover the visible windows only, so a huge line costs no more than its window
+                let (src_diffs, dest_diffs, src_styles, dest_styles) = if unchanged {
+                    (
+                        vec![(src_line.to_string(), false)],
+                        vec![(dest_line.to_string(), false)],
+                        plain,
+                        plain,
+                    )
+                } else {
+                    (
+                        compute_word_diff_source(src_line, dest_line),
+                        compute_word_diff_dest(dest_line, src_line),
+                        (source_modified_bg(), source_highlight()),
+                        (dest_modified_bg(), dest_highlight()),
+                    )
+                };
+
+                let row = (Some(src_idx), Some(dest_idx));
+                let mut src_wrapped = self.wrap(src_idx, row, &src_diffs, src_styles);
+                let mut dest_wrapped = self.wrap(dest_idx, row, &dest_diffs, dest_styles);
+                self.mark_truncated(index, &mut src_wrapped, src_hidden);
+                self.mark_truncated(index, &mut dest_wrapped, dest_hidden);
+                self.push_pair(src_wrapped, dest_wrapped, kind);
+            }
+            // One-sided lines of an ignore region are shown without highlighting
+            LineAlignment::SourceOnly(src_idx) if unchanged => {
+                let src_wrapped = self.one_sided(index, src_idx, true, false, plain);
+                self.push_pair(src_wrapped, Vec::new(), RowKind::Unchanged);
+            }
+            LineAlignment::DestOnly(dest_idx) if unchanged => {
+                let dest_wrapped = self.one_sided(index, dest_idx, false, false, plain);
+                self.push_pair(Vec::new(), dest_wrapped, RowKind::Unchanged);
+            }
+            LineAlignment::SourceOnly(src_idx) if self.source_moved[src_idx] => {
+                let src_wrapped = self.one_sided(index, src_idx, true, false, (moved_bg(), moved_bg()));
+                self.push_pair(src_wrapped, Vec::new(), RowKind::Moved);
+            }
+            LineAlignment::DestOnly(dest_idx) if self.dest_moved[dest_idx] => {
+                let dest_wrapped = self.one_sided(index, dest_idx, false, false, (moved_bg(), moved_bg()));
+                self.push_pair(Vec::new(), dest_wrapped, RowKind::Moved);
+            }
+            LineAlignment::SourceOnly(src_idx) => {
+                let src_wrapped = self.one_sided(index, src_idx, true, true, (source_modified_bg(), source_highlight()));
+                self.push_pair(src_wrapped, Vec::new(), RowKind::Removed);
+            }
+            LineAlignment::DestOnly(dest_idx) => {
+                let dest_wrapped = self.one_sided(index, dest_idx, false, true, (dest_modified_bg(), dest_highlight()));
+                self.push_pair(Vec::new(), dest_wrapped, RowKind::Added);
+            }
+        }
+    }
+
+    /// Wrapped rows of a line present on one side only (`is_source` tells which), truncated if long
+    fn one_sided(
+        &mut self,
+        index: usize,
+        idx: usize,
+        is_source: bool,
+        changed: bool,
+        styles: (Style, Style),
+    ) -> Vec<Line<'static>> {
+        let (lines, row) = if is_source {
+            (self.source_lines, (Some(idx), None))
+        } else {
+            (self.dest_lines, (None, Some(idx)))
+        };
+        let (line, hidden) = self.window(index, &lines[idx]);
+        let mut wrapped = self.wrap(idx, row, &[(line.to_string(), changed)], styles);
+        self.mark_truncated(index, &mut wrapped, hidden);
+        wrapped
+    }
+
+    /// Visible part of a line and the number of characters cut off; expanded lines are kept whole
+    fn window(&self, index: usize, line: &'a str) -> (&'a str, usize) {
+        if self.folds.is_line_expanded(index) {
+            (line, 0)
+        } else {
+            long_line_window(line, self.long_line_chars)
+        }
+    }
+
+    /// Append the "line continues" marker to the rows of a truncated line (about to be pushed)
+    fn mark_truncated(&mut self, index: usize, wrapped: &mut Vec<Line<'static>>, hidden_chars: usize) {
+        if hidden_chars == 0 {
+            return;
+        }
+        self.long_rows.push((self.source_visible.len() + wrapped.len(), index));
+        wrapped.push(create_continuation_marker(hidden_chars, self.text_width, self.gutter_width));
+    }
+
+    /// Wrap line `idx` of one side; `row` holds the (source, destination) lines of its row
+    fn wrap(
+        &self,
+        idx: usize,
+        row: (Option<usize>, Option<usize>),
+        diffs: &[(String, bool)],
+        (base, highlight): (Style, Style),
+    ) -> Vec<Line<'static>> {
+        let numbers = if self.dual_line_numbers {
+            GutterNumbers::Dual(row.0.map(|i| i + 1), row.1.map(|i| i + 1))
+        } else {
+            GutterNumbers::Single(idx + 1)
+        };
+        create_highlighted_lines(
+            numbers,
+            diffs,
+            self.text_width,
+            self.gutter_width,
+            self.max_line_digits,
+            self.wrap_marker,
+            self.tab_width,
+            base,
+            highlight,
+        )
+    }
+
+    /// Append both sides, padding the shorter one with blank lines
+    fn push_pair(&mut self, src_wrapped: Vec<Line<'static>>, dest_wrapped: Vec<Line<'static>>, kind: RowKind) {
+        let rows = src_wrapped.len().max(dest_wrapped.len());
+        self.row_kinds.extend(std::iter::repeat_n(kind, rows));
+        let src_pad = rows - src_wrapped.len();
+        let dest_pad = rows - dest_wrapped.len();
+
+        self.source_visible.extend(src_wrapped);
+        self.dest_visible.extend(dest_wrapped);
+
+        for _ in 0..src_pad {
+            self.source_visible.push(create_blank_line(self.text_width, self.gutter_width));
+        }
+        for _ in 0..dest_pad {
+            self.dest_visible.push(create_blank_line(self.text_width, self.gutter_width));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(state: &mut SplitDiffViewState, source: &[String], dest: &[String]) -> SplitDiffRenderData {
+        render_with(&SplitDiffViewConfig::default(), state, source, dest)
+    }
+
+    fn render_with(
+        config: &SplitDiffViewConfig,
+        state: &mut SplitDiffViewState,
+        source: &[String],
+        dest: &[String],
+    ) -> SplitDiffRenderData {
+        compute_render_data(RenderParams::new(config, state, source, dest, 40, 3, 2, 100))
+    }
+
+    #[test]
+    fn test_expanding_one_region_keeps_others_folded() {
+        // Two long unchanged regions separated by changes
+        let source: Vec<String> = (0..40).map(|i| format!("line {}", i)).collect();
+        let mut dest = source.clone();
+        dest[0] = "changed first".to_string();
+        dest[20] = "changed middle".to_string();
+        dest[39] = "changed last".to_string();
+
+        let mut state = SplitDiffViewState::default();
+        let folded = render(&mut state, &source, &dest);
+        assert_eq!(state.visible_folds.len(), 2);
+        assert_eq!(folded.row_kinds.iter().filter(|k| **k == RowKind::Folded).count(), 2);
+
+        assert!(state.expand_visible_fold());
+        let expanded = render(&mut state, &source, &dest);
+        assert_eq!(state.visible_folds.len(), 1);
+        assert_eq!(expanded.total_rows, folded.total_rows + 12);
+    }
+
+    #[test]
+    fn test_rows_are_rebuilt_when_text_width_changes() {
+        let source = vec!["a fairly long line that needs wrapping when the panel is narrow".to_string()];
+        let dest = vec!["a fairly long line that needs wrapping when the panel gets narrow".to_string()];
+        let mut state = SplitDiffViewState::default();
+        let config = SplitDiffViewConfig::default();
+
+        let wide = compute_render_data(RenderParams::new(&config, &mut state, &source, &dest, 80, 2, 1, 10));
+        let cached = compute_render_data(RenderParams::new(&config, &mut state, &source, &dest, 80, 2, 1, 10));
+        assert_eq!(wide.source_lines, cached.source_lines);
+
+        let narrow = compute_render_data(RenderParams::new(&config, &mut state, &source, &dest, 20, 2, 1, 10));
+        assert!(narrow.total_rows > wide.total_rows);
+    }
+
+    #[test]
+    fn test_dual_line_numbers_show_both_sides_of_each_row() {
+        let source = vec!["a".to_string(), "removed".to_string(), "b".to_string()];
+        let dest = vec!["a".to_string(), "b".to_string()];
+        let gutter = |line: &Line| line.spans[0].content.to_string();
+
+        let mut state = SplitDiffViewState::default();
+        let config = SplitDiffViewConfig::new().with_dual_line_numbers(true);
+        let data = compute_render_data(RenderParams::new(&config, &mut state, &source, &dest, 20, 4, 1, 10));
+        let source_gutters: Vec<String> = data.source_lines.iter().map(gutter).collect();
+        let dest_gutters: Vec<String> = data.dest_lines.iter().map(gutter).collect();
+        assert_eq!(source_gutters, ["1 1 ", "2   ", "3 2 "]);
+        assert_eq!(dest_gutters, ["1 1 ", "    ", "3 2 "]);
+    }
+
+    #[test]
+    fn test_moved_block_rows_are_marked_moved() {
+        let moved = "let value = compute_the_answer();";
+        let kept = ["a", "b", "c", "d"];
+        let source: Vec<String> = std::iter::once(&moved).chain(&kept).map(|s| s.to_string()).collect();
+        let dest: Vec<String> = kept.iter().chain(std::iter::once(&moved)).map(|s| s.to_string()).collect();
+
+        let mut state = SplitDiffViewState::default();
+        let data = render(&mut state, &source, &dest);
+        assert_eq!(data.row_kinds.iter().filter(|k| **k == RowKind::Moved).count(), 2);
+        assert!(!data.row_kinds.contains(&RowKind::Added) && !data.row_kinds.contains(&RowKind::Removed));
+    }
+
+    #[test]
+    fn test_context_lines_set_fold_threshold_and_context() {
+        let source: Vec<String> = (0..20).map(|i| format!("line {}", i)).collect();
+        let mut dest = source.clone();
+        dest[10] = "line 10 changed".to_string();
+
+        // 10 unchanged lines on each side of the change, each keeping `context_lines` visible
+        let mut state = SplitDiffViewState::default();
+        let one = render_with(&SplitDiffViewConfig::new().with_context_lines(1), &mut state, &source, &dest);
+        assert_eq!(one.total_rows, 1 + 1 + 1 + 1 + 1);
+        let five = render_with(&SplitDiffViewConfig::new().with_context_lines(5), &mut state, &source, &dest);
+        assert_eq!(five.total_rows, 1 + 5 + 1 + 5 + 1);
+
+        // A run no longer than the context isn't folded at all
+        let ten = render_with(&SplitDiffViewConfig::new().with_context_lines(10), &mut state, &source, &dest);
+        assert_eq!(ten.total_rows, 20);
+        assert!(state.visible_folds.is_empty());
+    }
+
+    #[test]
+    fn test_long_lines_are_truncated_until_expanded() {
+        let source = vec!["word ".repeat(1000)];
+        let dest = vec![format!("{}end", "word ".repeat(1000))];
+        let config = SplitDiffViewConfig::new().with_long_line_chars(100);
+
+        let mut state = SplitDiffViewState::default();
+        let truncated = render_with(&config, &mut state, &source, &dest);
+        // 100 characters wrap to 3 rows of 40, plus the marker
+        assert_eq!(truncated.total_rows, 4);
+        assert_eq!(state.visible_long_lines, [0]);
+        let marker = truncated.dest_lines[3].spans[1].content.to_string();
+        assert_eq!(marker, "… line continues (4903 chars)");
+
+        assert!(state.expand_visible_fold());
+        let expanded = render_with(&config, &mut state, &source, &dest);
+        assert_eq!(expanded.total_rows, 126);
+        assert!(state.visible_long_lines.is_empty());
+    }
+}
@@ -0,0 +1,168 @@
+// Word Diff
+// Token-level change detection for modified lines
+//
+// Lines are split into tokens (identifier/number runs, whitespace runs, single punctuation
+// characters) and compared with an LCS, so several disjoint changes within one line are each
+// highlighted on their own instead of everything between the first and last change.
+
+/// Token pairs beyond this many DP cells fall back to prefix/suffix trimming (e.g. minified lines)
+const MAX_LCS_CELLS: usize = 250_000;
+
+/// Compute word-level diff for source line
+/// Returns segments with (text, is_changed) where is_changed=true means this part was removed/changed
+pub fn compute_word_diff_source(line: &str, other: &str) -> Vec<(String, bool)> {
+    compute_word_diff(line, other)
+}
+
+/// Compute word-level diff for destination line
+/// Returns segments with (text, is_changed) where is_changed=true means this part was added/changed
+pub fn compute_word_diff_dest(line: &str, other: &str) -> Vec<(String, bool)> {
+    compute_word_diff(line, other)
+}
+
+/// Segments of `line`, marking the tokens that aren't part of the common subsequence with `other`
+fn compute_word_diff(line: &str, other: &str) -> Vec<(String, bool)> {
+    if line == other {
+        return vec![(line.to_string(), false)];
+    }
+
+    let tokens = tokenize(line);
+    let other_tokens = tokenize(other);
+    let common = common_tokens(&tokens, &other_tokens);
+
+    // Merge runs of tokens with the same state into segments
+    let mut result: Vec<(String, bool)> = Vec::new();
+    for (token, is_common) in tokens.iter().zip(common) {
+        match result.last_mut() {
+            Some((text, changed)) if *changed != is_common => text.push_str(token),
+            _ => result.push((token.to_string(), !is_common)),
+        }
+    }
+    result
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CharClass {
+    Word,
+    Space,
+    Punct,
+}
+
+impl CharClass {
+    fn of(c: char) -> Self {
+        if c.is_alphanumeric() || c == '_' {
+            CharClass::Word
+        } else if c.is_whitespace() {
+            CharClass::Space
+        } else {
+            CharClass::Punct
+        }
+    }
+}
+
+/// Split a line into word runs, whitespace runs and single punctuation characters
+fn tokenize(line: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut chars = line.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        let class = CharClass::of(c);
+        let mut end = start + c.len_utf8();
+        if class != CharClass::Punct {
+            while let Some(&(i, next)) = chars.peek() {
+                if CharClass::of(next) != class {
+                    break;
+                }
+                end = i + next.len_utf8();
+                chars.next();
+            }
+        }
+        tokens.push(&line[start..end]);
+    }
+
+    tokens
+}
+
+/// For every token of `tokens`, whether it belongs to the longest common subsequence with `other`
+fn common_tokens(tokens: &[&str], other: &[&str]) -> Vec<bool> {
+    let prefix = tokens.iter().zip(other).take_while(|(a, b)| a == b).count();
+    let max_suffix = tokens.len().min(other.len()) - prefix;
+    let suffix = tokens
+        .iter()
+        .rev()
+        .zip(other.iter().rev())
+        .take(max_suffix)
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut common = vec![true; tokens.len()];
+    let middle = &tokens[prefix..tokens.len() - suffix];
+    let other_middle = &other[prefix..other.len() - suffix];
+
+    if middle.len().saturating_mul(other_middle.len()) > MAX_LCS_CELLS {
+        common[prefix..tokens.len() - suffix].fill(false);
+        return common;
+    }
+
+    // dp[i][j] = length of LCS of middle[i..] and other_middle[j..]
+    let (n, m) = (middle.len(), other_middle.len());
+    let mut dp = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            dp[i][j] = if middle[i] == other_middle[j] {
+                dp[i + 1][j + 1] + 1
+            } else {
+                dp[i + 1][j].max(dp[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    while i < n {
+        if j < m && middle[i] == other_middle[j] {
+            i += 1;
+            j += 1;
+        } else if j < m && dp[i][j + 1] > dp[i + 1][j] {
+            j += 1;
+        } else {
+            common[prefix + i] = false;
+            i += 1;
+        }
+    }
+
+    common
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segments(parts: &[(&str, bool)]) -> Vec<(String, bool)> {
+        parts.iter().map(|(text, changed)| (text.to_string(), *changed)).collect()
+    }
+
+    #[test]
+    fn test_single_word_change() {
+        let source = compute_word_diff_source("let a = 1;", "let b = 1;");
+        assert_eq!(source, segments(&[("let ", false), ("a", true), (" = 1;", false)]));
+    }
+
+    #[test]
+    fn test_appended_text_highlights_only_addition() {
+        let dest = compute_word_diff_dest("hello world", "hello");
+        assert_eq!(dest, segments(&[("hello", false), (" world", true)]));
+        let source = compute_word_diff_source("hello", "hello world");
+        assert_eq!(source, segments(&[("hello", false)]));
+    }
+
+    #[test]
+    fn test_disjoint_changes_are_highlighted_separately() {
+        let source = compute_word_diff_source("call(alpha, beta) + gamma", "call(delta, beta) + omega");
+        assert_eq!(
+            source,
+            segments(&[("call(", false), ("alpha", true), (", beta) + ", false), ("gamma", true)])
+        );
+        let dest = compute_word_diff_dest("call(delta, beta) + omega", "call(alpha, beta) + gamma");
+        assert_eq!(dest, segments(&[("call(", false), ("delta", true), (", beta) + ", false), ("omega", true)]));
+    }
+}
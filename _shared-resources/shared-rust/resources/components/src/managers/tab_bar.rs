@@ -2,8 +2,8 @@
 // Provides YAML configuration helpers and OOP-style tab bar manager wrapper
 
 use serde::Deserialize;
-use crate::core::{RectHandle, RectRegistry, TabBarConfigData, TabConfigData, AlignmentConfigData, TabBarStateColors, TabState, TabBarState};
-use crate::elements::tab_bar::{TabBar, TabBarStyle};
+use crate::core::{RectHandle, RectRegistry, TabBarConfigData, TabConfigData, AlignmentConfigData, TabBarStateColors, TabState};
+use crate::elements::tab_bar::{PreparedTabBar, TabBar, TabBarChevron, TabBarStyle};
 
 // ┌────────────────────────────────────────────────────────────────────────────────────────────────┐
 // │                                    YAML Configuration Structures                               │
@@ -56,6 +56,8 @@ pub struct TabBarConfigYaml {
     pub min_tab_width: Option<u16>,
     /// Show tooltips (optional, defaults to true)
     pub tab_tooltips: Option<bool>,
+    /// Wrap around when navigating past the first/last tab (optional, defaults to true)
+    pub wrap: Option<bool>,
     /// List of tabs
     pub tabs: Vec<TabConfigYaml>,
 }
@@ -73,6 +75,9 @@ pub struct TabConfigYaml {
     pub name: String,
     /// Optional: "active" for non-static bars (sets active tab), or state ("active", "negate", "disabled") for static bars
     pub default: Option<String>,
+    /// Start disabled (skipped by navigation)
+    #[serde(default)]
+    pub disabled: bool,
 }
 
 // ┌────────────────────────────────────────────────────────────────────────────────────────────────┐
@@ -132,6 +137,7 @@ pub fn convert_tab_bar_config(config: &TabBarConfigYaml) -> TabBarConfigData {
         },
         min_tab_width: config.min_tab_width.unwrap_or(8),
         tab_tooltips: config.tab_tooltips.unwrap_or(true),
+        wrap: config.wrap.unwrap_or(true),
     }
 }
 
@@ -180,8 +186,10 @@ pub fn create_tab_configs(config: &TabBarConfigYaml) -> Vec<TabConfigData> {
         TabConfigData {
             id: t.id.clone(),
             name: t.name.clone(),
-            active,
+            active: active && !t.disabled,
             state,
+            disabled: t.disabled,
+            badge: None,
         }
     }).collect();
     
@@ -227,9 +235,9 @@ impl TabBarManager {
         self.handle
     }
     
-    /// Prepare tab bar for rendering (returns TabBar instance and state)
-    /// For Tab style, this also adjusts the anchor box (y+1, height-1) before returning
-    pub fn prepare(&self, registry: &mut RectRegistry, style_override: Option<TabBarStyle>) -> Option<(TabBar, RectHandle, TabBarState)> {
+    /// Prepare tab bar for rendering (returns TabBar instance, state, and the area left for content)
+    /// The anchor box is not modified; render content into `content_area`
+    pub fn prepare(&self, registry: &RectRegistry, style_override: Option<TabBarStyle>) -> Option<PreparedTabBar> {
         TabBar::from_registry(registry, self.handle, style_override)
     }
     
@@ -252,5 +260,45 @@ impl TabBarManager {
     pub fn set_tab_state(&self, registry: &mut RectRegistry, tab_index: usize, state: TabState) -> bool {
         registry.set_tab_state(self.handle, tab_index, state)
     }
+    
+    /// Disable a tab so navigation skips it (moves off it if it is active)
+    pub fn disable_tab(&self, registry: &mut RectRegistry, tab_index: usize) -> bool {
+        registry.set_tab_disabled(self.handle, tab_index, true)
+    }
+    
+    /// Re-enable a disabled tab
+    pub fn enable_tab(&self, registry: &mut RectRegistry, tab_index: usize) -> bool {
+        registry.set_tab_disabled(self.handle, tab_index, false)
+    }
+    
+    /// Set the badge rendered after a tab's name (e.g. a change count), or clear it with None
+    pub fn set_badge(&self, registry: &mut RectRegistry, tab_index: usize, badge: Option<String>) -> bool {
+        registry.set_tab_badge(self.handle, tab_index, badge)
+    }
+    
+    /// Handle a mouse click on the prepared tab bar: chevrons scroll to the previous/next tab,
+    /// tabs become active. Returns true if the active tab changed
+    pub fn handle_click(&self, registry: &mut RectRegistry, tab_bar: &TabBar, x: u16, y: u16) -> bool {
+        match tab_bar.get_chevron_at(x, y, Some(registry)) {
+            Some(TabBarChevron::Left) => return self.navigate_previous(registry),
+            Some(TabBarChevron::Right) => return self.navigate_next(registry),
+            None => {}
+        }
+        match tab_bar.get_tab_at(x, y, Some(registry)) {
+            Some(index) => self.set_active(registry, index),
+            None => false,
+        }
+    }
+    
+    /// Allow or prevent wrap-around navigation
+    pub fn set_wrap(&self, registry: &mut RectRegistry, wrap: bool) -> bool {
+        match registry.get_tab_bar_state_mut(self.handle) {
+            Some(state) => {
+                state.config.wrap = wrap;
+                true
+            }
+            None => false,
+        }
+    }
 }
 
@@ -0,0 +1,247 @@
+// Toast Manager
+// Queues toasts and applies display policies (visible limit, per-type durations, dedup, sticky errors)
+//
+// Usage:
+//   let mut toasts = ToastManager::new().with_sticky_errors(true);
+//   toasts.success("Synced 3 files");
+//   // every frame:
+//   toasts.tick();
+//   toasts.render(f, area);
+
+use ratatui::{layout::Rect, Frame};
+use std::collections::VecDeque;
+use std::time::{Duration, SystemTime};
+
+use crate::elements::toast::{render_toasts, Toast, ToastType};
+
+/// Default number of toasts shown at once
+const DEFAULT_MAX_VISIBLE: usize = 3;
+
+/// Display policy for a ToastManager
+#[derive(Debug, Clone)]
+pub struct ToastPolicy {
+    /// Toasts shown at once; the rest wait in the queue
+    pub max_visible: usize,
+    pub success_duration: Duration,
+    pub info_duration: Duration,
+    pub error_duration: Duration,
+    /// Errors stay visible until dismissed
+    pub sticky_errors: bool,
+}
+
+impl Default for ToastPolicy {
+    fn default() -> Self {
+        Self {
+            max_visible: DEFAULT_MAX_VISIBLE,
+            success_duration: Duration::from_millis(1500),
+            info_duration: Duration::from_millis(1500),
+            error_duration: Duration::from_secs(4),
+            sticky_errors: false,
+        }
+    }
+}
+
+impl ToastPolicy {
+    /// Display duration for a toast type
+    pub fn duration(&self, toast_type: ToastType) -> Duration {
+        match toast_type {
+            ToastType::Success => self.success_duration,
+            ToastType::Info => self.info_duration,
+            ToastType::Error => self.error_duration,
+        }
+    }
+
+    /// Whether a toast type is kept until dismissed
+    pub fn is_sticky(&self, toast_type: ToastType) -> bool {
+        self.sticky_errors && toast_type == ToastType::Error
+    }
+}
+
+/// Visible toasts plus a FIFO of toasts waiting for a free slot
+/// A toast's timer starts when it becomes visible, not when it is queued
+#[derive(Debug, Clone, Default)]
+pub struct ToastManager {
+    policy: ToastPolicy,
+    visible: Vec<Toast>,
+    queue: VecDeque<Toast>,
+}
+
+impl ToastManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_policy(mut self, policy: ToastPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    pub fn with_max_visible(mut self, max_visible: usize) -> Self {
+        self.policy.max_visible = max_visible.max(1);
+        self
+    }
+
+    pub fn with_duration(mut self, toast_type: ToastType, duration: Duration) -> Self {
+        match toast_type {
+            ToastType::Success => self.policy.success_duration = duration,
+            ToastType::Info => self.policy.info_duration = duration,
+            ToastType::Error => self.policy.error_duration = duration,
+        }
+        self
+    }
+
+    pub fn with_sticky_errors(mut self, sticky: bool) -> Self {
+        self.policy.sticky_errors = sticky;
+        self
+    }
+
+    pub fn policy(&self) -> &ToastPolicy {
+        &self.policy
+    }
+
+    /// Add a toast; an identical visible or queued toast is refreshed instead of duplicated
+    pub fn push(&mut self, toast: Toast) {
+        let same = |t: &&mut Toast| t.toast_type == toast.toast_type && t.message == toast.message;
+
+        if let Some(existing) = self.visible.iter_mut().find(same) {
+            existing.count += 1;
+            existing.shown_at = toast.shown_at;
+            return;
+        }
+        if let Some(existing) = self.queue.iter_mut().find(same) {
+            existing.count += 1;
+            return;
+        }
+
+        if self.visible.len() < self.policy.max_visible {
+            self.visible.push(toast);
+        } else {
+            self.queue.push_back(toast);
+        }
+    }
+
+    pub fn success(&mut self, message: impl Into<String>) {
+        self.push(Toast::success(message.into()));
+    }
+
+    pub fn info(&mut self, message: impl Into<String>) {
+        self.push(Toast::info(message.into()));
+    }
+
+    pub fn error(&mut self, message: impl Into<String>) {
+        self.push(Toast::error(message.into()));
+    }
+
+    /// Expire toasts and promote queued ones; call once per frame
+    pub fn tick(&mut self) {
+        self.tick_at(SystemTime::now());
+    }
+
+    /// Expire toasts relative to `now`
+    pub fn tick_at(&mut self, now: SystemTime) {
+        let policy = &self.policy;
+        self.visible.retain(|toast| {
+            if policy.is_sticky(toast.toast_type) {
+                return true;
+            }
+            match now.duration_since(toast.shown_at) {
+                Ok(elapsed) => elapsed < policy.duration(toast.toast_type),
+                Err(_) => true, // Shown "in the future" (clock moved back); keep it
+            }
+        });
+        self.promote(now);
+    }
+
+    /// Dismiss the oldest sticky toast; returns false if there is none
+    pub fn dismiss_sticky(&mut self) -> bool {
+        let policy = &self.policy;
+        match self.visible.iter().position(|t| policy.is_sticky(t.toast_type)) {
+            Some(index) => {
+                self.visible.remove(index);
+                self.promote(SystemTime::now());
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn has_sticky(&self) -> bool {
+        self.visible.iter().any(|t| self.policy.is_sticky(t.toast_type))
+    }
+
+    pub fn clear(&mut self) {
+        self.visible.clear();
+        self.queue.clear();
+    }
+
+    /// Toasts currently on screen (oldest first)
+    pub fn visible(&self) -> &[Toast] {
+        &self.visible
+    }
+
+    /// Number of toasts waiting for a free slot
+    pub fn queued(&self) -> usize {
+        self.queue.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.visible.is_empty() && self.queue.is_empty()
+    }
+
+    /// Render visible toasts stacked in the bottom-left corner of `area`
+    pub fn render(&self, f: &mut Frame, area: Rect) {
+        render_toasts(f, area, &self.visible);
+    }
+
+    /// Move queued toasts into free slots, starting their timers at `now`
+    fn promote(&mut self, now: SystemTime) {
+        while self.visible.len() < self.policy.max_visible {
+            let Some(mut toast) = self.queue.pop_front() else {
+                break;
+            };
+            toast.shown_at = now;
+            self.visible.push(toast);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_queue_dedup_and_expiry() {
+        let mut toasts = ToastManager::new().with_max_visible(2);
+        let start = SystemTime::now();
+
+        toasts.info("one");
+        toasts.info("two");
+        toasts.info("three");
+        toasts.info("one");
+        assert_eq!(toasts.visible().len(), 2);
+        assert_eq!(toasts.visible()[0].count, 2);
+        assert_eq!(toasts.queued(), 1);
+
+        // Info toasts expire after 1.5s and the queued one takes a slot
+        let later = start + Duration::from_secs(2);
+        toasts.tick_at(later);
+        assert_eq!(toasts.visible().len(), 1);
+        assert_eq!(toasts.visible()[0].message, "three");
+        assert_eq!(toasts.visible()[0].shown_at, later);
+    }
+
+    #[test]
+    fn test_sticky_errors_need_dismissal() {
+        let mut toasts = ToastManager::new().with_sticky_errors(true);
+        toasts.error("sync failed");
+        toasts.success("saved");
+
+        toasts.tick_at(SystemTime::now() + Duration::from_secs(60));
+        assert_eq!(toasts.visible().len(), 1);
+        assert!(toasts.has_sticky());
+
+        assert!(toasts.dismiss_sticky());
+        assert!(toasts.is_empty());
+        assert!(!toasts.dismiss_sticky());
+    }
+}
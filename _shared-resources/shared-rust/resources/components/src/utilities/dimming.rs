@@ -0,0 +1,86 @@
+// Frame-level dimming pass
+// Dims every cell outside the topmost overlay registered in the RectRegistry, so widgets
+// don't need a DimmingContext threaded through their render calls
+//
+// Usage:
+//   terminal.draw(|f| {
+//       registry.begin_frame();
+//       render_everything(f, &mut registry);   // popups use register_layer
+//       apply_dimming(f, &registry);
+//   })?;
+
+use ratatui::{buffer::Buffer, layout::Rect, style::Modifier, Frame};
+
+use crate::core::RectRegistry;
+use crate::utilities::{hex_color, DimmingContext};
+
+/// Foreground of dimmed cells (matches DimmingContext's dimmed text)
+const DIM_FG: u32 = 0x444444;
+/// Background of dimmed cells (matches the popup backdrop)
+const DIM_BG: u32 = 0x0A0A0A;
+
+/// Dim everything outside the topmost overlay layer; returns false if no overlay is open
+pub fn apply_dimming(f: &mut Frame, registry: &RectRegistry) -> bool {
+    dim_buffer(f.buffer_mut(), &registry.top_layer())
+}
+
+/// Dim every cell of `buf` not covered by one of `keep`
+pub fn dim_buffer(buf: &mut Buffer, keep: &[Rect]) -> bool {
+    if keep.is_empty() {
+        return false;
+    }
+
+    let area = buf.area;
+    for y in area.top()..area.bottom() {
+        for x in area.left()..area.right() {
+            if keep.iter().any(|rect| rect.contains((x, y).into())) {
+                continue;
+            }
+            if let Some(cell) = buf.cell_mut((x, y)) {
+                cell.set_fg(hex_color(DIM_FG))
+                    .set_bg(hex_color(DIM_BG))
+                    .modifier
+                    .remove(Modifier::BOLD);
+            }
+        }
+    }
+    true
+}
+
+impl DimmingContext {
+    /// Dimming state derived from the registry's overlays (this frame's or the previous one's)
+    pub fn from_registry(registry: &RectRegistry) -> Self {
+        Self::new(registry.has_overlay())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::{Color, Style};
+
+    #[test]
+    fn test_dims_outside_top_layer_only() {
+        let mut registry = RectRegistry::new();
+        registry.begin_frame();
+        registry.register_layer(Some("hwndForm"), Rect::new(0, 0, 6, 2), 1);
+        registry.register_layer(Some("hwndConfirm"), Rect::new(2, 0, 2, 1), 2);
+
+        let mut buf = Buffer::empty(Rect::new(0, 0, 6, 2));
+        buf.set_style(buf.area, Style::default().fg(Color::White));
+        assert!(dim_buffer(&mut buf, &registry.top_layer()));
+
+        // Only the confirm dialog keeps its colors; the form under it is dimmed too
+        assert_eq!(buf[(2, 0)].fg, Color::White);
+        assert_eq!(buf[(3, 0)].fg, Color::White);
+        assert_eq!(buf[(0, 0)].fg, hex_color(DIM_FG));
+        assert_eq!(buf[(2, 1)].fg, hex_color(DIM_FG));
+
+        // Next frame without overlays: nothing to dim, but widgets still see last frame's modal
+        registry.begin_frame();
+        assert!(registry.top_layer().is_empty());
+        assert!(DimmingContext::from_registry(&registry).modal_visible);
+        registry.begin_frame();
+        assert!(!DimmingContext::from_registry(&registry).modal_visible);
+    }
+}
@@ -1,5 +1,7 @@
 // Helper utilities for TUI components
 use ratatui::style::{Color, Modifier, Style};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
 /// Convert hex color to ratatui Color
 pub fn hex_color(hex: u32) -> Color {
@@ -164,8 +166,15 @@ pub fn centered_rect(percent_x: u16, percent_y: u16, r: ratatui::layout::Rect) -
         .split(popup_layout[1])[1]
 }
 
-/// Wrap text to fit within max width
+/// Terminal column width of a string (wide CJK/emoji characters count as 2)
+pub fn display_width(text: &str) -> usize {
+    UnicodeWidthStr::width(text)
+}
+
+/// Wrap text to fit within max width (measured in terminal columns)
+/// Words wider than the line are broken at grapheme boundaries
 pub fn wrap_text(text: &str, max_width: usize) -> Vec<String> {
+    let max_width = max_width.max(1);
     let mut lines = Vec::new();
     
     for paragraph in text.split('\n') {
@@ -174,18 +183,39 @@ pub fn wrap_text(text: &str, max_width: usize) -> Vec<String> {
             continue;
         }
         
-        let words: Vec<&str> = paragraph.split_whitespace().collect();
         let mut current_line = String::new();
+        let mut current_width = 0;
         
-        for word in words {
-            if current_line.is_empty() {
-                current_line = word.to_string();
-            } else if current_line.len() + 1 + word.len() <= max_width {
+        for word in paragraph.split_whitespace() {
+            let word_width = display_width(word);
+            
+            if current_width > 0 && current_width + 1 + word_width <= max_width {
                 current_line.push(' ');
                 current_line.push_str(word);
-            } else {
-                lines.push(current_line);
-                current_line = word.to_string();
+                current_width += 1 + word_width;
+                continue;
+            }
+            
+            if current_width > 0 {
+                lines.push(std::mem::take(&mut current_line));
+                current_width = 0;
+            }
+            
+            if word_width <= max_width {
+                current_line.push_str(word);
+                current_width = word_width;
+                continue;
+            }
+            
+            // Break an over-long word into line-sized chunks
+            for grapheme in word.graphemes(true) {
+                let grapheme_width = display_width(grapheme);
+                if current_width + grapheme_width > max_width && current_width > 0 {
+                    lines.push(std::mem::take(&mut current_line));
+                    current_width = 0;
+                }
+                current_line.push_str(grapheme);
+                current_width += grapheme_width;
             }
         }
         
@@ -196,3 +226,44 @@ pub fn wrap_text(text: &str, max_width: usize) -> Vec<String> {
     
     lines
 }
+
+/// Match a file name against a simple glob pattern (`*` and `?` wildcards, case-insensitive)
+pub fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let name: Vec<char> = name.to_lowercase().chars().collect();
+
+    // Iterative matcher with single-star backtracking
+    let (mut p, mut n) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, n));
+            p += 1;
+        } else if let Some((star_p, star_n)) = star {
+            p = star_p + 1;
+            n = star_n + 1;
+            star = Some((star_p, star_n + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap_text_measures_display_width() {
+        assert_eq!(display_width("日本語"), 6);
+        assert_eq!(wrap_text("日本 語語", 4), vec!["日本", "語語"]);
+        // Over-long words are split without breaking a wide character
+        assert_eq!(wrap_text("日本語ab", 5), vec!["日本", "語ab"]);
+    }
+}
@@ -0,0 +1,222 @@
+// Highlight Cache
+// Syntax highlighting of whole files on one background thread, kept per file, so a render
+// never waits for it: the host draws plain text until a file's spans are ready, then swaps
+// them in
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::sync::Arc;
+
+use ratatui::text::Span;
+
+use super::syntax_highlighting::{SyntaxHighlighter, DEFAULT_SYNTAX_THEME};
+
+/// Files kept highlighted; the least recently requested one is dropped past this
+const MAX_FILES: usize = 32;
+
+/// Highlighted spans of every line of a file
+pub type HighlightedLines = Arc<Vec<Vec<Span<'static>>>>;
+
+/// A file to highlight: (key, lines, extension)
+type Job = (u64, Vec<String>, String);
+
+#[derive(Debug)]
+enum Entry {
+    /// Queued on the worker
+    Pending,
+    Ready(HighlightedLines),
+    /// No grammar for the extension, or the worker died; the file stays plain
+    Plain,
+}
+
+/// The thread highlighting files one after the other, in request order
+#[derive(Debug)]
+struct Worker {
+    jobs: Sender<Job>,
+    results: Receiver<(u64, Vec<Vec<Span<'static>>>)>,
+}
+
+impl Worker {
+    /// Start the thread; it builds its highlighter with `make` once, then serves every file
+    /// until the cache is dropped
+    fn start<H>(make: impl FnOnce() -> H + Send + 'static) -> Self
+    where
+        H: Fn(&str, &str) -> Vec<Span<'static>>,
+    {
+        let (jobs, job_receiver) = mpsc::channel::<Job>();
+        let (result_sender, results) = mpsc::channel();
+        std::thread::spawn(move || {
+            let highlight = make();
+            for (key, lines, extension) in job_receiver {
+                let highlighted = lines.iter().map(|line| highlight(line, &extension)).collect();
+                if result_sender.send((key, highlighted)).is_err() {
+                    break;
+                }
+            }
+        });
+        Self { jobs, results }
+    }
+}
+
+/// Highlighted files by content and extension, filled by a background worker
+#[derive(Debug)]
+pub struct HighlightCache {
+    /// Theme name or .tmTheme path the worker highlights with
+    theme: String,
+    /// Started on the first file with a grammar
+    worker: Option<Worker>,
+    entries: HashMap<u64, Entry>,
+    /// Keys from least to most recently requested
+    order: VecDeque<u64>,
+}
+
+impl Default for HighlightCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_SYNTAX_THEME)
+    }
+}
+
+impl HighlightCache {
+    /// Empty cache highlighting with `theme` (see `SyntaxHighlighter::with_theme`)
+    pub fn new(theme: impl Into<String>) -> Self {
+        Self {
+            theme: theme.into(),
+            worker: None,
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    /// Highlighted lines of a file, or None while they are computed (draw plain text then)
+    /// The first request for some contents queues them on the worker; extensions without a
+    /// grammar are never highlighted
+    pub fn get(&mut self, lines: &[String], extension: &str) -> Option<HighlightedLines> {
+        let key = content_key(lines, extension);
+        self.touch(key);
+        if !self.entries.contains_key(&key) {
+            let entry = if SyntaxHighlighter::new().has_syntax_for_extension(extension) {
+                self.queue((key, lines.to_vec(), extension.to_string()))
+            } else {
+                Entry::Plain
+            };
+            self.entries.insert(key, entry);
+        }
+        self.receive();
+        match self.entries.get(&key) {
+            Some(Entry::Ready(highlighted)) => Some(Arc::clone(highlighted)),
+            _ => None,
+        }
+    }
+
+    /// Whether a file is still being highlighted (the host keeps redrawing until it isn't)
+    pub fn is_pending(&self) -> bool {
+        self.entries.values().any(|entry| matches!(entry, Entry::Pending))
+    }
+
+    /// Drop every file, e.g. after the theme changed
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+    }
+
+    /// Hand a file to the worker, starting it first if needed
+    fn queue(&mut self, job: Job) -> Entry {
+        let theme = self.theme.clone();
+        let worker = self.worker.get_or_insert_with(|| {
+            Worker::start(move || {
+                let highlighter = SyntaxHighlighter::with_theme(&theme).unwrap_or_default();
+                move |line: &str, extension: &str| highlighter.highlight_line(line, extension)
+            })
+        });
+        match worker.jobs.send(job) {
+            Ok(()) => Entry::Pending,
+            Err(_) => Entry::Plain,
+        }
+    }
+
+    /// Take the files the worker finished; if it died, the files still queued stay plain
+    fn receive(&mut self) {
+        let Some(worker) = &self.worker else {
+            return;
+        };
+        loop {
+            match worker.results.try_recv() {
+                // Files dropped from the cache meanwhile are not put back
+                Ok((key, highlighted)) => {
+                    if let Some(entry) = self.entries.get_mut(&key) {
+                        *entry = Entry::Ready(Arc::new(highlighted));
+                    }
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    for entry in self.entries.values_mut() {
+                        if matches!(entry, Entry::Pending) {
+                            *entry = Entry::Plain;
+                        }
+                    }
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Mark `key` as most recently requested, dropping the oldest file past `MAX_FILES`
+    fn touch(&mut self, key: u64) {
+        self.order.retain(|k| *k != key);
+        self.order.push_back(key);
+        while self.order.len() > MAX_FILES {
+            if let Some(oldest) = self.order.pop_front() {
+                self.entries.remove(&oldest);
+            }
+        }
+    }
+}
+
+fn content_key(lines: &[String], extension: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    lines.hash(&mut hasher);
+    extension.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Queue `lines` as Rust whether or not the feature provides its grammar
+    fn queue(cache: &mut HighlightCache, lines: &[String]) {
+        let key = content_key(lines, "rs");
+        cache.touch(key);
+        let entry = cache.queue((key, lines.to_vec(), "rs".to_string()));
+        cache.entries.insert(key, entry);
+    }
+
+    #[test]
+    fn test_files_are_highlighted_in_the_background() {
+        // One highlighter for every file, built on the worker thread
+        let mut cache = HighlightCache {
+            worker: Some(Worker::start(|| |line: &str, _: &str| vec![Span::raw(line.to_string())])),
+            ..HighlightCache::default()
+        };
+        let first = vec!["fn main() {}".to_string(), "}".to_string()];
+        let second = vec!["let x = 1;".to_string()];
+        queue(&mut cache, &first);
+        queue(&mut cache, &second);
+        assert!(cache.is_pending());
+
+        while cache.is_pending() {
+            std::thread::yield_now();
+            cache.receive();
+        }
+        assert_eq!(cache.get(&first, "rs").unwrap().len(), 2);
+        assert_eq!(cache.get(&second, "rs").unwrap()[0][0].content, "let x = 1;");
+
+        // Plain text for files without a grammar (all of them without the feature)
+        if !SyntaxHighlighter::new().has_syntax_for_extension("txt") {
+            assert!(cache.get(&first, "txt").is_none());
+            assert!(!cache.is_pending());
+        }
+    }
+}
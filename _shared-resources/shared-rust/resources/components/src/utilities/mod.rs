@@ -1,11 +1,18 @@
 // Utilities module
 // Helper functions and quality of life utilities
 
+pub mod dimming;
 pub mod helpers;
+pub mod highlight_cache;
 pub mod layout_calculator;
+pub mod snapshot;
 pub mod syntax_highlighting;
 
+pub use dimming::{apply_dimming, dim_buffer};
 pub use helpers::*;
+pub use highlight_cache::{HighlightCache, HighlightedLines};
 pub use layout_calculator::LayoutCalculator;
-pub use syntax_highlighting::{SyntaxHighlighter, get_file_extension};
+pub use syntax_highlighting::{
+    parse_tm_theme, tm_theme_colors, SyntaxHighlighter, get_file_extension, DEFAULT_SYNTAX_THEME,
+};
 
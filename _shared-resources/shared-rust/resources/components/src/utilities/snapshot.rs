@@ -0,0 +1,59 @@
+// Render Snapshots
+// Test harness that draws a component into a TestBackend and compares the buffer, as text,
+// with a snapshot under tests/snapshots, so layout changes (tab bar separators, gutter
+// widths, popup sizes) show up as a failing test with the old and new screens. Host apps
+// snapshot their own views with it the same way, under their own tests/snapshots
+
+use std::fs;
+use std::path::PathBuf;
+
+use ratatui::{backend::TestBackend, buffer::Buffer, Frame, Terminal};
+use unicode_width::UnicodeWidthStr;
+
+/// Draw one frame of `width` x `height` cells with `draw` and return the buffer as text
+pub fn render_to_text(width: u16, height: u16, draw: impl FnOnce(&mut Frame)) -> String {
+    let mut terminal = Terminal::new(TestBackend::new(width, height)).expect("test backend");
+    terminal.draw(draw).expect("draw into test backend");
+    buffer_text(terminal.backend().buffer())
+}
+
+/// Cell symbols of a buffer, one line per row, without trailing spaces
+/// The cells covered by a wide character are left out, so the text lines up as on screen
+pub fn buffer_text(buffer: &Buffer) -> String {
+    let area = buffer.area;
+    let mut lines = Vec::with_capacity(area.height as usize);
+    for y in area.top()..area.bottom() {
+        let mut line = String::new();
+        let mut covered = 0;
+        for x in area.left()..area.right() {
+            if covered > 0 {
+                covered -= 1;
+                continue;
+            }
+            let symbol = buffer[(x, y)].symbol();
+            covered = symbol.width().saturating_sub(1);
+            line.push_str(symbol);
+        }
+        lines.push(line.trim_end().to_string());
+    }
+    lines.join("\n") + "\n"
+}
+
+/// Compare `actual` with tests/snapshots/`name`.txt, panicking with both screens if they differ
+/// `UPDATE_SNAPSHOTS=1 cargo test` writes the snapshots from the current output
+pub fn assert_snapshot(name: &str, actual: &str) {
+    // Relative to the crate root, the working directory of its tests
+    let path = PathBuf::from("tests/snapshots").join(format!("{}.txt", name));
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::create_dir_all("tests/snapshots").expect("create tests/snapshots");
+        fs::write(&path, actual).expect("write snapshot");
+    }
+    let expected = fs::read_to_string(&path).unwrap_or_default();
+    assert!(
+        expected == actual,
+        "{} differs (UPDATE_SNAPSHOTS=1 rewrites it):\n--- expected\n{}--- actual\n{}",
+        path.display(),
+        expected,
+        actual
+    );
+}
@@ -1,41 +1,188 @@
 // Syntax highlighting module
 // Provides syntax highlighting based on file extensions using Syntastica (tree-sitter)
 // Requires the "syntax-highlighting" feature to be enabled
+//
+// The parsers and the themes are loaded once per process and shared by every highlighter, so
+// switching between files only costs a new processor. Themes are Syntastica's built-in ones
+// by name ("gruvbox::dark") or TextMate .tmTheme files by path.
+
+use std::collections::BTreeMap;
 
 use ratatui::text::Span;
 
 #[cfg(feature = "syntax-highlighting")]
 use ratatui::style::{Color, Style};
+#[cfg(feature = "syntax-highlighting")]
+use std::borrow::Cow;
+#[cfg(feature = "syntax-highlighting")]
+use std::cell::RefCell;
+#[cfg(feature = "syntax-highlighting")]
+use std::collections::HashMap;
+#[cfg(feature = "syntax-highlighting")]
+use std::sync::{Mutex, OnceLock};
 
 #[cfg(feature = "syntax-highlighting")]
-use syntastica::{process::Processor, renderer::TerminalRenderer};
+use syntastica::{language_set::SupportedLanguage, renderer::TerminalRenderer, theme::ResolvedTheme, Processor};
+#[cfg(feature = "syntax-highlighting")]
+use syntastica_parsers::{Lang, LanguageSetImpl};
+
+/// Theme used when none is chosen
+pub const DEFAULT_SYNTAX_THEME: &str = "gruvbox::dark";
+
+/// TextMate scope read for each highlight name of the tree-sitter grammars when a .tmTheme is
+/// loaded; a highlight name not listed here keeps the terminal's default color
+const TM_SCOPES: &[(&str, &str)] = &[
+    ("attribute", "entity.other.attribute-name"),
+    ("boolean", "constant.language"),
+    ("comment", "comment"),
+    ("constant", "constant"),
+    ("constant.builtin", "constant.language"),
+    ("constructor", "entity.name.type"),
+    ("function", "entity.name.function"),
+    ("function.builtin", "support.function"),
+    ("function.method", "entity.name.function"),
+    ("keyword", "keyword"),
+    ("keyword.operator", "keyword.operator"),
+    ("label", "entity.name.label"),
+    ("namespace", "entity.name.namespace"),
+    ("number", "constant.numeric"),
+    ("operator", "keyword.operator"),
+    ("property", "variable.other.property"),
+    ("punctuation", "punctuation"),
+    ("string", "string"),
+    ("string.escape", "constant.character.escape"),
+    ("tag", "entity.name.tag"),
+    ("type", "entity.name.type"),
+    ("type.builtin", "storage.type"),
+    ("variable", "variable"),
+    ("variable.builtin", "variable.language"),
+    ("variable.parameter", "variable.parameter"),
+];
+
+/// (scope selector, foreground) of every rule of a TextMate .tmTheme (plist XML) that sets a
+/// foreground color, in file order; the selector is as written ("string, constant.other")
+pub fn parse_tm_theme(xml: &str) -> Vec<(String, (u8, u8, u8))> {
+    let unescape = |text: &str| text.replace("&lt;", "<").replace("&gt;", ">").replace("&amp;", "&");
+    let mut rules = Vec::new();
+    // (key, value) pairs of the dicts being read, innermost last
+    let mut dicts: Vec<Vec<(String, String)>> = Vec::new();
+    let mut key = None;
+    let mut rest = xml;
+    while let Some(start) = rest.find('<') {
+        let Some(end) = rest[start..].find('>') else {
+            break;
+        };
+        let tag = &rest[start + 1..start + end];
+        rest = &rest[start + end + 1..];
+        let text = || unescape(&rest[..rest.find('<').unwrap_or(rest.len())]);
+        match tag {
+            "dict" => dicts.push(Vec::new()),
+            "key" => key = Some(text()),
+            "string" => {
+                if let (Some(key), Some(dict)) = (key.take(), dicts.last_mut()) {
+                    dict.push((key, text()));
+                }
+            }
+            "/dict" => {
+                let Some(dict) = dicts.pop() else {
+                    continue;
+                };
+                let value = |name: &str| dict.iter().find(|(k, _)| k == name).map(|(_, v)| v.clone());
+                let foreground = value("foreground");
+                match (value("scope"), foreground.as_deref().and_then(parse_hex)) {
+                    (Some(scope), Some(color)) => rules.push((scope, color)),
+                    // A rule's colors are in its nested `settings` dict: hand them up to the rule
+                    _ => {
+                        if let (Some(foreground), Some(parent)) = (foreground, dicts.last_mut()) {
+                            parent.push(("foreground".to_string(), foreground));
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    rules
+}
+
+/// "#RRGGBB" or "#RRGGBBAA" (the alpha is ignored)
+fn parse_hex(color: &str) -> Option<(u8, u8, u8)> {
+    let hex = color.trim().strip_prefix('#')?;
+    let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+    Some((channel(0)?, channel(2)?, channel(4)?))
+}
+
+/// Colors of the tree-sitter highlight names from the rules of a .tmTheme: each name takes the
+/// rule whose selector matches the most specific prefix of its TextMate scope
+pub fn tm_theme_colors(rules: &[(String, (u8, u8, u8))]) -> BTreeMap<&'static str, (u8, u8, u8)> {
+    let mut colors = BTreeMap::new();
+    for (name, scope) in TM_SCOPES {
+        let mut best: Option<(usize, (u8, u8, u8))> = None;
+        for (selector, color) in rules {
+            for part in selector.split(',').map(str::trim) {
+                let matches = *scope == part || scope.starts_with(&format!("{}.", part));
+                // Later rules win ties, as in TextMate
+                if matches && best.is_none_or(|(len, _)| part.len() >= len) {
+                    best = Some((part.len(), *color));
+                }
+            }
+        }
+        if let Some((_, color)) = best {
+            colors.insert(*name, color);
+        }
+    }
+    colors
+}
+
+/// Parsers of every supported language, loaded on first use
 #[cfg(feature = "syntax-highlighting")]
-use syntastica_parsers::LanguageSetImpl;
+fn languages() -> &'static LanguageSetImpl {
+    static LANGUAGES: OnceLock<LanguageSetImpl> = OnceLock::new();
+    LANGUAGES.get_or_init(LanguageSetImpl::new)
+}
+
+/// Theme by built-in name or .tmTheme path, resolved once and then served from the cache
 #[cfg(feature = "syntax-highlighting")]
-use syntastica_themes::gruvbox;
+fn cached_theme(theme: &str) -> Option<ResolvedTheme> {
+    static THEMES: OnceLock<Mutex<HashMap<String, ResolvedTheme>>> = OnceLock::new();
+    let mut themes = THEMES.get_or_init(Mutex::default).lock().unwrap_or_else(|err| err.into_inner());
+    if let Some(resolved) = themes.get(theme) {
+        return Some(resolved.clone());
+    }
+    let resolved = if theme.to_lowercase().ends_with(".tmtheme") {
+        let xml = std::fs::read_to_string(theme).ok()?;
+        let styles = tm_theme_colors(&parse_tm_theme(&xml))
+            .into_iter()
+            .map(|(name, (r, g, b))| (Cow::Borrowed(name), syntastica::style::Style::color_only(r, g, b)))
+            .collect::<BTreeMap<_, _>>();
+        ResolvedTheme::new(styles)
+    } else {
+        syntastica_themes::from_str(theme)?
+    };
+    themes.insert(theme.to_string(), resolved.clone());
+    Some(resolved)
+}
 
-/// Syntax highlighting state (caches processor and renderer)
+/// Syntax highlighting state (a processor over the shared parsers, and the theme)
 /// Only available when the "syntax-highlighting" feature is enabled
-#[derive(Debug)]
 pub struct SyntaxHighlighter {
+    /// Processing needs `&mut`; one highlighter serves one thread
     #[cfg(feature = "syntax-highlighting")]
-    language_set: LanguageSetImpl,
+    processor: RefCell<Processor<'static, LanguageSetImpl>>,
     #[cfg(feature = "syntax-highlighting")]
-    processor: Processor<'static>,
+    theme: ResolvedTheme,
 }
 
 impl SyntaxHighlighter {
-    /// Create a new syntax highlighter with default theme
+    /// Create a new syntax highlighter with the default theme (without colors if the theme
+    /// can't be resolved)
     /// Requires the "syntax-highlighting" feature to be enabled
     #[cfg(feature = "syntax-highlighting")]
     pub fn new() -> Self {
-        let language_set = LanguageSetImpl::new();
-        let processor = Processor::new(&language_set);
-        
-        Self {
-            language_set,
-            processor,
-        }
+        Self::with_theme(DEFAULT_SYNTAX_THEME).unwrap_or_else(|| Self {
+            processor: RefCell::new(Processor::new(languages())),
+            theme: ResolvedTheme::new(BTreeMap::new()),
+        })
     }
 
     #[cfg(not(feature = "syntax-highlighting"))]
@@ -43,17 +190,19 @@ impl SyntaxHighlighter {
         Self {}
     }
 
-    /// Create a new syntax highlighter with a specific theme
-    /// Note: Syntastica themes are applied during rendering, not initialization
-    /// This method currently uses the default theme
+    /// Create a new syntax highlighter with a specific theme: a built-in Syntastica theme by
+    /// name ("gruvbox::dark", "one::light") or the path of a .tmTheme file
+    /// None if there is no such theme or the file can't be read
     #[cfg(feature = "syntax-highlighting")]
-    pub fn with_theme(_theme_name: &str) -> Option<Self> {
-        // Themes are applied during rendering, so initialization is the same
-        Some(Self::new())
+    pub fn with_theme(theme: &str) -> Option<Self> {
+        Some(Self {
+            processor: RefCell::new(Processor::new(languages())),
+            theme: cached_theme(theme)?,
+        })
     }
 
     #[cfg(not(feature = "syntax-highlighting"))]
-    pub fn with_theme(_theme_name: &str) -> Option<Self> {
+    pub fn with_theme(_theme: &str) -> Option<Self> {
         Some(Self::new())
     }
 
@@ -68,12 +217,13 @@ impl SyntaxHighlighter {
             "rs" => Some("rust"),
             "py" => Some("python"),
             "js" | "jsx" => Some("javascript"),
-            "ts" | "tsx" => Some("typescript"),
+            "ts" => Some("typescript"),
+            "tsx" => Some("tsx"),
             "go" => Some("go"),
             "java" => Some("java"),
             "c" => Some("c"),
             "cpp" | "cc" | "cxx" => Some("cpp"),
-            "cs" => Some("csharp"),
+            "cs" => Some("c_sharp"),
             "rb" => Some("ruby"),
             "php" => Some("php"),
             "swift" => Some("swift"),
@@ -83,7 +233,6 @@ impl SyntaxHighlighter {
             "yaml" | "yml" => Some("yaml"),
             "json" => Some("json"),
             "toml" => Some("toml"),
-            "xml" => Some("xml"),
             "html" => Some("html"),
             "css" => Some("css"),
             "sql" => Some("sql"),
@@ -102,12 +251,15 @@ impl SyntaxHighlighter {
     #[cfg(feature = "syntax-highlighting")]
     pub fn highlight_line(&self, line: &str, extension: &str) -> Vec<Span<'static>> {
         // If no language found, return plain text
-        let Some(lang) = self.get_language_for_extension(extension) else {
+        let Some(lang) = self
+            .get_language_for_extension(extension)
+            .and_then(|name| Lang::for_name(name, languages()).ok())
+        else {
             return vec![Span::raw(line.to_string())];
         };
 
         // Process the line to get highlights
-        let highlights = match self.processor.process(line, lang) {
+        let highlights = match self.processor.borrow_mut().process(line, lang) {
             Ok(highlights) => highlights,
             Err(_) => {
                 // On error, return plain text
@@ -115,11 +267,9 @@ impl SyntaxHighlighter {
             }
         };
 
-        // Create renderer with theme
-        let mut renderer = TerminalRenderer::new(gruvbox::dark());
-
-        // Render highlights (render takes 2 args: highlights and renderer)
-        let output = syntastica::render(&highlights, &mut renderer);
+        // Render the highlights in the theme's colors, without a background
+        let mut renderer = TerminalRenderer::new(None);
+        let output = syntastica::render(&highlights, &mut renderer, &self.theme);
 
         // Parse the ANSI-colored output and convert to ratatui spans
         // Syntastica outputs ANSI escape codes, we need to parse them
@@ -131,8 +281,9 @@ impl SyntaxHighlighter {
     fn parse_ansi_to_spans(&self, ansi_text: &str) -> Vec<Span<'static>> {
         use regex::Regex;
         
-        // Regex to match ANSI escape sequences
-        let ansi_re = Regex::new(r"\x1b\[([0-9;]*?)m").unwrap();
+        // Regex to match ANSI escape sequences (compiled once)
+        static ANSI_RE: OnceLock<Regex> = OnceLock::new();
+        let ansi_re = ANSI_RE.get_or_init(|| Regex::new(r"\x1b\[([0-9;]*?)m").unwrap());
         let mut spans = Vec::new();
         let mut last_end = 0;
         let mut current_style = Style::default();
@@ -257,6 +408,13 @@ impl SyntaxHighlighter {
     }
 }
 
+// The processor holds parser state only, and has no Debug of its own
+impl std::fmt::Debug for SyntaxHighlighter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SyntaxHighlighter").finish_non_exhaustive()
+    }
+}
+
 impl Default for SyntaxHighlighter {
     fn default() -> Self {
         Self::new()
@@ -268,6 +426,7 @@ pub fn get_file_extension(file_path: &str) -> Option<String> {
     std::path::Path::new(file_path)
         .extension()
         .and_then(|ext| ext.to_str())
+        .filter(|ext| !ext.is_empty())
         .map(|s| s.to_lowercase())
 }
 
@@ -291,11 +450,38 @@ mod tests {
         let _ = highlighter.has_syntax_for_extension("rs");
     }
 
+    #[test]
+    fn test_tm_theme_colors_follow_scopes() {
+        let xml = r##"<plist><dict><key>settings</key><array>
+            <dict><key>settings</key><dict><key>foreground</key><string>#F8F8F2</string></dict></dict>
+            <dict><key>scope</key><string>comment</string>
+                <key>settings</key><dict><key>foreground</key><string>#75715E</string></dict></dict>
+            <dict><key>scope</key><string>keyword, storage.type</string>
+                <key>settings</key><dict><key>foreground</key><string>#F92672FF</string></dict></dict>
+            <dict><key>scope</key><string>keyword.operator</string>
+                <key>settings</key><dict><key>foreground</key><string>#66D9EF</string></dict></dict>
+        </array></dict></plist>"##;
+        let rules = parse_tm_theme(xml);
+        assert_eq!(rules.len(), 3);
+        assert_eq!(rules[1], ("keyword, storage.type".to_string(), (0xF9, 0x26, 0x72)));
+
+        let colors = tm_theme_colors(&rules);
+        assert_eq!(colors["comment"], (0x75, 0x71, 0x5E));
+        assert_eq!(colors["keyword"], (0xF9, 0x26, 0x72));
+        assert_eq!(colors["type.builtin"], (0xF9, 0x26, 0x72));
+        // The more specific selector wins
+        assert_eq!(colors["operator"], (0x66, 0xD9, 0xEF));
+        assert!(!colors.contains_key("string"));
+    }
+
     #[test]
     fn test_highlight_line() {
         let highlighter = SyntaxHighlighter::new();
         let spans = highlighter.highlight_line("fn main() {}", "rs");
         // Should return at least one span (even if plain text)
         assert!(!spans.is_empty());
+        // With the grammars built in, the keyword is colored
+        #[cfg(feature = "syntax-highlighting")]
+        assert!(spans.iter().any(|span| span.content == "fn" && span.style.fg.is_some()));
     }
 }
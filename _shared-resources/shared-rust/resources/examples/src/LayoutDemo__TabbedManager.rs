@@ -21,12 +21,12 @@ use serde::Deserialize;
 use tui_components::{
     BaseLayout, BaseLayoutConfig, BaseLayoutResult,
     BindingConfig, StatusBarConfig,
-    DimmingContext, RectRegistry, Popup, render_popup,
+    DimmingContext, RectRegistry, Popup, render_popup, apply_dimming, HWND_POPUP,
     TabBar, TabBarStyle, RectHandle,
-    Toast, ToastType, render_toasts,
+    Toast, ToastType, ToastManager,
     TabBarConfigYaml, TabBarManager,
     get_box_by_name, BoundingBox,
-    SplitDiffView, SplitDiffViewConfig, SplitDiffViewState,
+    SplitDiffView, SplitDiffViewConfig, SplitDiffViewState, DEFAULT_CONTEXT_LINES,
 };
 use crossterm::{
     event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind, KeyModifiers, MouseEventKind},
@@ -121,12 +121,13 @@ fn load_config(config_path: Option<PathBuf>) -> Result<AppConfig, Box<dyn std::e
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let mut popup: Option<Popup> = None;     //
     let mut popup_cycle_state = 0u8;         // 0=info, 1=warning, 2=error, then cycles
-    let mut toasts: Vec<Toast> = Vec::new(); //
+    let mut toasts = ToastManager::new();    //
     let mut toast_counter = 0u32;            // Counter for unique toast messages
     let mut registry = RectRegistry::new();  // Create registry for handle-based positioning
     
     // Split diff view state
     let mut diff_view_state = SplitDiffViewState::default();
+    let mut context_lines = DEFAULT_CONTEXT_LINES; // Adjusted with +/-
     
     // Load configuration from YAML file -------------------------------------->> 
     let app_config = match load_config(None) {
@@ -164,6 +165,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             default_text: app_config.application.status_bar.default_text.clone(),
             modal_text: Some(app_config.application.status_bar.modal_text.clone()),
         },
+        regions: vec![],
     };
     //--------------------------------------------------------------------------------------------<<
     
@@ -199,8 +201,6 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     
     //--------------------------------------------------------------------------------------------<<
     
-    let mut original_anchor_metrics: Option<Rect> = None; // Store before bounding box adjustment
-    
     
     // ┌────────────────────────────────────────────────────────────────────────────────────────────────┐
     // │                                           MAIN LOOP                                            │
@@ -208,6 +208,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
           
     loop {
         terminal.draw(|f| {
+            registry.begin_frame(); // Overlays registered last frame become the previous layer
             let area = f.area();
             
             // Create dimming context based on popup state
@@ -223,27 +224,6 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             let result: BaseLayoutResult = base_layout.render(f, area, &mut registry);
             let content_area = result.content_area; // Get the content area
             
-            // Store original anchor metrics after first render (if not already stored)
-            // Note: This stores the unadjusted metrics from the first render
-            if original_anchor_metrics.is_none() {
-                if let Some(anchor_handle) = registry.get_handle(main_content_box_handle_name) {
-                    if let Some(metrics) = registry.get_metrics(anchor_handle) {
-                        original_anchor_metrics = Some(metrics.into());
-                    }
-                } else {
-                    // If handle doesn't exist yet, store the content_area as original
-                    original_anchor_metrics = Some(content_area);
-                }
-            }
-            
-            // Restore anchor position (HWND_MAIN_CONTENT_BOX) ---------------->> 
-            // This clears any previous adjustments from the last frame
-            if let (Some(anchor_handle), Some(original_metrics)) = (registry.get_handle(main_content_box_handle_name), original_anchor_metrics) {
-                registry.update(anchor_handle, original_metrics);
-            }
-            
-            //------------------------------------------------------------------------------------<<
-            
             // Initialize or update main content bounding box (HWND_MAIN_CONTENT_BOX) with current content area
             // This is the same box that the tab bar uses as its anchor
             // If handle doesn't exist, create it; otherwise update it with current content_area
@@ -256,21 +236,17 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             }
 
             // Prepare tab bar ------------------------------------------------>> 
-            // This will adjust the anchor box (y+1, height-1) for Tab style
-            // The adjustment happens in from_registry() AFTER the box has been updated with content_area
-            // This ensures the anchor box is updated with current size, then adjusted for Tab style
-            let tab_bar_result = main_content_tab_bar.prepare(&mut registry, Some(tab_style));
+            // The anchor box is left as-is; the space the tab bar reserves (the decorative
+            // row for Tab style) is reported through content_area
+            let tab_bar_result = main_content_tab_bar.prepare(&registry, Some(tab_style));
             
             //------------------------------------------------------------------------------------<<
             
             // Render content block ------------------------------------------->> 
-            // Uses the adjusted anchor (if Tab style was applied)
-            // The anchor box has been adjusted by prepare(), so content renders with correct position
-            let render_area = if let Some(box_manager) = get_box_by_name(&registry, main_content_box_handle_name) {
-                box_manager.prepare(&mut registry).unwrap_or(content_area)
-            } else {
-                content_area
-            };
+            let render_area = tab_bar_result
+                .as_ref()
+                .map(|prepared| prepared.content_area)
+                .unwrap_or(content_area);
             
             // Always render the content box border first
             render_content(f, render_area, &dimming);
@@ -316,7 +292,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                             
                             let diff_config = SplitDiffViewConfig::new()
                                 .with_source_title(format!("Source: {}", file1_path.file_name().unwrap_or_default().to_string_lossy()))
-                                .with_dest_title(format!("Destination: {}", file2_path.file_name().unwrap_or_default().to_string_lossy()));
+                                .with_dest_title(format!("Destination: {}", file2_path.file_name().unwrap_or_default().to_string_lossy()))
+                                .with_minimap(true)
+                                .with_context_lines(context_lines);
                             
                             let mut diff_view = SplitDiffView::new(
                                 &diff_config,
@@ -339,11 +317,11 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             //------------------------------------------------------------------------------------<<
             
             // Render tab bar ------------------------------------------------->> 
-            if let Some((tab_bar, anchor_handle, tab_bar_state)) = tab_bar_result {
-                tab_bar.render_with_state(f, &mut registry, &tab_bar_state, Some(&dimming));
+            if let Some(prepared) = tab_bar_result {
+                prepared.tab_bar.render_with_state(f, &mut registry, &prepared.state, Some(&dimming));
                 
                 // Store tab bar for click detection
-                current_tab_bar = Some((tab_bar, anchor_handle));
+                current_tab_bar = Some((prepared.tab_bar, prepared.anchor));
             }
             
             //------------------------------------------------------------------------------------<<
@@ -358,22 +336,17 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             
             
             if let Some(ref popup) = popup { //> Render popup if active
-                render_popup(f, area, popup);
+                if let Some(rect) = render_popup(f, area, popup) {
+                    registry.register_layer(Some(HWND_POPUP), rect, 1);
+                }
             } //<
+            apply_dimming(f, &registry); // Dim everything outside the popup
             
-            //> Filter out expired toasts (older than 1.5 seconds)
-            let now = std::time::SystemTime::now();
-            toasts.retain(|toast| {
-                if let Ok(duration) = now.duration_since(toast.shown_at) {
-                    duration.as_secs_f64() < 1.5
-                } else {
-                    false // Remove if time calculation fails
-                }
-            });
+            toasts.tick(); // Expire toasts and promote queued ones
             
             //<<----------------------------------------------------------------------
             
-            render_toasts(f, area, &toasts); // Render toasts (stacked in bottom-left)
+            toasts.render(f, area); // Render toasts (stacked in bottom-left)
         })?;
         
         
@@ -481,13 +454,24 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                         if let Some(tab_bar_state) = registry.get_tab_bar_state(main_content_tab_bar.handle()) {
                             if let Some(tab_config) = tab_bar_state.tab_configs.get(active_tab_idx) {
                                 if tab_config.id == "diff" {
-                                    diff_view_state.fold_unchanged = !diff_view_state.fold_unchanged;
+                                    diff_view_state.folds.toggle_all();
                                     diff_view_state.scroll_offset = 0; // Reset scroll when toggling fold
                                 }
                             }
                         }
                     }
                 }
+                KeyCode::Char('+') | KeyCode::Char('=') => {
+                    // Show more context around changes in folded regions
+                    context_lines += 1;
+                }
+                KeyCode::Char('-') => {
+                    context_lines = context_lines.saturating_sub(1);
+                }
+                KeyCode::Enter => {
+                    // Expand the topmost "N lines hidden" block on screen
+                    diff_view_state.expand_visible_fold();
+                }
                 KeyCode::Up | KeyCode::Char('k') => {
                     // Scroll diff view up
                     if let Some(active_tab_idx) = registry.get_active_tab(main_content_tab_bar.handle()) {
@@ -542,6 +526,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             Event::Mouse(mouse_event) => {
                 // Handle mouse clicks on tabs
                 if mouse_event.kind == MouseEventKind::Down(crossterm::event::MouseButton::Left) {
+                    // Clicking the diff minimap jumps to that part of the file
+                    diff_view_state.jump_to_minimap(&registry, mouse_event.column, mouse_event.row);
+                    
                     if let Some((ref tab_bar, _handle)) = current_tab_bar {
                         let clicked_tab: Option<usize> = tab_bar.get_tab_at(mouse_event.column, mouse_event.row, Some(&registry));
                         if let Some(clicked_tab_idx) = clicked_tab {
@@ -15,9 +15,9 @@ use std::io;
 use tui_components::{
     BaseLayout, BaseLayoutConfig, BaseLayoutResult,
     BindingConfig, StatusBarConfig,
-    DimmingContext, RectRegistry, Popup, render_popup,
+    DimmingContext, RectRegistry, Popup, render_popup, apply_dimming, HWND_POPUP,
     TabBar, TabBarItem, TabBarStyle, TabBarAlignment, TabBarPosition, RectHandle,
-    Toast, ToastType, render_toasts,
+    Toast, ToastType, ToastManager,
 };
 use crossterm::{
     event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyModifiers, MouseEventKind},
@@ -51,12 +51,13 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             default_text: "Status: Ready | [7] Tabbed | [8] Boxed | [9] Text | [Alt+8] BoxStatic | [Alt+9] TextStatic | [p] Cycle Popups | [t] Toast | [q] Quit".to_string(),
             modal_text: Some("Modal active - Press 'm' to close | Use arrow keys to navigate | Enter to confirm | Esc to cancel".to_string()),
         },
+        regions: vec![],
     };
     
     // Application state
     let mut popup: Option<Popup> = None;
     let mut popup_cycle_state = 0u8; // 0=info, 1=warning, 2=error, then cycles
-    let mut toasts: Vec<Toast> = Vec::new();
+    let mut toasts = ToastManager::new();
     let mut toast_counter = 0u32; // Counter for unique toast messages
     let mut tab_style = TabBarStyle::Tab;
     let mut active_tab_index = 0;
@@ -73,6 +74,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Render loop
     loop {
         terminal.draw(|f| {
+            registry.begin_frame(); // Overlays registered last frame become the previous layer
             let area = f.area();
             
             // Create dimming context based on popup state
@@ -132,21 +134,15 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             
             // Render popup if active
             if let Some(ref popup) = popup {
-                render_popup(f, area, popup);
-            }
-            
-            // Filter out expired toasts (older than 1.5 seconds)
-            let now = std::time::SystemTime::now();
-            toasts.retain(|toast| {
-                if let Ok(duration) = now.duration_since(toast.shown_at) {
-                    duration.as_secs_f64() < 1.5
-                } else {
-                    false // Remove if time calculation fails
+                if let Some(rect) = render_popup(f, area, popup) {
+                    registry.register_layer(Some(HWND_POPUP), rect, 1);
                 }
-            });
+            }
+            apply_dimming(f, &registry); // Dim everything outside the popup
             
-            // Render toasts (stacked in bottom-left)
-            render_toasts(f, area, &toasts);
+            // Expire toasts, then render them (stacked in bottom-left)
+            toasts.tick();
+            toasts.render(f, area);
         })?;
         
         // Handle events (keyboard and mouse) - use non-blocking poll to allow toast timeout checking
@@ -0,0 +1,88 @@
+// Pattern Benchmarks
+// Exclude matching per path and a full scan of a generated tree; run with `cargo bench`
+
+use std::fs;
+use std::hint::black_box;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use sync_manager::operations::{DiffEngine, DiffType, TransformPipeline};
+use sync_manager::utilities::ExcludeMatcher;
+
+/// Directories and files per directory of the generated tree
+const TREE_DIRS: usize = 200;
+const TREE_FILES: usize = 50;
+
+/// Patterns like those of a typical mapping on top of the built-in excludes
+const MAPPING_EXCLUDES: &[&str] = &["drafts", "*.bak", "/archive/", "*.local.md", "scratch", "*.log"];
+
+fn main() {
+    let patterns: Vec<String> = DiffEngine::new()
+        .exclude_patterns()
+        .iter()
+        .cloned()
+        .chain(MAPPING_EXCLUDES.iter().map(|p| p.to_string()))
+        .collect();
+    let paths: Vec<PathBuf> = (0..100_000)
+        .map(|i| PathBuf::from(format!("/workspace/_shared-resources/rules/dir{}/sub{}/file{}.md", i % 300, i % 7, i)))
+        .collect();
+
+    bench("substring matching, 100k paths", || {
+        paths.iter().filter(|path| substring_match(path, &patterns)).count()
+    });
+    let matcher = ExcludeMatcher::new(&patterns);
+    bench("compiled matcher, 100k paths", || paths.iter().filter(|path| matcher.is_match(path)).count());
+    bench("compiling the matcher", || ExcludeMatcher::new(&patterns));
+
+    let root = std::env::temp_dir().join(format!("sync-manager-bench-{}", std::process::id()));
+    let (source, dest) = generate_tree(&root);
+    let excludes: Vec<String> = MAPPING_EXCLUDES.iter().map(|p| p.to_string()).collect();
+    let transforms = TransformPipeline::default();
+    bench(&format!("scan of {} files", TREE_DIRS * TREE_FILES), || {
+        DiffEngine::new()
+            .compute_diff(&source, &dest, DiffType::SharedToProject, &excludes, &[], &transforms)
+            .map(|diffs| diffs.len())
+    });
+    fs::remove_dir_all(&root).ok();
+}
+
+/// Run `f` for about a second (at least 3 times) and print the mean time per run
+fn bench<T>(name: &str, mut f: impl FnMut() -> T) {
+    black_box(f());
+    let started = Instant::now();
+    let mut runs = 0u32;
+    while runs < 3 || started.elapsed() < Duration::from_secs(1) {
+        black_box(f());
+        runs += 1;
+    }
+    let mean = started.elapsed() / runs;
+    println!("{:<40} {:>12.3?} per run ({} runs)", name, mean, runs);
+}
+
+/// Matching as done before patterns were compiled: lowercase and compare per pattern
+fn substring_match(path: &Path, patterns: &[String]) -> bool {
+    let path = path.to_string_lossy().to_lowercase();
+    patterns.iter().any(|pattern| {
+        let pattern = pattern.to_lowercase();
+        match pattern.strip_prefix('*') {
+            Some(suffix) => path.ends_with(suffix),
+            None => path.contains(&pattern),
+        }
+    })
+}
+
+/// Source and destination trees with identical files, so the scan compares every one
+fn generate_tree(root: &Path) -> (PathBuf, PathBuf) {
+    let (source, dest) = (root.join("shared"), root.join("project"));
+    for dir in 0..TREE_DIRS {
+        for side in [&source, &dest] {
+            let dir_path = side.join(format!("dir{}", dir));
+            fs::create_dir_all(&dir_path).expect("create benchmark tree");
+            for file in 0..TREE_FILES {
+                fs::write(dir_path.join(format!("file{}.md", file)), format!("# {} {}\n", dir, file))
+                    .expect("write benchmark file");
+            }
+        }
+    }
+    (source, dest)
+}
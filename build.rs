@@ -29,7 +29,18 @@ fn main() {
 
 pub const SHOW_LINE_NUMBERS: bool = {show_line_numbers};
 pub const SYNTAX_HIGHLIGHTING: bool = {syntax_highlighting};
+pub const SYNTAX_THEME: &str = "{syntax_theme}";
 pub const CONTEXT_LINES: usize = {context_lines};
+pub const FOLD_UNCHANGED: bool = {fold_unchanged};
+pub const GROUP_BY_STATUS: bool = {group_by_status};
+pub const DUAL_LINE_NUMBERS: bool = {dual_line_numbers};
+pub const ARCHIVE_DIFFS: bool = {archive_diffs};
+pub const IGNORE_WHITESPACE: bool = {ignore_whitespace};
+pub const IGNORE_TIMESTAMPS: bool = {ignore_timestamps};
+pub const SIMILARITY_THRESHOLD: f64 = {similarity_threshold:?};
+pub const LONG_LINE_CHARS: usize = {long_line_chars};
+pub const WRAP_MARKER: &str = "{wrap_marker}";
+pub const TAB_WIDTH: usize = {tab_width};
 pub const MOUSE_ENABLED: bool = {mouse_enabled};
 pub const THEME: &str = "{theme}";
 
@@ -37,6 +48,17 @@ pub const SYNC_DIRECTION: &str = "{sync_direction}";
 pub const CONFLICT_RESOLUTION: &str = "{conflict_resolution}";
 pub const CONTINUE_ON_ERROR: bool = {continue_on_error};
 pub const CREATE_BACKUPS: bool = {create_backups};
+pub const CONFIRM_SYNC: bool = {confirm_sync};
+pub const CLEAN_DESTINATION: &str = "{clean_destination}";
+pub const PROPAGATE_DELETES: bool = {propagate_deletes};
+pub const REMOVE_DIRECTORIES: bool = {remove_directories};
+pub const READ_ONLY: bool = {read_only};
+pub const SYNC_WORKERS: usize = {sync_workers};
+pub const MAX_KB_PER_SEC: u64 = {max_kb_per_sec};
+pub const WATCHDOG_SECS: u64 = {watchdog_secs};
+pub const RETRY_ATTEMPTS: u32 = {retry_attempts};
+pub const RETRY_BACKOFF_MS: u64 = {retry_backoff_ms};
+pub const EDITOR: &str = "{editor}";
 
 pub const GLOBAL_EXCLUDES: &[&str] = &[
 {excludes}
@@ -47,25 +69,49 @@ pub const SOURCE_DIM_BG: (u8, u8, u8) = {source_dim_bg};
 pub const SOURCE_BRIGHT_BG: (u8, u8, u8) = {source_bright_bg};
 pub const DEST_DIM_BG: (u8, u8, u8) = {dest_dim_bg};
 pub const DEST_BRIGHT_BG: (u8, u8, u8) = {dest_bright_bg};
+pub const MOVED_BG: (u8, u8, u8) = {moved_bg};
 "#,
         show_line_numbers = config.show_line_numbers,
         syntax_highlighting = config.syntax_highlighting,
+        syntax_theme = config.syntax_theme,
         context_lines = config.context_lines,
+        fold_unchanged = config.fold_unchanged,
+        group_by_status = config.group_by_status,
+        dual_line_numbers = config.dual_line_numbers,
+        archive_diffs = config.archive_diffs,
+        ignore_whitespace = config.ignore_whitespace,
+        ignore_timestamps = config.ignore_timestamps,
+        similarity_threshold = config.similarity_threshold,
+        long_line_chars = config.long_line_chars,
+        wrap_marker = config.wrap_marker,
+        tab_width = config.tab_width,
         mouse_enabled = config.mouse_enabled,
         theme = config.theme,
         sync_direction = config.sync_direction,
         conflict_resolution = config.conflict_resolution,
         continue_on_error = config.continue_on_error,
         create_backups = config.create_backups,
+        confirm_sync = config.confirm_sync,
+        clean_destination = config.clean_destination,
+        propagate_deletes = config.propagate_deletes,
+        remove_directories = config.remove_directories,
+        read_only = config.read_only,
+        sync_workers = config.sync_workers,
+        max_kb_per_sec = config.max_kb_per_sec,
+        watchdog_secs = config.watchdog_secs,
+        retry_attempts = config.retry_attempts,
+        retry_backoff_ms = config.retry_backoff_ms,
+        editor = config.editor.escape_default(),
         excludes = config.global_excludes
             .iter()
             .map(|e| format!("    \"{}\",", e))
             .collect::<Vec<_>>()
             .join("\n"),
-        source_dim_bg = format!("({}, {}, {})", config.source_dim_bg.0, config.source_dim_bg.1, config.source_dim_bg.2),
-        source_bright_bg = format!("({}, {}, {})", config.source_bright_bg.0, config.source_bright_bg.1, config.source_bright_bg.2),
-        dest_dim_bg = format!("({}, {}, {})", config.dest_dim_bg.0, config.dest_dim_bg.1, config.dest_dim_bg.2),
-        dest_bright_bg = format!("({}, {}, {})", config.dest_bright_bg.0, config.dest_bright_bg.1, config.dest_bright_bg.2),
+        source_dim_bg = rgb_tuple(config.source_dim_bg),
+        source_bright_bg = rgb_tuple(config.source_bright_bg),
+        dest_dim_bg = rgb_tuple(config.dest_dim_bg),
+        dest_bright_bg = rgb_tuple(config.dest_bright_bg),
+        moved_bg = rgb_tuple(config.moved_bg),
     );
     
     fs::write(&dest_path, generated).expect("Failed to write compiled config");
@@ -74,18 +120,41 @@ pub const DEST_BRIGHT_BG: (u8, u8, u8) = {dest_bright_bg};
 struct CompiledConfig {
     show_line_numbers: bool,
     syntax_highlighting: bool,
+    syntax_theme: String,
     context_lines: usize,
+    fold_unchanged: bool,
+    group_by_status: bool,
+    dual_line_numbers: bool,
+    archive_diffs: bool,
+    ignore_whitespace: bool,
+    ignore_timestamps: bool,
+    similarity_threshold: f64,
+    long_line_chars: usize,
+    wrap_marker: String,
+    tab_width: usize,
     mouse_enabled: bool,
     theme: String,
     sync_direction: String,
     conflict_resolution: String,
     continue_on_error: bool,
     create_backups: bool,
+    confirm_sync: bool,
+    clean_destination: String,
+    propagate_deletes: bool,
+    remove_directories: bool,
+    read_only: bool,
+    sync_workers: usize,
+    max_kb_per_sec: u64,
+    watchdog_secs: u64,
+    retry_attempts: u32,
+    retry_backoff_ms: u64,
+    editor: String,
     global_excludes: Vec<String>,
     source_dim_bg: (u8, u8, u8),
     source_bright_bg: (u8, u8, u8),
     dest_dim_bg: (u8, u8, u8),
     dest_bright_bg: (u8, u8, u8),
+    moved_bg: (u8, u8, u8),
 }
 
 impl Default for CompiledConfig {
@@ -93,13 +162,35 @@ impl Default for CompiledConfig {
         Self {
             show_line_numbers: true,
             syntax_highlighting: false,
+            syntax_theme: "gruvbox::dark".to_string(),
             context_lines: 3,
+            fold_unchanged: true,
+            group_by_status: false,
+            dual_line_numbers: false,
+            archive_diffs: false,
+            ignore_whitespace: false,
+            ignore_timestamps: false,
+            similarity_threshold: 0.3,
+            long_line_chars: 1000,
+            wrap_marker: "arrow".to_string(),
+            tab_width: 4,
             mouse_enabled: true,
             theme: "default".to_string(),
             sync_direction: "both".to_string(),
             conflict_resolution: "prompt".to_string(),
             continue_on_error: true,
             create_backups: true,
+            confirm_sync: true,
+            clean_destination: "none".to_string(),
+            propagate_deletes: true,
+            remove_directories: false,
+            read_only: false,
+            sync_workers: 0,
+            max_kb_per_sec: 0,
+            watchdog_secs: 30,
+            retry_attempts: 3,
+            retry_backoff_ms: 100,
+            editor: String::new(),
             global_excludes: vec![
                 ".git".to_string(),
                 "__pycache__".to_string(),
@@ -117,6 +208,7 @@ impl Default for CompiledConfig {
             source_bright_bg: (95, 3, 3),   // #5f0303
             dest_dim_bg: (35, 41, 21),      // #232915
             dest_bright_bg: (80, 102, 31),  // #50661f
+            moved_bg: (42, 33, 64),         // #2a2140
         }
     }
 }
@@ -186,6 +278,10 @@ fn parse_config(content: &str) -> CompiledConfig {
                         config.dest_bright_bg = parse_hex_color(value);
                         continue;
                     }
+                    "moved_bg" => {
+                        config.moved_bg = parse_hex_color(value);
+                        continue;
+                    }
                     _ => {
                         // Unknown key in colors section - stop parsing colors
                         in_colors = false;
@@ -195,7 +291,20 @@ fn parse_config(content: &str) -> CompiledConfig {
                 match key {
                     "show_line_numbers" => config.show_line_numbers = parse_bool(value),
                     "syntax_highlighting" => config.syntax_highlighting = parse_bool(value),
+                    "syntax_theme" => config.syntax_theme = value.trim_matches('"').to_string(),
                     "context_lines" => config.context_lines = value.parse().unwrap_or(3),
+                    "fold_unchanged" => config.fold_unchanged = parse_bool(value),
+                    "group_by_status" => config.group_by_status = parse_bool(value),
+                    "dual_line_numbers" => config.dual_line_numbers = parse_bool(value),
+                    "archive_diffs" => config.archive_diffs = parse_bool(value),
+                    "ignore_whitespace" => config.ignore_whitespace = parse_bool(value),
+                    "ignore_timestamps" => config.ignore_timestamps = parse_bool(value),
+                    "similarity_threshold" => {
+                        config.similarity_threshold = value.parse::<f64>().unwrap_or(0.3).clamp(0.0, 1.0)
+                    }
+                    "long_line_chars" => config.long_line_chars = value.parse().unwrap_or(1000),
+                    "wrap_marker" => config.wrap_marker = value.to_string(),
+                    "tab_width" => config.tab_width = value.parse().unwrap_or(4),
                     "mouse_enabled" => config.mouse_enabled = parse_bool(value),
                     "theme" => config.theme = value.to_string(),
                     _ => {}
@@ -206,6 +315,17 @@ fn parse_config(content: &str) -> CompiledConfig {
                     "conflict_resolution" => config.conflict_resolution = value.to_string(),
                     "continue_on_error" => config.continue_on_error = parse_bool(value),
                     "create_backups" => config.create_backups = parse_bool(value),
+                    "confirm_sync" => config.confirm_sync = parse_bool(value),
+                    "clean_destination" => config.clean_destination = value.to_string(),
+                    "propagate_deletes" => config.propagate_deletes = parse_bool(value),
+                    "remove_directories" => config.remove_directories = parse_bool(value),
+                    "read_only" => config.read_only = parse_bool(value),
+                    "sync_workers" => config.sync_workers = value.parse().unwrap_or(0),
+                    "max_kb_per_sec" => config.max_kb_per_sec = value.parse().unwrap_or(0),
+                    "watchdog_secs" => config.watchdog_secs = value.parse().unwrap_or(30),
+                    "retry_attempts" => config.retry_attempts = value.parse().unwrap_or(3),
+                    "retry_backoff_ms" => config.retry_backoff_ms = value.parse().unwrap_or(100),
+                    "editor" => config.editor = value.trim_matches('"').to_string(),
                     _ => {}
                 }
             }
@@ -264,6 +384,10 @@ fn parse_kv(line: &str) -> Option<(&str, &str)> {
     Some((key, value))
 }
 
+fn rgb_tuple((r, g, b): (u8, u8, u8)) -> String {
+    format!("({}, {}, {})", r, g, b)
+}
+
 fn parse_bool(s: &str) -> bool {
     matches!(s.to_lowercase().as_str(), "true" | "yes" | "1")
 }
@@ -273,7 +397,7 @@ fn parse_hex_color(s: &str) -> (u8, u8, u8) {
     let s = s.trim().trim_matches('"').trim_matches('\'');
     
     // Remove # if present
-    let s = if s.starts_with('#') { &s[1..] } else { s };
+    let s = s.strip_prefix('#').unwrap_or(s);
     
     // Parse hex string
     if s.len() == 6 {
@@ -0,0 +1,105 @@
+// Annotations
+// Short notes attached to diff entries ("intentionally diverged, revisit after refactor"),
+// kept in the state directory so they outlive rescans and restarts
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::session::STATE_DIR_NAME;
+use crate::operations::DiffEntry;
+
+/// Annotations file name inside the state directory
+const ANNOTATIONS_FILE_NAME: &str = "annotations.yaml";
+
+/// Notes by project ("" for the workspace's own files) and relative path
+/// A note applies to the path in both lists, since both compare the same pair of files
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Annotations {
+    #[serde(default)]
+    notes: BTreeMap<String, BTreeMap<PathBuf, String>>,
+}
+
+impl Annotations {
+    /// Path of the annotations file of a workspace
+    pub fn path_for(workspace_root: &Path) -> PathBuf {
+        workspace_root.join(STATE_DIR_NAME).join(ANNOTATIONS_FILE_NAME)
+    }
+    
+    /// Load the annotations of a workspace
+    /// A missing or unreadable file yields no annotations
+    pub fn load(workspace_root: &Path) -> Self {
+        fs::read_to_string(Self::path_for(workspace_root))
+            .ok()
+            .and_then(|content| serde_yaml::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+    
+    /// Save the annotations of a workspace, creating the state directory if needed
+    pub fn save(&self, workspace_root: &Path) -> Result<()> {
+        let path = Self::path_for(workspace_root);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+        }
+        let content = serde_yaml::to_string(self).context("Failed to serialize annotations")?;
+        fs::write(&path, content).with_context(|| format!("Failed to write annotations: {}", path.display()))
+    }
+    
+    /// Note of an entry
+    pub fn get(&self, entry: &DiffEntry) -> Option<&str> {
+        self.notes.get(&entry.project)?.get(&entry.path).map(String::as_str)
+    }
+    
+    /// Attach a note to an entry, replacing its note; a blank note removes it
+    pub fn set(&mut self, entry: &DiffEntry, note: &str) {
+        let note = note.trim();
+        if note.is_empty() {
+            if let Some(notes) = self.notes.get_mut(&entry.project) {
+                notes.remove(&entry.path);
+                if notes.is_empty() {
+                    self.notes.remove(&entry.project);
+                }
+            }
+            return;
+        }
+        self.notes
+            .entry(entry.project.clone())
+            .or_default()
+            .insert(entry.path.clone(), note.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(path: &str, project: &str) -> DiffEntry {
+        DiffEntry::for_test(path, project)
+    }
+
+    #[test]
+    fn test_annotations_round_trip() {
+        let root = std::env::temp_dir().join(format!("sync-manager-annotations-{}", std::process::id()));
+        let own = entry("rules/a.md", "");
+        let web = entry("rules/a.md", "web");
+
+        let mut annotations = Annotations::default();
+        annotations.set(&own, "  intentionally diverged  ");
+        annotations.set(&web, "revisit after refactor");
+        annotations.save(&root).unwrap();
+
+        let mut loaded = Annotations::load(&root);
+        assert_eq!(loaded, annotations);
+        assert_eq!(loaded.get(&own), Some("intentionally diverged"));
+        assert_eq!(loaded.get(&web), Some("revisit after refactor"));
+
+        loaded.set(&web, " ");
+        assert_eq!(loaded.get(&web), None);
+        assert!(!loaded.notes.contains_key("web"));
+
+        let _ = fs::remove_dir_all(&root);
+        assert_eq!(Annotations::load(&root), Annotations::default());
+    }
+}
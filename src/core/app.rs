@@ -1,15 +1,54 @@
 // Application State
 // Main application state management and lifecycle
 
-use anyhow::Result;
-use std::path::PathBuf;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+use tui_components::{
+    ContextMenu, FocusManager, FoldState, HighlightCache, ListPanel, MenuResult, Popup, PopupManager, PopupResult,
+    ToastManager,
+};
 
-use super::{AppConfig, ProjectConfig};
-use crate::operations::DiffEntry;
+use super::annotations::Annotations;
+use super::bookmarks::{BookmarkPrompt, Bookmarks};
+use super::entry_menu::{entry_menu, entry_pattern, history_menu, EntryAction, HISTORY_LIMIT};
+use super::project_config::{DirectionOverride, FilterPreset, PatternList};
+use super::diff_cache::{DiffCache, HashCache};
+use super::diff_scan::{DiffLists, DiffScan, ScanTask};
+use super::dirty_guard::{self, DirtyRepo};
+use super::frame_stats::FrameStats;
+use super::list_filter::{ListFilter, FILTER_SYNTAX};
+use super::list_groups::{destination_is_newer, group_by_status};
+use super::local_config::LOCAL_CONFIG_NAME;
+use super::pattern_tester;
+use super::review::{append_exclusion_report, Review, Verdict};
+use super::review_branch::ReviewBranch;
+use super::session::{log_path, EntryKey, ScrollPosition, SessionState, SessionTab, SessionViewMode, STATE_DIR_NAME};
+use super::setup::SetupWizard;
+use super::settings::settings_overrides;
+use super::sync_lock::{LockAttempt, LockOwner, SyncLock};
+use super::sync_report::SyncReport;
+use super::sync_task::{BranchCommit, SyncTask};
+use super::{AppConfig, DiffCopy, EntrySide, ProjectConfig, ProjectIdentity, SettingsTab, SnapshotsTab};
+use crate::operations::{
+    aligned_line, archive_diff_content, check_workspace, format_hunk, hunk_range, take_hunk, entry_leaves, is_structured, leaf_lines, parse_leaves, structural_diff_content, lockfile_summary, LockfileKind, export_archive, BlameLine, DiffEntry, DiffType, FileCommit, EntryMetadata, FileStatus, GitOps, HealthReport, ImagePair, Metrics, Severity, SyncEngine, SyncOptions, Watchdog,
+};
+use crate::error::SyncManagerError;
+use crate::operations::diff::LineAlignment;
+use crate::ui::side_by_side::SideBySideCache;
+use crate::utilities::{append_log, format_duration, join_lines, line_at, replace_line, Clipboard};
 
 /// Project config file name
 const PROJECT_CONFIG_NAME: &str = "sync-manager.yaml";
 
+/// Default directory (inside the state directory) for exported archives
+const EXPORTS_DIR_NAME: &str = "exports";
+
 /// The current view mode in the application
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum ViewMode {
@@ -19,6 +58,194 @@ pub enum ViewMode {
     ProjectToShared,
 }
 
+/// Top-level tabs
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum AppTab {
+    /// Diff lists and side-by-side view
+    #[default]
+    Sync,
+    /// Snapshot management
+    Snapshots,
+    /// Runtime settings form
+    Settings,
+}
+
+impl AppTab {
+    /// All tabs in display order
+    pub const ALL: [AppTab; 3] = [AppTab::Sync, AppTab::Snapshots, AppTab::Settings];
+    
+    /// Tab label
+    pub fn title(self) -> &'static str {
+        match self {
+            AppTab::Sync => "Sync",
+            AppTab::Snapshots => "Snapshots",
+            AppTab::Settings => "Settings",
+        }
+    }
+}
+
+/// Widgets that can hold keyboard focus
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FocusTarget {
+    /// Shared -> project diff list
+    SharedToProjectList,
+    /// Project -> shared diff list
+    ProjectToSharedList,
+    /// Side-by-side diff preview
+    Preview,
+    /// Snapshot list of the Snapshots tab
+    SnapshotList,
+    /// Form of the Settings tab
+    SettingsForm,
+    /// First-run setup wizard (modal)
+    SetupWizard,
+    /// Popup dialog stack (modal)
+    Dialog,
+    /// Context menu of the selected entry (modal)
+    EntryMenu,
+    /// Per-file outcomes of the last sync (modal)
+    SyncReport,
+    /// Progress of the running sync (modal)
+    SyncProgress,
+    /// Choice offered when a background operation stops making progress (modal)
+    StallPrompt,
+}
+
+/// Dialogs opened on the popup stack; the tag tells which one produced a result
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Dialog {
+    /// Confirm syncing the selected file
+    SyncSelected,
+    /// Confirm syncing every file in the current list
+    SyncAll,
+    /// Confirm syncing every file in the current list to a new branch of the destination repository
+    SyncToBranch,
+    /// Files of a sync look like secrets; Yes syncs them too, No syncs the other files only
+    SecretFiles,
+    /// Uncommitted changes are in the way of a sync; confirm stashing them and syncing
+    DirtyDestination,
+    /// Another running sync-manager holds the sync lock
+    SyncLocked,
+    /// The sync lock was left by a process that is gone; confirm removing it
+    StaleSyncLock,
+    /// Warning stacked on a sync confirm: destination files are newer than their source
+    NewerDestinations,
+    /// Findings of the health check
+    HealthReport,
+    /// Mappings in effect with the local overrides merged in
+    EffectiveConfig,
+    /// Filter expression prompt of the lists
+    ListFilter,
+    /// Name prompt for saving the filter and sort order as a preset
+    SaveFilterPreset,
+    /// Confirm syncing the accepted entries of a review and reporting the rejected ones
+    FinishReview,
+    /// Note prompt for the selected entry
+    Annotate,
+    /// New text of a destination line (index) edited in the side-by-side view
+    EditLine(usize),
+    /// Path prompt of the pattern tester
+    PatternTest,
+    /// Rules that apply to the tested path
+    PatternTestResult,
+    /// Reasons the selected entry was flagged
+    ChangeReasons,
+    /// Output path prompt for exporting the current list to an archive
+    ExportArchive,
+    /// Name prompt for a new snapshot
+    NewSnapshot,
+    /// Confirm deleting the selected snapshot
+    DeleteSnapshot,
+    /// A failed action that needs a file fixed (configuration errors)
+    Error,
+}
+
+/// Work that needs the terminal: run by the event loop with the TUI suspended
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ExternalAction {
+    /// Open a file in the external editor
+    Edit(PathBuf),
+    /// Run an interactive shell in the workspace root
+    Shell,
+    /// Stop the process (job control) until it is resumed
+    Suspend,
+}
+
+/// Background operation watched for stalls
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WatchedOperation {
+    Sync,
+    Scan,
+}
+
+/// Background operation stuck on a path, waiting for the user to choose what to do
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StallPrompt {
+    pub operation: WatchedOperation,
+    pub path: PathBuf,
+}
+
+/// Answer to a stall prompt
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StallChoice {
+    /// Keep waiting; ask again after another stall period
+    Wait,
+    /// Pass over the stuck path (and everything under it)
+    Skip,
+    /// Stop the operation and stop waiting for it
+    Cancel,
+}
+
+/// Sort order of the diff lists
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffSortOrder {
+    /// Path, A → Z
+    #[default]
+    PathAsc,
+    /// Path, Z → A
+    PathDesc,
+    /// Largest files first
+    LargestFirst,
+    /// Most recently modified first
+    RecentFirst,
+}
+
+impl DiffSortOrder {
+    /// Next order in the cycle
+    pub fn next(self) -> Self {
+        match self {
+            DiffSortOrder::PathAsc => DiffSortOrder::PathDesc,
+            DiffSortOrder::PathDesc => DiffSortOrder::LargestFirst,
+            DiffSortOrder::LargestFirst => DiffSortOrder::RecentFirst,
+            DiffSortOrder::RecentFirst => DiffSortOrder::PathAsc,
+        }
+    }
+    
+    pub fn label(self) -> &'static str {
+        match self {
+            DiffSortOrder::PathAsc => "path A→Z",
+            DiffSortOrder::PathDesc => "path Z→A",
+            DiffSortOrder::LargestFirst => "largest first",
+            DiffSortOrder::RecentFirst => "most recent first",
+        }
+    }
+    
+    /// Sort diff entries in place
+    pub fn sort(self, diffs: &mut [DiffEntry]) {
+        match self {
+            DiffSortOrder::PathAsc => diffs.sort_by(|a, b| a.path.cmp(&b.path)),
+            DiffSortOrder::PathDesc => diffs.sort_by(|a, b| b.path.cmp(&a.path)),
+            DiffSortOrder::LargestFirst => {
+                diffs.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)))
+            }
+            DiffSortOrder::RecentFirst => {
+                diffs.sort_by(|a, b| b.modified.cmp(&a.modified).then_with(|| a.path.cmp(&b.path)))
+            }
+        }
+    }
+}
+
 /// Main application state
 #[derive(Debug)]
 pub struct App {
@@ -31,6 +258,9 @@ pub struct App {
     /// Workspace root path
     pub workspace_root: PathBuf,
     
+    /// Active top-level tab
+    pub active_tab: AppTab,
+    
     /// Current view mode
     pub view_mode: ViewMode,
     
@@ -40,17 +270,55 @@ pub struct App {
     /// Diffs for project -> shared direction
     pub project_to_shared_diffs: Vec<DiffEntry>,
     
-    /// Selected index in shared -> project list
-    pub shared_to_project_index: usize,
+    /// Sort order applied to both diff lists (kept across refreshes)
+    pub sort_order: DiffSortOrder,
+    
+    /// Filter narrowing both diff lists (kept across refreshes)
+    pub list_filter: Option<ListFilter>,
+    
+    /// Filter preset last applied, until the filter or sort order is changed by hand
+    pub active_preset: Option<String>,
+    
+    /// Entries the filter hides, out of the lists until it changes
+    pub filtered_out: DiffLists,
     
-    /// Selected index in project -> shared list
-    pub project_to_shared_index: usize,
+    /// Whether the diff lists are grouped into status sections
+    pub group_by_status: bool,
+    
+    /// Project group the lists show (None: the workspace's own project)
+    pub active_group: Option<String>,
+    
+    /// List view state (selection, scroll) for shared -> project
+    pub shared_to_project_list: ListPanel,
+    
+    /// List view state (selection, scroll) for project -> shared
+    pub project_to_shared_list: ListPanel,
     
     /// Whether to show side-by-side diff view
     pub show_side_by_side: bool,
     
-    /// Whether to fold unchanged regions in diff
-    pub fold_unchanged: bool,
+    /// Per-file fold state (by project and relative path), kept while the app runs
+    pub fold_states: HashMap<(String, PathBuf), FoldState>,
+    
+    /// Files (by project and relative path) compared key by key instead of line by line, kept
+    /// while the app runs
+    pub structural_diffs: HashSet<(String, PathBuf)>,
+    
+    /// Lockfiles (by project and relative path) previewed as their line diff instead of a
+    /// dependency summary
+    pub raw_lockfiles: HashSet<(String, PathBuf)>,
+    
+    /// Regions of the fold indicators on screen, top to bottom (updated by rendering)
+    pub visible_folds: RefCell<Vec<usize>>,
+    
+    /// Aligned indices of the truncated long lines on screen, top to bottom (updated by rendering)
+    pub visible_long_lines: RefCell<Vec<usize>>,
+    
+    /// Alignment and wrapped rows of the side-by-side file pair (updated by rendering)
+    pub side_by_side_cache: RefCell<SideBySideCache>,
+    
+    /// Syntax highlighted side-by-side files, filled in the background (used by rendering)
+    pub highlight_cache: RefCell<HighlightCache>,
     
     /// Current scroll offset in diff view
     pub diff_scroll_offset: usize,
@@ -67,54 +335,264 @@ pub struct App {
     /// Destination lines for side-by-side view
     pub side_by_side_dest: Option<Vec<String>>,
     
+    /// Metadata and thumbnails of both sides when the side-by-side view shows an image
+    pub side_by_side_images: Option<ImagePair>,
+    
+    /// Whether the side-by-side view shows the last commit of each destination line
+    pub show_blame: bool,
+    
+    /// Blame of the destination file in the side-by-side view (loaded while `show_blame`)
+    pub side_by_side_blame: Option<Vec<BlameLine>>,
+    
+    /// Commit whose version of the destination file the side-by-side view shows instead of
+    /// the current file (picked from the history menu)
+    pub history_version: Option<FileCommit>,
+    
+    /// Remembered side-by-side scroll offset per entry
+    pub scroll_positions: HashMap<EntryKey, usize>,
+    
+    /// Whether the info panel shows the metadata of both files of the selected entry
+    pub show_metadata: bool,
+    
+    /// Metadata of the selected entry, keyed by its source path (read while the panel shows it)
+    pub cached_metadata: Option<(PathBuf, EntryMetadata)>,
+    
+    /// Last path tested against the patterns (the prompt starts with it)
+    pub pattern_test_input: String,
+    
+    /// Frame timings, shown in an overlay while profiling (F12)
+    pub frame_stats: FrameStats,
+    
+    /// Scan and sync totals since launch, served by `serve --metrics`
+    pub metrics: Metrics,
+    
+    /// Snapshot list and comparison of the Snapshots tab
+    pub snapshots: SnapshotsTab,
+    
+    /// Settings form of the Settings tab
+    pub settings: SettingsTab,
+    
+    /// First-run setup wizard (active while no project config exists)
+    pub setup_wizard: Option<SetupWizard>,
+    
+    /// Keyboard focus (lists, preview, modal wizard)
+    pub focus: FocusManager<FocusTarget>,
+    
+    /// Transient status notifications (errors stay until dismissed)
+    pub toasts: ToastManager,
+    
+    /// Modal dialogs (sync confirmations, errors); nested dialogs unwind one level per Esc
+    pub popups: PopupManager<Dialog>,
+    
+    /// Context menu of the selected entry, while open
+    pub entry_menu: Option<ContextMenu<EntryAction>>,
+    
+    /// Commits listed by the history menu, in menu order
+    pub file_history: Vec<FileCommit>,
+    
+    /// Outcomes of the last sync, while its report is open
+    pub sync_report: Option<SyncReport>,
+    
+    /// Sync running in the background, until its result is picked up
+    pub sync_task: Option<SyncTask>,
+    
+    /// Sync held back by uncommitted destination changes, with the files in its way
+    pub held_sync: Option<(Vec<DiffEntry>, Vec<DirtyRepo>)>,
+    
+    /// Sync held until the files that look like secrets are confirmed or left out
+    /// (entries, whether it goes to a review branch)
+    pub held_secrets: Option<(Vec<DiffEntry>, bool)>,
+    
+    /// Review under way: verdicts on the entries of one list
+    pub review: Option<Review>,
+    
+    /// Notes attached to entries (saved in the state directory)
+    pub annotations: Annotations,
+    
+    /// Side-by-side positions marked with `m` and a letter, per file (for the session)
+    pub bookmarks: Bookmarks,
+    
+    /// `m` or `'` pressed in the side-by-side view, waiting for the bookmark letter
+    pub bookmark_prompt: Option<BookmarkPrompt>,
+    
+    /// Lock of the workspace, held while a sync runs
+    pub sync_lock: Option<SyncLock>,
+    
+    /// Owner of a stale lock, while asking whether to remove it
+    pub stale_lock: Option<LockOwner>,
+    
+    /// Content hashes of the source files, carried from the last diff cache into the next
+    pub hash_cache: HashCache,
+    
+    /// Fresh scan running in the background while the lists show the cached diff of the last run
+    pub scan_task: Option<ScanTask>,
+    
+    /// Whether the lists show the cached diff of the last run rather than a scan of this one
+    stale: bool,
+    
+    /// Whether the first scan of the launch is still running without a cached diff to show,
+    /// so the startup screen shows its progress in place of the dashboard
+    starting_up: bool,
+    
+    /// Session saved by the last run, kept while the startup scan runs so its selection can
+    /// be restored once the lists are in
+    startup_session: Option<SessionState>,
+    
+    /// Stuck background operation, while its prompt is open
+    pub stall_prompt: Option<StallPrompt>,
+    
+    /// System clipboard for the copy-path actions
+    pub clipboard: Clipboard,
+    
+    /// External program to run before the next frame (taken by the event loop)
+    pub pending_external: Option<ExternalAction>,
+    
     /// Whether the application should quit
     pub should_quit: bool,
 }
 
 impl App {
-    /// Create a new application instance
+    /// Create a new application instance on the detected workspace root
     pub fn new() -> Result<Self> {
-        let workspace_root = Self::detect_workspace_root()?;
+        Self::open(Self::detect_workspace_root()?)
+    }
+    
+    /// Create the application on a workspace root
+    pub fn open(workspace_root: PathBuf) -> Result<Self> {
+        // Load project config from sync-manager.yaml (a broken one is reported once the UI is up)
+        let (project_config, config_error) = match ProjectConfig::load_from_workspace(&workspace_root, PROJECT_CONFIG_NAME) {
+            Ok(config) => (Some(config), None),
+            Err(err) => (None, Some(err)),
+        };
         
-        // Load project config from sync-manager.yaml
-        let project_config = ProjectConfig::load_from_workspace(
-            &workspace_root,
-            PROJECT_CONFIG_NAME,
-        ).ok();
+        // Without a config file, start the setup wizard instead of an empty dashboard
+        let setup_wizard = if workspace_root.join(PROJECT_CONFIG_NAME).exists() {
+            None
+        } else {
+            Some(SetupWizard::new(&workspace_root))
+        };
         
+        let config = Self::config_with_settings(project_config.as_ref());
+        
+        let highlight_cache = HighlightCache::new(&config.ui.syntax_theme);
         let mut app = Self {
-            config: AppConfig::default(),
+            settings: SettingsTab::new(&config),
+            group_by_status: config.ui.group_by_status,
+            config,
             project_config,
+            snapshots: SnapshotsTab::new(&workspace_root),
             workspace_root,
+            active_tab: AppTab::Sync,
             view_mode: ViewMode::SharedToProject,
             shared_to_project_diffs: Vec::new(),
             project_to_shared_diffs: Vec::new(),
-            shared_to_project_index: 0,
-            project_to_shared_index: 0,
+            sort_order: DiffSortOrder::default(),
+            list_filter: None,
+            active_preset: None,
+            filtered_out: DiffLists::default(),
+            active_group: None,
+            shared_to_project_list: ListPanel::new(),
+            project_to_shared_list: ListPanel::new(),
             show_side_by_side: false,
-            fold_unchanged: true,
+            fold_states: HashMap::new(),
+            structural_diffs: HashSet::new(),
+            raw_lockfiles: HashSet::new(),
+            visible_folds: RefCell::new(Vec::new()),
+            visible_long_lines: RefCell::new(Vec::new()),
+            side_by_side_cache: RefCell::new(SideBySideCache::default()),
+            highlight_cache: RefCell::new(highlight_cache),
             diff_scroll_offset: 0,
             cached_diff_content: None,
             cached_diff_path: None,
             side_by_side_source: None,
             side_by_side_dest: None,
+            show_blame: false,
+            side_by_side_images: None,
+            side_by_side_blame: None,
+            history_version: None,
+            scroll_positions: HashMap::new(),
+            show_metadata: false,
+            cached_metadata: None,
+            pattern_test_input: String::new(),
+            frame_stats: FrameStats::default(),
+            metrics: Metrics::default(),
+            setup_wizard,
+            focus: FocusManager::new(vec![
+                FocusTarget::SharedToProjectList,
+                FocusTarget::ProjectToSharedList,
+            ]),
+            toasts: ToastManager::new().with_sticky_errors(true),
+            popups: PopupManager::new(),
+            entry_menu: None,
+            file_history: Vec::new(),
+            sync_report: None,
+            sync_task: None,
+            held_sync: None,
+            held_secrets: None,
+            review: None,
+            annotations: Annotations::default(),
+            bookmarks: Bookmarks::default(),
+            bookmark_prompt: None,
+            sync_lock: None,
+            stale_lock: None,
+            hash_cache: HashCache::default(),
+            scan_task: None,
+            stale: false,
+            starting_up: false,
+            startup_session: None,
+            stall_prompt: None,
+            clipboard: Clipboard::default(),
+            pending_external: None,
             should_quit: false,
         };
         
-        // Load initial diffs if project config is available
+        if app.setup_wizard.is_some() {
+            app.focus.push_modal(FocusTarget::SetupWizard);
+        } else if let Some(err) = config_error {
+            app.report_error(&format!("Loading {} failed", PROJECT_CONFIG_NAME), &err);
+        }
+        
+        // Show the diff of the last run at once and rescan in the background; without one the
+        // startup screen shows the progress of the scan until its lists are in
         if app.project_config.is_some() {
-            app.refresh_diffs()?;
+            match DiffCache::load(&app.workspace_root) {
+                Some(cache) => {
+                    let (lists, hashes) = cache.into_parts();
+                    app.set_diff_lists(lists);
+                    app.hash_cache = hashes;
+                    app.start_background_scan();
+                }
+                None => {
+                    app.start_background_scan();
+                    app.starting_up = app.scan_task.is_some();
+                }
+            }
+        }
+        
+        // Checked once here; the rest of the session works without git
+        if !GitOps::can_query() {
+            app.log("git not found on PATH: file history is off and diffs use the built-in differ");
+        } else if !GitOps::is_available() {
+            app.log("git not found on PATH: diffs use the built-in differ (history uses libgit2)");
         }
         
+        app.annotations = Annotations::load(&app.workspace_root);
+        
+        // Resume the previous session (selection, view mode, scroll positions)
+        let session = SessionState::load(&app.workspace_root);
+        if app.starting_up {
+            app.startup_session = Some(session.clone());
+        }
+        app.restore_session(session);
+        
         Ok(app)
     }
     
     /// Detect the workspace root directory
-    fn detect_workspace_root() -> Result<PathBuf> {
-        // First try environment variable
+    pub fn detect_workspace_root() -> Result<PathBuf> {
+        // First try environment variable (made absolute, so paths don't move with the working directory)
         if let Ok(path) = std::env::var("WORKSPACE_ROOT") {
-            let path = PathBuf::from(path);
-            if path.exists() {
+            if let Ok(path) = std::fs::canonicalize(path) {
                 return Ok(path);
             }
         }
@@ -138,14 +616,150 @@ impl App {
         Ok(cwd)
     }
     
+    /// Project name used in workspace_settings (workspace directory name)
+    pub fn project_name(&self) -> String {
+        Self::project_name_for(&self.workspace_root)
+    }
+    
+    /// Icon and color marking the entries of a project
+    pub fn project_identity(&self, project_name: &str) -> ProjectIdentity {
+        match &self.project_config {
+            Some(config) => config.project_identity(project_name),
+            None => ProjectConfig::default().project_identity(project_name),
+        }
+    }
+    
+    /// Project name of a workspace root
+    fn project_name_for(workspace_root: &Path) -> String {
+        workspace_root
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("sync-manager")
+            .to_string()
+    }
+    
+    /// App config of a workspace, with the settings of its sync-manager.yaml (if it loads)
+    pub fn workspace_config(workspace_root: &Path) -> AppConfig {
+        Self::config_with_settings(ProjectConfig::load_from_workspace(workspace_root, PROJECT_CONFIG_NAME).ok().as_ref())
+    }
+    
+    /// Compiled-in defaults overridden by the settings saved from the Settings tab
+    fn config_with_settings(project_config: Option<&ProjectConfig>) -> AppConfig {
+        let mut config = AppConfig::default();
+        if let Some(project_config) = project_config {
+            config.apply_settings(&project_config.settings);
+        }
+        config
+    }
+    
+    /// Check the config, mapped paths, git and state files of a workspace (`sync-manager doctor`)
+    pub fn check_health(workspace_root: &Path, config: &AppConfig) -> HealthReport {
+        check_workspace(workspace_root, PROJECT_CONFIG_NAME, &Self::project_name_for(workspace_root), config)
+    }
+    
+    /// Run the health check and show the report in a popup
+    pub fn show_health_report(&mut self) {
+        let report = Self::check_health(&self.workspace_root, &self.config);
+        let title = format!("Health Check: {}", report.summary());
+        let popup = match report.worst() {
+            Severity::Ok => Popup::info(title, report.to_string()),
+            Severity::Warning => Popup::warning(title, report.to_string()),
+            Severity::Error => Popup::error(title, report.to_string()),
+        };
+        self.open_dialog(Dialog::HealthReport, popup);
+    }
+    
+    /// Show the mappings of the listed projects as scanned: with their local config merged in
+    pub fn show_effective_config(&mut self) {
+        let Some(config) = &self.project_config else {
+            self.toasts.info(format!("No {} loaded", PROJECT_CONFIG_NAME));
+            return;
+        };
+        let mut text = String::new();
+        for project_name in self.listed_projects() {
+            let root = config.project_root(&self.workspace_root, &project_name);
+            let local = root.join(LOCAL_CONFIG_NAME);
+            let source = if local.is_file() { local.display().to_string() } else { "none".to_string() };
+            text.push_str(&format!("# {} (local overrides: {})\n", project_name, source));
+            let mappings = config
+                .effective_mappings(&root, &project_name)
+                .and_then(|mappings| Ok(serde_yaml::to_string(&mappings)?));
+            match mappings {
+                Ok(yaml) => text.push_str(&yaml),
+                Err(err) => text.push_str(&format!("{:#}\n", err)),
+            }
+        }
+        self.open_dialog(Dialog::EffectiveConfig, Popup::info("Effective Config".into(), text));
+    }
+    
+    /// Ask for a path to test against the patterns of the listed projects' mappings
+    pub fn request_pattern_test(&mut self) {
+        if self.project_config.is_none() {
+            self.toasts.info(format!("No {} loaded", PROJECT_CONFIG_NAME));
+            return;
+        }
+        let prompt = "Path (relative to the mappings, or absolute):".to_string();
+        self.open_dialog(
+            Dialog::PatternTest,
+            Popup::input("Test Patterns".into(), prompt, self.pattern_test_input.clone()),
+        );
+    }
+    
+    /// Show which rules apply to `input` in each mapping
+    fn show_pattern_test(&mut self, input: String) {
+        let Some(scan) = self.diff_scan() else {
+            return;
+        };
+        let tests = pattern_tester::test_path(&scan.config, &scan.projects, &scan.engine, &input);
+        let text = if tests.is_empty() {
+            format!("{} isn't inside any mapping", input.trim())
+        } else {
+            tests.iter().flat_map(|test| test.lines()).collect::<Vec<_>>().join("\n")
+        };
+        self.pattern_test_input = input;
+        self.open_dialog(Dialog::PatternTestResult, Popup::info("Test Patterns".into(), text));
+    }
+    
+    /// Finish the setup wizard: build and write sync-manager.yaml, then load diffs
+    /// Validation errors are kept on the wizard so the user can correct the form
+    pub fn complete_setup(&mut self) -> Result<()> {
+        let Some(wizard) = &self.setup_wizard else {
+            return Ok(());
+        };
+        
+        if self.config.defaults.read_only {
+            if let Some(wizard) = &mut self.setup_wizard {
+                wizard.error = Some(format!("Read-only mode: {} can't be written", PROJECT_CONFIG_NAME));
+            }
+            return Ok(());
+        }
+        
+        let config = match wizard.build_config(&self.project_name()) {
+            Ok(config) => config,
+            Err(err) => {
+                if let Some(wizard) = &mut self.setup_wizard {
+                    wizard.error = Some(err.to_string());
+                }
+                return Ok(());
+            }
+        };
+        
+        config.save(&self.workspace_root.join(PROJECT_CONFIG_NAME))?;
+        self.project_config = Some(config);
+        self.setup_wizard = None;
+        self.focus.remove_modal(FocusTarget::SetupWizard);
+        self.toasts.success(format!("Saved {}", PROJECT_CONFIG_NAME));
+        self.refresh_diffs()
+    }
+    
     /// Get the currently selected diff entry
     pub fn selected_diff(&self) -> Option<&DiffEntry> {
         match self.view_mode {
             ViewMode::SharedToProject => {
-                self.shared_to_project_diffs.get(self.shared_to_project_index)
+                self.shared_to_project_diffs.get(self.shared_to_project_list.selected)
             }
             ViewMode::ProjectToShared => {
-                self.project_to_shared_diffs.get(self.project_to_shared_index)
+                self.project_to_shared_diffs.get(self.project_to_shared_list.selected)
             }
         }
     }
@@ -161,197 +775,2277 @@ impl App {
     /// Get the current selected index
     pub fn current_index(&self) -> usize {
         match self.view_mode {
-            ViewMode::SharedToProject => self.shared_to_project_index,
-            ViewMode::ProjectToShared => self.project_to_shared_index,
+            ViewMode::SharedToProject => self.shared_to_project_list.selected,
+            ViewMode::ProjectToShared => self.project_to_shared_list.selected,
         }
     }
     
     /// Set the current selected index
     pub fn set_current_index(&mut self, index: usize) {
         match self.view_mode {
-            ViewMode::SharedToProject => self.shared_to_project_index = index,
-            ViewMode::ProjectToShared => self.project_to_shared_index = index,
+            ViewMode::SharedToProject => self.shared_to_project_list.selected = index,
+            ViewMode::ProjectToShared => self.project_to_shared_list.selected = index,
         }
     }
     
-    /// Move selection up
+    /// Move selection up (past the rows of collapsed sections)
     pub fn select_previous(&mut self) {
         let index = self.current_index();
-        if index > 0 {
-            self.set_current_index(index - 1);
+        self.current_list_mut().select_previous();
+        if self.current_index() != index {
             self.clear_diff_cache();
         }
     }
     
-    /// Move selection down
+    /// Move selection down (past the rows of collapsed sections)
     pub fn select_next(&mut self) {
         let index = self.current_index();
-        let max = self.current_diffs().len().saturating_sub(1);
-        if index < max {
-            self.set_current_index(index + 1);
+        let count = self.current_diffs().len();
+        self.current_list_mut().select_next(count);
+        if self.current_index() != index {
+            self.clear_diff_cache();
+        }
+    }
+    
+    /// Move selection up by one visible page
+    pub fn select_page_up(&mut self) {
+        let index = self.current_index();
+        let list = self.current_list_mut();
+        list.page_up();
+        if list.selected != index {
+            self.clear_diff_cache();
+        }
+    }
+    
+    /// Move selection down by one visible page
+    pub fn select_page_down(&mut self) {
+        let index = self.current_index();
+        let count = self.current_diffs().len();
+        let list = self.current_list_mut();
+        list.page_down(count);
+        if list.selected != index {
+            self.clear_diff_cache();
+        }
+    }
+    
+    /// Jump to the first entry of the current list
+    pub fn select_first(&mut self) {
+        let index = self.current_index();
+        self.current_list_mut().select_first();
+        if self.current_index() != index {
+            self.clear_diff_cache();
+        }
+    }
+    
+    /// Jump to the last entry of the current list
+    pub fn select_last(&mut self) {
+        let index = self.current_index();
+        let count = self.current_diffs().len();
+        self.current_list_mut().select_last(count);
+        if self.current_index() != index {
+            self.clear_diff_cache();
+        }
+    }
+    
+    /// 1-based position of the selection and the entry count of the current list
+    /// None when the list is empty
+    pub fn list_position(&self) -> Option<(usize, usize)> {
+        let total = self.current_diffs().len();
+        (total > 0).then(|| (self.current_index().min(total - 1) + 1, total))
+    }
+    
+    /// Cycle the diff list sort order, keeping each list's selected file selected
+    pub fn cycle_sort_order(&mut self) {
+        self.active_preset = None;
+        self.set_sort_order(self.sort_order.next());
+    }
+    
+    /// Sort both lists by `order`, keeping each list's selected file selected
+    fn set_sort_order(&mut self, order: DiffSortOrder) {
+        self.sort_order = order;
+        self.filter_lists();
+    }
+        
+    /// Group the lists into status sections, or list them flat again
+    pub fn toggle_grouping(&mut self) {
+        self.group_by_status = !self.group_by_status;
+        self.filter_lists();
+        self.toasts.info(if self.group_by_status {
+            "Grouped by status (z collapses a section)"
+        } else {
+            "Listed without groups"
+        });
+    }
+    
+    /// Collapse or expand the status section of the selected entry
+    pub fn toggle_section(&mut self) {
+        let index = self.current_index();
+        if self.current_list_mut().toggle_section().is_none() {
+            self.toasts.info("The lists aren't grouped (G groups them by status)");
+            return;
+        }
+        if self.current_index() != index {
             self.clear_diff_cache();
         }
     }
     
-    /// Toggle between view modes
-    pub fn toggle_view_mode(&mut self) {
-        self.view_mode = match self.view_mode {
-            ViewMode::SharedToProject => ViewMode::ProjectToShared,
-            ViewMode::ProjectToShared => ViewMode::SharedToProject,
+    /// Ask for the filter expression of the lists (empty clears it)
+    pub fn request_list_filter(&mut self) {
+        let prompt = format!("Filter, e.g. {} (empty shows all):", FILTER_SYNTAX);
+        let current = self.list_filter.as_ref().map(ListFilter::to_string).unwrap_or_default();
+        self.open_dialog(Dialog::ListFilter, Popup::input("Filter Lists".into(), prompt, current));
+    }
+    
+    /// Apply a filter expression to both lists; an invalid one keeps the filter as it was
+    fn set_list_filter(&mut self, expression: &str) {
+        match ListFilter::parse(expression) {
+            Ok(filter) => {
+                self.list_filter = filter;
+                self.active_preset = None;
+                self.filter_lists();
+                let hidden = self.filtered_out.0.len() + self.filtered_out.1.len();
+                match &self.list_filter {
+                    Some(filter) => self.toasts.info(format!("Filter {}: {} entries hidden", filter, hidden)),
+                    None => self.toasts.info("Filter cleared"),
+                }
+            }
+            Err(err) => self.toasts.error(format!("{:#}", err)),
+        }
+    }
+    
+    /// Apply the next filter preset of sync-manager.yaml (after the last, no filter again;
+    /// the sort order stays)
+    pub fn cycle_filter_preset(&mut self) {
+        let Some(config) = &self.project_config else {
+            self.toasts.info(format!("No {} loaded", PROJECT_CONFIG_NAME));
+            return;
+        };
+        if config.filter_presets.is_empty() {
+            self.toasts.info(format!("No filter_presets in {} (P saves the current filter)", PROJECT_CONFIG_NAME));
+            return;
+        }
+        let names: Vec<&String> = config.filter_presets.keys().collect();
+        let next = match self.active_preset.as_ref().and_then(|preset| names.iter().position(|name| *name == preset)) {
+            Some(index) => names.get(index + 1),
+            None => names.first(),
+        };
+        let Some((name, preset)) = next.map(|name| (name.to_string(), config.filter_presets[*name].clone())) else {
+            self.active_preset = None;
+            self.list_filter = None;
+            self.filter_lists();
+            self.toasts.info("Filter presets off");
+            return;
+        };
+        
+        let filter = match ListFilter::parse(&preset.filter) {
+            Ok(filter) => filter,
+            Err(err) => {
+                self.toasts.error(format!("Filter preset {}: {:#}", name, err));
+                return;
+            }
+        };
+        self.list_filter = filter;
+        if let Some(order) = preset.sort {
+            self.sort_order = order;
+        }
+        self.filter_lists();
+        self.toasts.info(format!("Preset {}: {}", name, preset.describe()));
+        self.active_preset = Some(name);
+    }
+    
+    /// Ask for a name to save the current filter and sort order under
+    pub fn request_save_filter_preset(&mut self) {
+        if !self.allow_write("saving filter presets") {
+            return;
+        }
+        let current = self.active_preset.clone().unwrap_or_default();
+        self.open_dialog(
+            Dialog::SaveFilterPreset,
+            Popup::input("Save Filter Preset".into(), "Preset name (an existing one is replaced):".into(), current),
+        );
+    }
+    
+    /// Save the current filter and sort order as preset `name` in sync-manager.yaml
+    fn save_filter_preset(&mut self, name: &str) {
+        let name = name.trim();
+        if name.is_empty() {
+            self.toasts.info("A preset needs a name");
+            return;
+        }
+        let Some(config) = &mut self.project_config else {
+            self.toasts.info(format!("No {} loaded", PROJECT_CONFIG_NAME));
+            return;
+        };
+        let preset = FilterPreset {
+            filter: self.list_filter.as_ref().map(ListFilter::to_string).unwrap_or_default(),
+            sort: Some(self.sort_order),
         };
+        let description = preset.describe();
+        config.filter_presets.insert(name.to_string(), preset);
+        if let Err(err) = config.save(&self.workspace_root.join(PROJECT_CONFIG_NAME)) {
+            self.report_error("Saving the filter preset failed", &err);
+            return;
+        }
+        self.toasts.success(format!("Saved preset {}: {}", name, description));
+        self.active_preset = Some(name.to_string());
+    }
+    
+    /// Filter, sort and group the lists after the user changed how they are shown; the open
+    /// file is closed
+    fn filter_lists(&mut self) {
+        self.refilter_lists();
         self.clear_diff_cache();
     }
     
+    /// Move the entries the filter hides out of the lists, after putting back those it hid
+    /// before, then sort (and group) them; each list keeps its selected file selected while
+    /// it stays listed, and the open file stays open
+    fn refilter_lists(&mut self) {
+        let (hidden_shared_to_project, hidden_project_to_shared) = std::mem::take(&mut self.filtered_out);
+        for (diffs, list, hidden, filtered_out) in [
+            (
+                &mut self.shared_to_project_diffs,
+                &mut self.shared_to_project_list,
+                hidden_shared_to_project,
+                &mut self.filtered_out.0,
+            ),
+            (
+                &mut self.project_to_shared_diffs,
+                &mut self.project_to_shared_list,
+                hidden_project_to_shared,
+                &mut self.filtered_out.1,
+            ),
+        ] {
+            let selected = diffs.get(list.selected).map(|d| d.path.clone());
+            diffs.extend(hidden);
+            if let Some(filter) = &self.list_filter {
+                let (kept, removed) = std::mem::take(diffs).into_iter().partition(|diff| filter.matches(diff));
+                *diffs = kept;
+                *filtered_out = removed;
+            }
+            self.sort_order.sort(diffs);
+            let sections = if self.group_by_status { group_by_status(diffs) } else { Vec::new() };
+            list.selected = selected
+                .and_then(|path| diffs.iter().position(|d| d.path == path))
+                .unwrap_or(0);
+            list.set_sections(sections);
+        }
+    }
+    
+    /// Get the list view state for the current view mode
+    fn current_list_mut(&mut self) -> &mut ListPanel {
+        match self.view_mode {
+            ViewMode::SharedToProject => &mut self.shared_to_project_list,
+            ViewMode::ProjectToShared => &mut self.project_to_shared_list,
+        }
+    }
+    
+    /// React to focus moving to `target` (focusing a list selects its view mode)
+    pub fn focus_changed(&mut self, target: FocusTarget) {
+        let view_mode = match target {
+            FocusTarget::SharedToProjectList => ViewMode::SharedToProject,
+            FocusTarget::ProjectToSharedList => ViewMode::ProjectToShared,
+            FocusTarget::Preview
+            | FocusTarget::SnapshotList
+            | FocusTarget::SettingsForm
+            | FocusTarget::SetupWizard
+            | FocusTarget::Dialog
+            | FocusTarget::EntryMenu
+            | FocusTarget::SyncReport
+            | FocusTarget::SyncProgress
+            | FocusTarget::StallPrompt => return,
+        };
+        
+        if self.view_mode != view_mode {
+            self.view_mode = view_mode;
+            self.clear_diff_cache();
+        }
+    }
+    
+    /// Rebuild the focus ring for the current layout
+    /// The side-by-side view owns focus alone; otherwise the two lists share the ring
+    fn sync_focus_ring(&mut self) {
+        if self.active_tab == AppTab::Snapshots {
+            self.focus.set_ring(vec![FocusTarget::SnapshotList]);
+        } else if self.active_tab == AppTab::Settings {
+            self.focus.set_ring(vec![FocusTarget::SettingsForm]);
+        } else if self.show_side_by_side {
+            self.focus.set_ring(vec![FocusTarget::Preview]);
+        } else {
+            self.focus.set_ring(vec![
+                FocusTarget::SharedToProjectList,
+                FocusTarget::ProjectToSharedList,
+            ]);
+            self.focus.focus(match self.view_mode {
+                ViewMode::SharedToProject => FocusTarget::SharedToProjectList,
+                ViewMode::ProjectToShared => FocusTarget::ProjectToSharedList,
+            });
+        }
+    }
+    
+    /// Close the side-by-side view and return focus to the list
+    pub fn close_side_by_side(&mut self) {
+        self.remember_scroll_position();
+        self.show_side_by_side = false;
+        self.side_by_side_source = None;
+        self.side_by_side_dest = None;
+        self.side_by_side_images = None;
+        self.side_by_side_blame = None;
+        self.history_version = None;
+        self.bookmark_prompt = None;
+        self.sync_focus_ring();
+    }
+    
     /// Toggle side-by-side view
     pub fn toggle_side_by_side(&mut self) {
+        self.remember_scroll_position();
         self.show_side_by_side = !self.show_side_by_side;
+        self.history_version = None;
         
         if self.show_side_by_side {
-            // Load source and destination files - clone paths to avoid borrow issues
-            let paths = self.selected_diff().map(|diff| {
-                (diff.source_path.clone(), diff.destination_path.clone())
-            });
-            
-            if let Some((source_path, dest_path)) = paths {
-                self.side_by_side_source = std::fs::read_to_string(&source_path)
-                    .ok()
-                    .map(|s| s.lines().map(|l| l.to_string()).collect());
-                self.side_by_side_dest = std::fs::read_to_string(&dest_path)
-                    .ok()
-                    .map(|s| s.lines().map(|l| l.to_string()).collect());
+            // Load source and destination as compared (tokens substituted in the shared copy)
+            if let Some((source, dest)) = self.selected_diff().map(|diff| self.side_by_side_lines(diff)) {
+                self.side_by_side_source = source;
+                self.side_by_side_dest = dest;
             }
+            self.side_by_side_images = self.selected_diff().and_then(ImagePair::load);
+            self.load_blame();
         } else {
             self.side_by_side_source = None;
             self.side_by_side_dest = None;
+            self.side_by_side_images = None;
+            self.side_by_side_blame = None;
         }
         
-        self.diff_scroll_offset = 0;
+        // Resume at the last position viewed for this file
+        self.diff_scroll_offset = if self.show_side_by_side {
+            self.selected_diff()
+                .and_then(|diff| self.scroll_positions.get(&EntryKey::of(diff)))
+                .copied()
+                .unwrap_or(0)
+        } else {
+            0
+        };
+        
+        self.sync_focus_ring();
     }
     
-    /// Toggle folding of unchanged regions
+    /// Fold state of the selected file (`ui.fold_unchanged` if it has none yet)
+    pub fn current_folds(&self) -> FoldState {
+        self.selected_diff()
+            .and_then(|diff| self.fold_states.get(&entry_key(diff)))
+            .cloned()
+            .unwrap_or_else(|| FoldState::new(self.config.ui.fold_unchanged))
+    }
+    
+    /// Fold state of the selected file, created from the default on first change
+    fn current_folds_mut(&mut self) -> Option<&mut FoldState> {
+        let key = entry_key(self.selected_diff()?);
+        let fold_unchanged = self.config.ui.fold_unchanged;
+        Some(self.fold_states.entry(key).or_insert_with(|| FoldState::new(fold_unchanged)))
+    }
+    
+    /// Toggle folding of unchanged regions for the selected file
     pub fn toggle_fold(&mut self) {
         if self.show_side_by_side {
-            self.fold_unchanged = !self.fold_unchanged;
+            if let Some(folds) = self.current_folds_mut() {
+                folds.toggle_all();
+            }
             self.diff_scroll_offset = 0;
         }
     }
     
-    /// Clear the diff cache
-    pub fn clear_diff_cache(&mut self) {
-        self.cached_diff_content = None;
-        self.cached_diff_path = None;
-        self.show_side_by_side = false;
-        self.side_by_side_source = None;
-        self.side_by_side_dest = None;
-        self.diff_scroll_offset = 0;
+    /// Show one more (or, with `more == false`, one fewer) context line around changes
+    /// in folded regions; adjusts `config.ui.context_lines` for the rest of the run
+    pub fn adjust_context_lines(&mut self, more: bool) {
+        if self.show_side_by_side {
+            let lines = &mut self.config.ui.context_lines;
+            *lines = if more { *lines + 1 } else { lines.saturating_sub(1) };
+        }
     }
     
-    /// Scroll diff view up
-    pub fn scroll_up(&mut self, amount: usize) {
-        self.diff_scroll_offset = self.diff_scroll_offset.saturating_sub(amount);
+    /// Expand the topmost folded region on screen, else show the topmost truncated line whole;
+    /// returns false if neither is visible
+    pub fn expand_visible_fold(&mut self) -> bool {
+        let region = self.visible_folds.borrow().first().copied();
+        let long_line = self.visible_long_lines.borrow().first().copied();
+        match (region, long_line) {
+            (Some(region), _) => self.current_folds_mut().is_some_and(|folds| folds.expand(region)),
+            (None, Some(index)) => self.current_folds_mut().is_some_and(|folds| folds.expand_line(index)),
+            (None, None) => false,
+        }
     }
     
-    /// Scroll diff view down
-    pub fn scroll_down(&mut self, amount: usize) {
-        self.diff_scroll_offset += amount;
+    /// Source and destination lines of the side-by-side view: the text as compared (tokens
+    /// substituted in the shared copy), or the key paths and values of a file compared by keys
+    fn side_by_side_lines(&self, diff: &DiffEntry) -> (Option<Vec<String>>, Option<Vec<String>>) {
+        if self.compares_keys(diff) {
+            if let Ok((source, dest)) = entry_leaves(diff) {
+                return (
+                    diff.source_path.is_file().then(|| leaf_lines(&source)),
+                    diff.destination_path.is_file().then(|| leaf_lines(&dest)),
+                );
+            }
+        }
+        let (source, dest) = diff.comparison_text();
+        let to_lines = |s: String| s.lines().map(|l| l.to_string()).collect();
+        (source.map(to_lines), dest.map(to_lines))
+    }
+    
+    /// Whether an entry is compared key by key
+    pub fn compares_keys(&self, diff: &DiffEntry) -> bool {
+        self.structural_diffs.contains(&entry_key(diff))
+    }
+    
+    /// Compare the selected JSON / YAML file key by key (ignoring key order and formatting),
+    /// or line by line again
+    pub fn toggle_structural_diff(&mut self) {
+        let Some(diff) = self.selected_diff().cloned() else {
+            return;
+        };
+        if self.structural_diffs.remove(&entry_key(&diff)) {
+            self.toasts.info("Comparing line by line");
+        } else if !is_structured(&diff.path) {
+            self.toasts.info("Key-level comparison is for JSON and YAML files");
+            return;
+        } else if let Err(err) = entry_leaves(&diff) {
+            self.report_error("Key-level comparison failed", &err);
+            return;
+        } else {
+            self.structural_diffs.insert(entry_key(&diff));
+            self.toasts.info("Comparing keys (order and formatting ignored)");
+        }
+        
+        self.cached_diff_content = self.diff_content(&diff);
+        self.diff_scroll_offset = 0;
+        if self.show_side_by_side && self.history_version.is_none() {
+            (self.side_by_side_source, self.side_by_side_dest) = self.side_by_side_lines(&diff);
+        }
+    }
+    
+    /// Whether the diff view shows the dependency summary of a lockfile rather than its lines
+    pub fn summarizes_lockfile(&self, diff: &DiffEntry) -> bool {
+        LockfileKind::of(&diff.path).is_some()
+            && !self.raw_lockfiles.contains(&entry_key(diff))
+            && !self.compares_keys(diff)
+    }
+    
+    /// Show the line diff of the selected lockfile in the diff view, or its dependency summary again
+    pub fn toggle_lockfile_summary(&mut self) {
+        let Some(diff) = self.selected_diff().cloned() else {
+            return;
+        };
+        if LockfileKind::of(&diff.path).is_none() {
+            self.toasts.info("Dependency summaries are for Cargo.lock and package-lock.json");
+            return;
+        }
+        let key = entry_key(&diff);
+        if !self.raw_lockfiles.remove(&key) {
+            self.raw_lockfiles.insert(key);
+        }
+        self.cached_diff_content = self.diff_content(&diff);
+        self.diff_scroll_offset = 0;
+    }
+    
+    /// Diff view content of an entry: its key-level diff when compared by keys, the dependency
+    /// summary of a lockfile, the differing members of an archive when archive diffs are on,
+    /// else the unified diff
+    pub fn diff_content(&self, diff: &DiffEntry) -> Option<String> {
+        if self.compares_keys(diff) {
+            if let Ok(content) = structural_diff_content(diff) {
+                return Some(content);
+            }
+        }
+        if self.summarizes_lockfile(diff) {
+            if let Some(content) = lockfile_summary(diff) {
+                return Some(content);
+            }
+        }
+        self.config
+            .ui
+            .archive_diffs
+            .then(|| archive_diff_content(diff))
+            .flatten()
+            .or_else(|| crate::operations::DiffEngine::load_diff_content(diff))
+    }
+    
+    /// Clear the diff cache
+    pub fn clear_diff_cache(&mut self) {
+        self.remember_scroll_position();
+        self.cached_diff_content = None;
+        self.cached_diff_path = None;
+        self.cached_metadata = None;
+        self.show_side_by_side = false;
+        self.side_by_side_source = None;
+        self.side_by_side_dest = None;
+        self.side_by_side_images = None;
+        self.side_by_side_blame = None;
+        self.history_version = None;
+        self.diff_scroll_offset = 0;
+        self.sync_focus_ring();
+    }
+    
+    /// Show or hide the metadata of the selected entry in the info panel
+    pub fn toggle_metadata(&mut self) {
+        self.show_metadata = !self.show_metadata;
+        self.cached_metadata = None;
+    }
+    
+    /// Show or hide who last changed each destination line, to judge whether syncing would
+    /// overwrite deliberate local edits
+    pub fn toggle_blame(&mut self) {
+        if !self.show_side_by_side {
+            return;
+        }
+        if self.history_version.is_some() {
+            self.toasts.info("Blame is for the current file; Esc returns to it");
+            return;
+        }
+        if !self.show_blame && !GitOps::can_query() {
+            self.toasts.info("Blame needs git, which wasn't found on PATH");
+            return;
+        }
+        self.show_blame = !self.show_blame;
+        self.load_blame();
+    }
+    
+    /// Load the blame of the open destination file while blame is shown; turned off again
+    /// if git can't give one (e.g. the file isn't tracked)
+    fn load_blame(&mut self) {
+        self.side_by_side_blame = None;
+        if !self.show_blame {
+            return;
+        }
+        let Some(diff) = self.selected_diff() else {
+            return;
+        };
+        if !diff.destination_path.is_file() {
+            return;
+        }
+        match GitOps::blame(&diff.destination_path) {
+            Ok(blame) => self.side_by_side_blame = Some(blame),
+            Err(err) => {
+                self.show_blame = false;
+                self.report_error("Blame failed", &err);
+            }
+        }
+    }
+    
+    /// Scroll diff view up
+    pub fn scroll_up(&mut self, amount: usize) {
+        self.diff_scroll_offset = self.diff_scroll_offset.saturating_sub(amount);
+    }
+    
+    /// Scroll diff view down
+    pub fn scroll_down(&mut self, amount: usize) {
+        self.diff_scroll_offset += amount;
     }
     
     /// Refresh diff lists
     pub fn refresh_diffs(&mut self) -> Result<()> {
-        let project_config = match &self.project_config {
-            Some(config) => config,
-            None => return Ok(()), // No config, nothing to do
+        // The lists are current now, so a background scan would only bring them back
+        self.stop_background_scan();
+        let Some(mut scan) = self.diff_scan() else {
+            return Ok(()); // No config, nothing to do
+        };
+        let watchdog = Arc::new(Watchdog::default());
+        scan.engine = scan.engine.with_watchdog(Arc::clone(&watchdog));
+        let started = Instant::now();
+        let lists = scan.run();
+        self.record_scan_time(started.elapsed());
+        self.metrics.record_scan(watchdog.walked());
+        self.set_diff_lists(lists);
+        self.stale = false;
+        Ok(())
+    }
+    
+    /// Owned copy of everything a diff scan of the listed projects needs (None without a project config)
+    fn diff_scan(&self) -> Option<DiffScan> {
+        let config = self.project_config.clone()?;
+        let projects = self
+            .listed_projects()
+            .into_iter()
+            .map(|name| {
+                let root = config.project_root(&self.workspace_root, &name);
+                (name, root)
+            })
+            .collect();
+        Some(DiffScan {
+            config,
+            projects,
+            engine: self.diff_engine(),
+            sort_order: self.sort_order,
+        })
+    }
+    
+    /// Projects the lists show: the members of the active group, else the workspace's own project
+    pub fn listed_projects(&self) -> Vec<String> {
+        let group = self
+            .active_group
+            .as_ref()
+            .and_then(|group| self.project_config.as_ref()?.project_groups.get(group));
+        match group {
+            Some(projects) => projects.clone(),
+            None => vec![self.project_name()],
+        }
+    }
+    
+    /// Show the next project group in the lists (after the last, the workspace's own project again)
+    /// The lists empty and fill from a background scan of the group
+    pub fn cycle_project_group(&mut self) {
+        let Some(config) = &self.project_config else {
+            self.toasts.info(format!("No {} loaded", PROJECT_CONFIG_NAME));
+            return;
+        };
+        if config.project_groups.is_empty() {
+            self.toasts.info(format!("No project_groups in {}", PROJECT_CONFIG_NAME));
+            return;
+        }
+        let groups: Vec<&String> = config.project_groups.keys().collect();
+        let next = match self.active_group.as_ref().and_then(|group| groups.iter().position(|g| *g == group)) {
+            Some(index) => groups.get(index + 1),
+            None => groups.first(),
+        };
+        self.active_group = next.map(|group| group.to_string());
+        
+        let message = match &self.active_group {
+            Some(group) => format!("Group {}: {}", group, self.listed_projects().join(", ")),
+            None => format!("Project {}", self.project_name()),
+        };
+        self.toasts.info(message);
+        self.set_diff_lists((Vec::new(), Vec::new()));
+        self.start_background_scan();
+    }
+    
+    /// Replace both diff lists
+    fn set_diff_lists(&mut self, (shared_to_project_diffs, project_to_shared_diffs): DiffLists) {
+        self.shared_to_project_diffs = shared_to_project_diffs;
+        self.project_to_shared_diffs = project_to_shared_diffs;
+        self.filtered_out = DiffLists::default();
+        self.refilter_lists();
+        
+        // Reset indices if they're out of bounds
+        if self.shared_to_project_list.selected >= self.shared_to_project_diffs.len() {
+            self.shared_to_project_list.selected = 0;
+        }
+        if self.project_to_shared_list.selected >= self.project_to_shared_diffs.len() {
+            self.project_to_shared_list.selected = 0;
+        }
+        
+        // Clear cached diff since lists have changed
+        self.clear_diff_cache();
+    }
+    
+    /// Whether the lists still show the cached diff of the last run
+    pub fn diffs_stale(&self) -> bool {
+        self.stale
+    }
+    
+    /// Whether the startup screen shows in place of the dashboard: the first scan of the
+    /// launch is running and there are no lists to show yet
+    pub fn is_starting_up(&self) -> bool {
+        self.starting_up && self.scan_task.is_some()
+    }
+    
+    /// Mark the lists stale and rescan in the background (replacing a scan already running)
+    fn start_background_scan(&mut self) {
+        self.stop_background_scan();
+        self.scan_task = self.diff_scan().map(ScanTask::start);
+        self.stale = true;
+    }
+    
+    /// Cancel the background scan, if any, and stop waiting for it
+    /// The scan thread ends on its own once a stuck file system call returns
+    fn stop_background_scan(&mut self) {
+        self.starting_up = false;
+        self.startup_session = None;
+        if let Some(task) = self.scan_task.take() {
+            task.cancel.cancel();
+        }
+        self.close_stall_prompt(WatchedOperation::Scan);
+    }
+    
+    /// Rescan in the background; the lists stay usable for review and Esc cancels
+    pub fn rescan(&mut self) {
+        if self.project_config.is_none() {
+            self.toasts.info(format!("No {} loaded", PROJECT_CONFIG_NAME));
+            return;
+        }
+        self.start_background_scan();
+    }
+    
+    /// Cancel the background scan; the lists keep showing the last complete scan (empty if it
+    /// was the first of the launch)
+    pub fn cancel_scan(&mut self) {
+        if self.scan_task.is_none() {
+            return;
+        }
+        // The first scan of a launch without a cached diff has no lists to fall back on
+        let starting_up = self.is_starting_up();
+        self.stop_background_scan();
+        if starting_up {
+            self.log("startup scan cancelled; the lists stay empty until a rescan");
+            self.toasts.info("Scan cancelled; the lists stay empty until a rescan (r)");
+            return;
+        }
+        self.log("scan cancelled; keeping the lists of the last complete scan");
+        self.toasts.info("Scan cancelled; the lists still show the last complete scan (r rescans)");
+    }
+    
+    /// Show a failed action: configuration errors in a popup, as they need a file fixed,
+    /// others as an error toast; both end with the usual remedy for the kind of error
+    pub fn report_error(&mut self, action: &str, err: &anyhow::Error) {
+        let error = SyncManagerError::from(err);
+        match error {
+            SyncManagerError::Config { .. } => {
+                let text = format!("{}:\n{}", action, error.user_message());
+                self.open_dialog(Dialog::Error, Popup::info(error.title().to_string(), text));
+            }
+            _ => self.toasts.error(format!("{}: {}", action, error.user_message().replace('\n', " "))),
+        }
+    }
+    
+    /// Append an entry to the operation log in the state directory
+    pub fn log(&self, message: &str) {
+        append_log(&log_path(&self.workspace_root), message);
+    }
+    
+    /// Show or hide the frame timing overlay; while shown, timings are also logged
+    pub fn toggle_profiler(&mut self) {
+        self.frame_stats.enabled = !self.frame_stats.enabled;
+        let state = if self.frame_stats.enabled { "on" } else { "off" };
+        self.log(&format!("profile: overlay {}", state));
+    }
+    
+    /// Remember how long a scan took (logged while profiling)
+    fn record_scan_time(&mut self, elapsed: Duration) {
+        self.frame_stats.last_scan = Some(elapsed);
+        if self.frame_stats.enabled {
+            self.log(&format!("profile: scan took {}", format_duration(elapsed)));
+        }
+    }
+    
+    /// Swap in the lists of a finished background scan, keeping the selections by path
+    pub fn poll_scan(&mut self) {
+        if !self.scan_task.as_ref().is_some_and(ScanTask::is_finished) {
+            return;
+        }
+        let Some(task) = self.scan_task.take() else {
+            return;
+        };
+        self.close_stall_prompt(WatchedOperation::Scan);
+        self.record_scan_time(task.started.elapsed());
+        self.metrics.record_scan(task.watchdog.walked());
+        self.stale = false;
+        self.starting_up = false;
+        self.cached_metadata = None;
+        // The first lists of a launch get the selection of the last run
+        let session = self.startup_session.take().unwrap_or_else(|| self.session_state());
+        let selected = self.selected_diff().map(|d| d.path.clone());
+        
+        (self.shared_to_project_diffs, self.project_to_shared_diffs) = task.finish();
+        self.filtered_out = DiffLists::default();
+        self.refilter_lists();
+        self.shared_to_project_list.selected = 0;
+        self.project_to_shared_list.selected = 0;
+        self.restore_selection(&session);
+        
+        // The open file stays open if it's still listed, reloaded with the transforms of the fresh scan
+        let Some(diff) = self.selected_diff().filter(|d| Some(&d.path) == selected.as_ref()).cloned() else {
+            self.clear_diff_cache();
+            return;
+        };
+        self.cached_diff_content = self.diff_content(&diff);
+        if self.show_side_by_side {
+            let (source, dest) = self.side_by_side_lines(&diff);
+            self.side_by_side_source = source;
+            self.side_by_side_images = ImagePair::load(&diff);
+            // A past version picked from the history stays as it was
+            if self.history_version.is_none() {
+                self.side_by_side_dest = dest;
+                self.load_blame();
+            }
+        }
+    }
+    
+    /// Persist both diff lists so the next launch can show them before its scan finishes
+    pub fn save_diff_cache(&mut self) -> Result<()> {
+        // The cache holds the workspace's own project; group lists would replace it at the next launch
+        if self.project_config.is_none() || self.diffs_stale() || self.active_group.is_some() {
+            return Ok(());
+        }
+        let hashes = std::mem::take(&mut self.hash_cache);
+        // Entries hidden by the filter are cached too; the filter doesn't outlive the run
+        let all = |diffs: &[DiffEntry], hidden: &[DiffEntry]| -> Vec<DiffEntry> { diffs.iter().chain(hidden).cloned().collect() };
+        let shared_to_project = all(&self.shared_to_project_diffs, &self.filtered_out.0);
+        let project_to_shared = all(&self.project_to_shared_diffs, &self.filtered_out.1);
+        DiffCache::from_lists(&shared_to_project, &project_to_shared, hashes).save(&self.workspace_root)
+    }
+    
+    /// Wait for the letter of a bookmark to set or jump to (listing those of the selected file)
+    pub fn start_bookmark(&mut self, prompt: BookmarkPrompt) {
+        let Some(diff) = self.selected_diff() else {
+            return;
+        };
+        let letters: String = self.bookmarks.letters(diff).into_iter().map(|letter| format!(" {}", letter)).collect();
+        match prompt {
+            BookmarkPrompt::Set => self.toasts.info("Bookmark: press a letter (m: entry menu)"),
+            BookmarkPrompt::Jump if letters.is_empty() => {
+                self.toasts.info("No bookmarks in this file (m then a letter sets one)");
+                return;
+            }
+            BookmarkPrompt::Jump => self.toasts.info(format!("Jump to bookmark:{}", letters)),
+        }
+        self.bookmark_prompt = Some(prompt);
+    }
+    
+    /// Mark the side-by-side position of the selected file as `letter`, or scroll back to it
+    pub fn finish_bookmark(&mut self, prompt: BookmarkPrompt, letter: char) {
+        let Some(diff) = self.selected_diff().cloned() else {
+            return;
+        };
+        match prompt {
+            BookmarkPrompt::Set => {
+                self.bookmarks.set(&diff, letter, self.diff_scroll_offset);
+                self.toasts.info(format!("Bookmark '{}' set", letter));
+            }
+            BookmarkPrompt::Jump => match self.bookmarks.get(&diff, letter) {
+                Some(row) => self.diff_scroll_offset = row,
+                None => self.toasts.info(format!("No bookmark '{}' in this file", letter)),
+            },
+        }
+    }
+    
+    /// Record the side-by-side scroll offset of the selected file
+    pub fn remember_scroll_position(&mut self) {
+        if !self.show_side_by_side || self.history_version.is_some() {
+            return;
+        }
+        
+        let offset = self.diff_scroll_offset;
+        if let Some(key) = self.selected_diff().map(EntryKey::of) {
+            if offset == 0 {
+                self.scroll_positions.remove(&key);
+            } else {
+                self.scroll_positions.insert(key, offset);
+            }
+        }
+    }
+    
+    /// Capture the UI state worth persisting between runs
+    pub fn session_state(&self) -> SessionState {
+        SessionState {
+            active_tab: match self.active_tab {
+                AppTab::Sync => SessionTab::Sync,
+                AppTab::Snapshots => SessionTab::Snapshots,
+                AppTab::Settings => SessionTab::Settings,
+            },
+            view_mode: match self.view_mode {
+                ViewMode::SharedToProject => SessionViewMode::SharedToProject,
+                ViewMode::ProjectToShared => SessionViewMode::ProjectToShared,
+            },
+            shared_to_project_selected: self
+                .shared_to_project_diffs
+                .get(self.shared_to_project_list.selected)
+                .map(EntryKey::of),
+            project_to_shared_selected: self
+                .project_to_shared_diffs
+                .get(self.project_to_shared_list.selected)
+                .map(EntryKey::of),
+            sort_order: Some(self.sort_order),
+            list_filter: self.list_filter.as_ref().map(ListFilter::to_string),
+            scroll_positions: self
+                .scroll_positions
+                .iter()
+                .map(|(entry, offset)| ScrollPosition { entry: entry.clone(), offset: *offset })
+                .collect(),
+        }
+    }
+    
+    /// Apply a previously saved session
+    /// Selections are matched by entry so they survive changes to the diff lists
+    pub fn restore_session(&mut self, session: SessionState) {
+        self.view_mode = match session.view_mode {
+            SessionViewMode::SharedToProject => ViewMode::SharedToProject,
+            SessionViewMode::ProjectToShared => ViewMode::ProjectToShared,
+        };
+        // A filter that no longer parses (e.g. after an upgrade) is dropped
+        if let Some(filter) = session.list_filter.as_deref().and_then(|expression| ListFilter::parse(expression).ok()) {
+            self.list_filter = filter;
+        }
+        if let Some(order) = session.sort_order {
+            self.sort_order = order;
+        }
+        self.filter_lists();
+        
+        self.restore_selection(&session);
+        
+        self.scroll_positions = session.scroll_offsets();
+        self.clear_diff_cache();
+        
+        self.show_tab(match session.active_tab {
+            SessionTab::Sync => AppTab::Sync,
+            SessionTab::Snapshots => AppTab::Snapshots,
+            SessionTab::Settings => AppTab::Settings,
+        });
+    }
+    
+    /// Select the entries a session had selected
+    fn restore_selection(&mut self, session: &SessionState) {
+        if let Some(index) = session.shared_to_project_selected.as_ref().and_then(|key| {
+            self.shared_to_project_diffs.iter().position(|d| key.matches(d))
+        }) {
+            self.shared_to_project_list.selected = index;
+        }
+        if let Some(index) = session.project_to_shared_selected.as_ref().and_then(|key| {
+            self.project_to_shared_diffs.iter().position(|d| key.matches(d))
+        }) {
+            self.project_to_shared_list.selected = index;
+        }
+    }
+    
+    /// Persist the current session to the workspace state directory
+    pub fn save_session(&mut self) -> Result<()> {
+        self.remember_scroll_position();
+        self.session_state().save(&self.workspace_root)
+    }
+    
+    /// Open a dialog on top of the popup stack; the stack owns keyboard focus while open
+    pub fn open_dialog(&mut self, dialog: Dialog, popup: Popup) {
+        if !self.popups.is_open() {
+            self.focus.push_modal(FocusTarget::Dialog);
+        }
+        self.popups.push(dialog, popup);
+    }
+    
+    /// Act on a key result from the popup stack
+    pub fn handle_dialog_result(&mut self, result: PopupResult<Dialog>) {
+        match result {
+            PopupResult::Confirmed(Dialog::SyncSelected, true) => {
+                if let Some(diff) = self.selected_diff().cloned() {
+                    self.sync_entries(vec![diff]);
+                }
+            }
+            PopupResult::Confirmed(Dialog::SyncAll, true) => {
+                self.sync_entries(self.current_diffs().to_vec());
+            }
+            PopupResult::Confirmed(Dialog::FinishReview, true) => self.finish_review(),
+            PopupResult::Confirmed(Dialog::SyncToBranch, true) => {
+                self.sync_to_branch(self.current_diffs().to_vec());
+            }
+            PopupResult::Confirmed(Dialog::StaleSyncLock, confirmed) => {
+                if let Some(owner) = self.stale_lock.take().filter(|_| confirmed) {
+                    self.remove_stale_lock(&owner);
+                }
+            }
+            PopupResult::Confirmed(Dialog::SecretFiles, include) => {
+                if let Some((entries, to_branch)) = self.held_secrets.take() {
+                    self.release_secrets(entries, to_branch, include);
+                }
+            }
+            PopupResult::Confirmed(Dialog::DirtyDestination, confirmed) => {
+                if let Some((entries, dirty)) = self.held_sync.take().filter(|_| confirmed) {
+                    self.stash_and_sync(entries, &dirty);
+                }
+            }
+            PopupResult::Submitted(Dialog::ExportArchive, path) => self.export_archive(&path),
+            PopupResult::Submitted(Dialog::ListFilter, expression) => self.set_list_filter(&expression),
+            PopupResult::Submitted(Dialog::SaveFilterPreset, name) => self.save_filter_preset(&name),
+            PopupResult::Submitted(Dialog::Annotate, note) => self.annotate_selected(&note),
+            PopupResult::Submitted(Dialog::EditLine(index), line) => self.save_line_edit(index, &line),
+            PopupResult::Submitted(Dialog::PatternTest, path) => self.show_pattern_test(path),
+            PopupResult::Submitted(Dialog::NewSnapshot, name) => self.create_snapshot(&name),
+            PopupResult::Confirmed(Dialog::DeleteSnapshot, true) => self.delete_snapshot(),
+            _ => {}
+        }
+        
+        if !self.popups.is_open() {
+            self.focus.remove_modal(FocusTarget::Dialog);
+        }
+    }
+    
+    /// Whether actions that write files are allowed; in read-only mode toasts that `action` is disabled
+    fn allow_write(&mut self, action: &str) -> bool {
+        if self.config.defaults.read_only {
+            self.toasts.info(format!("Read-only mode: {} is disabled", action));
+        }
+        !self.config.defaults.read_only
+    }
+    
+    /// Whether the lists are fresh enough to sync from; toasts while they still show the cached diff
+    fn allow_sync_from_lists(&mut self) -> bool {
+        if self.diffs_stale() {
+            self.toasts.info("Still scanning: syncing waits for the fresh diff");
+        }
+        !self.diffs_stale()
+    }
+    
+    /// Ask to sync the selected file
+    pub fn request_sync_selected(&mut self) {
+        if !self.allow_write("syncing") || !self.allow_sync_from_lists() {
+            return;
+        }
+        let Some(diff) = self.selected_diff() else {
+            self.toasts.info("No file selected");
+            return;
+        };
+        
+        if !self.config.defaults.confirm_sync {
+            let diff = diff.clone();
+            self.sync_entries(vec![diff]);
+            return;
+        }
+        
+        let message = format!("{} {}?", sync_verb(diff), diff.path.display());
+        let newer = count_newer_destinations(std::slice::from_ref(diff));
+        self.open_dialog(Dialog::SyncSelected, Popup::confirm("Sync File".into(), message));
+        self.warn_newer_destinations(newer);
+    }
+    
+    /// Ask to sync every file in the current list
+    pub fn request_sync_all(&mut self) {
+        if !self.allow_write("syncing") || !self.allow_sync_from_lists() {
+            return;
+        }
+        let diffs = self.current_diffs();
+        if diffs.is_empty() {
+            self.toasts.info("Nothing to sync");
+            return;
+        }
+        
+        if !self.config.defaults.confirm_sync {
+            self.sync_entries(diffs.to_vec());
+            return;
+        }
+        
+        let direction = self.direction_label();
+        let message = match &self.active_group {
+            Some(group) => format!("Sync {} files {} in the projects of {}?", diffs.len(), direction, group),
+            None => format!("Sync {} files {}?", diffs.len(), direction),
+        };
+        let newer = count_newer_destinations(diffs);
+        self.open_dialog(Dialog::SyncAll, Popup::confirm("Sync All".into(), message));
+        self.warn_newer_destinations(newer);
+    }
+    
+    /// Sync the shared → project list without asking, for a request to the control socket
+    /// Files that look like secrets or are pinned stay listed; returns the number of files synced
+    pub fn sync_unattended(&mut self) -> Result<usize> {
+        if self.config.defaults.read_only {
+            anyhow::bail!("Read-only mode: syncing is disabled");
+        }
+        if self.diffs_stale() {
+            anyhow::bail!("Still scanning: syncing waits for the fresh diff");
+        }
+        if self.sync_task.is_some() {
+            anyhow::bail!("A sync is already running");
+        }
+        let entries: Vec<DiffEntry> = self
+            .shared_to_project_diffs
+            .iter()
+            .filter(|entry| entry.secret.is_none() && !entry.is_blocked())
+            .cloned()
+            .collect();
+        let count = entries.len();
+        let check = &self.config.defaults.clean_destination;
+        if count > 0 && check != "none" && GitOps::is_available() {
+            let dirty = dirty_guard::dirty_destinations(&entries, check)?;
+            if let Some(repo) = dirty.first() {
+                anyhow::bail!("{} has uncommitted files in the way of the sync", repo.repo.display());
+            }
+        }
+        if count > 0 {
+            self.start_sync(entries);
+            if self.sync_task.is_none() {
+                anyhow::bail!("Another sync-manager holds {}", SyncLock::path_for(&self.workspace_root).display());
+            }
+        }
+        Ok(count)
+    }
+    
+    /// Direction of the current list, as "shared → project"
+    fn direction_label(&self) -> &'static str {
+        match self.view_mode {
+            ViewMode::SharedToProject => "shared → project",
+            ViewMode::ProjectToShared => "project → shared",
+        }
+    }
+    
+    /// Comparison shown by the current list
+    fn current_diff_type(&self) -> DiffType {
+        match self.view_mode {
+            ViewMode::SharedToProject => DiffType::SharedToProject,
+            ViewMode::ProjectToShared => DiffType::ProjectToShared,
+        }
+    }
+    
+    /// Whether the review under way is of the current list; toasts when it's of the other one
+    fn reviewing_current_list(&mut self) -> bool {
+        let current = self.review.as_ref().is_some_and(|review| review.diff_type == self.current_diff_type());
+        if !current {
+            self.toasts.info("The review is of the other list (Tab switches lists)");
+        }
+        current
+    }
+    
+    /// Start reviewing the current list, or ask to finish the review under way
+    pub fn toggle_review(&mut self) {
+        if self.review.is_some() {
+            if self.reviewing_current_list() {
+                self.request_finish_review();
+            }
+            return;
+        }
+        if self.current_diffs().is_empty() {
+            self.toasts.info("Nothing to review");
+            return;
+        }
+        self.review = Some(Review::new(self.current_diff_type()));
+        self.toasts.info(format!(
+            "Reviewing {}: a accept, r reject, d defer; R finishes, Esc discards",
+            self.direction_label()
+        ));
+    }
+    
+    /// Give the selected entry a verdict and move on to the next entry
+    pub fn review_selected(&mut self, verdict: Verdict) {
+        if !self.reviewing_current_list() {
+            return;
+        }
+        let Some(diff) = self.selected_diff().cloned() else {
+            return;
+        };
+        if let Some(review) = &mut self.review {
+            review.set(&diff, verdict);
+        }
+        self.select_next();
+    }
+    
+    /// Entries reviewed so far and the entries of the reviewed list, while a review is under way
+    pub fn review_progress(&self) -> Option<(usize, usize)> {
+        let review = self.review.as_ref()?;
+        let diffs = match review.diff_type {
+            DiffType::SharedToProject => &self.shared_to_project_diffs,
+            DiffType::ProjectToShared => &self.project_to_shared_diffs,
+        };
+        Some(review.progress(diffs))
+    }
+    
+    /// Stop reviewing without syncing or reporting anything
+    pub fn discard_review(&mut self) {
+        if self.review.take().is_some() {
+            self.toasts.info("Review discarded");
+        }
+    }
+    
+    /// Ask to sync the accepted entries and report the rejected ones
+    fn request_finish_review(&mut self) {
+        let Some(review) = &self.review else {
+            return;
+        };
+        let accepted = review.with_verdict(self.current_diffs(), Verdict::Accept).len();
+        let rejected = review.with_verdict(self.current_diffs(), Verdict::Reject).len();
+        let (reviewed, total) = review.progress(self.current_diffs());
+        if accepted == 0 && rejected == 0 {
+            self.review = None;
+            self.toasts.info("Review ended without verdicts");
+            return;
+        }
+        if accepted > 0 && (!self.allow_write("syncing") || !self.allow_sync_from_lists()) {
+            return;
+        }
+        
+        let mut message = format!(
+            "Sync {} accepted {} and add {} rejected to the exclusion report?",
+            accepted,
+            self.direction_label(),
+            rejected
+        );
+        if reviewed < total {
+            let undecided = total - reviewed;
+            message.push_str(&format!("\n{} of {} entries have no verdict or were deferred; they stay listed.", undecided, total));
+        }
+        self.open_dialog(Dialog::FinishReview, Popup::confirm("Finish Review".into(), message));
+    }
+    
+    /// End the review: report the rejected entries, then sync the accepted ones in one batch
+    fn finish_review(&mut self) {
+        let Some(review) = self.review.take() else {
+            return;
+        };
+        let accepted = review.with_verdict(self.current_diffs(), Verdict::Accept);
+        let rejected = review.with_verdict(self.current_diffs(), Verdict::Reject);
+        if !rejected.is_empty() {
+            match append_exclusion_report(&self.workspace_root, self.direction_label(), &rejected) {
+                Ok(path) => {
+                    let message = format!("Added {} rejected to {}", rejected.len(), path.display());
+                    append_log(&log_path(&self.workspace_root), &message);
+                    self.toasts.info(message);
+                }
+                Err(err) => self.report_error("Writing the exclusion report failed", &err),
+            }
+        }
+        if !accepted.is_empty() {
+            self.sync_entries(accepted);
+        }
+    }
+    
+    /// Ask to sync every file in the current list to a new branch of the destination repository
+    pub fn request_sync_to_branch(&mut self) {
+        if !self.allow_write("syncing") || !self.allow_sync_from_lists() {
+            return;
+        }
+        if !GitOps::is_available() {
+            self.toasts.info("Syncing to a branch needs git on PATH");
+            return;
+        }
+        if self.sync_task.is_some() {
+            self.toasts.info("A sync is already running");
+            return;
+        }
+        let diffs = self.current_diffs();
+        if diffs.is_empty() {
+            self.toasts.info("Nothing to sync");
+            return;
+        }
+        
+        let message = format!(
+            "Sync {} files {} on a new branch of the destination repository?\nThe working tree stays as it is; the branch gets one commit per directory.",
+            diffs.len(),
+            self.direction_label(),
+        );
+        let newer = count_newer_destinations(diffs);
+        self.open_dialog(Dialog::SyncToBranch, Popup::confirm("Sync to Branch".into(), message));
+        self.warn_newer_destinations(newer);
+    }
+    
+    /// Create the review branch for the entries, then sync them onto it
+    /// The sync lock is taken first, so no other sync writes to the branch
+    fn sync_to_branch(&mut self, entries: Vec<DiffEntry>) {
+        if !self.allow_write("syncing") || self.hold_secrets(&entries, true) || !self.lock_for_sync() {
+            return;
+        }
+        let label = self.active_group.clone().unwrap_or_else(|| self.project_name());
+        match ReviewBranch::create(&entries, &label, self.direction_label()) {
+            Ok(branch) => {
+                self.log(&format!("sync to branch: created {} in {}", branch.branch, branch.repo.display()));
+                self.start_sync_to(entries, Some(branch));
+            }
+            Err(err) => {
+                self.sync_lock = None;
+                self.report_error("Sync to branch failed", &err);
+            }
+        }
+    }
+    
+    /// Report how committing a finished sync to its review branch went
+    fn report_branch_commit(&mut self, (branch, commit): BranchCommit) {
+        match commit {
+            Ok(commits) => {
+                self.log(&format!(
+                    "sync to branch: {} commit(s) on {} in {}",
+                    commits,
+                    branch.branch,
+                    branch.repo.display()
+                ));
+                self.toasts.success(format!("Committed to branch {}; push it to open a pull request", branch.branch));
+            }
+            Err(err) => {
+                let err = err.context(format!(
+                    "Committing to {} failed; {} is left on that branch (switch back to {} once it's sorted out)",
+                    branch.branch,
+                    branch.repo.display(),
+                    branch.base
+                ));
+                self.report_error("Sync to branch failed", &err);
+            }
+        }
+    }
+    
+    /// Stack a warning over the pending confirm when the sync would overwrite newer files
+    fn warn_newer_destinations(&mut self, newer: usize) {
+        if newer == 0 {
+            return;
+        }
+        let message = format!(
+            "{} destination file{} newer than the source and will be overwritten",
+            newer,
+            if newer == 1 { " is" } else { "s are" },
+        );
+        self.open_dialog(Dialog::NewerDestinations, Popup::warning("Newer Files".into(), message));
+    }
+    
+    /// Sync entries unless uncommitted destination changes are in the way (with
+    /// `defaults.clean_destination` set); those are listed with the offer to stash them first
+    fn sync_entries(&mut self, entries: Vec<DiffEntry>) {
+        if !self.allow_write("syncing") || self.hold_secrets(&entries, false) {
+            return;
+        }
+        let check = self.config.defaults.clean_destination.clone();
+        if check != "none" && GitOps::is_available() {
+            match dirty_guard::dirty_destinations(&entries, &check) {
+                Ok(dirty) if !dirty.is_empty() => {
+                    self.hold_sync(entries, dirty);
+                    return;
+                }
+                Ok(_) => {}
+                Err(err) => {
+                    self.report_error("Checking the destination for uncommitted changes failed", &err);
+                    return;
+                }
+            }
+        }
+        self.start_sync(entries);
+    }
+    
+    /// Keep a sync back when files of it look like secrets, asking whether to sync them too
+    /// Returns whether the sync was held
+    fn hold_secrets(&mut self, entries: &[DiffEntry], to_branch: bool) -> bool {
+        const LISTED: usize = 10;
+        let secrets: Vec<&DiffEntry> = entries.iter().filter(|entry| entry.secret.is_some()).collect();
+        if secrets.is_empty() {
+            return false;
+        }
+        let mut message = match secrets.len() {
+            1 => "1 file looks like a secret:\n".to_string(),
+            count => format!("{} files look like secrets:\n", count),
+        };
+        for entry in secrets.iter().take(LISTED) {
+            message.push_str(&format!("\n{} ({})", entry.path.display(), entry.secret.as_deref().unwrap_or_default()));
+        }
+        if secrets.len() > LISTED {
+            message.push_str(&format!("\n... and {} more", secrets.len() - LISTED));
+        }
+        message.push_str("\n\nSync them too? No syncs the other files only");
+        self.held_secrets = Some((entries.to_vec(), to_branch));
+        self.open_dialog(Dialog::SecretFiles, Popup::confirm("Possible Secrets".into(), message));
+        true
+    }
+    
+    /// Continue a sync held for secrets, with them (the override) or without them
+    fn release_secrets(&mut self, mut entries: Vec<DiffEntry>, to_branch: bool, include: bool) {
+        if include {
+            for entry in entries.iter_mut().filter(|entry| entry.secret.is_some()) {
+                self.log(&format!("secret override: syncing {}", entry.path.display()));
+                entry.secret = None;
+            }
+        } else {
+            entries.retain(|entry| entry.secret.is_none());
+        }
+        if entries.is_empty() {
+            self.toasts.info("Nothing left to sync");
+        } else if to_branch {
+            self.sync_to_branch(entries);
+        } else {
+            self.sync_entries(entries);
+        }
+    }
+    
+    /// Keep a sync back and ask to stash the files in its way
+    fn hold_sync(&mut self, entries: Vec<DiffEntry>, dirty: Vec<DirtyRepo>) {
+        const LISTED: usize = 10;
+        let count: usize = dirty.iter().map(|repo| repo.files.len()).sum();
+        let mut message = format!(
+            "{} uncommitted file{} in the way of the sync:\n",
+            count,
+            if count == 1 { " is" } else { "s are" },
+        );
+        for repo in &dirty {
+            message.push_str(&format!("\n{}", repo.repo.display()));
+            for file in repo.files.iter().take(LISTED) {
+                message.push_str(&format!("\n  {}", file));
+            }
+            if repo.files.len() > LISTED {
+                message.push_str(&format!("\n  ... and {} more", repo.files.len() - LISTED));
+            }
+        }
+        message.push_str("\n\nStash them and sync?");
+        self.held_sync = Some((entries, dirty));
+        self.open_dialog(Dialog::DirtyDestination, Popup::confirm("Uncommitted Changes".into(), message));
+    }
+    
+    /// Take the workspace's sync lock (kept until `poll_sync` picks up the result); when another
+    /// process holds it, says which one, offering to remove the lock if that process is gone
+    fn lock_for_sync(&mut self) -> bool {
+        if self.sync_lock.is_some() {
+            return true;
+        }
+        let owner = match SyncLock::acquire(&self.workspace_root) {
+            Ok(LockAttempt::Acquired(lock)) => {
+                self.sync_lock = Some(lock);
+                return true;
+            }
+            Ok(LockAttempt::Held(owner)) => owner,
+            Err(err) => {
+                self.report_error("Taking the sync lock failed", &err);
+                return false;
+            }
         };
         
-        // Detect project name (directory name)
-        let project_name = self.workspace_root
-            .file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("sync-manager")
-            .to_string();
+        self.log(&format!("sync lock held by {}", owner.describe()));
+        if owner.is_stale() {
+            let message = format!(
+                "The sync lock was left by {}, which is no longer running.\nRemove the lock? Start the sync again afterwards.",
+                owner.describe()
+            );
+            self.stale_lock = Some(owner);
+            self.open_dialog(Dialog::StaleSyncLock, Popup::confirm("Stale Sync Lock".into(), message));
+        } else {
+            let message = format!(
+                "Another sync-manager ({}) is syncing this workspace.\nTry again once it has finished.\n\nLock file: {}",
+                owner.describe(),
+                SyncLock::path_for(&self.workspace_root).display()
+            );
+            self.open_dialog(Dialog::SyncLocked, Popup::warning("Sync Locked".into(), message));
+        }
+        false
+    }
+    
+    /// Remove a lock left by a process that is gone
+    fn remove_stale_lock(&mut self, owner: &LockOwner) {
+        match SyncLock::remove_stale(&self.workspace_root, owner) {
+            Ok(()) => {
+                self.log(&format!("removed the stale sync lock of {}", owner.describe()));
+                self.toasts.success("Removed the stale sync lock");
+            }
+            Err(err) => self.report_error("Removing the sync lock failed", &err),
+        }
+    }
+    
+    /// Stash the files in the way of a held sync, then start it
+    fn stash_and_sync(&mut self, entries: Vec<DiffEntry>, dirty: &[DirtyRepo]) {
+        if let Err(err) = dirty_guard::stash(dirty) {
+            self.report_error("Stashing failed", &err);
+            return;
+        }
+        for repo in dirty {
+            self.log(&format!("stashed {} file(s) in {} before syncing", repo.files.len(), repo.repo.display()));
+        }
+        self.toasts.info("Stashed the uncommitted changes; git stash pop restores them");
+        self.start_sync(entries);
+    }
+    
+    /// Start syncing entries in the background with the configured options
+    /// Entries are synced project by project, so the report lists them grouped; the progress
+    /// popup stays open until `poll_sync` picks up the result
+    fn start_sync(&mut self, entries: Vec<DiffEntry>) {
+        self.start_sync_to(entries, None);
+    }
+    
+    /// Start syncing entries in the background, committing them to `review_branch` once synced
+    fn start_sync_to(&mut self, mut entries: Vec<DiffEntry>, review_branch: Option<ReviewBranch>) {
+        if !self.allow_write("syncing") {
+            return;
+        }
+        if self.sync_task.is_some() {
+            self.toasts.info("A sync is already running");
+            return;
+        }
+        if !self.lock_for_sync() {
+            return;
+        }
+        let options = self.sync_options();
+        let projects = self.listed_projects();
+        entries.sort_by_key(|entry| projects.iter().position(|project| *project == entry.project));
+        self.sync_task = Some(SyncTask::start(entries, options, review_branch));
+        self.focus.push_modal(FocusTarget::SyncProgress);
+    }
+    
+    /// Sync options from the config and the project's copy strategy
+    fn sync_options(&self) -> SyncOptions {
+        SyncOptions {
+            create_backup: self.config.defaults.create_backups,
+            continue_on_error: self.config.defaults.continue_on_error,
+            dry_run: false,
+            propagate_deletes: self.config.defaults.propagate_deletes,
+            remove_directories: self.config.defaults.remove_directories,
+            retry_attempts: self.config.defaults.retry_attempts,
+            retry_backoff: Duration::from_millis(self.config.defaults.retry_backoff_ms),
+            workers: self.config.defaults.sync_workers,
+            copy_strategy: self
+                .project_config
+                .as_ref()
+                .and_then(|config| config.global_settings.copy_strategy)
+                .unwrap_or_default(),
+            max_bytes_per_sec: self.config.defaults.max_kb_per_sec.saturating_mul(1024),
+        }
+    }
+    
+    /// Pick up a finished background sync, then rescan the lists in the background
+    /// The per-file outcomes open in the sync report (a sync that only skipped files just toasts)
+    pub fn poll_sync(&mut self) {
+        if !self.sync_task.as_ref().is_some_and(SyncTask::is_finished) {
+            return;
+        }
+        let Some(task) = self.sync_task.take() else {
+            return;
+        };
+        self.focus.remove_modal(FocusTarget::SyncProgress);
+        self.close_stall_prompt(WatchedOperation::Sync);
+        let (entries, result, commit) = task.finish();
+        self.metrics.record_sync(&result, SystemTime::now());
+        if let Some(commit) = commit {
+            self.report_branch_commit(commit);
+        }
+        self.sync_lock = None;
+        self.log(&format!(
+            "sync {}: {} of {} files done ({} synced, {} failed, {} skipped)",
+            if result.cancelled { "cancelled" } else { "finished" },
+            result.outcomes.len(),
+            entries.len(),
+            result.synced,
+            result.failed,
+            result.skipped,
+        ));
         
-        // Get mappings for this project
-        let mappings = project_config.get_project_mappings(&project_name);
+        self.start_background_scan();
+        if result.synced == 0 && result.failed == 0 {
+            if result.cancelled {
+                self.toasts.info("Sync cancelled before any file was synced");
+            } else {
+                self.toasts.info(format!("Skipped {} pinned or deleted file(s)", result.skipped));
+            }
+            return;
+        }
         
-        if mappings.is_empty() {
-            // No mappings found - clear diffs
-            self.shared_to_project_diffs.clear();
-            self.project_to_shared_diffs.clear();
-            return Ok(());
+        // A retry replaces the report it was started from
+        if self.sync_report.replace(SyncReport::new(entries, result)).is_none() {
+            self.focus.push_modal(FocusTarget::SyncReport);
+        }
+    }
+    
+    /// Heartbeat of a running background operation
+    pub fn watchdog_of(&self, operation: WatchedOperation) -> Option<&Watchdog> {
+        match operation {
+            WatchedOperation::Sync => self.sync_task.as_ref().map(|task| &*task.watchdog),
+            WatchedOperation::Scan => self.scan_task.as_ref().map(|task| &*task.watchdog),
+        }
+    }
+    
+    /// Ask what to do when a sync or background scan made no progress for `watchdog_secs`
+    /// The prompt closes by itself once the operation moves on
+    pub fn check_watchdog(&mut self) {
+        if self.config.defaults.watchdog_secs == 0 {
+            return;
+        }
+        let after = Duration::from_secs(self.config.defaults.watchdog_secs);
+        if let Some(prompt) = &self.stall_prompt {
+            let operation = prompt.operation;
+            if self.watchdog_of(operation).is_some_and(|watchdog| watchdog.stalled(after).is_none()) {
+                self.close_stall_prompt(operation);
+            }
+            return;
         }
+        let stalled = [WatchedOperation::Sync, WatchedOperation::Scan]
+            .into_iter()
+            .find_map(|operation| Some((operation, self.watchdog_of(operation)?.stalled(after)?)));
+        let Some((operation, stall)) = stalled else {
+            return;
+        };
         
-        // Collect all diffs from all mappings
-        let mut shared_to_project_diffs = Vec::new();
-        let mut project_to_shared_diffs = Vec::new();
+        let name = match operation {
+            WatchedOperation::Sync => "Sync",
+            WatchedOperation::Scan => "Scan",
+        };
+        self.toasts.info(format!(
+            "{} made no progress for {} on {}",
+            name,
+            format_duration(stall.elapsed),
+            stall.path.display(),
+        ));
+        self.stall_prompt = Some(StallPrompt { operation, path: stall.path });
+        self.focus.push_modal(FocusTarget::StallPrompt);
+    }
+    
+    /// Apply the answer to the open stall prompt
+    pub fn resolve_stall(&mut self, choice: StallChoice) {
+        let Some(prompt) = self.stall_prompt.clone() else {
+            return;
+        };
+        match choice {
+            StallChoice::Wait => {
+                if let Some(watchdog) = self.watchdog_of(prompt.operation) {
+                    watchdog.snooze();
+                }
+            }
+            StallChoice::Skip => {
+                if let Some(watchdog) = self.watchdog_of(prompt.operation) {
+                    watchdog.skip(&prompt.path);
+                }
+                self.toasts.info(format!("Skipping {}", prompt.path.display()));
+            }
+            StallChoice::Cancel => match prompt.operation {
+                WatchedOperation::Sync => self.cancel_sync(),
+                WatchedOperation::Scan => self.cancel_scan(),
+            },
+        }
+        self.close_stall_prompt(prompt.operation);
+    }
+    
+    /// Close the stall prompt if it is about `operation`
+    fn close_stall_prompt(&mut self, operation: WatchedOperation) {
+        if self.stall_prompt.as_ref().is_some_and(|prompt| prompt.operation == operation) {
+            self.stall_prompt = None;
+            self.focus.remove_modal(FocusTarget::StallPrompt);
+        }
+    }
+    
+    /// Ask the running sync to stop; the files in flight finish, then the report shows what was done
+    pub fn request_cancel_sync(&mut self) {
+        let Some(task) = &self.sync_task else {
+            return;
+        };
+        if !task.cancel.is_cancelled() {
+            task.cancel.cancel();
+            self.toasts.info("Cancelling: the files in flight finish first");
+        }
+    }
+    
+    /// Stop the stuck sync and stop waiting for it; the lists are rescanned in the background
+    /// Files already synced stay synced; a file stuck in a system call finishes or fails on its own
+    /// A review branch is committed by the sync thread once the stuck file returns; until then
+    /// the repository stays on it, and the toast names it
+    fn cancel_sync(&mut self) {
+        let Some(task) = self.sync_task.take() else {
+            return;
+        };
+        task.cancel.cancel();
+        self.focus.remove_modal(FocusTarget::SyncProgress);
+        self.close_stall_prompt(WatchedOperation::Sync);
+        self.sync_lock = None;
+        let snapshot = task.progress.snapshot();
+        self.log(&format!(
+            "sync cancelled while stuck: {} of {} files done, stopped waiting for the rest",
+            snapshot.done,
+            snapshot.total,
+        ));
+        match task.review_branch {
+            Some(branch) => {
+                self.log(&format!(
+                    "sync to branch: cancelled before committing; {} stays on {} until the stuck file returns (base {})",
+                    branch.repo.display(),
+                    branch.branch,
+                    branch.base
+                ));
+                self.toasts.error(format!(
+                    "Sync cancelled; {} stays on branch {} until the stuck file returns, then the synced files are committed and {} is checked out",
+                    branch.repo.display(),
+                    branch.branch,
+                    branch.base
+                ));
+            }
+            None => self.toasts.info("Sync cancelled; files already synced are kept"),
+        }
+        self.start_background_scan();
+    }
+    
+    /// Close the sync report
+    pub fn close_sync_report(&mut self) {
+        self.sync_report = None;
+        self.focus.remove_modal(FocusTarget::SyncReport);
+    }
+    
+    /// Sync again only the files that failed in the open report
+    pub fn retry_failed_sync(&mut self) {
+        let Some(failed) = self.sync_report.as_ref().map(SyncReport::failed_entries) else {
+            return;
+        };
+        if failed.is_empty() {
+            self.toasts.info("No failed files to retry");
+            return;
+        }
+        // The destinations may be dirty from the sync being retried, so no clean check
+        self.start_sync(failed);
+    }
+    
+    /// Ask where to export the files a sync of the current list would write
+    pub fn request_export_archive(&mut self) {
+        if self.current_diffs().is_empty() {
+            self.toasts.info("Nothing to export");
+            return;
+        }
         
-        // Get shared resources base path
-        let shared_resources_base = self.workspace_root.join("_shared-resources");
+        let name = match self.view_mode {
+            ViewMode::SharedToProject => "shared-to-project.zip",
+            ViewMode::ProjectToShared => "project-to-shared.zip",
+        };
+        let path = Path::new(STATE_DIR_NAME).join(EXPORTS_DIR_NAME).join(name);
+        self.open_dialog(
+            Dialog::ExportArchive,
+            Popup::input("Export Archive".into(), "Path (.zip / .tar.gz):".into(), path.display().to_string()),
+        );
+    }
+    
+    /// Write the current list's pending files to an archive (relative paths are workspace-relative)
+    fn export_archive(&mut self, path: &str) {
+        if !self.allow_write("exporting") {
+            return;
+        }
+        let output = self.workspace_root.join(path.trim());
+        match export_archive(self.current_diffs(), &output) {
+            Ok(summary) => {
+                let skipped = if summary.skipped > 0 {
+                    format!(" ({} skipped)", summary.skipped)
+                } else {
+                    String::new()
+                };
+                self.toasts.success(format!("Exported {} file(s) to {}{}", summary.files, path.trim(), skipped));
+            }
+            Err(err) => self.report_error("Export failed", &err),
+        }
+    }
+    
+    /// Path of one side of the selected entry
+    fn selected_path(&self, side: EntrySide) -> Option<&Path> {
+        let diff = self.selected_diff()?;
+        Some(match side {
+            EntrySide::Source => &diff.source_path,
+            EntrySide::Destination => &diff.destination_path,
+        })
+    }
+    
+    /// Copy the source or destination path of the selected entry to the clipboard
+    pub fn copy_selected_path(&mut self, side: EntrySide) {
+        let Some(path) = self.selected_path(side).map(|p| p.display().to_string()) else {
+            self.toasts.info("No file selected");
+            return;
+        };
+        match self.clipboard.copy(&path) {
+            Ok(()) => self.toasts.info(format!("Copied {}", path)),
+            Err(err) => self.report_error("Copy failed", &err),
+        }
+    }
+    
+    /// Copy the line at the top of the side-by-side view, the hunk there or the whole diff
+    pub fn copy_diff(&mut self, what: DiffCopy) {
+        let Some(diff) = self.selected_diff().cloned() else {
+            self.toasts.info("No file selected");
+            return;
+        };
+        let row = self.diff_scroll_offset;
+        let text = match (what, &self.side_by_side_source, &self.side_by_side_dest) {
+            (DiffCopy::File, _, _) => self.diff_content(&diff),
+            (_, Some(source), Some(dest)) => {
+                let cache = self.side_by_side_cache.borrow();
+                let aligned = cache.aligned();
+                cache.entry_at(row).and_then(|index| match what {
+                    DiffCopy::Line => aligned_line(aligned, source, dest, index),
+                    _ => hunk_range(aligned, source, dest, index)
+                        .map(|hunk| format_hunk(aligned, source, dest, hunk, self.config.ui.context_lines)),
+                })
+            }
+            // A new or deleted file is one hunk; its view scrolls by line
+            (DiffCopy::Line, Some(lines), None) | (DiffCopy::Line, None, Some(lines)) => {
+                lines.get(row.min(lines.len().saturating_sub(1))).cloned()
+            }
+            _ => self.diff_content(&diff),
+        };
+        let Some(text) = text else {
+            self.toasts.info(match what {
+                DiffCopy::Hunk => "No changes at or below the top of the view",
+                _ => "Nothing to copy",
+            });
+            return;
+        };
+        let lines = text.lines().count();
+        match self.clipboard.copy(&text) {
+            Ok(()) => self.toasts.info(match what {
+                DiffCopy::Line => "Copied the line".to_string(),
+                DiffCopy::Hunk => format!("Copied the hunk ({} lines)", lines),
+                DiffCopy::File => format!("Copied the diff ({} lines)", lines),
+            }),
+            Err(err) => self.report_error("Copy failed", &err),
+        }
+    }
+    
+    /// Ask for the new text of the destination line at the top of the side-by-side view
+    pub fn request_line_edit(&mut self) {
+        if !self.allow_write("editing") {
+            return;
+        }
+        let Some(diff) = self.selected_diff().cloned() else {
+            self.toasts.info("No file selected");
+            return;
+        };
+        if self.history_version.is_some() || self.compares_keys(&diff) {
+            self.toasts.info("Inline edits need the destination's current lines (not a past version or keys)");
+            return;
+        }
+        let index = match (&self.side_by_side_source, &self.side_by_side_dest) {
+            (Some(_), Some(_)) => {
+                let cache = self.side_by_side_cache.borrow();
+                cache.entry_at(self.diff_scroll_offset).and_then(|entry| match cache.aligned().get(entry)? {
+                    LineAlignment::Both(_, dest) | LineAlignment::DestOnly(dest) => Some(*dest),
+                    LineAlignment::SourceOnly(_) => None,
+                })
+            }
+            // A deleted file's view scrolls by line
+            (None, Some(lines)) => Some(self.diff_scroll_offset.min(lines.len().saturating_sub(1))),
+            _ => None,
+        };
+        let Some(index) = index else {
+            self.toasts.info("No destination line at the top of the view");
+            return;
+        };
         
-        // Create diff engine with global excludes
-        let diff_engine = crate::operations::DiffEngine::new()
-            .with_excludes(self.config.global_excludes.clone());
+        // Only the file's own text can be edited in place: a line shown transformed goes to the editor
+        let content = fs::read_to_string(&diff.destination_path).unwrap_or_default();
+        let shown = self.side_by_side_dest.as_ref().and_then(|lines| lines.get(index));
+        let Some(line) = line_at(&content, index).filter(|line| shown.is_some_and(|shown| shown == line)) else {
+            self.toasts.info("This line is shown transformed; press e to edit the file in the editor");
+            return;
+        };
+        self.open_dialog(
+            Dialog::EditLine(index),
+            Popup::input(
+                format!("Edit line {} of {}", index + 1, diff.path.display()),
+                "Destination line (Enter saves, Esc cancels):".into(),
+                line.to_string(),
+            ),
+        );
+    }
+    
+    /// Replace destination line `index` of the selected entry with `line`, then diff the file again
+    /// Written through the sync engine, so the file is backed up as in a sync
+    fn save_line_edit(&mut self, index: usize, line: &str) {
+        let Some(diff) = self.selected_diff().cloned() else {
+            return;
+        };
+        let path = &diff.destination_path;
+        let result = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))
+            .and_then(|content| replace_line(&content, index, line).context("The line no longer exists"))
+            .and_then(|edited| SyncEngine::new(self.sync_options()).write_file(path, edited.as_bytes()));
+        if let Err(err) = result {
+            self.report_error("Saving the edit failed", &err);
+            return;
+        }
         
-        // Get shared-cursor package (or first enabled package) for resolving relative paths
-        let shared_package = project_config.get_package("shared-cursor")
-            .or_else(|| project_config.enabled_packages().next());
+        self.reload_edited(&diff);
+        self.toasts.success(format!("Saved line {} of {}", index + 1, path.display()));
+    }
+    
+    /// Write the other side's version of the hunk at (or after) the top of the side-by-side view
+    /// into one side (`from` is the side taken from), through the sync engine so the overwritten
+    /// file is backed up as in a sync
+    pub fn take_hunk(&mut self, from: EntrySide) {
+        if !self.allow_write("taking hunks") {
+            return;
+        }
+        let Some(diff) = self.selected_diff().cloned() else {
+            self.toasts.info("No file selected");
+            return;
+        };
+        if self.history_version.is_some() || self.compares_keys(&diff) {
+            self.toasts.info("Hunks can only be taken between the current files (not a past version or keys)");
+            return;
+        }
+        let into_dest = from == EntrySide::Source;
+        if diff.direction_override == Some(DirectionOverride::Pinned)
+            || (into_dest && (diff.is_blocked() || diff.secret.is_some()))
+        {
+            self.toasts.info("This file is pinned or flagged against syncing in this direction");
+            return;
+        }
+        let (Some(source), Some(dest)) = (&self.side_by_side_source, &self.side_by_side_dest) else {
+            self.toasts.info("A new or deleted file is a single hunk; sync it instead");
+            return;
+        };
+        let lines = {
+            let cache = self.side_by_side_cache.borrow();
+            let aligned = cache.aligned();
+            cache
+                .entry_at(self.diff_scroll_offset)
+                .and_then(|index| hunk_range(aligned, source, dest, index))
+                .map(|hunk| take_hunk(aligned, source, dest, hunk, into_dest))
+        };
+        let Some(lines) = lines else {
+            self.toasts.info("No changes at or below the top of the view");
+            return;
+        };
         
-        let shared_repo_path = if let Some(pkg) = shared_package {
-            shared_resources_base.join(&pkg.location)
-        } else {
-            shared_resources_base // Fallback if no packages
+        // The written side must be shown as it is on disk, or its transformed lines would be saved
+        let (path, shown) = if into_dest { (&diff.destination_path, dest) } else { (&diff.source_path, source) };
+        let content = fs::read_to_string(path).unwrap_or_default();
+        if !content.lines().eq(shown.iter().map(String::as_str)) {
+            self.toasts.info("This side is shown transformed; press e / E to edit the file in the editor");
+            return;
+        }
+        let result = SyncEngine::new(self.sync_options()).write_file(path, join_lines(&lines, &content).as_bytes());
+        if let Err(err) = result {
+            self.report_error("Taking the hunk failed", &err);
+            return;
+        }
+        self.reload_edited(&diff);
+        self.toasts.success(match from {
+            EntrySide::Source => "Took the source's hunk into the destination",
+            EntrySide::Destination => "Took the destination's hunk into the source",
+        });
+    }
+    
+    /// Diff a file edited from the side-by-side view again right away; the lists catch up in
+    /// the background
+    fn reload_edited(&mut self, diff: &DiffEntry) {
+        let (source, dest) = self.side_by_side_lines(diff);
+        self.side_by_side_source = source;
+        self.side_by_side_dest = dest;
+        self.cached_diff_content = self.diff_content(diff);
+        self.load_blame();
+        self.start_background_scan();
+    }
+    
+    /// Open the source or destination file of the selected entry in the external editor
+    pub fn open_selected_in_editor(&mut self, side: EntrySide) {
+        if !self.allow_write("editing") {
+            return;
+        }
+        let Some(path) = self.selected_path(side).map(Path::to_path_buf) else {
+            self.toasts.info("No file selected");
+            return;
+        };
+        if !path.exists() {
+            self.toasts.info(format!("{} does not exist", path.display()));
+            return;
+        }
+        self.pending_external = Some(ExternalAction::Edit(path));
+    }
+    
+    /// Focus a diff list and select one of its entries (e.g. the row under a mouse click)
+    pub fn select_entry(&mut self, list: FocusTarget, index: usize) {
+        if self.focus.focus(list) {
+            self.focus_changed(list);
+        }
+        if self.current_index() != index && index < self.current_diffs().len() {
+            self.set_current_index(index);
+            self.clear_diff_cache();
+        }
+    }
+    
+    /// Open the context menu of the selected entry at a screen cell; it owns keyboard focus while open
+    pub fn open_entry_menu(&mut self, anchor: (u16, u16)) {
+        let Some(diff) = self.selected_diff() else {
+            self.toasts.info("No file selected");
+            return;
+        };
+        self.entry_menu = Some(entry_menu(diff, anchor, !self.config.defaults.read_only, GitOps::can_query()));
+        self.focus.push_modal(FocusTarget::EntryMenu);
+    }
+    
+    /// Act on a key or mouse result from the entry context menu
+    pub fn handle_entry_menu_result(&mut self, result: MenuResult<EntryAction>) {
+        let action = match result {
+            MenuResult::Selected(action) => Some(action),
+            MenuResult::Closed => None,
+            MenuResult::Consumed | MenuResult::Ignored => return,
         };
+        let anchor = self.entry_menu.take().map(|menu| menu.anchor).unwrap_or_default();
+        self.focus.remove_modal(FocusTarget::EntryMenu);
         
-        for mapping in mappings {
-            // Resolve shared path
-            // If shared path starts with '_shared-resources', resolve from workspace root
-            // Otherwise, resolve relative to shared_repo_path
-            let shared_path = if mapping.shared.starts_with("_shared-resources/") {
-                self.workspace_root.join(&mapping.shared)
-            } else {
-                shared_repo_path.join(&mapping.shared)
-            };
-            
-            // Resolve project path (always relative to workspace root)
-            let project_path = self.workspace_root.join(&mapping.project);
-            
-            // Get exclude patterns for this mapping
-            let mapping_excludes: Vec<String> = mapping.exclude.clone();
-            
-            // Compute diffs in both directions
-            let shared_to_proj = diff_engine.compute_diff(
-                &shared_path,
-                &project_path,
-                crate::operations::DiffType::SharedToProject,
-                &mapping_excludes,
-            ).unwrap_or_default();
-            
-            let proj_to_shared = diff_engine.compute_diff(
-                &project_path,
-                &shared_path,
-                crate::operations::DiffType::ProjectToShared,
-                &mapping_excludes,
-            ).unwrap_or_default();
-            
-            shared_to_project_diffs.extend(shared_to_proj);
-            project_to_shared_diffs.extend(proj_to_shared);
-        }
-        
-        // Update the diff lists
-        self.shared_to_project_diffs = shared_to_project_diffs;
-        self.project_to_shared_diffs = project_to_shared_diffs;
+        match action {
+            Some(EntryAction::Sync) => self.request_sync_selected(),
+            Some(action @ (EntryAction::ExcludeFile | EntryAction::ExcludeDirectory)) => {
+                self.add_selected_pattern(action, PatternList::Exclude)
+            }
+            Some(EntryAction::Pin) => self.add_selected_pattern(EntryAction::Pin, PatternList::Pinned),
+            Some(EntryAction::OpenInEditor) => self.open_selected_in_editor(EntrySide::Destination),
+            Some(EntryAction::CopyPath) => self.copy_selected_path(EntrySide::Source),
+            Some(EntryAction::ShowHistory) => self.open_file_history(anchor),
+            Some(EntryAction::Explain) => self.explain_selected(),
+            Some(EntryAction::Annotate) => self.request_annotation(),
+            Some(EntryAction::CompareByKeys) => self.toggle_structural_diff(),
+            Some(EntryAction::CompareWithCommit(index)) => self.compare_with_commit(index),
+            None => {}
+        }
+    }
+    
+    /// Add a pattern for the selected entry to a list of its mapping, save the config and refresh
+    fn add_selected_pattern(&mut self, action: EntryAction, list: PatternList) {
+        if !self.allow_write("editing the config") {
+            return;
+        }
+        let Some(diff) = self.selected_diff() else {
+            return;
+        };
+        let Some(pattern) = entry_pattern(diff, action) else {
+            return;
+        };
+        let file_path = diff.source_path.clone();
+        let project_name = if diff.project.is_empty() { self.project_name() } else { diff.project.clone() };
+        let config_path = self.workspace_root.join(PROJECT_CONFIG_NAME);
+        let Some(config) = self.project_config.as_mut() else {
+            return;
+        };
+        let project_root = config.project_root(&self.workspace_root, &project_name);
+        let Some(mapping) = config.mapping_containing_mut(&project_root, &project_name, &file_path) else {
+            self.toasts.error(format!("No mapping contains {}", file_path.display()));
+            return;
+        };
         
-        // Reset indices if they're out of bounds
-        if self.shared_to_project_index >= self.shared_to_project_diffs.len() {
-            self.shared_to_project_index = 0;
+        let patterns = mapping.patterns_mut(list);
+        if patterns.contains(&pattern) {
+            self.toasts.info(format!("{} already lists {}", list.key(), pattern));
+            return;
+        }
+        patterns.push(pattern.clone());
+        let mapping = mapping.clone();
+        
+        let result = config
+            .save_added_pattern(&config_path, &mapping, list, &pattern)
+            .and_then(|()| self.refresh_diffs());
+        match result {
+            Ok(()) => self.toasts.success(format!("Added {} to {}", pattern, list.key())),
+            Err(err) => self.report_error(&format!("Updating {} failed", PROJECT_CONFIG_NAME), &err),
         }
-        if self.project_to_shared_index >= self.project_to_shared_diffs.len() {
-            self.project_to_shared_index = 0;
+    }
+    
+    /// Open the menu of recent commits of the selected entry's destination file at a screen
+    /// cell; picking one compares the source with that version
+    pub fn open_file_history(&mut self, anchor: (u16, u16)) {
+        if !GitOps::can_query() {
+            self.toasts.info("History needs git, which wasn't found on PATH");
+            return;
         }
+        let Some(diff) = self.selected_diff() else {
+            self.toasts.info("No file selected");
+            return;
+        };
         
-        // Clear cached diff since lists have changed
-        self.clear_diff_cache();
+        match GitOps::file_history(&diff.destination_path, HISTORY_LIMIT) {
+            Ok(commits) if commits.is_empty() => self.toasts.info("No commits touch the destination file"),
+            Ok(commits) => {
+                self.entry_menu = Some(history_menu(diff, &commits, anchor));
+                self.file_history = commits;
+                self.focus.push_modal(FocusTarget::EntryMenu);
+            }
+            Err(err) => self.report_error("History failed", &err),
+        }
+    }
+    
+    /// Show the source against the destination file as of a commit of the history menu
+    fn compare_with_commit(&mut self, index: usize) {
+        let (Some(commit), Some(diff)) = (self.file_history.get(index).cloned(), self.selected_diff()) else {
+            return;
+        };
+        let content = match GitOps::show_file(&diff.destination_path, &commit) {
+            Ok(content) => content,
+            Err(err) => {
+                self.report_error(&format!("Loading {} failed", commit.hash), &err);
+                return;
+            }
+        };
+        let source = self.side_by_side_lines(diff).0;
+        // A file compared by keys is compared with the past version's keys (its text if that
+        // doesn't parse)
+        let dest = match self.compares_keys(diff).then(|| parse_leaves(&content)) {
+            Some(Ok(leaves)) => leaf_lines(&leaves),
+            _ => content.lines().map(|l| l.to_string()).collect(),
+        };
         
-        Ok(())
+        self.remember_scroll_position();
+        self.side_by_side_source = Some(source.unwrap_or_default());
+        self.side_by_side_dest = Some(dest);
+        self.side_by_side_images = None;
+        self.side_by_side_blame = None;
+        self.history_version = Some(commit);
+        self.show_side_by_side = true;
+        self.diff_scroll_offset = 0;
+        self.sync_focus_ring();
+    }
+    
+    /// Show why the selected entry differs, re-checked with the scan's settings
+    pub fn explain_selected(&mut self) {
+        let Some(diff) = self.selected_diff() else {
+            return;
+        };
+        let reasons = self.diff_engine().explain(diff);
+        let title = format!("Why it differs: {}", diff.path.display());
+        let mut text = if reasons.is_empty() {
+            "No difference found: the files are in sync now (r rescans)".to_string()
+        } else {
+            reasons.iter().map(|reason| format!("• {}", reason.label())).collect::<Vec<_>>().join("\n")
+        };
+        if let Some(reason) = &diff.secret {
+            text.push_str(&format!("\n\n! Looks like a secret ({}): syncing asks before writing it", reason));
+        }
+        self.open_dialog(Dialog::ChangeReasons, Popup::info(title, text));
+    }
+    
+    /// Ask for the note of the selected entry, starting with its current note
+    pub fn request_annotation(&mut self) {
+        let Some(diff) = self.selected_diff() else {
+            self.toasts.info("No file selected");
+            return;
+        };
+        let title = format!("Note: {}", diff.path.display());
+        let current = self.annotations.get(diff).unwrap_or_default().to_string();
+        self.open_dialog(
+            Dialog::Annotate,
+            Popup::input(title, "Note on this file (empty removes it):".into(), current),
+        );
+    }
+    
+    /// Attach `note` to the selected entry (a blank note removes it) and save the notes
+    fn annotate_selected(&mut self, note: &str) {
+        if !self.allow_write("annotating") {
+            return;
+        }
+        let Some(diff) = self.selected_diff().cloned() else {
+            return;
+        };
+        let had_note = self.annotations.get(&diff).is_some();
+        self.annotations.set(&diff, note);
+        if let Err(err) = self.annotations.save(&self.workspace_root) {
+            self.report_error("Saving the note failed", &err);
+            return;
+        }
+        match self.annotations.get(&diff) {
+            Some(_) => self.toasts.success(format!("Noted {}", diff.path.display())),
+            None if had_note => self.toasts.info(format!("Removed the note of {}", diff.path.display())),
+            None => {}
+        }
+    }
+    
+    /// Switch the top-level tab (closes the side-by-side view)
+    /// Entering the Snapshots tab re-reads the snapshot files
+    pub fn show_tab(&mut self, tab: AppTab) {
+        if self.active_tab == tab || self.setup_wizard.is_some() {
+            return;
+        }
+        if self.show_side_by_side {
+            self.close_side_by_side();
+        }
+        
+        self.active_tab = tab;
+        match tab {
+            AppTab::Snapshots => {
+                if let Err(err) = self.snapshots.reload() {
+                    self.report_error("Loading snapshots failed", &err);
+                }
+            }
+            AppTab::Settings => self.settings.reset(&self.config),
+            AppTab::Sync => {}
+        }
+        self.sync_focus_ring();
+    }
+    
+    /// Diff engine with the global excludes and the whitespace and timestamp settings
+    fn diff_engine(&self) -> crate::operations::DiffEngine {
+        crate::operations::DiffEngine::new()
+            .with_excludes(self.config.global_excludes.clone())
+            .with_ignore_whitespace(self.config.ui.ignore_whitespace)
+            .with_ignore_timestamps(self.config.ui.ignore_timestamps)
+    }
+    
+    /// Apply the Settings form and write the changed values to sync-manager.yaml
+    /// Errors are kept on the form so the user can correct it
+    pub fn save_settings(&mut self) {
+        if self.config.defaults.read_only {
+            self.settings.error = Some(format!("Read-only mode: {} can't be written", PROJECT_CONFIG_NAME));
+            return;
+        }
+        let config = match self.settings.apply_to(&self.config) {
+            Ok(config) => config,
+            Err(err) => {
+                self.settings.error = Some(format!("{:#}", err));
+                return;
+            }
+        };
+        let Some(project_config) = &mut self.project_config else {
+            self.settings.error = Some(format!("No {} loaded", PROJECT_CONFIG_NAME));
+            return;
+        };
+        
+        project_config.settings = settings_overrides(&config);
+        if let Err(err) = project_config.save(&self.workspace_root.join(PROJECT_CONFIG_NAME)) {
+            self.settings.error = Some(format!("{:#}", err));
+            return;
+        }
+        
+        let rescan = config.ui.ignore_whitespace != self.config.ui.ignore_whitespace
+            || config.ui.ignore_timestamps != self.config.ui.ignore_timestamps;
+        let regroup = config.ui.group_by_status != self.config.ui.group_by_status;
+        // The open diff is reloaded with (or without) its archive members
+        if config.ui.archive_diffs != self.config.ui.archive_diffs {
+            self.cached_diff_path = None;
+        }
+        self.config = config;
+        if regroup {
+            self.group_by_status = self.config.ui.group_by_status;
+            self.filter_lists();
+        }
+        self.settings.error = None;
+        self.toasts.success(format!("Saved settings to {}", PROJECT_CONFIG_NAME));
+        if rescan {
+            if let Err(err) = self.refresh_diffs() {
+                self.report_error("Refresh failed", &err);
+            }
+        }
+    }
+    
+    /// Root of the tree recorded by snapshots
+    fn shared_resources_root(&self) -> PathBuf {
+        let root = match &self.project_config {
+            Some(config) => config.resolve_root(&self.workspace_root),
+            None => self.workspace_root.clone(),
+        };
+        root.join("_shared-resources")
+    }
+    
+    /// Ask for the name of a new snapshot of the shared resources
+    pub fn request_create_snapshot(&mut self) {
+        if !self.allow_write("creating snapshots") {
+            return;
+        }
+        let name = format!("snapshot-{}", self.snapshots.snapshots.len() + 1);
+        self.open_dialog(
+            Dialog::NewSnapshot,
+            Popup::input("New Snapshot".into(), "Name:".into(), name),
+        );
+    }
+    
+    /// Record the shared resources under `name`
+    fn create_snapshot(&mut self, name: &str) {
+        let root = self.shared_resources_root();
+        let engine = self.diff_engine();
+        match self.snapshots.create(name, &root, &engine) {
+            Ok(name) => self.toasts.success(format!("Created snapshot {}", name)),
+            Err(err) => self.report_error("Snapshot failed", &err),
+        }
+    }
+    
+    /// Ask to delete the selected snapshot
+    pub fn request_delete_snapshot(&mut self) {
+        if !self.allow_write("deleting snapshots") {
+            return;
+        }
+        let Some(snapshot) = self.snapshots.selected() else {
+            self.toasts.info("No snapshot selected");
+            return;
+        };
+        let message = format!("Delete snapshot {}?", snapshot.name);
+        self.open_dialog(Dialog::DeleteSnapshot, Popup::confirm("Delete Snapshot".into(), message));
+    }
+    
+    fn delete_snapshot(&mut self) {
+        match self.snapshots.delete_selected() {
+            Ok(Some(name)) => self.toasts.success(format!("Deleted snapshot {}", name)),
+            Ok(None) => {}
+            Err(err) => self.report_error("Delete failed", &err),
+        }
+    }
+    
+    /// Compare the shared resources against the selected snapshot
+    pub fn compare_selected_snapshot(&mut self) {
+        let root = self.shared_resources_root();
+        let engine = self.diff_engine();
+        match self.snapshots.compare_selected(&root, &engine) {
+            Ok(Some(comparison)) => {
+                let count = comparison.changes.len();
+                self.toasts.info(format!("{} file(s) changed since {}", count, comparison.name));
+            }
+            Ok(None) => self.toasts.info("No snapshot selected"),
+            Err(err) => self.report_error("Compare failed", &err),
+        }
     }
     
     /// Request application quit
@@ -359,3 +3053,98 @@ impl App {
         self.should_quit = true;
     }
 }
+
+/// Confirm prompt verb for a single entry
+fn sync_verb(diff: &DiffEntry) -> &'static str {
+    match diff.status {
+        FileStatus::Added => "Copy new file",
+        FileStatus::Deleted => "Delete from destination",
+        _ => "Overwrite destination with",
+    }
+}
+
+/// Key of the per-file view state: entries of a group can share a relative path
+fn entry_key(diff: &DiffEntry) -> (String, PathBuf) {
+    (diff.project.clone(), diff.path.clone())
+}
+
+/// Number of modified entries whose destination was changed after the source
+fn count_newer_destinations(diffs: &[DiffEntry]) -> usize {
+    diffs.iter().filter(|diff| destination_is_newer(diff)).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    /// App in a temporary workspace whose shared copy of `src/lib.rs` differs from the project's,
+    /// after its startup scan
+    fn app_with_modified_file(name: &str) -> (App, PathBuf) {
+        let root = std::env::temp_dir().join(format!("sync-manager-{}-{}", name, std::process::id()));
+        fs::remove_dir_all(&root).ok();
+        fs::create_dir_all(root.join("_shared-resources/shared/src")).unwrap();
+        fs::create_dir_all(root.join(".project/src")).unwrap();
+        fs::write(root.join("_shared-resources/shared/src/lib.rs"), "pub fn shared() {}\n").unwrap();
+        fs::write(root.join(".project/src/lib.rs"), "pub fn project() {}\n").unwrap();
+        let project = App::project_name_for(&root);
+        let yaml = format!("workspace_settings:\n  {}:\n    shared:\n      mappings:\n        - shared: _shared-resources/shared\n          project: .project\n", project);
+        fs::write(root.join(PROJECT_CONFIG_NAME), yaml).unwrap();
+        let mut app = App::open(root.clone()).unwrap();
+        finish_scan(&mut app);
+        (app, root)
+    }
+    
+    /// Wait for the background scan and take its lists
+    fn finish_scan(app: &mut App) {
+        while app.scan_task.as_ref().is_some_and(|task| !task.is_finished()) {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        app.poll_scan();
+    }
+    
+    #[test]
+    fn test_side_by_side_survives_a_background_scan() {
+        let (mut app, root) = app_with_modified_file("rescan-keeps-view");
+        assert_eq!(app.selected_diff().map(|diff| diff.path.clone()), Some(PathBuf::from("src/lib.rs")));
+        app.toggle_side_by_side();
+        assert!(app.show_side_by_side);
+        
+        app.rescan();
+        finish_scan(&mut app);
+        assert!(app.show_side_by_side);
+        assert!(app.side_by_side_source.is_some());
+        
+        fs::remove_dir_all(&root).ok();
+    }
+    
+    #[test]
+    fn test_closing_the_view_keeps_the_configured_fold() {
+        let (mut app, root) = app_with_modified_file("fold-default");
+        app.config.ui.fold_unchanged = true;
+        app.toggle_side_by_side();
+        assert!(app.current_folds().fold_unchanged);
+        
+        app.close_side_by_side();
+        app.toggle_side_by_side();
+        assert!(app.current_folds().fold_unchanged);
+        assert!(app.config.ui.fold_unchanged);
+        
+        fs::remove_dir_all(&root).ok();
+    }
+    
+    #[test]
+    fn test_read_only_blocks_export_and_notes() {
+        let (mut app, root) = app_with_modified_file("read-only-writes");
+        app.config.defaults.read_only = true;
+        let diff = app.selected_diff().cloned().unwrap();
+        
+        app.export_archive("export.zip");
+        assert!(!root.join("export.zip").exists());
+        
+        app.annotate_selected("reviewed");
+        assert_eq!(app.annotations.get(&diff), None);
+        assert_eq!(Annotations::load(&root).get(&diff), None);
+        
+        fs::remove_dir_all(&root).ok();
+    }
+}
@@ -2,6 +2,8 @@
 // Defaults compiled from config.yaml at build time
 // Modify config.yaml and rebuild to change these values
 
+use super::project_config::UserSettings;
+
 // Include the auto-generated config from build.rs
 pub mod compiled {
     include!(concat!(env!("OUT_DIR"), "/compiled_config.rs"));
@@ -29,9 +31,42 @@ pub struct UiSettings {
     /// Enable syntax highlighting
     pub syntax_highlighting: bool,
     
+    /// Syntax highlighting theme: a built-in name or a .tmTheme path
+    pub syntax_theme: String,
+    
     /// Number of context lines around changes
     pub context_lines: usize,
     
+    /// Fold unchanged regions of files opened side-by-side
+    pub fold_unchanged: bool,
+    
+    /// Group the diff lists into status sections
+    pub group_by_status: bool,
+    
+    /// Side-by-side gutters show both line numbers (source and destination) of each row
+    pub dual_line_numbers: bool,
+    
+    /// The diff view lists the differing members of archive entries (zip / tar)
+    pub archive_diffs: bool,
+    
+    /// Files differing only in the amount of whitespace within lines are unchanged
+    pub ignore_whitespace: bool,
+    
+    /// Files are compared by content only, never by modification time
+    pub ignore_timestamps: bool,
+    
+    /// Word overlap above which differing lines are paired as modified
+    pub similarity_threshold: f64,
+    
+    /// Characters of a side-by-side line shown before it is truncated (0 = never)
+    pub long_line_chars: usize,
+    
+    /// Gutter of wrapped continuation rows in the side-by-side view: "arrow" or "blank"
+    pub wrap_marker: String,
+    
+    /// Columns between tab stops in the side-by-side view (0 = tabs as they are)
+    pub tab_width: usize,
+    
     /// Enable mouse support
     pub mouse_enabled: bool,
     
@@ -52,6 +87,39 @@ pub struct DefaultSettings {
     
     /// Create backups before overwriting files
     pub create_backups: bool,
+    
+    /// Ask for confirmation before syncing
+    pub confirm_sync: bool,
+    
+    /// Uncommitted changes that block a sync into a git repository: "none", "repository", "paths"
+    pub clean_destination: String,
+    
+    /// Delete destination files whose source was deleted
+    pub propagate_deletes: bool,
+    
+    /// Also remove destination directories without files whose source was deleted
+    pub remove_directories: bool,
+    
+    /// Disable every action that writes files (sync, config edits, snapshots)
+    pub read_only: bool,
+    
+    /// Files synced in parallel (0 = one per CPU core)
+    pub sync_workers: usize,
+    
+    /// Cap on the combined write rate of a sync in KB/s (0 = unlimited)
+    pub max_kb_per_sec: u64,
+    
+    /// Seconds without progress before a scan or sync counts as stuck (0 = never)
+    pub watchdog_secs: u64,
+    
+    /// Retries of a copy or delete that fails with a transient error (0 = no retries)
+    pub retry_attempts: u32,
+    
+    /// Wait before the first retry in milliseconds (doubles on each further retry)
+    pub retry_backoff_ms: u64,
+    
+    /// Editor command for opening files (empty = $VISUAL / $EDITOR)
+    pub editor: String,
 }
 
 impl Default for UiSettings {
@@ -59,7 +127,18 @@ impl Default for UiSettings {
         Self {
             show_line_numbers: compiled::SHOW_LINE_NUMBERS,
             syntax_highlighting: compiled::SYNTAX_HIGHLIGHTING,
+            syntax_theme: compiled::SYNTAX_THEME.to_string(),
             context_lines: compiled::CONTEXT_LINES,
+            fold_unchanged: compiled::FOLD_UNCHANGED,
+            group_by_status: compiled::GROUP_BY_STATUS,
+            dual_line_numbers: compiled::DUAL_LINE_NUMBERS,
+            archive_diffs: compiled::ARCHIVE_DIFFS,
+            ignore_whitespace: compiled::IGNORE_WHITESPACE,
+            ignore_timestamps: compiled::IGNORE_TIMESTAMPS,
+            similarity_threshold: compiled::SIMILARITY_THRESHOLD,
+            long_line_chars: compiled::LONG_LINE_CHARS,
+            wrap_marker: compiled::WRAP_MARKER.to_string(),
+            tab_width: compiled::TAB_WIDTH,
             mouse_enabled: compiled::MOUSE_ENABLED,
             theme: compiled::THEME.to_string(),
         }
@@ -73,6 +152,77 @@ impl Default for DefaultSettings {
             conflict_resolution: compiled::CONFLICT_RESOLUTION.to_string(),
             continue_on_error: compiled::CONTINUE_ON_ERROR,
             create_backups: compiled::CREATE_BACKUPS,
+            confirm_sync: compiled::CONFIRM_SYNC,
+            clean_destination: compiled::CLEAN_DESTINATION.to_string(),
+            propagate_deletes: compiled::PROPAGATE_DELETES,
+            remove_directories: compiled::REMOVE_DIRECTORIES,
+            read_only: compiled::READ_ONLY,
+            sync_workers: compiled::SYNC_WORKERS,
+            max_kb_per_sec: compiled::MAX_KB_PER_SEC,
+            watchdog_secs: compiled::WATCHDOG_SECS,
+            retry_attempts: compiled::RETRY_ATTEMPTS,
+            retry_backoff_ms: compiled::RETRY_BACKOFF_MS,
+            editor: compiled::EDITOR.to_string(),
+        }
+    }
+}
+
+impl AppConfig {
+    /// Apply the overrides from the project config's `settings` section
+    pub fn apply_settings(&mut self, settings: &UserSettings) {
+        if let Some(fold) = settings.fold_unchanged {
+            self.ui.fold_unchanged = fold;
+        }
+        if let Some(group) = settings.group_by_status {
+            self.ui.group_by_status = group;
+        }
+        if let Some(dual) = settings.dual_line_numbers {
+            self.ui.dual_line_numbers = dual;
+        }
+        if let Some(archives) = settings.archive_diffs {
+            self.ui.archive_diffs = archives;
+        }
+        if let Some(ignore) = settings.ignore_whitespace {
+            self.ui.ignore_whitespace = ignore;
+        }
+        if let Some(ignore) = settings.ignore_timestamps {
+            self.ui.ignore_timestamps = ignore;
+        }
+        if let Some(theme) = &settings.theme {
+            self.ui.theme = theme.clone();
+        }
+        if let Some(lines) = settings.context_lines {
+            self.ui.context_lines = lines;
+        }
+        if let Some(confirm) = settings.confirm_sync {
+            self.defaults.confirm_sync = confirm;
+        }
+        if let Some(clean) = &settings.clean_destination {
+            self.defaults.clean_destination = clean.clone();
+        }
+        if let Some(propagate) = settings.propagate_deletes {
+            self.defaults.propagate_deletes = propagate;
+        }
+        if let Some(remove) = settings.remove_directories {
+            self.defaults.remove_directories = remove;
+        }
+        if let Some(read_only) = settings.read_only {
+            self.defaults.read_only = read_only;
+        }
+        if let Some(workers) = settings.sync_workers {
+            self.defaults.sync_workers = workers;
+        }
+        if let Some(limit) = settings.max_kb_per_sec {
+            self.defaults.max_kb_per_sec = limit;
+        }
+        if let Some(secs) = settings.watchdog_secs {
+            self.defaults.watchdog_secs = secs;
+        }
+        if let Some(attempts) = settings.retry_attempts {
+            self.defaults.retry_attempts = attempts;
+        }
+        if let Some(backoff) = settings.retry_backoff_ms {
+            self.defaults.retry_backoff_ms = backoff;
         }
     }
 }
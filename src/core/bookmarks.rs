@@ -0,0 +1,68 @@
+// Bookmarks
+// Positions in the side-by-side view of a file (`m` then a letter) to jump back to (`'` then the
+// letter) while reviewing a large file; kept per file for the rest of the session
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+
+use crate::operations::DiffEntry;
+
+/// Bookmark key waiting for its letter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BookmarkPrompt {
+    /// `m`: mark the current position
+    Set,
+    /// `'`: scroll back to a marked position
+    Jump,
+}
+
+/// Scroll rows of the side-by-side view by project and path, then by letter
+#[derive(Debug, Clone, Default)]
+pub struct Bookmarks {
+    rows: HashMap<(String, PathBuf), BTreeMap<char, usize>>,
+}
+
+fn key(entry: &DiffEntry) -> (String, PathBuf) {
+    (entry.project.clone(), entry.path.clone())
+}
+
+impl Bookmarks {
+    /// Mark `row` of an entry as `letter`, replacing the bookmark of that letter
+    pub fn set(&mut self, entry: &DiffEntry, letter: char, row: usize) {
+        self.rows.entry(key(entry)).or_default().insert(letter, row);
+    }
+    
+    /// Row marked as `letter` in an entry
+    pub fn get(&self, entry: &DiffEntry, letter: char) -> Option<usize> {
+        self.rows.get(&key(entry))?.get(&letter).copied()
+    }
+    
+    /// Letters of an entry's bookmarks, in order
+    pub fn letters(&self, entry: &DiffEntry) -> Vec<char> {
+        self.rows.get(&key(entry)).map(|marks| marks.keys().copied().collect()).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(path: &str, project: &str) -> DiffEntry {
+        DiffEntry::for_test(path, project)
+    }
+
+    #[test]
+    fn test_bookmarks_are_kept_per_file() {
+        let own = entry("dist/app.js", "");
+        let web = entry("dist/app.js", "web");
+        let mut bookmarks = Bookmarks::default();
+        bookmarks.set(&own, 'b', 1200);
+        bookmarks.set(&own, 'a', 40);
+        bookmarks.set(&own, 'a', 75);
+
+        assert_eq!(bookmarks.get(&own, 'a'), Some(75));
+        assert_eq!(bookmarks.letters(&own), ['a', 'b']);
+        assert_eq!(bookmarks.get(&web, 'a'), None);
+        assert!(bookmarks.letters(&web).is_empty());
+    }
+}
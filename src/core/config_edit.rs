@@ -0,0 +1,192 @@
+// Config Editing
+// Targeted text edits of sync-manager.yaml, so actions that change the config (exclude, pin)
+// keep the user's comments and layout instead of re-serializing the whole file
+
+use super::project_config::Mapping;
+
+/// Add `value` to the `key` list (e.g. `exclude`) of `mapping` in the YAML text
+/// Handles block lists, single-line flow lists and a missing key; returns None when the
+/// mapping or list can't be edited safely (the caller falls back to a full rewrite)
+pub fn add_mapping_list_item(content: &str, mapping: &Mapping, key: &str, value: &str) -> Option<String> {
+    let newline = if content.contains("\r\n") { "\r\n" } else { "\n" };
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let (start, end, key_indent) = find_mapping_block(&lines, mapping)?;
+    let quoted = quote(value);
+
+    let key_line = (start..end)
+        .filter(|&i| i == start || indent_of(&lines[i]) == key_indent)
+        .find(|&i| key_of(split_comment(item_content(&lines[i], i == start)).0) == Some(key));
+
+    match key_line {
+        Some(i) => {
+            let content = item_content(&lines[i], i == start);
+            let (code, comment) = split_comment(content);
+            let list = code.split_once(':')?.1.trim();
+            if let Some(inner) = list.strip_prefix('[') {
+                // Flow list on one line: [a, b] -> [a, b, "value"]
+                let items = inner.strip_suffix(']')?.trim();
+                let edited = if items.is_empty() {
+                    format!("[{}]", quoted)
+                } else {
+                    format!("[{}, {}]", items, quoted)
+                };
+                let prefix = lines[i][..lines[i].len() - content.len()].to_string();
+                lines[i] = format!("{}{}: {}{}", prefix, key, edited, comment);
+            } else if list.is_empty() {
+                // Block list: append after the last item, at the items' indentation
+                let is_item = |line: &str| line.trim_start().starts_with("- ");
+                let last_item = (i + 1..end)
+                    .take_while(|&j| is_blank(&lines[j]) || indent_of(&lines[j]) > key_indent || is_item(&lines[j]))
+                    .filter(|&j| is_item(&lines[j]))
+                    .last();
+                let (after, indent) = match last_item {
+                    Some(last) => (last, indent_of(&lines[last])),
+                    None => (i, key_indent + 2),
+                };
+                lines.insert(after + 1, format!("{}- {}", " ".repeat(indent), quoted));
+            } else {
+                // A scalar where a list was expected
+                return None;
+            }
+        }
+        None => {
+            let last = (start..end).rev().find(|&i| !is_blank(&lines[i]))?;
+            let indent = " ".repeat(key_indent);
+            lines.insert(last + 1, format!("{}{}:", indent, key));
+            lines.insert(last + 2, format!("{}  - {}", indent, quoted));
+        }
+    }
+
+    let mut edited = lines.join(newline);
+    if content.ends_with('\n') {
+        edited.push_str(newline);
+    }
+    Some(edited)
+}
+
+/// Lines [start, end) of the `- shared: ... project: ...` list item for `mapping`, and the
+/// indentation of the item's keys
+fn find_mapping_block(lines: &[String], mapping: &Mapping) -> Option<(usize, usize, usize)> {
+    (0..lines.len()).find_map(|start| {
+        let line = &lines[start];
+        let dash = indent_of(line);
+        let rest = line.trim_start().strip_prefix('-')?;
+        let key_indent = dash + 1 + (rest.len() - rest.trim_start().len());
+
+        let end = (start + 1..lines.len())
+            .find(|&i| !is_blank(&lines[i]) && indent_of(&lines[i]) <= dash)
+            .unwrap_or(lines.len());
+
+        let value = |key: &str| {
+            (start..end)
+                .filter(|&i| i == start || indent_of(&lines[i]) == key_indent)
+                .map(|i| split_comment(item_content(&lines[i], i == start)).0)
+                .find(|code| key_of(code) == Some(key))
+                .and_then(|code| code.split_once(':'))
+                .map(|(_, value)| unquote(value.trim()))
+        };
+        (value("shared")? == mapping.shared && value("project")? == mapping.project).then_some((start, end, key_indent))
+    })
+}
+
+/// Content of a line without its indentation (and without the "- " of a list item's first line)
+fn item_content(line: &str, first_of_item: bool) -> &str {
+    let content = line.trim_start();
+    if first_of_item {
+        content.strip_prefix('-').map(str::trim_start).unwrap_or(content)
+    } else {
+        content
+    }
+}
+
+/// Key of a `key: value` line
+fn key_of(code: &str) -> Option<&str> {
+    let (key, _) = code.split_once(':')?;
+    let key = key.trim();
+    (!key.is_empty() && !key.starts_with('#') && !key.starts_with('-')).then_some(key)
+}
+
+/// Split a line into code and a trailing ` # comment` (kept with its leading space)
+fn split_comment(line: &str) -> (&str, &str) {
+    let mut quote = None;
+    for (i, c) in line.char_indices() {
+        match (c, quote) {
+            ('"' | '\'', None) => quote = Some(c),
+            (c, Some(q)) if c == q => quote = None,
+            ('#', None) if i > 0 && line[..i].ends_with(' ') => return (line[..i].trim_end(), &line[i - 1..]),
+            _ => {}
+        }
+    }
+    (line.trim_end(), "")
+}
+
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
+
+fn is_blank(line: &str) -> bool {
+    let trimmed = line.trim();
+    trimmed.is_empty() || trimmed.starts_with('#')
+}
+
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+        .unwrap_or(value)
+}
+
+/// Double-quoted YAML scalar (patterns often start with `*`, which YAML reads as an alias)
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mapping(shared: &str, project: &str) -> Mapping {
+        serde_yaml::from_str(&format!("shared: {}\nproject: {}", shared, project)).unwrap()
+    }
+
+    const CONFIG: &str = "\
+workspace_settings:
+  demo:
+    shared-cursor:
+      mappings:
+        # Commands
+        - shared: commands
+          project: .cursor/commands
+
+        - shared: rules
+          project: .cursor/rules
+          # Keep workspace notes out
+          exclude:
+            - \"_*-workspace/\"  # per-project
+          pinned: [\"local-*\"]
+";
+
+    #[test]
+    fn test_append_to_block_list_keeps_comments() {
+        let edited = add_mapping_list_item(CONFIG, &mapping("rules", ".cursor/rules"), "exclude", "/notes.md").unwrap();
+        assert!(edited.contains("            - \"_*-workspace/\"  # per-project\n            - \"/notes.md\"\n"));
+        assert!(edited.contains("# Keep workspace notes out"));
+    }
+
+    #[test]
+    fn test_append_to_flow_list_and_missing_key() {
+        let rules = mapping("rules", ".cursor/rules");
+        let edited = add_mapping_list_item(CONFIG, &rules, "pinned", "a.md").unwrap();
+        assert!(edited.contains("pinned: [\"local-*\", \"a.md\"]"));
+
+        let commands = mapping("commands", ".cursor/commands");
+        let edited = add_mapping_list_item(CONFIG, &commands, "exclude", "*.tmp").unwrap();
+        assert!(edited.contains("          project: .cursor/commands\n          exclude:\n            - \"*.tmp\"\n"));
+
+        let parsed: serde_yaml::Value = serde_yaml::from_str(&edited).unwrap();
+        let mappings = &parsed["workspace_settings"]["demo"]["shared-cursor"]["mappings"];
+        assert_eq!(mappings[0]["exclude"][0], "*.tmp");
+        assert!(add_mapping_list_item(CONFIG, &mapping("other", "x"), "exclude", "a").is_none());
+    }
+}
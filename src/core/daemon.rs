@@ -0,0 +1,98 @@
+// Daemon
+// `sync-manager serve`: keeps the diff of the workspace current without a terminal, rescanning
+// on an interval, and answers requests on the control socket in the state directory and,
+// if given an address, metrics requests over HTTP
+
+use anyhow::Result;
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+
+use super::App;
+use crate::operations::{ControlRequest, ControlResponse, MetricsServer};
+
+/// Time between rescans while watching
+pub const RESCAN_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How often finished scans and syncs are picked up and waiting clients are answered
+const TICK: Duration = Duration::from_millis(200);
+
+/// Serve the control socket of the workspace until the process is stopped
+/// Rescans every `RESCAN_INTERVAL` unless paused; `sync` requests sync the shared → project list
+/// With `metrics_addr`, `/metrics` there answers with the scan and sync totals
+#[cfg(unix)]
+pub fn serve(app: &mut App, metrics_addr: Option<SocketAddr>) -> Result<()> {
+    use crate::operations::control::{socket_path, ControlServer};
+    
+    let path = socket_path(&app.workspace_root);
+    let server = ControlServer::bind(&path)?;
+    app.log(&format!("serving on {}", path.display()));
+    println!("Listening on {} (Ctrl+C stops)", path.display());
+    let metrics = metrics_addr.map(MetricsServer::bind).transpose()?;
+    if let Some(metrics) = &metrics {
+        println!("Metrics at http://{}/metrics", metrics.local_addr()?);
+    }
+    
+    let mut paused = false;
+    let mut last_scan = Instant::now();
+    loop {
+        app.poll_scan();
+        app.poll_sync();
+        server.poll(|request| answer(app, &mut paused, request))?;
+        if let Some(metrics) = &metrics {
+            metrics.poll(&app.metrics)?;
+        }
+        
+        let idle = app.scan_task.is_none() && app.sync_task.is_none();
+        if !paused && idle && last_scan.elapsed() >= RESCAN_INTERVAL {
+            app.rescan();
+            last_scan = Instant::now();
+        }
+        std::thread::sleep(TICK);
+    }
+}
+
+/// Serve the control socket of the workspace until the process is stopped
+#[cfg(not(unix))]
+pub fn serve(_app: &mut App, _metrics_addr: Option<SocketAddr>) -> Result<()> {
+    anyhow::bail!("`serve` needs Unix domain sockets, which this platform doesn't have")
+}
+
+/// Answer one control request
+fn answer(app: &mut App, paused: &mut bool, request: ControlRequest) -> ControlResponse {
+    let message = match request {
+        ControlRequest::Status => status(app, *paused),
+        ControlRequest::SyncNow => match app.sync_unattended() {
+            Ok(0) => "Nothing to sync".to_string(),
+            Ok(count) => format!("Syncing {} file(s) shared → project", count),
+            Err(err) => return ControlResponse::Error(err.to_string()),
+        },
+        ControlRequest::Pause => {
+            *paused = true;
+            "Paused: no rescans until resumed".to_string()
+        }
+        ControlRequest::Resume => {
+            *paused = false;
+            "Watching".to_string()
+        }
+    };
+    app.log(&format!("control {}: {}", request.as_str(), message));
+    ControlResponse::Ok(message)
+}
+
+/// "watching, idle; 3 to sync shared → project, 1 project → shared"
+fn status(app: &App, paused: bool) -> String {
+    let activity = if app.sync_task.is_some() {
+        "syncing"
+    } else if app.scan_task.is_some() {
+        "scanning"
+    } else {
+        "idle"
+    };
+    format!(
+        "{}, {}; {} to sync shared → project, {} project → shared",
+        if paused { "paused" } else { "watching" },
+        activity,
+        app.shared_to_project_diffs.len(),
+        app.project_to_shared_diffs.len(),
+    )
+}
@@ -0,0 +1,376 @@
+// Diff Cache
+// Diff lists of the last run, shown at startup while a fresh scan runs in the background
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use tui_components::DiffStats;
+
+use super::diff_scan::DiffLists;
+use super::session::{log_path, STATE_DIR_NAME};
+use crate::operations::snapshot::content_hash;
+use crate::operations::{DiffEntry, DiffType, FileStatus};
+use crate::utilities::append_log;
+
+/// Diff cache file name inside the state directory
+const DIFF_CACHE_FILE_NAME: &str = "diff-cache.yaml";
+
+/// Format version in the cache header; a file of any other version is rebuilt
+const DIFF_CACHE_VERSION: u32 = 1;
+
+/// Hash records not used for this long are pruned on save
+const MAX_HASH_AGE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// Most hash records kept; the least recently used go first
+const MAX_HASH_RECORDS: usize = 10_000;
+
+/// Both diff lists as computed by the last scan, saved on quit
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct DiffCache {
+    /// Format version (0 for files written before the header existed)
+    #[serde(default)]
+    pub version: u32,
+    
+    #[serde(default)]
+    pub shared_to_project: Vec<CachedEntry>,
+    
+    #[serde(default)]
+    pub project_to_shared: Vec<CachedEntry>,
+    
+    /// Content hashes of the source files seen by recent runs
+    #[serde(default)]
+    pub hashes: HashCache,
+}
+
+/// Content hashes keyed by file path, reused while a file's size and modification time are unchanged
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(transparent)]
+pub struct HashCache {
+    records: BTreeMap<PathBuf, HashRecord>,
+    
+    /// Lookups answered from a record this run
+    #[serde(skip)]
+    pub hits: usize,
+    
+    /// Lookups that had to read the file this run
+    #[serde(skip)]
+    pub misses: usize,
+}
+
+/// Hash of one file with the metadata it was computed for
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct HashRecord {
+    size: u64,
+    /// Modification time in seconds since the Unix epoch
+    modified: u64,
+    hash: String,
+    /// Last run that used the record, in seconds since the Unix epoch
+    used: u64,
+}
+
+impl HashCache {
+    /// Content hash of the file at `path` (None if it can't be read)
+    pub fn hash(&mut self, path: &Path, now: u64) -> Option<String> {
+        let meta = fs::metadata(path).ok()?;
+        let modified = unix_secs(meta.modified().ok()?);
+        
+        if let Some(record) = self.records.get_mut(path) {
+            if record.size == meta.len() && record.modified == modified {
+                record.used = now;
+                self.hits += 1;
+                return Some(record.hash.clone());
+            }
+        }
+        
+        let hash = content_hash(&fs::read(path).ok()?);
+        self.misses += 1;
+        self.records.insert(path.to_path_buf(), HashRecord {
+            size: meta.len(),
+            modified,
+            hash: hash.clone(),
+            used: now,
+        });
+        Some(hash)
+    }
+    
+    /// Drop records unused for `max_age`, then the least recently used past `max_records`
+    /// Returns the number of records dropped
+    pub fn prune(&mut self, now: u64, max_age: Duration, max_records: usize) -> usize {
+        let before = self.records.len();
+        self.records.retain(|_, record| now.saturating_sub(record.used) <= max_age.as_secs());
+        
+        if self.records.len() > max_records {
+            let mut by_use: Vec<(u64, PathBuf)> =
+                self.records.iter().map(|(path, record)| (record.used, path.clone())).collect();
+            by_use.sort_by_key(|(used, _)| std::cmp::Reverse(*used));
+            for (_, path) in by_use.drain(max_records..) {
+                self.records.remove(&path);
+            }
+        }
+        before - self.records.len()
+    }
+    
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+}
+
+/// One diff entry as persisted (transforms and direction overrides come back with the fresh scan)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedEntry {
+    pub path: PathBuf,
+    pub source_path: PathBuf,
+    pub destination_path: PathBuf,
+    pub status: FileStatus,
+    pub diff_type: DiffType,
+    pub size: u64,
+    
+    /// Source modification time in seconds since the Unix epoch
+    #[serde(default)]
+    pub modified: Option<u64>,
+    
+    /// Line counts: (source only, destination only, modified)
+    #[serde(default)]
+    pub stats: Option<(usize, usize, usize)>,
+    
+    /// Content hash of the source file when the cache was saved (None if it couldn't be read)
+    #[serde(default)]
+    pub hash: Option<String>,
+    
+    /// Project whose mapping the entry came from
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub project: String,
+    
+    /// The entry is a directory
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_dir: bool,
+}
+
+impl CachedEntry {
+    fn from_entry(entry: &DiffEntry, hashes: &mut HashCache, now: u64) -> Self {
+        Self {
+            path: entry.path.clone(),
+            source_path: entry.source_path.clone(),
+            destination_path: entry.destination_path.clone(),
+            status: entry.status.clone(),
+            diff_type: entry.diff_type.clone(),
+            size: entry.size,
+            modified: entry.modified.map(unix_secs),
+            stats: entry.stats.map(|stats| (stats.source_only, stats.dest_only, stats.modified)),
+            hash: hashes.hash(&entry.source_path, now),
+            project: entry.project.clone(),
+            is_dir: entry.is_dir,
+        }
+    }
+    
+    fn into_entry(self) -> DiffEntry {
+        DiffEntry {
+            path: self.path,
+            source_path: self.source_path,
+            destination_path: self.destination_path,
+            status: self.status,
+            diff_type: self.diff_type,
+            size: self.size,
+            modified: self.modified.map(|secs| UNIX_EPOCH + Duration::from_secs(secs)),
+            stats: self.stats.map(|(source_only, dest_only, modified)| DiffStats {
+                source_only,
+                dest_only,
+                modified,
+            }),
+            transform: None,
+            direction_override: None,
+            project: self.project,
+            is_dir: self.is_dir,
+            secret: None,
+        }
+    }
+}
+
+impl DiffCache {
+    /// Cache of the two diff lists, hashing their source files through `hashes`
+    pub fn from_lists(shared_to_project: &[DiffEntry], project_to_shared: &[DiffEntry], mut hashes: HashCache) -> Self {
+        let now = unix_secs(SystemTime::now());
+        let mut cached = |entries: &[DiffEntry]| -> Vec<CachedEntry> {
+            entries.iter().map(|entry| CachedEntry::from_entry(entry, &mut hashes, now)).collect()
+        };
+        let shared_to_project = cached(shared_to_project);
+        let project_to_shared = cached(project_to_shared);
+        Self {
+            version: DIFF_CACHE_VERSION,
+            shared_to_project,
+            project_to_shared,
+            hashes,
+        }
+    }
+    
+    /// The cached diff lists and the hash cache to carry into the next save
+    pub fn into_parts(self) -> (DiffLists, HashCache) {
+        let lists = (
+            self.shared_to_project.into_iter().map(CachedEntry::into_entry).collect(),
+            self.project_to_shared.into_iter().map(CachedEntry::into_entry).collect(),
+        );
+        (lists, self.hashes)
+    }
+    
+    /// Path of the diff cache file for a workspace
+    pub fn path_for(workspace_root: &Path) -> PathBuf {
+        workspace_root.join(STATE_DIR_NAME).join(DIFF_CACHE_FILE_NAME)
+    }
+    
+    /// Load the cache of a workspace (None if there is none)
+    /// A corrupt cache, or one of another format version, is logged and deleted so the next save rebuilds it
+    pub fn load(workspace_root: &Path) -> Option<Self> {
+        let path = Self::path_for(workspace_root);
+        if !path.exists() {
+            append_log(&log_path(workspace_root), "diff cache: none yet, scanning before startup");
+            return None;
+        }
+        
+        let problem = match Self::load_from(&path) {
+            Ok(cache) if cache.version == DIFF_CACHE_VERSION => {
+                append_log(&log_path(workspace_root), &format!(
+                    "diff cache: loaded {} entries and {} hashes",
+                    cache.shared_to_project.len() + cache.project_to_shared.len(),
+                    cache.hashes.len(),
+                ));
+                return Some(cache);
+            }
+            Ok(cache) => format!("version {} (expected {})", cache.version, DIFF_CACHE_VERSION),
+            Err(err) => format!("{:#}", err),
+        };
+        append_log(&log_path(workspace_root), &format!("diff cache: rebuilding unusable cache: {}", problem));
+        let _ = fs::remove_file(&path);
+        None
+    }
+    
+    /// Load a cache from an explicit file path
+    pub fn load_from(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read diff cache: {}", path.display()))?;
+        
+        serde_yaml::from_str(&content).context("Failed to parse diff cache YAML")
+    }
+    
+    /// Prune the hash cache and save the cache of a workspace, creating the state directory if needed
+    /// The hash hit rate of this run is logged
+    pub fn save(&mut self, workspace_root: &Path) -> Result<()> {
+        let pruned = self.hashes.prune(unix_secs(SystemTime::now()), MAX_HASH_AGE, MAX_HASH_RECORDS);
+        let lookups = self.hashes.hits + self.hashes.misses;
+        append_log(&log_path(workspace_root), &format!(
+            "diff cache: saved {} entries; hashes {} hits / {} misses ({}% hit rate), {} pruned, {} kept",
+            self.shared_to_project.len() + self.project_to_shared.len(),
+            self.hashes.hits,
+            self.hashes.misses,
+            (self.hashes.hits * 100).checked_div(lookups).unwrap_or(0),
+            pruned,
+            self.hashes.len(),
+        ));
+        self.save_to(&Self::path_for(workspace_root))
+    }
+    
+    /// Save the cache to an explicit file path
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+        }
+        
+        let content = serde_yaml::to_string(self).context("Failed to serialize diff cache")?;
+        
+        fs::write(path, content)
+            .with_context(|| format!("Failed to write diff cache: {}", path.display()))?;
+        
+        Ok(())
+    }
+}
+
+/// Seconds since the Unix epoch (0 for earlier times)
+fn unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).map(|age| age.as_secs()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::SystemTime;
+
+    /// Cached modification times keep whole seconds
+    fn whole_seconds(time: SystemTime) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(time.duration_since(UNIX_EPOCH).unwrap().as_secs())
+    }
+
+    #[test]
+    fn test_diff_cache_round_trip() {
+        let dir = std::env::temp_dir().join(format!("sync-manager-diff-cache-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("a.md"), "shared").unwrap();
+
+        let modified = SystemTime::now();
+        let entry = DiffEntry {
+            source_path: dir.join("a.md"),
+            destination_path: dir.join("project/a.md"),
+            size: 6,
+            modified: Some(modified),
+            stats: Some(DiffStats { source_only: 1, dest_only: 2, modified: 3 }),
+            ..DiffEntry::for_test("a.md", "")
+        };
+
+        let path = dir.join(DIFF_CACHE_FILE_NAME);
+        let cache = DiffCache::from_lists(std::slice::from_ref(&entry), &[], HashCache::default());
+        assert_eq!(cache.shared_to_project[0].hash, Some(content_hash(b"shared")));
+        assert_eq!((cache.hashes.hits, cache.hashes.misses), (0, 1));
+        cache.save_to(&path).unwrap();
+
+        let loaded = DiffCache::load_from(&path).unwrap();
+        assert_eq!(loaded.version, DIFF_CACHE_VERSION);
+        let ((shared_to_project, project_to_shared), hashes) = loaded.into_parts();
+
+        // An unchanged source file is hashed from the record
+        let cache = DiffCache::from_lists(&[entry], &[], hashes);
+        assert_eq!((cache.hashes.hits, cache.hashes.misses), (1, 0));
+        let _ = fs::remove_dir_all(&dir);
+
+        assert!(project_to_shared.is_empty());
+        let loaded = &shared_to_project[0];
+        assert_eq!(loaded.path, PathBuf::from("a.md"));
+        assert_eq!(loaded.status, FileStatus::Modified);
+        assert_eq!(loaded.diff_type, DiffType::SharedToProject);
+        assert_eq!(loaded.modified, Some(whole_seconds(modified)));
+        assert_eq!(loaded.stats.map(|stats| stats.dest_only), Some(2));
+    }
+
+    #[test]
+    fn test_prune_drops_old_then_least_recently_used() {
+        let record = |used| HashRecord { size: 0, modified: 0, hash: String::new(), used };
+        let mut hashes = HashCache::default();
+        hashes.records.insert(PathBuf::from("old"), record(0));
+        hashes.records.insert(PathBuf::from("a"), record(900));
+        hashes.records.insert(PathBuf::from("b"), record(950));
+        hashes.records.insert(PathBuf::from("c"), record(1_000));
+
+        assert_eq!(hashes.prune(1_000, Duration::from_secs(500), 2), 2);
+        assert_eq!(hashes.records.keys().collect::<Vec<_>>(), [Path::new("b"), Path::new("c")]);
+    }
+
+    #[test]
+    fn test_corrupt_cache_is_rebuilt() {
+        let root = std::env::temp_dir().join(format!("sync-manager-diff-cache-corrupt-{}", std::process::id()));
+        let path = DiffCache::path_for(&root);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "shared_to_project: [not: an entry").unwrap();
+
+        assert!(DiffCache::load(&root).is_none());
+        assert!(!path.exists());
+        let log = fs::read_to_string(log_path(&root)).unwrap();
+        let _ = fs::remove_dir_all(&root);
+        assert!(log.contains("rebuilding unusable cache"));
+    }
+}
@@ -0,0 +1,262 @@
+// Diff Scan
+// Computes both diff lists of a project, in place or on a background thread, with the files
+// walked per project for the progress shown while the first scan of a launch runs
+
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Instant;
+
+use super::app::DiffSortOrder;
+use super::project_config::ProjectConfig;
+use crate::operations::{CancellationToken, DiffEngine, DiffEntry, DiffType, FileStatus, SecretScanner, TransformPipeline, Watchdog};
+
+/// Diff lists in both directions: (shared -> project, project -> shared)
+pub type DiffLists = (Vec<DiffEntry>, Vec<DiffEntry>);
+
+/// Everything a scan needs, owned so it can move to another thread
+#[derive(Debug)]
+pub struct DiffScan {
+    pub config: ProjectConfig,
+    /// (name, root directory) of each project to scan, in order
+    pub projects: Vec<(String, PathBuf)>,
+    pub engine: DiffEngine,
+    pub sort_order: DiffSortOrder,
+}
+
+impl DiffScan {
+    /// Compare every mapping of the projects in both directions
+    /// A cancelled scan stops early, so its lists are incomplete
+    pub fn run(&self) -> DiffLists {
+        self.scan(None)
+    }
+    
+    /// `run`, telling `progress` when each project starts and when the scan ends
+    pub fn run_with_progress(&self, progress: &ScanProgress) -> DiffLists {
+        let lists = self.scan(Some(progress));
+        progress.finish();
+        lists
+    }
+    
+    fn scan(&self, progress: Option<&ScanProgress>) -> DiffLists {
+        let mut shared_to_project_diffs = Vec::new();
+        let mut project_to_shared_diffs = Vec::new();
+        // Invalid secret patterns are left out here too; the health check reports them
+        let secrets = SecretScanner::new(self.config.global_settings.secrets.as_ref());
+        
+        // A local config that can't be read is left out here; the health check reports it
+        let mappings = self.projects.iter().enumerate().flat_map(|(index, (name, root))| {
+            let mappings = self.config.effective_mappings(root, name).unwrap_or_else(|_| {
+                self.config.get_project_mappings(name).into_iter().cloned().collect()
+            });
+            mappings.into_iter().map(move |mapping| (index, name, root, mapping))
+        });
+        for (index, project_name, project_root, mapping) in mappings {
+            if self.engine.is_cancelled() {
+                break;
+            }
+            if let Some(progress) = progress {
+                progress.enter(index);
+            }
+            let (shared_path, project_path) = self.config.mapping_paths(project_root, &mapping);
+            let engine = self.engine.clone().with_repo_boundaries(self.config.stops_at_nested_repos(project_name));
+            
+            // Get exclude patterns and token substitutions for this mapping
+            let mapping_excludes: Vec<String> = mapping.exclude.clone();
+            let transforms = TransformPipeline::new(mapping.transforms.clone())
+                .with_token("project_name", project_name);
+            
+            // Compute diffs in both directions
+            let mut shared_to_proj = engine.compute_diff(
+                &shared_path,
+                &project_path,
+                DiffType::SharedToProject,
+                &mapping_excludes,
+                &mapping.include,
+                &transforms,
+            ).unwrap_or_default();
+            
+            let mut proj_to_shared = engine.compute_diff(
+                &project_path,
+                &shared_path,
+                DiffType::ProjectToShared,
+                &mapping_excludes,
+                &mapping.include,
+                &transforms,
+            ).unwrap_or_default();
+            
+            for diff in shared_to_proj.iter_mut().chain(proj_to_shared.iter_mut()) {
+                diff.direction_override = mapping.direction_override(&diff.path);
+                diff.project = project_name.clone();
+                // Only files the sync would write are checked
+                let written = !diff.is_dir && !matches!(diff.status, FileStatus::Unchanged | FileStatus::Deleted);
+                if let Some(secrets) = secrets.as_ref().filter(|_| written) {
+                    diff.secret = secrets.check(&diff.source_path);
+                }
+            }
+            
+            shared_to_project_diffs.extend(shared_to_proj);
+            project_to_shared_diffs.extend(proj_to_shared);
+        }
+        
+        self.sort_order.sort(&mut shared_to_project_diffs);
+        self.sort_order.sort(&mut project_to_shared_diffs);
+        (shared_to_project_diffs, project_to_shared_diffs)
+    }
+}
+
+/// Where a scan of one project stands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProjectScanState {
+    Waiting,
+    Scanning,
+    Done,
+}
+
+/// Progress of one project of a scan
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectScan {
+    pub name: String,
+    /// Files and directories walked in the project so far
+    pub walked: usize,
+    pub state: ProjectScanState,
+}
+
+/// Files walked per project by a running scan, read from the UI thread
+/// The walk itself only beats the watchdog; the count at each project's start splits its
+/// total between the projects
+#[derive(Debug)]
+pub struct ScanProgress {
+    watchdog: Arc<Watchdog>,
+    names: Vec<String>,
+    /// Walked count when each project reached so far started; one more once the scan ended
+    starts: Mutex<Vec<usize>>,
+}
+
+impl ScanProgress {
+    /// Progress of a scan of the projects `names`, counting the beats of `watchdog`
+    pub fn new(names: Vec<String>, watchdog: Arc<Watchdog>) -> Self {
+        Self {
+            watchdog,
+            names,
+            starts: Mutex::new(Vec::new()),
+        }
+    }
+    
+    fn starts(&self) -> std::sync::MutexGuard<'_, Vec<usize>> {
+        self.starts.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+    
+    /// The scan got to project `index` (projects before it without mappings are done as well)
+    pub fn enter(&self, index: usize) {
+        let walked = self.watchdog.walked();
+        let mut starts = self.starts();
+        while starts.len() <= index.min(self.names.len()) {
+            starts.push(walked);
+        }
+    }
+    
+    /// The scan ended: every project is done
+    pub fn finish(&self) {
+        self.enter(self.names.len());
+    }
+    
+    /// Every project of the scan in order, with its files walked so far
+    pub fn projects(&self) -> Vec<ProjectScan> {
+        let walked = self.watchdog.walked();
+        let starts = self.starts();
+        self.names
+            .iter()
+            .enumerate()
+            .map(|(index, name)| {
+                let (walked, state) = match (starts.get(index), starts.get(index + 1)) {
+                    (Some(start), Some(end)) => (end - start, ProjectScanState::Done),
+                    (Some(start), None) => (walked - start, ProjectScanState::Scanning),
+                    _ => (0, ProjectScanState::Waiting),
+                };
+                ProjectScan {
+                    name: name.clone(),
+                    walked,
+                    state,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Scan running on a background thread, polled each frame until it finishes
+#[derive(Debug)]
+pub struct ScanTask {
+    /// Heartbeat of the scan, for noticing it got stuck
+    pub watchdog: Arc<Watchdog>,
+    
+    /// Files walked per project so far
+    pub progress: Arc<ScanProgress>,
+    
+    /// Stop request; a cancelled scan's lists are incomplete and should be discarded
+    pub cancel: CancellationToken,
+    
+    /// When the scan started
+    pub started: Instant,
+    
+    handle: JoinHandle<DiffLists>,
+}
+
+impl ScanTask {
+    /// Start running `scan` on a new thread
+    pub fn start(mut scan: DiffScan) -> Self {
+        let watchdog = Arc::new(Watchdog::default());
+        let cancel = CancellationToken::new();
+        scan.engine = scan.engine.with_watchdog(Arc::clone(&watchdog)).with_cancellation(cancel.clone());
+        let names = scan.projects.iter().map(|(name, _)| name.clone()).collect();
+        let progress = Arc::new(ScanProgress::new(names, Arc::clone(&watchdog)));
+        let reported = Arc::clone(&progress);
+        Self {
+            watchdog,
+            progress,
+            cancel,
+            started: Instant::now(),
+            handle: std::thread::spawn(move || scan.run_with_progress(&reported)),
+        }
+    }
+    
+    pub fn is_finished(&self) -> bool {
+        self.handle.is_finished()
+    }
+    
+    /// Wait for the scan and return its lists
+    pub fn finish(self) -> DiffLists {
+        self.handle.join().expect("diff scan thread panicked")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn test_files_walked_are_split_by_project() {
+        let watchdog = Arc::new(Watchdog::default());
+        let names = ["web", "api", "docs"].map(String::from).to_vec();
+        let progress = ScanProgress::new(names, Arc::clone(&watchdog));
+        let states = |progress: &ScanProgress| -> Vec<(usize, ProjectScanState)> {
+            progress.projects().into_iter().map(|project| (project.walked, project.state)).collect()
+        };
+        assert_eq!(states(&progress)[0], (0, ProjectScanState::Waiting));
+
+        progress.enter(0);
+        watchdog.beat(Path::new("web/a.md"));
+        watchdog.beat(Path::new("web/b.md"));
+        assert_eq!(states(&progress)[0], (2, ProjectScanState::Scanning));
+
+        // api has no mappings, so the scan goes from web straight to docs
+        progress.enter(2);
+        watchdog.beat(Path::new("docs/c.md"));
+        progress.finish();
+        assert_eq!(
+            states(&progress),
+            [(2, ProjectScanState::Done), (0, ProjectScanState::Done), (1, ProjectScanState::Done)]
+        );
+    }
+}
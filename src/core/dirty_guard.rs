@@ -0,0 +1,110 @@
+// Dirty Destination Guard
+// Before a sync into a git repository, find the uncommitted changes in its way (anywhere in
+// the working tree, or only in the files the sync touches) so they can be stashed first
+
+use anyhow::Result;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use super::review_branch::{existing_dir, repo_relative};
+use crate::operations::{DiffEntry, GitOps};
+
+/// Values of `defaults.clean_destination`
+pub const CLEAN_DESTINATION_CHECKS: [&str; 3] = ["none", "repository", "paths"];
+
+/// Message of the stashes made before a sync
+pub const STASH_MESSAGE: &str = "sync-manager: changes in the way of a sync";
+
+/// Uncommitted files of one destination repository that are in the way of a sync
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirtyRepo {
+    /// Root of the working tree
+    pub repo: PathBuf,
+    /// Paths relative to the root
+    pub files: Vec<String>,
+}
+
+/// Dirty files in the way of touching `touched`: all of them when checking the "repository",
+/// those touched (or inside a touched directory, given with a trailing `/`) when checking "paths"
+pub fn in_the_way(dirty: Vec<String>, touched: &[String], check: &str) -> Vec<String> {
+    match check {
+        "repository" => dirty,
+        "paths" => dirty
+            .into_iter()
+            .filter(|file| {
+                touched
+                    .iter()
+                    .any(|path| path == file || (path.ends_with('/') && file.starts_with(path.as_str())))
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Repositories the destinations of `entries` are in, with the entries' paths relative to
+/// each root (destinations outside a repository are left out)
+fn destination_repos(entries: &[DiffEntry]) -> BTreeMap<PathBuf, Vec<String>> {
+    let mut roots: BTreeMap<&Path, Option<PathBuf>> = BTreeMap::new();
+    let mut repos: BTreeMap<PathBuf, Vec<String>> = BTreeMap::new();
+    for entry in entries {
+        let Some(dir) = existing_dir(&entry.destination_path) else {
+            continue;
+        };
+        let root = roots
+            .entry(dir)
+            .or_insert_with(|| {
+                let toplevel = GitOps::toplevel(dir).ok()?;
+                Some(std::fs::canonicalize(&toplevel).unwrap_or(toplevel))
+            })
+            .clone();
+        let Some(root) = root else {
+            continue;
+        };
+        let Some(mut path) = repo_relative(&root, &entry.destination_path) else {
+            continue;
+        };
+        if entry.is_dir {
+            path.push('/');
+        }
+        repos.entry(root).or_default().push(path);
+    }
+    repos
+}
+
+/// Uncommitted files in the way of syncing `entries`, by repository (empty when `check` is
+/// "none" or nothing is in the way)
+pub fn dirty_destinations(entries: &[DiffEntry], check: &str) -> Result<Vec<DirtyRepo>> {
+    if !matches!(check, "repository" | "paths") {
+        return Ok(Vec::new());
+    }
+    let mut dirty = Vec::new();
+    for (repo, touched) in destination_repos(entries) {
+        let files = in_the_way(GitOps::backend().dirty_files(&repo)?, &touched, check);
+        if !files.is_empty() {
+            dirty.push(DirtyRepo { repo, files });
+        }
+    }
+    Ok(dirty)
+}
+
+/// Stash the files in the way in each repository
+pub fn stash(dirty: &[DirtyRepo]) -> Result<()> {
+    for repo in dirty {
+        GitOps::stash(&repo.repo, &repo.files, STASH_MESSAGE)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_in_the_way() {
+        let dirty = || vec!["rules/a.md".to_string(), "notes.md".to_string(), "templates/new/b.md".to_string()];
+        let touched = ["rules/a.md".to_string(), "rules/c.md".to_string(), "templates/new/".to_string()];
+        assert_eq!(in_the_way(dirty(), &touched, "paths"), ["rules/a.md", "templates/new/b.md"]);
+        assert_eq!(in_the_way(dirty(), &touched, "repository").len(), 3);
+        assert!(in_the_way(dirty(), &touched, "none").is_empty());
+    }
+}
@@ -0,0 +1,159 @@
+// Entry Context Menu
+// Actions offered on the selected diff entry (opened with `m` or a right click)
+
+use std::path::Path;
+use tui_components::{ContextMenu, MenuItem};
+
+use super::project_config::DirectionOverride;
+use crate::operations::{is_structured, DiffEntry, FileCommit};
+
+/// Number of commits listed by "Show history"
+pub const HISTORY_LIMIT: usize = 20;
+
+/// Characters of a commit summary shown in the history menu
+const HISTORY_SUMMARY_WIDTH: usize = 48;
+
+/// Action picked from the entry context menu
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EntryAction {
+    /// Sync the entry (asks for confirmation like `s`)
+    Sync,
+    /// Add the entry's path to its mapping's exclude list
+    ExcludeFile,
+    /// Add the directory containing the entry to its mapping's exclude list
+    ExcludeDirectory,
+    /// Add the entry's path to its mapping's pinned list
+    Pin,
+    /// Open the destination file in the editor
+    OpenInEditor,
+    /// Copy the source path to the clipboard
+    CopyPath,
+    /// Recent git commits of the destination file
+    ShowHistory,
+    /// Why the scan flagged the entry
+    Explain,
+    /// Attach a note to the entry
+    Annotate,
+    /// Compare a JSON / YAML entry by keys, or by lines again
+    CompareByKeys,
+    /// Compare the source with the destination as of a commit (index in the history menu)
+    CompareWithCommit(usize),
+}
+
+/// Menu for a diff entry, opened at `anchor`
+/// Items that can't apply to the entry (syncing a pinned file, editing a missing file) are disabled,
+/// as are the items that write files when not `writable` (read-only mode) and the history
+/// without `git` (git not found)
+pub fn entry_menu(diff: &DiffEntry, anchor: (u16, u16), writable: bool, git: bool) -> ContextMenu<EntryAction> {
+    let title = file_name(diff);
+    let pinned = diff.direction_override == Some(DirectionOverride::Pinned);
+
+    ContextMenu::new(title, anchor)
+        .with_item(MenuItem::new("Sync", EntryAction::Sync).with_key('s').with_enabled(writable && !diff.is_blocked()))
+        .with_item(MenuItem::new("Exclude file", EntryAction::ExcludeFile).with_key('x').with_enabled(writable))
+        .with_item(
+            MenuItem::new("Exclude directory", EntryAction::ExcludeDirectory)
+                .with_key('X')
+                .with_enabled(writable && entry_directory(diff).is_some()),
+        )
+        .with_item(MenuItem::new("Pin", EntryAction::Pin).with_key('p').with_enabled(writable && !pinned))
+        .with_item(
+            MenuItem::new("Open in editor", EntryAction::OpenInEditor)
+                .with_key('e')
+                .with_enabled(writable && diff.destination_path.exists()),
+        )
+        .with_item(MenuItem::new("Copy path", EntryAction::CopyPath).with_key('c'))
+        .with_item(MenuItem::new("Show history", EntryAction::ShowHistory).with_key('h').with_enabled(git))
+        .with_item(MenuItem::new("Why modified?", EntryAction::Explain).with_key('w'))
+        .with_item(MenuItem::new("Note…", EntryAction::Annotate).with_key('n'))
+        .with_item(
+            MenuItem::new("Compare by keys", EntryAction::CompareByKeys)
+                .with_key('k')
+                .with_enabled(is_structured(&diff.path)),
+        )
+}
+
+/// Commits of the entry's destination file, newest first; picking one (or its digit, for
+/// the first nine) compares the source with the file as of that commit
+pub fn history_menu(diff: &DiffEntry, commits: &[FileCommit], anchor: (u16, u16)) -> ContextMenu<EntryAction> {
+    commits.iter().enumerate().fold(
+        ContextMenu::new(format!("History: {}", file_name(diff)), anchor),
+        |menu, (index, commit)| {
+            let summary: String = commit.summary.chars().take(HISTORY_SUMMARY_WIDTH).collect();
+            let label = format!("{} {} {}", commit.hash, commit.date, summary);
+            let item = MenuItem::new(label, EntryAction::CompareWithCommit(index));
+            match char::from_digit(index as u32 + 1, 10) {
+                Some(key) => menu.with_item(item.with_key(key)),
+                None => menu.with_item(item),
+            }
+        },
+    )
+}
+
+/// File name of the entry, for menu titles
+fn file_name(diff: &DiffEntry) -> String {
+    diff.path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| diff.path.display().to_string())
+}
+
+/// Directory of the entry relative to its mapping (None for files at the mapping root)
+fn entry_directory(diff: &DiffEntry) -> Option<&Path> {
+    diff.path.parent().filter(|dir| !dir.as_os_str().is_empty())
+}
+
+/// Pattern that selects this entry (or its directory) in its mapping's pinned or exclude list
+/// Pinned patterns match the mapping-relative path; exclude patterns match the full path, so
+/// they start at a path separator to avoid matching longer names ("/a.md" vs "data.md")
+pub fn entry_pattern(diff: &DiffEntry, action: EntryAction) -> Option<String> {
+    let slashed = |path: &Path| path.to_string_lossy().replace('\\', "/");
+    match action {
+        EntryAction::Pin => Some(slashed(&diff.path)),
+        EntryAction::ExcludeFile => Some(format!("/{}", slashed(&diff.path))),
+        EntryAction::ExcludeDirectory => entry_directory(diff).map(|dir| format!("/{}/", slashed(dir))),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    
+    #[test]
+    fn test_read_only_menu_disables_writes() {
+        let diff = DiffEntry {
+            source_path: PathBuf::from("src/rules/a.md"),
+            destination_path: PathBuf::from("dest/rules/a.md"),
+            ..DiffEntry::for_test("rules/a.md", "")
+        };
+        
+        let menu = entry_menu(&diff, (0, 0), false, true);
+        let enabled: Vec<_> = menu.items.iter().filter(|item| item.enabled).map(|item| item.action).collect();
+        // Notes live in the state directory, which read-only mode still writes
+        assert_eq!(
+            enabled,
+            [EntryAction::CopyPath, EntryAction::ShowHistory, EntryAction::Explain, EntryAction::Annotate]
+        );
+        assert_eq!(menu.items[menu.selected].action, EntryAction::CopyPath);
+        
+        let menu = entry_menu(&diff, (0, 0), false, false);
+        assert!(!menu.items.iter().any(|item| item.enabled && item.action == EntryAction::ShowHistory));
+        
+        assert_eq!(entry_pattern(&diff, EntryAction::ExcludeDirectory).as_deref(), Some("/rules/"));
+        
+        let commit = FileCommit {
+            hash: "a1b2c3d".into(),
+            date: "2024-05-02".into(),
+            author: "Ada".into(),
+            summary: "Tweak rules".into(),
+            path: "rules/a.md".into(),
+        };
+        let menu = history_menu(&diff, &vec![commit; 10], (0, 0));
+        assert_eq!(menu.title, "History: a.md");
+        assert_eq!(menu.items[0].label, "a1b2c3d 2024-05-02 Tweak rules");
+        assert_eq!((menu.items[8].key, menu.items[9].key), (Some('9'), None));
+        assert_eq!(menu.items[9].action, EntryAction::CompareWithCommit(9));
+    }
+}
@@ -3,6 +3,8 @@
 
 use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers, MouseEvent, MouseEventKind};
 
+use super::{AppTab, ExternalAction};
+
 /// Application events that can be handled
 #[derive(Debug, Clone)]
 pub enum AppEvent {
@@ -15,15 +17,45 @@ pub enum AppEvent {
     /// Move selection down
     SelectNext,
     
-    /// Toggle between view modes
-    ToggleViewMode,
-    
     /// Toggle side-by-side diff view
     ToggleSideBySide,
     
     /// Toggle fold unchanged regions
     ToggleFold,
     
+    /// Show more context lines around changes in folded regions
+    MoreContext,
+    
+    /// Show fewer context lines around changes in folded regions
+    LessContext,
+    
+    /// Cycle diff list sort order
+    CycleSortOrder,
+    
+    /// Attach a note to the selected entry
+    Annotate,
+    
+    /// Start reviewing the current list, or finish the review under way
+    ToggleReview,
+    
+    /// Group the diff lists by status, or list them flat again
+    ToggleGrouping,
+    
+    /// Collapse or expand the status section of the selected entry
+    ToggleSection,
+    
+    /// Show the next project group in the diff lists
+    CycleProjectGroup,
+    
+    /// Show or hide the file metadata in the info panel
+    ToggleMetadata,
+    
+    /// Show or hide the blame of the destination lines in the side-by-side view
+    ToggleBlame,
+    
+    /// Open the commit history of the selected entry's destination file
+    FileHistory,
+    
     /// Scroll up by amount
     ScrollUp(usize),
     
@@ -36,6 +68,18 @@ pub enum AppEvent {
     /// Page down
     PageDown,
     
+    /// Move the list selection up by a page where PgUp scrolls a preview instead
+    ListPageUp,
+    
+    /// Move the list selection down by a page where PgDn scrolls a preview instead
+    ListPageDown,
+    
+    /// Jump to the first entry
+    SelectFirst,
+    
+    /// Jump to the last entry
+    SelectLast,
+    
     /// Go back / escape current mode
     Back,
     
@@ -48,10 +92,96 @@ pub enum AppEvent {
     /// Sync all files
     SyncAll,
     
+    /// Sync all files to a new branch of the destination repository
+    SyncToBranch,
+    
+    /// Export the files a sync would write to an archive
+    ExportArchive,
+    
+    /// Copy a path of the selected entry to the clipboard
+    CopyPath(EntrySide),
+    
+    /// Copy part of the side-by-side diff to the clipboard
+    CopyDiff(DiffCopy),
+    
+    /// Edit the destination line at the top of the side-by-side view in place
+    EditLine,
+    
+    /// Write the hunk at the top of the side-by-side view from this side into the other one
+    TakeHunk(EntrySide),
+    
+    /// Open a file of the selected entry in the external editor
+    OpenInEditor(EntrySide),
+    
+    /// Open the context menu of the selected entry
+    EntryMenu,
+    
+    /// Explain why the selected entry was flagged
+    ExplainChange,
+    
+    /// Compare the selected JSON / YAML file by keys, or by lines again
+    ToggleStructuralDiff,
+    
+    /// Preview the selected lockfile as its line diff, or as its dependency summary again
+    ToggleLockfileSummary,
+    
+    /// Ask for the filter expression of the lists
+    FilterLists,
+    
+    /// Apply the next filter preset
+    CycleFilterPreset,
+    
+    /// Save the filter and sort order as a preset
+    SaveFilterPreset,
+    
+    /// Run the health check and show its report
+    HealthCheck,
+    
+    /// Show the mappings in effect, with the local overrides merged in
+    EffectiveConfig,
+    
+    /// Test a path against the exclude and include patterns
+    TestPatterns,
+    
+    /// Show or hide the frame timing overlay
+    ToggleProfiler,
+    
+    /// Hand the terminal to a shell or suspend the process
+    External(ExternalAction),
+    
+    /// Switch to a top-level tab
+    ShowTab(AppTab),
+    
+    /// Record a new snapshot
+    NewSnapshot,
+    
+    /// Delete the selected snapshot
+    DeleteSnapshot,
+    
     /// No operation
     None,
 }
 
+/// Which file of a diff entry an action applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntrySide {
+    /// The file a sync reads
+    Source,
+    /// The file a sync writes
+    Destination,
+}
+
+/// What of the side-by-side diff is copied
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffCopy {
+    /// The line at the top of the view
+    Line,
+    /// The change block at (or after) the top of the view, in unified diff form
+    Hunk,
+    /// The file's whole diff, as in the diff view
+    File,
+}
+
 /// Event handler that converts terminal events to application events
 pub struct EventHandler;
 
@@ -77,18 +207,44 @@ impl EventHandler {
             KeyCode::Char('q') => AppEvent::Quit,
             KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => AppEvent::Quit,
             
+            // Leave the TUI: suspend like a shell job, or drop into a shell
+            KeyCode::Char('z') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                AppEvent::External(ExternalAction::Suspend)
+            }
+            KeyCode::Char('!') => AppEvent::External(ExternalAction::Shell),
+            KeyCode::Char('D') => AppEvent::HealthCheck,
+            KeyCode::Char('M') => AppEvent::EffectiveConfig,
+            KeyCode::Char('T') => AppEvent::TestPatterns,
+            KeyCode::F(12) => AppEvent::ToggleProfiler,
+            
             // Navigation
             KeyCode::Up | KeyCode::Char('k') => AppEvent::SelectPrevious,
             KeyCode::Down | KeyCode::Char('j') => AppEvent::SelectNext,
             
             // Scrolling
+            KeyCode::PageUp if key.modifiers.contains(KeyModifiers::SHIFT) => AppEvent::ListPageUp,
+            KeyCode::PageDown if key.modifiers.contains(KeyModifiers::SHIFT) => AppEvent::ListPageDown,
             KeyCode::PageUp => AppEvent::PageUp,
             KeyCode::PageDown => AppEvent::PageDown,
+            KeyCode::Home => AppEvent::SelectFirst,
+            KeyCode::End => AppEvent::SelectLast,
             
             // View toggles
-            KeyCode::Tab => AppEvent::ToggleViewMode,
             KeyCode::Enter | KeyCode::Char(' ') => AppEvent::ToggleSideBySide,
             KeyCode::Char('f') => AppEvent::ToggleFold,
+            KeyCode::Char('+') | KeyCode::Char('=') => AppEvent::MoreContext,
+            KeyCode::Char('-') => AppEvent::LessContext,
+            KeyCode::Char('o') => AppEvent::CycleSortOrder,
+            KeyCode::Char('/') => AppEvent::FilterLists,
+            KeyCode::Char('p') => AppEvent::CycleFilterPreset,
+            KeyCode::Char('P') => AppEvent::SaveFilterPreset,
+            KeyCode::Char('R') => AppEvent::ToggleReview,
+            KeyCode::Char('G') => AppEvent::ToggleGrouping,
+            KeyCode::Char('z') => AppEvent::ToggleSection,
+            KeyCode::Char('g') => AppEvent::CycleProjectGroup,
+            KeyCode::Char('i') => AppEvent::ToggleMetadata,
+            KeyCode::Char('b') => AppEvent::ToggleBlame,
+            KeyCode::Char('h') => AppEvent::FileHistory,
             
             // Back / Escape
             KeyCode::Esc => AppEvent::Back,
@@ -99,6 +255,32 @@ impl EventHandler {
             // Sync operations
             KeyCode::Char('s') => AppEvent::SyncSelected,
             KeyCode::Char('S') => AppEvent::SyncAll,
+            KeyCode::Char('B') => AppEvent::SyncToBranch,
+            KeyCode::Char('x') => AppEvent::ExportArchive,
+            
+            // Paths and external editor
+            KeyCode::Char('c') => AppEvent::CopyPath(EntrySide::Source),
+            KeyCode::Char('C') => AppEvent::CopyPath(EntrySide::Destination),
+            KeyCode::Char('y') => AppEvent::CopyDiff(DiffCopy::Line),
+            KeyCode::Char('Y') => AppEvent::CopyDiff(DiffCopy::Hunk),
+            KeyCode::Char('u') => AppEvent::CopyDiff(DiffCopy::File),
+            KeyCode::Char('e') => AppEvent::OpenInEditor(EntrySide::Destination),
+            KeyCode::Char('E') => AppEvent::OpenInEditor(EntrySide::Source),
+            KeyCode::Char('I') => AppEvent::EditLine,
+            KeyCode::Char('>') => AppEvent::TakeHunk(EntrySide::Source),
+            KeyCode::Char('<') => AppEvent::TakeHunk(EntrySide::Destination),
+            KeyCode::Char('m') | KeyCode::Menu => AppEvent::EntryMenu,
+            KeyCode::Char('w') => AppEvent::ExplainChange,
+            KeyCode::Char('K') => AppEvent::ToggleStructuralDiff,
+            KeyCode::Char('L') => AppEvent::ToggleLockfileSummary,
+            KeyCode::Char('N') => AppEvent::Annotate,
+            
+            // Tabs and snapshots
+            KeyCode::Char('1') => AppEvent::ShowTab(AppTab::Sync),
+            KeyCode::Char('2') => AppEvent::ShowTab(AppTab::Snapshots),
+            KeyCode::Char('3') => AppEvent::ShowTab(AppTab::Settings),
+            KeyCode::Char('n') => AppEvent::NewSnapshot,
+            KeyCode::Char('d') => AppEvent::DeleteSnapshot,
             
             _ => AppEvent::None,
         }
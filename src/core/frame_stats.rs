@@ -0,0 +1,128 @@
+// Frame Stats
+// Render profiling: draw, preparation and input latency timings of recent frames
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::utilities::format_duration;
+
+/// Frames kept for the averages; a summary is logged each time this many have passed
+pub const FRAME_WINDOW: usize = 120;
+
+/// Timings of recent frames, shown in the profiling overlay while `enabled`
+#[derive(Debug, Default)]
+pub struct FrameStats {
+    pub enabled: bool,
+    /// Time spent in `terminal.draw`
+    draw: Samples,
+    /// Time spent preparing a frame: polling background work, loading the selected diff and
+    /// metadata, updating the lists
+    prepare: Samples,
+    /// From reading an input event until the frame showing its effect was drawn
+    latency: Samples,
+    /// When the event awaiting its frame was read
+    pending_event: Option<Instant>,
+    /// Duration of the last diff scan (background or in place)
+    pub last_scan: Option<Duration>,
+    frames: usize,
+}
+
+/// Rolling window of durations
+#[derive(Debug, Default)]
+struct Samples(VecDeque<Duration>);
+
+impl Samples {
+    fn push(&mut self, duration: Duration) {
+        if self.0.len() == FRAME_WINDOW {
+            self.0.pop_front();
+        }
+        self.0.push_back(duration);
+    }
+    
+    fn last(&self) -> Duration {
+        self.0.back().copied().unwrap_or_default()
+    }
+    
+    fn mean(&self) -> Duration {
+        match self.0.len() {
+            0 => Duration::ZERO,
+            n => self.0.iter().sum::<Duration>() / n as u32,
+        }
+    }
+    
+    fn max(&self) -> Duration {
+        self.0.iter().max().copied().unwrap_or_default()
+    }
+    
+    /// "last / mean / max" of the window
+    fn summary(&self) -> String {
+        format!(
+            "{} / {} / {}",
+            format_duration(self.last()),
+            format_duration(self.mean()),
+            format_duration(self.max())
+        )
+    }
+}
+
+impl FrameStats {
+    /// Remember when an input event was read; its latency ends with the next drawn frame
+    pub fn event_read(&mut self, at: Instant) {
+        self.pending_event.get_or_insert(at);
+    }
+    
+    /// Record a drawn frame; returns a summary line to log when a window has passed while enabled
+    pub fn record_frame(&mut self, prepare: Duration, draw: Duration, drawn_at: Instant) -> Option<String> {
+        self.prepare.push(prepare);
+        self.draw.push(draw);
+        if let Some(read_at) = self.pending_event.take() {
+            self.latency.push(drawn_at.duration_since(read_at));
+        }
+        self.frames += 1;
+        (self.enabled && self.frames.is_multiple_of(FRAME_WINDOW)).then(|| {
+            format!(
+                "profile: last {} frames (last / mean / max): draw {}, prepare {}, input latency {}",
+                FRAME_WINDOW,
+                self.draw.summary(),
+                self.prepare.summary(),
+                self.latency.summary()
+            )
+        })
+    }
+    
+    /// Lines of the overlay
+    pub fn lines(&self) -> Vec<String> {
+        let scan = self.last_scan.map(format_duration).unwrap_or_else(|| "-".to_string());
+        vec![
+            "         last / mean / max".to_string(),
+            format!("draw     {}", self.draw.summary()),
+            format!("prepare  {}", self.prepare.summary()),
+            format!("input    {}", self.latency.summary()),
+            format!("scan     {}", scan),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_window_and_latency() {
+        let mut stats = FrameStats { enabled: true, ..FrameStats::default() };
+        let start = Instant::now();
+        stats.event_read(start);
+        stats.event_read(start + Duration::from_millis(5));
+        assert!(stats.record_frame(Duration::from_millis(1), Duration::from_millis(4), start + Duration::from_millis(20)).is_none());
+        assert_eq!(stats.latency.last(), Duration::from_millis(20));
+
+        for _ in 1..FRAME_WINDOW - 1 {
+            stats.record_frame(Duration::from_millis(1), Duration::from_millis(2), start);
+        }
+        assert_eq!(stats.draw.max(), Duration::from_millis(4));
+        let summary = stats.record_frame(Duration::ZERO, Duration::from_millis(2), start);
+        assert!(summary.is_some_and(|line| line.starts_with("profile: last 120 frames")));
+        assert_eq!(stats.draw.0.len(), FRAME_WINDOW);
+        assert_eq!(stats.latency.0.len(), 1);
+    }
+}
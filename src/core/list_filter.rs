@@ -0,0 +1,181 @@
+// List Filter
+// Filter expression narrowing the diff lists by extension, size, text vs binary and status
+
+use anyhow::{bail, Context, Result};
+use std::fmt;
+
+use crate::operations::{DiffEntry, FileStatus};
+
+/// Syntax reminder shown in the filter prompt
+pub const FILTER_SYNTAX: &str = ".rs  size>10k  size:1k..2m  text  binary  status:added,modified";
+
+/// Whether a file compares as text (with line counts) or not
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentKind {
+    Text,
+    /// Not UTF-8 text: binary, or in another encoding
+    Binary,
+}
+
+/// Parsed filter expression; an entry is listed when it matches every kind of term given
+/// (terms of the same kind are alternatives: `.rs .md` lists both)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListFilter {
+    /// Terms as entered, for the list titles
+    expression: String,
+    /// Extensions without the dot, lowercase
+    extensions: Vec<String>,
+    min_size: Option<u64>,
+    max_size: Option<u64>,
+    content: Option<ContentKind>,
+    statuses: Vec<FileStatus>,
+}
+
+/// Bytes of a size like `512`, `10k`, `1.5MB` (units of 1024)
+fn parse_size(text: &str) -> Result<u64> {
+    let lower = text.trim().to_lowercase();
+    let number_end = lower.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(lower.len());
+    let (number, unit) = lower.split_at(number_end);
+    let multiplier: u64 = match unit.trim_end_matches('b') {
+        "" => 1,
+        "k" => 1024,
+        "m" => 1024 * 1024,
+        "g" => 1024 * 1024 * 1024,
+        _ => bail!("Unknown size unit in {:?} (use b, k, m or g)", text),
+    };
+    let number: f64 = number.parse().with_context(|| format!("Invalid size: {:?}", text))?;
+    Ok((number * multiplier as f64) as u64)
+}
+
+/// Status named by a word or its list letter
+fn parse_status(text: &str) -> Result<FileStatus> {
+    Ok(match text.to_lowercase().as_str() {
+        "a" | "added" => FileStatus::Added,
+        "m" | "modified" => FileStatus::Modified,
+        "d" | "deleted" => FileStatus::Deleted,
+        "?" | "untracked" => FileStatus::Untracked,
+        "unchanged" => FileStatus::Unchanged,
+        _ => bail!("Unknown status {:?} (use added, modified, deleted or untracked)", text),
+    })
+}
+
+impl ListFilter {
+    /// Parse a whitespace-separated expression; None when it has no terms
+    pub fn parse(expression: &str) -> Result<Option<Self>> {
+        let terms: Vec<&str> = expression.split_whitespace().collect();
+        if terms.is_empty() {
+            return Ok(None);
+        }
+        let mut filter = Self {
+            expression: terms.join(" "),
+            extensions: Vec::new(),
+            min_size: None,
+            max_size: None,
+            content: None,
+            statuses: Vec::new(),
+        };
+        for term in terms {
+            let lower = term.to_lowercase();
+            if let Some(extension) = lower.strip_prefix("*.").or_else(|| lower.strip_prefix('.')) {
+                filter.extensions.push(extension.to_string());
+            } else if let Some(extensions) = lower.strip_prefix("ext:") {
+                filter.extensions.extend(extensions.split(',').map(|e| e.trim_start_matches('.').to_string()));
+            } else if let Some(size) = lower.strip_prefix("size>") {
+                filter.min_size = Some(parse_size(size)?.saturating_add(1));
+            } else if let Some(size) = lower.strip_prefix("size<") {
+                filter.max_size = Some(parse_size(size)?.saturating_sub(1));
+            } else if let Some(range) = lower.strip_prefix("size:") {
+                let Some((min, max)) = range.split_once("..") else {
+                    bail!("Size ranges read size:MIN..MAX, e.g. size:1k..2m");
+                };
+                // Either end may be left open: size:1m.. or size:..10k
+                let bound = |text: &str| Some(text).filter(|text| !text.is_empty()).map(parse_size).transpose();
+                filter.min_size = bound(min)?;
+                filter.max_size = bound(max)?;
+            } else if lower == "text" {
+                filter.content = Some(ContentKind::Text);
+            } else if lower == "binary" {
+                filter.content = Some(ContentKind::Binary);
+            } else if let Some(statuses) = lower.strip_prefix("status:") {
+                for status in statuses.split(',') {
+                    filter.statuses.push(parse_status(status)?);
+                }
+            } else {
+                bail!("Unknown filter term {:?} (try {})", term, FILTER_SYNTAX);
+            }
+        }
+        Ok(Some(filter))
+    }
+    
+    /// Whether the entry passes the filter
+    /// Directories have no extension, size or content, so only a status-only filter lists them
+    pub fn matches(&self, entry: &DiffEntry) -> bool {
+        if !self.statuses.is_empty() && !self.statuses.contains(&entry.status) {
+            return false;
+        }
+        let by_file = !self.extensions.is_empty() || self.min_size.is_some() || self.max_size.is_some() || self.content.is_some();
+        if entry.is_dir {
+            return !by_file;
+        }
+        if !self.extensions.is_empty() {
+            let extension = entry.path.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default();
+            if !self.extensions.contains(&extension) {
+                return false;
+            }
+        }
+        if self.min_size.is_some_and(|min| entry.size < min) || self.max_size.is_some_and(|max| entry.size > max) {
+            return false;
+        }
+        match self.content {
+            Some(ContentKind::Text) => entry.stats.is_some(),
+            Some(ContentKind::Binary) => entry.stats.is_none(),
+            None => true,
+        }
+    }
+}
+
+impl fmt::Display for ListFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.expression)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operations::DiffStats;
+
+    fn entry(path: &str, status: FileStatus, size: u64, text: bool) -> DiffEntry {
+        DiffEntry {
+            status,
+            size,
+            stats: text.then(DiffStats::default),
+            ..DiffEntry::for_test(path, "")
+        }
+    }
+
+    #[test]
+    fn test_filter_terms() {
+        let source = entry("src/main.RS", FileStatus::Modified, 20 * 1024, true);
+        let image = entry("logo.png", FileStatus::Added, 3 * 1024 * 1024, false);
+        let notes = entry("notes.md", FileStatus::Added, 300, true);
+
+        let filter = ListFilter::parse("  .rs   status:m ").unwrap().unwrap();
+        assert_eq!(filter.to_string(), ".rs status:m");
+        assert!(filter.matches(&source) && !filter.matches(&notes));
+
+        let filter = ListFilter::parse("size>10k text").unwrap().unwrap();
+        assert!(filter.matches(&source) && !filter.matches(&image) && !filter.matches(&notes));
+
+        let filter = ListFilter::parse("binary size:1m..").unwrap().unwrap();
+        assert!(filter.matches(&image) && !filter.matches(&source));
+
+        let filter = ListFilter::parse("ext:md,png status:added").unwrap().unwrap();
+        assert!(filter.matches(&image) && filter.matches(&notes) && !filter.matches(&source));
+
+        assert!(ListFilter::parse("   ").unwrap().is_none());
+        assert!(ListFilter::parse("size>10q").is_err());
+        assert!(ListFilter::parse("status:renamed").is_err());
+        assert!(ListFilter::parse("huge").is_err());
+    }
+}
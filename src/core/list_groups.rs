@@ -0,0 +1,127 @@
+// List Groups
+// Status sections of the diff lists (Added / Modified / Deleted / Conflict / Untracked),
+// each in the current sort order
+
+use ratatui::style::Style;
+use tui_components::ListSection;
+
+use crate::operations::{DiffEntry, FileStatus};
+use crate::ui::Styles;
+
+/// Section of an entry when the lists are grouped by status
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum StatusGroup {
+    Added,
+    Modified,
+    Deleted,
+    /// Modified, and the destination was changed after the source: syncing overwrites that edit
+    Conflict,
+    Untracked,
+    Unchanged,
+}
+
+/// Whether the destination of a modified entry was changed after its source
+pub fn destination_is_newer(diff: &DiffEntry) -> bool {
+    if diff.status != FileStatus::Modified {
+        return false;
+    }
+    let dest_modified = std::fs::metadata(&diff.destination_path).and_then(|m| m.modified());
+    matches!((diff.modified, dest_modified), (Some(source), Ok(dest)) if dest > source)
+}
+
+impl StatusGroup {
+    /// Group of an entry (checks the destination's modification time of modified entries)
+    pub fn of(diff: &DiffEntry) -> Self {
+        match diff.status {
+            FileStatus::Added => Self::Added,
+            FileStatus::Modified if destination_is_newer(diff) => Self::Conflict,
+            FileStatus::Modified => Self::Modified,
+            FileStatus::Deleted => Self::Deleted,
+            FileStatus::Untracked => Self::Untracked,
+            FileStatus::Unchanged => Self::Unchanged,
+        }
+    }
+    
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Added => "Added",
+            Self::Modified => "Modified",
+            Self::Deleted => "Deleted",
+            Self::Conflict => "Conflict",
+            Self::Untracked => "Untracked",
+            Self::Unchanged => "Unchanged",
+        }
+    }
+    
+    fn style(self) -> Style {
+        match self {
+            Self::Added => Styles::status_added(),
+            Self::Modified => Styles::status_modified(),
+            Self::Deleted => Styles::status_deleted(),
+            Self::Conflict => Styles::status_conflict(),
+            Self::Untracked => Styles::status_untracked(),
+            Self::Unchanged => Styles::status_unchanged(),
+        }
+    }
+}
+
+/// Move sorted `diffs` into status groups, keeping the order within each group,
+/// and return the section of each group that has entries
+pub fn group_by_status(diffs: &mut Vec<DiffEntry>) -> Vec<ListSection> {
+    let mut grouped: Vec<(StatusGroup, DiffEntry)> = std::mem::take(diffs)
+        .into_iter()
+        .map(|diff| (StatusGroup::of(&diff), diff))
+        .collect();
+    // Stable, so each group keeps the sort order
+    grouped.sort_by_key(|(group, _)| *group);
+    
+    let mut sections: Vec<(StatusGroup, ListSection)> = Vec::new();
+    for (index, (group, _)) in grouped.iter().enumerate() {
+        match sections.last_mut() {
+            Some((last, section)) if last == group => section.len += 1,
+            _ => sections.push((*group, ListSection::new(group.label(), index, 1).with_style(group.style()))),
+        }
+    }
+    *diffs = grouped.into_iter().map(|(_, diff)| diff).collect();
+    sections.into_iter().map(|(_, section)| section).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use std::time::SystemTime;
+
+    fn entry(path: &str, status: FileStatus, destination: PathBuf) -> DiffEntry {
+        DiffEntry {
+            destination_path: destination,
+            status,
+            modified: Some(SystemTime::UNIX_EPOCH),
+            ..DiffEntry::for_test(path, "")
+        }
+    }
+
+    #[test]
+    fn test_group_by_status() {
+        let dir = std::env::temp_dir().join(format!("sync-manager-groups-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let edited = dir.join("edited.md");
+        std::fs::write(&edited, "changed after the source").unwrap();
+        let missing = dir.join("missing.md");
+
+        let mut diffs = vec![
+            entry("a.md", FileStatus::Deleted, missing.clone()),
+            entry("b.md", FileStatus::Modified, edited),
+            entry("c.md", FileStatus::Added, missing.clone()),
+            entry("d.md", FileStatus::Modified, missing.clone()),
+            entry("e.md", FileStatus::Added, missing),
+        ];
+        let sections = group_by_status(&mut diffs);
+
+        let paths: Vec<_> = diffs.iter().map(|d| d.path.to_string_lossy().into_owned()).collect();
+        assert_eq!(paths, ["c.md", "e.md", "d.md", "a.md", "b.md"]);
+        let titles: Vec<_> = sections.iter().map(|s| (s.title.as_str(), s.start, s.len)).collect();
+        assert_eq!(titles, [("Added", 0, 2), ("Modified", 2, 1), ("Deleted", 3, 1), ("Conflict", 4, 1)]);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
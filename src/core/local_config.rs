@@ -0,0 +1,96 @@
+// Local Config
+// Per-project overrides read from `.sync-manager.yaml` in the project directory
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use super::project_config::Mapping;
+use crate::error::SyncManagerError;
+
+/// Local config file name, looked up in each project's root directory
+pub const LOCAL_CONFIG_NAME: &str = ".sync-manager.yaml";
+
+/// Pattern lists a project adds to every one of its mappings
+/// Includes win over the excludes of both files; pinned wins over reverse as in the main config
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct LocalConfig {
+    /// Patterns excluded from syncing
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<String>,
+    
+    /// Patterns kept even when an exclude of either file matches them
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub include: Vec<String>,
+    
+    /// Files never overwritten by a sync in either direction
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pinned: Vec<String>,
+    
+    /// Files that only sync project -> shared
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reverse: Vec<String>,
+}
+
+impl LocalConfig {
+    /// Local config of a project, or None if the project has no local config file
+    pub fn load(project_root: &Path) -> Result<Option<Self>> {
+        let path = project_root.join(LOCAL_CONFIG_NAME);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read local config: {}", path.display()))?;
+        
+        // An empty file is a valid config without overrides
+        if content.trim().is_empty() {
+            return Ok(Some(Self::default()));
+        }
+        let config = serde_yaml::from_str(&content)
+            .map_err(|err| SyncManagerError::config(&path, err))?;
+        Ok(Some(config))
+    }
+    
+    /// `mapping` with this config's patterns added to its lists
+    pub fn merge_into(&self, mapping: &Mapping) -> Mapping {
+        let merged = |global: &[String], local: &[String]| -> Vec<String> {
+            let mut patterns = global.to_vec();
+            patterns.extend(local.iter().filter(|p| !global.contains(p)).cloned());
+            patterns
+        };
+        Mapping {
+            exclude: merged(&mapping.exclude, &self.exclude),
+            include: merged(&mapping.include, &self.include),
+            pinned: merged(&mapping.pinned, &self.pinned),
+            reverse: merged(&mapping.reverse, &self.reverse),
+            ..mapping.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_load_and_merge() {
+        let dir = std::env::temp_dir().join(format!("sync-manager-local-config-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        assert_eq!(LocalConfig::load(&dir).unwrap(), None);
+        
+        fs::write(dir.join(LOCAL_CONFIG_NAME), "exclude: [\"*.log\", drafts]\ninclude: [keep.log]\n").unwrap();
+        let local = LocalConfig::load(&dir).unwrap().unwrap();
+        let mapping: Mapping = serde_yaml::from_str("shared: rules\nproject: .rules\nexclude: [drafts]").unwrap();
+        
+        let merged = local.merge_into(&mapping);
+        assert_eq!(merged.exclude, ["drafts", "*.log"]);
+        assert_eq!(merged.include, ["keep.log"]);
+        assert_eq!(merged.shared, "rules");
+        
+        fs::write(dir.join(LOCAL_CONFIG_NAME), "exclude: [unclosed").unwrap();
+        assert!(LocalConfig::load(&dir).is_err());
+        
+        fs::remove_dir_all(&dir).ok();
+    }
+}
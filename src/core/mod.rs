@@ -1,12 +1,47 @@
 // Core infrastructure module
 // Provides foundational systems that other modules depend on
 
+pub mod annotations;
 pub mod app;
 pub mod app_config;
+pub mod bookmarks;
+pub mod config_edit;
+pub mod daemon;
+pub mod diff_cache;
+pub mod diff_scan;
+pub mod dirty_guard;
+pub mod entry_menu;
+pub mod local_config;
+pub mod pattern_tester;
 pub mod project_config;
+pub mod review;
+pub mod review_branch;
 pub mod events;
+pub mod frame_stats;
+pub mod list_filter;
+pub mod list_groups;
+pub mod session;
+pub mod settings;
+pub mod setup;
+pub mod snapshots;
+pub mod sync_lock;
+pub mod sync_report;
+pub mod sync_task;
 
-pub use app::{App, ViewMode};
+pub use app::{
+    App, AppTab, Dialog, DiffSortOrder, ExternalAction, FocusTarget, StallChoice, StallPrompt, ViewMode, WatchedOperation,
+};
 pub use app_config::AppConfig;
-pub use project_config::ProjectConfig;
-pub use events::{AppEvent, EventHandler};
+pub use bookmarks::BookmarkPrompt;
+pub use diff_cache::{DiffCache, HashCache};
+pub use diff_scan::{DiffScan, ProjectScan, ProjectScanState, ScanTask};
+pub use entry_menu::EntryAction;
+pub use project_config::{ProjectConfig, ProjectIdentity};
+pub use review_branch::ReviewBranch;
+pub use events::{AppEvent, DiffCopy, EntrySide, EventHandler};
+pub use session::SessionState;
+pub use settings::SettingsTab;
+pub use setup::SetupWizard;
+pub use snapshots::SnapshotsTab;
+pub use sync_report::SyncReport;
+pub use sync_task::SyncTask;
@@ -0,0 +1,119 @@
+// Pattern Tester
+// Which exclude, include, pinned and reverse rules apply to a path, without rescanning
+
+use std::path::{Path, PathBuf};
+
+use super::project_config::{Mapping, ProjectConfig};
+use crate::operations::{DiffEngine, ExcludeRule};
+use crate::utilities::matches_pattern;
+
+/// Verdict of one mapping's patterns for the tested path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatternTest {
+    pub project: String,
+    /// Mapping as "shared → project"
+    pub mapping: String,
+    /// Path relative to the mapping
+    pub path: PathBuf,
+    pub rule: ExcludeRule,
+    /// Pinned pattern that matches the path (pinned wins over reverse)
+    pub pinned: Option<String>,
+    /// Reverse pattern that matches the path
+    pub reverse: Option<String>,
+}
+
+impl PatternTest {
+    /// Lines describing the verdict for a popup
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = vec![format!("[{}] {}: {}", self.project, self.mapping, self.path.display())];
+        lines.push(match &self.rule {
+            ExcludeRule::Kept => "  synced: no exclude matches".to_string(),
+            ExcludeRule::Included { exclude, include } => {
+                format!("  synced: exclude '{}' is overridden by include '{}'", exclude, include)
+            }
+            ExcludeRule::Excluded { rule, builtin, at } => format!(
+                "  excluded by {} '{}' (matches {})",
+                if *builtin { "built-in/global exclude" } else { "mapping exclude" },
+                rule,
+                at.display()
+            ),
+        });
+        if let Some(pattern) = &self.pinned {
+            lines.push(format!("  pinned by '{}': never synced", pattern));
+        } else if let Some(pattern) = &self.reverse {
+            lines.push(format!("  reverse by '{}': only syncs project → shared", pattern));
+        }
+        lines
+    }
+}
+
+/// Test `input` against the mappings of `projects` ((name, root) pairs)
+/// A relative input is taken as relative to every mapping; an absolute one is only tested
+/// against the mappings whose shared or project directory contains it
+pub fn test_path(config: &ProjectConfig, projects: &[(String, PathBuf)], engine: &DiffEngine, input: &str) -> Vec<PatternTest> {
+    let input = Path::new(input.trim());
+    let mut tests = Vec::new();
+    for (project, root) in projects {
+        let mappings = config
+            .effective_mappings(root, project)
+            .unwrap_or_else(|_| config.get_project_mappings(project).into_iter().cloned().collect());
+        for mapping in mappings {
+            let (shared_path, project_path) = config.mapping_paths(root, &mapping);
+            let relative = if input.is_absolute() {
+                match input.strip_prefix(&shared_path).or_else(|_| input.strip_prefix(&project_path)) {
+                    Ok(relative) => relative.to_path_buf(),
+                    Err(_) => continue,
+                }
+            } else {
+                input.to_path_buf()
+            };
+            tests.push(test_mapping(project, &mapping, &shared_path, relative, engine));
+        }
+    }
+    tests
+}
+
+/// Verdict of one mapping, checked on the shared side as the shared → project scan walks it
+fn test_mapping(project: &str, mapping: &Mapping, shared_path: &Path, relative: PathBuf, engine: &DiffEngine) -> PatternTest {
+    let first = |patterns: &[String]| patterns.iter().find(|p| matches_pattern(&relative, p)).cloned();
+    PatternTest {
+        project: project.to_string(),
+        mapping: format!("{} → {}", mapping.shared, mapping.project),
+        rule: engine.exclude_rule(shared_path, &shared_path.join(&relative), &mapping.exclude, &mapping.include),
+        pinned: first(&mapping.pinned),
+        reverse: first(&mapping.reverse),
+        path: relative,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rules_for_path() {
+        let mapping = Mapping {
+            shared: "rules".to_string(),
+            project: ".cursor/rules".to_string(),
+            exclude: vec!["drafts".to_string(), "*.tmp".to_string()],
+            include: vec!["keep.tmp".to_string()],
+            transforms: Vec::new(),
+            pinned: vec!["local.md".to_string()],
+            reverse: Vec::new(),
+        };
+        let engine = DiffEngine::new();
+        let root = Path::new("/ws/shared");
+        let test = |path: &str| test_mapping("app", &mapping, root, PathBuf::from(path), &engine);
+
+        assert_eq!(test("a.md").rule, ExcludeRule::Kept);
+        assert_eq!(test("local.md").pinned.as_deref(), Some("local.md"));
+        assert_eq!(
+            test("drafts/b.md").rule,
+            ExcludeRule::Excluded { rule: "drafts".to_string(), builtin: false, at: root.join("drafts") }
+        );
+        // The walk skips an excluded directory, so an include can't reach files inside it
+        assert!(matches!(test("drafts/keep.tmp").rule, ExcludeRule::Excluded { .. }));
+        assert!(matches!(test("notes/keep.tmp").rule, ExcludeRule::Included { .. }));
+        assert!(matches!(test("node_modules/x/y.js").rule, ExcludeRule::Excluded { builtin: true, .. }));
+    }
+}
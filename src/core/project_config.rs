@@ -3,14 +3,25 @@
 
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::path::{Path, PathBuf};
 
+use super::app::DiffSortOrder;
+use super::config_edit::add_mapping_list_item;
+use super::local_config::LocalConfig;
+use crate::error::SyncManagerError;
+use crate::utilities::{hostname, matches_pattern};
+
 /// Project-level configuration
 /// This defines what files/directories to sync for a specific project
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ProjectConfig {
+    /// Directory the config's relative paths resolve against, relative to the directory of the
+    /// config file (default: that directory)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub root: Option<String>,
+    
     /// Workspace-level settings for different projects
     #[serde(default)]
     pub workspace_settings: WorkspaceSettings,
@@ -22,6 +33,68 @@ pub struct ProjectConfig {
     /// Global settings that apply to all sync operations
     #[serde(default)]
     pub global_settings: GlobalSettings,
+    
+    /// Overrides of the built-in defaults, edited in the Settings tab
+    #[serde(default, skip_serializing_if = "UserSettings::is_empty")]
+    pub settings: UserSettings,
+    
+    /// Named lists of projects that are scanned and synced together
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub project_groups: BTreeMap<String, Vec<String>>,
+    
+    /// Named filter and sort combinations of the diff lists, cycled with `p`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub filter_presets: BTreeMap<String, FilterPreset>,
+    
+    /// Per-machine directories, picked by hostname or `SYNC_MANAGER_PROFILE`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, Profile>,
+    
+    /// Profile in effect on this machine (chosen on load)
+    #[serde(skip)]
+    pub active_profile: Option<String>,
+}
+
+/// Saved view of the diff lists
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct FilterPreset {
+    /// Filter expression (same syntax as the `/` prompt; empty lists everything)
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub filter: String,
+    
+    /// Sort order: path_asc, path_desc, largest_first, recent_first (unset keeps the current one)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sort: Option<DiffSortOrder>,
+}
+
+impl FilterPreset {
+    /// ".rs status:modified, largest first"
+    pub fn describe(&self) -> String {
+        let filter = if self.filter.is_empty() { "no filter" } else { self.filter.as_str() };
+        match self.sort {
+            Some(sort) => format!("{}, {}", filter, sort.label()),
+            None => filter.to_string(),
+        }
+    }
+}
+
+/// Environment variable naming the profile to use, whatever the hostname
+pub const PROFILE_ENV: &str = "SYNC_MANAGER_PROFILE";
+
+/// Directories that differ on one machine; set values replace those of the rest of the file
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct Profile {
+    /// Hostnames the profile is used on (case-insensitive)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hosts: Vec<String>,
+    
+    /// Replaces `root`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub root: Option<String>,
+    
+    /// Map of project name -> directory, replacing the project's `path`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub project_paths: BTreeMap<String, String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -33,6 +106,23 @@ pub struct WorkspaceSettings {
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ProjectSettings {
+    /// Color marking the project's entries: a color name ("magenta") or "#rrggbb"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    
+    /// Single character shown before the project's entries
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    
+    /// Directory of the project relative to the config's root, for group scans
+    /// (default: `../<name>`, a sibling of the root)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    
+    /// Leave git submodules and nested repositories inside the project out of scans
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub stop_at_nested_repos: bool,
+    
     /// Map of package name -> package settings
     #[serde(flatten)]
     pub packages: HashMap<String, PackageSettings>,
@@ -56,6 +146,95 @@ pub struct Mapping {
     /// Patterns to exclude from syncing
     #[serde(default)]
     pub exclude: Vec<String>,
+    
+    /// Patterns kept even when an exclude matches them
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub include: Vec<String>,
+    
+    /// Transforms applied to matching files when syncing
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub transforms: Vec<TransformRule>,
+    
+    /// Files never overwritten by a sync in either direction
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pinned: Vec<String>,
+    
+    /// Files that only sync project -> shared
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reverse: Vec<String>,
+}
+
+impl Mapping {
+    /// Patterns of one of the mapping's lists
+    pub fn patterns_mut(&mut self, list: PatternList) -> &mut Vec<String> {
+        match list {
+            PatternList::Exclude => &mut self.exclude,
+            PatternList::Pinned => &mut self.pinned,
+        }
+    }
+    
+    /// Direction override for a file (path relative to the mapping); pinned wins over reverse
+    pub fn direction_override(&self, relative_path: &Path) -> Option<DirectionOverride> {
+        let matches = |patterns: &[String]| patterns.iter().any(|p| matches_pattern(relative_path, p));
+        if matches(&self.pinned) {
+            Some(DirectionOverride::Pinned)
+        } else if matches(&self.reverse) {
+            Some(DirectionOverride::Reverse)
+        } else {
+            None
+        }
+    }
+}
+
+/// Pattern lists of a mapping that actions can add to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PatternList {
+    Exclude,
+    Pinned,
+}
+
+impl PatternList {
+    /// YAML key of the list
+    pub fn key(self) -> &'static str {
+        match self {
+            PatternList::Exclude => "exclude",
+            PatternList::Pinned => "pinned",
+        }
+    }
+}
+
+/// Per-file override of the sync direction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DirectionOverride {
+    /// Never overwritten by a sync
+    Pinned,
+    /// Always flows project -> shared
+    Reverse,
+}
+
+/// Transforms for the files of a mapping that match a pattern
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransformRule {
+    /// File pattern, relative to the mapping (same syntax as excludes, e.g. "*.md")
+    pub files: String,
+    
+    /// Values substituted for `{{token}}` placeholders (shared -> project only)
+    #[serde(default)]
+    pub tokens: BTreeMap<String, String>,
+    
+    /// Commands run on the file after it is synced from shared, e.g. "rustfmt" or "prettier --write {file}"
+    #[serde(default)]
+    pub commands: Vec<String>,
+}
+
+/// Colors given in turn to projects without a `color`, in project name order
+const PROJECT_COLORS: [&str; 6] = ["cyan", "magenta", "green", "yellow", "blue", "red"];
+
+/// Icon and color marking a project's entries in the UI
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectIdentity {
+    pub icon: String,
+    pub color: String,
 }
 
 /// A managed package definition
@@ -101,28 +280,143 @@ pub struct GlobalSettings {
     
     /// Auto-initialize git repository if not present
     pub auto_init_repo: Option<bool>,
+    
+    /// How synced files are written: "copy", "reflink" (default) or "hardlink"
+    pub copy_strategy: Option<CopyStrategy>,
+    
+    /// Patterns of files held back as likely secrets, on top of the built-in ones
+    pub secrets: Option<SecretRules>,
 }
 
-fn default_true() -> bool { true }
+/// Files a sync only writes after an explicit override (see `operations::secrets`)
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct SecretRules {
+    /// Turn the check off, built-in patterns included
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub disabled: bool,
+    
+    /// File name patterns (globs, case-insensitive, e.g. "*.keystore")
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<String>,
+    
+    /// Regular expressions matched against the content of files up to 1 MiB
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub content: Vec<String>,
+    
+    /// File name patterns never treated as secrets (e.g. "test-*.pem")
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow: Vec<String>,
+}
 
-impl Default for ProjectConfig {
-    fn default() -> Self {
-        Self {
-            workspace_settings: WorkspaceSettings::default(),
-            managed_packages: Vec::new(),
-            global_settings: GlobalSettings::default(),
-        }
+/// How a sync writes file contents to the destination
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CopyStrategy {
+    /// Byte-for-byte copy
+    Copy,
+    /// Copy-on-write clone on filesystems that support it (APFS, Btrfs, XFS, ReFS),
+    /// otherwise a byte copy
+    #[default]
+    Reflink,
+    /// Hard link to the source, so both paths share one file (editing either changes both);
+    /// falls back to a reflink or copy when the paths are on different filesystems
+    Hardlink,
+}
+
+/// Overrides of the compiled-in defaults (unset values keep the default)
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct UserSettings {
+    /// Fold unchanged regions of files opened side-by-side
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fold_unchanged: Option<bool>,
+    
+    /// Group the diff lists into status sections
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group_by_status: Option<bool>,
+    
+    /// Show source and destination line numbers in the side-by-side gutters
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dual_line_numbers: Option<bool>,
+    
+    /// List the differing members of archive entries in the diff view
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archive_diffs: Option<bool>,
+    
+    /// Treat whitespace-only changes within lines as unchanged
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ignore_whitespace: Option<bool>,
+    
+    /// Compare by content only, never by modification time
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ignore_timestamps: Option<bool>,
+    
+    /// UI theme: "default", "dark", "light"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub theme: Option<String>,
+    
+    /// Context lines around changes in folded regions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_lines: Option<usize>,
+    
+    /// Ask for confirmation before syncing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confirm_sync: Option<bool>,
+    
+    /// Uncommitted changes that block a sync: "none", "repository", "paths"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clean_destination: Option<String>,
+    
+    /// Delete destination files whose source was deleted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub propagate_deletes: Option<bool>,
+    
+    /// Also remove destination directories without files whose source was deleted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remove_directories: Option<bool>,
+    
+    /// Disable every action that writes files (audit mode for reviewers)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub read_only: Option<bool>,
+    
+    /// Files synced in parallel (0 = one per CPU core)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sync_workers: Option<usize>,
+    
+    /// Cap on the combined write rate of a sync in KB/s (0 = unlimited)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_kb_per_sec: Option<u64>,
+    
+    /// Seconds without progress before a scan or sync counts as stuck (0 = never)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub watchdog_secs: Option<u64>,
+    
+    /// Retries of a copy or delete that fails with a transient error
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_attempts: Option<u32>,
+    
+    /// Wait before the first retry in milliseconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_backoff_ms: Option<u64>,
+}
+
+impl UserSettings {
+    /// Whether no value is overridden
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
     }
 }
 
+fn default_true() -> bool { true }
+
 impl ProjectConfig {
     /// Load project configuration from a file
     pub fn load(path: &Path) -> Result<Self> {
         let content = fs::read_to_string(path)
             .with_context(|| format!("Failed to read project config: {}", path.display()))?;
         
-        let config: ProjectConfig = serde_yaml::from_str(&content)
-            .context("Failed to parse project config YAML")?;
+        let mut config: ProjectConfig = serde_yaml::from_str(&content)
+            .map_err(|err| SyncManagerError::config(path, err))?;
+        config.active_profile = config.select_profile(std::env::var(PROFILE_ENV).ok().as_deref(), &hostname());
         
         Ok(config)
     }
@@ -146,6 +440,90 @@ impl ProjectConfig {
         mappings
     }
     
+    /// Icon and color of a project
+    /// Unset values fall back to the name's first letter and a palette color picked by the
+    /// project's position in name order, so configured projects get distinct colors
+    pub fn project_identity(&self, project_name: &str) -> ProjectIdentity {
+        let settings = self.workspace_settings.projects.get(project_name);
+        let icon = match settings.and_then(|project| project.icon.as_deref()).and_then(|icon| icon.chars().next()) {
+            Some(icon) => icon.to_string(),
+            None => project_name.chars().next().map(|c| c.to_uppercase().to_string()).unwrap_or_default(),
+        };
+        let color = match settings.and_then(|project| project.color.clone()) {
+            Some(color) => color,
+            None => {
+                let mut names: Vec<&String> = self.workspace_settings.projects.keys().collect();
+                names.sort();
+                let index = names.iter().position(|name| *name == project_name).unwrap_or(names.len());
+                PROJECT_COLORS[index % PROJECT_COLORS.len()].to_string()
+            }
+        };
+        ProjectIdentity { icon, color }
+    }
+    
+    /// Profile for a machine: the one `env` names (the value of `SYNC_MANAGER_PROFILE`), else
+    /// the first listing `host`
+    pub fn select_profile(&self, env: Option<&str>, host: &str) -> Option<String> {
+        if let Some(name) = env.filter(|name| self.profiles.contains_key(*name)) {
+            return Some(name.to_string());
+        }
+        self.profiles
+            .iter()
+            .find(|(_, profile)| profile.hosts.iter().any(|h| h.eq_ignore_ascii_case(host)))
+            .map(|(name, _)| name.clone())
+    }
+    
+    /// Profile in effect on this machine
+    pub fn profile(&self) -> Option<&Profile> {
+        self.profiles.get(self.active_profile.as_ref()?)
+    }
+    
+    /// Whether scans of a project stop at its submodules and nested repositories
+    pub fn stops_at_nested_repos(&self, project_name: &str) -> bool {
+        self.workspace_settings.projects.get(project_name).is_some_and(|project| project.stop_at_nested_repos)
+    }
+    
+    /// Directory relative paths resolve against: `root` relative to `config_dir` (the directory
+    /// of the config file), else `config_dir` itself; never the process's working directory
+    /// (the active profile's `root` replaces the file's)
+    pub fn resolve_root(&self, config_dir: &Path) -> PathBuf {
+        match self.profile().and_then(|profile| profile.root.as_ref()).or(self.root.as_ref()) {
+            Some(root) => {
+                let path = config_dir.join(root);
+                fs::canonicalize(&path).unwrap_or(path)
+            }
+            None => config_dir.to_path_buf(),
+        }
+    }
+    
+    /// Root directory of a project; mapping paths of the project resolve against it
+    /// The project named after `config_dir` (the workspace's own) is rooted at the config's root
+    pub fn project_root(&self, config_dir: &Path, project_name: &str) -> PathBuf {
+        let root = self.resolve_root(config_dir);
+        if config_dir.file_name().and_then(|n| n.to_str()) == Some(project_name) {
+            return root;
+        }
+        let profile_path = self.profile().and_then(|profile| profile.project_paths.get(project_name));
+        match profile_path.or_else(|| self.workspace_settings.projects.get(project_name).and_then(|p| p.path.as_ref())) {
+            Some(path) => root.join(path),
+            None => root.parent().unwrap_or(&root).join(project_name),
+        }
+    }
+    
+    /// Mappings of a project with the overrides of its local config (`.sync-manager.yaml` in
+    /// `project_root`) merged in; errors if the local config can't be read or parsed
+    pub fn effective_mappings(&self, project_root: &Path, project_name: &str) -> Result<Vec<Mapping>> {
+        let local = LocalConfig::load(project_root)?;
+        Ok(self
+            .get_project_mappings(project_name)
+            .into_iter()
+            .map(|mapping| match &local {
+                Some(local) => local.merge_into(mapping),
+                None => mapping.clone(),
+            })
+            .collect())
+    }
+    
     /// Get an enabled package by name
     pub fn get_package(&self, name: &str) -> Option<&ManagedPackage> {
         self.managed_packages
@@ -167,11 +545,73 @@ impl ProjectConfig {
         }
     }
     
+    /// Resolve the (shared, project) directories of a mapping
+    /// Shared paths starting with `_shared-resources/` are workspace-relative; others are relative
+    /// to the shared-cursor package (or the first enabled one). Project paths are workspace-relative
+    pub fn mapping_paths(&self, workspace_root: &Path, mapping: &Mapping) -> (PathBuf, PathBuf) {
+        let shared_resources_base = workspace_root.join("_shared-resources");
+        let shared_repo_path = match self.get_package("shared-cursor").or_else(|| self.enabled_packages().next()) {
+            Some(pkg) => shared_resources_base.join(&pkg.location),
+            None => shared_resources_base,
+        };
+        
+        let shared_path = if mapping.shared.starts_with("_shared-resources/") {
+            workspace_root.join(&mapping.shared)
+        } else {
+            shared_repo_path.join(&mapping.shared)
+        };
+        (shared_path, workspace_root.join(&mapping.project))
+    }
+    
+    /// Mapping of a project whose shared or project directory contains `file_path`
+    pub fn mapping_containing_mut(
+        &mut self,
+        workspace_root: &Path,
+        project_name: &str,
+        file_path: &Path,
+    ) -> Option<&mut Mapping> {
+        let index = self.get_project_mappings(project_name).into_iter().position(|mapping| {
+            let (shared_path, project_path) = self.mapping_paths(workspace_root, mapping);
+            file_path.starts_with(shared_path) || file_path.starts_with(project_path)
+        })?;
+        
+        // Same traversal order as get_project_mappings
+        self.workspace_settings
+            .projects
+            .get_mut(project_name)?
+            .packages
+            .values_mut()
+            .flat_map(|package| package.mappings.iter_mut())
+            .nth(index)
+    }
+    
     /// Resolve a project path relative to the workspace
     pub fn resolve_project_path(&self, workspace_root: &Path, project_path: &str) -> PathBuf {
         workspace_root.join(project_path)
     }
     
+    /// Save after `pattern` was added to a list of `mapping`
+    /// The file's text is edited in place so comments survive; if the edit can't be made (or
+    /// doesn't read back as this config) the whole file is rewritten
+    pub fn save_added_pattern(&self, path: &Path, mapping: &Mapping, list: PatternList, pattern: &str) -> Result<()> {
+        let edited = fs::read_to_string(path)
+            .ok()
+            .and_then(|content| add_mapping_list_item(&content, mapping, list.key(), pattern))
+            .filter(|content| self.matches_yaml(content));
+        
+        match edited {
+            Some(content) => fs::write(path, content)
+                .with_context(|| format!("Failed to write project config: {}", path.display())),
+            None => self.save(path),
+        }
+    }
+    
+    /// Whether YAML text parses to exactly this config
+    fn matches_yaml(&self, content: &str) -> bool {
+        let parsed = serde_yaml::from_str::<ProjectConfig>(content).and_then(serde_yaml::to_value);
+        matches!((parsed, serde_yaml::to_value(self)), (Ok(parsed), Ok(current)) if parsed == current)
+    }
+    
     /// Save project configuration to a file
     pub fn save(&self, path: &Path) -> Result<()> {
         let content = serde_yaml::to_string(self)
@@ -183,3 +623,63 @@ impl ProjectConfig {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_project_identity() {
+        let yaml = "workspace_settings:\n  beta:\n    color: \"#ff8800\"\n    icon: \"β\"\n    shared-cursor:\n      mappings: []\n  alpha:\n    shared-cursor:\n      mappings: []\n";
+        let config: ProjectConfig = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(config.workspace_settings.projects["beta"].packages.len(), 1);
+        
+        let beta = config.project_identity("beta");
+        assert_eq!((beta.icon.as_str(), beta.color.as_str()), ("β", "#ff8800"));
+        let alpha = config.project_identity("alpha");
+        assert_eq!((alpha.icon.as_str(), alpha.color.as_str()), ("A", PROJECT_COLORS[0]));
+        assert_eq!(config.project_identity("gamma").color, PROJECT_COLORS[2]);
+    }
+    
+    #[test]
+    fn test_project_groups_and_roots() {
+        let yaml = "workspace_settings:\n  alpha:\n    path: ../repos/alpha\n  beta: {}\nproject_groups:\n  extensions: [alpha, beta]\n";
+        let config: ProjectConfig = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(config.project_groups["extensions"], ["alpha", "beta"]);
+        
+        let workspace = Path::new("/work/beta");
+        assert_eq!(config.project_root(workspace, "beta"), workspace);
+        assert_eq!(config.project_root(workspace, "alpha"), Path::new("/work/beta/../repos/alpha"));
+        assert_eq!(config.project_root(workspace, "gamma"), Path::new("/work/gamma"));
+        
+        let rooted = ProjectConfig { root: Some("checkout".to_string()), ..config };
+        assert_eq!(rooted.project_root(workspace, "beta"), Path::new("/work/beta/checkout"));
+        assert_eq!(rooted.project_root(workspace, "alpha"), Path::new("/work/beta/checkout/../repos/alpha"));
+    }
+    
+    #[test]
+    fn test_filter_presets() {
+        let yaml = "filter_presets:\n  rust:\n    filter: .rs status:modified\n    sort: largest_first\n  all: {}\n";
+        let config: ProjectConfig = serde_yaml::from_str(yaml).unwrap();
+        let names: Vec<_> = config.filter_presets.keys().map(String::as_str).collect();
+        assert_eq!(names, ["all", "rust"]);
+        assert_eq!(config.filter_presets["rust"].describe(), ".rs status:modified, largest first");
+        assert_eq!(config.filter_presets["all"].describe(), "no filter");
+        assert!(serde_yaml::to_string(&config).unwrap().contains("sort: largest_first"));
+    }
+    
+    #[test]
+    fn test_profiles() {
+        let yaml = "workspace_settings:\n  alpha:\n    path: ../repos/alpha\nprofiles:\n  laptop:\n    hosts: [Ada-Laptop]\n    root: ../code\n    project_paths:\n      alpha: ../src/alpha\n  desktop:\n    hosts: [ada-desktop]\n";
+        let mut config: ProjectConfig = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(config.select_profile(None, "ada-laptop").as_deref(), Some("laptop"));
+        assert_eq!(config.select_profile(Some("desktop"), "ada-laptop").as_deref(), Some("desktop"));
+        assert_eq!(config.select_profile(Some("travel"), "other-host"), None);
+        
+        let workspace = Path::new("/work/beta");
+        assert_eq!(config.project_root(workspace, "alpha"), Path::new("/work/beta/../repos/alpha"));
+        config.active_profile = Some("laptop".to_string());
+        assert_eq!(config.resolve_root(workspace), Path::new("/work/beta/../code"));
+        assert_eq!(config.project_root(workspace, "alpha"), Path::new("/work/beta/../code/../src/alpha"));
+    }
+}
@@ -0,0 +1,138 @@
+// Review
+// Accept / reject / defer verdicts on the entries of one diff list: accepted entries sync in
+// one batch when the review is finished, rejected ones are appended to an exclusion report
+
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use super::session::STATE_DIR_NAME;
+use crate::operations::{DiffEntry, DiffType};
+use crate::utilities::format_timestamp;
+
+/// Exclusion report file name inside the state directory
+const EXCLUSION_REPORT_NAME: &str = "review-exclusions.txt";
+
+/// Decision on an entry under review
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    /// Sync it when the review is finished
+    Accept,
+    /// Don't sync it; listed in the exclusion report
+    Reject,
+    /// Decide later (counts as not reviewed)
+    Defer,
+}
+
+/// Review of the list of one direction
+#[derive(Debug, Clone)]
+pub struct Review {
+    pub diff_type: DiffType,
+    /// Verdicts by project and path, so they survive rescans and re-sorting
+    verdicts: HashMap<(String, PathBuf), Verdict>,
+}
+
+fn key(entry: &DiffEntry) -> (String, PathBuf) {
+    (entry.project.clone(), entry.path.clone())
+}
+
+impl Review {
+    pub fn new(diff_type: DiffType) -> Self {
+        Self { diff_type, verdicts: HashMap::new() }
+    }
+    
+    /// Record a verdict; giving an entry its current verdict again clears it
+    pub fn set(&mut self, entry: &DiffEntry, verdict: Verdict) {
+        if self.verdicts.get(&key(entry)) == Some(&verdict) {
+            self.verdicts.remove(&key(entry));
+        } else {
+            self.verdicts.insert(key(entry), verdict);
+        }
+    }
+    
+    pub fn verdict(&self, entry: &DiffEntry) -> Option<Verdict> {
+        self.verdicts.get(&key(entry)).copied()
+    }
+    
+    /// Entries of `entries` accepted or rejected, and the number of entries
+    pub fn progress(&self, entries: &[DiffEntry]) -> (usize, usize) {
+        let reviewed = entries
+            .iter()
+            .filter(|entry| matches!(self.verdict(entry), Some(Verdict::Accept | Verdict::Reject)))
+            .count();
+        (reviewed, entries.len())
+    }
+    
+    /// Entries of `entries` with `verdict`, in list order
+    pub fn with_verdict(&self, entries: &[DiffEntry], verdict: Verdict) -> Vec<DiffEntry> {
+        entries.iter().filter(|entry| self.verdict(entry) == Some(verdict)).cloned().collect()
+    }
+}
+
+/// Path of the exclusion report of a workspace
+pub fn exclusion_report_path(workspace_root: &Path) -> PathBuf {
+    workspace_root.join(STATE_DIR_NAME).join(EXCLUSION_REPORT_NAME)
+}
+
+/// Append the rejected entries of a review to the exclusion report, one path per line
+/// (prefixed with the project when it isn't the workspace's own) under a dated heading
+pub fn append_exclusion_report(workspace_root: &Path, direction: &str, rejected: &[DiffEntry]) -> Result<PathBuf> {
+    let path = exclusion_report_path(workspace_root);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+    }
+    let mut text = format!("# Rejected {} on {}\n", direction, format_timestamp(SystemTime::now()));
+    for entry in rejected {
+        match entry.project.as_str() {
+            "" => text.push_str(&format!("{}\n", entry.path.display())),
+            project => text.push_str(&format!("{}: {}\n", project, entry.path.display())),
+        }
+    }
+    text.push('\n');
+    
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut file| file.write_all(text.as_bytes()))
+        .with_context(|| format!("Failed to write the exclusion report: {}", path.display()))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(path: &str, project: &str) -> DiffEntry {
+        DiffEntry::for_test(path, project)
+    }
+
+    #[test]
+    fn test_review_verdicts_and_report() {
+        let entries = vec![entry("a.md", ""), entry("b.md", ""), entry("b.md", "web"), entry("c.md", "")];
+        let mut review = Review::new(DiffType::SharedToProject);
+        review.set(&entries[0], Verdict::Accept);
+        review.set(&entries[1], Verdict::Reject);
+        review.set(&entries[2], Verdict::Reject);
+        review.set(&entries[3], Verdict::Defer);
+        assert_eq!(review.progress(&entries), (3, 4));
+
+        // The same verdict again clears it
+        review.set(&entries[0], Verdict::Accept);
+        assert_eq!(review.verdict(&entries[0]), None);
+        review.set(&entries[0], Verdict::Accept);
+        assert_eq!(review.with_verdict(&entries, Verdict::Accept).len(), 1);
+
+        let root = std::env::temp_dir().join(format!("sync-manager-review-{}", std::process::id()));
+        let rejected = review.with_verdict(&entries, Verdict::Reject);
+        append_exclusion_report(&root, "shared → project", &rejected).unwrap();
+        append_exclusion_report(&root, "shared → project", &rejected[..1]).unwrap();
+        let report = fs::read_to_string(exclusion_report_path(&root)).unwrap();
+        assert_eq!(report.matches("# Rejected shared → project on ").count(), 2);
+        assert!(report.contains("\nb.md\nweb: b.md\n\n"));
+        let _ = fs::remove_dir_all(&root);
+    }
+}
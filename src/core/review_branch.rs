@@ -0,0 +1,200 @@
+// Review Branch
+// Sync into a new branch of the destination repository, one commit per directory, so the
+// changes go through code review instead of landing in the working tree
+
+use anyhow::{bail, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::error::SyncManagerError;
+use crate::operations::{DiffEntry, FileOutcome, FileStatus, GitOps, OutcomeStatus};
+use crate::utilities::format_timestamp;
+
+/// Branch a sync is written to, created before the sync starts
+#[derive(Debug, Clone)]
+pub struct ReviewBranch {
+    /// Root of the destination repository
+    pub repo: PathBuf,
+    /// Name of the new branch
+    pub branch: String,
+    /// Branch checked out before; switched back to once the sync is committed
+    pub base: String,
+    /// Direction of the sync ("shared → project"), for the commit messages
+    pub direction: String,
+}
+
+/// Synced files of one directory, committed together
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitGroup {
+    /// Directory relative to the repository root ("" for the root)
+    pub dir: String,
+    /// Change letter (A, M or D) and repository-relative path of each file
+    pub changes: Vec<(char, String)>,
+}
+
+impl CommitGroup {
+    /// "Sync dir/ (direction)", then the files with their change letters
+    pub fn message(&self, direction: &str) -> String {
+        let dir = if self.dir.is_empty() { "repository root".to_string() } else { format!("{}/", self.dir) };
+        let mut message = format!(
+            "Sync {} ({})\n\n{} file{} synced by sync-manager:\n",
+            dir,
+            direction,
+            self.changes.len(),
+            if self.changes.len() == 1 { "" } else { "s" },
+        );
+        for (change, path) in &self.changes {
+            message.push_str(&format!("\n{} {}", change, path));
+        }
+        message
+    }
+}
+
+/// Branch name for a sync of `label` (project or group) started at `time`:
+/// `sync-manager/<label>-<yyyymmdd>-<hhmmss>`
+pub fn branch_name(label: &str, time: SystemTime) -> String {
+    let label: String = label
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c.to_ascii_lowercase() } else { '-' })
+        .collect();
+    let stamp: String = format_timestamp(time)
+        .chars()
+        .filter(|c| c.is_ascii_digit() || *c == ' ')
+        .map(|c| if c == ' ' { '-' } else { c })
+        .collect();
+    format!("sync-manager/{}-{}", label.trim_matches('-'), stamp.trim_end_matches('-'))
+}
+
+/// Changes grouped by directory, directories and files sorted by path
+pub fn group_changes(mut changes: Vec<(char, String)>) -> Vec<CommitGroup> {
+    changes.sort_by(|a, b| a.1.cmp(&b.1));
+    let mut groups: Vec<CommitGroup> = Vec::new();
+    for (change, path) in changes {
+        let dir = path.rsplit_once('/').map(|(dir, _)| dir.to_string()).unwrap_or_default();
+        match groups.iter_mut().find(|group| group.dir == dir) {
+            Some(group) => group.changes.push((change, path)),
+            None => groups.push(CommitGroup { dir, changes: vec![(change, path)] }),
+        }
+    }
+    groups.sort_by(|a, b| a.dir.cmp(&b.dir));
+    groups
+}
+
+/// Nearest existing directory above `path` (the destination of an added file may not exist yet)
+pub(super) fn existing_dir(path: &Path) -> Option<&Path> {
+    path.ancestors().skip(1).find(|dir| dir.is_dir())
+}
+
+/// `path` relative to the repository root, with forward slashes
+pub(super) fn repo_relative(repo: &Path, path: &Path) -> Option<String> {
+    let dir = existing_dir(path)?;
+    let canonical = fs::canonicalize(dir).ok()?;
+    let rest = path.strip_prefix(dir).ok()?;
+    let relative = canonical.join(rest);
+    let relative = relative.strip_prefix(repo).ok()?;
+    Some(relative.to_string_lossy().replace('\\', "/"))
+}
+
+impl ReviewBranch {
+    /// Check that the destinations of `entries` are in one repository with a branch checked out
+    /// and nothing uncommitted, then create the branch there and switch to it
+    pub fn create(entries: &[DiffEntry], label: &str, direction: &str) -> Result<Self> {
+        let mut repo: Option<PathBuf> = None;
+        let mut dirs: Vec<&Path> = entries.iter().filter_map(|entry| existing_dir(&entry.destination_path)).collect();
+        dirs.dedup();
+        for dir in dirs {
+            let toplevel = GitOps::toplevel(dir)?;
+            let toplevel = fs::canonicalize(&toplevel).unwrap_or(toplevel);
+            match &repo {
+                Some(repo) if *repo != toplevel => bail!(SyncManagerError::git(format!(
+                    "The files go to more than one repository ({} and {})",
+                    repo.display(),
+                    toplevel.display()
+                ))),
+                Some(_) => {}
+                None => repo = Some(toplevel),
+            }
+        }
+        let Some(repo) = repo else {
+            bail!(SyncManagerError::git("No destination repository found"));
+        };
+        
+        let base = GitOps::backend().current_branch(&repo)?;
+        if base.is_empty() {
+            bail!(SyncManagerError::git(format!("{} is on a detached HEAD", repo.display())));
+        }
+        if GitOps::backend().has_uncommitted_changes(&repo)? {
+            bail!(SyncManagerError::git(format!(
+                "{} has uncommitted changes; commit or stash them first",
+                repo.display()
+            )));
+        }
+        let branch = branch_name(label, SystemTime::now());
+        GitOps::create_branch(&repo, &branch)?;
+        Ok(Self { repo, branch, base, direction: direction.to_string() })
+    }
+    
+    /// Files the sync wrote or deleted, by directory
+    pub fn commit_groups(&self, entries: &[DiffEntry], outcomes: &[FileOutcome]) -> Vec<CommitGroup> {
+        let changes = entries
+            .iter()
+            .zip(outcomes)
+            .filter(|(entry, _)| !entry.is_dir)
+            .filter_map(|(entry, outcome)| {
+                let change = match outcome.status {
+                    OutcomeStatus::Deleted => 'D',
+                    OutcomeStatus::Copied | OutcomeStatus::Cloned | OutcomeStatus::Linked => match entry.status {
+                        FileStatus::Added => 'A',
+                        _ => 'M',
+                    },
+                    _ => return None,
+                };
+                Some((change, repo_relative(&self.repo, &entry.destination_path)?))
+            })
+            .collect();
+        group_changes(changes)
+    }
+    
+    /// Commit the synced files, one commit per directory, then switch back to the base branch
+    /// Returns the number of commits; on a failure the repository stays on the new branch
+    pub fn commit(&self, entries: &[DiffEntry], outcomes: &[FileOutcome]) -> Result<usize> {
+        let groups = self.commit_groups(entries, outcomes);
+        for group in &groups {
+            let paths: Vec<&Path> = group.changes.iter().map(|(_, path)| Path::new(path)).collect();
+            for path in &paths {
+                GitOps::add(&self.repo, path)?;
+            }
+            GitOps::commit(&self.repo, &group.message(&self.direction), &paths)?;
+        }
+        GitOps::switch(&self.repo, &self.base)?;
+        Ok(groups.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_groups_and_messages() {
+        let changes = vec![
+            ('M', "rules/b.md".to_string()),
+            ('A', "README.md".to_string()),
+            ('D', "rules/a.md".to_string()),
+            ('A', "rules/nested/c.md".to_string()),
+        ];
+        let groups = group_changes(changes);
+        let dirs: Vec<_> = groups.iter().map(|group| group.dir.as_str()).collect();
+        assert_eq!(dirs, ["", "rules", "rules/nested"]);
+        assert_eq!(
+            groups[1].message("shared → project"),
+            "Sync rules/ (shared → project)\n\n2 files synced by sync-manager:\n\nD rules/a.md\nM rules/b.md"
+        );
+        assert!(groups[0].message("project → shared").starts_with("Sync repository root"));
+
+        let time = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        assert_eq!(branch_name("My Project", time), "sync-manager/my-project-20231114-221320");
+    }
+}
@@ -0,0 +1,212 @@
+// Session State
+// UI state persisted between runs so a review can be resumed where it was left
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::app::DiffSortOrder;
+use crate::operations::{DiffEntry, DiffType};
+
+/// Directory (relative to the workspace root) holding sync-manager runtime state
+pub const STATE_DIR_NAME: &str = ".sync-manager";
+
+/// Session state file name inside the state directory
+const SESSION_FILE_NAME: &str = "session.yaml";
+
+/// Diagnostics log file name inside the state directory
+const LOG_FILE_NAME: &str = "sync-manager.log";
+
+/// Path of the diagnostics log of a workspace
+pub fn log_path(workspace_root: &Path) -> PathBuf {
+    workspace_root.join(STATE_DIR_NAME).join(LOG_FILE_NAME)
+}
+
+/// Persisted view mode (mirrors `ViewMode` without tying it to serde)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionViewMode {
+    #[default]
+    SharedToProject,
+    ProjectToShared,
+}
+
+/// Persisted top-level tab (mirrors `AppTab` without tying it to serde)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionTab {
+    #[default]
+    Sync,
+    Snapshots,
+    Settings,
+}
+
+/// A diff entry as remembered between runs: the same relative path can be listed in both
+/// directions and in several projects of a group
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct EntryKey {
+    pub direction: SessionViewMode,
+    /// Project of the entry (empty for the workspace's own project)
+    #[serde(default)]
+    pub project: String,
+    pub path: PathBuf,
+}
+
+impl EntryKey {
+    pub fn of(diff: &DiffEntry) -> Self {
+        Self {
+            direction: match diff.diff_type {
+                DiffType::SharedToProject => SessionViewMode::SharedToProject,
+                DiffType::ProjectToShared => SessionViewMode::ProjectToShared,
+            },
+            project: diff.project.clone(),
+            path: diff.path.clone(),
+        }
+    }
+
+    /// Whether `diff` is this entry
+    pub fn matches(&self, diff: &DiffEntry) -> bool {
+        *self == Self::of(diff)
+    }
+}
+
+/// Side-by-side scroll offset of one entry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScrollPosition {
+    #[serde(flatten)]
+    pub entry: EntryKey,
+    pub offset: usize,
+}
+
+/// UI state saved on quit and restored on launch
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct SessionState {
+    /// Active tab
+    #[serde(default)]
+    pub active_tab: SessionTab,
+
+    /// Active list (view mode)
+    #[serde(default)]
+    pub view_mode: SessionViewMode,
+
+    /// Selected entry in the shared -> project list
+    #[serde(default)]
+    pub shared_to_project_selected: Option<EntryKey>,
+
+    /// Selected entry in the project -> shared list
+    #[serde(default)]
+    pub project_to_shared_selected: Option<EntryKey>,
+
+    /// Sort order of the diff lists
+    #[serde(default)]
+    pub sort_order: Option<DiffSortOrder>,
+
+    /// Filter expression of the diff lists (see `ListFilter::parse`)
+    #[serde(default)]
+    pub list_filter: Option<String>,
+
+    /// Side-by-side scroll offset per entry (entries at the top aren't listed)
+    #[serde(default)]
+    pub scroll_positions: Vec<ScrollPosition>,
+}
+
+impl SessionState {
+    /// Path of the session file for a workspace
+    pub fn path_for(workspace_root: &Path) -> PathBuf {
+        workspace_root.join(STATE_DIR_NAME).join(SESSION_FILE_NAME)
+    }
+
+    /// Load the session for a workspace
+    /// A missing or unreadable file yields the default (empty) session
+    pub fn load(workspace_root: &Path) -> Self {
+        Self::load_from(&Self::path_for(workspace_root)).unwrap_or_default()
+    }
+
+    /// Load a session from an explicit file path
+    pub fn load_from(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read session state: {}", path.display()))?;
+
+        serde_yaml::from_str(&content).context("Failed to parse session state YAML")
+    }
+
+    /// Scroll offsets by entry
+    pub fn scroll_offsets(&self) -> HashMap<EntryKey, usize> {
+        self.scroll_positions.iter().map(|position| (position.entry.clone(), position.offset)).collect()
+    }
+
+    /// Save the session for a workspace, creating the state directory if needed
+    pub fn save(&self, workspace_root: &Path) -> Result<()> {
+        self.save_to(&Self::path_for(workspace_root))
+    }
+
+    /// Save the session to an explicit file path
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+        }
+
+        let content = serde_yaml::to_string(self).context("Failed to serialize session state")?;
+
+        fs::write(path, content)
+            .with_context(|| format!("Failed to write session state: {}", path.display()))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_round_trip() {
+        let dir = std::env::temp_dir().join(format!("sync-manager-session-{}", std::process::id()));
+        let path = dir.join(SESSION_FILE_NAME);
+
+        let key = |direction, project: &str| EntryKey {
+            direction,
+            project: project.to_string(),
+            path: PathBuf::from("rules/a.md"),
+        };
+        let position = |entry, offset| ScrollPosition { entry, offset };
+        let state = SessionState {
+            active_tab: SessionTab::Snapshots,
+            view_mode: SessionViewMode::ProjectToShared,
+            shared_to_project_selected: Some(key(SessionViewMode::SharedToProject, "web")),
+            sort_order: Some(DiffSortOrder::LargestFirst),
+            list_filter: Some(".md size>10k".to_string()),
+            // The same path in the other direction and in another project keeps its own offset
+            scroll_positions: vec![
+                position(key(SessionViewMode::SharedToProject, "web"), 42),
+                position(key(SessionViewMode::ProjectToShared, "web"), 7),
+                position(key(SessionViewMode::SharedToProject, "api"), 3),
+            ],
+            ..Default::default()
+        };
+
+        state.save_to(&path).unwrap();
+        let loaded = SessionState::load_from(&path).unwrap();
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(loaded.active_tab, SessionTab::Snapshots);
+        assert_eq!(loaded.view_mode, SessionViewMode::ProjectToShared);
+        assert_eq!(loaded.shared_to_project_selected, Some(key(SessionViewMode::SharedToProject, "web")));
+        assert_eq!(loaded.project_to_shared_selected, None);
+        assert_eq!(loaded.sort_order, Some(DiffSortOrder::LargestFirst));
+        assert_eq!(loaded.list_filter.as_deref(), Some(".md size>10k"));
+        let offsets = loaded.scroll_offsets();
+        assert_eq!(offsets.len(), 3);
+        assert_eq!(offsets[&key(SessionViewMode::ProjectToShared, "web")], 7);
+    }
+
+    #[test]
+    fn test_missing_session_is_default() {
+        let state = SessionState::load(Path::new("/nonexistent/sync-manager-workspace"));
+        assert_eq!(state.view_mode, SessionViewMode::SharedToProject);
+        assert!(state.scroll_positions.is_empty());
+    }
+}
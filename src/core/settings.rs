@@ -0,0 +1,249 @@
+// Settings Tab
+// Runtime-editable settings form; saved values are written to the project config
+
+use anyhow::{bail, Result};
+use tui_components::{FormField, FormPanel};
+
+use super::dirty_guard::CLEAN_DESTINATION_CHECKS;
+use super::project_config::UserSettings;
+use super::AppConfig;
+
+/// Form field indices
+const FIELD_FOLD_UNCHANGED: usize = 0;
+const FIELD_IGNORE_WHITESPACE: usize = 1;
+const FIELD_IGNORE_TIMESTAMPS: usize = 2;
+const FIELD_GROUP_BY_STATUS: usize = 3;
+const FIELD_DUAL_LINE_NUMBERS: usize = 4;
+const FIELD_ARCHIVE_DIFFS: usize = 5;
+const FIELD_THEME: usize = 6;
+const FIELD_CONTEXT_LINES: usize = 7;
+const FIELD_CONFIRM_SYNC: usize = 8;
+const FIELD_CLEAN_DESTINATION: usize = 9;
+const FIELD_PROPAGATE_DELETES: usize = 10;
+const FIELD_REMOVE_DIRECTORIES: usize = 11;
+const FIELD_RETRY_ATTEMPTS: usize = 12;
+const FIELD_RETRY_BACKOFF: usize = 13;
+const FIELD_SYNC_WORKERS: usize = 14;
+const FIELD_MAX_KB_PER_SEC: usize = 15;
+const FIELD_WATCHDOG_SECS: usize = 16;
+
+/// Values accepted by ui.theme
+const THEMES: [&str; 3] = ["default", "dark", "light"];
+
+/// Settings form of the Settings tab
+#[derive(Debug, Clone)]
+pub struct SettingsTab {
+    pub form: FormPanel,
+    /// Error from the last failed save
+    pub error: Option<String>,
+}
+
+impl SettingsTab {
+    /// Form showing the current values of `config`
+    pub fn new(config: &AppConfig) -> Self {
+        let theme = THEMES.iter().position(|t| *t == config.ui.theme).unwrap_or(0);
+        let clean = CLEAN_DESTINATION_CHECKS
+            .iter()
+            .position(|c| *c == config.defaults.clean_destination)
+            .unwrap_or(0);
+        let form = FormPanel::new()
+            .with_title("Settings")
+            .with_field(
+                FormField::checkbox("Fold unchanged regions", config.ui.fold_unchanged)
+                    .with_hint("Default for files opened side-by-side (f toggles per file)"),
+            )
+            .with_field(
+                FormField::checkbox("Ignore whitespace changes", config.ui.ignore_whitespace)
+                    .with_hint("Files differing only in the amount of whitespace within lines are unchanged"),
+            )
+            .with_field(
+                FormField::checkbox("Ignore timestamps", config.ui.ignore_timestamps)
+                    .with_hint("Compare by content only, never by modification time"),
+            )
+            .with_field(
+                FormField::checkbox("Group lists by status", config.ui.group_by_status)
+                    .with_hint("Added / Modified / Deleted / Conflict sections (G toggles, z collapses one)"),
+            )
+            .with_field(
+                FormField::checkbox("Both line numbers", config.ui.dual_line_numbers)
+                    .with_hint("Side-by-side gutters show the source and destination line of each row"),
+            )
+            .with_field(
+                FormField::checkbox("Archive contents", config.ui.archive_diffs)
+                    .with_hint("List the files that differ inside zip / tar entries (reads both archives)"),
+            )
+            .with_field(
+                FormField::select("Theme", THEMES.iter().map(|s| s.to_string()).collect(), theme)
+                    .with_hint("←/→ to change"),
+            )
+            .with_field(
+                FormField::text("Context lines", config.ui.context_lines.to_string())
+                    .with_hint("Lines shown around changes in folded regions")
+                    .with_validator(validate_count),
+            )
+            .with_field(
+                FormField::checkbox("Confirm before syncing", config.defaults.confirm_sync),
+            )
+            .with_field(
+                FormField::select("Clean destination", CLEAN_DESTINATION_CHECKS.iter().map(|s| s.to_string()).collect(), clean)
+                    .with_hint("Uncommitted changes that stop a sync into a git repository (←/→ to change)"),
+            )
+            .with_field(
+                FormField::checkbox("Propagate deletions", config.defaults.propagate_deletes)
+                    .with_hint("Delete destination files whose source was deleted"),
+            )
+            .with_field(
+                FormField::checkbox("Remove directories", config.defaults.remove_directories)
+                    .with_hint("Also remove destination directories without files whose source was deleted"),
+            )
+            .with_field(
+                FormField::text("Retry attempts", config.defaults.retry_attempts.to_string())
+                    .with_hint("Retries of a copy that fails because the file is locked or busy")
+                    .with_validator(validate_count),
+            )
+            .with_field(
+                FormField::text("Retry backoff (ms)", config.defaults.retry_backoff_ms.to_string())
+                    .with_hint("Wait before the first retry; doubles on each further retry")
+                    .with_validator(validate_count),
+            )
+            .with_field(
+                FormField::text("Sync workers", config.defaults.sync_workers.to_string())
+                    .with_hint("Files synced in parallel; 0 uses one per CPU core")
+                    .with_validator(validate_count),
+            )
+            .with_field(
+                FormField::text("Throughput cap (KB/s)", config.defaults.max_kb_per_sec.to_string())
+                    .with_hint("Combined write rate of a sync, for network shares; 0 = unlimited")
+                    .with_validator(validate_count),
+            )
+            .with_field(
+                FormField::text("Stall warning (s)", config.defaults.watchdog_secs.to_string())
+                    .with_hint("Seconds without progress before asking to wait, skip or cancel; 0 = never")
+                    .with_validator(validate_count),
+            );
+        
+        Self { form, error: None }
+    }
+    
+    /// Discard unsaved edits and show the values of `config` again
+    pub fn reset(&mut self, config: &AppConfig) {
+        let focused = self.form.focused;
+        *self = Self::new(config);
+        self.form.focused = focused;
+    }
+    
+    /// `config` with the form values applied
+    pub fn apply_to(&self, config: &AppConfig) -> Result<AppConfig> {
+        let counts = [
+            FIELD_CONTEXT_LINES,
+            FIELD_RETRY_ATTEMPTS,
+            FIELD_RETRY_BACKOFF,
+            FIELD_SYNC_WORKERS,
+            FIELD_MAX_KB_PER_SEC,
+            FIELD_WATCHDOG_SECS,
+        ];
+        for field in counts {
+            if let Err(message) = validate_count(self.form.value(field)) {
+                bail!("{}: {}", self.form.fields[field].label, message);
+            }
+        }
+        
+        let mut config = config.clone();
+        config.ui.fold_unchanged = self.form.checked(FIELD_FOLD_UNCHANGED);
+        config.ui.ignore_whitespace = self.form.checked(FIELD_IGNORE_WHITESPACE);
+        config.ui.ignore_timestamps = self.form.checked(FIELD_IGNORE_TIMESTAMPS);
+        config.ui.group_by_status = self.form.checked(FIELD_GROUP_BY_STATUS);
+        config.ui.dual_line_numbers = self.form.checked(FIELD_DUAL_LINE_NUMBERS);
+        config.ui.archive_diffs = self.form.checked(FIELD_ARCHIVE_DIFFS);
+        config.ui.theme = self.form.value(FIELD_THEME).to_string();
+        config.ui.context_lines = self.form.value(FIELD_CONTEXT_LINES).trim().parse()?;
+        config.defaults.confirm_sync = self.form.checked(FIELD_CONFIRM_SYNC);
+        config.defaults.clean_destination = self.form.value(FIELD_CLEAN_DESTINATION).to_string();
+        config.defaults.propagate_deletes = self.form.checked(FIELD_PROPAGATE_DELETES);
+        config.defaults.remove_directories = self.form.checked(FIELD_REMOVE_DIRECTORIES);
+        config.defaults.retry_attempts = self.form.value(FIELD_RETRY_ATTEMPTS).trim().parse()?;
+        config.defaults.retry_backoff_ms = self.form.value(FIELD_RETRY_BACKOFF).trim().parse()?;
+        config.defaults.sync_workers = self.form.value(FIELD_SYNC_WORKERS).trim().parse()?;
+        config.defaults.max_kb_per_sec = self.form.value(FIELD_MAX_KB_PER_SEC).trim().parse()?;
+        config.defaults.watchdog_secs = self.form.value(FIELD_WATCHDOG_SECS).trim().parse()?;
+        Ok(config)
+    }
+}
+
+/// Overrides that turn the compiled-in defaults into `config` (only differing values are set)
+pub fn settings_overrides(config: &AppConfig) -> UserSettings {
+    let defaults = AppConfig::default();
+    let changed = |value, default| (value != default).then_some(value);
+    UserSettings {
+        fold_unchanged: changed(config.ui.fold_unchanged, defaults.ui.fold_unchanged),
+        ignore_whitespace: changed(config.ui.ignore_whitespace, defaults.ui.ignore_whitespace),
+        ignore_timestamps: changed(config.ui.ignore_timestamps, defaults.ui.ignore_timestamps),
+        group_by_status: changed(config.ui.group_by_status, defaults.ui.group_by_status),
+        dual_line_numbers: changed(config.ui.dual_line_numbers, defaults.ui.dual_line_numbers),
+        archive_diffs: changed(config.ui.archive_diffs, defaults.ui.archive_diffs),
+        theme: (config.ui.theme != defaults.ui.theme).then(|| config.ui.theme.clone()),
+        context_lines: (config.ui.context_lines != defaults.ui.context_lines).then_some(config.ui.context_lines),
+        confirm_sync: changed(config.defaults.confirm_sync, defaults.defaults.confirm_sync),
+        clean_destination: (config.defaults.clean_destination != defaults.defaults.clean_destination)
+            .then(|| config.defaults.clean_destination.clone()),
+        propagate_deletes: changed(config.defaults.propagate_deletes, defaults.defaults.propagate_deletes),
+        remove_directories: changed(config.defaults.remove_directories, defaults.defaults.remove_directories),
+        read_only: changed(config.defaults.read_only, defaults.defaults.read_only),
+        sync_workers: (config.defaults.sync_workers != defaults.defaults.sync_workers)
+            .then_some(config.defaults.sync_workers),
+        max_kb_per_sec: (config.defaults.max_kb_per_sec != defaults.defaults.max_kb_per_sec)
+            .then_some(config.defaults.max_kb_per_sec),
+        watchdog_secs: (config.defaults.watchdog_secs != defaults.defaults.watchdog_secs)
+            .then_some(config.defaults.watchdog_secs),
+        retry_attempts: (config.defaults.retry_attempts != defaults.defaults.retry_attempts)
+            .then_some(config.defaults.retry_attempts),
+        retry_backoff_ms: (config.defaults.retry_backoff_ms != defaults.defaults.retry_backoff_ms)
+            .then_some(config.defaults.retry_backoff_ms),
+    }
+}
+
+/// Validator: a non-negative whole number
+fn validate_count(value: &str) -> Result<(), String> {
+    value
+        .trim()
+        .parse::<usize>()
+        .map(|_| ())
+        .map_err(|_| "Must be a whole number".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tui_components::FieldKind;
+    
+    #[test]
+    fn test_form_round_trip_and_overrides() {
+        let defaults = AppConfig::default();
+        let mut tab = SettingsTab::new(&defaults);
+        assert_eq!(settings_overrides(&tab.apply_to(&defaults).unwrap()), UserSettings::default());
+        
+        tab.form.fields[FIELD_CONTEXT_LINES].set_value(format!("{}", defaults.ui.context_lines + 2));
+        tab.form.fields[FIELD_PROPAGATE_DELETES].kind = FieldKind::Checkbox {
+            checked: !defaults.defaults.propagate_deletes,
+        };
+        let config = tab.apply_to(&defaults).unwrap();
+        
+        let overrides = settings_overrides(&config);
+        assert_eq!(overrides.context_lines, Some(defaults.ui.context_lines + 2));
+        assert_eq!(overrides.propagate_deletes, Some(!defaults.defaults.propagate_deletes));
+        assert_eq!(overrides.fold_unchanged, None);
+        
+        let mut reloaded = AppConfig::default();
+        reloaded.apply_settings(&overrides);
+        assert_eq!(reloaded.ui.context_lines, config.ui.context_lines);
+        assert_eq!(reloaded.defaults.propagate_deletes, config.defaults.propagate_deletes);
+    }
+    
+    #[test]
+    fn test_invalid_context_lines() {
+        let defaults = AppConfig::default();
+        let mut tab = SettingsTab::new(&defaults);
+        tab.form.fields[FIELD_CONTEXT_LINES].set_value("many");
+        assert!(tab.apply_to(&defaults).is_err());
+    }
+}
@@ -0,0 +1,176 @@
+// First-Run Setup
+// Wizard state for creating sync-manager.yaml when no project config exists
+
+use anyhow::{bail, Result};
+use std::path::Path;
+use tui_components::{required, FormField, FormPanel};
+
+use super::project_config::{ManagedPackage, Mapping, PackageSettings, ProjectConfig, ProjectSettings};
+
+/// Directory under the workspace root holding shared resource packages
+const SHARED_RESOURCES_DIR: &str = "_shared-resources";
+
+/// Form field indices
+const FIELD_SHARED_PATH: usize = 0;
+const FIELD_PROJECT_PATHS: usize = 1;
+const FIELD_EXCLUDES: usize = 2;
+const FIELD_SYNC_DIRECTION: usize = 3;
+
+/// Values accepted by global_settings.sync_direction
+const SYNC_DIRECTIONS: [&str; 3] = ["both", "to_project", "to_shared"];
+
+/// Setup wizard state (form values and the last validation error)
+#[derive(Debug, Clone)]
+pub struct SetupWizard {
+    pub form: FormPanel,
+    pub error: Option<String>,
+}
+
+impl SetupWizard {
+    /// Create the wizard with defaults guessed from the workspace layout
+    pub fn new(workspace_root: &Path) -> Self {
+        let default_shared = if workspace_root.join(SHARED_RESOURCES_DIR).join("shared-cursor").is_dir() {
+            format!("{}/shared-cursor", SHARED_RESOURCES_DIR)
+        } else {
+            format!("{}/", SHARED_RESOURCES_DIR)
+        };
+
+        let form = FormPanel::new()
+            .with_title("Sync Manager Setup")
+            .with_browse_root(workspace_root)
+            .with_field(
+                FormField::path("Shared resources path", default_shared)
+                    .with_hint("Package directory, relative to the workspace root")
+                    .with_validator(required)
+                    .with_validator(validate_shared_path),
+            )
+            .with_field(
+                FormField::text("Project paths", "")
+                    .with_hint("Comma-separated; use shared:project when the paths differ (e.g. rules:.cursor/rules)")
+                    .with_validator(required),
+            )
+            .with_field(
+                FormField::text("Excludes", "")
+                    .with_hint("Comma-separated glob patterns (e.g. *.tmp, drafts/)"),
+            )
+            .with_field(
+                FormField::select(
+                    "Sync direction",
+                    SYNC_DIRECTIONS.iter().map(|s| s.to_string()).collect(),
+                    0,
+                )
+                .with_hint("←/→ to change"),
+            );
+
+        Self { form, error: None }
+    }
+
+    /// Build a project configuration from the form values
+    pub fn build_config(&self, project_name: &str) -> Result<ProjectConfig> {
+        let location = package_location(self.form.value(FIELD_SHARED_PATH));
+        if let Err(message) = validate_shared_path(location) {
+            bail!("Shared resources path: {}", message);
+        }
+
+        let package_name = location.rsplit('/').next().unwrap_or(location).to_string();
+        let exclude = split_list(self.form.value(FIELD_EXCLUDES));
+
+        let mappings: Vec<Mapping> = split_list(self.form.value(FIELD_PROJECT_PATHS))
+            .into_iter()
+            .map(|entry| {
+                let (shared, project) = match entry.split_once(':') {
+                    Some((shared, project)) => (shared.trim().to_string(), project.trim().to_string()),
+                    None => (entry.clone(), entry),
+                };
+                Mapping {
+                    shared: format!("{}/{}/{}", SHARED_RESOURCES_DIR, location, shared.trim_matches('/')),
+                    project,
+                    exclude: exclude.clone(),
+                    include: Vec::new(),
+                    transforms: Vec::new(),
+                    pinned: Vec::new(),
+                    reverse: Vec::new(),
+                }
+            })
+            .collect();
+        if mappings.is_empty() {
+            bail!("At least one project path is required");
+        }
+
+        let mut config = ProjectConfig::default();
+        config.global_settings.sync_direction = Some(self.form.value(FIELD_SYNC_DIRECTION).to_string());
+        config.managed_packages.push(ManagedPackage {
+            name: package_name.clone(),
+            package_type: None,
+            enabled: true,
+            description: None,
+            location: location.to_string(),
+            git_remote: None,
+        });
+
+        let mut project = ProjectSettings::default();
+        project.packages.insert(package_name, PackageSettings { mappings });
+        config.workspace_settings.projects.insert(project_name.to_string(), project);
+
+        Ok(config)
+    }
+}
+
+/// Package location inside _shared-resources (the path without that prefix)
+fn package_location(shared_path: &str) -> &str {
+    let shared_path = shared_path.trim().trim_end_matches('/');
+    shared_path
+        .strip_prefix(SHARED_RESOURCES_DIR)
+        .unwrap_or(shared_path)
+        .trim_matches('/')
+}
+
+/// Validator: the shared path must name a package directory, not _shared-resources itself
+fn validate_shared_path(value: &str) -> Result<(), String> {
+    if package_location(value).is_empty() {
+        Err("Must name a package directory inside _shared-resources".to_string())
+    } else {
+        Ok(())
+    }
+}
+
+/// Split a comma-separated list, dropping empty entries
+fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_config_from_form() {
+        let mut wizard = SetupWizard::new(Path::new("/nonexistent"));
+        wizard.form.fields[FIELD_SHARED_PATH].value = "_shared-resources/shared-cursor".to_string();
+        wizard.form.fields[FIELD_PROJECT_PATHS].value = "rules:.cursor/rules, docs".to_string();
+        wizard.form.fields[FIELD_EXCLUDES].value = "*.tmp,".to_string();
+
+        let config = wizard.build_config("my-project").unwrap();
+        assert_eq!(config.managed_packages[0].name, "shared-cursor");
+        assert_eq!(config.managed_packages[0].location, "shared-cursor");
+
+        let mappings = config.get_project_mappings("my-project");
+        assert_eq!(mappings.len(), 2);
+        assert_eq!(mappings[0].shared, "_shared-resources/shared-cursor/rules");
+        assert_eq!(mappings[0].project, ".cursor/rules");
+        assert_eq!(mappings[1].project, "docs");
+        assert_eq!(mappings[1].exclude, vec!["*.tmp".to_string()]);
+        assert_eq!(config.global_settings.sync_direction.as_deref(), Some("both"));
+    }
+
+    #[test]
+    fn test_build_config_requires_project_path() {
+        let wizard = SetupWizard::new(Path::new("/nonexistent"));
+        assert!(wizard.build_config("my-project").is_err());
+    }
+}
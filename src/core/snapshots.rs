@@ -0,0 +1,102 @@
+// Snapshots Tab
+// State of the snapshot management tab: the stored snapshots and the last comparison
+
+use anyhow::Result;
+use std::path::Path;
+use tui_components::ListPanel;
+
+use crate::operations::snapshot::hash_tree;
+use crate::operations::{DiffEngine, Snapshot, SnapshotChange, SnapshotStore};
+
+/// Files changed since a snapshot, as of the last comparison
+#[derive(Debug, Clone)]
+pub struct SnapshotComparison {
+    /// Name of the snapshot compared against
+    pub name: String,
+    /// Changed files, sorted by path
+    pub changes: Vec<SnapshotChange>,
+}
+
+/// Snapshot list and comparison shown in the Snapshots tab
+#[derive(Debug)]
+pub struct SnapshotsTab {
+    /// Snapshot files of the workspace
+    pub store: SnapshotStore,
+    
+    /// Stored snapshots, newest first
+    pub snapshots: Vec<Snapshot>,
+    
+    /// List view state (selection, scroll) for the snapshots
+    pub list: ListPanel,
+    
+    /// Result of the last "compare with current" action
+    pub comparison: Option<SnapshotComparison>,
+    
+    /// List view state for the compared files
+    pub changes_list: ListPanel,
+}
+
+impl SnapshotsTab {
+    /// Tab for the snapshots of a workspace (loaded by `reload`)
+    pub fn new(workspace_root: &Path) -> Self {
+        Self {
+            store: SnapshotStore::new(workspace_root),
+            snapshots: Vec::new(),
+            list: ListPanel::new(),
+            comparison: None,
+            changes_list: ListPanel::new(),
+        }
+    }
+    
+    /// Re-read the snapshot files, keeping the selected snapshot selected
+    pub fn reload(&mut self) -> Result<()> {
+        let selected = self.selected().map(|s| s.name.clone());
+        self.snapshots = self.store.list()?;
+        self.list.selected = selected
+            .and_then(|name| self.snapshots.iter().position(|s| s.name == name))
+            .unwrap_or(0);
+        Ok(())
+    }
+    
+    /// Currently selected snapshot
+    pub fn selected(&self) -> Option<&Snapshot> {
+        self.snapshots.get(self.list.selected)
+    }
+    
+    /// Record `root` under a new name and select it
+    pub fn create(&mut self, name: &str, root: &Path, engine: &DiffEngine) -> Result<String> {
+        let snapshot = self.store.create(name, root, engine)?;
+        let name = snapshot.name.clone();
+        self.reload()?;
+        self.list.selected = self.snapshots.iter().position(|s| s.name == name).unwrap_or(0);
+        Ok(name)
+    }
+    
+    /// Delete the selected snapshot; returns its name
+    pub fn delete_selected(&mut self) -> Result<Option<String>> {
+        let Some(name) = self.selected().map(|s| s.name.clone()) else {
+            return Ok(None);
+        };
+        self.store.delete(&name)?;
+        if self.comparison.as_ref().is_some_and(|c| c.name == name) {
+            self.comparison = None;
+        }
+        self.reload()?;
+        self.list.selected = self.list.selected.min(self.snapshots.len().saturating_sub(1));
+        Ok(Some(name))
+    }
+    
+    /// Compare the current state of `root` against the selected snapshot
+    pub fn compare_selected(&mut self, root: &Path, engine: &DiffEngine) -> Result<Option<&SnapshotComparison>> {
+        let Some(snapshot) = self.selected() else {
+            return Ok(None);
+        };
+        let changes = snapshot.compare(&hash_tree(root, engine)?);
+        self.comparison = Some(SnapshotComparison {
+            name: snapshot.name.clone(),
+            changes,
+        });
+        self.changes_list.selected = 0;
+        Ok(self.comparison.as_ref())
+    }
+}
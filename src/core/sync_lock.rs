@@ -0,0 +1,172 @@
+// Sync Lock
+// Lock file in the state directory held while a sync runs, so two sync-manager processes
+// can't sync the same workspace at once
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use super::session::STATE_DIR_NAME;
+use crate::utilities::{format_timestamp, hostname};
+
+/// Lock file name inside the state directory
+const LOCK_FILE_NAME: &str = "sync.lock";
+
+/// Process holding a lock, as written in the lock file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockOwner {
+    pub pid: u32,
+    /// Host the process runs on (a lock on a shared drive may come from another machine)
+    pub host: String,
+    /// When the lock was taken, in seconds since the Unix epoch
+    pub since: u64,
+}
+
+/// Outcome of trying to take the lock
+#[derive(Debug)]
+pub enum LockAttempt {
+    Acquired(SyncLock),
+    /// Another process holds it
+    Held(LockOwner),
+}
+
+/// The lock, released (its file removed) when dropped
+#[derive(Debug)]
+pub struct SyncLock {
+    path: PathBuf,
+}
+
+/// Whether a process with `pid` runs on this machine
+#[cfg(unix)]
+fn is_running(pid: u32) -> bool {
+    let Some(pid) = libc::pid_t::try_from(pid).ok().filter(|pid| *pid > 0) else {
+        return false;
+    };
+    // SAFETY: signal 0 sends nothing, it only checks that the process exists
+    // (EPERM: it does, owned by another user)
+    let exists = unsafe { libc::kill(pid, 0) } == 0;
+    exists || std::io::Error::last_os_error().kind() == ErrorKind::PermissionDenied
+}
+
+/// Whether a process with `pid` runs on this machine (assumed, without a way to tell)
+#[cfg(not(unix))]
+fn is_running(_pid: u32) -> bool {
+    true
+}
+
+impl LockOwner {
+    /// This process
+    fn current() -> Self {
+        let since = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or(0);
+        Self { pid: std::process::id(), host: hostname(), since }
+    }
+    
+    /// Whether the owner is known to be gone: it ran on this machine and its process no longer exists
+    pub fn is_stale(&self) -> bool {
+        self.host == hostname() && !is_running(self.pid)
+    }
+    
+    /// "PID 1234 on host since 2024-03-09 14:05:31 UTC"
+    pub fn describe(&self) -> String {
+        let since = format_timestamp(SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(self.since));
+        match self.host.as_str() {
+            "" => format!("PID {} since {}", self.pid, since),
+            host => format!("PID {} on {} since {}", self.pid, host, since),
+        }
+    }
+}
+
+impl SyncLock {
+    /// Path of the lock file of a workspace
+    pub fn path_for(workspace_root: &Path) -> PathBuf {
+        workspace_root.join(STATE_DIR_NAME).join(LOCK_FILE_NAME)
+    }
+    
+    /// Take the lock of a workspace unless another process holds it
+    /// A lock file that can't be parsed counts as held by PID 0, which is stale on any host
+    pub fn acquire(workspace_root: &Path) -> Result<LockAttempt> {
+        let path = Self::path_for(workspace_root);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+        }
+        
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(mut file) => {
+                let content = serde_yaml::to_string(&LockOwner::current()).context("Failed to serialize the sync lock")?;
+                file.write_all(content.as_bytes())
+                    .with_context(|| format!("Failed to write the sync lock: {}", path.display()))?;
+                Ok(LockAttempt::Acquired(Self { path }))
+            }
+            Err(err) if err.kind() == ErrorKind::AlreadyExists => {
+                let owner = fs::read_to_string(&path)
+                    .ok()
+                    .and_then(|content| serde_yaml::from_str(&content).ok())
+                    .unwrap_or(LockOwner { pid: 0, host: hostname(), since: 0 });
+                Ok(LockAttempt::Held(owner))
+            }
+            Err(err) => Err(err).with_context(|| format!("Failed to create the sync lock: {}", path.display())),
+        }
+    }
+    
+    /// Remove the lock `owner` left behind, unless another process has taken it since
+    pub fn remove_stale(workspace_root: &Path, owner: &LockOwner) -> Result<()> {
+        let path = Self::path_for(workspace_root);
+        let current: Option<LockOwner> = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_yaml::from_str(&content).ok());
+        if let Some(current) = current.filter(|current| current != owner) {
+            bail!("The sync lock was taken by {} in the meantime", current.describe());
+        }
+        match fs::remove_file(&path) {
+            Err(err) if err.kind() != ErrorKind::NotFound => {
+                Err(err).with_context(|| format!("Failed to remove the sync lock: {}", path.display()))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Drop for SyncLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lock_is_exclusive_and_stale_locks_can_be_removed() {
+        let root = std::env::temp_dir().join(format!("sync-manager-lock-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+
+        let LockAttempt::Acquired(lock) = SyncLock::acquire(&root).unwrap() else {
+            panic!("the lock should be free");
+        };
+        let LockAttempt::Held(owner) = SyncLock::acquire(&root).unwrap() else {
+            panic!("the lock should be held");
+        };
+        assert_eq!(owner.pid, std::process::id());
+        assert!(!owner.is_stale());
+        drop(lock);
+        assert!(!SyncLock::path_for(&root).exists());
+
+        let gone = LockOwner { pid: 99_999_999, host: hostname(), since: 0 };
+        fs::write(SyncLock::path_for(&root), serde_yaml::to_string(&gone).unwrap()).unwrap();
+        let LockAttempt::Held(owner) = SyncLock::acquire(&root).unwrap() else {
+            panic!("the stale lock should still be held");
+        };
+        assert!(owner.is_stale());
+        SyncLock::remove_stale(&root, &owner).unwrap();
+        assert!(matches!(SyncLock::acquire(&root).unwrap(), LockAttempt::Acquired(_)));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
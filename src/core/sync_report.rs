@@ -0,0 +1,128 @@
+// Sync Report
+// Per-file outcomes of the last sync, shown in a scrollable popup until dismissed
+
+use tui_components::ListPanel;
+
+use crate::operations::{DiffEntry, FileOutcome, OutcomeStatus, SyncResult};
+
+/// Outcomes of a sync and the entries it was given (kept so failed files can be retried)
+#[derive(Debug)]
+pub struct SyncReport {
+    /// Entries handed to the sync engine, in order
+    pub entries: Vec<DiffEntry>,
+    
+    /// Counters and per-file outcomes
+    pub result: SyncResult,
+    
+    /// List view state (selection, scroll) for the outcomes
+    pub list: ListPanel,
+}
+
+/// Outcome counts of the files of one project
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProjectTotals {
+    pub project: String,
+    pub synced: usize,
+    pub failed: usize,
+    pub skipped: usize,
+}
+
+impl SyncReport {
+    /// Report for a finished sync; the first failure starts selected
+    pub fn new(entries: Vec<DiffEntry>, result: SyncResult) -> Self {
+        let mut list = ListPanel::new();
+        list.selected = result.outcomes.iter().position(FileOutcome::is_failed).unwrap_or(0);
+        Self { entries, result, list }
+    }
+    
+    /// Currently selected outcome
+    pub fn selected(&self) -> Option<&FileOutcome> {
+        self.result.outcomes.get(self.list.selected)
+    }
+    
+    /// Outcome counts per project, in the order the projects were synced
+    pub fn project_totals(&self) -> Vec<ProjectTotals> {
+        let mut totals: Vec<ProjectTotals> = Vec::new();
+        for (entry, outcome) in self.entries.iter().zip(&self.result.outcomes) {
+            if totals.last().is_none_or(|last| last.project != entry.project) {
+                totals.push(ProjectTotals { project: entry.project.clone(), ..ProjectTotals::default() });
+            }
+            let Some(last) = totals.last_mut() else {
+                continue;
+            };
+            match outcome.status {
+                OutcomeStatus::Skipped => last.skipped += 1,
+                OutcomeStatus::Failed { .. } => last.failed += 1,
+                _ => last.synced += 1,
+            }
+        }
+        totals
+    }
+    
+    /// Entries whose sync failed
+    pub fn failed_entries(&self) -> Vec<DiffEntry> {
+        self.entries
+            .iter()
+            .zip(&self.result.outcomes)
+            .filter(|(_, outcome)| outcome.is_failed())
+            .map(|(entry, _)| entry.clone())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::project_config::CopyStrategy;
+    use crate::operations::{FailureKind, FileStatus, OutcomeStatus, SyncEngine, SyncOptions};
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    fn entry(dir: &Path, path: &str, status: FileStatus) -> DiffEntry {
+        DiffEntry {
+            source_path: dir.join("src").join(path),
+            destination_path: dir.join("dest").join(path),
+            status,
+            ..DiffEntry::for_test(path, "")
+        }
+    }
+
+    #[test]
+    fn test_report_outcomes_and_failed_subset() {
+        let dir = std::env::temp_dir().join(format!("sync-manager-report-{}", std::process::id()));
+        fs::create_dir_all(dir.join("src")).unwrap();
+        fs::write(dir.join("src/a.md"), "alpha").unwrap();
+
+        let mut entries = vec![
+            entry(&dir, "a.md", FileStatus::Added),
+            entry(&dir, "missing.md", FileStatus::Modified),
+            entry(&dir, "same.md", FileStatus::Unchanged),
+        ];
+        entries[0].project = "alpha".to_string();
+        entries[1].project = "beta".to_string();
+        entries[2].project = "beta".to_string();
+        let engine = SyncEngine::new(SyncOptions {
+            create_backup: false,
+            copy_strategy: CopyStrategy::Copy,
+            ..SyncOptions::default()
+        });
+        let result = engine.sync_files(&entries);
+        let report = SyncReport::new(entries, result);
+
+        let statuses: Vec<_> = report.result.outcomes.iter().map(|o| &o.status).collect();
+        assert!(matches!(statuses[..], [OutcomeStatus::Copied, OutcomeStatus::Failed { kind: FailureKind::Permanent, .. }, OutcomeStatus::Skipped]));
+        assert_eq!(report.result.bytes(), 5);
+        assert_eq!(report.list.selected, 1);
+
+        let failed = report.failed_entries();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].path, PathBuf::from("missing.md"));
+
+        let totals = report.project_totals();
+        assert_eq!(totals.len(), 2);
+        assert_eq!((totals[0].project.as_str(), totals[0].synced), ("alpha", 1));
+        assert_eq!((totals[1].project.as_str(), totals[1].failed, totals[1].skipped), ("beta", 1, 1));
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
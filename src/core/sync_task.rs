@@ -0,0 +1,104 @@
+// Sync Task
+// A sync running on a background thread, so the UI keeps drawing its progress
+
+use anyhow::Result;
+use std::sync::Arc;
+use std::thread::JoinHandle;
+
+use super::review_branch::ReviewBranch;
+use crate::operations::{CancellationToken, DiffEntry, SyncEngine, SyncOptions, SyncProgress, SyncResult, Watchdog};
+
+/// Review branch of a finished sync with the outcome of committing to it (the number of commits)
+pub type BranchCommit = (ReviewBranch, Result<usize>);
+
+/// Sync started by the app; polled each frame until it finishes
+#[derive(Debug)]
+pub struct SyncTask {
+    /// Entries being synced, in the order of the result's outcomes
+    pub entries: Vec<DiffEntry>,
+    
+    /// Counters updated by the sync workers
+    pub progress: Arc<SyncProgress>,
+    
+    /// Heartbeat of the sync, for noticing it got stuck
+    pub watchdog: Arc<Watchdog>,
+    
+    /// Stop request; the files in flight finish first
+    pub cancel: CancellationToken,
+    
+    /// Throughput cap of this sync in bytes per second (0 = unlimited)
+    pub max_bytes_per_sec: u64,
+    
+    /// Branch the sync writes to, committed on the sync thread once the files are synced
+    pub review_branch: Option<ReviewBranch>,
+    
+    handle: JoinHandle<(SyncResult, Option<Result<usize>>)>,
+}
+
+impl SyncTask {
+    /// Start syncing `entries` on a new thread, then committing them to `review_branch`
+    pub fn start(entries: Vec<DiffEntry>, options: SyncOptions, review_branch: Option<ReviewBranch>) -> Self {
+        let max_bytes_per_sec = options.max_bytes_per_sec;
+        let engine = SyncEngine::new(options);
+        let progress = engine.progress();
+        let watchdog = engine.watchdog();
+        let cancel = engine.cancellation_token();
+        progress.begin(entries.len());
+        
+        let work = entries.clone();
+        let branch = review_branch.clone();
+        let handle = std::thread::spawn(move || {
+            let result = engine.sync_files(&work);
+            let commit = branch.map(|branch| branch.commit(&work, &result.outcomes));
+            (result, commit)
+        });
+        Self {
+            entries,
+            progress,
+            watchdog,
+            cancel,
+            max_bytes_per_sec,
+            review_branch,
+            handle,
+        }
+    }
+    
+    /// Number of projects the entries belong to
+    pub fn project_count(&self) -> usize {
+        let mut projects: Vec<&str> = self.entries.iter().map(|entry| entry.project.as_str()).collect();
+        projects.sort_unstable();
+        projects.dedup();
+        projects.len()
+    }
+    
+    pub fn is_finished(&self) -> bool {
+        self.handle.is_finished()
+    }
+    
+    /// Wait for the sync; returns the entries with their result, and the commit to the review
+    /// branch if there is one
+    pub fn finish(self) -> (Vec<DiffEntry>, SyncResult, Option<BranchCommit>) {
+        let (result, commit) = self.handle.join().expect("sync thread panicked");
+        let commit = self.review_branch.zip(commit);
+        (self.entries, result, commit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_task_reports_progress_and_result() {
+        let task = SyncTask::start(Vec::new(), SyncOptions::default(), None);
+        while !task.is_finished() {
+            std::thread::yield_now();
+        }
+        assert_eq!(task.progress.snapshot().total, 0);
+
+        let (entries, result, commit) = task.finish();
+        assert!(entries.is_empty());
+        assert!(commit.is_none());
+        assert_eq!(result.outcomes.len(), 0);
+    }
+}
@@ -0,0 +1,153 @@
+// Errors
+// Error categories, with messages for popups and stable codes for the command line
+//
+// Most code returns `anyhow::Result` and adds context as errors travel up. Where an error
+// starts, it is tagged with its category by returning a `SyncManagerError`; where errors are
+// shown, `SyncManagerError::from(&err)` recovers the category (IO errors are found in the
+// chain) and gives the message, hint and code.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// An error by the part of the tool it concerns
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SyncManagerError {
+    /// sync-manager.yaml or a project's .sync-manager.yaml can't be parsed
+    #[error("{}: {message}", path.display())]
+    Config { path: PathBuf, message: String },
+    /// Reading, writing or removing files
+    #[error("{message}")]
+    Io { kind: io::ErrorKind, message: String },
+    /// A git command failed or git couldn't be run
+    #[error("{message}")]
+    Git { message: String },
+    /// Setting up or drawing the terminal
+    #[error("{message}")]
+    Render { message: String },
+    /// Anything else
+    #[error("{message}")]
+    Other { message: String },
+}
+
+impl SyncManagerError {
+    pub fn config(path: &Path, err: impl std::fmt::Display) -> Self {
+        Self::Config {
+            path: path.to_path_buf(),
+            message: err.to_string(),
+        }
+    }
+    
+    pub fn git(message: impl Into<String>) -> Self {
+        Self::Git { message: message.into() }
+    }
+    
+    pub fn render(err: impl std::fmt::Display) -> Self {
+        Self::Render { message: err.to_string() }
+    }
+    
+    /// Stable code printed on the command line ("config", "io", "git", "render" or "other")
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Config { .. } => "config",
+            Self::Io { .. } => "io",
+            Self::Git { .. } => "git",
+            Self::Render { .. } => "render",
+            Self::Other { .. } => "other",
+        }
+    }
+    
+    /// Process exit status for the category
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            Self::Other { .. } => 1,
+            Self::Config { .. } => 2,
+            Self::Io { .. } => 3,
+            Self::Git { .. } => 4,
+            Self::Render { .. } => 5,
+        }
+    }
+    
+    /// Popup title
+    pub fn title(&self) -> &'static str {
+        match self {
+            Self::Config { .. } => "Configuration error",
+            Self::Io { .. } => "File error",
+            Self::Git { .. } => "Git error",
+            Self::Render { .. } => "Terminal error",
+            Self::Other { .. } => "Error",
+        }
+    }
+    
+    /// What the user can do about it, if there is a usual remedy
+    pub fn hint(&self) -> Option<&'static str> {
+        match self {
+            Self::Config { .. } => Some("Fix the file, then press r to rescan (sync-manager.yaml itself is read at startup)."),
+            Self::Io { kind: io::ErrorKind::PermissionDenied, .. } => Some("Check the permissions of the file and its directory."),
+            Self::Io { kind: io::ErrorKind::NotFound, .. } => Some("The file may have been moved or deleted; press r to rescan."),
+            Self::Io { kind: io::ErrorKind::ResourceBusy | io::ErrorKind::WouldBlock, .. } => {
+                Some("Another program is using the file; close it and try again.")
+            }
+            Self::Io { .. } => None,
+            Self::Git { .. } => Some("Check that git is installed and on PATH, and that the repository is reachable."),
+            Self::Render { .. } => Some("Make sure sync-manager runs in an interactive terminal."),
+            Self::Other { .. } => None,
+        }
+    }
+    
+    /// The message followed by the hint, for popups and the command line
+    pub fn user_message(&self) -> String {
+        match self.hint() {
+            Some(hint) => format!("{}\n{}", self, hint),
+            None => self.to_string(),
+        }
+    }
+}
+
+impl From<&anyhow::Error> for SyncManagerError {
+    /// Category of an error: the tag where it started, else IO if an IO error caused it
+    /// The message includes the context added on the way up (a config error keeps its own)
+    fn from(err: &anyhow::Error) -> Self {
+        let message = format!("{:#}", err);
+        if let Some(tagged) = err.chain().find_map(|cause| cause.downcast_ref::<SyncManagerError>()) {
+            return match tagged {
+                Self::Config { .. } => tagged.clone(),
+                Self::Io { kind, .. } => Self::Io { kind: *kind, message },
+                Self::Git { .. } => Self::Git { message },
+                Self::Render { .. } => Self::Render { message },
+                Self::Other { .. } => Self::Other { message },
+            };
+        }
+        match err.chain().find_map(|cause| cause.downcast_ref::<io::Error>()) {
+            Some(io_err) => Self::Io { kind: io_err.kind(), message },
+            None => Self::Other { message },
+        }
+    }
+}
+
+impl From<anyhow::Error> for SyncManagerError {
+    fn from(err: anyhow::Error) -> Self {
+        Self::from(&err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn test_category_survives_context() {
+        let err = anyhow::Error::from(SyncManagerError::git("git fetch failed: no remote")).context("Refresh failed");
+        let error = SyncManagerError::from(&err);
+        assert_eq!((error.code(), error.exit_code()), ("git", 4));
+        assert_eq!(error.to_string(), "Refresh failed: git fetch failed: no remote");
+
+        let err = std::fs::read("/nonexistent/sync-manager.yaml").context("Failed to read project config");
+        let error = SyncManagerError::from(&err.unwrap_err());
+        assert!(matches!(error, SyncManagerError::Io { kind: io::ErrorKind::NotFound, .. }));
+        assert!(error.user_message().ends_with("press r to rescan."));
+
+        let error = SyncManagerError::from(anyhow::anyhow!("something else"));
+        assert_eq!(error.code(), "other");
+    }
+}
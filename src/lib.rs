@@ -13,6 +13,10 @@ pub mod ui;
 // Utilities - helper functions and tools
 pub mod utilities;
 
+// Errors - categories, user-facing messages and exit codes
+pub mod error;
+
 // Re-export commonly used items for convenience
 pub use core::{App, AppConfig, ProjectConfig};
+pub use error::SyncManagerError;
 pub use operations::{DiffEngine, SyncEngine, GitOps};
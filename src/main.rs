@@ -1,41 +1,110 @@
-use anyhow::Result;
-use crossterm::{
-    event::{DisableMouseCapture, EnableMouseCapture},
-    execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
-};
-use ratatui::{backend::CrosstermBackend, Terminal};
-use std::io::stdout;
-
-use sync_manager::core::App;
-use sync_manager::ui::run_app;
-
-fn main() -> Result<()> {
-    // Initialize terminal
-    enable_raw_mode()?;
-    let mut stdout = stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+use anyhow::{Context, Result};
+use std::process::ExitCode;
+
+use sync_manager::core::{daemon, App};
+use sync_manager::operations::{ControlRequest, ControlResponse, Severity};
+use sync_manager::ui::{run_app, TerminalGuard};
+use sync_manager::SyncManagerError;
+
+#[cfg(unix)]
+use sync_manager::operations::control::send;
+
+fn main() -> ExitCode {
+    // `sync-manager doctor` prints the health check instead of starting the TUI; `serve`
+    // runs without a terminal, answering the `control` requests of other processes
+    let result = match std::env::args().nth(1).as_deref() {
+        Some("doctor") => doctor(),
+        Some("serve") => serve().map(|()| ExitCode::SUCCESS),
+        Some("control") => control(std::env::args().nth(2).as_deref().unwrap_or("status")),
+        _ => run().map(|()| ExitCode::SUCCESS),
+    };
+
+    match result {
+        Ok(code) => code,
+        Err(err) => {
+            // `error[<code>]: <message>` with the category's exit status, for scripts
+            let error = SyncManagerError::from(&err);
+            eprintln!("error[{}]: {}", error.code(), error);
+            if let Some(hint) = error.hint() {
+                eprintln!("hint: {}", hint);
+            }
+            ExitCode::from(error.exit_code())
+        }
+    }
+}
+
+/// Run the TUI
+fn run() -> Result<()> {
+    // `--read-only` disables syncing and every other action that writes files
+    let read_only = std::env::args().skip(1).any(|arg| arg == "--read-only");
+
+    // Initialize terminal (restored on drop, on error, and on panic)
+    let mut terminal = TerminalGuard::new()?;
 
     // Initialize application state (loads sync-manager.yaml from workspace)
-    let mut app = App::new()?;
+    let result = App::new().and_then(|mut app| {
+        app.config.defaults.read_only |= read_only;
 
-    // Run the main event loop
-    let result = run_app(&mut terminal, &mut app);
+        // Run the main event loop
+        let result = run_app(&mut terminal, &mut app);
 
-    // Restore terminal
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
+        // Remember selection, scroll positions and the diff lists for the next launch
+        result.and(app.save_session()).and(app.save_diff_cache())
+    });
 
-    if let Err(err) = result {
-        eprintln!("Error: {}", err);
+    // Restore terminal before reporting errors
+    drop(terminal);
+    result
+}
+
+/// Rescan in the background and serve the control socket until stopped
+/// `--metrics <addr>` also serves the scan and sync totals over HTTP
+fn serve() -> Result<()> {
+    let mut args = std::env::args().skip(2);
+    let mut metrics_addr = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--metrics" => {
+                let addr = args.next().context("--metrics needs an address, e.g. 127.0.0.1:9464")?;
+                metrics_addr = Some(addr.parse().with_context(|| format!("Invalid metrics address: {}", addr))?);
+            }
+            other => anyhow::bail!("Unknown option for serve: {}", other),
+        }
     }
+    let mut app = App::new()?;
+    daemon::serve(&mut app, metrics_addr)
+}
+
+/// Send a request (status, sync, pause or resume) to `sync-manager serve` and print its answer
+/// Exits with status 1 if the request was refused
+fn control(request: &str) -> Result<ExitCode> {
+    let request = ControlRequest::parse(request)?;
+    let path = sync_manager::operations::control::socket_path(&App::detect_workspace_root()?);
+    Ok(match send(&path, request)? {
+        ControlResponse::Ok(message) => {
+            println!("{}", message);
+            ExitCode::SUCCESS
+        }
+        ControlResponse::Error(message) => {
+            eprintln!("{}", message);
+            ExitCode::FAILURE
+        }
+    })
+}
+
+/// The control socket is a Unix domain socket
+#[cfg(not(unix))]
+fn send(_path: &std::path::Path, _request: ControlRequest) -> Result<ControlResponse> {
+    anyhow::bail!("`control` needs Unix domain sockets, which this platform doesn't have")
+}
+
+/// Print the workspace health check; exits with status 1 if any check failed
+fn doctor() -> Result<ExitCode> {
+    let workspace_root = App::detect_workspace_root()?;
+    println!("Workspace: {}\n", workspace_root.display());
+
+    let report = App::check_health(&workspace_root, &App::workspace_config(&workspace_root));
+    println!("{}", report);
 
-    Ok(())
+    Ok(if report.worst() == Severity::Error { ExitCode::FAILURE } else { ExitCode::SUCCESS })
 }
@@ -0,0 +1,230 @@
+// Control Protocol
+// Requests a long-running sync-manager answers over a local socket in the state directory:
+// one line per request, one line per response
+//
+// `sync-manager serve` (core::daemon) polls `ControlServer` between rescans, and
+// `sync-manager control <request>` sends requests with `send`. Unix domain sockets only:
+// Windows named pipes are not implemented.
+
+use anyhow::{bail, Result};
+use std::path::{Path, PathBuf};
+
+use crate::core::session::STATE_DIR_NAME;
+
+/// Socket file name inside the state directory
+pub const SOCKET_FILE_NAME: &str = "control.sock";
+
+/// What a client asks the serving process to do
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControlRequest {
+    /// Report what the process is doing
+    Status,
+    /// Sync now instead of waiting for the next change
+    SyncNow,
+    /// Stop reacting to file changes until resumed
+    Pause,
+    Resume,
+}
+
+/// Answer to a request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ControlResponse {
+    Ok(String),
+    Error(String),
+}
+
+impl ControlRequest {
+    /// Word sent on the wire
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Status => "status",
+            Self::SyncNow => "sync",
+            Self::Pause => "pause",
+            Self::Resume => "resume",
+        }
+    }
+    
+    pub fn parse(line: &str) -> Result<Self> {
+        Ok(match line.trim() {
+            "status" => Self::Status,
+            "sync" => Self::SyncNow,
+            "pause" => Self::Pause,
+            "resume" => Self::Resume,
+            other => bail!("Unknown control request: {:?}", other),
+        })
+    }
+}
+
+impl ControlResponse {
+    /// "ok <message>" or "error <message>", with line breaks in the message escaped
+    pub fn encode(&self) -> String {
+        let (status, message) = match self {
+            Self::Ok(message) => ("ok", message),
+            Self::Error(message) => ("error", message),
+        };
+        format!("{} {}", status, message.replace('\\', "\\\\").replace('\n', "\\n"))
+    }
+    
+    pub fn parse(line: &str) -> Result<Self> {
+        let line = line.trim_end_matches(['\r', '\n']);
+        let (status, message) = line.split_once(' ').unwrap_or((line, ""));
+        let message = unescape(message);
+        Ok(match status {
+            "ok" => Self::Ok(message),
+            "error" => Self::Error(message),
+            _ => bail!("Malformed control response: {:?}", line),
+        })
+    }
+}
+
+/// Undo the escaping of `ControlResponse::encode`
+fn unescape(message: &str) -> String {
+    let mut text = String::with_capacity(message.len());
+    let mut chars = message.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            text.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => text.push('\n'),
+            Some(other) => text.push(other),
+            None => text.push('\\'),
+        }
+    }
+    text
+}
+
+/// Path of the control socket of a workspace
+pub fn socket_path(workspace_root: &Path) -> PathBuf {
+    workspace_root.join(STATE_DIR_NAME).join(SOCKET_FILE_NAME)
+}
+
+#[cfg(unix)]
+pub use unix::{send, ControlServer};
+
+#[cfg(unix)]
+mod unix {
+    use super::{ControlRequest, ControlResponse};
+    use anyhow::{bail, Context, Result};
+    use std::fs;
+    use std::io::{BufRead, BufReader, ErrorKind, Write};
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::path::{Path, PathBuf};
+    use std::time::Duration;
+    
+    /// How long a connected client may take to send its request
+    const READ_TIMEOUT: Duration = Duration::from_secs(2);
+    
+    /// Listening socket; the socket file is removed when dropped
+    #[derive(Debug)]
+    pub struct ControlServer {
+        listener: UnixListener,
+        path: PathBuf,
+    }
+    
+    impl ControlServer {
+        /// Listen at `path`, replacing a socket file left by a process that is gone
+        /// Fails if another process is listening there
+        pub fn bind(path: &Path) -> Result<Self> {
+            if path.exists() {
+                if UnixStream::connect(path).is_ok() {
+                    bail!("Another sync-manager is listening on {}", path.display());
+                }
+                fs::remove_file(path).with_context(|| format!("Failed to remove stale socket: {}", path.display()))?;
+            }
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+            }
+            let listener = UnixListener::bind(path).with_context(|| format!("Failed to listen on {}", path.display()))?;
+            listener.set_nonblocking(true)?;
+            Ok(Self { listener, path: path.to_path_buf() })
+        }
+        
+        /// Answer the clients waiting to be served, without blocking when there are none
+        /// Returns the number of requests handled
+        pub fn poll(&self, mut handler: impl FnMut(ControlRequest) -> ControlResponse) -> Result<usize> {
+            let mut handled = 0;
+            loop {
+                let stream = match self.listener.accept() {
+                    Ok((stream, _)) => stream,
+                    Err(err) if err.kind() == ErrorKind::WouldBlock => return Ok(handled),
+                    Err(err) => return Err(err).context("Failed to accept a control connection"),
+                };
+                stream.set_nonblocking(false)?;
+                stream.set_read_timeout(Some(READ_TIMEOUT))?;
+                let mut line = String::new();
+                // A connection closed without a request only checked that someone listens
+                if BufReader::new(&stream).read_line(&mut line)? == 0 {
+                    continue;
+                }
+                let response = match ControlRequest::parse(&line) {
+                    Ok(request) => handler(request),
+                    Err(err) => ControlResponse::Error(err.to_string()),
+                };
+                // A client that hung up doesn't stop the others from being served
+                let _ = writeln!(&stream, "{}", response.encode());
+                handled += 1;
+            }
+        }
+    }
+    
+    impl Drop for ControlServer {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+    
+    /// Send a request to the process listening at `path` and wait for its answer
+    pub fn send(path: &Path, request: ControlRequest) -> Result<ControlResponse> {
+        let stream = UnixStream::connect(path)
+            .with_context(|| format!("No sync-manager is listening on {}", path.display()))?;
+        writeln!(&stream, "{}", request.as_str())?;
+        let mut line = String::new();
+        BufReader::new(&stream).read_line(&mut line)?;
+        ControlResponse::parse(&line)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_protocol_round_trip() {
+        for request in [ControlRequest::Status, ControlRequest::SyncNow, ControlRequest::Pause, ControlRequest::Resume] {
+            assert_eq!(ControlRequest::parse(request.as_str()).unwrap(), request);
+        }
+        assert!(ControlRequest::parse("reboot").is_err());
+
+        let response = ControlResponse::Ok("watching\n2 projects \\ 0 pending".to_string());
+        assert_eq!(ControlResponse::parse(&response.encode()).unwrap(), response);
+        assert!(ControlResponse::parse("maybe").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_socket_request() {
+        let root = std::env::temp_dir().join(format!("sync-manager-control-{}", std::process::id()));
+        let path = socket_path(&root);
+        let server = ControlServer::bind(&path).unwrap();
+        assert!(ControlServer::bind(&path).is_err());
+
+        let client = {
+            let path = path.clone();
+            std::thread::spawn(move || send(&path, ControlRequest::Pause).unwrap())
+        };
+        let mut handled = 0;
+        while handled == 0 {
+            handled = server
+                .poll(|request| ControlResponse::Ok(format!("{} done", request.as_str())))
+                .unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+        assert_eq!(client.join().unwrap(), ControlResponse::Ok("pause done".to_string()));
+
+        drop(server);
+        assert!(!path.exists());
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -2,12 +2,28 @@
 // Computes differences between source and destination directories
 
 use anyhow::{Context, Result};
-use std::fs;
+use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use super::transform::{read_transformed, FileTransform, TransformPipeline};
+use super::cancel::CancellationToken;
+use super::filesystem::{FileSystem, RealFileSystem, Walk};
+use super::git::GitOps;
+use super::metadata::{Encoding, EntryMetadata, LineEndings};
+use super::watchdog::Watchdog;
+use crate::core::project_config::DirectionOverride;
+use crate::utilities::{format_timestamp, ExcludeMatcher};
+
+/// Transforms applied to the (source, destination) files before comparing them
+type Transforms<'a> = (Option<&'a FileTransform>, Option<&'a FileTransform>);
 
 /// Type of diff comparison being made
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum DiffType {
     /// Comparing shared resources to project (shared -> project)
     SharedToProject,
@@ -15,8 +31,16 @@ pub enum DiffType {
     ProjectToShared,
 }
 
+impl DiffType {
+    /// Whether the source side of this comparison is the shared copy (which holds the templates)
+    pub fn shared_is_source(&self) -> bool {
+        *self == DiffType::SharedToProject
+    }
+}
+
 /// Status of a file in the diff
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum FileStatus {
     /// File exists only in source (will be added to destination)
     Added,
@@ -43,12 +67,174 @@ pub struct DiffEntry {
     pub status: FileStatus,
     /// Type of diff this entry belongs to
     pub diff_type: DiffType,
+    /// Size of the source file in bytes
+    pub size: u64,
+    /// Modification time of the source file
+    pub modified: Option<SystemTime>,
+    /// Line counts of the difference (None for binary or unreadable files)
+    pub stats: Option<DiffStats>,
+    /// Transform applied to the shared copy (None if no transform rule matches the file)
+    pub transform: Option<FileTransform>,
+    /// Per-file direction override from the mapping (pinned / reverse)
+    pub direction_override: Option<DirectionOverride>,
+    /// Project whose mapping the entry came from (empty outside a project scan)
+    pub project: String,
+    /// The entry is a directory: an empty one missing from the destination (Added), or one
+    /// holding no files that is missing from the source (Deleted)
+    pub is_dir: bool,
+    /// Why the source looks like a secret (set by project scans); such entries are only synced
+    /// after an explicit override clears it
+    pub secret: Option<String>,
+}
+
+impl DiffEntry {
+    /// Whether a direction override keeps this entry from syncing in its direction
+    pub fn is_blocked(&self) -> bool {
+        match self.direction_override {
+            Some(DirectionOverride::Pinned) => true,
+            Some(DirectionOverride::Reverse) => self.diff_type.shared_is_source(),
+            None => false,
+        }
+    }
+    
+    /// Transform applied to the source file before comparing or copying it
+    pub fn source_transform(&self) -> Option<&FileTransform> {
+        self.transform.as_ref().filter(|_| self.diff_type.shared_is_source())
+    }
+    
+    /// Transform applied to the destination file before comparing it
+    pub fn dest_transform(&self) -> Option<&FileTransform> {
+        self.transform.as_ref().filter(|_| !self.diff_type.shared_is_source())
+    }
+    
+    /// Source and destination text as compared (tokens substituted in the shared copy)
+    /// Either side is None if it is missing or isn't valid UTF-8
+    pub fn comparison_text(&self) -> (Option<String>, Option<String>) {
+        (
+            read_transformed(&RealFileSystem, &self.source_path, self.source_transform()),
+            read_transformed(&RealFileSystem, &self.destination_path, self.dest_transform()),
+        )
+    }
+    
+    /// Modified shared → project entry of `project` whose relative, source and destination
+    /// paths are all `path`; tests override the fields they are about
+    #[cfg(test)]
+    pub fn for_test(path: impl AsRef<Path>, project: &str) -> Self {
+        let path = path.as_ref().to_path_buf();
+        Self {
+            source_path: path.clone(),
+            destination_path: path.clone(),
+            path,
+            status: FileStatus::Modified,
+            diff_type: DiffType::SharedToProject,
+            size: 0,
+            modified: None,
+            stats: None,
+            transform: None,
+            direction_override: None,
+            project: project.to_string(),
+            is_dir: false,
+            secret: None,
+        }
+    }
+}
+
+/// Why an entry differs, found by re-checking both files the way the scan does
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChangeReason {
+    /// Only the source exists
+    NewFile,
+    /// The source is gone but the destination remains
+    SourceRemoved,
+    /// An empty directory that the destination lacks
+    EmptyDirectory,
+    /// A directory holding no files that the source lacks
+    DirectoryRemoved,
+    /// (source, destination) sizes
+    SizeDiffers(u64, u64),
+    /// (source, destination) modification times, the source's being later
+    SourceNewer(SystemTime, SystemTime),
+    /// (source, destination) content hashes
+    ContentDiffers(String, String),
+    /// (source, destination) encodings
+    EncodingDiffers(Encoding, Encoding),
+    /// The text is the same apart from (source, destination) line endings
+    LineEndingsOnly(LineEndings, LineEndings),
+    /// The text is the same apart from whitespace within lines
+    WhitespaceOnly,
+    /// Only ignored differences (whitespace if ignored, sync-ignore regions, tokens): not a change
+    IgnoredOnly,
+    /// (source, destination) permissions; the scan doesn't compare them
+    PermissionsDiffer(String, String),
+}
+
+impl ChangeReason {
+    pub fn label(&self) -> String {
+        match self {
+            ChangeReason::NewFile => "New file: the destination doesn't exist".to_string(),
+            ChangeReason::SourceRemoved => "Removed: the source no longer exists".to_string(),
+            ChangeReason::EmptyDirectory => "Empty directory: the destination doesn't have it".to_string(),
+            ChangeReason::DirectoryRemoved => {
+                "Directory without files that the source doesn't have".to_string()
+            }
+            ChangeReason::SizeDiffers(source, dest) => format!("Size differs: {} vs {} bytes", source, dest),
+            ChangeReason::SourceNewer(source, dest) => format!(
+                "Source is newer: modified {} vs {}",
+                format_timestamp(*source),
+                format_timestamp(*dest)
+            ),
+            ChangeReason::ContentDiffers(source, dest) => {
+                format!("Content hash mismatch: {} vs {}", source, dest)
+            }
+            ChangeReason::EncodingDiffers(source, dest) => {
+                format!("Encoding differs: {} vs {}", source.label(), dest.label())
+            }
+            ChangeReason::LineEndingsOnly(source, dest) => {
+                format!("Line endings only: {} vs {}", source.label(), dest.label())
+            }
+            ChangeReason::WhitespaceOnly => "Whitespace only: lines differ in spacing".to_string(),
+            ChangeReason::IgnoredOnly => {
+                "Only ignored differences (whitespace, sync-ignore regions or tokens): not a change".to_string()
+            }
+            ChangeReason::PermissionsDiffer(source, dest) => {
+                format!("Permissions differ: {} vs {} (not synced)", source, dest)
+            }
+        }
+    }
+}
+
+/// Rule that decides whether a scan walks a path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExcludeRule {
+    /// No exclude matches
+    Kept,
+    /// An exclude matched, but so did an include
+    Included { exclude: String, include: String },
+    /// Skipped because of `rule` (built-in or global when `builtin`, else the mapping's),
+    /// which matched at `at` (the path itself or one of its directories)
+    Excluded { rule: String, builtin: bool, at: PathBuf },
 }
 
 /// Engine for computing directory differences
+#[derive(Debug, Clone)]
 pub struct DiffEngine {
     /// Global exclude patterns
     exclude_patterns: Vec<String>,
+    /// Pattern lists compiled so far, keyed by the list; shared by the engine's clones, so a
+    /// refresh compiles each list once however many paths and mappings it checks
+    matchers: Arc<Mutex<HashMap<Vec<String>, Arc<ExcludeMatcher>>>>,
+    /// Treat changes in the amount of whitespace within lines as no change
+    ignore_whitespace: bool,
+    /// Decide by content alone, never by modification times
+    ignore_timestamps: bool,
+    /// Heartbeat of scans run in the background (skipped paths aren't walked)
+    watchdog: Option<Arc<Watchdog>>,
+    /// Stop request of scans run in the background
+    cancel: Option<CancellationToken>,
+    /// Where the scanned files are read from
+    fs: Arc<dyn FileSystem>,
+    /// Don't descend into directories that are git repositories of their own
+    repo_boundaries: bool,
 }
 
 impl Default for DiffEngine {
@@ -79,6 +265,13 @@ impl DiffEngine {
                 ".idea".to_string(),
                 ".vscode".to_string(),
             ],
+            matchers: Arc::default(),
+            ignore_whitespace: false,
+            ignore_timestamps: false,
+            watchdog: None,
+            cancel: None,
+            fs: Arc::new(RealFileSystem),
+            repo_boundaries: false,
         }
     }
     
@@ -88,55 +281,183 @@ impl DiffEngine {
         self
     }
     
+    /// Compare lines with runs of whitespace collapsed and leading/trailing whitespace removed
+    pub fn with_ignore_whitespace(mut self, ignore: bool) -> Self {
+        self.ignore_whitespace = ignore;
+        self
+    }
+    
+    /// Compare content only: a newer source with the same bytes isn't a change (for checkouts
+    /// and CI artifacts, where timestamps say nothing)
+    pub fn with_ignore_timestamps(mut self, ignore: bool) -> Self {
+        self.ignore_timestamps = ignore;
+        self
+    }
+    
+    /// Report progress to `watchdog`; paths it skips aren't walked
+    pub fn with_watchdog(mut self, watchdog: Arc<Watchdog>) -> Self {
+        self.watchdog = Some(watchdog);
+        self
+    }
+    
+    /// Stop walking when `cancel` is cancelled (the result then misses the files not reached)
+    pub fn with_cancellation(mut self, cancel: CancellationToken) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+    
+    /// Scan `fs` instead of the disk
+    pub fn with_filesystem(mut self, fs: Arc<dyn FileSystem>) -> Self {
+        self.fs = fs;
+        self
+    }
+    
+    /// Treat submodules and nested repositories (directories with a `.git` on either side) as
+    /// boundaries: they are left out of the scan, as their content belongs to another repository
+    pub fn with_repo_boundaries(mut self, enabled: bool) -> Self {
+        self.repo_boundaries = enabled;
+        self
+    }
+    
+    /// Whether the scan was asked to stop
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.as_ref().is_some_and(CancellationToken::is_cancelled)
+    }
+    
+    /// Whether the watchdog asked to skip `path`
+    fn is_skipped(&self, path: &Path) -> bool {
+        self.watchdog.as_ref().is_some_and(|watchdog| watchdog.is_skipped(path))
+    }
+    
+    /// Whether the scan stops at directory `dir` or its `counterpart` on the other side, as
+    /// one of them is a repository of its own
+    fn is_repo_boundary(&self, dir: &Path, counterpart: &Path) -> bool {
+        self.repo_boundaries && (self.fs.exists(&dir.join(".git")) || self.fs.exists(&counterpart.join(".git")))
+    }
+    
     /// Compute differences between two directories
+    /// Paths matched by `includes` are kept even when an exclude matches them; files matched
+    /// by a rule of `transforms` are compared with tokens substituted in the shared copy
     pub fn compute_diff(
         &self,
         source_dir: &Path,
         dest_dir: &Path,
         diff_type: DiffType,
         additional_excludes: &[String],
+        includes: &[String],
+        transforms: &TransformPipeline,
     ) -> Result<Vec<DiffEntry>> {
         let mut diffs = Vec::new();
         
-        // Combine all exclude patterns
-        let all_excludes: Vec<&str> = self
-            .exclude_patterns
-            .iter()
-            .chain(additional_excludes.iter())
-            .map(|s| s.as_str())
-            .collect();
+        // Patterns are compiled once per list and reused across mappings and directions
+        let builtin = self.matcher(&self.exclude_patterns);
+        let mapping = self.matcher(additional_excludes);
+        let includes = self.matcher(includes);
+        let excluded = |path: &Path| (builtin.is_match(path) || mapping.is_match(path)) && !includes.is_match(path);
         
         // Walk through source directory
-        if source_dir.exists() {
-            for entry in walkdir::WalkDir::new(source_dir)
-                .into_iter()
-                .filter_entry(|e| !Self::should_exclude(e.path(), &all_excludes))
-                .filter_map(|e| e.ok())
-            {
-                let source_path = entry.path();
+        let fs = self.fs.as_ref();
+        if fs.exists(source_dir) {
+            let mut walker = Walk::new(fs, source_dir);
+            while let Some(entry) = walker.next() {
+                let source_path = entry.path.as_path();
+                if excluded(source_path) || self.is_skipped(source_path) {
+                    walker.skip_current_dir();
+                    continue;
+                }
+                if self.is_cancelled() {
+                    break;
+                }
+                if let Some(watchdog) = &self.watchdog {
+                    watchdog.beat(source_path);
+                }
                 
-                if source_path.is_file() {
+                if entry.is_dir && entry.depth > 0 {
+                    let relative_path = source_path
+                        .strip_prefix(source_dir)
+                        .context("Failed to calculate relative path")?;
+                    let dest_path = dest_dir.join(relative_path);
+                    if self.is_repo_boundary(source_path, &dest_path) {
+                        walker.skip_current_dir();
+                        continue;
+                    }
+                    // Directories with files are created by copying them; only empty ones need an entry
+                    if !fs.is_dir(&dest_path) && is_empty_dir(fs, source_path) {
+                        diffs.push(Self::dir_entry(relative_path, source_path, dest_path, FileStatus::Added, &diff_type));
+                    }
+                } else if fs.is_file(source_path) {
                     let relative_path = source_path
                         .strip_prefix(source_dir)
                         .context("Failed to calculate relative path")?;
                     
                     let dest_path = dest_dir.join(relative_path);
-                    let status = Self::determine_status(source_path, &dest_path)?;
+                    let transform = transforms.for_file(relative_path);
+                    let sides = if diff_type.shared_is_source() {
+                        (transform.as_ref(), None)
+                    } else {
+                        (None, transform.as_ref())
+                    };
+                    let status = self.determine_status(source_path, &dest_path, sides)?;
                     
                     // Only include files that need syncing
                     if status != FileStatus::Unchanged {
+                        let metadata = fs.metadata(source_path).ok();
+                        let stats = self.compute_stats(source_path, &dest_path, &status, sides);
                         diffs.push(DiffEntry {
                             path: relative_path.to_path_buf(),
                             source_path: source_path.to_path_buf(),
                             destination_path: dest_path,
                             status,
                             diff_type: diff_type.clone(),
+                            size: metadata.map(|m| m.len).unwrap_or(0),
+                            modified: metadata.and_then(|m| m.modified),
+                            stats,
+                            transform,
+                            direction_override: None,
+                            project: String::new(),
+                            is_dir: false,
+                            secret: None,
                         });
                     }
                 }
             }
         }
         
+        // Directories left in the destination without a source: reported once at the top,
+        // and only if no file is left in them (files are reported by the reverse scan)
+        if fs.exists(dest_dir) {
+            let mut walker = Walk::new(fs, dest_dir);
+            while let Some(entry) = walker.next() {
+                if self.is_cancelled() {
+                    break;
+                }
+                if !entry.is_dir || entry.depth == 0 {
+                    continue;
+                }
+                let dest_path = entry.path.as_path();
+                if excluded(dest_path) || self.is_skipped(dest_path) {
+                    walker.skip_current_dir();
+                    continue;
+                }
+                let relative_path = dest_path
+                    .strip_prefix(dest_dir)
+                    .context("Failed to calculate relative path")?;
+                let source_path = source_dir.join(relative_path);
+                if self.is_repo_boundary(dest_path, &source_path) {
+                    walker.skip_current_dir();
+                    continue;
+                }
+                if fs.exists(&source_path) {
+                    continue;
+                }
+                walker.skip_current_dir();
+                if !contains_files(fs, dest_path) {
+                    let entry = Self::dir_entry(relative_path, &source_path, dest_path.to_path_buf(), FileStatus::Deleted, &diff_type);
+                    diffs.push(entry);
+                }
+            }
+        }
+        
         // Sort and deduplicate
         diffs.sort_by(|a, b| a.path.cmp(&b.path));
         diffs.dedup_by(|a, b| a.path == b.path);
@@ -144,29 +465,98 @@ impl DiffEngine {
         Ok(diffs)
     }
     
-    /// Check if a path should be excluded
-    fn should_exclude(path: &Path, patterns: &[&str]) -> bool {
-        let path_str = path.to_string_lossy().to_lowercase();
+    /// Entry for a directory that exists on one side only
+    fn dir_entry(
+        relative_path: &Path,
+        source_path: &Path,
+        destination_path: PathBuf,
+        status: FileStatus,
+        diff_type: &DiffType,
+    ) -> DiffEntry {
+        DiffEntry {
+            path: relative_path.to_path_buf(),
+            source_path: source_path.to_path_buf(),
+            destination_path,
+            status,
+            diff_type: diff_type.clone(),
+            size: 0,
+            modified: None,
+            stats: None,
+            transform: None,
+            direction_override: None,
+            project: String::new(),
+            is_dir: true,
+            secret: None,
+        }
+    }
+    
+    /// Built-in and global exclude patterns
+    pub fn exclude_patterns(&self) -> &[String] {
+        &self.exclude_patterns
+    }
+    
+    /// Whether the engine's exclude patterns skip a path
+    pub fn is_excluded(&self, path: &Path) -> bool {
+        self.matcher(&self.exclude_patterns).is_match(path)
+    }
+    
+    /// Compiled matcher of a pattern list, compiling it on first use
+    fn matcher(&self, patterns: &[String]) -> Arc<ExcludeMatcher> {
+        let mut matchers = self.matchers.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        matchers
+            .entry(patterns.to_vec())
+            .or_insert_with(|| Arc::new(ExcludeMatcher::new(patterns)))
+            .clone()
+    }
+    
+    /// Which rule decides whether the walk under `root` keeps `path`, checking the path's
+    /// directories from the top the way the walk does
+    pub fn exclude_rule(&self, root: &Path, path: &Path, additional_excludes: &[String], includes: &[String]) -> ExcludeRule {
+        let builtin = self.matcher(&self.exclude_patterns);
+        let mapping = self.matcher(additional_excludes);
+        let includes = self.matcher(includes);
         
-        patterns.iter().any(|pattern| {
-            if pattern.starts_with('*') {
-                path_str.ends_with(&pattern[1..])
-            } else {
-                path_str.contains(pattern)
+        let relative = path.strip_prefix(root).unwrap_or(path);
+        let mut current = root.to_path_buf();
+        let mut overridden = None;
+        for component in relative.components() {
+            current.push(component);
+            let (exclude, is_builtin) = match builtin.first_match(&current) {
+                Some(rule) => (rule, true),
+                None => match mapping.first_match(&current) {
+                    Some(rule) => (rule, false),
+                    None => continue,
+                },
+            };
+            match includes.first_match(&current) {
+                Some(include) => {
+                    overridden.get_or_insert_with(|| ExcludeRule::Included {
+                        exclude: exclude.to_string(),
+                        include: include.to_string(),
+                    });
+                }
+                None => {
+                    return ExcludeRule::Excluded {
+                        rule: exclude.to_string(),
+                        builtin: is_builtin,
+                        at: current,
+                    }
+                }
             }
-        })
+        }
+        overridden.unwrap_or(ExcludeRule::Kept)
     }
     
     /// Determine the status of a file
-    fn determine_status(source: &Path, dest: &Path) -> Result<FileStatus> {
-        let source_exists = source.exists();
-        let dest_exists = dest.exists();
+    fn determine_status(&self, source: &Path, dest: &Path, transforms: Transforms) -> Result<FileStatus> {
+        let source_exists = self.fs.exists(source);
+        let dest_exists = self.fs.exists(dest);
         
         match (source_exists, dest_exists) {
             (false, true) => Ok(FileStatus::Deleted),
             (true, false) => Ok(FileStatus::Added),
             (true, true) => {
-                if Self::files_need_sync(source, dest)? {
+                if self.files_need_sync(source, dest)? && self.differs_outside_ignored(source, dest, transforms) {
                     Ok(FileStatus::Modified)
                 } else {
                     Ok(FileStatus::Unchanged)
@@ -177,18 +567,24 @@ impl DiffEngine {
     }
     
     /// Check if files need to be synchronized
-    fn files_need_sync(source: &Path, dest: &Path) -> Result<bool> {
-        let source_meta = fs::metadata(source)?;
-        let dest_meta = fs::metadata(dest)?;
+    fn files_need_sync(&self, source: &Path, dest: &Path) -> Result<bool> {
+        let fs = self.fs.as_ref();
+        let source_meta = fs.metadata(source)?;
+        let dest_meta = fs.metadata(dest)?;
         
         // Compare file sizes
-        if source_meta.len() != dest_meta.len() {
+        if source_meta.len != dest_meta.len {
             return Ok(true);
         }
         
+        if self.ignore_timestamps {
+            return Ok(fs.read(source)? != fs.read(dest)?);
+        }
+        
         // Compare modification times
-        let source_mtime = source_meta.modified()?;
-        let dest_mtime = dest_meta.modified()?;
+        let (Some(source_mtime), Some(dest_mtime)) = (source_meta.modified, dest_meta.modified) else {
+            return Ok(fs.read(source)? != fs.read(dest)?);
+        };
         
         if source_mtime > dest_mtime {
             return Ok(true);
@@ -201,45 +597,181 @@ impl DiffEngine {
             .unwrap_or_default();
         
         if time_diff.as_secs_f64() > 1.0 {
-            let source_content = fs::read(source)?;
-            let dest_content = fs::read(dest)?;
+            let source_content = fs.read(source)?;
+            let dest_content = fs.read(dest)?;
             return Ok(source_content != dest_content);
         }
         
         // Final content check
-        let source_content = fs::read(source)?;
-        let dest_content = fs::read(dest)?;
+        let source_content = fs.read(source)?;
+        let dest_content = fs.read(dest)?;
         
         Ok(source_content != dest_content)
     }
     
+    /// Whether the files still differ once tokens are substituted and ignore regions
+    /// (sync-ignore-start/end) are left out; files that aren't UTF-8 text always count as different
+    /// A source whose transform runs commands also counts as equal if its formatted text is,
+    /// as the sync writes it formatted
+    fn differs_outside_ignored(&self, source: &Path, dest: &Path, (source_tf, dest_tf): Transforms) -> bool {
+        match (self.read_compared(source, source_tf), self.read_compared(dest, dest_tf)) {
+            (Some(source_text), Some(dest_text)) => {
+                !equal_ignoring_regions(&source_text, &dest_text)
+                    && !source_tf.is_some_and(|transform| self.formats_to(source, dest, transform, &dest_text))
+            }
+            _ => true,
+        }
+    }
+    
+    /// Whether `source`, rendered and run through the commands of `transform` as a sync would,
+    /// compares equal to `dest_text`
+    /// The commands run on a scratch copy next to `dest`, so formatters pick up its local config
+    fn formats_to(&self, source: &Path, dest: &Path, transform: &FileTransform, dest_text: &str) -> bool {
+        if transform.commands.is_empty() {
+            return false;
+        }
+        let (Some(dir), Some(name)) = (dest.parent(), dest.file_name()) else {
+            return false;
+        };
+        let Some(rendered) = read_transformed(self.fs.as_ref(), source, Some(transform)) else {
+            return false;
+        };
+        let scratch = dir.join(format!(".sync-manager-format-{}-{}", std::process::id(), name.to_string_lossy()));
+        let formatted = self.fs.write(&scratch, rendered.as_bytes()).is_ok() && transform.run_commands(&scratch).is_ok();
+        let text = self.read_compared(&scratch, None).filter(|_| formatted);
+        let _ = self.fs.remove_file(&scratch);
+        text.is_some_and(|text| equal_ignoring_regions(&text, dest_text))
+    }
+    
+    /// Text of a file as compared: transformed, and whitespace-normalized if enabled
+    fn read_compared(&self, path: &Path, transform: Option<&FileTransform>) -> Option<String> {
+        let content = read_transformed(self.fs.as_ref(), path, transform)?;
+        Some(if self.ignore_whitespace {
+            content.lines().map(collapse_whitespace).collect::<Vec<_>>().join("\n")
+        } else {
+            content
+        })
+    }
+    
+    /// Reasons the entry differs, as found now (empty if both files are gone or identical)
+    /// Mirrors the scan: size and mtime (unless timestamps are ignored) make it compare content, which decides
+    pub fn explain(&self, diff: &DiffEntry) -> Vec<ChangeReason> {
+        if diff.is_dir {
+            return match (self.fs.is_dir(&diff.source_path), self.fs.is_dir(&diff.destination_path)) {
+                (true, false) => vec![ChangeReason::EmptyDirectory],
+                (false, true) => vec![ChangeReason::DirectoryRemoved],
+                _ => Vec::new(),
+            };
+        }
+        let metadata = EntryMetadata::read(diff);
+        let (source, dest) = match (metadata.source, metadata.destination) {
+            (Some(source), Some(dest)) => (source, dest),
+            (Some(_), None) => return vec![ChangeReason::NewFile],
+            (None, Some(_)) => return vec![ChangeReason::SourceRemoved],
+            (None, None) => return Vec::new(),
+        };
+        
+        let mut reasons = Vec::new();
+        if source.size != dest.size {
+            reasons.push(ChangeReason::SizeDiffers(source.size, dest.size));
+        }
+        if let (Some(source_mtime), Some(dest_mtime)) = (source.modified, dest.modified) {
+            if source_mtime > dest_mtime && !self.ignore_timestamps {
+                reasons.push(ChangeReason::SourceNewer(source_mtime, dest_mtime));
+            }
+        }
+        if source.hash != dest.hash {
+            if let (Some(source_hash), Some(dest_hash)) = (&source.hash, &dest.hash) {
+                reasons.push(ChangeReason::ContentDiffers(source_hash.clone(), dest_hash.clone()));
+            }
+            if let (Some(source_enc), Some(dest_enc)) = (source.encoding, dest.encoding) {
+                if source_enc != dest_enc {
+                    reasons.push(ChangeReason::EncodingDiffers(source_enc, dest_enc));
+                }
+            }
+            // Compared line by line, so CRLF vs LF alone already reads as unchanged to the scan
+            let transforms = (diff.source_transform(), diff.dest_transform());
+            let texts = diff.comparison_text();
+            if let (Some(source_text), Some(dest_text)) = &texts {
+                if source_text.replace("\r\n", "\n") == dest_text.replace("\r\n", "\n") {
+                    let endings = source.line_endings.zip(dest.line_endings);
+                    let (source_eol, dest_eol) = endings.unwrap_or((LineEndings::None, LineEndings::None));
+                    reasons.push(ChangeReason::LineEndingsOnly(source_eol, dest_eol));
+                } else if !self.differs_outside_ignored(&diff.source_path, &diff.destination_path, transforms) {
+                    reasons.push(ChangeReason::IgnoredOnly);
+                } else if source_text.lines().map(collapse_whitespace).eq(dest_text.lines().map(collapse_whitespace)) {
+                    reasons.push(ChangeReason::WhitespaceOnly);
+                }
+            }
+        }
+        if source.permissions != dest.permissions {
+            reasons.push(ChangeReason::PermissionsDiffer(source.permissions, dest.permissions));
+        }
+        reasons
+    }
+    
+    /// Count differing lines between source and destination
+    /// A missing side counts as empty; returns None if either file isn't valid UTF-8 text
+    fn compute_stats(
+        &self,
+        source: &Path,
+        dest: &Path,
+        status: &FileStatus,
+        (source_tf, dest_tf): Transforms,
+    ) -> Option<DiffStats> {
+        let read_lines = |path: &Path, transform| -> Option<Vec<String>> {
+            if !self.fs.exists(path) {
+                return Some(Vec::new());
+            }
+            self.read_compared(path, transform)
+                .map(|content| content.lines().map(str::to_string).collect())
+        };
+        
+        let source_lines = read_lines(source, source_tf)?;
+        let dest_lines = read_lines(dest, dest_tf)?;
+        
+        // Only modified files need an alignment; added/deleted files are all one-sided
+        let aligned = match status {
+            FileStatus::Modified => align_lines(&source_lines, &dest_lines),
+            _ => (0..source_lines.len())
+                .map(LineAlignment::SourceOnly)
+                .chain((0..dest_lines.len()).map(LineAlignment::DestOnly))
+                .collect(),
+        };
+        Some(DiffStats::from_alignment(&aligned, &source_lines, &dest_lines))
+    }
+    
     /// Load unified diff content for a diff entry
     pub fn load_diff_content(diff: &DiffEntry) -> Option<String> {
-        // Try git diff first
-        if let Ok(output) = Command::new("git")
-            .args(["diff", "--no-index"])
-            .arg(&diff.source_path)
-            .arg(&diff.destination_path)
-            .output()
-        {
-            if !output.stdout.is_empty() {
-                return String::from_utf8(output.stdout).ok();
+        // Try git diff first (except for transformed files: git would only see the raw template)
+        if diff.transform.is_none() && GitOps::is_available() {
+            if let Ok(output) = Command::new("git")
+                .args(["diff", "--no-index"])
+                .arg(&diff.source_path)
+                .arg(&diff.destination_path)
+                .output()
+            {
+                if !output.stdout.is_empty() {
+                    return String::from_utf8(output.stdout).ok();
+                }
             }
         }
         
-        // Fallback to simple diff
-        match (
-            fs::read_to_string(&diff.source_path),
-            fs::read_to_string(&diff.destination_path),
-        ) {
-            (Ok(source), Ok(dest)) => Some(Self::generate_simple_diff(&source, &dest, &diff.source_path)),
-            (Ok(source), Err(_)) => Some(format!(
+        Self::simple_diff(diff)
+    }
+    
+    /// Diff built without git: used for transformed files, files missing on one side and
+    /// when git isn't available. None if neither file can be read as text
+    pub fn simple_diff(diff: &DiffEntry) -> Option<String> {
+        match diff.comparison_text() {
+            (Some(source), Some(dest)) => Some(Self::generate_simple_diff(&source, &dest, &diff.source_path)),
+            (Some(source), None) => Some(format!(
                 "--- {}\n+++ {}\n@@ -1,0 +0,0 @@\n{}",
                 diff.source_path.display(),
                 diff.destination_path.display(),
                 source.lines().map(|l| format!("+{}", l)).collect::<Vec<_>>().join("\n")
             )),
-            (Err(_), Ok(dest)) => Some(format!(
+            (None, Some(dest)) => Some(format!(
                 "--- {}\n+++ {}\n@@ -0,0 +1,0 @@\n{}",
                 diff.source_path.display(),
                 diff.destination_path.display(),
@@ -286,262 +818,246 @@ impl DiffEngine {
     }
 }
 
-// ============================================================================
-// Line Alignment for Side-by-Side View
-// ============================================================================
-
-/// How lines are aligned between source and destination
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
-pub enum LineAlignment {
-    /// Lines exist in both files at given indices
-    Both(usize, usize),
-    /// Line only exists in source (removed)
-    SourceOnly(usize),
-    /// Line only exists in destination (added)
-    DestOnly(usize),
+/// Whether `dir` has no entries at all
+fn is_empty_dir(fs: &dyn FileSystem, dir: &Path) -> bool {
+    fs.read_dir(dir).is_ok_and(|entries| entries.is_empty())
 }
 
-/// Check if two lines are similar enough to show as modified (Both) vs separate (SourceOnly/DestOnly)
-/// Lines are considered similar if they share significant content
-fn lines_are_similar(line1: &str, line2: &str) -> bool {
-    if line1.is_empty() && line2.is_empty() {
-        return true;
-    }
-    if line1.is_empty() || line2.is_empty() {
-        return false;
-    }
-    
-    // Check if lines share significant word overlap
-    let words1: std::collections::HashSet<&str> = line1.split_whitespace().collect();
-    let words2: std::collections::HashSet<&str> = line2.split_whitespace().collect();
-    
-    let intersection: usize = words1.intersection(&words2).count();
-    let union: usize = words1.union(&words2).count();
-    
-    if union == 0 {
-        return false;
-    }
-    
-    // If more than 30% of words overlap, consider them similar
-    let similarity = intersection as f64 / union as f64;
-    similarity > 0.3
+/// Whether any file (or symlink) is found anywhere under `dir`
+pub(crate) fn contains_files(fs: &dyn FileSystem, dir: &Path) -> bool {
+    Walk::new(fs, dir).any(|entry| !entry.is_dir)
 }
 
-/// Align lines between source and destination using LCS (Longest Common Subsequence)
-/// This finds the optimal alignment by maximizing matching lines
-pub fn align_lines(source: &[String], dest: &[String]) -> Vec<LineAlignment> {
-    let n = source.len();
-    let m = dest.len();
-    
-    // dp[i][j] = length of LCS of source[0..i] and dest[0..j]
-    let mut dp = vec![vec![0u32; m + 1]; n + 1];
-    
-    // Fill DP table
-    for i in 1..=n {
-        for j in 1..=m {
-            if source[i - 1] == dest[j - 1] {
-                dp[i][j] = dp[i - 1][j - 1] + 1;
-            } else {
-                dp[i][j] = dp[i - 1][j].max(dp[i][j - 1]);
-            }
-        }
-    }
-    
-    // Backtrack to build alignment
-    // When backtracking, we prefer matching lines when they're equal
-    // Otherwise, we choose the path that maintains the LCS length
-    let mut aligned = Vec::new();
-    let mut i = n;
-    let mut j = m;
-    
-    while i > 0 || j > 0 {
-        if i > 0 && j > 0 && source[i - 1] == dest[j - 1] {
-            // Lines match exactly - always prefer this (part of LCS)
-            aligned.push(LineAlignment::Both(i - 1, j - 1));
-            i -= 1;
-            j -= 1;
-        } else if i > 0 && j > 0 {
-            // Lines don't match - choose path that maintains LCS
-            // Check if lines are similar enough to show as modified (Both) vs separate (SourceOnly/DestOnly)
-            let src_line = &source[i - 1];
-            let dest_line = &dest[j - 1];
-            
-            // If lines share significant content, show as Both (modified) for word-level diff
-            // Otherwise, show as separate insertions/deletions
-            let are_similar = lines_are_similar(src_line, dest_line);
-            
-            if are_similar {
-                // Lines are similar - show as Both (modified) for word-level highlighting
-                aligned.push(LineAlignment::Both(i - 1, j - 1));
-                i -= 1;
-                j -= 1;
-            } else if dp[i - 1][j] > dp[i][j - 1] {
-                // Removing from source maintains better LCS
-                aligned.push(LineAlignment::SourceOnly(i - 1));
-                i -= 1;
-            } else if dp[i][j - 1] > dp[i - 1][j] {
-                // Removing from dest maintains better LCS
-                aligned.push(LineAlignment::DestOnly(j - 1));
-                j -= 1;
-            } else {
-                // Tie - prefer showing as separate changes
-                aligned.push(LineAlignment::SourceOnly(i - 1));
-                i -= 1;
-            }
-        } else if i > 0 {
-            // Only source has lines left
-            aligned.push(LineAlignment::SourceOnly(i - 1));
-            i -= 1;
-        } else if j > 0 {
-            // Only dest has lines left
-            aligned.push(LineAlignment::DestOnly(j - 1));
-            j -= 1;
-        } else {
-            break;
-        }
-    }
-    
-    // Reverse because we built backwards
-    aligned.reverse();
-    aligned
+/// A line with leading/trailing whitespace removed and inner runs collapsed to one space
+fn collapse_whitespace(line: &str) -> String {
+    line.split_whitespace().collect::<Vec<_>>().join(" ")
 }
 
-/// Compute word-level diff for source line
-/// Returns segments with (text, is_changed) where is_changed=true means this part was removed/changed
-pub fn compute_word_diff_source(line: &str, other: &str) -> Vec<(String, bool)> {
-    if line == other {
-        return vec![(line.to_string(), false)];
-    }
-    
-    // Special case: if source is a prefix of destination (text was added), source shows no changes
-    if other.starts_with(line) {
-        return vec![(line.to_string(), false)];
-    }
-    
-    let line_words: Vec<&str> = line.split_inclusive(char::is_whitespace).collect();
-    let other_words: Vec<&str> = other.split_inclusive(char::is_whitespace).collect();
+// ============================================================================
+// Line Alignment and Word Diff for Side-by-Side View
+// ============================================================================
+
+// Alignment and word diffs are shared with the components' split diff view
+pub use tui_components::{
+    align_lines, compute_word_diff_dest, compute_word_diff_source, equal_ignoring_regions, DiffStats, LineAlignment,
+};
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operations::MemoryFileSystem;
+    use std::fs;
     
-    // Find longest common prefix
-    let mut prefix_len = 0;
-    let min_len = line_words.len().min(other_words.len());
-    for i in 0..min_len {
-        if line_words[i] == other_words[i] {
-            prefix_len = i + 1;
-        } else {
-            break;
-        }
+    #[test]
+    fn test_explain_line_endings_and_whitespace() {
+        let dir = std::env::temp_dir().join(format!("sync-manager-explain-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let entry = |name: &str, source: &str, dest: &str| {
+            let source_path = dir.join(format!("{}.src", name));
+            let destination_path = dir.join(format!("{}.dest", name));
+            fs::write(&destination_path, dest).unwrap();
+            fs::write(&source_path, source).unwrap();
+            DiffEntry {
+                source_path,
+                destination_path,
+                ..DiffEntry::for_test(name, "")
+            }
+        };
+        let engine = DiffEngine::new();
+        
+        let reasons = engine.explain(&entry("eol", "a\r\nb\r\n", "a\nb\n"));
+        assert!(matches!(reasons[0], ChangeReason::SizeDiffers(6, 4)));
+        assert!(reasons.contains(&ChangeReason::LineEndingsOnly(LineEndings::CrLf, LineEndings::Lf)));
+        
+        let reasons = engine.explain(&entry("spaces", "a  b\n", "a b \n"));
+        assert!(reasons.contains(&ChangeReason::WhitespaceOnly));
+        let reasons = DiffEngine::new().with_ignore_whitespace(true).explain(&entry("spaces", "a  b\n", "a b \n"));
+        assert!(reasons.contains(&ChangeReason::IgnoredOnly));
+        
+        let reasons = engine.explain(&entry("same", "same\n", "same\n"));
+        assert!(reasons.iter().all(|reason| matches!(reason, ChangeReason::SourceNewer(..))));
+        
+        fs::remove_dir_all(&dir).ok();
     }
     
-    // Find longest common suffix
-    let mut suffix_len = 0;
-    let max_suffix = (min_len - prefix_len)
-        .min(line_words.len() - prefix_len)
-        .min(other_words.len() - prefix_len);
-    
-    for i in 0..max_suffix {
-        let line_idx = line_words.len() - 1 - i;
-        let other_idx = other_words.len() - 1 - i;
-        if line_idx >= prefix_len && other_idx >= prefix_len && line_words[line_idx] == other_words[other_idx] {
-            suffix_len = i + 1;
-        } else {
-            break;
-        }
+    #[test]
+    fn test_ignore_timestamps_compares_content_only() {
+        let dir = std::env::temp_dir().join(format!("sync-manager-timestamps-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let (source, dest) = (dir.join("artifact.bin"), dir.join("artifact.bin.dest"));
+        fs::write(&source, b"\xffsame").unwrap();
+        fs::write(&dest, b"\xffsame").unwrap();
+        let newer = SystemTime::now() + std::time::Duration::from_secs(60);
+        fs::File::options().write(true).open(&source).unwrap().set_modified(newer).unwrap();
+        
+        let status = |engine: DiffEngine| engine.determine_status(&source, &dest, (None, None)).unwrap();
+        assert_eq!(status(DiffEngine::new()), FileStatus::Modified);
+        assert_eq!(status(DiffEngine::new().with_ignore_timestamps(true)), FileStatus::Unchanged);
+        
+        fs::write(&dest, b"\xffdiff").unwrap();
+        assert_eq!(status(DiffEngine::new().with_ignore_timestamps(true)), FileStatus::Modified);
+        
+        fs::remove_dir_all(&dir).ok();
     }
     
-    let mut result = Vec::new();
-    
-    // Add prefix (unchanged)
-    if prefix_len > 0 {
-        let prefix: String = line_words[..prefix_len].concat();
-        result.push((prefix, false));
+    #[test]
+    fn test_directory_entries_sync() {
+        use crate::operations::{SyncEngine, SyncOptions};
+        
+        let dir = std::env::temp_dir().join(format!("sync-manager-dirs-{}", std::process::id()));
+        let (source, dest) = (dir.join("shared"), dir.join("project"));
+        fs::create_dir_all(source.join("empty")).unwrap();
+        fs::create_dir_all(source.join("docs")).unwrap();
+        fs::write(source.join("docs/a.md"), "a").unwrap();
+        fs::create_dir_all(dest.join("docs")).unwrap();
+        fs::write(dest.join("docs/a.md"), "a").unwrap();
+        fs::create_dir_all(dest.join("old/nested")).unwrap();
+        fs::create_dir_all(dest.join("kept")).unwrap();
+        fs::write(dest.join("kept/file.md"), "kept").unwrap();
+        
+        let engine = DiffEngine::new().with_ignore_timestamps(true);
+        let diffs = engine
+            .compute_diff(&source, &dest, DiffType::SharedToProject, &[], &[], &TransformPipeline::default())
+            .unwrap();
+        let found: Vec<_> = diffs.iter().map(|diff| (diff.path.to_str().unwrap(), diff.status.clone(), diff.is_dir)).collect();
+        assert_eq!(found, [("empty", FileStatus::Added, true), ("old", FileStatus::Deleted, true)]);
+        
+        // Removing directories is off by default
+        let result = SyncEngine::new(SyncOptions { create_backup: false, ..SyncOptions::default() }).sync_files(&diffs);
+        assert_eq!((result.synced, result.skipped), (1, 1));
+        assert!(dest.join("empty").is_dir() && dest.join("old").is_dir());
+        
+        let options = SyncOptions { create_backup: false, remove_directories: true, ..SyncOptions::default() };
+        SyncEngine::new(options).sync_files(&diffs);
+        assert!(!dest.join("old").exists() && dest.join("kept/file.md").exists());
+        
+        fs::remove_dir_all(&dir).ok();
     }
     
-    // Add middle part (changed/removed in source)
-    let middle_start = prefix_len;
-    let middle_end = line_words.len() - suffix_len;
-    if middle_start < middle_end {
-        let middle: String = line_words[middle_start..middle_end].concat();
-        result.push((middle, true));
+    #[test]
+    fn test_scan_on_memory_filesystem() {
+        let fs = Arc::new(MemoryFileSystem::new());
+        fs.add_file("/shared/same.md", "same");
+        fs.add_file("/project/same.md", "same");
+        fs.add_file("/project/changed.md", "old");
+        fs.add_file("/shared/changed.md", "new");
+        fs.add_file("/shared/added.md", "added");
+        fs.add_file("/shared/node_modules/pkg/index.js", "excluded");
+        fs.add_dir("/shared/empty");
+        fs.add_dir("/project/removed/nested");
+        
+        let engine = DiffEngine::new().with_filesystem(fs);
+        let diffs = engine
+            .compute_diff(
+                Path::new("/shared"),
+                Path::new("/project"),
+                DiffType::SharedToProject,
+                &[],
+                &[],
+                &TransformPipeline::default(),
+            )
+            .unwrap();
+        let found: Vec<_> = diffs.iter().map(|diff| (diff.path.to_str().unwrap(), &diff.status, diff.is_dir)).collect();
+        assert_eq!(
+            found,
+            [
+                ("added.md", &FileStatus::Added, false),
+                ("changed.md", &FileStatus::Modified, false),
+                ("empty", &FileStatus::Added, true),
+                ("removed", &FileStatus::Deleted, true),
+            ]
+        );
+        assert_eq!(diffs[1].stats.map(|stats| (stats.source_changes(), stats.dest_changes())), Some((1, 1)));
     }
     
-    // Add suffix (unchanged)
-    if suffix_len > 0 {
-        let suffix: String = line_words[line_words.len() - suffix_len..].concat();
-        result.push((suffix, false));
+    #[test]
+    fn test_repo_boundaries() {
+        let fs = Arc::new(MemoryFileSystem::new());
+        fs.add_file("/shared/lib/readme.md", "shared");
+        fs.add_file("/project/lib/.git", "gitdir: ../.git/modules/lib");
+        fs.add_file("/project/lib/readme.md", "submodule");
+        fs.add_file("/shared/vendor/tool.md", "tool");
+        fs.add_dir("/shared/vendor/.git");
+        fs.add_file("/shared/docs.md", "docs");
+        
+        let scan = |engine: DiffEngine| -> Vec<String> {
+            engine
+                .compute_diff(
+                    Path::new("/shared"),
+                    Path::new("/project"),
+                    DiffType::SharedToProject,
+                    &[],
+                    &[],
+                    &TransformPipeline::default(),
+                )
+                .unwrap()
+                .iter()
+                .map(|diff| diff.path.display().to_string())
+                .collect()
+        };
+        let engine = DiffEngine::new().with_filesystem(fs);
+        assert_eq!(scan(engine.clone()), ["docs.md", "lib/readme.md", "vendor/tool.md"]);
+        assert_eq!(scan(engine.with_repo_boundaries(true)), ["docs.md"]);
     }
     
-    result
-}
-
-/// Compute word-level diff for destination line
-/// Returns segments with (text, is_changed) where is_changed=true means this part was added/changed
-pub fn compute_word_diff_dest(line: &str, other: &str) -> Vec<(String, bool)> {
-    if line == other {
-        return vec![(line.to_string(), false)];
-    }
-    
-    // Special case: if source is a prefix of destination (text was added), only highlight the added part
-    if line.starts_with(other) {
-        let added = &line[other.len()..];
-        if !added.is_empty() {
-            return vec![
-                (other.to_string(), false),  // Original part (from source) unchanged
-                (added.to_string(), true),    // Added part highlighted
-            ];
+    /// Entry for a case under tests/fixtures/unified_diff: the file in `source/` against the
+    /// file in `dest/` (a side without one gets the other side's name, so it doesn't exist)
+    fn fixture_entry(case: &Path) -> DiffEntry {
+        let file_in = |side: &str| {
+            fs::read_dir(case.join(side)).ok()?.filter_map(|e| e.ok()).map(|e| e.file_name()).next()
+        };
+        let (source_name, dest_name) = (file_in("source"), file_in("dest"));
+        let name = source_name.clone().or(dest_name.clone()).expect("fixture has a file");
+        let status = match (&source_name, &dest_name) {
+            (Some(_), None) => FileStatus::Added,
+            (None, Some(_)) => FileStatus::Deleted,
+            _ => FileStatus::Modified,
+        };
+        DiffEntry {
+            source_path: case.join("source").join(source_name.unwrap_or(name.clone())),
+            destination_path: case.join("dest").join(dest_name.unwrap_or(name.clone())),
+            status,
+            ..DiffEntry::for_test(name, "")
         }
     }
     
-    let line_words: Vec<&str> = line.split_inclusive(char::is_whitespace).collect();
-    let other_words: Vec<&str> = other.split_inclusive(char::is_whitespace).collect();
-    
-    // Find longest common prefix
-    let mut prefix_len = 0;
-    let min_len = line_words.len().min(other_words.len());
-    for i in 0..min_len {
-        if line_words[i] == other_words[i] {
-            prefix_len = i + 1;
-        } else {
-            break;
+    /// Compare `actual` with the golden file (a missing golden file means no diff is expected)
+    /// `UPDATE_GOLDEN=1 cargo test` rewrites the golden files from the current output
+    fn check_golden(golden: &Path, actual: Option<String>) -> Result<(), String> {
+        if std::env::var_os("UPDATE_GOLDEN").is_some() {
+            match &actual {
+                Some(text) => fs::write(golden, text).map_err(|e| e.to_string())?,
+                None => {
+                    fs::remove_file(golden).ok();
+                }
+            }
         }
-    }
-    
-    // Find longest common suffix
-    let mut suffix_len = 0;
-    let max_suffix = (min_len - prefix_len)
-        .min(line_words.len() - prefix_len)
-        .min(other_words.len() - prefix_len);
-    
-    for i in 0..max_suffix {
-        let line_idx = line_words.len() - 1 - i;
-        let other_idx = other_words.len() - 1 - i;
-        if line_idx >= prefix_len && other_idx >= prefix_len && line_words[line_idx] == other_words[other_idx] {
-            suffix_len = i + 1;
+        let expected = fs::read_to_string(golden).ok();
+        if expected == actual {
+            Ok(())
         } else {
-            break;
+            Err(format!("{}:\n--- expected\n{:?}\n--- actual\n{:?}", golden.display(), expected, actual))
         }
     }
     
-    let mut result = Vec::new();
-    
-    // Add prefix (unchanged)
-    if prefix_len > 0 {
-        let prefix: String = line_words[..prefix_len].concat();
-        result.push((prefix, false));
-    }
-    
-    // Add middle part (added/changed in destination)
-    let middle_start = prefix_len;
-    let middle_end = line_words.len() - suffix_len;
-    if middle_start < middle_end {
-        let middle: String = line_words[middle_start..middle_end].concat();
-        result.push((middle, true));
-    }
-    
-    // Add suffix (unchanged)
-    if suffix_len > 0 {
-        let suffix: String = line_words[line_words.len() - suffix_len..].concat();
-        result.push((suffix, false));
+    #[test]
+    fn test_unified_diff_golden_files() {
+        // Relative to the crate root (the test's working directory), so headers don't hold temp paths
+        let fixtures = Path::new("tests/fixtures/unified_diff");
+        let mut cases: Vec<PathBuf> = fs::read_dir(fixtures).unwrap().filter_map(|e| e.ok()).map(|e| e.path()).collect();
+        cases.sort();
+        assert!(cases.len() >= 5);
+        let git_available = GitOps::is_available();
+        
+        let mut failures = Vec::new();
+        for case in &cases {
+            let entry = fixture_entry(case);
+            failures.extend(check_golden(&case.join("simple.diff"), DiffEngine::simple_diff(&entry)).err());
+            if git_available {
+                failures.extend(check_golden(&case.join("unified.diff"), DiffEngine::load_diff_content(&entry)).err());
+            }
+        }
+        assert!(failures.is_empty(), "{}", failures.join("\n\n"));
     }
-    
-    result
 }
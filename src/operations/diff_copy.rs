@@ -0,0 +1,169 @@
+// Diff Copy
+// Snippets of the side-by-side diff for the clipboard: the line at a position, or the change
+// block (hunk) there in unified diff form (`-` for the source, `+` for the destination); and
+// one side's lines with a hunk taken over from the other side
+
+use std::ops::Range;
+
+use super::diff::LineAlignment;
+
+fn is_unchanged(entry: &LineAlignment, source: &[String], dest: &[String]) -> bool {
+    matches!(*entry, LineAlignment::Both(s, d) if source[s] == dest[d])
+}
+
+/// Text of aligned entry `index`: its destination line, or its source line if it has none
+pub fn aligned_line(aligned: &[LineAlignment], source: &[String], dest: &[String], index: usize) -> Option<String> {
+    match *aligned.get(index)? {
+        LineAlignment::Both(_, d) | LineAlignment::DestOnly(d) => dest.get(d).cloned(),
+        LineAlignment::SourceOnly(s) => source.get(s).cloned(),
+    }
+}
+
+/// Entries of the run of changes containing `index`, else of the first run after it
+pub fn hunk_range(aligned: &[LineAlignment], source: &[String], dest: &[String], index: usize) -> Option<Range<usize>> {
+    let changed = |i: usize| !is_unchanged(&aligned[i], source, dest);
+    let mut start = (index..aligned.len()).find(|&i| changed(i))?;
+    if start == index {
+        while start > 0 && changed(start - 1) {
+            start -= 1;
+        }
+    }
+    let end = (start..aligned.len()).find(|&i| !changed(i)).unwrap_or(aligned.len());
+    Some(start..end)
+}
+
+/// A hunk in unified diff form with up to `context` unchanged entries on each side, under its
+/// `@@ -start,count +start,count @@` header
+pub fn format_hunk(
+    aligned: &[LineAlignment],
+    source: &[String],
+    dest: &[String],
+    hunk: Range<usize>,
+    context: usize,
+) -> String {
+    let start = hunk.start.saturating_sub(context);
+    let end = (hunk.end + context).min(aligned.len());
+    
+    // Lines of each side before the hunk, for the header's start lines
+    let (mut source_line, mut dest_line) = (0, 0);
+    for entry in &aligned[..start] {
+        match entry {
+            LineAlignment::Both(..) => {
+                source_line += 1;
+                dest_line += 1;
+            }
+            LineAlignment::SourceOnly(_) => source_line += 1,
+            LineAlignment::DestOnly(_) => dest_line += 1,
+        }
+    }
+    
+    let (mut body, mut removed, mut added) = (Vec::new(), Vec::new(), Vec::new());
+    let (mut source_count, mut dest_count) = (0, 0);
+    for entry in &aligned[start..end] {
+        match *entry {
+            LineAlignment::Both(s, d) if source[s] == dest[d] => {
+                body.append(&mut removed);
+                body.append(&mut added);
+                body.push(format!(" {}", source[s]));
+                source_count += 1;
+                dest_count += 1;
+            }
+            LineAlignment::Both(s, d) => {
+                removed.push(format!("-{}", source[s]));
+                added.push(format!("+{}", dest[d]));
+                source_count += 1;
+                dest_count += 1;
+            }
+            LineAlignment::SourceOnly(s) => {
+                removed.push(format!("-{}", source[s]));
+                source_count += 1;
+            }
+            LineAlignment::DestOnly(d) => {
+                added.push(format!("+{}", dest[d]));
+                dest_count += 1;
+            }
+        }
+    }
+    body.append(&mut removed);
+    body.append(&mut added);
+    
+    // An empty side starts at the line before it, as in `diff -u`
+    let header_start = |before: usize, count: usize| if count == 0 { before } else { before + 1 };
+    let mut lines = vec![format!(
+        "@@ -{},{} +{},{} @@",
+        header_start(source_line, source_count),
+        source_count,
+        header_start(dest_line, dest_count),
+        dest_count
+    )];
+    lines.extend(body);
+    lines.join("\n")
+}
+
+/// Lines of one side (the destination if `into_dest`, else the source) with the entries of
+/// `hunk` replaced by the other side's lines there
+pub fn take_hunk(
+    aligned: &[LineAlignment],
+    source: &[String],
+    dest: &[String],
+    hunk: Range<usize>,
+    into_dest: bool,
+) -> Vec<String> {
+    aligned
+        .iter()
+        .enumerate()
+        .filter_map(|(index, entry)| {
+            let (s, d) = match *entry {
+                LineAlignment::Both(s, d) => (Some(s), Some(d)),
+                LineAlignment::SourceOnly(s) => (Some(s), None),
+                LineAlignment::DestOnly(d) => (None, Some(d)),
+            };
+            match (hunk.contains(&index), into_dest) {
+                (true, true) | (false, false) => s.map(|s| source[s].clone()),
+                (false, true) | (true, false) => d.map(|d| dest[d].clone()),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operations::diff::align_lines;
+
+    #[test]
+    fn test_hunk_at_position() {
+        let lines = |text: &str| -> Vec<String> { text.split(' ').map(str::to_string).collect() };
+        let source = lines("a b c d e f g h");
+        let dest = lines("a b C d e f g h new");
+        let aligned = align_lines(&source, &dest);
+
+        // From the top, the first change is found (c and C are too different to pair up)
+        let hunk = hunk_range(&aligned, &source, &dest, 0).unwrap();
+        assert_eq!(hunk, 2..4);
+        assert_eq!(hunk_range(&aligned, &source, &dest, 3), Some(2..4));
+        assert_eq!(format_hunk(&aligned, &source, &dest, hunk, 1), "@@ -2,3 +2,3 @@\n b\n-c\n+C\n d");
+
+        // Past it, the next one: a line only in the destination at the end
+        let hunk = hunk_range(&aligned, &source, &dest, 4).unwrap();
+        assert_eq!(format_hunk(&aligned, &source, &dest, hunk, 0), "@@ -8,0 +9,1 @@\n+new");
+        assert_eq!(hunk_range(&aligned, &source, &dest, 10), None);
+
+        assert_eq!(aligned_line(&aligned, &source, &dest, 3).as_deref(), Some("C"));
+    }
+
+    #[test]
+    fn test_take_hunk_from_either_side() {
+        let lines = |text: &str| -> Vec<String> { text.split(' ').map(str::to_string).collect() };
+        let source = lines("a b c d e");
+        let dest = lines("a B d e extra");
+        let aligned = align_lines(&source, &dest);
+        let first = hunk_range(&aligned, &source, &dest, 0).unwrap();
+        let last = hunk_range(&aligned, &source, &dest, first.end).unwrap();
+
+        // Only the taken hunk changes; the other one stays as it was
+        assert_eq!(take_hunk(&aligned, &source, &dest, first.clone(), true), lines("a b c d e extra"));
+        assert_eq!(take_hunk(&aligned, &source, &dest, first, false), lines("a B d e"));
+        assert_eq!(take_hunk(&aligned, &source, &dest, last, false), lines("a b c d e extra"));
+    }
+}
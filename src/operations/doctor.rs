@@ -0,0 +1,434 @@
+// Health Check
+// `sync-manager doctor`: verifies that the configuration and workspace are usable and
+// reports actionable findings (also shown in the TUI as a popup report)
+
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::path::Path;
+
+use super::secrets::invalid_patterns;
+use super::snapshot::SnapshotStore;
+use super::GitOps;
+use crate::core::local_config::{LocalConfig, LOCAL_CONFIG_NAME};
+use crate::core::project_config::ProjectConfig;
+use crate::core::diff_cache::DiffCache;
+use crate::core::session::{SessionState, STATE_DIR_NAME};
+use crate::core::AppConfig;
+use crate::utilities::pattern_problem;
+
+/// How serious a finding is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Check passed
+    Ok,
+    /// Works, but probably not as intended
+    Warning,
+    /// Syncing will fail or do the wrong thing
+    Error,
+}
+
+/// Result of one check
+#[derive(Debug, Clone)]
+pub struct Finding {
+    pub severity: Severity,
+    /// What was checked (e.g. "config", "mapping .cursor/rules")
+    pub subject: String,
+    pub message: String,
+    /// What to do about it
+    pub hint: Option<String>,
+}
+
+/// All findings of a health check, in check order
+#[derive(Debug, Clone, Default)]
+pub struct HealthReport {
+    pub findings: Vec<Finding>,
+}
+
+impl HealthReport {
+    fn push(&mut self, severity: Severity, subject: impl Into<String>, message: impl Into<String>, hint: Option<&str>) {
+        self.findings.push(Finding {
+            severity,
+            subject: subject.into(),
+            message: message.into(),
+            hint: hint.map(str::to_string),
+        });
+    }
+    
+    fn ok(&mut self, subject: impl Into<String>, message: impl Into<String>) {
+        self.push(Severity::Ok, subject, message, None);
+    }
+    
+    /// Most serious severity found (Ok for an empty report)
+    pub fn worst(&self) -> Severity {
+        self.findings.iter().map(|f| f.severity).max().unwrap_or(Severity::Ok)
+    }
+    
+    /// Number of findings with the given severity
+    pub fn count(&self, severity: Severity) -> usize {
+        self.findings.iter().filter(|f| f.severity == severity).count()
+    }
+    
+    /// One-line summary, e.g. "2 errors, 1 warning"
+    pub fn summary(&self) -> String {
+        let (errors, warnings) = (self.count(Severity::Error), self.count(Severity::Warning));
+        if errors == 0 && warnings == 0 {
+            return "All checks passed".to_string();
+        }
+        let plural = |n: usize, word: &str| format!("{} {}{}", n, word, if n == 1 { "" } else { "s" });
+        format!("{}, {}", plural(errors, "error"), plural(warnings, "warning"))
+    }
+}
+
+impl fmt::Display for HealthReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for finding in &self.findings {
+            let icon = match finding.severity {
+                Severity::Ok => "✓",
+                Severity::Warning => "!",
+                Severity::Error => "✗",
+            };
+            writeln!(f, "{} {}: {}", icon, finding.subject, finding.message)?;
+            if let Some(hint) = &finding.hint {
+                writeln!(f, "    → {}", hint)?;
+            }
+        }
+        write!(f, "{}", self.summary())
+    }
+}
+
+/// Run every check for a workspace
+pub fn check_workspace(workspace_root: &Path, config_name: &str, project_name: &str, app_config: &AppConfig) -> HealthReport {
+    let mut report = HealthReport::default();
+    
+    check_patterns(&mut report, "global excludes", &app_config.global_excludes);
+    
+    let config_path = workspace_root.join(config_name);
+    let config = if !config_path.exists() {
+        report.push(
+            Severity::Error,
+            "config",
+            format!("{} not found in {}", config_name, workspace_root.display()),
+            Some("Run sync-manager in the workspace root to create it with the setup wizard"),
+        );
+        None
+    } else {
+        match ProjectConfig::load(&config_path) {
+            Ok(config) => {
+                report.ok("config", format!("{} loaded", config_name));
+                Some(config)
+            }
+            Err(err) => {
+                report.push(Severity::Error, "config", format!("{:#}", err), Some("Fix the YAML syntax"));
+                None
+            }
+        }
+    };
+    
+    if let Some(config) = &config {
+        check_mappings(&mut report, workspace_root, config, project_name);
+        check_groups(&mut report, config);
+        check_secrets(&mut report, config);
+        check_local_configs(&mut report, workspace_root, config, project_name);
+        check_git(&mut report, config);
+        check_nested_repos(&mut report, workspace_root, config, project_name);
+    }
+    check_state(&mut report, workspace_root);
+    
+    report
+}
+
+fn check_mappings(report: &mut HealthReport, workspace_root: &Path, config: &ProjectConfig, project_name: &str) {
+    let mappings = config.get_project_mappings(project_name);
+    if mappings.is_empty() {
+        report.push(
+            Severity::Warning,
+            "mappings",
+            format!("No mappings for project '{}'", project_name),
+            Some("Add them under workspace_settings.<project>.<package>.mappings (project = workspace directory name)"),
+        );
+        return;
+    }
+    
+    let project_root = config.project_root(workspace_root, project_name);
+    for mapping in mappings {
+        let subject = format!("mapping {}", mapping.project);
+        let (shared, project) = config.mapping_paths(&project_root, mapping);
+        
+        for (side, dir) in [("shared", &shared), ("project", &project)] {
+            if !dir.is_dir() {
+                let severity = if side == "shared" { Severity::Error } else { Severity::Warning };
+                let hint = if side == "shared" {
+                    "Check the `shared` path and the package location"
+                } else {
+                    "It is created by the first sync; check the `project` path if that is unexpected"
+                };
+                report.push(severity, subject.clone(), format!("{} path {} does not exist", side, dir.display()), Some(hint));
+            } else if let Err(err) = probe_writable(dir) {
+                report.push(
+                    Severity::Error,
+                    subject.clone(),
+                    format!("{} path {} is not writable: {}", side, dir.display(), err),
+                    Some("Fix the directory permissions or ownership"),
+                );
+            }
+        }
+        
+        check_patterns(report, &subject, &mapping.exclude);
+        check_patterns(report, &subject, &mapping.include);
+        check_patterns(report, &subject, &mapping.pinned);
+        check_patterns(report, &subject, &mapping.reverse);
+        let transform_patterns: Vec<String> = mapping.transforms.iter().map(|t| t.files.clone()).collect();
+        check_patterns(report, &subject, &transform_patterns);
+        
+        if shared.is_dir() && project.is_dir() {
+            report.ok(subject, format!("{} ↔ {}", shared.display(), project.display()));
+        }
+    }
+}
+
+/// Secret patterns that don't compile are left out of scans, so files they were meant to
+/// catch would sync without asking
+fn check_secrets(report: &mut HealthReport, config: &ProjectConfig) {
+    let Some(rules) = &config.global_settings.secrets else {
+        return;
+    };
+    if rules.disabled {
+        report.push(
+            Severity::Warning,
+            "secrets",
+            "the secret check is disabled; files that look like secrets sync without asking",
+            None,
+        );
+    }
+    for problem in invalid_patterns(rules) {
+        report.push(
+            Severity::Warning,
+            "secrets",
+            problem,
+            Some("File patterns are globs (*.pem, id_*); content patterns are regular expressions"),
+        );
+    }
+}
+
+/// A group project without mappings would silently contribute nothing to group scans
+fn check_groups(report: &mut HealthReport, config: &ProjectConfig) {
+    for (group, projects) in &config.project_groups {
+        for project in projects {
+            if config.get_project_mappings(project).is_empty() {
+                report.push(
+                    Severity::Warning,
+                    format!("group {}", group),
+                    format!("project '{}' has no mappings", project),
+                    Some("Add its mappings under workspace_settings or remove it from the group"),
+                );
+            }
+        }
+    }
+}
+
+/// Local configs of the workspace's project and the group projects must parse, otherwise
+/// scans silently fall back to the main config alone
+fn check_local_configs(report: &mut HealthReport, workspace_root: &Path, config: &ProjectConfig, project_name: &str) {
+    let mut projects: Vec<&str> = config.project_groups.values().flatten().map(String::as_str).collect();
+    projects.push(project_name);
+    projects.sort_unstable();
+    projects.dedup();
+    
+    for project in projects {
+        let root = config.project_root(workspace_root, project);
+        let subject = format!("local config of {}", project);
+        match LocalConfig::load(&root) {
+            Ok(Some(local)) => {
+                let patterns = [&local.exclude, &local.include, &local.pinned, &local.reverse];
+                for list in patterns {
+                    check_patterns(report, &subject, list);
+                }
+                report.ok(subject, format!("{} loaded", root.join(LOCAL_CONFIG_NAME).display()));
+            }
+            Ok(None) => {}
+            Err(err) => report.push(Severity::Error, subject, format!("{:#}", err), Some("Fix the YAML syntax")),
+        }
+    }
+}
+
+fn check_patterns(report: &mut HealthReport, subject: &str, patterns: &[String]) {
+    for pattern in patterns {
+        if let Some(problem) = pattern_problem(pattern) {
+            report.push(
+                Severity::Warning,
+                subject,
+                format!("pattern '{}' {}", pattern, problem),
+                Some("Use a single * (e.g. *.tmp, drafts/*) or a plain path fragment"),
+            );
+        }
+    }
+}
+
+/// Git is required when a package names a remote or the repository is auto-initialized
+fn check_git(report: &mut HealthReport, config: &ProjectConfig) {
+    let required = config.enabled_packages().any(|p| p.git_remote.is_some())
+        || config.global_settings.auto_init_repo == Some(true);
+    
+    match GitOps::version() {
+        Ok(version) if cfg!(feature = "libgit2") => report.ok("git", format!("{}; status, history and blame use libgit2", version)),
+        Ok(version) => report.ok("git", version),
+        Err(_) if required => report.push(
+            Severity::Error,
+            "git",
+            "git is not available but a package has a git_remote or auto_init_repo is set",
+            Some("Install git and make sure it is on PATH"),
+        ),
+        Err(_) => report.push(
+            Severity::Warning,
+            "git",
+            "git is not available; diffs use the built-in fallback",
+            Some("Install git for better unified diffs"),
+        ),
+    }
+}
+
+/// Submodules and nested repositories in the mapped project directories, with their state;
+/// scanning into one syncs files that belong to another repository
+fn check_nested_repos(report: &mut HealthReport, workspace_root: &Path, config: &ProjectConfig, project_name: &str) {
+    let project_root = config.project_root(workspace_root, project_name);
+    let bounded = config.stops_at_nested_repos(project_name);
+    let mut seen = Vec::new();
+    for mapping in config.get_project_mappings(project_name) {
+        let (_, project) = config.mapping_paths(&project_root, mapping);
+        for repo in GitOps::nested_repos(&project) {
+            let dir = project.join(&repo.path);
+            if seen.contains(&dir) {
+                continue;
+            }
+            let kind = if repo.submodule { "submodule" } else { "nested repo" };
+            let subject = format!("{} {}", kind, dir.strip_prefix(&project_root).unwrap_or(&dir).display());
+            let state = nested_repo_state(&dir);
+            if bounded {
+                report.ok(subject, format!("{}; not scanned", state));
+            } else {
+                report.push(
+                    Severity::Warning,
+                    subject,
+                    format!("{}; mapping {} scans and syncs into it", state, mapping.project),
+                    Some("Set stop_at_nested_repos: true under workspace_settings.<project> to leave it out of scans"),
+                );
+            }
+            seen.push(dir);
+        }
+    }
+}
+
+/// Branch and changes of a nested repository, e.g. "branch main, uncommitted changes"
+fn nested_repo_state(dir: &Path) -> String {
+    if !GitOps::can_query() {
+        return "state unknown without git".to_string();
+    }
+    match GitOps::status(dir) {
+        Ok(status) => {
+            let mut state = match &status.branch {
+                Some(branch) if !branch.is_empty() => format!("branch {}", branch),
+                _ => "detached HEAD".to_string(),
+            };
+            if status.commits_ahead > 0 || status.commits_behind > 0 {
+                state.push_str(&format!(", {} ahead / {} behind", status.commits_ahead, status.commits_behind));
+            }
+            state.push_str(if status.has_uncommitted_changes { ", uncommitted changes" } else { ", clean" });
+            state
+        }
+        Err(err) => format!("state unknown: {:#}", err),
+    }
+}
+
+/// Session state and snapshots must be readable, and the state directory writable
+fn check_state(report: &mut HealthReport, workspace_root: &Path) {
+    let state_dir = workspace_root.join(STATE_DIR_NAME);
+    if !state_dir.exists() {
+        report.ok("state", format!("{} not created yet", STATE_DIR_NAME));
+        return;
+    }
+    
+    let mut healthy = true;
+    if let Err(err) = probe_writable(&state_dir) {
+        healthy = false;
+        report.push(
+            Severity::Error,
+            "state",
+            format!("{} is not writable: {}", state_dir.display(), err),
+            Some("Session state and snapshots can't be saved; fix the directory permissions"),
+        );
+    }
+    
+    let session = SessionState::path_for(workspace_root);
+    if session.exists() {
+        if let Err(err) = SessionState::load_from(&session) {
+            healthy = false;
+            report.push(
+                Severity::Warning,
+                "state",
+                format!("{:#}", err),
+                Some("Delete the session file; it is recreated on quit"),
+            );
+        }
+    }
+    
+    let diff_cache = DiffCache::path_for(workspace_root);
+    if diff_cache.exists() {
+        if let Err(err) = DiffCache::load_from(&diff_cache) {
+            healthy = false;
+            report.push(
+                Severity::Warning,
+                "state",
+                format!("{:#}", err),
+                Some("Delete the diff cache; startup scans in place until it is recreated on quit"),
+            );
+        }
+    }
+    
+    for (path, err) in SnapshotStore::new(workspace_root).unreadable() {
+        healthy = false;
+        report.push(
+            Severity::Warning,
+            "snapshots",
+            format!("{}: {:#}", path.display(), err),
+            Some("The snapshot is skipped; delete or repair the file"),
+        );
+    }
+    
+    if healthy {
+        report.ok("state", format!("{} readable", STATE_DIR_NAME));
+    }
+}
+
+/// Check that files can be created in `dir` by creating and removing a probe file
+fn probe_writable(dir: &Path) -> std::io::Result<()> {
+    let probe = dir.join(format!(".sync-manager-probe-{}", std::process::id()));
+    OpenOptions::new().write(true).create_new(true).open(&probe)?;
+    fs::remove_file(&probe)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_missing_config_is_an_error() {
+        let root = std::env::temp_dir().join(format!("sync-manager-doctor-{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        let report = check_workspace(&root, "sync-manager.yaml", "demo", &AppConfig::default());
+        let _ = fs::remove_dir_all(&root);
+        
+        assert_eq!(report.worst(), Severity::Error);
+        assert!(report.findings.iter().any(|f| f.subject == "config" && f.severity == Severity::Error));
+        assert!(report.to_string().ends_with(&report.summary()));
+    }
+    
+    #[test]
+    fn test_summary_counts() {
+        let mut report = HealthReport::default();
+        assert_eq!(report.summary(), "All checks passed");
+        report.push(Severity::Warning, "x", "w", None);
+        report.push(Severity::Error, "y", "e", None);
+        report.push(Severity::Warning, "z", "w", None);
+        assert_eq!(report.summary(), "1 error, 2 warnings");
+    }
+}
@@ -0,0 +1,178 @@
+// Archive Export
+// Packages the files a sync would write into a .zip or .tar.gz, keyed by their relative
+// paths, so a change set can be handed to someone without access to the repositories
+
+use anyhow::{bail, Context, Result};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+use super::filesystem::RealFileSystem;
+use super::transform::read_transformed;
+use super::{DiffEntry, FileStatus};
+
+/// Archive container, chosen from the output file name
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    /// .zip (deflate)
+    Zip,
+    /// .tar.gz / .tgz
+    TarGz,
+}
+
+impl ArchiveFormat {
+    /// Format for an output path, or None if the extension isn't a supported archive
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_string_lossy().to_lowercase();
+        if name.ends_with(".zip") {
+            Some(ArchiveFormat::Zip)
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(ArchiveFormat::TarGz)
+        } else {
+            None
+        }
+    }
+}
+
+/// What went into an archive
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExportSummary {
+    /// Files written to the archive
+    pub files: usize,
+    /// Entries left out: deletions, directories, unchanged files and files pinned against this direction
+    pub skipped: usize,
+}
+
+/// Content a sync of `diff` would write, or None if the entry wouldn't write a file
+/// Shared templates are exported with their tokens substituted
+fn export_content(diff: &DiffEntry) -> Option<Result<Vec<u8>>> {
+    if diff.is_blocked() || diff.is_dir || matches!(diff.status, FileStatus::Deleted | FileStatus::Unchanged) {
+        return None;
+    }
+
+    let source = &diff.source_path;
+    Some(match diff.source_transform().and_then(|transform| read_transformed(&RealFileSystem, source, Some(transform))) {
+        Some(content) => Ok(content.into_bytes()),
+        None => fs::read(source).with_context(|| format!("Failed to read {}", source.display())),
+    })
+}
+
+/// Archive path of an entry ('/'-separated, relative)
+fn archive_name(diff: &DiffEntry) -> String {
+    diff.path.to_string_lossy().replace('\\', "/")
+}
+
+/// Write the files that syncing `diffs` would write to an archive at `output`
+/// The format follows the file extension (.zip, .tar.gz or .tgz)
+pub fn export_archive(diffs: &[DiffEntry], output: &Path) -> Result<ExportSummary> {
+    let Some(format) = ArchiveFormat::from_path(output) else {
+        bail!("Unsupported archive type: {} (use .zip or .tar.gz)", output.display());
+    };
+
+    let mut summary = ExportSummary::default();
+    let mut files = Vec::new();
+    for diff in diffs {
+        match export_content(diff) {
+            Some(content) => files.push((diff, content?)),
+            None => summary.skipped += 1,
+        }
+    }
+    if files.is_empty() {
+        bail!("Nothing to export");
+    }
+    summary.files = files.len();
+
+    if let Some(parent) = output.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+    }
+    let file = File::create(output).with_context(|| format!("Failed to create {}", output.display()))?;
+
+    match format {
+        ArchiveFormat::Zip => write_zip(file, &files),
+        ArchiveFormat::TarGz => write_tar_gz(file, &files),
+    }
+    .with_context(|| format!("Failed to write {}", output.display()))?;
+
+    Ok(summary)
+}
+
+fn write_zip(file: File, files: &[(&DiffEntry, Vec<u8>)]) -> Result<()> {
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    for (diff, content) in files {
+        zip.start_file(archive_name(diff), options)?;
+        zip.write_all(content)?;
+    }
+    zip.finish()?;
+    Ok(())
+}
+
+fn write_tar_gz(file: File, files: &[(&DiffEntry, Vec<u8>)]) -> Result<()> {
+    let mut tar = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+    for (diff, content) in files {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o644);
+        header.set_mtime(
+            diff.modified
+                .and_then(|m| m.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+        );
+        header.set_cksum();
+        tar.append_data(&mut header, archive_name(diff), content.as_slice())?;
+    }
+    tar.into_inner()?.finish()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    fn entry(dir: &Path, path: &str, status: FileStatus) -> DiffEntry {
+        DiffEntry {
+            source_path: dir.join("src").join(path),
+            destination_path: dir.join("dest").join(path),
+            status,
+            ..DiffEntry::for_test(path, "")
+        }
+    }
+
+    #[test]
+    fn test_format_from_extension() {
+        assert_eq!(ArchiveFormat::from_path(Path::new("out/changes.ZIP")), Some(ArchiveFormat::Zip));
+        assert_eq!(ArchiveFormat::from_path(Path::new("changes.tar.gz")), Some(ArchiveFormat::TarGz));
+        assert_eq!(ArchiveFormat::from_path(Path::new("changes.tgz")), Some(ArchiveFormat::TarGz));
+        assert_eq!(ArchiveFormat::from_path(Path::new("changes.tar")), None);
+    }
+
+    #[test]
+    fn test_export_zip_keeps_relative_paths() {
+        let dir = std::env::temp_dir().join(format!("sync-manager-export-{}", std::process::id()));
+        fs::create_dir_all(dir.join("src/rules")).unwrap();
+        fs::write(dir.join("src/rules/a.md"), "alpha").unwrap();
+
+        let diffs = [
+            entry(&dir, "rules/a.md", FileStatus::Modified),
+            entry(&dir, "rules/gone.md", FileStatus::Deleted),
+        ];
+        let output = dir.join("changes.zip");
+        let summary = export_archive(&diffs, &output).unwrap();
+
+        let mut archive = zip::ZipArchive::new(File::open(&output).unwrap()).unwrap();
+        let mut content = String::new();
+        archive.by_name("rules/a.md").unwrap().read_to_string(&mut content).unwrap();
+        let count = archive.len();
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(summary, ExportSummary { files: 1, skipped: 1 });
+        assert_eq!(count, 1);
+        assert_eq!(content, "alpha");
+    }
+}
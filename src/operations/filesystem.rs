@@ -0,0 +1,490 @@
+// File System
+// The file operations the diff and sync engines use, on disk or in memory for tests
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, Cursor, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, SystemTime};
+
+/// Kind, size and modification time of a path (links are followed)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileInfo {
+    pub is_dir: bool,
+    /// Size in bytes (0 for directories)
+    pub len: u64,
+    pub modified: Option<SystemTime>,
+}
+
+/// A directory entry; `is_dir` is false for links, which aren't followed into
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WalkEntry {
+    pub path: PathBuf,
+    pub is_dir: bool,
+    /// Directories below the walk's root (the root itself is 0)
+    pub depth: usize,
+}
+
+/// File operations of the engines
+///
+/// Streams, links, clones and permissions have defaults for file systems without them:
+/// streams go through `read` / `write`, links and clones are unsupported (so the sync falls
+/// back to copying) and permissions are left alone.
+pub trait FileSystem: fmt::Debug + Send + Sync {
+    fn metadata(&self, path: &Path) -> io::Result<FileInfo>;
+    
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>>;
+    
+    /// Create or truncate a file; its directory must exist
+    fn write(&self, path: &Path, contents: &[u8]) -> io::Result<()>;
+    
+    /// Copy a file's content (and permissions), returning the bytes copied
+    fn copy(&self, from: &Path, to: &Path) -> io::Result<u64>;
+    
+    fn create_dir_all(&self, path: &Path) -> io::Result<()>;
+    
+    fn remove_file(&self, path: &Path) -> io::Result<()>;
+    
+    fn remove_dir_all(&self, path: &Path) -> io::Result<()>;
+    
+    /// Entries directly in a directory, sorted by path (entries with depth 0)
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<WalkEntry>>;
+    
+    fn exists(&self, path: &Path) -> bool {
+        self.metadata(path).is_ok()
+    }
+    
+    fn is_dir(&self, path: &Path) -> bool {
+        self.metadata(path).is_ok_and(|info| info.is_dir)
+    }
+    
+    fn is_file(&self, path: &Path) -> bool {
+        self.metadata(path).is_ok_and(|info| !info.is_dir)
+    }
+    
+    /// `read`, failing with `InvalidData` if the file isn't UTF-8
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        String::from_utf8(self.read(path)?).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+    
+    fn open(&self, path: &Path) -> io::Result<Box<dyn Read + Send>> {
+        Ok(Box::new(Cursor::new(self.read(path)?)))
+    }
+    
+    /// Create or truncate a file for writing in pieces
+    fn create(&self, path: &Path) -> io::Result<Box<dyn Write + Send>>;
+    
+    fn hard_link(&self, _from: &Path, _to: &Path) -> io::Result<()> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+    
+    /// Copy-on-write clone of a file
+    fn reflink(&self, _from: &Path, _to: &Path) -> io::Result<()> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+    
+    fn copy_permissions(&self, _from: &Path, _to: &Path) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Depth-first walk over a `FileSystem`, like `walkdir::WalkDir`: unreadable directories
+/// are passed over, and `skip_current_dir` keeps the walk out of the directory last returned
+pub struct Walk<'a> {
+    fs: &'a dyn FileSystem,
+    /// Entries still to return, the next one last
+    pending: Vec<WalkEntry>,
+    /// Directory returned last, entered on the next call unless skipped
+    current: Option<WalkEntry>,
+}
+
+impl<'a> Walk<'a> {
+    /// Walk `root` and everything below it, parents before their contents
+    pub fn new(fs: &'a dyn FileSystem, root: &Path) -> Self {
+        let pending = match fs.metadata(root) {
+            Ok(info) => vec![WalkEntry { path: root.to_path_buf(), is_dir: info.is_dir, depth: 0 }],
+            Err(_) => Vec::new(),
+        };
+        Self { fs, pending, current: None }
+    }
+    
+    pub fn skip_current_dir(&mut self) {
+        self.current = None;
+    }
+}
+
+impl Iterator for Walk<'_> {
+    type Item = WalkEntry;
+    
+    fn next(&mut self) -> Option<WalkEntry> {
+        if let Some(dir) = self.current.take() {
+            if let Ok(children) = self.fs.read_dir(&dir.path) {
+                self.pending.extend(children.into_iter().rev().map(|child| WalkEntry {
+                    depth: dir.depth + 1,
+                    ..child
+                }));
+            }
+        }
+        let entry = self.pending.pop()?;
+        if entry.is_dir {
+            self.current = Some(entry.clone());
+        }
+        Some(entry)
+    }
+}
+
+/// The operating system's file system
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RealFileSystem;
+
+impl FileSystem for RealFileSystem {
+    fn metadata(&self, path: &Path) -> io::Result<FileInfo> {
+        let metadata = fs::metadata(path)?;
+        Ok(FileInfo {
+            is_dir: metadata.is_dir(),
+            len: if metadata.is_dir() { 0 } else { metadata.len() },
+            modified: metadata.modified().ok(),
+        })
+    }
+    
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        fs::read(path)
+    }
+    
+    fn write(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        fs::write(path, contents)
+    }
+    
+    fn copy(&self, from: &Path, to: &Path) -> io::Result<u64> {
+        fs::copy(from, to)
+    }
+    
+    fn create_dir_all(&self, path: &Path) -> io::Result<()> {
+        fs::create_dir_all(path)
+    }
+    
+    fn remove_file(&self, path: &Path) -> io::Result<()> {
+        fs::remove_file(path)
+    }
+    
+    fn remove_dir_all(&self, path: &Path) -> io::Result<()> {
+        fs::remove_dir_all(path)
+    }
+    
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<WalkEntry>> {
+        let mut entries = Vec::new();
+        for entry in fs::read_dir(path)? {
+            let entry = entry?;
+            entries.push(WalkEntry {
+                path: entry.path(),
+                is_dir: entry.file_type()?.is_dir(),
+                depth: 0,
+            });
+        }
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(entries)
+    }
+    
+    fn exists(&self, path: &Path) -> bool {
+        path.exists()
+    }
+    
+    fn open(&self, path: &Path) -> io::Result<Box<dyn Read + Send>> {
+        Ok(Box::new(File::open(path)?))
+    }
+    
+    fn create(&self, path: &Path) -> io::Result<Box<dyn Write + Send>> {
+        Ok(Box::new(File::create(path)?))
+    }
+    
+    fn hard_link(&self, from: &Path, to: &Path) -> io::Result<()> {
+        fs::hard_link(from, to)
+    }
+    
+    fn reflink(&self, from: &Path, to: &Path) -> io::Result<()> {
+        reflink_copy::reflink(from, to)
+    }
+    
+    fn copy_permissions(&self, from: &Path, to: &Path) -> io::Result<()> {
+        fs::set_permissions(to, fs::metadata(from)?.permissions())
+    }
+}
+
+/// In-memory file system for tests: deterministic timestamps (each change ticks a clock by
+/// one second) and injected failures for paths that should be locked or read-only
+#[derive(Debug, Clone, Default)]
+pub struct MemoryFileSystem {
+    state: Arc<Mutex<MemoryState>>,
+}
+
+#[derive(Debug, Default)]
+struct MemoryState {
+    nodes: BTreeMap<PathBuf, Node>,
+    /// Error kind and remaining count of failures of writes and removals per path
+    faults: BTreeMap<PathBuf, (io::ErrorKind, u32)>,
+    /// Seconds since the epoch given to the next change
+    clock: u64,
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Dir { modified: SystemTime },
+    File { data: Vec<u8>, modified: SystemTime },
+}
+
+impl MemoryState {
+    fn tick(&mut self) -> SystemTime {
+        self.clock += 1;
+        SystemTime::UNIX_EPOCH + Duration::from_secs(self.clock)
+    }
+    
+    /// Fail if a failure is injected for `path`, using up one of its count
+    fn check_fault(&mut self, path: &Path) -> io::Result<()> {
+        let Some((kind, remaining)) = self.faults.get_mut(path) else {
+            return Ok(());
+        };
+        let kind = *kind;
+        *remaining -= 1;
+        if *remaining == 0 {
+            self.faults.remove(path);
+        }
+        Err(io::Error::new(kind, format!("injected failure: {}", path.display())))
+    }
+    
+    fn file(&self, path: &Path) -> io::Result<&Vec<u8>> {
+        match self.nodes.get(path) {
+            Some(Node::File { data, .. }) => Ok(data),
+            Some(Node::Dir { .. }) => Err(io::Error::new(io::ErrorKind::IsADirectory, path.display().to_string())),
+            None => Err(not_found(path)),
+        }
+    }
+    
+    /// Store a file, checking faults and that its directory exists
+    fn put_file(&mut self, path: &Path, data: Vec<u8>) -> io::Result<()> {
+        self.check_fault(path)?;
+        let parent = path.parent().filter(|parent| !parent.as_os_str().is_empty());
+        if parent.is_some_and(|parent| !matches!(self.nodes.get(parent), Some(Node::Dir { .. }))) {
+            return Err(not_found(parent.unwrap_or(path)));
+        }
+        if let Some(Node::Dir { .. }) = self.nodes.get(path) {
+            return Err(io::Error::new(io::ErrorKind::IsADirectory, path.display().to_string()));
+        }
+        let modified = self.tick();
+        self.nodes.insert(path.to_path_buf(), Node::File { data, modified });
+        Ok(())
+    }
+}
+
+fn not_found(path: &Path) -> io::Error {
+    io::Error::new(io::ErrorKind::NotFound, path.display().to_string())
+}
+
+impl MemoryFileSystem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    fn state(&self) -> MutexGuard<'_, MemoryState> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+    
+    /// Add a file, creating its directories
+    pub fn add_file(&self, path: impl AsRef<Path>, contents: impl AsRef<[u8]>) {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            self.add_dir(parent);
+        }
+        let mut state = self.state();
+        let modified = state.tick();
+        state.nodes.insert(path.to_path_buf(), Node::File { data: contents.as_ref().to_vec(), modified });
+    }
+    
+    /// Add a directory and its parents
+    pub fn add_dir(&self, path: impl AsRef<Path>) {
+        let mut state = self.state();
+        for dir in path.as_ref().ancestors().filter(|dir| !dir.as_os_str().is_empty()) {
+            if !state.nodes.contains_key(dir) {
+                let modified = state.tick();
+                state.nodes.insert(dir.to_path_buf(), Node::Dir { modified });
+            }
+        }
+    }
+    
+    /// Set the modification time of a file or directory
+    pub fn set_modified(&self, path: impl AsRef<Path>, time: SystemTime) {
+        match self.state().nodes.get_mut(path.as_ref()) {
+            Some(Node::File { modified, .. } | Node::Dir { modified }) => *modified = time,
+            None => panic!("no such path: {}", path.as_ref().display()),
+        }
+    }
+    
+    /// Make the next `times` writes, creations or removals of `path` fail with `kind`
+    /// (`u32::MAX` for every one); reads still succeed
+    pub fn fail(&self, path: impl AsRef<Path>, kind: io::ErrorKind, times: u32) {
+        self.state().faults.insert(path.as_ref().to_path_buf(), (kind, times));
+    }
+    
+    /// Content of a file, if it exists
+    pub fn contents(&self, path: impl AsRef<Path>) -> Option<Vec<u8>> {
+        self.state().file(path.as_ref()).ok().cloned()
+    }
+}
+
+impl FileSystem for MemoryFileSystem {
+    fn metadata(&self, path: &Path) -> io::Result<FileInfo> {
+        match self.state().nodes.get(path) {
+            Some(Node::Dir { modified }) => Ok(FileInfo { is_dir: true, len: 0, modified: Some(*modified) }),
+            Some(Node::File { data, modified }) => Ok(FileInfo {
+                is_dir: false,
+                len: data.len() as u64,
+                modified: Some(*modified),
+            }),
+            None => Err(not_found(path)),
+        }
+    }
+    
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        self.state().file(path).cloned()
+    }
+    
+    fn write(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        self.state().put_file(path, contents.to_vec())
+    }
+    
+    fn copy(&self, from: &Path, to: &Path) -> io::Result<u64> {
+        let mut state = self.state();
+        let data = state.file(from)?.clone();
+        let len = data.len() as u64;
+        state.put_file(to, data)?;
+        Ok(len)
+    }
+    
+    fn create_dir_all(&self, path: &Path) -> io::Result<()> {
+        let mut state = self.state();
+        for dir in path.ancestors().filter(|dir| !dir.as_os_str().is_empty()) {
+            match state.nodes.get(dir) {
+                Some(Node::Dir { .. }) => break,
+                Some(Node::File { .. }) => {
+                    return Err(io::Error::new(io::ErrorKind::AlreadyExists, dir.display().to_string()))
+                }
+                None => state.check_fault(dir)?,
+            }
+        }
+        for dir in path.ancestors().filter(|dir| !dir.as_os_str().is_empty()) {
+            if !state.nodes.contains_key(dir) {
+                let modified = state.tick();
+                state.nodes.insert(dir.to_path_buf(), Node::Dir { modified });
+            }
+        }
+        Ok(())
+    }
+    
+    fn remove_file(&self, path: &Path) -> io::Result<()> {
+        let mut state = self.state();
+        state.file(path)?;
+        state.check_fault(path)?;
+        state.nodes.remove(path);
+        Ok(())
+    }
+    
+    fn remove_dir_all(&self, path: &Path) -> io::Result<()> {
+        let mut state = self.state();
+        match state.nodes.get(path) {
+            Some(Node::Dir { .. }) => {}
+            Some(Node::File { .. }) => return Err(io::Error::new(io::ErrorKind::NotADirectory, path.display().to_string())),
+            None => return Err(not_found(path)),
+        }
+        state.check_fault(path)?;
+        state.nodes.retain(|node, _| !node.starts_with(path));
+        Ok(())
+    }
+    
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<WalkEntry>> {
+        let state = self.state();
+        match state.nodes.get(path) {
+            Some(Node::Dir { .. }) => {}
+            Some(Node::File { .. }) => return Err(io::Error::new(io::ErrorKind::NotADirectory, path.display().to_string())),
+            None => return Err(not_found(path)),
+        }
+        Ok(state
+            .nodes
+            .iter()
+            .filter(|(node, _)| node.parent() == Some(path))
+            .map(|(node, kind)| WalkEntry {
+                path: node.clone(),
+                is_dir: matches!(kind, Node::Dir { .. }),
+                depth: 0,
+            })
+            .collect())
+    }
+    
+    fn create(&self, path: &Path) -> io::Result<Box<dyn Write + Send>> {
+        self.state().put_file(path, Vec::new())?;
+        Ok(Box::new(MemoryWriter {
+            state: Arc::clone(&self.state),
+            path: path.to_path_buf(),
+        }))
+    }
+}
+
+/// Appends to a file of a `MemoryFileSystem` as it is written
+struct MemoryWriter {
+    state: Arc<Mutex<MemoryState>>,
+    path: PathBuf,
+}
+
+impl Write for MemoryWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut state = self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        match state.nodes.get_mut(&self.path) {
+            Some(Node::File { data, .. }) => {
+                data.extend_from_slice(buf);
+                Ok(buf.len())
+            }
+            _ => Err(not_found(&self.path)),
+        }
+    }
+    
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_walk_and_faults() {
+        let fs = MemoryFileSystem::new();
+        fs.add_file("/root/b/two.md", "2");
+        fs.add_file("/root/a/one.md", "1");
+        fs.add_dir("/root/a/empty");
+
+        let mut walk = Walk::new(&fs, Path::new("/root"));
+        let mut seen = Vec::new();
+        while let Some(entry) = walk.next() {
+            if entry.path.ends_with("a") {
+                walk.skip_current_dir();
+            }
+            seen.push((entry.path.display().to_string(), entry.depth));
+        }
+        assert_eq!(
+            seen,
+            [("/root".to_string(), 0), ("/root/a".to_string(), 1), ("/root/b".to_string(), 1), ("/root/b/two.md".to_string(), 2)]
+        );
+
+        // Writes need the directory; injected failures run out
+        assert_eq!(fs.write(Path::new("/missing/x.md"), b"x").unwrap_err().kind(), io::ErrorKind::NotFound);
+        fs.fail("/root/a/one.md", io::ErrorKind::PermissionDenied, 1);
+        assert_eq!(fs.write(Path::new("/root/a/one.md"), b"new").unwrap_err().kind(), io::ErrorKind::PermissionDenied);
+        assert_eq!(fs.read_to_string(Path::new("/root/a/one.md")).unwrap(), "1");
+        fs.write(Path::new("/root/a/one.md"), b"new").unwrap();
+        assert_eq!(fs.contents("/root/a/one.md").unwrap(), b"new");
+
+        let before = fs.metadata(Path::new("/root/b/two.md")).unwrap().modified;
+        assert!(fs.metadata(Path::new("/root/a/one.md")).unwrap().modified > before);
+    }
+}
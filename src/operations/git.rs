@@ -2,8 +2,13 @@
 // Git integration for repository status and operations
 
 use anyhow::{bail, Result};
-use std::path::Path;
-use std::process::Command;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime};
+use walkdir::WalkDir;
+
+use crate::error::SyncManagerError;
 
 /// Git repository status information
 #[derive(Debug, Clone)]
@@ -24,15 +29,146 @@ pub struct GitStatus {
     pub has_uncommitted_changes: bool,
 }
 
+/// A repository inside another project: a submodule or a separate clone
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NestedRepo {
+    /// Directory of the repository relative to the directory searched
+    pub path: PathBuf,
+    /// Whether it is a submodule (its `.git` is a file pointing into the parent's)
+    pub submodule: bool,
+}
+
+/// Last commit that touched a line, from `git blame`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlameLine {
+    /// Abbreviated commit hash ("0000000" for a line changed since the last commit)
+    pub commit: String,
+    pub author: String,
+    /// Author date of the commit
+    pub time: SystemTime,
+    /// First line of the commit message
+    pub summary: String,
+}
+
+impl BlameLine {
+    /// Whether the line is an edit in the working tree that no commit has recorded yet
+    pub fn is_uncommitted(&self) -> bool {
+        self.commit.bytes().all(|b| b == b'0')
+    }
+}
+
+/// A commit that changed a file, from `git log --follow`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileCommit {
+    /// Abbreviated commit hash
+    pub hash: String,
+    /// Author date (YYYY-MM-DD)
+    pub date: String,
+    pub author: String,
+    /// First line of the commit message
+    pub summary: String,
+    /// Path of the file in the commit relative to the repository root (differs before a rename)
+    pub path: String,
+}
+
+/// Read-only queries of a repository: run by the git executable, or in process by libgit2
+/// when built with the `libgit2` feature (see `GitOps::backend`)
+/// Commands that change a repository or talk to a remote always run git.
+pub trait GitBackend: Send + Sync {
+    /// Name for reports ("git" or "libgit2")
+    fn name(&self) -> &'static str;
+    
+    /// URL of the `origin` remote, if there is one
+    fn remote_url(&self, repo_path: &Path) -> Result<Option<String>>;
+    
+    /// Checked-out branch (empty on a detached HEAD)
+    fn current_branch(&self, repo_path: &Path) -> Result<String>;
+    
+    /// Commits ahead of and behind `origin/HEAD` ((0, 0) if it can't be resolved)
+    fn commit_status(&self, repo_path: &Path) -> Result<(u32, u32)>;
+    
+    /// Whether the working tree has changes or untracked files
+    fn has_uncommitted_changes(&self, repo_path: &Path) -> Result<bool>;
+    
+    /// Changed and untracked files of the working tree, relative to its root
+    fn dirty_files(&self, repo_path: &Path) -> Result<Vec<String>>;
+    
+    /// Commits that changed a file (newest first), following it across renames
+    fn file_history(&self, file_path: &Path, limit: usize) -> Result<Vec<FileCommit>>;
+    
+    /// Content of a file as it was in `commit` (one of its `file_history`)
+    fn show_file(&self, file_path: &Path, commit: &FileCommit) -> Result<String>;
+    
+    /// Last commit of every line of a file as it is in the working tree
+    fn blame(&self, file_path: &Path) -> Result<Vec<BlameLine>>;
+}
+
 /// Git operations handler
 pub struct GitOps;
 
+/// Whether git could be run, checked on first use
+static AVAILABLE: OnceLock<bool> = OnceLock::new();
+
 impl GitOps {
+    /// Version string of the git executable (e.g. "git version 2.43.0"); fails if git isn't on PATH
+    pub fn version() -> Result<String> {
+        let output = run(Command::new("git").arg("--version"))?;
+        if !output.status.success() {
+            return Err(failure("git --version", &output));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+    
+    /// Whether git is on PATH; checked once per process, so callers on hot paths (diff loading
+    /// on every selection) don't spawn a failing process each time
+    pub fn is_available() -> bool {
+        *AVAILABLE.get_or_init(|| Self::version().is_ok())
+    }
+    
+    /// Backend of the read-only queries: libgit2 when built with the `libgit2` feature, else
+    /// the git executable
+    pub fn backend() -> &'static dyn GitBackend {
+        #[cfg(feature = "libgit2")]
+        return &super::git_libgit2::Libgit2;
+        #[cfg(not(feature = "libgit2"))]
+        return &GitCli;
+    }
+    
+    /// Whether history and blame can be queried: always with libgit2, else if git is on PATH
+    pub fn can_query() -> bool {
+        cfg!(feature = "libgit2") || Self::is_available()
+    }
+    
     /// Check if a path is a git repository
     pub fn is_repo(path: &Path) -> bool {
         path.join(".git").exists()
     }
     
+    /// Submodules and nested repositories below `root` (not `root` itself), found by their
+    /// `.git`; repositories inside them aren't searched, nor are `node_modules` directories
+    pub fn nested_repos(root: &Path) -> Vec<NestedRepo> {
+        let mut repos = Vec::new();
+        let mut walker = WalkDir::new(root)
+            .min_depth(1)
+            .into_iter()
+            .filter_entry(|entry| entry.file_name() != ".git" && entry.file_name() != "node_modules");
+        while let Some(entry) = walker.next() {
+            let Ok(entry) = entry else { continue };
+            if !entry.file_type().is_dir() {
+                continue;
+            }
+            let git = entry.path().join(".git");
+            if git.exists() {
+                repos.push(NestedRepo {
+                    path: entry.path().strip_prefix(root).unwrap_or(entry.path()).to_path_buf(),
+                    submodule: git.is_file(),
+                });
+                walker.skip_current_dir();
+            }
+        }
+        repos
+    }
+    
     /// Get full status of a git repository
     pub fn status(repo_path: &Path) -> Result<GitStatus> {
         let is_repo = Self::is_repo(repo_path);
@@ -49,14 +185,16 @@ impl GitOps {
             });
         }
         
-        let (has_remote, remote_url) = Self::check_remote(repo_path)?;
-        let branch = Self::current_branch(repo_path).ok();
+        let backend = Self::backend();
+        let remote_url = backend.remote_url(repo_path)?;
+        let has_remote = remote_url.is_some();
+        let branch = backend.current_branch(repo_path).ok();
         let (commits_ahead, commits_behind) = if has_remote {
-            Self::commit_status(repo_path).unwrap_or((0, 0))
+            backend.commit_status(repo_path).unwrap_or((0, 0))
         } else {
             (0, 0)
         };
-        let has_uncommitted_changes = Self::has_uncommitted_changes(repo_path)?;
+        let has_uncommitted_changes = backend.has_uncommitted_changes(repo_path)?;
         
         Ok(GitStatus {
             is_repo,
@@ -69,49 +207,188 @@ impl GitOps {
         })
     }
     
-    /// Check if repository has a remote and get its URL
-    fn check_remote(repo_path: &Path) -> Result<(bool, Option<String>)> {
-        let output = Command::new("git")
+    /// Fetch from remote
+    pub fn fetch(repo_path: &Path) -> Result<()> {
+        let output = run(Command::new("git")
+            .args(["fetch", "--all"])
+            .current_dir(repo_path))?;
+        
+        if !output.status.success() {
+            return Err(failure("git fetch", &output));
+        }
+        
+        Ok(())
+    }
+    
+    /// Pull from remote
+    pub fn pull(repo_path: &Path) -> Result<()> {
+        let output = run(Command::new("git")
+            .args(["pull"])
+            .current_dir(repo_path))?;
+        
+        if !output.status.success() {
+            return Err(failure("git pull", &output));
+        }
+        
+        Ok(())
+    }
+    
+    /// Push to remote
+    pub fn push(repo_path: &Path) -> Result<()> {
+        let output = run(Command::new("git")
+            .args(["push"])
+            .current_dir(repo_path))?;
+        
+        if !output.status.success() {
+            return Err(failure("git push", &output));
+        }
+        
+        Ok(())
+    }
+    
+    /// Stage a file
+    pub fn add(repo_path: &Path, file_path: &Path) -> Result<()> {
+        let output = run(Command::new("git")
+            .args(["add", &file_path.to_string_lossy()])
+            .current_dir(repo_path))?;
+        
+        if !output.status.success() {
+            return Err(failure("git add", &output));
+        }
+        
+        Ok(())
+    }
+    
+    /// Commit the changes of `paths` only; anything else staged stays staged and uncommitted
+    pub fn commit(repo_path: &Path, message: &str, paths: &[&Path]) -> Result<()> {
+        let output = run(Command::new("git")
+            .args(["commit", "-m", message, "--only", "--"])
+            .args(paths)
+            .current_dir(repo_path))?;
+        
+        if !output.status.success() {
+            return Err(failure("git commit", &output));
+        }
+        
+        Ok(())
+    }
+    
+    /// Root of the working tree containing `path`
+    pub fn toplevel(path: &Path) -> Result<PathBuf> {
+        let output = run(Command::new("git")
+            .args(["rev-parse", "--show-toplevel"])
+            .current_dir(path))?;
+        
+        if !output.status.success() {
+            return Err(failure("git rev-parse", &output));
+        }
+        
+        Ok(PathBuf::from(String::from_utf8_lossy(&output.stdout).trim()))
+    }
+    
+    /// Create a branch at HEAD and switch to it
+    pub fn create_branch(repo_path: &Path, name: &str) -> Result<()> {
+        let output = run(Command::new("git")
+            .args(["switch", "-c", name])
+            .current_dir(repo_path))?;
+        
+        if !output.status.success() {
+            return Err(failure("git switch", &output));
+        }
+        
+        Ok(())
+    }
+    
+    /// Switch to an existing branch
+    pub fn switch(repo_path: &Path, name: &str) -> Result<()> {
+        let output = run(Command::new("git")
+            .args(["switch", name])
+            .current_dir(repo_path))?;
+        
+        if !output.status.success() {
+            return Err(failure("git switch", &output));
+        }
+        
+        Ok(())
+    }
+    
+    /// Stash the changes of `files` (untracked ones included), leaving the rest of the working tree
+    pub fn stash(repo_path: &Path, files: &[String], message: &str) -> Result<()> {
+        let output = run(Command::new("git")
+            .args(["stash", "push", "--include-untracked", "-m", message, "--"])
+            .args(files)
+            .current_dir(repo_path))?;
+        
+        if !output.status.success() {
+            return Err(failure("git stash", &output));
+        }
+        
+        Ok(())
+    }
+    
+    /// Commits that changed a file (newest first), following it across renames
+    pub fn file_history(file_path: &Path, limit: usize) -> Result<Vec<FileCommit>> {
+        Self::backend().file_history(file_path, limit)
+    }
+    
+    /// Content of a file as it was in `commit` (one of its `file_history`)
+    pub fn show_file(file_path: &Path, commit: &FileCommit) -> Result<String> {
+        Self::backend().show_file(file_path, commit)
+    }
+    
+    /// Last commit of every line of a file as it is in the working tree (uncommitted edits
+    /// included); fails for files git doesn't track
+    pub fn blame(file_path: &Path) -> Result<Vec<BlameLine>> {
+        Self::backend().blame(file_path)
+    }
+}
+
+/// Backend running the git executable
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GitCli;
+
+impl GitBackend for GitCli {
+    fn name(&self) -> &'static str {
+        "git"
+    }
+    
+    fn remote_url(&self, repo_path: &Path) -> Result<Option<String>> {
+        let output = run(Command::new("git")
             .args(["remote", "get-url", "origin"])
-            .current_dir(repo_path)
-            .output()?;
+            .current_dir(repo_path))?;
         
         if output.status.success() {
             let url = String::from_utf8(output.stdout)?
                 .trim()
                 .to_string();
-            Ok((true, Some(url)))
+            Ok(Some(url))
         } else {
-            Ok((false, None))
+            Ok(None)
         }
     }
     
-    /// Get the current branch name
-    fn current_branch(repo_path: &Path) -> Result<String> {
-        let output = Command::new("git")
+    fn current_branch(&self, repo_path: &Path) -> Result<String> {
+        let output = run(Command::new("git")
             .args(["branch", "--show-current"])
-            .current_dir(repo_path)
-            .output()?;
+            .current_dir(repo_path))?;
         
         if output.status.success() {
             Ok(String::from_utf8(output.stdout)?
                 .trim()
                 .to_string())
         } else {
-            bail!("Failed to get current branch")
+            Err(failure("git branch --show-current", &output))
         }
     }
     
-    /// Get commits ahead/behind remote
-    fn commit_status(repo_path: &Path) -> Result<(u32, u32)> {
-        let output = Command::new("git")
+    fn commit_status(&self, repo_path: &Path) -> Result<(u32, u32)> {
+        let output = run(Command::new("git")
             .args(["rev-list", "--left-right", "--count", "HEAD...origin/HEAD"])
-            .current_dir(repo_path)
-            .output()?;
+            .current_dir(repo_path))?;
         
         if output.status.success() {
             let text = String::from_utf8(output.stdout)?;
-            let parts: Vec<&str> = text.trim().split_whitespace().collect();
+            let parts: Vec<&str> = text.split_whitespace().collect();
             
             if parts.len() >= 2 {
                 let ahead = parts[0].parse().unwrap_or(0);
@@ -125,12 +402,10 @@ impl GitOps {
         }
     }
     
-    /// Check if there are uncommitted changes
-    fn has_uncommitted_changes(repo_path: &Path) -> Result<bool> {
-        let output = Command::new("git")
+    fn has_uncommitted_changes(&self, repo_path: &Path) -> Result<bool> {
+        let output = run(Command::new("git")
             .args(["status", "--porcelain"])
-            .current_dir(repo_path)
-            .output()?;
+            .current_dir(repo_path))?;
         
         if output.status.success() {
             let text = String::from_utf8(output.stdout)?;
@@ -140,73 +415,203 @@ impl GitOps {
         }
     }
     
-    /// Fetch from remote
-    pub fn fetch(repo_path: &Path) -> Result<()> {
-        let output = Command::new("git")
-            .args(["fetch", "--all"])
-            .current_dir(repo_path)
-            .output()?;
+    fn dirty_files(&self, repo_path: &Path) -> Result<Vec<String>> {
+        let output = run(Command::new("git")
+            .args(["status", "--porcelain", "-z", "--untracked-files=all"])
+            .current_dir(repo_path))?;
         
         if !output.status.success() {
-            bail!("Git fetch failed: {}", String::from_utf8_lossy(&output.stderr));
+            return Err(failure("git status", &output));
         }
         
-        Ok(())
+        Ok(parse_status(&String::from_utf8_lossy(&output.stdout)))
     }
     
-    /// Pull from remote
-    pub fn pull(repo_path: &Path) -> Result<()> {
-        let output = Command::new("git")
-            .args(["pull"])
-            .current_dir(repo_path)
-            .output()?;
+    /// Runs from the closest existing directory above the file, so deleted files still resolve
+    fn file_history(&self, file_path: &Path, limit: usize) -> Result<Vec<FileCommit>> {
+        if !GitOps::is_available() {
+            return Err(SyncManagerError::git("git log needs git, which wasn't found on PATH").into());
+        }
+        let Some(dir) = file_path.ancestors().skip(1).find(|dir| dir.is_dir()) else {
+            bail!("No directory found for {}", file_path.display());
+        };
+        let output = run(Command::new("git")
+            .args(["log", "--follow", "--name-only", "--date=short", "--format=%x1e%h%x1f%ad%x1f%an%x1f%s"])
+            .arg(format!("-n{}", limit))
+            .arg("--")
+            .arg(file_path)
+            .current_dir(dir))?;
         
         if !output.status.success() {
-            bail!("Git pull failed: {}", String::from_utf8_lossy(&output.stderr));
+            return Err(failure("git log", &output));
         }
         
-        Ok(())
+        Ok(parse_file_history(&String::from_utf8_lossy(&output.stdout)))
     }
     
-    /// Push to remote
-    pub fn push(repo_path: &Path) -> Result<()> {
-        let output = Command::new("git")
-            .args(["push"])
-            .current_dir(repo_path)
-            .output()?;
+    fn show_file(&self, file_path: &Path, commit: &FileCommit) -> Result<String> {
+        let Some(dir) = file_path.ancestors().skip(1).find(|dir| dir.is_dir()) else {
+            bail!("No directory found for {}", file_path.display());
+        };
+        let output = run(Command::new("git")
+            .arg("show")
+            .arg(format!("{}:{}", commit.hash, commit.path))
+            .current_dir(dir))?;
         
         if !output.status.success() {
-            bail!("Git push failed: {}", String::from_utf8_lossy(&output.stderr));
+            return Err(failure("git show", &output));
         }
         
-        Ok(())
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
     }
     
-    /// Stage a file
-    pub fn add(repo_path: &Path, file_path: &Path) -> Result<()> {
-        let output = Command::new("git")
-            .args(["add", &file_path.to_string_lossy()])
-            .current_dir(repo_path)
-            .output()?;
+    fn blame(&self, file_path: &Path) -> Result<Vec<BlameLine>> {
+        if !GitOps::is_available() {
+            return Err(SyncManagerError::git("git blame needs git, which wasn't found on PATH").into());
+        }
+        let Some(dir) = file_path.parent().filter(|dir| dir.is_dir()) else {
+            bail!("No directory found for {}", file_path.display());
+        };
+        let output = run(Command::new("git")
+            .args(["blame", "--line-porcelain", "--"])
+            .arg(file_path)
+            .current_dir(dir))?;
         
         if !output.status.success() {
-            bail!("Git add failed: {}", String::from_utf8_lossy(&output.stderr));
+            return Err(failure("git blame", &output));
         }
         
-        Ok(())
+        Ok(parse_blame(&String::from_utf8_lossy(&output.stdout)))
     }
-    
-    /// Commit staged changes
-    pub fn commit(repo_path: &Path, message: &str) -> Result<()> {
-        let output = Command::new("git")
-            .args(["commit", "-m", message])
-            .current_dir(repo_path)
-            .output()?;
-        
-        if !output.status.success() {
-            bail!("Git commit failed: {}", String::from_utf8_lossy(&output.stderr));
+}
+
+/// Records of `git log --name-only` in the format of `file_history`: each starts with a
+/// record separator, then the fields separated by unit separators, then the file's path
+fn parse_file_history(log: &str) -> Vec<FileCommit> {
+    log.split('\x1e')
+        .filter_map(|record| {
+            let mut lines = record.lines();
+            let mut fields = lines.next()?.split('\x1f');
+            let (hash, date, author, summary) = (fields.next()?, fields.next()?, fields.next()?, fields.next()?);
+            let path = lines.rfind(|line| !line.trim().is_empty())?;
+            Some(FileCommit {
+                hash: hash.to_string(),
+                date: date.to_string(),
+                author: author.to_string(),
+                summary: summary.to_string(),
+                path: path.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Lines of `git blame --line-porcelain` output: a header line per source line ("<hash>
+/// <original line> <final line>"), then `key value` lines, then the content after a tab
+fn parse_blame(porcelain: &str) -> Vec<BlameLine> {
+    let mut lines = Vec::new();
+    let mut current: Option<BlameLine> = None;
+    for line in porcelain.lines() {
+        if line.starts_with('\t') {
+            lines.extend(current.take());
+            continue;
         }
-        
-        Ok(())
+        let Some(blame) = current.as_mut() else {
+            let hash = line.split(' ').next().unwrap_or_default();
+            current = Some(BlameLine {
+                commit: hash.chars().take(7).collect(),
+                author: String::new(),
+                time: SystemTime::UNIX_EPOCH,
+                summary: String::new(),
+            });
+            continue;
+        };
+        if let Some(author) = line.strip_prefix("author ") {
+            blame.author = author.to_string();
+        } else if let Some(time) = line.strip_prefix("author-time ") {
+            blame.time = SystemTime::UNIX_EPOCH + Duration::from_secs(time.parse().unwrap_or(0));
+        } else if let Some(summary) = line.strip_prefix("summary ") {
+            blame.summary = summary.to_string();
+        }
+    }
+    lines
+}
+
+/// Paths of `git status --porcelain -z` output (the new path of renames and copies)
+fn parse_status(porcelain: &str) -> Vec<String> {
+    let mut files = Vec::new();
+    let mut records = porcelain.split('\0').filter(|record| !record.is_empty());
+    while let Some(record) = records.next() {
+        let Some(path) = record.get(3..) else {
+            continue;
+        };
+        if record.starts_with('R') || record.starts_with('C') {
+            records.next();
+        }
+        files.push(path.to_string());
+    }
+    files
+}
+
+/// Run a git command; failing to start it (git missing from PATH) is a git error
+fn run(command: &mut Command) -> Result<Output> {
+    command
+        .output()
+        .map_err(|err| SyncManagerError::git(format!("Couldn't run git: {}", err)).into())
+}
+
+/// Git error for a command that exited with a failure status
+fn failure(command: &str, output: &Output) -> anyhow::Error {
+    SyncManagerError::git(format!("{} failed: {}", command, String::from_utf8_lossy(&output.stderr).trim())).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_blame() {
+        let porcelain = "\
+3f5a8c21e0b94d7e0c5b1f2a9e8d7c6b5a4f3e2d 1 1 2
+author Ada Lovelace
+author-mail <ada@example.com>
+author-time 1700000000
+author-tz +0000
+summary Add notes
+filename notes.md
+\t# Notes
+3f5a8c21e0b94d7e0c5b1f2a9e8d7c6b5a4f3e2d 2 2
+author Ada Lovelace
+author-time 1700000000
+summary Add notes
+filename notes.md
+\tfirst
+0000000000000000000000000000000000000000 3 3 1
+author Not Committed Yet
+author-time 1710000000
+summary Version of notes.md from notes.md
+filename notes.md
+\tlocal edit
+";
+        let lines = parse_blame(porcelain);
+        assert_eq!(lines.len(), 3);
+        assert_eq!((lines[0].commit.as_str(), lines[0].author.as_str(), lines[0].summary.as_str()), ("3f5a8c2", "Ada Lovelace", "Add notes"));
+        assert_eq!(lines[1].time, SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000));
+        assert!(!lines[1].is_uncommitted());
+        assert!(lines[2].is_uncommitted());
+    }
+    
+    #[test]
+    fn test_parse_file_history() {
+        let log = "\x1ea1b2c3d\x1f2024-05-02\x1fAda\x1fTweak rules\n\ndocs/rules.md\n\x1e9f8e7d6\x1f2024-04-01\x1fBob\x1fAdd rules\n\nrules.md\n";
+        let commits = parse_file_history(log);
+        assert_eq!(commits.len(), 2);
+        assert_eq!((commits[0].hash.as_str(), commits[0].path.as_str()), ("a1b2c3d", "docs/rules.md"));
+        assert_eq!((commits[1].date.as_str(), commits[1].summary.as_str(), commits[1].path.as_str()), ("2024-04-01", "Add rules", "rules.md"));
+    }
+    
+    #[test]
+    fn test_parse_status() {
+        let porcelain = " M rules/a.md\0R  docs/new.md\0docs/old.md\0?? notes/todo.md\0";
+        assert_eq!(parse_status(porcelain), ["rules/a.md", "docs/new.md", "notes/todo.md"]);
     }
 }
@@ -0,0 +1,261 @@
+// Git Queries via libgit2
+// In-process `GitBackend` (built with the `libgit2` feature): no git process per query and
+// no output to parse
+
+use anyhow::{Context, Result};
+use git2::{BlameOptions, Commit, DiffFindOptions, ErrorCode, Oid, Repository, StatusOptions, Time};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use super::git::{BlameLine, FileCommit, GitBackend};
+use crate::error::SyncManagerError;
+use crate::utilities::format_timestamp;
+
+/// Backend answering the queries with libgit2
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Libgit2;
+
+/// libgit2 errors are git errors
+fn git_error(err: git2::Error) -> anyhow::Error {
+    SyncManagerError::git(err.message()).into()
+}
+
+/// Repository containing `path` and the path relative to its working tree
+fn open_for(path: &Path) -> Result<(Repository, PathBuf)> {
+    let dir = path.ancestors().skip(1).find(|dir| dir.is_dir()).context("No directory found")?;
+    let repo = Repository::discover(dir).map_err(git_error)?;
+    let workdir = repo.workdir().context("Repository has no working tree")?;
+    let workdir = fs::canonicalize(workdir).unwrap_or_else(|_| workdir.to_path_buf());
+    let dir = fs::canonicalize(dir).unwrap_or_else(|_| dir.to_path_buf());
+    let relative = dir
+        .strip_prefix(&workdir)
+        .map(|dir| dir.join(path.file_name().unwrap_or_default()))
+        .with_context(|| format!("{} is outside the repository", path.display()))?;
+    Ok((repo, relative))
+}
+
+/// Point in time of a commit signature
+fn system_time(time: Time) -> SystemTime {
+    SystemTime::UNIX_EPOCH + Duration::from_secs(time.seconds().max(0) as u64)
+}
+
+/// Date of a signature in its own time zone, like `git log --date=short`
+fn short_date(time: Time) -> String {
+    let local = time.seconds() + i64::from(time.offset_minutes()) * 60;
+    format_timestamp(SystemTime::UNIX_EPOCH + Duration::from_secs(local.max(0) as u64))[..10].to_string()
+}
+
+/// Blob id of `path` in a commit's tree (None where the commit has no such file)
+fn blob_at(commit: &Commit, path: &Path) -> Option<Oid> {
+    commit.tree().ok()?.get_path(path).ok().map(|entry| entry.id())
+}
+
+/// Path `path` had in `parent` if `commit` renamed it from there
+fn renamed_from(repo: &Repository, parent: &Commit, commit: &Commit, path: &Path) -> Option<PathBuf> {
+    let old_tree = parent.tree().ok()?;
+    let new_tree = commit.tree().ok()?;
+    let mut diff = repo.diff_tree_to_tree(Some(&old_tree), Some(&new_tree), None).ok()?;
+    diff.find_similar(Some(DiffFindOptions::new().renames(true))).ok()?;
+    diff.deltas()
+        .find(|delta| delta.status() == git2::Delta::Renamed && delta.new_file().path() == Some(path))
+        .and_then(|delta| delta.old_file().path().map(Path::to_path_buf))
+}
+
+impl GitBackend for Libgit2 {
+    fn name(&self) -> &'static str {
+        "libgit2"
+    }
+    
+    fn remote_url(&self, repo_path: &Path) -> Result<Option<String>> {
+        let repo = Repository::open(repo_path).map_err(git_error)?;
+        let url = match repo.find_remote("origin") {
+            Ok(remote) => remote.url().map(str::to_string),
+            Err(err) if err.code() == ErrorCode::NotFound => None,
+            Err(err) => return Err(git_error(err)),
+        };
+        Ok(url)
+    }
+    
+    fn current_branch(&self, repo_path: &Path) -> Result<String> {
+        let repo = Repository::open(repo_path).map_err(git_error)?;
+        if repo.head_detached().map_err(git_error)? {
+            return Ok(String::new());
+        }
+        // An unborn branch (no commit yet) still has its name in HEAD
+        let head = repo.find_reference("HEAD").map_err(git_error)?;
+        let target = head.symbolic_target().unwrap_or_default();
+        Ok(target.strip_prefix("refs/heads/").unwrap_or(target).to_string())
+    }
+    
+    fn commit_status(&self, repo_path: &Path) -> Result<(u32, u32)> {
+        let repo = Repository::open(repo_path).map_err(git_error)?;
+        let resolve = |name: &str| repo.revparse_single(name).and_then(|object| object.peel_to_commit()).map(|c| c.id());
+        let (Ok(local), Ok(upstream)) = (resolve("HEAD"), resolve("origin/HEAD")) else {
+            return Ok((0, 0));
+        };
+        let (ahead, behind) = repo.graph_ahead_behind(local, upstream).map_err(git_error)?;
+        Ok((ahead as u32, behind as u32))
+    }
+    
+    fn has_uncommitted_changes(&self, repo_path: &Path) -> Result<bool> {
+        let repo = Repository::open(repo_path).map_err(git_error)?;
+        let mut options = StatusOptions::new();
+        options.include_untracked(true).recurse_untracked_dirs(false);
+        let changed = !repo.statuses(Some(&mut options)).map_err(git_error)?.is_empty();
+        Ok(changed)
+    }
+    
+    fn dirty_files(&self, repo_path: &Path) -> Result<Vec<String>> {
+        let repo = Repository::open(repo_path).map_err(git_error)?;
+        let mut options = StatusOptions::new();
+        options.include_untracked(true).recurse_untracked_dirs(true);
+        let files = repo
+            .statuses(Some(&mut options))
+            .map_err(git_error)?
+            .iter()
+            .filter_map(|entry| entry.path().map(str::to_string))
+            .collect();
+        Ok(files)
+    }
+    
+    /// Walks the first-parent chain from HEAD, comparing the file's blob with the parent's;
+    /// where the file appears, a rename is looked for and followed to the old path
+    fn file_history(&self, file_path: &Path, limit: usize) -> Result<Vec<FileCommit>> {
+        let (repo, mut path) = open_for(file_path)?;
+        let mut commits = Vec::new();
+        let mut commit = repo.head().and_then(|head| head.peel_to_commit()).map_err(git_error)?;
+        while commits.len() < limit {
+            let parent = commit.parent(0).ok();
+            let blob = blob_at(&commit, &path);
+            let parent_blob = parent.as_ref().and_then(|parent| blob_at(parent, &path));
+            if blob.is_some() && blob != parent_blob {
+                commits.push(FileCommit {
+                    hash: commit.as_object().short_id().map_err(git_error)?.as_str().unwrap_or_default().to_string(),
+                    date: short_date(commit.author().when()),
+                    author: commit.author().name().unwrap_or_default().to_string(),
+                    summary: commit.summary().unwrap_or_default().to_string(),
+                    path: path.to_string_lossy().replace('\\', "/"),
+                });
+            }
+            let Some(parent) = parent else {
+                break;
+            };
+            if blob.is_some() && parent_blob.is_none() {
+                match renamed_from(&repo, &parent, &commit, &path) {
+                    Some(old_path) => path = old_path,
+                    None => break,
+                }
+            }
+            commit = parent;
+        }
+        Ok(commits)
+    }
+    
+    fn show_file(&self, file_path: &Path, commit: &FileCommit) -> Result<String> {
+        let (repo, _) = open_for(file_path)?;
+        let blob = repo
+            .revparse_single(&commit.hash)
+            .and_then(|object| object.peel_to_tree())
+            .and_then(|tree| tree.get_path(Path::new(&commit.path)))
+            .and_then(|entry| entry.to_object(&repo))
+            .and_then(|object| object.peel_to_blob())
+            .map_err(git_error)?;
+        Ok(String::from_utf8_lossy(blob.content()).into_owned())
+    }
+    
+    /// Blames the committed file, then the working tree's content against it, so edited lines
+    /// come out with a zero commit id like `git blame` gives them
+    fn blame(&self, file_path: &Path) -> Result<Vec<BlameLine>> {
+        let (repo, path) = open_for(file_path)?;
+        let committed = repo.blame_file(&path, Some(&mut BlameOptions::new())).map_err(git_error)?;
+        let content = fs::read(file_path)?;
+        let blame = committed.blame_buffer(&content).map_err(git_error)?;
+        
+        let mut commits: HashMap<Oid, (String, SystemTime, String)> = HashMap::new();
+        let line_count = String::from_utf8_lossy(&content).lines().count();
+        let mut lines = Vec::with_capacity(line_count);
+        for line in 1..=line_count {
+            let id = blame.get_line(line).map(|hunk| hunk.final_commit_id()).unwrap_or_else(Oid::zero);
+            if id.is_zero() {
+                lines.push(BlameLine {
+                    commit: "0000000".to_string(),
+                    author: "Not Committed Yet".to_string(),
+                    time: SystemTime::now(),
+                    summary: String::new(),
+                });
+                continue;
+            }
+            let (author, time, summary) = match commits.get(&id) {
+                Some(known) => known.clone(),
+                None => {
+                    let commit = repo.find_commit(id).map_err(git_error)?;
+                    let known = (
+                        commit.author().name().unwrap_or_default().to_string(),
+                        system_time(commit.author().when()),
+                        commit.summary().unwrap_or_default().to_string(),
+                    );
+                    commits.insert(id, known.clone());
+                    known
+                }
+            };
+            lines.push(BlameLine {
+                commit: id.to_string()[..7].to_string(),
+                author,
+                time,
+                summary,
+            });
+        }
+        Ok(lines)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::Signature;
+
+    /// Commit the index with `message` on top of HEAD (if any)
+    fn commit_all(repo: &Repository, message: &str) {
+        let mut index = repo.index().unwrap();
+        index.add_all(["*"], git2::IndexAddOption::DEFAULT, None).unwrap();
+        index.update_all(["*"], None).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = Signature::new("Ada", "ada@example.com", &Time::new(1_700_000_000, 0)).unwrap();
+        let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+        let parents: Vec<&Commit> = parent.iter().collect();
+        repo.commit(Some("HEAD"), &signature, &signature, message, &tree, &parents).unwrap();
+    }
+
+    #[test]
+    fn test_history_follows_renames_and_blame_marks_edits() {
+        let root = std::env::temp_dir().join(format!("sync-manager-libgit2-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        let repo = Repository::init(&root).unwrap();
+
+        fs::write(root.join("old.md"), "one\ntwo\nthree\nfour\n").unwrap();
+        commit_all(&repo, "Add notes");
+        fs::rename(root.join("old.md"), root.join("new.md")).unwrap();
+        commit_all(&repo, "Rename notes");
+        fs::write(root.join("new.md"), "one\ntwo\nthree\nfour\nfive\n").unwrap();
+        commit_all(&repo, "Extend notes");
+        fs::write(root.join("new.md"), "one\nTWO\nthree\nfour\nfive\n").unwrap();
+
+        let history = Libgit2.file_history(&root.join("new.md"), 10).unwrap();
+        let summaries: Vec<_> = history.iter().map(|c| (c.summary.as_str(), c.path.as_str())).collect();
+        assert_eq!(summaries, [("Extend notes", "new.md"), ("Rename notes", "new.md"), ("Add notes", "old.md")]);
+        assert_eq!(history[0].date, "2023-11-14");
+        assert_eq!(Libgit2.show_file(&root.join("new.md"), &history[2]).unwrap(), "one\ntwo\nthree\nfour\n");
+
+        let blame = Libgit2.blame(&root.join("new.md")).unwrap();
+        let uncommitted: Vec<_> = blame.iter().map(BlameLine::is_uncommitted).collect();
+        assert_eq!(uncommitted, [false, true, false, false, false]);
+        assert!(Libgit2.has_uncommitted_changes(&root).unwrap());
+        assert_eq!(Libgit2.remote_url(&root).unwrap(), None);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
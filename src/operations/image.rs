@@ -0,0 +1,268 @@
+// Image Preview
+// Metadata and downscaled thumbnails of png / jpg / svg entries, compared side by side
+// instead of a text diff
+
+use regex::Regex;
+use std::fs;
+use std::io::Cursor;
+use std::path::Path;
+use zune_jpeg::zune_core::bytestream::ZCursor;
+use zune_jpeg::zune_core::colorspace::ColorSpace;
+use zune_jpeg::zune_core::options::DecoderOptions;
+use zune_jpeg::JpegDecoder;
+
+use super::snapshot::content_hash;
+use super::DiffEntry;
+
+/// Largest side of a thumbnail kept in memory; previews are downscaled from it
+const THUMBNAIL_SIZE: usize = 160;
+
+/// Format of a previewed image
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Png,
+    Jpeg,
+    /// Compared by metadata; its markup is diffed as text (not rasterized)
+    Svg,
+}
+
+impl ImageFormat {
+    /// Format of a file, by extension
+    pub fn of(path: &Path) -> Option<Self> {
+        match path.extension()?.to_string_lossy().to_lowercase().as_str() {
+            "png" => Some(Self::Png),
+            "jpg" | "jpeg" => Some(Self::Jpeg),
+            "svg" => Some(Self::Svg),
+            _ => None,
+        }
+    }
+    
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Png => "PNG",
+            Self::Jpeg => "JPEG",
+            Self::Svg => "SVG",
+        }
+    }
+}
+
+/// One cell of a half-block preview: its (top, bottom) pixels; no bottom pixel below the last row
+pub type HalfBlock = ([u8; 3], Option<[u8; 3]>);
+
+/// Downscaled RGB pixels (transparency blended over black)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Thumbnail {
+    pub width: usize,
+    pub height: usize,
+    pixels: Vec<[u8; 3]>,
+}
+
+impl Thumbnail {
+    /// Average `data` (`channels` bytes per pixel: gray, gray + alpha, RGB or RGBA) down so
+    /// neither side exceeds `max`
+    fn from_pixels(width: usize, height: usize, data: &[u8], channels: usize, max: usize) -> Option<Self> {
+        if width == 0 || height == 0 || data.len() < width * height * channels {
+            return None;
+        }
+        let scale = (width.max(height) as f64 / max as f64).max(1.0);
+        let (thumb_width, thumb_height) = (
+            ((width as f64 / scale) as usize).max(1),
+            ((height as f64 / scale) as usize).max(1),
+        );
+        
+        let rgb = |index: usize| -> [u32; 3] {
+            let pixel = &data[index * channels..(index + 1) * channels];
+            let (color, alpha) = match channels {
+                1 => ([pixel[0]; 3], 255),
+                2 => ([pixel[0]; 3], pixel[1]),
+                3 => ([pixel[0], pixel[1], pixel[2]], 255),
+                _ => ([pixel[0], pixel[1], pixel[2]], pixel[3]),
+            };
+            color.map(|c| u32::from(c) * u32::from(alpha) / 255)
+        };
+        let mut pixels = Vec::with_capacity(thumb_width * thumb_height);
+        for ty in 0..thumb_height {
+            let (y0, y1) = (ty * height / thumb_height, ((ty + 1) * height / thumb_height).max(ty * height / thumb_height + 1));
+            for tx in 0..thumb_width {
+                let (x0, x1) = (tx * width / thumb_width, ((tx + 1) * width / thumb_width).max(tx * width / thumb_width + 1));
+                let mut sum = [0u32; 3];
+                for y in y0..y1 {
+                    for x in x0..x1 {
+                        let [r, g, b] = rgb(y * width + x);
+                        sum = [sum[0] + r, sum[1] + g, sum[2] + b];
+                    }
+                }
+                let count = ((y1 - y0) * (x1 - x0)) as u32;
+                pixels.push(sum.map(|c| (c / count) as u8));
+            }
+        }
+        Some(Self { width: thumb_width, height: thumb_height, pixels })
+    }
+    
+    pub fn pixel(&self, x: usize, y: usize) -> [u8; 3] {
+        self.pixels[y * self.width + x]
+    }
+    
+    /// Cells of a half-block preview fitting `cols` × `rows` with the aspect ratio kept
+    pub fn cells(&self, cols: usize, rows: usize) -> Vec<Vec<HalfBlock>> {
+        let scale = (cols as f64 / self.width as f64).min((rows * 2) as f64 / self.height as f64);
+        let width = ((self.width as f64 * scale) as usize).clamp(1, cols.max(1));
+        let height = ((self.height as f64 * scale) as usize).clamp(1, (rows * 2).max(1));
+        let sample = |x: usize, y: usize| self.pixel(x * self.width / width, y * self.height / height);
+        
+        (0..height.div_ceil(2))
+            .map(|row| {
+                (0..width)
+                    .map(|x| (sample(x, row * 2), (row * 2 + 1 < height).then(|| sample(x, row * 2 + 1))))
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// One side of an image comparison
+#[derive(Debug, Clone)]
+pub struct ImageFile {
+    pub format: ImageFormat,
+    /// (width, height) in pixels; None if the header can't be read or an SVG declares no size
+    pub dimensions: Option<(u32, u32)>,
+    pub size: u64,
+    /// Content hash, as stored in snapshots
+    pub hash: String,
+    /// None for SVGs and files that can't be decoded
+    pub thumbnail: Option<Thumbnail>,
+}
+
+impl ImageFile {
+    /// Read the image at `path`; None if it is missing or not a previewed format
+    pub fn load(path: &Path) -> Option<Self> {
+        let format = ImageFormat::of(path)?;
+        let bytes = fs::read(path).ok()?;
+        let (dimensions, thumbnail) = match format {
+            ImageFormat::Png => decode_png(&bytes),
+            ImageFormat::Jpeg => decode_jpeg(&bytes),
+            ImageFormat::Svg => (svg_dimensions(&String::from_utf8_lossy(&bytes)), None),
+        };
+        Some(Self { format, dimensions, size: bytes.len() as u64, hash: content_hash(&bytes), thumbnail })
+    }
+}
+
+/// Both sides of an image entry
+#[derive(Debug, Clone)]
+pub struct ImagePair {
+    pub source: Option<ImageFile>,
+    pub dest: Option<ImageFile>,
+}
+
+impl ImagePair {
+    /// Images of an entry; None unless its path has a previewed extension
+    pub fn load(diff: &DiffEntry) -> Option<Self> {
+        ImageFormat::of(&diff.path)?;
+        Some(Self { source: ImageFile::load(&diff.source_path), dest: ImageFile::load(&diff.destination_path) })
+    }
+    
+    /// Whether the entry is an SVG (whose markup is still diffed as text)
+    pub fn is_svg(&self) -> bool {
+        [&self.source, &self.dest].into_iter().flatten().any(|image| image.format == ImageFormat::Svg)
+    }
+}
+
+fn decode_png(bytes: &[u8]) -> (Option<(u32, u32)>, Option<Thumbnail>) {
+    let mut decoder = png::Decoder::new(Cursor::new(bytes));
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let Ok(mut reader) = decoder.read_info() else {
+        return (None, None);
+    };
+    let dimensions = reader.info().size();
+    let mut buffer = vec![0; reader.output_buffer_size().unwrap_or(0)];
+    let thumbnail = reader.next_frame(&mut buffer).ok().and_then(|info| {
+        let channels = info.color_type.samples();
+        Thumbnail::from_pixels(info.width as usize, info.height as usize, &buffer, channels, THUMBNAIL_SIZE)
+    });
+    (Some(dimensions), thumbnail)
+}
+
+fn decode_jpeg(bytes: &[u8]) -> (Option<(u32, u32)>, Option<Thumbnail>) {
+    let options = DecoderOptions::default().jpeg_set_out_colorspace(ColorSpace::RGB);
+    let mut decoder = JpegDecoder::new_with_options(ZCursor::new(bytes), options);
+    if decoder.decode_headers().is_err() {
+        return (None, None);
+    }
+    let Some(info) = decoder.info() else {
+        return (None, None);
+    };
+    let (width, height) = (usize::from(info.width), usize::from(info.height));
+    let thumbnail = decoder
+        .decode()
+        .ok()
+        .and_then(|pixels| Thumbnail::from_pixels(width, height, &pixels, 3, THUMBNAIL_SIZE));
+    (Some((width as u32, height as u32)), thumbnail)
+}
+
+/// Size declared by the root element: its width and height, else its viewBox
+fn svg_dimensions(markup: &str) -> Option<(u32, u32)> {
+    let root = Regex::new(r"<svg\b[^>]*>").ok()?.find(markup)?.as_str();
+    let attribute = |name: &str| -> Option<f64> {
+        let pattern = format!(r#"\b{}\s*=\s*["']\s*([0-9.]+)\s*(px)?\s*["']"#, name);
+        Regex::new(&pattern).ok()?.captures(root)?[1].parse().ok()
+    };
+    if let (Some(width), Some(height)) = (attribute("width"), attribute("height")) {
+        return Some((width as u32, height as u32));
+    }
+    let view_box = Regex::new(r#"\bviewBox\s*=\s*["']([^"']*)["']"#).ok()?.captures(root)?;
+    let values: Vec<f64> = view_box[1]
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter_map(|value| value.parse().ok())
+        .collect();
+    match values[..] {
+        [_, _, width, height] => Some((width as u32, height as u32)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_image_metadata_and_thumbnail() {
+        let dir = std::env::temp_dir().join(format!("sync-manager-image-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        // 4 × 2 RGBA: red left half, half-transparent white right half
+        let mut data = Vec::new();
+        for _ in 0..2 {
+            data.extend_from_slice(&[255, 0, 0, 255, 255, 0, 0, 255, 255, 255, 255, 128, 255, 255, 255, 128]);
+        }
+        let png_path = dir.join("logo.png");
+        let mut encoded = Vec::new();
+        let mut encoder = png::Encoder::new(&mut encoded, 4, 2);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.write_header().unwrap().write_image_data(&data).unwrap();
+        fs::write(&png_path, &encoded).unwrap();
+
+        let image = ImageFile::load(&png_path).unwrap();
+        assert_eq!(image.format, ImageFormat::Png);
+        assert_eq!(image.dimensions, Some((4, 2)));
+        assert_eq!(image.size, encoded.len() as u64);
+        let thumbnail = image.thumbnail.unwrap();
+        assert_eq!(thumbnail.pixel(0, 0), [255, 0, 0]);
+        assert_eq!(thumbnail.pixel(3, 1), [128, 128, 128]);
+
+        // Aspect ratio kept: 4 × 2 pixels fit 2 columns as 2 × 1 pixels, one half-filled cell
+        let cells = thumbnail.cells(2, 5);
+        assert_eq!(cells.len(), 1);
+        assert_eq!(cells[0], vec![([255, 0, 0], None), ([128, 128, 128], None)]);
+
+        let svg_path = dir.join("icon.svg");
+        fs::write(&svg_path, r#"<?xml version="1.0"?><svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 16"></svg>"#).unwrap();
+        let svg = ImageFile::load(&svg_path).unwrap();
+        assert_eq!((svg.format, svg.dimensions, svg.thumbnail), (ImageFormat::Svg, Some((24, 16)), None));
+        assert_eq!(svg_dimensions(r#"<svg width="100px" height='50' viewBox="0 0 1 1">"#), Some((100, 50)));
+
+        assert!(ImageFile::load(&dir.join("missing.png")).is_none());
+        assert!(ImageFile::load(&dir.join("notes.md")).is_none());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,231 @@
+// Lockfile Summary
+// Dependency-level summary of Cargo.lock / package-lock.json entries ("serde 1.0.1 → 1.0.2")
+// shown in place of the line diff, which is mostly checksum and resolution noise
+
+use anyhow::{Context, Result};
+use serde_yaml::Value;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use super::DiffEntry;
+
+/// Lockfile format of a compared file, by file name
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LockfileKind {
+    /// Cargo.lock
+    Cargo,
+    /// package-lock.json (npm)
+    Npm,
+}
+
+impl LockfileKind {
+    pub fn of(path: &Path) -> Option<Self> {
+        match path.file_name()?.to_str()? {
+            "Cargo.lock" => Some(Self::Cargo),
+            "package-lock.json" => Some(Self::Npm),
+            _ => None,
+        }
+    }
+}
+
+/// Locked versions by package name (a package can be locked at several versions)
+pub type LockedPackages = BTreeMap<String, BTreeSet<String>>;
+
+/// Packages locked by a lockfile
+pub fn locked_packages(kind: LockfileKind, text: &str) -> Result<LockedPackages> {
+    match kind {
+        LockfileKind::Cargo => Ok(cargo_packages(text)),
+        LockfileKind::Npm => npm_packages(text),
+    }
+}
+
+/// `name` and `version` of every `[[package]]` table
+fn cargo_packages(text: &str) -> LockedPackages {
+    let mut packages = LockedPackages::new();
+    let mut name = None;
+    let string = |line: &str, key: &str| -> Option<String> {
+        let (k, v) = line.split_once('=')?;
+        (k.trim() == key).then(|| v.trim().trim_matches('"').to_string())
+    };
+    for line in text.lines().map(str::trim) {
+        if line.starts_with('[') {
+            name = None;
+        } else if let Some(value) = string(line, "name") {
+            name = Some(value);
+        } else if let (Some(version), Some(name)) = (string(line, "version"), &name) {
+            packages.entry(name.clone()).or_default().insert(version);
+        }
+    }
+    packages
+}
+
+/// Versions under `packages` (lockfile v2 / v3, keyed by install path), else under the nested
+/// `dependencies` of v1
+fn npm_packages(text: &str) -> Result<LockedPackages> {
+    let lock: Value = serde_yaml::from_str(text).context("Not a valid package-lock.json")?;
+    let mut packages = LockedPackages::new();
+    if let Some(installed) = lock.get("packages").and_then(Value::as_mapping) {
+        for (path, package) in installed {
+            let path = path.as_str().unwrap_or_default();
+            // "" is the project itself
+            let name = match path.rsplit_once("node_modules/") {
+                Some((_, name)) => name,
+                None => continue,
+            };
+            if let Some(version) = package.get("version").and_then(Value::as_str) {
+                packages.entry(name.to_string()).or_default().insert(version.to_string());
+            }
+        }
+    } else if let Some(dependencies) = lock.get("dependencies") {
+        npm_v1_dependencies(dependencies, &mut packages);
+    }
+    Ok(packages)
+}
+
+fn npm_v1_dependencies(dependencies: &Value, packages: &mut LockedPackages) {
+    for (name, dependency) in dependencies.as_mapping().into_iter().flatten() {
+        if let (Some(name), Some(version)) = (name.as_str(), dependency.get("version").and_then(Value::as_str)) {
+            packages.entry(name.to_string()).or_default().insert(version.to_string());
+        }
+        if let Some(nested) = dependency.get("dependencies") {
+            npm_v1_dependencies(nested, packages);
+        }
+    }
+}
+
+/// Order of two versions: numeric parts compared as numbers, the rest as text
+/// ("1.10.0" > "1.9.2", "2.0.0" > "2.0.0-rc.1")
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let (a_core, a_pre) = a.split_once('-').unwrap_or((a, ""));
+    let (b_core, b_pre) = b.split_once('-').unwrap_or((b, ""));
+    let parts = |core: &str| -> Vec<u64> { core.split('.').map(|part| part.parse().unwrap_or(0)).collect() };
+    parts(a_core).cmp(&parts(b_core)).then_with(|| match (a_pre.is_empty(), b_pre.is_empty()) {
+        (true, true) => Ordering::Equal,
+        // A pre-release comes before its release
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) => a_pre.cmp(b_pre),
+    })
+}
+
+/// What syncing does to a dependency of the destination's lockfile
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DependencyChange {
+    Added(BTreeSet<String>),
+    Removed(BTreeSet<String>),
+    /// (destination versions, source versions)
+    Upgraded(BTreeSet<String>, BTreeSet<String>),
+    Downgraded(BTreeSet<String>, BTreeSet<String>),
+}
+
+/// Dependencies whose locked versions differ, by name: the destination's versions as they
+/// are and as the source would make them
+pub fn dependency_changes(source: &LockedPackages, dest: &LockedPackages) -> Vec<(String, DependencyChange)> {
+    let names: BTreeSet<&String> = source.keys().chain(dest.keys()).collect();
+    let newest = |versions: &BTreeSet<String>| versions.iter().max_by(|a, b| compare_versions(a, b)).cloned();
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let change = match (dest.get(name), source.get(name)) {
+                (None, Some(to)) => DependencyChange::Added(to.clone()),
+                (Some(from), None) => DependencyChange::Removed(from.clone()),
+                (Some(from), Some(to)) if from == to => return None,
+                (Some(from), Some(to)) => match (newest(from), newest(to)) {
+                    (Some(a), Some(b)) if compare_versions(&b, &a) == Ordering::Less => {
+                        DependencyChange::Downgraded(from.clone(), to.clone())
+                    }
+                    _ => DependencyChange::Upgraded(from.clone(), to.clone()),
+                },
+                (None, None) => return None,
+            };
+            Some((name.clone(), change))
+        })
+        .collect()
+}
+
+/// Dependency summary of a lockfile entry in the layout of the diff view: what syncing would
+/// change in the destination's lockfile (`+` added, `-` removed, `~` upgraded or downgraded)
+/// None if the entry isn't a lockfile or a side can't be read or parsed
+pub fn lockfile_summary(diff: &DiffEntry) -> Option<String> {
+    let kind = LockfileKind::of(&diff.path)?;
+    let parse = |text: Option<String>| match text {
+        Some(text) => locked_packages(kind, &text).ok(),
+        None => Some(LockedPackages::new()),
+    };
+    let (source, dest) = diff.comparison_text();
+    let (source, dest) = (parse(source)?, parse(dest)?);
+    let changes = dependency_changes(&source, &dest);
+    
+    let versions = |versions: &BTreeSet<String>| versions.iter().cloned().collect::<Vec<_>>().join(", ");
+    let (mut upgraded, mut downgraded, mut added, mut removed) = (0, 0, 0, 0);
+    let mut body = Vec::new();
+    for (name, change) in &changes {
+        body.push(match change {
+            DependencyChange::Added(to) => {
+                added += 1;
+                format!("+{} {}", name, versions(to))
+            }
+            DependencyChange::Removed(from) => {
+                removed += 1;
+                format!("-{} {}", name, versions(from))
+            }
+            DependencyChange::Upgraded(from, to) => {
+                upgraded += 1;
+                format!("~{} {} → {}", name, versions(from), versions(to))
+            }
+            DependencyChange::Downgraded(from, to) => {
+                downgraded += 1;
+                format!("~{} {} → {} (downgrade)", name, versions(from), versions(to))
+            }
+        });
+    }
+    
+    let mut lines = vec![
+        format!("--- {}", diff.source_path.display()),
+        format!("+++ {}", diff.destination_path.display()),
+        format!(
+            "@@ syncing changes {} dependencies of the destination: {} upgraded, {} downgraded, {} added, {} removed @@",
+            changes.len(),
+            upgraded,
+            downgraded,
+            added,
+            removed
+        ),
+    ];
+    if body.is_empty() {
+        lines.push(" (same dependency versions; only checksums, sources or ordering differ)".to_string());
+    }
+    lines.extend(body);
+    Some(lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lockfile_dependency_changes() {
+        let dest = "version = 3\n\n[[package]]\nname = \"serde\"\nversion = \"1.0.9\"\nsource = \"registry\"\n\n[[package]]\nname = \"old\"\nversion = \"0.1.0\"\n\n[[package]]\nname = \"log\"\nversion = \"0.4.2\"\n";
+        let source = "[[package]]\nname = \"log\"\nversion = \"0.4.2\"\nchecksum = \"abc\"\n\n[[package]]\nname = \"serde\"\nversion = \"1.0.10\"\n\n[[package]]\nname = \"tokio\"\nversion = \"1.38.0\"\n";
+        let changes = dependency_changes(&cargo_packages(source), &cargo_packages(dest));
+        let set = |v: &str| BTreeSet::from([v.to_string()]);
+        assert_eq!(
+            changes,
+            [
+                ("old".to_string(), DependencyChange::Removed(set("0.1.0"))),
+                ("serde".to_string(), DependencyChange::Upgraded(set("1.0.9"), set("1.0.10"))),
+                ("tokio".to_string(), DependencyChange::Added(set("1.38.0"))),
+            ]
+        );
+
+        let npm = r#"{"lockfileVersion": 3, "packages": {"": {"name": "app"}, "node_modules/left-pad": {"version": "1.3.0"}, "node_modules/a/node_modules/left-pad": {"version": "1.1.0"}}}"#;
+        let packages = npm_packages(npm).unwrap();
+        assert_eq!(packages.len(), 1);
+        assert_eq!(packages["left-pad"].len(), 2);
+
+        assert_eq!(compare_versions("2.0.0", "2.0.0-rc.1"), Ordering::Greater);
+        assert_eq!(compare_versions("1.9.2", "1.10.0"), Ordering::Less);
+        assert_eq!(LockfileKind::of(Path::new("web/package-lock.json")), Some(LockfileKind::Npm));
+    }
+}
@@ -0,0 +1,198 @@
+// File Metadata
+// Size, times, permissions, hash, encoding and line endings of a file, for diagnosing diffs
+
+use std::fs;
+use std::path::Path;
+use std::time::SystemTime;
+
+use super::snapshot::content_hash;
+use super::DiffEntry;
+
+/// Text encoding guessed from a file's bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Ascii,
+    Utf8,
+    /// UTF-8 starting with a byte order mark
+    Utf8Bom,
+    Utf16Le,
+    Utf16Be,
+    /// Contains NUL bytes
+    Binary,
+    /// Not valid UTF-8 (e.g. Latin-1 or Windows-1252)
+    Other,
+}
+
+impl Encoding {
+    /// Guess the encoding of `content` from its byte order mark and bytes
+    pub fn detect(content: &[u8]) -> Self {
+        if content.starts_with(&[0xEF, 0xBB, 0xBF]) {
+            Encoding::Utf8Bom
+        } else if content.starts_with(&[0xFF, 0xFE]) {
+            Encoding::Utf16Le
+        } else if content.starts_with(&[0xFE, 0xFF]) {
+            Encoding::Utf16Be
+        } else if content.contains(&0) {
+            Encoding::Binary
+        } else if content.is_ascii() {
+            Encoding::Ascii
+        } else if std::str::from_utf8(content).is_ok() {
+            Encoding::Utf8
+        } else {
+            Encoding::Other
+        }
+    }
+    
+    pub fn label(self) -> &'static str {
+        match self {
+            Encoding::Ascii => "ASCII",
+            Encoding::Utf8 => "UTF-8",
+            Encoding::Utf8Bom => "UTF-8 with BOM",
+            Encoding::Utf16Le => "UTF-16 LE",
+            Encoding::Utf16Be => "UTF-16 BE",
+            Encoding::Binary => "binary",
+            Encoding::Other => "not UTF-8",
+        }
+    }
+}
+
+/// Line endings used in a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineEndings {
+    /// No line breaks at all
+    None,
+    Lf,
+    CrLf,
+    Cr,
+    /// More than one style: (CRLF, LF, CR) counts
+    Mixed(usize, usize, usize),
+}
+
+impl LineEndings {
+    /// Count the line breaks of `content` by style
+    pub fn detect(content: &[u8]) -> Self {
+        let (mut crlf, mut lf, mut cr) = (0, 0, 0);
+        let mut bytes = content.iter().peekable();
+        while let Some(&byte) = bytes.next() {
+            match byte {
+                b'\r' if bytes.peek() == Some(&&b'\n') => {
+                    bytes.next();
+                    crlf += 1;
+                }
+                b'\r' => cr += 1,
+                b'\n' => lf += 1,
+                _ => {}
+            }
+        }
+        match (crlf, lf, cr) {
+            (0, 0, 0) => LineEndings::None,
+            (_, 0, 0) => LineEndings::CrLf,
+            (0, _, 0) => LineEndings::Lf,
+            (0, 0, _) => LineEndings::Cr,
+            _ => LineEndings::Mixed(crlf, lf, cr),
+        }
+    }
+    
+    pub fn label(self) -> String {
+        match self {
+            LineEndings::None => "none".to_string(),
+            LineEndings::Lf => "LF".to_string(),
+            LineEndings::CrLf => "CRLF".to_string(),
+            LineEndings::Cr => "CR".to_string(),
+            LineEndings::Mixed(crlf, lf, cr) => format!("mixed ({} CRLF, {} LF, {} CR)", crlf, lf, cr),
+        }
+    }
+}
+
+/// What the file system and the content say about one file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileMetadata {
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+    /// Permission bits as `rwxr-xr-x (755)`, or `read-only` / `writable` where there are none
+    pub permissions: String,
+    /// Content hash as used by snapshots (None if the file couldn't be read)
+    pub hash: Option<String>,
+    pub encoding: Option<Encoding>,
+    pub line_endings: Option<LineEndings>,
+}
+
+impl FileMetadata {
+    /// Metadata of the file at `path`, or None if there is no file
+    /// The content-based fields are None when the file can't be read
+    pub fn read(path: &Path) -> Option<Self> {
+        let meta = fs::metadata(path).ok().filter(|meta| meta.is_file())?;
+        let content = fs::read(path).ok();
+        let encoding = content.as_deref().map(Encoding::detect);
+        Some(Self {
+            size: meta.len(),
+            modified: meta.modified().ok(),
+            permissions: permissions_label(&meta),
+            hash: content.as_deref().map(content_hash),
+            line_endings: content
+                .as_deref()
+                .filter(|_| encoding != Some(Encoding::Binary))
+                .map(LineEndings::detect),
+            encoding,
+        })
+    }
+}
+
+/// Metadata of both files of a diff entry (None where a side doesn't exist)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntryMetadata {
+    pub source: Option<FileMetadata>,
+    pub destination: Option<FileMetadata>,
+}
+
+impl EntryMetadata {
+    pub fn read(entry: &DiffEntry) -> Self {
+        Self {
+            source: FileMetadata::read(&entry.source_path),
+            destination: FileMetadata::read(&entry.destination_path),
+        }
+    }
+}
+
+#[cfg(unix)]
+fn permissions_label(meta: &fs::Metadata) -> String {
+    use std::os::unix::fs::PermissionsExt;
+    
+    let mode = meta.permissions().mode() & 0o777;
+    let bits: String = (0..9)
+        .map(|bit| {
+            let set = mode & (0o400 >> bit) != 0;
+            match (set, bit % 3) {
+                (false, _) => '-',
+                (true, 0) => 'r',
+                (true, 1) => 'w',
+                (true, _) => 'x',
+            }
+        })
+        .collect();
+    format!("{} ({:03o})", bits, mode)
+}
+
+#[cfg(not(unix))]
+fn permissions_label(meta: &fs::Metadata) -> String {
+    if meta.permissions().readonly() { "read-only" } else { "writable" }.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_encoding_and_line_endings() {
+        assert_eq!(Encoding::detect(b"plain"), Encoding::Ascii);
+        assert_eq!(Encoding::detect("größe".as_bytes()), Encoding::Utf8);
+        assert_eq!(Encoding::detect(b"\xEF\xBB\xBFbom"), Encoding::Utf8Bom);
+        assert_eq!(Encoding::detect(b"caf\xE9"), Encoding::Other);
+        assert_eq!(Encoding::detect(b"a\0b"), Encoding::Binary);
+        
+        assert_eq!(LineEndings::detect(b"one"), LineEndings::None);
+        assert_eq!(LineEndings::detect(b"a\nb\n"), LineEndings::Lf);
+        assert_eq!(LineEndings::detect(b"a\r\nb\r\n"), LineEndings::CrLf);
+        assert_eq!(LineEndings::detect(b"a\r\nb\nc\r\n"), LineEndings::Mixed(2, 1, 0));
+    }
+}
@@ -0,0 +1,176 @@
+// Metrics
+// Counters and gauges of scans and syncs in the Prometheus text format, served over HTTP on
+// an optional port
+//
+// The app records into `Metrics` after each scan and sync; `sync-manager serve --metrics
+// <addr>` (core::daemon) polls `MetricsServer` between rescans.
+
+use anyhow::{Context, Result};
+use std::io::{BufRead, BufReader, ErrorKind, Write};
+use std::net::{SocketAddr, TcpListener};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime};
+
+use super::SyncResult;
+
+/// How long a connected client may take to send its request
+const READ_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Totals since the process started
+#[derive(Debug, Default)]
+pub struct Metrics {
+    files_scanned: AtomicU64,
+    scans: AtomicU64,
+    files_synced: AtomicU64,
+    bytes_synced: AtomicU64,
+    sync_errors: AtomicU64,
+    /// Seconds since the Unix epoch of the last sync without failures (0 = none yet)
+    last_success: AtomicU64,
+}
+
+impl Metrics {
+    /// Count a finished scan that compared `files` files
+    pub fn record_scan(&self, files: usize) {
+        self.scans.fetch_add(1, Ordering::Relaxed);
+        self.files_scanned.fetch_add(files as u64, Ordering::Relaxed);
+    }
+    
+    /// Count the outcomes of a finished sync
+    pub fn record_sync(&self, result: &SyncResult, finished: SystemTime) {
+        let bytes: u64 = result.outcomes.iter().filter(|outcome| !outcome.is_failed()).map(|outcome| outcome.bytes).sum();
+        self.files_synced.fetch_add(result.synced as u64, Ordering::Relaxed);
+        self.bytes_synced.fetch_add(bytes, Ordering::Relaxed);
+        self.sync_errors.fetch_add(result.failed as u64, Ordering::Relaxed);
+        if result.failed == 0 && !result.cancelled {
+            let secs = finished.duration_since(SystemTime::UNIX_EPOCH).map(|elapsed| elapsed.as_secs()).unwrap_or(0);
+            self.last_success.store(secs, Ordering::Relaxed);
+        }
+    }
+    
+    /// Prometheus text exposition of the metrics
+    pub fn render(&self) -> String {
+        let metrics = [
+            ("sync_manager_scans_total", "counter", "Diff scans finished", &self.scans),
+            ("sync_manager_files_scanned_total", "counter", "Files compared by diff scans", &self.files_scanned),
+            ("sync_manager_files_synced_total", "counter", "Files copied, linked or deleted by syncs", &self.files_synced),
+            ("sync_manager_bytes_synced_total", "counter", "Bytes written to destinations by syncs", &self.bytes_synced),
+            ("sync_manager_sync_errors_total", "counter", "Files that failed to sync", &self.sync_errors),
+            (
+                "sync_manager_last_success_timestamp_seconds",
+                "gauge",
+                "Unix time of the last sync without failures",
+                &self.last_success,
+            ),
+        ];
+        let mut text = String::new();
+        for (name, kind, help, value) in metrics {
+            text.push_str(&format!(
+                "# HELP {0} {1}\n# TYPE {0} {2}\n{0} {3}\n",
+                name,
+                help,
+                kind,
+                value.load(Ordering::Relaxed)
+            ));
+        }
+        text
+    }
+}
+
+/// HTTP listener answering every GET of `/metrics` with `Metrics::render`
+#[derive(Debug)]
+pub struct MetricsServer {
+    listener: TcpListener,
+}
+
+impl MetricsServer {
+    /// Listen on `addr` (e.g. 127.0.0.1:9464; port 0 picks a free one)
+    pub fn bind(addr: SocketAddr) -> Result<Self> {
+        let listener = TcpListener::bind(addr).with_context(|| format!("Failed to listen on {}", addr))?;
+        listener.set_nonblocking(true)?;
+        Ok(Self { listener })
+    }
+    
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        Ok(self.listener.local_addr()?)
+    }
+    
+    /// Answer the requests waiting to be served, without blocking when there are none
+    /// Returns the number of requests answered
+    pub fn poll(&self, metrics: &Metrics) -> Result<usize> {
+        let mut answered = 0;
+        loop {
+            let stream = match self.listener.accept() {
+                Ok((stream, _)) => stream,
+                Err(err) if err.kind() == ErrorKind::WouldBlock => return Ok(answered),
+                Err(err) => return Err(err).context("Failed to accept a metrics connection"),
+            };
+            stream.set_nonblocking(false)?;
+            stream.set_read_timeout(Some(READ_TIMEOUT))?;
+            let mut request_line = String::new();
+            if BufReader::new(&stream).read_line(&mut request_line).is_err() {
+                continue;
+            }
+            let (status, content_type, body) = match request_line.split_whitespace().take(2).collect::<Vec<_>>()[..] {
+                ["GET", "/metrics"] => ("200 OK", "text/plain; version=0.0.4", metrics.render()),
+                ["GET", _] => ("404 Not Found", "text/plain", "Not found; metrics are at /metrics\n".to_string()),
+                _ => ("405 Method Not Allowed", "text/plain", "Only GET is supported\n".to_string()),
+            };
+            let response = format!(
+                "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                content_type,
+                body.len(),
+                body
+            );
+            // A client that hung up doesn't stop the others from being served
+            let _ = (&stream).write_all(response.as_bytes());
+            answered += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operations::{FileOutcome, OutcomeStatus};
+    use std::io::Read;
+    use std::net::TcpStream;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_metrics_endpoint() {
+        let metrics = Metrics::default();
+        metrics.record_scan(120);
+        let outcome = |status, bytes| FileOutcome {
+            path: PathBuf::from("a.md"),
+            status,
+            bytes,
+            duration: Duration::ZERO,
+            retries: 0,
+        };
+        let result = SyncResult {
+            synced: 2,
+            failed: 0,
+            skipped: 0,
+            errors: Vec::new(),
+            outcomes: vec![outcome(OutcomeStatus::Copied, 300), outcome(OutcomeStatus::Copied, 45)],
+            cancelled: false,
+        };
+        metrics.record_sync(&result, SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000));
+
+        let server = MetricsServer::bind("127.0.0.1:0".parse().unwrap()).unwrap();
+        let mut client = TcpStream::connect(server.local_addr().unwrap()).unwrap();
+        client.write_all(b"GET /metrics HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+        while server.poll(&metrics).unwrap() == 0 {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        let mut response = String::new();
+        client.read_to_string(&mut response).unwrap();
+
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.contains("\nsync_manager_files_scanned_total 120\n"));
+        assert!(response.contains("\nsync_manager_bytes_synced_total 345\n"));
+        assert!(response.contains("\nsync_manager_last_success_timestamp_seconds 1700000000\n"));
+        assert!(response.contains("# TYPE sync_manager_sync_errors_total counter"));
+    }
+}
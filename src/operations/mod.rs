@@ -1,10 +1,46 @@
 // Operations module
 // Business logic for sync operations, diff computation, and git integration
 
+pub mod archive_diff;
+pub mod cancel;
+pub mod control;
 pub mod diff;
+pub mod diff_copy;
+pub mod doctor;
+pub mod export;
+pub mod filesystem;
+pub mod image;
+pub mod lockfile;
+pub mod metadata;
+pub mod metrics;
+pub mod secrets;
 pub mod sync;
 pub mod git;
+#[cfg(feature = "libgit2")]
+pub mod git_libgit2;
+pub mod progress;
+pub mod snapshot;
+pub mod structural_diff;
+pub mod transform;
+pub mod watchdog;
 
-pub use diff::{DiffEngine, DiffEntry, DiffType, FileStatus};
-pub use sync::SyncEngine;
-pub use git::GitOps;
+pub use archive_diff::{archive_diff_content, ArchiveKind, MemberChange};
+pub use cancel::CancellationToken;
+pub use control::{ControlRequest, ControlResponse};
+pub use diff::{ChangeReason, DiffEngine, DiffEntry, DiffStats, DiffType, ExcludeRule, FileStatus};
+pub use diff_copy::{aligned_line, format_hunk, hunk_range, take_hunk};
+pub use doctor::{check_workspace, HealthReport, Severity};
+pub use export::{export_archive, ArchiveFormat, ExportSummary};
+pub use filesystem::{FileSystem, MemoryFileSystem, RealFileSystem};
+pub use image::{HalfBlock, ImageFile, ImageFormat, ImagePair, Thumbnail};
+pub use lockfile::{lockfile_summary, DependencyChange, LockfileKind};
+pub use metadata::{Encoding, EntryMetadata, FileMetadata, LineEndings};
+pub use metrics::{Metrics, MetricsServer};
+pub use secrets::SecretScanner;
+pub use sync::{FailureKind, FileOutcome, OutcomeStatus, SyncEngine, SyncOptions, SyncResult};
+pub use git::{BlameLine, FileCommit, GitBackend, GitCli, GitOps, NestedRepo};
+pub use progress::{ProgressSnapshot, SyncProgress};
+pub use snapshot::{Snapshot, SnapshotChange, SnapshotStore};
+pub use structural_diff::{entry_leaves, is_structured, leaf_lines, parse_leaves, structural_diff_content};
+pub use transform::{FileTransform, TransformPipeline};
+pub use watchdog::{Stall, Watchdog};
@@ -0,0 +1,147 @@
+// Sync Progress
+// Counters shared by the sync workers and the UI, and the throughput cap for slow destinations
+
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Progress of a running sync, updated by the workers and read from other threads
+#[derive(Debug)]
+pub struct SyncProgress {
+    total: AtomicUsize,
+    done: AtomicUsize,
+    bytes: AtomicU64,
+    started: Mutex<Instant>,
+}
+
+/// Progress at one point in time
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProgressSnapshot {
+    /// Files finished (synced, skipped or failed)
+    pub done: usize,
+    pub total: usize,
+    /// Bytes written so far
+    pub bytes: u64,
+    pub elapsed: Duration,
+}
+
+impl ProgressSnapshot {
+    /// Average bytes written per second since the sync started
+    pub fn throughput(&self) -> u64 {
+        let secs = self.elapsed.as_secs_f64();
+        if secs > 0.0 {
+            (self.bytes as f64 / secs) as u64
+        } else {
+            0
+        }
+    }
+    
+    /// Finished share of the files, 0.0 - 1.0
+    pub fn ratio(&self) -> f64 {
+        if self.total == 0 {
+            0.0
+        } else {
+            (self.done as f64 / self.total as f64).min(1.0)
+        }
+    }
+}
+
+impl Default for SyncProgress {
+    fn default() -> Self {
+        Self {
+            total: AtomicUsize::new(0),
+            done: AtomicUsize::new(0),
+            bytes: AtomicU64::new(0),
+            started: Mutex::new(Instant::now()),
+        }
+    }
+}
+
+impl SyncProgress {
+    /// Reset the counters for a sync of `total` files
+    pub fn begin(&self, total: usize) {
+        self.total.store(total, Ordering::Relaxed);
+        self.done.store(0, Ordering::Relaxed);
+        self.bytes.store(0, Ordering::Relaxed);
+        *self.started.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = Instant::now();
+    }
+    
+    pub fn file_done(&self) {
+        self.done.fetch_add(1, Ordering::Relaxed);
+    }
+    
+    pub fn add_bytes(&self, bytes: u64) {
+        self.bytes.fetch_add(bytes, Ordering::Relaxed);
+    }
+    
+    pub fn snapshot(&self) -> ProgressSnapshot {
+        let started = *self.started.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        ProgressSnapshot {
+            done: self.done.load(Ordering::Relaxed),
+            total: self.total.load(Ordering::Relaxed),
+            bytes: self.bytes.load(Ordering::Relaxed),
+            elapsed: started.elapsed(),
+        }
+    }
+}
+
+/// Caps the combined write rate of all sync workers
+#[derive(Debug)]
+pub struct Throttle {
+    bytes_per_sec: u64,
+    started: Instant,
+    consumed: AtomicU64,
+}
+
+impl Throttle {
+    /// Cap at `bytes_per_sec` (None for 0, meaning unlimited)
+    pub fn new(bytes_per_sec: u64) -> Option<Self> {
+        (bytes_per_sec > 0).then(|| Self {
+            bytes_per_sec,
+            started: Instant::now(),
+            consumed: AtomicU64::new(0),
+        })
+    }
+    
+    /// Account for `bytes` about to be written, sleeping until the cap allows them
+    pub fn acquire(&self, bytes: u64) {
+        let wait = self.delay_for(bytes);
+        if !wait.is_zero() {
+            std::thread::sleep(wait);
+        }
+    }
+    
+    /// How long to wait before writing `bytes` more
+    fn delay_for(&self, bytes: u64) -> Duration {
+        let consumed = self.consumed.fetch_add(bytes, Ordering::Relaxed) + bytes;
+        let due = Duration::from_secs_f64(consumed as f64 / self.bytes_per_sec as f64);
+        due.saturating_sub(self.started.elapsed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_throttle_delays_past_the_cap() {
+        assert!(Throttle::new(0).is_none());
+
+        let throttle = Throttle::new(1_000).unwrap();
+        // 500 bytes are due at 0.5 s, the next 1000 at 1.5 s
+        assert!(throttle.delay_for(500) > Duration::from_millis(400));
+        assert!(throttle.delay_for(1_000) > Duration::from_millis(1_400));
+    }
+
+    #[test]
+    fn test_snapshot_ratio_and_throughput() {
+        let snapshot = ProgressSnapshot {
+            done: 3,
+            total: 4,
+            bytes: 2_000,
+            elapsed: Duration::from_secs(2),
+        };
+        assert_eq!(snapshot.ratio(), 0.75);
+        assert_eq!(snapshot.throughput(), 1_000);
+    }
+}
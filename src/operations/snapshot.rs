@@ -0,0 +1,243 @@
+// Snapshots
+// Named baselines of the shared-resources tree (a content hash per file) that the current
+// tree can be compared against later, e.g. to review what changed since a release
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::{DiffEngine, FileStatus};
+use crate::core::session::STATE_DIR_NAME;
+
+/// Directory (inside the state directory) holding one YAML file per snapshot
+const SNAPSHOTS_DIR_NAME: &str = "snapshots";
+
+/// Content hash of every file in a tree, keyed by relative path ('/'-separated)
+pub type TreeHashes = BTreeMap<String, String>;
+
+/// A named record of the shared-resources tree at one point in time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Snapshot name (also its file name)
+    pub name: String,
+    /// Creation time in seconds since the Unix epoch
+    pub created: u64,
+    /// Hash of every file at creation time
+    pub files: TreeHashes,
+}
+
+/// A file that differs between a snapshot and the current tree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotChange {
+    /// Path relative to the tree root
+    pub path: PathBuf,
+    /// Added / Modified / Deleted since the snapshot
+    pub status: FileStatus,
+}
+
+impl Snapshot {
+    /// Creation time as a SystemTime
+    pub fn created_time(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(self.created)
+    }
+    
+    /// Files added, modified or deleted in `current` since this snapshot, sorted by path
+    pub fn compare(&self, current: &TreeHashes) -> Vec<SnapshotChange> {
+        let change = |path: &String, status| SnapshotChange {
+            path: PathBuf::from(path),
+            status,
+        };
+        
+        let mut changes: Vec<SnapshotChange> = current
+            .iter()
+            .filter_map(|(path, hash)| match self.files.get(path) {
+                None => Some(change(path, FileStatus::Added)),
+                Some(old) if old != hash => Some(change(path, FileStatus::Modified)),
+                Some(_) => None,
+            })
+            .chain(
+                self.files
+                    .keys()
+                    .filter(|path| !current.contains_key(*path))
+                    .map(|path| change(path, FileStatus::Deleted)),
+            )
+            .collect();
+        changes.sort_by(|a, b| a.path.cmp(&b.path));
+        changes
+    }
+}
+
+/// Snapshot files of a workspace
+#[derive(Debug, Clone)]
+pub struct SnapshotStore {
+    dir: PathBuf,
+}
+
+impl SnapshotStore {
+    /// Store under the workspace's state directory
+    pub fn new(workspace_root: &Path) -> Self {
+        Self {
+            dir: workspace_root.join(STATE_DIR_NAME).join(SNAPSHOTS_DIR_NAME),
+        }
+    }
+    
+    /// All snapshots, newest first
+    /// Unreadable snapshot files are skipped
+    pub fn list(&self) -> Result<Vec<Snapshot>> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+        
+        let entries = fs::read_dir(&self.dir)
+            .with_context(|| format!("Failed to read snapshots: {}", self.dir.display()))?;
+        let mut snapshots: Vec<Snapshot> = entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "yaml"))
+            .filter_map(|entry| Self::load_from(&entry.path()).ok())
+            .collect();
+        snapshots.sort_by(|a, b| b.created.cmp(&a.created).then_with(|| a.name.cmp(&b.name)));
+        Ok(snapshots)
+    }
+    
+    /// Snapshot files that can't be read or parsed (skipped by `list`)
+    pub fn unreadable(&self) -> Vec<(PathBuf, anyhow::Error)> {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+        entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "yaml"))
+            .filter_map(|path| Self::load_from(&path).err().map(|err| (path, err)))
+            .collect()
+    }
+    
+    /// Record the current state of `root` under a new name
+    pub fn create(&self, name: &str, root: &Path, engine: &DiffEngine) -> Result<Snapshot> {
+        let name = name.trim();
+        validate_name(name)?;
+        let path = self.path_for(name);
+        if path.exists() {
+            bail!("Snapshot '{}' already exists", name);
+        }
+        
+        let snapshot = Snapshot {
+            name: name.to_string(),
+            created: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+            files: hash_tree(root, engine)?,
+        };
+        
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Failed to create snapshot directory: {}", self.dir.display()))?;
+        let content = serde_yaml::to_string(&snapshot).context("Failed to serialize snapshot")?;
+        fs::write(&path, content)
+            .with_context(|| format!("Failed to write snapshot: {}", path.display()))?;
+        Ok(snapshot)
+    }
+    
+    /// Delete a snapshot
+    pub fn delete(&self, name: &str) -> Result<()> {
+        let path = self.path_for(name);
+        fs::remove_file(&path).with_context(|| format!("Failed to delete snapshot: {}", path.display()))
+    }
+    
+    fn path_for(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{}.yaml", name))
+    }
+    
+    fn load_from(path: &Path) -> Result<Snapshot> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read snapshot: {}", path.display()))?;
+        serde_yaml::from_str(&content).context("Failed to parse snapshot YAML")
+    }
+}
+
+/// Snapshot names become file names, so they can't contain path separators
+fn validate_name(name: &str) -> Result<()> {
+    if name.is_empty() {
+        bail!("Snapshot name is empty");
+    }
+    if name.starts_with('.') || name.contains(['/', '\\', ':']) {
+        bail!("Invalid snapshot name '{}'", name);
+    }
+    Ok(())
+}
+
+/// Hash every file under `root` that the diff engine doesn't exclude
+pub fn hash_tree(root: &Path, engine: &DiffEngine) -> Result<TreeHashes> {
+    let mut files = TreeHashes::new();
+    if !root.exists() {
+        return Ok(files);
+    }
+    
+    for entry in walkdir::WalkDir::new(root)
+        .into_iter()
+        .filter_entry(|e| !engine.is_excluded(e.path()))
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+    {
+        let relative = entry
+            .path()
+            .strip_prefix(root)
+            .context("Failed to calculate relative path")?;
+        let content = fs::read(entry.path())
+            .with_context(|| format!("Failed to read {}", entry.path().display()))?;
+        files.insert(relative.to_string_lossy().replace('\\', "/"), content_hash(&content));
+    }
+    Ok(files)
+}
+
+/// Hex content hash of a file's bytes, as stored in snapshots
+pub fn content_hash(content: &[u8]) -> String {
+    format!("{:016x}", fnv1a(content))
+}
+
+/// 64-bit FNV-1a: stable across runs and Rust versions, unlike std's DefaultHasher
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn hashes(files: &[(&str, &str)]) -> TreeHashes {
+        files.iter().map(|(path, hash)| (path.to_string(), hash.to_string())).collect()
+    }
+    
+    #[test]
+    fn test_compare_against_snapshot() {
+        let snapshot = Snapshot {
+            name: "v1".to_string(),
+            created: 0,
+            files: hashes(&[("a.md", "1"), ("b.md", "2"), ("c.md", "3")]),
+        };
+        let changes = snapshot.compare(&hashes(&[("a.md", "1"), ("b.md", "9"), ("d.md", "4")]));
+        
+        let summary: Vec<(&str, FileStatus)> = changes
+            .iter()
+            .map(|c| (c.path.to_str().unwrap(), c.status.clone()))
+            .collect();
+        assert_eq!(
+            summary,
+            [("b.md", FileStatus::Modified), ("c.md", FileStatus::Deleted), ("d.md", FileStatus::Added)]
+        );
+    }
+    
+    #[test]
+    fn test_snapshot_names_and_hash() {
+        assert!(validate_name("release-1.2").is_ok());
+        assert!(validate_name("").is_err());
+        assert!(validate_name("../escape").is_err());
+        assert_eq!(fnv1a(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+    }
+}
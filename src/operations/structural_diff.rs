@@ -0,0 +1,150 @@
+// Structural Diff
+// Key-level comparison of JSON / YAML entries: both sides are parsed and flattened to sorted
+// `key.path: value` leaves, so key order, indentation and quoting don't show up as changes
+
+use anyhow::{anyhow, Context, Result};
+use serde_yaml::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use super::DiffEntry;
+
+/// Whether a file is compared structurally when asked to, by extension
+pub fn is_structured(path: &Path) -> bool {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .is_some_and(|ext| matches!(ext.as_str(), "json" | "yaml" | "yml"))
+}
+
+/// Leaves of a document by key path (`a.b`, `list[2].name`), values written inline
+/// Empty mappings and sequences are leaves of their own, so removing every key of one still
+/// leaves it in place
+pub type Leaves = BTreeMap<String, String>;
+
+/// Parse a JSON or YAML document (JSON is read as YAML, of which it is a subset) into its leaves
+pub fn parse_leaves(text: &str) -> Result<Leaves> {
+    let value: Value = serde_yaml::from_str(text)?;
+    let mut leaves = Leaves::new();
+    flatten(&value, String::new(), &mut leaves);
+    Ok(leaves)
+}
+
+fn flatten(value: &Value, path: String, leaves: &mut Leaves) {
+    match value {
+        Value::Mapping(map) if !map.is_empty() => {
+            for (key, value) in map {
+                let key = match key {
+                    Value::String(key) => key.clone(),
+                    key => inline(key),
+                };
+                let path = if path.is_empty() { key } else { format!("{}.{}", path, key) };
+                flatten(value, path, leaves);
+            }
+        }
+        Value::Sequence(items) if !items.is_empty() => {
+            for (index, item) in items.iter().enumerate() {
+                flatten(item, format!("{}[{}]", path, index), leaves);
+            }
+        }
+        Value::Tagged(tagged) => flatten(&tagged.value, path, leaves),
+        value => {
+            leaves.insert(path, inline(value));
+        }
+    }
+}
+
+/// A value on one line: scalars as in JSON, collections in flow style
+fn inline(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => format!("{:?}", s),
+        Value::Sequence(items) => format!("[{}]", items.iter().map(inline).collect::<Vec<_>>().join(", ")),
+        Value::Mapping(map) => format!(
+            "{{{}}}",
+            map.iter().map(|(k, v)| format!("{}: {}", inline(k), inline(v))).collect::<Vec<_>>().join(", ")
+        ),
+        Value::Tagged(tagged) => format!("{} {}", tagged.tag, inline(&tagged.value)),
+    }
+}
+
+/// Leaves as the lines shown side by side, in key path order
+pub fn leaf_lines(leaves: &Leaves) -> Vec<String> {
+    leaves.iter().map(|(path, value)| format!("{}: {}", path, value)).collect()
+}
+
+/// Leaves of both sides of an entry as compared (a missing side has none); fails if an
+/// existing side isn't a valid document
+pub fn entry_leaves(diff: &DiffEntry) -> Result<(Leaves, Leaves)> {
+    let (source, dest) = diff.comparison_text();
+    if source.is_none() && dest.is_none() {
+        return Err(anyhow!("Neither side of {} can be read as text", diff.path.display()));
+    }
+    let parse = |text: Option<String>, side: &str| match text {
+        Some(text) => parse_leaves(&text).with_context(|| format!("Failed to parse the {} of {}", side, diff.path.display())),
+        None => Ok(Leaves::new()),
+    };
+    Ok((parse(source, "source")?, parse(dest, "destination")?))
+}
+
+/// Key-level diff of an entry in the unified diff layout of the diff view (`-` for the
+/// source's value, `+` for the destination's)
+pub fn structural_diff_content(diff: &DiffEntry) -> Result<String> {
+    let (source, dest) = entry_leaves(diff)?;
+    let keys: BTreeSet<&String> = source.keys().chain(dest.keys()).collect();
+    
+    let (mut source_only, mut dest_only, mut changed) = (0, 0, 0);
+    let mut body = Vec::new();
+    for key in keys {
+        match (source.get(key), dest.get(key)) {
+            (Some(a), Some(b)) if a == b => {}
+            (Some(a), Some(b)) => {
+                changed += 1;
+                body.push(format!("-{}: {}", key, a));
+                body.push(format!("+{}: {}", key, b));
+            }
+            (Some(a), None) => {
+                source_only += 1;
+                body.push(format!("-{}: {}", key, a));
+            }
+            (None, Some(b)) => {
+                dest_only += 1;
+                body.push(format!("+{}: {}", key, b));
+            }
+            (None, None) => {}
+        }
+    }
+    
+    let mut lines = vec![
+        format!("--- {}", diff.source_path.display()),
+        format!("+++ {}", diff.destination_path.display()),
+        format!(
+            "@@ keys: {} only in source, {} only in destination, {} changed @@",
+            source_only, dest_only, changed
+        ),
+    ];
+    if body.is_empty() {
+        lines.push(" (same keys and values; only order or formatting differs)".to_string());
+    }
+    lines.extend(body);
+    Ok(lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_leaves_ignore_order_and_formatting() {
+        let json = r#"{"b": [1, {"c": "x"}], "a": {"enabled": true, "empty": {}}}"#;
+        let yaml = "a:\n  empty: {}\n  enabled: true\nb:\n  - 1\n  - c: 'x'\n";
+        let leaves = parse_leaves(json).unwrap();
+        assert_eq!(leaves, parse_leaves(yaml).unwrap());
+        assert_eq!(leaf_lines(&leaves), ["a.empty: {}", "a.enabled: true", "b[0]: 1", "b[1].c: \"x\""]);
+
+        assert!(parse_leaves("{ not: [valid").is_err());
+        assert!(is_structured(Path::new("config/app.YML")));
+        assert!(!is_structured(Path::new("README.md")));
+    }
+}
@@ -1,11 +1,23 @@
 // Sync Engine
 // Handles file synchronization operations
 
-use anyhow::{Context, Result};
-use std::fs;
-use std::path::Path;
+use anyhow::{bail, Context, Result};
+use std::collections::BTreeSet;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use super::DiffEntry;
+use super::cancel::CancellationToken;
+use super::filesystem::{FileSystem, RealFileSystem};
+use super::progress::{SyncProgress, Throttle};
+use super::transform::{read_transformed, FileTransform};
+use super::watchdog::Watchdog;
+use super::diff::contains_files;
+use super::{DiffEntry, FileStatus};
+use crate::core::project_config::CopyStrategy;
+use tui_components::{keep_ignored_regions, IGNORE_START_MARKER};
 
 /// Options for sync operations
 #[derive(Debug, Clone)]
@@ -16,6 +28,20 @@ pub struct SyncOptions {
     pub continue_on_error: bool,
     /// Dry run - don't actually modify files
     pub dry_run: bool,
+    /// Delete destination files whose source was deleted (otherwise they are skipped)
+    pub propagate_deletes: bool,
+    /// Also remove destination directories without files whose source was deleted
+    pub remove_directories: bool,
+    /// Retries of a file operation that fails with a transient error (locked or busy file)
+    pub retry_attempts: u32,
+    /// Wait before the first retry; doubled for each further retry
+    pub retry_backoff: Duration,
+    /// Files synced in parallel (0 = one per CPU core)
+    pub workers: usize,
+    /// Copy, clone or hard link files that are written without a transform
+    pub copy_strategy: CopyStrategy,
+    /// Cap on the combined write rate of all workers in bytes per second (0 = unlimited)
+    pub max_bytes_per_sec: u64,
 }
 
 impl Default for SyncOptions {
@@ -24,6 +50,13 @@ impl Default for SyncOptions {
             create_backup: true,
             continue_on_error: true,
             dry_run: false,
+            propagate_deletes: true,
+            remove_directories: false,
+            retry_attempts: 3,
+            retry_backoff: Duration::from_millis(100),
+            workers: 0,
+            copy_strategy: CopyStrategy::default(),
+            max_bytes_per_sec: 0,
         }
     }
 }
@@ -39,6 +72,11 @@ pub struct SyncResult {
     pub skipped: usize,
     /// Error messages for failed files
     pub errors: Vec<String>,
+    /// What happened to each file, in the order the files were given
+    /// (when not continuing on errors, files not yet started at the first failure are missing)
+    pub outcomes: Vec<FileOutcome>,
+    /// Whether the sync was cancelled before it started every file (those files are missing from `outcomes`)
+    pub cancelled: bool,
 }
 
 impl SyncResult {
@@ -48,13 +86,96 @@ impl SyncResult {
             failed: 0,
             skipped: 0,
             errors: Vec::new(),
+            outcomes: Vec::new(),
+            cancelled: false,
         }
     }
+    
+    /// Total bytes written to destinations
+    pub fn bytes(&self) -> u64 {
+        self.outcomes.iter().map(|outcome| outcome.bytes).sum()
+    }
+}
+
+/// What a sync did with one file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OutcomeStatus {
+    Copied,
+    /// Written as a copy-on-write clone of the source
+    Cloned,
+    /// Written as a hard link to the source
+    Linked,
+    /// An empty directory was created
+    Created,
+    Deleted,
+    /// Unchanged, pinned against this direction, flagged as a secret, or a delete that doesn't propagate
+    Skipped,
+    /// The error with its causes, and whether retrying later may succeed
+    Failed { error: String, kind: FailureKind },
+}
+
+/// Whether a failure is worth retrying
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureKind {
+    /// The file was locked or busy on every attempt; another program may release it
+    Transient,
+    /// Missing files, permissions and other errors that retrying won't fix
+    Permanent,
+}
+
+impl FailureKind {
+    /// Kind of an error from a file operation (the innermost IO error decides)
+    pub fn of(error: &anyhow::Error) -> Self {
+        match error.chain().find_map(|cause| cause.downcast_ref::<io::Error>()) {
+            Some(err) if is_transient(err) => FailureKind::Transient,
+            _ => FailureKind::Permanent,
+        }
+    }
+}
+
+/// Whether an IO error may go away on its own: sharing and lock violations on Windows,
+/// EBUSY / ETXTBSY on Unix, and interrupted or timed-out calls
+pub fn is_transient(err: &io::Error) -> bool {
+    #[cfg(windows)]
+    const TRANSIENT_CODES: &[i32] = &[32, 33]; // ERROR_SHARING_VIOLATION, ERROR_LOCK_VIOLATION
+    #[cfg(not(windows))]
+    const TRANSIENT_CODES: &[i32] = &[16, 26]; // EBUSY, ETXTBSY
+    
+    matches!(
+        err.kind(),
+        io::ErrorKind::Interrupted | io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut | io::ErrorKind::ResourceBusy
+    ) || err.raw_os_error().is_some_and(|code| TRANSIENT_CODES.contains(&code))
+}
+
+/// Outcome of syncing one file
+#[derive(Debug, Clone)]
+pub struct FileOutcome {
+    /// Path relative to the mapping root
+    pub path: PathBuf,
+    pub status: OutcomeStatus,
+    /// Size of the content written to the destination (also for clones and links)
+    pub bytes: u64,
+    /// Time spent on the file, including retries (zero for skipped files)
+    pub duration: Duration,
+    /// Retries after transient errors
+    pub retries: u32,
+}
+
+impl FileOutcome {
+    pub fn is_failed(&self) -> bool {
+        matches!(self.status, OutcomeStatus::Failed { .. })
+    }
 }
 
 /// Engine for file synchronization operations
 pub struct SyncEngine {
     options: SyncOptions,
+    progress: Arc<SyncProgress>,
+    watchdog: Arc<Watchdog>,
+    cancel: CancellationToken,
+    throttle: Option<Throttle>,
+    /// Where files are read and written
+    fs: Arc<dyn FileSystem>,
 }
 
 impl Default for SyncEngine {
@@ -66,67 +187,332 @@ impl Default for SyncEngine {
 impl SyncEngine {
     /// Create a new sync engine with the given options
     pub fn new(options: SyncOptions) -> Self {
-        Self { options }
+        Self {
+            throttle: Throttle::new(options.max_bytes_per_sec),
+            progress: Arc::default(),
+            watchdog: Arc::default(),
+            cancel: CancellationToken::new(),
+            fs: Arc::new(RealFileSystem),
+            options,
+        }
+    }
+    
+    /// Read and write files on `fs` instead of the disk
+    pub fn with_filesystem(mut self, fs: Arc<dyn FileSystem>) -> Self {
+        self.fs = fs;
+        self
+    }
+    
+    /// Progress of the current `sync_files` call, readable from other threads
+    pub fn progress(&self) -> Arc<SyncProgress> {
+        Arc::clone(&self.progress)
+    }
+    
+    /// Heartbeat of the current `sync_files` call; skipped paths take effect before the next file
+    pub fn watchdog(&self) -> Arc<Watchdog> {
+        Arc::clone(&self.watchdog)
+    }
+    
+    /// Stop request of `sync_files`: no new file is started once it is cancelled
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.cancel.clone()
+    }
+    
+    /// Run a file operation, retrying transient failures with exponential backoff
+    /// Retries are added to `retries`
+    fn with_retry<T>(&self, retries: &mut u32, mut operation: impl FnMut() -> io::Result<T>) -> io::Result<T> {
+        let mut delay = self.options.retry_backoff;
+        let mut attempt = 0;
+        loop {
+            match operation() {
+                Err(err) if attempt < self.options.retry_attempts && is_transient(&err) => {
+                    attempt += 1;
+                    *retries += 1;
+                    std::thread::sleep(delay);
+                    delay *= 2;
+                }
+                result => return result,
+            }
+        }
+    }
+    
+    /// Sync a single file from source to destination, returning the bytes written
+    pub fn sync_file(&self, diff: &DiffEntry) -> Result<u64> {
+        self.copy_file(diff, &mut 0).map(|(_, bytes)| bytes)
     }
     
-    /// Sync a single file from source to destination
-    pub fn sync_file(&self, diff: &DiffEntry) -> Result<()> {
+    /// `sync_file`, counting retries after transient errors; also returns how the file was written
+    fn copy_file(&self, diff: &DiffEntry, retries: &mut u32) -> Result<(OutcomeStatus, u64)> {
         let source = &diff.source_path;
         let dest = &diff.destination_path;
         
+        if diff.is_blocked() {
+            bail!("{} is pinned against syncing in this direction", diff.path.display());
+        }
+        if let Some(reason) = &diff.secret {
+            bail!("{} looks like a secret ({})", diff.path.display(), reason);
+        }
+        
         if self.options.dry_run {
             println!("Would sync: {} -> {}", source.display(), dest.display());
-            return Ok(());
+            return Ok((OutcomeStatus::Copied, 0));
         }
         
         // Create backup if needed
-        if self.options.create_backup && dest.exists() {
-            self.create_backup(dest)?;
+        if self.options.create_backup && self.fs.exists(dest) {
+            self.create_backup(dest, retries)?;
         }
         
         // Ensure destination directory exists
         if let Some(parent) = dest.parent() {
-            fs::create_dir_all(parent)
+            self.fs
+                .create_dir_all(parent)
                 .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
         }
         
-        // Copy file
-        fs::copy(source, dest)
-            .with_context(|| format!("Failed to copy {} to {}", source.display(), dest.display()))?;
+        // Copy file, substituting tokens into shared templates and restoring them in edits going back
+        let content = match &diff.transform {
+            Some(transform) if diff.diff_type.shared_is_source() => read_transformed(self.fs.as_ref(), source, Some(transform)),
+            Some(transform) => self.retemplate(transform, source, dest),
+            None => None,
+        };
+        let (status, bytes) = match self.keep_destination_regions(content, source, dest) {
+            Some(content) => {
+                if let Some(throttle) = &self.throttle {
+                    throttle.acquire(content.len() as u64);
+                }
+                self.with_retry(retries, || self.fs.write(dest, content.as_bytes()))
+                    .with_context(|| format!("Failed to write {}", dest.display()))?;
+                self.progress.add_bytes(content.len() as u64);
+                (OutcomeStatus::Copied, content.len() as u64)
+            }
+            // Commands run on the written file must not reach the source through a hard link
+            None => self.place_file(source, dest, diff.transform.is_none(), retries)
+                .with_context(|| format!("Failed to copy {} to {}", source.display(), dest.display()))?,
+        };
         
         // Preserve modification time
-        if let Ok(metadata) = fs::metadata(source) {
-            if let Ok(mtime) = metadata.modified() {
+        if let Ok(metadata) = self.fs.metadata(source) {
+            if let Some(mtime) = metadata.modified {
                 let _ = filetime::set_file_mtime(dest, filetime::FileTime::from(mtime));
             }
         }
         
-        Ok(())
+        // Run formatters and other hooks on a file written from its shared template
+        if let Some(transform) = diff.source_transform() {
+            transform
+                .run_commands(dest)
+                .context("Written, but a transform command failed")?;
+        }
+        
+        Ok((status, bytes))
     }
     
-    /// Sync multiple files
+    /// Project file `source` as a template to write over the shared copy `dest`
+    /// None (copied as is) if either isn't text; without a shared copy there is nothing to restore from
+    fn retemplate(&self, transform: &FileTransform, source: &Path, dest: &Path) -> Option<String> {
+        let edited = read_transformed(self.fs.as_ref(), source, None)?;
+        let template = read_transformed(self.fs.as_ref(), dest, None)?;
+        Some(transform.retemplate(&template, &edited))
+    }
+    
+    /// Text to write in place of `content` (None: copy the file as is) that keeps the contents
+    /// of the destination's sync-ignore regions, which the scan never reports as changes
+    fn keep_destination_regions(&self, content: Option<String>, source: &Path, dest: &Path) -> Option<String> {
+        let Some(existing) = read_transformed(self.fs.as_ref(), dest, None).filter(|text| text.contains(IGNORE_START_MARKER)) else {
+            return content;
+        };
+        let text = match content {
+            Some(text) => text,
+            None => read_transformed(self.fs.as_ref(), source, None)?,
+        };
+        Some(keep_ignored_regions(&text, &existing))
+    }
+    
+    /// Write `source` to `dest` with the configured copy strategy, falling back to plainer ones
+    /// Clones and links need a fresh destination, so an existing file is removed first
+    /// (after its backup); the size of the source is returned for every strategy
+    fn place_file(&self, source: &Path, dest: &Path, allow_link: bool, retries: &mut u32) -> io::Result<(OutcomeStatus, u64)> {
+        if self.options.copy_strategy != CopyStrategy::Copy {
+            // Also removes a dangling link, which `exists` doesn't see
+            match self.with_retry(retries, || self.fs.remove_file(dest)) {
+                Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err),
+                _ => {}
+            }
+            if allow_link && self.options.copy_strategy == CopyStrategy::Hardlink && self.fs.hard_link(source, dest).is_ok() {
+                return Ok((OutcomeStatus::Linked, self.fs.metadata(dest)?.len));
+            }
+            if self.fs.reflink(source, dest).is_ok() {
+                return Ok((OutcomeStatus::Cloned, self.fs.metadata(dest)?.len));
+            }
+        }
+        self.with_retry(retries, || self.copy_bytes(source, dest))
+            .map(|bytes| (OutcomeStatus::Copied, bytes))
+    }
+    
+    /// Byte copy; under a throughput cap it goes in chunks so the cap also holds within large files
+    fn copy_bytes(&self, source: &Path, dest: &Path) -> io::Result<u64> {
+        const CHUNK_SIZE: usize = 64 * 1024;
+        
+        let Some(throttle) = &self.throttle else {
+            let bytes = self.fs.copy(source, dest)?;
+            self.progress.add_bytes(bytes);
+            return Ok(bytes);
+        };
+        
+        let mut reader = self.fs.open(source)?;
+        let mut writer = self.fs.create(dest)?;
+        let mut buffer = vec![0; CHUNK_SIZE];
+        let mut total = 0;
+        loop {
+            let read = reader.read(&mut buffer)?;
+            if read == 0 {
+                break;
+            }
+            throttle.acquire(read as u64);
+            writer.write_all(&buffer[..read])?;
+            self.watchdog.beat(dest);
+            self.progress.add_bytes(read as u64);
+            total += read as u64;
+        }
+        self.fs.copy_permissions(source, dest)?;
+        Ok(total)
+    }
+    
+    /// Sync multiple files on up to `workers` threads
+    /// Files deleted from the source are removed from the destination (if deletes propagate);
+    /// unchanged files, files whose direction override forbids this direction and files flagged
+    /// as secrets are skipped.
+    /// Destination directories are created before any file is copied. Outcomes are reported in
+    /// input order; without `continue_on_error` no new file is started after a failure, and
+    /// none after the cancellation token is cancelled (files in flight still finish)
     pub fn sync_files(&self, diffs: &[DiffEntry]) -> SyncResult {
-        let mut result = SyncResult::new();
+        self.progress.begin(diffs.len());
+        self.create_destination_dirs(diffs);
         
-        for diff in diffs {
-            match self.sync_file(diff) {
-                Ok(()) => result.synced += 1,
-                Err(e) => {
+        let next = AtomicUsize::new(0);
+        let stop = AtomicBool::new(false);
+        let worker = || {
+            let mut done = Vec::new();
+            while !stop.load(Ordering::Relaxed) && !self.cancel.is_cancelled() {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(diff) = diffs.get(index) else {
+                    break;
+                };
+                let outcome = self.sync_one(diff);
+                self.progress.file_done();
+                if outcome.is_failed() && !self.options.continue_on_error {
+                    stop.store(true, Ordering::Relaxed);
+                }
+                done.push((index, outcome));
+            }
+            done
+        };
+        
+        let workers = self.worker_count().min(diffs.len()).max(1);
+        let mut outcomes: Vec<(usize, FileOutcome)> = if workers == 1 {
+            worker()
+        } else {
+            std::thread::scope(|scope| {
+                let handles: Vec<_> = (0..workers).map(|_| scope.spawn(worker)).collect();
+                handles
+                    .into_iter()
+                    .flat_map(|handle| handle.join().expect("sync worker panicked"))
+                    .collect()
+            })
+        };
+        outcomes.sort_by_key(|(index, _)| *index);
+        
+        let mut result = SyncResult::new();
+        result.cancelled = self.cancel.is_cancelled() && outcomes.len() < diffs.len();
+        for (_, outcome) in outcomes {
+            match &outcome.status {
+                OutcomeStatus::Skipped => result.skipped += 1,
+                OutcomeStatus::Failed { error, .. } => {
                     result.failed += 1;
-                    result.errors.push(format!("{}: {}", diff.path.display(), e));
-                    
-                    if !self.options.continue_on_error {
-                        break;
-                    }
+                    result.errors.push(format!("{}: {}", outcome.path.display(), error));
                 }
+                OutcomeStatus::Copied
+                | OutcomeStatus::Cloned
+                | OutcomeStatus::Linked
+                | OutcomeStatus::Created
+                | OutcomeStatus::Deleted => result.synced += 1,
             }
+            result.outcomes.push(outcome);
         }
-        
         result
     }
     
+    /// Threads used by `sync_files` (0 in the options = one per CPU core)
+    fn worker_count(&self) -> usize {
+        match self.options.workers {
+            0 => std::thread::available_parallelism().map_or(1, |count| count.get()),
+            count => count,
+        }
+    }
+    
+    /// Create the destination directories of the files that will be copied, parents first,
+    /// so parallel copies never race on creating the same directory
+    /// Failures are left for the copies to report against their files
+    fn create_destination_dirs(&self, diffs: &[DiffEntry]) {
+        if self.options.dry_run {
+            return;
+        }
+        let dirs: BTreeSet<&Path> = diffs
+            .iter()
+            .filter(|diff| !diff.is_blocked() && diff.secret.is_none())
+            .filter(|diff| !matches!(diff.status, FileStatus::Unchanged | FileStatus::Deleted))
+            .filter_map(|diff| diff.destination_path.parent())
+            .collect();
+        for dir in dirs {
+            let _ = self.fs.create_dir_all(dir);
+        }
+    }
+    
+    /// Copy, delete or skip one file
+    fn sync_one(&self, diff: &DiffEntry) -> FileOutcome {
+        let mut retries = 0;
+        let started = Instant::now();
+        let skipped = self.watchdog.is_skipped(&diff.source_path) || self.watchdog.is_skipped(&diff.destination_path);
+        if !skipped {
+            self.watchdog.beat(&diff.destination_path);
+        }
+        let outcome = match diff.status {
+            _ if diff.is_blocked() || diff.secret.is_some() || skipped => None,
+            FileStatus::Unchanged => None,
+            FileStatus::Deleted if !self.options.propagate_deletes => None,
+            FileStatus::Deleted if diff.is_dir && !self.options.remove_directories => None,
+            FileStatus::Deleted if diff.is_dir => Some(
+                self.remove_directory(&diff.destination_path, &mut retries)
+                    .map(|()| (OutcomeStatus::Deleted, 0)),
+            ),
+            _ if diff.is_dir => Some(self.create_directory(&diff.destination_path).map(|()| (OutcomeStatus::Created, 0))),
+            FileStatus::Deleted => Some(
+                self.remove_file(&diff.destination_path, &mut retries)
+                    .map(|()| (OutcomeStatus::Deleted, 0)),
+            ),
+            _ => Some(self.copy_file(diff, &mut retries)),
+        };
+        
+        let (status, bytes, duration) = match outcome {
+            None => (OutcomeStatus::Skipped, 0, Duration::ZERO),
+            Some(Ok((status, bytes))) => (status, bytes, started.elapsed()),
+            Some(Err(e)) => {
+                let kind = FailureKind::of(&e);
+                (OutcomeStatus::Failed { error: format!("{:#}", e), kind }, 0, started.elapsed())
+            }
+        };
+        FileOutcome {
+            path: diff.path.clone(),
+            status,
+            bytes,
+            duration,
+            retries,
+        }
+    }
+    
     /// Create a backup of a file
-    fn create_backup(&self, path: &Path) -> Result<()> {
+    fn create_backup(&self, path: &Path, retries: &mut u32) -> Result<()> {
         let backup_path = path.with_extension(format!(
             "{}.backup",
             path.extension()
@@ -134,24 +520,65 @@ impl SyncEngine {
                 .unwrap_or_default()
         ));
         
-        fs::copy(path, &backup_path)
+        self.with_retry(retries, || self.fs.copy(path, &backup_path))
             .with_context(|| format!("Failed to create backup: {}", backup_path.display()))?;
         
         Ok(())
     }
     
+    /// Create an empty destination directory (with its parents)
+    fn create_directory(&self, path: &Path) -> Result<()> {
+        if self.options.dry_run {
+            println!("Would create: {}", path.display());
+            return Ok(());
+        }
+        self.fs.create_dir_all(path).with_context(|| format!("Failed to create directory: {}", path.display()))
+    }
+    
+    /// Remove a destination directory, refusing if a file has appeared in it since the scan
+    fn remove_directory(&self, path: &Path, retries: &mut u32) -> Result<()> {
+        if contains_files(self.fs.as_ref(), path) {
+            bail!("Not removing {}: it contains files", path.display());
+        }
+        if self.options.dry_run {
+            println!("Would remove: {}", path.display());
+            return Ok(());
+        }
+        self.with_retry(retries, || self.fs.remove_dir_all(path))
+            .with_context(|| format!("Failed to remove directory: {}", path.display()))
+    }
+    
+    /// Replace a file's contents with `content` (after its backup), for edits made in the diff view
+    pub fn write_file(&self, path: &Path, content: &[u8]) -> Result<()> {
+        let retries = &mut 0;
+        if self.options.dry_run {
+            println!("Would write: {}", path.display());
+            return Ok(());
+        }
+        if self.options.create_backup && self.fs.exists(path) {
+            self.create_backup(path, retries)?;
+        }
+        self.with_retry(retries, || self.fs.write(path, content))
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+    
     /// Delete a file (for removing files that only exist in destination)
     pub fn delete_file(&self, path: &Path) -> Result<()> {
+        self.remove_file(path, &mut 0)
+    }
+    
+    /// `delete_file`, counting retries after transient errors
+    fn remove_file(&self, path: &Path, retries: &mut u32) -> Result<()> {
         if self.options.dry_run {
             println!("Would delete: {}", path.display());
             return Ok(());
         }
         
         if self.options.create_backup {
-            self.create_backup(path)?;
+            self.create_backup(path, retries)?;
         }
         
-        fs::remove_file(path)
+        self.with_retry(retries, || self.fs.remove_file(path))
             .with_context(|| format!("Failed to delete: {}", path.display()))?;
         
         Ok(())
@@ -180,3 +607,258 @@ mod filetime {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operations::MemoryFileSystem;
+    use std::fs;
+    
+    #[test]
+    fn test_retry_only_transient_errors() {
+        let engine = SyncEngine::new(SyncOptions {
+            retry_backoff: Duration::ZERO,
+            ..SyncOptions::default()
+        });
+        
+        let mut failures = 2;
+        let mut retries = 0;
+        let result = engine.with_retry(&mut retries, || {
+            if failures > 0 {
+                failures -= 1;
+                Err(io::Error::from(io::ErrorKind::ResourceBusy))
+            } else {
+                Ok(())
+            }
+        });
+        assert!(result.is_ok());
+        assert_eq!(retries, 2);
+        
+        let mut retries = 0;
+        let result: io::Result<()> = engine.with_retry(&mut retries, || Err(io::ErrorKind::NotFound.into()));
+        assert_eq!(retries, 0);
+        let error = anyhow::Error::from(result.unwrap_err()).context("Failed to copy");
+        assert_eq!(FailureKind::of(&error), FailureKind::Permanent);
+        
+        let mut retries = 0;
+        let result: io::Result<()> = engine.with_retry(&mut retries, || Err(io::ErrorKind::ResourceBusy.into()));
+        assert_eq!(retries, 3);
+        let error = anyhow::Error::from(result.unwrap_err()).context("Failed to copy");
+        assert_eq!(FailureKind::of(&error), FailureKind::Transient);
+    }
+    
+    #[test]
+    fn test_parallel_sync_keeps_input_order() {
+        let dir = std::env::temp_dir().join(format!("sync-manager-parallel-{}", std::process::id()));
+        let diffs: Vec<DiffEntry> = (0..40)
+            .map(|i| {
+                let path = PathBuf::from(format!("d{}/nested/f{}.md", i % 5, i));
+                let source_path = dir.join("src").join(&path);
+                fs::create_dir_all(source_path.parent().unwrap()).unwrap();
+                fs::write(&source_path, i.to_string()).unwrap();
+                DiffEntry {
+                    destination_path: dir.join("dest").join(&path),
+                    source_path,
+                    status: FileStatus::Added,
+                    ..DiffEntry::for_test(path, "")
+                }
+            })
+            .collect();
+        
+        let engine = SyncEngine::new(SyncOptions {
+            create_backup: false,
+            workers: 4,
+            ..SyncOptions::default()
+        });
+        let result = engine.sync_files(&diffs);
+        
+        assert_eq!((result.synced, result.failed), (40, 0));
+        let paths: Vec<_> = result.outcomes.iter().map(|outcome| &outcome.path).collect();
+        assert_eq!(paths, diffs.iter().map(|diff| &diff.path).collect::<Vec<_>>());
+        assert_eq!(fs::read_to_string(&diffs[17].destination_path).unwrap(), "17");
+        
+        fs::remove_dir_all(&dir).ok();
+    }
+    
+    #[test]
+    fn test_cancelled_sync_starts_no_more_files() {
+        let dir = std::env::temp_dir().join(format!("sync-manager-cancel-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("a.md"), "a").unwrap();
+        let diff = DiffEntry {
+            source_path: dir.join("a.md"),
+            destination_path: dir.join("dest/a.md"),
+            status: FileStatus::Added,
+            size: 1,
+            ..DiffEntry::for_test("a.md", "")
+        };
+        
+        let engine = SyncEngine::default();
+        engine.cancellation_token().cancel();
+        let result = engine.sync_files(&[diff]);
+        
+        assert!(result.cancelled);
+        assert!(result.outcomes.is_empty());
+        assert!(!dir.join("dest/a.md").exists());
+        
+        fs::remove_dir_all(&dir).ok();
+    }
+    
+    #[test]
+    fn test_hardlink_replaces_destination() {
+        let dir = std::env::temp_dir().join(format!("sync-manager-hardlink-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let (source, dest) = (dir.join("source.md"), dir.join("dest.md"));
+        fs::write(&source, "new").unwrap();
+        fs::write(&dest, "old").unwrap();
+        
+        let engine = SyncEngine::new(SyncOptions {
+            create_backup: false,
+            copy_strategy: CopyStrategy::Hardlink,
+            ..SyncOptions::default()
+        });
+        let placed = engine.place_file(&source, &dest, true, &mut 0).unwrap();
+        assert_eq!(placed, (OutcomeStatus::Linked, 3));
+        
+        // Without links allowed, the file is cloned or copied instead
+        let (status, _) = engine.place_file(&source, &dest, false, &mut 0).unwrap();
+        assert!(matches!(status, OutcomeStatus::Copied | OutcomeStatus::Cloned));
+        assert_eq!(fs::read_to_string(&dest).unwrap(), "new");
+        
+        fs::remove_dir_all(&dir).ok();
+    }
+    
+    #[test]
+    fn test_partial_failures_on_memory_filesystem() {
+        let fs = Arc::new(MemoryFileSystem::new());
+        fs.add_file("/shared/locked.md", "locked");
+        fs.add_file("/shared/busy.md", "busy");
+        fs.add_file("/shared/changed.md", "new");
+        fs.add_file("/project/changed.md", "old");
+        fs.add_file("/project/gone/appeared.md", "written after the scan");
+        fs.fail("/project/locked.md", io::ErrorKind::PermissionDenied, u32::MAX);
+        fs.fail("/project/busy.md", io::ErrorKind::ResourceBusy, 2);
+        let entry = |name: &str, status: FileStatus| DiffEntry {
+            source_path: Path::new("/shared").join(name),
+            destination_path: Path::new("/project").join(name),
+            status,
+            is_dir: name == "gone",
+            ..DiffEntry::for_test(name, "")
+        };
+        
+        let engine = SyncEngine::new(SyncOptions {
+            retry_backoff: Duration::ZERO,
+            remove_directories: true,
+            workers: 1,
+            ..SyncOptions::default()
+        })
+        .with_filesystem(fs.clone());
+        let result = engine.sync_files(&[
+            entry("locked.md", FileStatus::Added),
+            entry("busy.md", FileStatus::Added),
+            entry("changed.md", FileStatus::Modified),
+            entry("gone", FileStatus::Deleted),
+        ]);
+        
+        assert_eq!((result.synced, result.failed), (2, 2));
+        let statuses: Vec<_> = result.outcomes.iter().map(|outcome| &outcome.status).collect();
+        assert!(matches!(statuses[0], OutcomeStatus::Failed { kind: FailureKind::Permanent, .. }));
+        assert_eq!((statuses[1], result.outcomes[1].retries), (&OutcomeStatus::Copied, 2));
+        assert!(matches!(statuses[3], OutcomeStatus::Failed { error, .. } if error.contains("contains files")));
+        assert_eq!(fs.contents("/project/changed.md").unwrap(), b"new");
+        assert_eq!(fs.contents("/project/changed.md.backup").unwrap(), b"old");
+        assert!(fs.contents("/project/gone/appeared.md").is_some());
+    }
+    
+    #[test]
+    fn test_project_to_shared_sync_keeps_tokens() {
+        let fs = Arc::new(MemoryFileSystem::new());
+        fs.add_file("/shared/Cargo.toml", "name = \"{{crate_name}}\"\nversion = \"0.1.0\"\n");
+        fs.add_file("/project/Cargo.toml", "name = \"alpha\"\nversion = \"0.2.0\"\n");
+        let entry = DiffEntry {
+            source_path: PathBuf::from("/project/Cargo.toml"),
+            destination_path: PathBuf::from("/shared/Cargo.toml"),
+            diff_type: crate::operations::DiffType::ProjectToShared,
+            transform: Some(FileTransform {
+                tokens: [("crate_name".to_string(), "alpha".to_string())].into(),
+                commands: Vec::new(),
+            }),
+            ..DiffEntry::for_test("Cargo.toml", "alpha")
+        };
+        
+        let engine = SyncEngine::new(SyncOptions {
+            create_backup: false,
+            ..SyncOptions::default()
+        })
+        .with_filesystem(fs.clone());
+        engine.sync_file(&entry).unwrap();
+        assert_eq!(
+            fs.contents("/shared/Cargo.toml").unwrap(),
+            b"name = \"{{crate_name}}\"\nversion = \"0.2.0\"\n"
+        );
+    }
+    
+    #[cfg(unix)]
+    #[test]
+    fn test_commands_run_only_on_files_written_from_shared() {
+        use crate::core::project_config::TransformRule;
+        use crate::operations::{DiffEngine, DiffType, TransformPipeline};
+        
+        let dir = std::env::temp_dir().join(format!("sync-manager-commands-{}", std::process::id()));
+        let (shared, project) = (dir.join("shared"), dir.join("project"));
+        fs::create_dir_all(&shared).unwrap();
+        fs::create_dir_all(&project).unwrap();
+        fs::write(shared.join("notes.txt"), "a\nb\n").unwrap();
+        let pipeline = TransformPipeline::new(vec![TransformRule {
+            files: "*.txt".to_string(),
+            tokens: Default::default(),
+            commands: vec!["sed -i s/^/>/".to_string()],
+        }]);
+        let scan = |diff_type| {
+            let (source, dest) = if diff_type == DiffType::SharedToProject { (&shared, &project) } else { (&project, &shared) };
+            DiffEngine::new().compute_diff(source, dest, diff_type, &[], &[], &pipeline).unwrap()
+        };
+        let engine = SyncEngine::new(SyncOptions {
+            create_backup: false,
+            ..SyncOptions::default()
+        });
+        
+        engine.sync_files(&scan(DiffType::SharedToProject));
+        assert_eq!(fs::read_to_string(project.join("notes.txt")).unwrap(), ">a\n>b\n");
+        
+        // The scan compares the template as formatted, so the synced copy is unchanged
+        let rescan = scan(DiffType::SharedToProject);
+        assert!(rescan.is_empty(), "{:?}", rescan);
+        assert_eq!(fs::read_dir(&project).unwrap().count(), 1);
+        
+        // Writing the shared copy runs no commands on it
+        engine.sync_files(&scan(DiffType::ProjectToShared));
+        assert_eq!(fs::read_to_string(shared.join("notes.txt")).unwrap(), ">a\n>b\n");
+        
+        fs::remove_dir_all(&dir).ok();
+    }
+    
+    #[test]
+    fn test_sync_keeps_destination_ignore_regions() {
+        let fs = Arc::new(MemoryFileSystem::new());
+        fs.add_file("/shared/header.rs", "// sync-ignore-start\n// shared\n// sync-ignore-end\nfn new() {}\n");
+        fs.add_file("/project/header.rs", "// sync-ignore-start\n// alpha\n// sync-ignore-end\nfn old() {}\n");
+        let entry = DiffEntry {
+            source_path: PathBuf::from("/shared/header.rs"),
+            destination_path: PathBuf::from("/project/header.rs"),
+            ..DiffEntry::for_test("header.rs", "")
+        };
+        
+        let engine = SyncEngine::new(SyncOptions {
+            create_backup: false,
+            copy_strategy: CopyStrategy::Hardlink,
+            ..SyncOptions::default()
+        })
+        .with_filesystem(fs.clone());
+        engine.sync_file(&entry).unwrap();
+        assert_eq!(
+            fs.contents("/project/header.rs").unwrap(),
+            b"// sync-ignore-start\n// alpha\n// sync-ignore-end\nfn new() {}\n"
+        );
+    }
+}
@@ -0,0 +1,257 @@
+// Transform Pipeline
+// Per-file rewrites applied to shared templates when they are synced into a project
+//
+// Shared files may contain `{{token}}` placeholders (project name, crate name, ...). Rules in
+// a mapping select files by glob and supply token values; the substituted text is what gets
+// written to the project and what the diff compares, so token-only differences don't show up.
+// Syncing a project copy back restores the placeholders of the shared template.
+// Rules can also name commands (formatters) that are run on each file after it is written.
+
+use anyhow::{bail, Context, Result};
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::path::Path;
+use std::process::Command;
+
+use super::filesystem::FileSystem;
+use crate::core::project_config::TransformRule;
+use crate::utilities::matches_pattern;
+use tui_components::{align_lines, LineAlignment};
+
+/// Transforms that apply to a single file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileTransform {
+    /// Token name -> replacement value
+    pub tokens: BTreeMap<String, String>,
+    /// Commands run on the file after it is written, in order
+    pub commands: Vec<String>,
+}
+
+impl FileTransform {
+    /// Substitute the tokens into `content`
+    pub fn render(&self, content: &str) -> String {
+        substitute_tokens(content, &self.tokens)
+    }
+    
+    /// Turn `edited`, a rendered copy of `template` edited in a project, back into a template
+    /// Lines that still render the same keep the template's text; changed lines paired with a
+    /// template line get the placeholders of that line back where its token values appear
+    pub fn retemplate(&self, template: &str, edited: &str) -> String {
+        let template_lines: Vec<&str> = template.split_inclusive('\n').collect();
+        let rendered: Vec<String> = template_lines.iter().map(|line| self.render(line)).collect();
+        let edited_lines: Vec<String> = edited.split_inclusive('\n').map(str::to_string).collect();
+        
+        let mut result = String::with_capacity(edited.len());
+        for entry in align_lines(&rendered, &edited_lines) {
+            match entry {
+                LineAlignment::Both(t, e) if rendered[t] == edited_lines[e] => result.push_str(template_lines[t]),
+                LineAlignment::Both(t, e) => result.push_str(&self.restore_placeholders(template_lines[t], &edited_lines[e])),
+                LineAlignment::DestOnly(e) => result.push_str(&edited_lines[e]),
+                LineAlignment::SourceOnly(_) => {}
+            }
+        }
+        result
+    }
+    
+    /// `line` with the values of the tokens used in `template_line` replaced by their placeholders
+    fn restore_placeholders(&self, template_line: &str, line: &str) -> String {
+        let mut line = line.to_string();
+        let mut rest = template_line;
+        while let Some(start) = rest.find("{{") {
+            let Some(len) = rest[start + 2..].find("}}") else {
+                break;
+            };
+            let end = start + 2 + len + 2;
+            if let Some(value) = self.tokens.get(rest[start + 2..end - 2].trim()).filter(|value| !value.is_empty()) {
+                line = line.replace(value.as_str(), &rest[start..end]);
+            }
+            rest = &rest[end..];
+        }
+        line
+    }
+    
+    /// Run the commands on a written file, stopping at the first one that fails
+    /// Commands run in the file's directory so formatters pick up the local config
+    pub fn run_commands(&self, path: &Path) -> Result<()> {
+        for command in &self.commands {
+            let mut args = command_args(command, path).into_iter();
+            let Some(program) = args.next() else {
+                continue;
+            };
+            
+            let mut process = Command::new(&program);
+            process.args(args);
+            if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+                process.current_dir(dir);
+            }
+            let output = process
+                .output()
+                .with_context(|| format!("Failed to run `{}`", command))?;
+            
+            if !output.status.success() {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                let reason = stderr.lines().find(|line| !line.trim().is_empty()).unwrap_or_default();
+                bail!("`{}` failed ({}): {}", command, output.status, reason.trim());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Split a command into program and arguments; `{file}` is replaced by the path,
+/// and the path is appended if the command doesn't mention it
+fn command_args(command: &str, path: &Path) -> Vec<OsString> {
+    let mut args: Vec<OsString> = Vec::new();
+    let mut has_file = false;
+    for word in command.split_whitespace() {
+        if word == "{file}" {
+            args.push(path.as_os_str().to_os_string());
+            has_file = true;
+        } else {
+            args.push(word.into());
+        }
+    }
+    
+    if !has_file && !args.is_empty() {
+        args.push(path.as_os_str().to_os_string());
+    }
+    args
+}
+
+/// Transform rules of one mapping plus built-in token values
+#[derive(Debug, Clone, Default)]
+pub struct TransformPipeline {
+    rules: Vec<TransformRule>,
+    builtins: BTreeMap<String, String>,
+}
+
+impl TransformPipeline {
+    /// Create a pipeline from a mapping's rules
+    pub fn new(rules: Vec<TransformRule>) -> Self {
+        Self {
+            rules,
+            builtins: BTreeMap::new(),
+        }
+    }
+    
+    /// Provide a token value to every matching rule (rules can override it)
+    pub fn with_token(mut self, name: &str, value: &str) -> Self {
+        self.builtins.insert(name.to_string(), value.to_string());
+        self
+    }
+    
+    /// Transform for a file (path relative to the mapping), or None if no rule matches it
+    /// Later rules override token values of earlier ones; commands of all rules run in order
+    pub fn for_file(&self, relative_path: &Path) -> Option<FileTransform> {
+        let mut matching = self
+            .rules
+            .iter()
+            .filter(|rule| matches_pattern(relative_path, &rule.files))
+            .peekable();
+        matching.peek()?;
+        
+        let mut transform = FileTransform {
+            tokens: self.builtins.clone(),
+            commands: Vec::new(),
+        };
+        for rule in matching {
+            transform.tokens.extend(rule.tokens.iter().map(|(name, value)| (name.clone(), value.clone())));
+            transform.commands.extend(rule.commands.iter().cloned());
+        }
+        Some(transform)
+    }
+}
+
+/// Replace `{{name}}` (whitespace inside the braces allowed) with the token's value
+/// Unknown tokens are left untouched
+pub fn substitute_tokens(content: &str, tokens: &BTreeMap<String, String>) -> String {
+    let mut result = String::with_capacity(content.len());
+    let mut rest = content;
+    
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        let end = start + 2 + len + 2;
+        result.push_str(&rest[..start]);
+        match tokens.get(rest[start + 2..end - 2].trim()) {
+            Some(value) => result.push_str(value),
+            None => result.push_str(&rest[start..end]),
+        }
+        rest = &rest[end..];
+    }
+    
+    result.push_str(rest);
+    result
+}
+
+/// Read a text file, applying the transform if there is one
+/// Returns None if the file is missing or isn't valid UTF-8
+pub fn read_transformed(fs: &dyn FileSystem, path: &Path, transform: Option<&FileTransform>) -> Option<String> {
+    let content = fs.read_to_string(path).ok()?;
+    Some(match transform {
+        Some(transform) => transform.render(&content),
+        None => content,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn rule(files: &str, tokens: &[(&str, &str)]) -> TransformRule {
+        TransformRule {
+            files: files.to_string(),
+            tokens: tokens.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+            commands: Vec::new(),
+        }
+    }
+    
+    #[test]
+    fn test_substitute_tokens() {
+        let tokens: BTreeMap<String, String> = [("crate_name".to_string(), "sync_manager".to_string())].into();
+        assert_eq!(
+            substitute_tokens("use {{crate_name}}::App; {{ crate_name }} {{other}} {{", &tokens),
+            "use sync_manager::App; sync_manager {{other}} {{"
+        );
+    }
+    
+    #[test]
+    fn test_rules_match_by_glob_and_override_builtins() {
+        let pipeline = TransformPipeline::new(vec![
+            rule("*.md", &[("title", "Docs")]),
+            rule("readme*", &[("project_name", "Renamed")]),
+        ])
+        .with_token("project_name", "sync-manager");
+        
+        assert_eq!(pipeline.for_file(Path::new("src/main.rs")), None);
+        
+        let guide = pipeline.for_file(Path::new("guide.md")).unwrap();
+        assert_eq!(guide.render("{{title}}: {{project_name}}"), "Docs: sync-manager");
+        
+        let readme = pipeline.for_file(Path::new("README.md")).unwrap();
+        assert_eq!(readme.render("{{title}}: {{project_name}}"), "Docs: Renamed");
+    }
+    
+    #[test]
+    fn test_retemplate_keeps_placeholders() {
+        let transform = FileTransform {
+            tokens: [("crate_name".to_string(), "alpha".to_string())].into(),
+            commands: Vec::new(),
+        };
+        let template = "[package]\nname = \"{{crate_name}}\"\nversion = \"0.1.0\"\n# {{ crate_name }} docs\n";
+        let edited = "[package]\nname = \"alpha\"\nversion = \"0.2.0\"\nedition = \"2021\"\n# alpha docs for alpha\n";
+        assert_eq!(
+            transform.retemplate(template, edited),
+            "[package]\nname = \"{{crate_name}}\"\nversion = \"0.2.0\"\nedition = \"2021\"\n# {{ crate_name }} docs for {{ crate_name }}\n"
+        );
+    }    
+    #[test]
+    fn test_command_args_place_the_file() {
+        let path = Path::new("src/lib.rs");
+        let args = |command| command_args(command, path);
+        assert_eq!(args("rustfmt --edition 2021"), ["rustfmt", "--edition", "2021", "src/lib.rs"]);
+        assert_eq!(args("prettier --write {file} --log-level warn"), ["prettier", "--write", "src/lib.rs", "--log-level", "warn"]);
+        assert!(args("  ").is_empty());
+    }
+}
@@ -0,0 +1,111 @@
+// Watchdog
+// Tracks the path a scan or sync is working on, so the UI can notice when it stops making
+// progress (e.g. a hanging network mount) and skip that path
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Progress heartbeat and skip requests shared by an operation and the UI
+#[derive(Debug)]
+pub struct Watchdog {
+    state: Mutex<WatchState>,
+}
+
+#[derive(Debug)]
+struct WatchState {
+    /// Path worked on since the last beat
+    path: Option<PathBuf>,
+    last_beat: Instant,
+    /// Paths (with everything under them) to pass over
+    skipped: Vec<PathBuf>,
+    /// Beats so far (for a scan, the files and directories it walked)
+    walked: usize,
+}
+
+/// An operation stuck on one path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stall {
+    pub path: PathBuf,
+    pub elapsed: Duration,
+}
+
+impl Default for Watchdog {
+    fn default() -> Self {
+        Self {
+            state: Mutex::new(WatchState {
+                path: None,
+                last_beat: Instant::now(),
+                skipped: Vec::new(),
+                walked: 0,
+            }),
+        }
+    }
+}
+
+impl Watchdog {
+    fn state(&self) -> std::sync::MutexGuard<'_, WatchState> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+    
+    /// Record progress on `path`
+    pub fn beat(&self, path: &Path) {
+        let mut state = self.state();
+        if state.path.as_deref() != Some(path) {
+            state.path = Some(path.to_path_buf());
+        }
+        state.last_beat = Instant::now();
+        state.walked += 1;
+    }
+    
+    /// Number of beats so far
+    pub fn walked(&self) -> usize {
+        self.state().walked
+    }
+    
+    /// The path worked on, if there was no progress for `after`
+    pub fn stalled(&self, after: Duration) -> Option<Stall> {
+        let state = self.state();
+        let elapsed = state.last_beat.elapsed();
+        let path = state.path.clone().filter(|_| elapsed >= after)?;
+        Some(Stall { path, elapsed })
+    }
+    
+    /// Keep waiting: restart the stall clock
+    pub fn snooze(&self) {
+        self.state().last_beat = Instant::now();
+    }
+    
+    /// Pass over `path` and everything under it from now on, and restart the stall clock
+    pub fn skip(&self, path: &Path) {
+        let mut state = self.state();
+        state.skipped.push(path.to_path_buf());
+        state.last_beat = Instant::now();
+    }
+    
+    /// Whether `path` is (under) a skipped path
+    pub fn is_skipped(&self, path: &Path) -> bool {
+        self.state().skipped.iter().any(|skipped| path.starts_with(skipped))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stall_and_skip() {
+        let watchdog = Watchdog::default();
+        assert_eq!(watchdog.stalled(Duration::ZERO), None);
+
+        watchdog.beat(Path::new("/mnt/share/a.md"));
+        let stall = watchdog.stalled(Duration::ZERO).unwrap();
+        assert_eq!(stall.path, PathBuf::from("/mnt/share/a.md"));
+        assert_eq!(watchdog.walked(), 1);
+        assert_eq!(watchdog.stalled(Duration::from_secs(60)), None);
+
+        watchdog.skip(Path::new("/mnt/share"));
+        assert!(watchdog.is_skipped(Path::new("/mnt/share/b/c.md")));
+        assert!(!watchdog.is_skipped(Path::new("/mnt/shared.md")));
+    }
+}
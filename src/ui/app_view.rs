@@ -3,15 +3,37 @@
 
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
-    widgets::{Block, Borders, Paragraph},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
     Frame,
 };
 
-use crate::core::{App, ViewMode};
-use super::{render_diff_list, render_side_by_side, Styles};
+use tui_components::{apply_dimming, RectRegistry, TabBar, TabBarAlignment, TabBarItem, TabBarPosition, TabBarStyle};
+
+use crate::core::{App, AppTab, FocusTarget};
+use crate::operations::{FileMetadata, GitOps};
+use crate::utilities::{format_size, format_timestamp};
+use super::diff_list::{HWND_PROJECT_TO_SHARED_LIST, HWND_SHARED_TO_PROJECT_LIST};
+use super::{
+    render_diff_list, render_diff_view, render_settings, render_setup_wizard, render_side_by_side, render_snapshots, render_stall_prompt,
+    render_startup_scan, render_sync_progress, render_sync_report, Styles,
+};
 
 /// Render the entire application
-pub fn render_app(f: &mut Frame, app: &App) {
+/// Open dialogs are registered as overlay layers so everything behind them is dimmed
+pub fn render_app(f: &mut Frame, app: &App, registry: &mut RectRegistry) {
+    registry.begin_frame();
+    
+    // The dashboard waits for the lists of the first scan when there are none to show yet
+    if app.is_starting_up() {
+        render_startup_scan(f, app, f.area());
+        render_stall_prompt(f, app, f.area(), registry);
+        app.popups.render_with_registry(f, f.area(), registry);
+        apply_dimming(f, registry);
+        app.toasts.render(f, f.area());
+        return;
+    }
+    
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -21,30 +43,121 @@ pub fn render_app(f: &mut Frame, app: &App) {
         ])
         .split(f.area());
     
-    render_header(f, chunks[0]);
-    render_main_content(f, app, chunks[1]);
+    render_header(f, app, chunks[0]);
+    render_main_content(f, app, chunks[1], registry);
     render_footer(f, app, chunks[2]);
+    if let Some(menu) = &app.entry_menu {
+        menu.render(f, chunks[1], registry);
+    }
+    render_sync_report(f, app, chunks[1], registry);
+    render_sync_progress(f, app, chunks[1], registry);
+    render_stall_prompt(f, app, chunks[1], registry);
+    app.popups.render_with_registry(f, f.area(), registry);
+    apply_dimming(f, registry);
+    render_profiler(f, app, chunks[1]);
+    app.toasts.render(f, chunks[1]);
 }
 
-/// Render the header bar
-fn render_header(f: &mut Frame, area: Rect) {
-    let header = Paragraph::new("Sync Manager TUI")
+/// Render the frame timing overlay in the top right corner of `area` (F12)
+fn render_profiler(f: &mut Frame, app: &App, area: Rect) {
+    if !app.frame_stats.enabled {
+        return;
+    }
+    let lines: Vec<Line> = app.frame_stats.lines().into_iter().map(Line::from).collect();
+    let width = lines.iter().map(Line::width).max().unwrap_or(0) as u16 + 2;
+    let height = lines.len() as u16 + 2;
+    let overlay = Rect {
+        x: area.right().saturating_sub(width),
+        y: area.y,
+        width: width.min(area.width),
+        height: height.min(area.height),
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Styles::border_focused())
+        .title(" Frame Times ");
+    f.render_widget(Clear, overlay);
+    f.render_widget(Paragraph::new(lines).style(Styles::status_unchanged()).block(block), overlay);
+}
+
+/// Render the header bar with the tabs on its bottom border
+fn render_header(f: &mut Frame, app: &App, area: Rect) {
+    let mut title = vec![Span::styled("Sync Manager TUI", Styles::header())];
+    if let Some(group) = &app.active_group {
+        // A group names itself, followed by the icons of its projects
+        title.push(Span::raw(" "));
+        title.push(Span::styled(format!(" {} ", group), Styles::group_badge()));
+        for project_name in app.listed_projects() {
+            let project = app.project_identity(&project_name);
+            title.push(Span::raw(" "));
+            title.push(Span::styled(project.icon, Styles::project_icon(&project.color)));
+        }
+    } else if app.project_config.is_some() {
+        let project_name = app.project_name();
+        let project = app.project_identity(&project_name);
+        title.push(Span::raw(" "));
+        title.push(Span::styled(format!(" {} {} ", project.icon, project_name), Styles::project_badge(&project.color)));
+    }
+    if let Some(profile) = app.project_config.as_ref().and_then(|config| config.active_profile.as_ref()) {
+        title.push(Span::raw(" "));
+        title.push(Span::styled(format!(" {} ", profile), Styles::profile_badge()));
+    }
+    if app.config.defaults.read_only {
+        title.push(Span::raw(" "));
+        title.push(Span::styled(" READ-ONLY ", Styles::read_only_badge()));
+    }
+    if app.diffs_stale() {
+        let badge = if app.scan_task.is_some() { " SCANNING " } else { " STALE " };
+        title.push(Span::raw(" "));
+        title.push(Span::styled(badge, Styles::stale_badge()));
+    }
+    let header = Paragraph::new(Line::from(title))
         .style(Styles::header())
         .block(Block::default().borders(Borders::ALL));
     f.render_widget(header, area);
+    
+    if app.setup_wizard.is_some() {
+        return;
+    }
+    // The Sync tab carries the icon of the project it syncs, or the name of the group
+    let project_icon = match &app.active_group {
+        Some(group) => group.clone(),
+        None => app.project_identity(&app.project_name()).icon,
+    };
+    let items = AppTab::ALL
+        .iter()
+        .enumerate()
+        .map(|(idx, tab)| TabBarItem {
+            name: format!("{} {}", idx + 1, tab.title()),
+            active: *tab == app.active_tab,
+            state: None,
+            badge: (*tab == AppTab::Sync && app.project_config.is_some()).then(|| project_icon.clone()),
+            disabled: false,
+        })
+        .collect();
+    TabBar::new(items, TabBarStyle::Boxed, TabBarAlignment::Right)
+        .with_position(TabBarPosition::BottomOf(area))
+        .render(f);
 }
 
 /// Render the main content area
-fn render_main_content(f: &mut Frame, app: &App, area: Rect) {
-    if app.show_side_by_side {
+fn render_main_content(f: &mut Frame, app: &App, area: Rect, registry: &mut RectRegistry) {
+    if let Some(wizard) = &app.setup_wizard {
+        render_setup_wizard(f, wizard, area);
+    } else if app.active_tab == AppTab::Snapshots {
+        render_snapshots(f, app, area);
+    } else if app.active_tab == AppTab::Settings {
+        render_settings(f, app, area);
+    } else if app.show_side_by_side {
         render_side_by_side(f, app, area);
     } else {
-        render_split_view(f, app, area);
+        render_split_view(f, app, area, registry);
     }
 }
 
 /// Render the split view (diff lists + diff view)
-fn render_split_view(f: &mut Frame, app: &App, area: Rect) {
+/// The list rects are registered for mouse hit-testing (right click opens the context menu)
+fn render_split_view(f: &mut Frame, app: &App, area: Rect, registry: &mut RectRegistry) {
     // Split into left (lists) and right (diff view)
     let main_chunks = Layout::default()
         .direction(Direction::Horizontal)
@@ -57,56 +170,148 @@ fn render_split_view(f: &mut Frame, app: &App, area: Rect) {
         .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
         .split(main_chunks[0]);
     
+    registry.register(Some(HWND_SHARED_TO_PROJECT_LIST), left_chunks[0]);
+    registry.register(Some(HWND_PROJECT_TO_SHARED_LIST), left_chunks[1]);
+    
     // Top list: shared -> project
     render_diff_list(
         f,
+        app,
+        &app.shared_to_project_list,
         &app.shared_to_project_diffs,
-        app.shared_to_project_index,
-        app.view_mode == ViewMode::SharedToProject,
+        app.focus.is_focused(FocusTarget::SharedToProjectList),
         left_chunks[0],
-        "_shared → .project",
     );
     
     // Bottom list: project -> shared
     render_diff_list(
         f,
+        app,
+        &app.project_to_shared_list,
         &app.project_to_shared_diffs,
-        app.project_to_shared_index,
-        app.view_mode == ViewMode::ProjectToShared,
+        app.focus.is_focused(FocusTarget::ProjectToSharedList),
         left_chunks[1],
-        ".project → _shared",
     );
     
-    // Right side: Info panel (diff view disabled)
+    if app.show_metadata {
+        render_metadata_panel(f, app, main_chunks[1]);
+        return;
+    }
+    
+    // Right side: the selected file's hunks, else what to do
+    if let (Some(diff), Some(_)) = (app.selected_diff(), &app.cached_diff_content) {
+        render_diff_view(f, diff, app, main_chunks[1]);
+        return;
+    }
     let info_text = if let Some(diff) = app.selected_diff() {
+        let note = app
+            .annotations
+            .get(diff)
+            .map(|note| format!("\n\n✎ {}\n(N edits the note)", note))
+            .unwrap_or_default();
         format!(
-            "File: {}\nStatus: {:?}\n\nPress Enter/Space to view\nside-by-side diff",
+            "File: {}\nStatus: {:?}{}\n\nPress Enter/Space to view\nside-by-side diff",
             diff.path.display(),
-            diff.status
+            diff.status,
+            note
         )
     } else {
         "No file selected\n\nUse Tab to switch between views\n↑/↓ to navigate\nEnter/Space: Side-by-Side diff".to_string()
     };
     
+    // Notes wrap rather than run off the panel
     let info_panel = Paragraph::new(info_text)
+        .wrap(Wrap { trim: false })
         .block(Block::default().borders(Borders::ALL).title("File Info"));
     f.render_widget(info_panel, main_chunks[1]);
 }
 
+/// Render the metadata of both files of the selected entry, one row per property
+/// Rows whose values differ are highlighted, pointing at why the file shows as modified
+fn render_metadata_panel(f: &mut Frame, app: &App, area: Rect) {
+    let block = Block::default().borders(Borders::ALL).title("File Metadata (i: hide)");
+    let (Some(diff), Some((_, metadata))) = (app.selected_diff(), &app.cached_metadata) else {
+        f.render_widget(Paragraph::new("No file selected").block(block), area);
+        return;
+    };
+    
+    let sides = [&metadata.source, &metadata.destination];
+    let values = |value: &dyn Fn(&FileMetadata) -> String| sides.map(|side| side.as_ref().map(value));
+    let rows = [
+        ("Size", values(&|m| format!("{} ({} B)", format_size(m.size), m.size))),
+        ("Modified", values(&|m| m.modified.map(format_timestamp).unwrap_or_else(|| "unknown".to_string()))),
+        ("Permissions", values(&|m| m.permissions.clone())),
+        ("Hash", values(&|m| m.hash.clone().unwrap_or_else(|| "unreadable".to_string()))),
+        ("Encoding", values(&|m| m.encoding.map(|e| e.label().to_string()).unwrap_or_default())),
+        ("Line endings", values(&|m| m.line_endings.map(|l| l.label()).unwrap_or_default())),
+    ];
+    
+    let mut lines = vec![
+        Line::from(Span::styled(diff.path.display().to_string(), Styles::header())),
+        Line::default(),
+    ];
+    for (label, [source, destination]) in rows {
+        let style = if source != destination { Styles::status_modified() } else { Styles::list_normal() };
+        let missing = || "—".to_string();
+        lines.push(Line::from(Span::styled(label, Styles::title_unfocused())));
+        lines.push(Line::from(Span::styled(format!("  source       {}", source.unwrap_or_else(missing)), style)));
+        lines.push(Line::from(Span::styled(format!("  destination  {}", destination.unwrap_or_else(missing)), style)));
+    }
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
 /// Render the footer bar
 fn render_footer(f: &mut Frame, app: &App, area: Rect) {
-    let help_text = if app.show_side_by_side {
-        if app.fold_unchanged {
-            "q: Quit | Esc: Back | ↑/↓: Scroll | F: Unfold | PgUp/PgDn: Scroll | Mouse Wheel: Scroll"
+    let help_text = if app.entry_menu.is_some() {
+        "↑/↓: Choose | Enter/Click: Run | Letter: Shortcut | Esc: Close Menu"
+    } else if app.popups.is_open() {
+        "Enter: Confirm | ←/→ or y/n: Choose | Esc: Back One Dialog"
+    } else if app.stall_prompt.is_some() {
+        "w/Esc: Keep Waiting | s: Skip Path | c: Cancel"
+    } else if app.sync_task.is_some() {
+        "Esc: Cancel Sync (files in flight finish first)"
+    } else if app.sync_report.is_some() {
+        "↑/↓: Scroll | PgUp/PgDn/Home/End: Page | r: Retry Failed | Enter/Esc: Close"
+    } else if app.setup_wizard.is_some() {
+        "Esc: Quit | Tab/Shift+Tab: Next/Prev Field | Ctrl+O: Browse Path | Enter: Save Config"
+    } else if app.active_tab == AppTab::Settings {
+        "↑/↓: Field | Space/←/→: Change | Enter: Save | Esc: Discard And Return | 1/2: Tabs"
+    } else if app.active_tab == AppTab::Snapshots {
+        "q: Quit | Esc/1: Sync Tab | ↑/↓: Navigate | Enter: Compare With Current | n: New Snapshot | d: Delete | r: Refresh"
+    } else if app.review.is_some() && !app.show_side_by_side {
+        "a: Accept | r: Reject | d: Defer | ↑/↓: Navigate | Enter/Space: Side-by-Side | R: Finish Review (sync accepted) | Esc: Discard Review"
+    } else if app.show_side_by_side {
+        if app.current_folds().fold_unchanged {
+            "q: Quit | Esc: Back | ↑/↓: Scroll | Enter: Expand Fold | F: Unfold All | +/-: Context | PgUp/PgDn: Scroll | Mouse Wheel: Scroll"
         } else {
             "q: Quit | Esc: Back | ↑/↓: Scroll | F: Fold | PgUp/PgDn: Scroll | Mouse Wheel: Scroll"
         }
     } else {
-        "q: Quit | Tab: Switch View | ↑/↓: Navigate | Enter/Space: Side-by-Side | PgUp/PgDn: Scroll | r: Refresh"
+        "q: Quit | Tab: Focus List | ↑/↓: Navigate | Enter/Space: Side-by-Side | PgUp/PgDn/Home/End: Page | o: Sort | g: Group | i: Metadata | r: Refresh | s/S: Sync | m: Menu | e: Edit | c: Copy Path | x: Export | 2: Snapshots | 3: Settings"
     };
     
+    // "N of M" for the focused diff list, on the footer's right border
+    let mut block = Block::default().borders(Borders::ALL);
+    let on_lists = app.setup_wizard.is_none() && app.active_tab == AppTab::Sync && !app.show_side_by_side;
+    if let Some((position, total)) = app.list_position().filter(|_| on_lists) {
+        block = block.title_bottom(Line::from(format!(" {} of {} ", position, total)).right_aligned());
+    }
+    if let Some((reviewed, total)) = app.review_progress() {
+        let progress = Span::styled(format!(" {}/{} reviewed ", reviewed, total), Styles::profile_badge());
+        block = block.title_bottom(Line::from(progress).centered());
+    }
+    if !GitOps::is_available() {
+        let text = if GitOps::can_query() {
+            " git not found: diffs built in "
+        } else {
+            " git not found: history off, diffs built in "
+        };
+        let note = Span::styled(text, Styles::missing_tool_note());
+        block = block.title_bottom(Line::from(note).left_aligned());
+    }
+    
     let footer = Paragraph::new(help_text)
         .style(Styles::footer())
-        .block(Block::default().borders(Borders::ALL));
+        .block(block);
     f.render_widget(footer, area);
 }
@@ -1,72 +1,318 @@
 // Diff List Component
-// Renders a list of diff entries
+// Renders a list of diff entries on the shared ListPanel
 
-use ratatui::{
-    layout::Rect,
-    text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, ListState},
-    Frame,
+use crossterm::event::{Event, MouseButton, MouseEventKind};
+use ratatui::{layout::Rect, Frame};
+use std::time::SystemTime;
+use tui_components::{
+    ColumnWidth, ListCell, ListColumn, ListPanel, ListPanelStyles, ListRow, RectRegistry, SortDirection,
 };
 
+use crate::core::project_config::DirectionOverride;
+use crate::core::review::Verdict;
+use crate::core::{App, AppEvent, DiffSortOrder, FocusTarget, ProjectIdentity};
 use crate::operations::{DiffEntry, FileStatus};
+use crate::utilities::{format_age, format_size};
+use super::diff_view::preview_line_count;
+use super::input::{handle_entry_event, review_verdict, view_event};
 use super::Styles;
 
+/// Lines the peek pane scrolls per PgUp / PgDn
+const PREVIEW_PAGE: usize = 10;
+
+/// Registry handle names (HWND) of the two lists, registered each frame for mouse hit-testing
+pub const HWND_SHARED_TO_PROJECT_LIST: &str = "hwndSharedToProjectList";
+pub const HWND_PROJECT_TO_SHARED_LIST: &str = "hwndProjectToSharedList";
+
+/// Column indices
+const COLUMN_PATH: usize = 3;
+const COLUMN_SIZE: usize = 6;
+const COLUMN_MODIFIED: usize = 7;
+
+/// Sync the diff list panels with app state (title, columns, styles, sort indicator)
+/// Selection and scroll state already on the panels are kept
+pub fn update_diff_lists(app: &mut App) {
+    let sort = match app.sort_order {
+        DiffSortOrder::PathAsc => (COLUMN_PATH, SortDirection::Ascending),
+        DiffSortOrder::PathDesc => (COLUMN_PATH, SortDirection::Descending),
+        DiffSortOrder::LargestFirst => (COLUMN_SIZE, SortDirection::Descending),
+        DiffSortOrder::RecentFirst => (COLUMN_MODIFIED, SortDirection::Descending),
+    };
+    
+    // The active filter (and its preset) follow the direction, e.g. "_shared → .project [rust: .rs size>10k]"
+    let filter = match (&app.active_preset, &app.list_filter) {
+        (Some(preset), Some(filter)) => format!(" [{}: {}]", preset, filter),
+        (Some(preset), None) => format!(" [{}]", preset),
+        (None, Some(filter)) => format!(" [{}]", filter),
+        (None, None) => String::new(),
+    };
+    let empty = if app.list_filter.is_none() { "No differences" } else { "No differences match the filter" };
+    configure_diff_list(&mut app.shared_to_project_list, &format!("_shared → .project{}", filter), empty, sort);
+    configure_diff_list(&mut app.project_to_shared_list, &format!(".project → _shared{}", filter), empty, sort);
+}
+
+fn configure_diff_list(list: &mut ListPanel, title: &str, empty: &str, sort: (usize, SortDirection)) {
+    title.clone_into(&mut list.title);
+    empty.clone_into(&mut list.empty_message);
+    if list.columns.is_empty() {
+        list.columns = vec![
+            ListColumn::new("", ColumnWidth::Fixed(1)),
+            ListColumn::new("", ColumnWidth::Fixed(1)),
+            ListColumn::new("", ColumnWidth::Fixed(1)),
+            ListColumn::new("Path", ColumnWidth::Fill),
+            ListColumn::new("+", ColumnWidth::Fixed(5)).align_right(),
+            ListColumn::new("−", ColumnWidth::Fixed(5)).align_right(),
+            ListColumn::new("Size", ColumnWidth::Fixed(7)).align_right(),
+            ListColumn::new("Age", ColumnWidth::Fixed(5)).align_right(),
+        ];
+        list.styles = ListPanelStyles {
+            border_focused: Styles::border_focused(),
+            border_unfocused: Styles::border_unfocused(),
+            title_focused: Styles::title_focused(),
+            title_unfocused: Styles::title_unfocused(),
+            selected_focused: Styles::list_selected_focused(),
+            selected_unfocused: Styles::list_selected_unfocused(),
+            ..ListPanelStyles::default()
+        };
+    }
+    list.sort = Some(sort);
+}
+
 /// Render a diff list component
+/// Only the rows inside the visible window are built, so huge result sets stay cheap per frame
 pub fn render_diff_list(
     f: &mut Frame,
+    app: &App,
+    list: &ListPanel,
     diffs: &[DiffEntry],
-    selected_index: usize,
     is_focused: bool,
     area: Rect,
-    title: &str,
 ) {
-    let items: Vec<ListItem> = diffs
-        .iter()
-        .enumerate()
-        .map(|(idx, diff)| {
-            let style = if idx == selected_index && is_focused {
-                Styles::list_selected_focused()
-            } else if idx == selected_index {
-                Styles::list_selected_unfocused()
-            } else {
-                Styles::list_normal()
-            };
-            
-            let (status_icon, status_style) = match diff.status {
-                FileStatus::Added => ("A", Styles::status_added()),
-                FileStatus::Modified => ("M", Styles::status_modified()),
-                FileStatus::Deleted => ("D", Styles::status_deleted()),
-                FileStatus::Untracked => ("?", Styles::status_untracked()),
-                FileStatus::Unchanged => (" ", Styles::status_unchanged()),
-            };
-            
-            ListItem::new(Line::from(vec![
-                Span::styled(format!("{} ", status_icon), status_style),
-                Span::styled(diff.path.display().to_string(), style),
-            ]))
-        })
-        .collect();
+    let now = SystemTime::now();
+    let review = app.review.as_ref();
+    let row = |idx: usize| {
+        let diff = &diffs[idx];
+        let verdict = review
+            .filter(|review| review.diff_type == diff.diff_type)
+            .and_then(|review| review.verdict(diff));
+        let noted = app.annotations.get(diff).is_some();
+        diff_row(diff, &app.project_identity(&diff.project), verdict, noted, now)
+    };
+    list.render(f, area, diffs.len(), row, is_focused);
+}
+
+/// Handle input for the diff lists of the Sync tab
+pub fn handle_list_event(app: &mut App, event: Event, registry: &RectRegistry) {
+    // A right click selects the row under the mouse and opens its context menu there
+    if let Event::Mouse(mouse) = event {
+        if mouse.kind == MouseEventKind::Down(MouseButton::Right) {
+            if let Some((list, index)) = list_row_at(app, registry, mouse.column, mouse.row) {
+                app.select_entry(list, index);
+                app.open_entry_menu((mouse.column, mouse.row));
+            }
+            return;
+        }
+    }
+    
+    // While reviewing, a / r / d give the selected entry a verdict (r and d rescan and
+    // delete snapshots otherwise)
+    if let Some(verdict) = review_verdict(app, &event) {
+        app.review_selected(verdict);
+        return;
+    }
+    
+    let Some(event) = view_event(app, event).and_then(|event| handle_entry_event(app, event)) else {
+        return;
+    };
+    
+    let peeking = app.cached_diff_content.is_some() && !app.show_metadata;
+    match event {
+        AppEvent::SelectPrevious => app.select_previous(),
+        AppEvent::SelectNext => app.select_next(),
+        // PgUp / PgDn scroll the peek pane while it shows a diff, Shift pages the list
+        AppEvent::PageUp if peeking => app.scroll_up(PREVIEW_PAGE),
+        AppEvent::PageDown if peeking => {
+            app.scroll_down(PREVIEW_PAGE);
+            app.diff_scroll_offset = app.diff_scroll_offset.min(preview_line_count(app).saturating_sub(1));
+        }
+        AppEvent::PageUp | AppEvent::ListPageUp => app.select_page_up(),
+        AppEvent::PageDown | AppEvent::ListPageDown => app.select_page_down(),
+        AppEvent::SelectFirst => app.select_first(),
+        AppEvent::SelectLast => app.select_last(),
+        AppEvent::ScrollUp(amount) => app.scroll_up(amount),
+        AppEvent::ScrollDown(amount) => app.scroll_down(amount),
+        AppEvent::ToggleSideBySide => app.toggle_side_by_side(),
+        AppEvent::CycleSortOrder => app.cycle_sort_order(),
+        AppEvent::FilterLists => app.request_list_filter(),
+        AppEvent::CycleFilterPreset => app.cycle_filter_preset(),
+        AppEvent::SaveFilterPreset => app.request_save_filter_preset(),
+        AppEvent::ToggleReview => app.toggle_review(),
+        AppEvent::ToggleGrouping => app.toggle_grouping(),
+        AppEvent::ToggleSection => app.toggle_section(),
+        AppEvent::CycleProjectGroup => app.cycle_project_group(),
+        AppEvent::ToggleMetadata => app.toggle_metadata(),
+        AppEvent::EntryMenu => app.open_entry_menu(selected_row_anchor(app, registry)),
+        AppEvent::FileHistory => app.open_file_history(selected_row_anchor(app, registry)),
+        AppEvent::ExplainChange => app.explain_selected(),
+        // Esc acknowledges a sticky error before quitting
+        AppEvent::Back if app.toasts.dismiss_sticky() => {}
+        AppEvent::Back if app.review.is_some() => app.discard_review(),
+        AppEvent::Back if app.scan_task.is_some() => app.cancel_scan(),
+        AppEvent::Back => app.quit(),
+        _ => {}
+    }
+}
+
+/// List and row under a screen cell
+fn list_row_at(app: &App, registry: &RectRegistry, column: u16, row: u16) -> Option<(FocusTarget, usize)> {
+    let lists = [
+        (HWND_SHARED_TO_PROJECT_LIST, FocusTarget::SharedToProjectList, &app.shared_to_project_list, app.shared_to_project_diffs.len()),
+        (HWND_PROJECT_TO_SHARED_LIST, FocusTarget::ProjectToSharedList, &app.project_to_shared_list, app.project_to_shared_diffs.len()),
+    ];
+    lists.into_iter().find_map(|(hwnd, target, list, count)| {
+        let area = Rect::from(registry.get_metrics_by_name(hwnd)?);
+        list.row_at(area, column, row, count).map(|index| (target, index))
+    })
+}
+
+/// Cell below the path of the selected row, where a key-opened context menu appears
+fn selected_row_anchor(app: &App, registry: &RectRegistry) -> (u16, u16) {
+    let (hwnd, list) = match app.focus.focused() {
+        Some(FocusTarget::ProjectToSharedList) => (HWND_PROJECT_TO_SHARED_LIST, &app.project_to_shared_list),
+        _ => (HWND_SHARED_TO_PROJECT_LIST, &app.shared_to_project_list),
+    };
+    let Some(area) = registry.get_metrics_by_name(hwnd).map(Rect::from) else {
+        return (0, 0);
+    };
+    let y = list.row_y(area, list.selected).unwrap_or(area.y);
+    (area.x + 6, y + 1)
+}
+
+/// Build the row for a single diff entry, marked with the icon of its project
+/// Under review, the path starts with the entry's verdict; a ✎ marks entries with a note
+fn diff_row(
+    diff: &DiffEntry,
+    project: &ProjectIdentity,
+    verdict: Option<Verdict>,
+    noted: bool,
+    now: SystemTime,
+) -> ListRow {
+    let (status_icon, status_style) = match diff.status {
+        FileStatus::Added => ("A", Styles::status_added()),
+        FileStatus::Modified => ("M", Styles::status_modified()),
+        FileStatus::Deleted => ("D", Styles::status_deleted()),
+        FileStatus::Untracked => ("?", Styles::status_untracked()),
+        FileStatus::Unchanged => (" ", Styles::status_unchanged()),
+    };
     
-    let title_style = if is_focused {
-        Styles::title_focused()
+    // Pinned files never sync; reverse files only sync project → shared (dimmed when blocked);
+    // files that look like secrets only sync after an override
+    let override_icon = match diff.direction_override {
+        _ if diff.secret.is_some() => "!",
+        Some(DirectionOverride::Pinned) => "⊘",
+        Some(DirectionOverride::Reverse) => "⇠",
+        None => " ",
+    };
+    let override_style = if diff.secret.is_some() {
+        Styles::status_secret()
+    } else if diff.is_blocked() {
+        Styles::status_unchanged()
     } else {
-        Styles::title_unfocused()
+        Styles::status_untracked()
     };
     
-    let border_style = if is_focused {
-        Styles::border_focused()
+    let age = diff.modified.map(|m| format_age(m, now)).unwrap_or_default();
+    let (added, removed) = stat_counts(diff);
+    // Directories end in a separator and have no size
+    let (mut path, size) = if diff.is_dir {
+        (format!("{}/", diff.path.display()), String::new())
     } else {
-        Styles::border_unfocused()
+        (diff.path.display().to_string(), format_size(diff.size))
+    };
+    let path_style = match verdict {
+        Some(Verdict::Accept) => Styles::status_added(),
+        Some(Verdict::Reject) => Styles::status_deleted(),
+        Some(Verdict::Defer) => Styles::status_unchanged(),
+        None => Styles::list_normal(),
+    };
+    if noted {
+        path = format!("✎ {}", path);
+    }
+    if let Some(verdict) = verdict {
+        let mark = match verdict {
+            Verdict::Accept => "✓",
+            Verdict::Reject => "✗",
+            Verdict::Defer => "…",
+        };
+        path = format!("{} {}", mark, path);
+    }
+    
+    ListRow::new(vec![
+        ListCell::styled(project.icon.clone(), Styles::project_icon(&project.color)),
+        ListCell::styled(status_icon, status_style),
+        ListCell::styled(override_icon, override_style),
+        ListCell::styled(path, path_style),
+        ListCell::styled(added, Styles::status_added()),
+        ListCell::styled(removed, Styles::status_deleted()),
+        ListCell::styled(size, Styles::status_unchanged()),
+        ListCell::styled(age, Styles::status_unchanged()),
+    ])
+}
+
+/// "+X" / "−Y" cells: lines the sync adds to and removes from the destination (blank if zero)
+fn stat_counts(diff: &DiffEntry) -> (String, String) {
+    let Some(stats) = diff.stats else {
+        return (String::new(), String::new());
     };
+    let count = |sign: char, n: usize| if n > 0 { format!("{}{}", sign, n) } else { String::new() };
+    (count('+', stats.source_changes()), count('−', stats.dest_changes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::PathBuf;
+    use tui_components::utilities::snapshot::{assert_snapshot, render_to_text};
+    use crate::operations::DiffStats;
     
-    let list = List::new(items).block(
-        Block::default()
-            .borders(Borders::ALL)
-            .border_style(border_style)
-            .title(Span::styled(title, title_style)),
-    );
+    fn entry(path: &str, status: FileStatus, size: u64, stats: Option<DiffStats>) -> DiffEntry {
+        DiffEntry {
+            source_path: PathBuf::from("_shared").join(path),
+            destination_path: PathBuf::from(".project").join(path),
+            status,
+            size,
+            stats,
+            ..DiffEntry::for_test(path, "")
+        }
+    }
     
-    let mut list_state = ListState::default();
-    list_state.select(Some(selected_index));
-    f.render_stateful_widget(list, area, &mut list_state);
+    #[test]
+    fn test_diff_list_snapshot() {
+        let root = std::env::temp_dir().join(format!("sync-manager-diff-list-{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        let mut app = App::open(root.clone()).unwrap();
+        update_diff_lists(&mut app);
+        
+        let stats = DiffStats { source_only: 3, dest_only: 1, modified: 2 };
+        let mut pinned = entry("config/settings.json", FileStatus::Modified, 2048, Some(stats));
+        pinned.direction_override = Some(DirectionOverride::Pinned);
+        let mut secret = entry(".env", FileStatus::Added, 120, None);
+        secret.secret = Some("looks like an API key".to_string());
+        let mut dir = entry("assets/empty", FileStatus::Added, 0, None);
+        dir.is_dir = true;
+        let diffs = vec![
+            entry("src/main.rs", FileStatus::Modified, 1536, Some(stats)),
+            entry("README.md", FileStatus::Added, 80, None),
+            entry("old/notes.txt", FileStatus::Deleted, 300, None),
+            pinned,
+            secret,
+            dir,
+        ];
+        
+        let list = &app.shared_to_project_list;
+        let screen = render_to_text(60, 9, |f| render_diff_list(f, &app, list, &diffs, true, f.area()));
+        assert_snapshot("diff_list", &screen);
+        fs::remove_dir_all(&root).ok();
+    }
 }
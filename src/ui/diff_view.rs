@@ -1,10 +1,11 @@
 // Diff View Component
-// Renders unified diff content
+// Renders the peek pane of the list view: the selected file's hunks, colored and scrollable,
+// for triaging without opening the side-by-side view
 
 use ratatui::{
     layout::Rect,
     text::{Line, Span},
-    widgets::{Block, Borders, Paragraph, Wrap},
+    widgets::{Block, Borders, Paragraph},
     Frame,
 };
 
@@ -13,33 +14,44 @@ use crate::operations::DiffEntry;
 use super::Styles;
 
 /// Render the diff view panel
+/// The file headers are left out (the title names the file) and lines are cut at the panel
+/// edge rather than wrapped, so the hunks stay compact; the status line stays put while the
+/// hunks scroll
 pub fn render_diff_view(f: &mut Frame, diff: &DiffEntry, app: &App, area: Rect) {
     if let Some(content) = &app.cached_diff_content {
         // Parse and style all lines
-        let all_lines: Vec<Line> = content
-            .lines()
-            .map(|line| style_diff_line(line))
-            .collect();
+        let all_lines: Vec<Line> = preview_lines(content).map(style_diff_line).collect();
+        
+        // Key-level diffs and lockfile summaries are titled as such, so they aren't mistaken
+        // for the text diff
+        let kind = if app.compares_keys(diff) {
+            "Keys"
+        } else if app.summarizes_lockfile(diff) {
+            "Dependencies (L: raw diff)"
+        } else {
+            "Diff"
+        };
+        
+        let mut status = vec![Span::styled(format!("{:?}", diff.status), Styles::diff_context())];
+        if let Some(note) = app.annotations.get(diff) {
+            status.push(Span::styled(format!("  ✎ {}", note), Styles::diff_hunk_header()));
+        }
         
         // Calculate visible area
-        let available_height = area.height.saturating_sub(2) as usize;
+        let available_height = area.height.saturating_sub(3) as usize;
         let max_offset = all_lines.len().saturating_sub(available_height);
         let scroll_offset = app.diff_scroll_offset.min(max_offset);
         
         // Get visible lines
-        let visible_lines: Vec<Line> = all_lines
-            .into_iter()
-            .skip(scroll_offset)
-            .take(available_height)
+        let visible_lines: Vec<Line> = std::iter::once(Line::from(status))
+            .chain(all_lines.into_iter().skip(scroll_offset).take(available_height))
             .collect();
         
-        let diff_widget = Paragraph::new(visible_lines)
-            .wrap(Wrap { trim: true })
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .title(format!("Diff: {} (PgUp/PgDn: Scroll)", diff.path.display())),
-            );
+        let diff_widget = Paragraph::new(visible_lines).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("{}: {} (PgUp/PgDn: scroll, Enter: side-by-side)", kind, diff.path.display())),
+        );
         
         f.render_widget(diff_widget, area);
     } else {
@@ -49,12 +61,25 @@ pub fn render_diff_view(f: &mut Frame, diff: &DiffEntry, app: &App, area: Rect)
     }
 }
 
+/// Lines of a diff without its `---` / `+++` file headers
+fn preview_lines(content: &str) -> impl Iterator<Item = &str> {
+    content.lines().filter(|line| !line.starts_with("---") && !line.starts_with("+++"))
+}
+
+/// Lines the peek pane scrolls through for the selected file
+pub(super) fn preview_line_count(app: &App) -> usize {
+    app.cached_diff_content.as_deref().map_or(0, |content| preview_lines(content).count())
+}
+
 /// Style a single diff line based on its prefix
 fn style_diff_line(line: &str) -> Line<'static> {
     let style = if line.starts_with('+') && !line.starts_with("+++") {
         Styles::diff_added()
     } else if line.starts_with('-') && !line.starts_with("---") {
         Styles::diff_removed()
+    } else if line.starts_with('~') {
+        // Changed dependency of a lockfile summary
+        Styles::status_modified()
     } else if line.starts_with('@') {
         Styles::diff_hunk_header()
     } else if line.starts_with("+++") || line.starts_with("---") {
@@ -0,0 +1,95 @@
+// Image Preview
+// Metadata comparison and half-block previews of image entries in the side-by-side view
+
+use ratatui::{
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+use crate::operations::{ImageFile, ImageFormat, ImagePair};
+use crate::utilities::format_size;
+use super::Styles;
+
+/// Rows of the metadata comparison, borders included
+pub const METADATA_HEIGHT: u16 = 6;
+
+/// Width of the row labels of the metadata comparison
+const LABEL_WIDTH: usize = 12;
+
+/// Compare format, dimensions, size and hash of both sides; values that differ are highlighted
+pub fn render_image_metadata(f: &mut Frame, images: &ImagePair, (left, right): (&str, &str), area: Rect) {
+    let value_width = (area.width.saturating_sub(2) as usize).saturating_sub(LABEL_WIDTH) / 2;
+    let side = |image: &Option<ImageFile>, value: fn(&ImageFile) -> String| -> String {
+        image.as_ref().map(value).unwrap_or_else(|| "(missing)".to_string())
+    };
+    let row = |label: &str, value: fn(&ImageFile) -> String| -> Line<'static> {
+        let (source, dest) = (side(&images.source, value), side(&images.dest, value));
+        let style = if source == dest { Style::default() } else { Styles::status_modified() };
+        Line::from(vec![
+            Span::styled(format!("{:LABEL_WIDTH$}", label), Styles::gutter()),
+            Span::styled(format!("{:value_width$}", source), style),
+            Span::styled(dest, style),
+        ])
+    };
+    let lines = vec![
+        Line::from(vec![
+            Span::raw(" ".repeat(LABEL_WIDTH)),
+            Span::styled(format!("{:value_width$}", left), Styles::gutter()),
+            Span::styled(right.to_string(), Styles::gutter()),
+        ]),
+        row("Format", |image| image.format.label().to_string()),
+        row("Dimensions", |image| match image.dimensions {
+            Some((width, height)) => format!("{} × {}", width, height),
+            None => "unknown".to_string(),
+        }),
+        row("Size", |image| format!("{} ({} bytes)", format_size(image.size), image.size)),
+    ];
+    
+    let identical = matches!((&images.source, &images.dest), (Some(source), Some(dest)) if source.hash == dest.hash);
+    let title = match (&images.source, &images.dest) {
+        (Some(source), Some(_)) if identical => format!(" Image: identical content ({}) ", source.hash),
+        (Some(source), Some(dest)) => format!(" Image: content differs ({} → {}) ", source.hash, dest.hash),
+        _ => " Image ".to_string(),
+    };
+    f.render_widget(Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(title)), area);
+}
+
+/// Both sides as half-block previews (each cell shows two pixels), downscaled to fit
+pub fn render_image_previews(f: &mut Frame, images: &ImagePair, titles: (String, String), area: Rect) {
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(area);
+    render_preview(f, images.source.as_ref(), titles.0, columns[0]);
+    render_preview(f, images.dest.as_ref(), titles.1, columns[1]);
+}
+
+fn render_preview(f: &mut Frame, image: Option<&ImageFile>, title: String, area: Rect) {
+    let block = Block::default().borders(Borders::ALL).title(title);
+    let inner = block.inner(area);
+    let rgb = |[r, g, b]: [u8; 3]| Color::Rgb(r, g, b);
+    
+    let lines: Vec<Line> = match image.map(|image| (image.format, image.thumbnail.as_ref())) {
+        Some((_, Some(thumbnail))) => thumbnail
+            .cells(inner.width as usize, inner.height as usize)
+            .into_iter()
+            .map(|row| {
+                Line::from(
+                    row.into_iter()
+                        .map(|(top, bottom)| {
+                            let style = Style::default().fg(rgb(top));
+                            Span::styled("▀", bottom.map_or(style, |bottom| style.bg(rgb(bottom))))
+                        })
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect(),
+        Some((ImageFormat::Svg, None)) => vec![Line::raw(""), Line::styled("(SVG: no raster preview)", Styles::fold_indicator())],
+        Some(_) => vec![Line::raw(""), Line::styled("(can't decode image)", Styles::fold_indicator())],
+        None => vec![Line::raw(""), Line::styled("(file does not exist)", Styles::fold_indicator())],
+    };
+    f.render_widget(Paragraph::new(lines).alignment(Alignment::Center).block(block), area);
+}
@@ -0,0 +1,189 @@
+// Input Routing
+// Dispatches terminal events to the handler registered for the active input context
+//
+// Each view registers one handler; the event loop only works out which context is active.
+// Adding a view means adding a context, a handler in the view's module and one `register`
+// call, without touching the other views' key handling.
+
+use crossterm::event::{Event, KeyCode, KeyEventKind, KeyModifiers};
+use std::collections::HashMap;
+use tui_components::RectRegistry;
+
+use crate::core::review::Verdict;
+use crate::core::{App, AppEvent, AppTab, EventHandler, FocusTarget};
+use super::{
+    diff_list, settings_view, setup_wizard, side_by_side, snapshots_view, sync_progress_view, sync_report_view,
+};
+
+/// Which view receives input
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum InputContext {
+    /// First-run setup form (modal)
+    SetupWizard,
+    /// Topmost popup of the dialog stack (modal)
+    Dialog,
+    /// Context menu of the selected entry (modal)
+    EntryMenu,
+    /// Per-file outcomes of the last sync (modal)
+    SyncReport,
+    /// Progress of the running sync (modal; Esc cancels it)
+    SyncProgress,
+    /// What to do about a stuck sync or scan (modal)
+    StallPrompt,
+    /// Progress of the first scan of the launch, shown in place of the dashboard
+    StartupScan,
+    /// The two diff lists of the Sync tab
+    DiffLists,
+    /// Side-by-side diff of the selected file
+    SideBySide,
+    /// Snapshot list of the Snapshots tab
+    Snapshots,
+    /// Form of the Settings tab
+    Settings,
+}
+
+impl InputContext {
+    /// Context of the current app state; modal focus wins over the visible view
+    pub fn active(app: &App) -> Self {
+        match app.focus.focused() {
+            Some(FocusTarget::SetupWizard) => InputContext::SetupWizard,
+            Some(FocusTarget::Dialog) => InputContext::Dialog,
+            Some(FocusTarget::EntryMenu) => InputContext::EntryMenu,
+            Some(FocusTarget::SyncReport) => InputContext::SyncReport,
+            Some(FocusTarget::SyncProgress) => InputContext::SyncProgress,
+            Some(FocusTarget::StallPrompt) => InputContext::StallPrompt,
+            _ if app.is_starting_up() => InputContext::StartupScan,
+            _ if app.active_tab == AppTab::Snapshots => InputContext::Snapshots,
+            _ if app.active_tab == AppTab::Settings => InputContext::Settings,
+            _ if app.show_side_by_side => InputContext::SideBySide,
+            _ => InputContext::DiffLists,
+        }
+    }
+}
+
+/// Handler for the events of one context
+/// The registry holds the rects rendered last frame, for hit-testing mouse events
+pub type InputHandler = fn(&mut App, Event, &RectRegistry);
+
+/// Context -> handler table
+#[derive(Debug, Default)]
+pub struct InputRouter {
+    handlers: HashMap<InputContext, InputHandler>,
+}
+
+impl InputRouter {
+    /// Empty table (events of unregistered contexts are dropped)
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Table with the handlers of every view
+    pub fn with_default_views() -> Self {
+        Self::new()
+            .register(InputContext::SetupWizard, setup_wizard::handle_setup_event)
+            .register(InputContext::Dialog, handle_dialog_event)
+            .register(InputContext::EntryMenu, handle_entry_menu_event)
+            .register(InputContext::SyncReport, sync_report_view::handle_sync_report_event)
+            .register(InputContext::SyncProgress, sync_progress_view::handle_sync_progress_event)
+            .register(InputContext::StallPrompt, sync_progress_view::handle_stall_prompt_event)
+            .register(InputContext::StartupScan, sync_progress_view::handle_startup_scan_event)
+            .register(InputContext::DiffLists, diff_list::handle_list_event)
+            .register(InputContext::SideBySide, side_by_side::handle_side_by_side_event)
+            .register(InputContext::Snapshots, snapshots_view::handle_snapshots_event)
+            .register(InputContext::Settings, settings_view::handle_settings_event)
+    }
+    
+    /// Set the handler of a context, replacing any earlier one
+    pub fn register(mut self, context: InputContext, handler: InputHandler) -> Self {
+        self.handlers.insert(context, handler);
+        self
+    }
+    
+    /// Deliver an event to the handler of the active context
+    pub fn dispatch(&self, app: &mut App, event: Event, registry: &RectRegistry) {
+        if let Some(handler) = self.handlers.get(&InputContext::active(app)) {
+            handler(app, event, registry);
+        }
+    }
+}
+
+/// Keys go to the topmost popup; its result is acted on by the app
+fn handle_dialog_event(app: &mut App, event: Event, _registry: &RectRegistry) {
+    if let Event::Key(key) = event {
+        if key.kind == KeyEventKind::Press {
+            let result = app.popups.handle_key(&key);
+            app.handle_dialog_result(result);
+        }
+    }
+}
+
+/// Keys and mouse clicks go to the entry context menu; the picked action is run by the app
+fn handle_entry_menu_event(app: &mut App, event: Event, registry: &RectRegistry) {
+    let Some(menu) = app.entry_menu.as_mut() else {
+        return;
+    };
+    let result = match event {
+        Event::Key(key) if key.kind == KeyEventKind::Press => menu.handle_key(&key),
+        Event::Mouse(mouse) => menu.handle_mouse(&mouse, registry),
+        _ => return,
+    };
+    app.handle_entry_menu_result(result);
+}
+
+/// Actions available in every non-modal view; returns the event back if it isn't one
+pub fn handle_global_event(app: &mut App, event: AppEvent) -> Option<AppEvent> {
+    match event {
+        AppEvent::Quit => app.quit(),
+        AppEvent::ShowTab(tab) => app.show_tab(tab),
+        AppEvent::External(action) => app.pending_external = Some(action),
+        AppEvent::HealthCheck => app.show_health_report(),
+        AppEvent::EffectiveConfig => app.show_effective_config(),
+        AppEvent::TestPatterns => app.request_pattern_test(),
+        AppEvent::ToggleProfiler => app.toggle_profiler(),
+        AppEvent::None => {}
+        other => return Some(other),
+    }
+    None
+}
+
+/// Actions on the selected entry or the current list, shared by the lists and the
+/// side-by-side view; returns the event back if it isn't one
+pub fn handle_entry_event(app: &mut App, event: AppEvent) -> Option<AppEvent> {
+    match event {
+        AppEvent::Refresh => app.rescan(),
+        AppEvent::SyncSelected => app.request_sync_selected(),
+        AppEvent::SyncAll => app.request_sync_all(),
+        AppEvent::SyncToBranch => app.request_sync_to_branch(),
+        AppEvent::ExportArchive => app.request_export_archive(),
+        AppEvent::CopyPath(side) => app.copy_selected_path(side),
+        AppEvent::OpenInEditor(side) => app.open_selected_in_editor(side),
+        AppEvent::Annotate => app.request_annotation(),
+        AppEvent::ToggleStructuralDiff => app.toggle_structural_diff(),
+        AppEvent::ToggleLockfileSummary => app.toggle_lockfile_summary(),
+        other => return Some(other),
+    }
+    None
+}
+
+/// Convert a terminal event to an app event, handling the global actions
+/// Returns the event for the view if it isn't global
+pub fn view_event(app: &mut App, event: Event) -> Option<AppEvent> {
+    handle_global_event(app, EventHandler::handle(event))
+}
+
+/// Verdict given by a review key (a / r / d) while a review is under way
+pub fn review_verdict(app: &App, event: &Event) -> Option<Verdict> {
+    let Event::Key(key) = event else {
+        return None;
+    };
+    let modified = key.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT);
+    if app.review.is_none() || key.kind != KeyEventKind::Press || modified {
+        return None;
+    }
+    match key.code {
+        KeyCode::Char('a') => Some(Verdict::Accept),
+        KeyCode::Char('r') => Some(Verdict::Reject),
+        KeyCode::Char('d') => Some(Verdict::Defer),
+        _ => None,
+    }
+}
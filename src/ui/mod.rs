@@ -4,41 +4,95 @@
 pub mod app_view;
 pub mod diff_list;
 pub mod diff_view;
+pub mod image_preview;
+pub mod input;
+pub mod settings_view;
+pub mod setup_wizard;
 pub mod side_by_side;
+pub mod snapshots_view;
 pub mod styles;
+pub mod sync_progress_view;
+pub mod sync_report_view;
+pub mod terminal;
 
 use anyhow::Result;
-use crossterm::event;
-use ratatui::{backend::CrosstermBackend, Terminal};
-use std::io::Stdout;
-use std::time::Duration;
+use crossterm::event::{self, Event, KeyEventKind};
+use std::time::{Duration, Instant};
 
-use crate::core::{App, AppEvent, EventHandler};
+use tui_components::{KeyRoute, RectRegistry};
+
+use crate::core::{App, ExternalAction};
+use crate::error::SyncManagerError;
+use crate::operations::EntryMetadata;
+use crate::utilities::{editor_command, open_in_editor, run_shell, suspend_process};
 
 pub use app_view::render_app;
-pub use diff_list::render_diff_list;
+pub use diff_list::{render_diff_list, update_diff_lists};
 pub use diff_view::render_diff_view;
+pub use input::{InputContext, InputRouter};
+pub use settings_view::render_settings;
+pub use setup_wizard::render_setup_wizard;
 pub use side_by_side::render_side_by_side;
+pub use snapshots_view::{render_snapshots, update_snapshot_lists};
 pub use styles::Styles;
+pub use sync_progress_view::{render_stall_prompt, render_startup_scan, render_sync_progress};
+pub use sync_report_view::{render_sync_report, update_sync_report};
+pub use terminal::{restore_terminal, suspend_terminal, AppTerminal, TerminalGuard};
 
 /// Run the main application event loop
 pub fn run_app(
-    terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    terminal: &mut AppTerminal,
     app: &mut App,
 ) -> Result<()> {
+    // Overlay layers (dialogs) registered during rendering drive the dimming pass
+    let mut registry = RectRegistry::new();
+    let router = InputRouter::with_default_views();
+    
     loop {
+        // A finished background sync opens its report, a finished scan replaces the cached diff
+        let frame_started = Instant::now();
+        app.poll_sync();
+        app.poll_scan();
+        app.check_watchdog();
+        
         // Ensure diff is cached before rendering
         ensure_diff_cached(app);
+        ensure_metadata_cached(app);
+        update_diff_lists(app);
+        update_snapshot_lists(app);
+        update_sync_report(app);
+        app.toasts.tick();
         
         // Render the UI
-        terminal.draw(|f| render_app(f, app))?;
+        let draw_started = Instant::now();
+        terminal.draw(|f| render_app(f, app, &mut registry)).map_err(SyncManagerError::render)?;
+        let drawn_at = Instant::now();
+        let summary = app.frame_stats.record_frame(draw_started - frame_started, drawn_at - draw_started, drawn_at);
+        if let Some(summary) = summary {
+            app.log(&summary);
+        }
         
-        // Handle events
-        if event::poll(Duration::from_millis(250))? {
+        // Handle events (poll faster while a sync, scan or syntax highlighting runs, so its
+        // result shows promptly)
+        let busy = app.sync_task.is_some() || app.diffs_stale() || app.highlight_cache.borrow().is_pending();
+        let timeout = if busy { 100 } else { 250 };
+        if event::poll(Duration::from_millis(timeout))? {
             let event = event::read()?;
-            let app_event = EventHandler::handle(event);
+            app.frame_stats.event_read(Instant::now());
             
-            handle_event(app, app_event);
+            // Tab/Shift-Tab move focus; everything else goes to the active view's handler
+            match event {
+                Event::Key(key) if key.kind == KeyEventKind::Press => match app.focus.route_key(&key) {
+                    KeyRoute::Traversed(target) => app.focus_changed(target),
+                    KeyRoute::Deliver(_) | KeyRoute::Unrouted => router.dispatch(app, event, &registry),
+                },
+                _ => router.dispatch(app, event, &registry),
+            }
+        }
+        
+        // Editors and shells take over the terminal until they exit
+        if let Some(action) = app.pending_external.take() {
+            run_external(terminal, app, action)?;
         }
         
         // Check if we should quit
@@ -48,6 +102,48 @@ pub fn run_app(
     }
 }
 
+/// Run an external program with the terminal suspended, then pick up its changes
+fn run_external(terminal: &mut AppTerminal, app: &mut App, action: ExternalAction) -> Result<()> {
+    match action {
+        ExternalAction::Edit(path) => {
+            let command = editor_command(&app.config.defaults.editor);
+            let result = suspend_terminal(terminal, || open_in_editor(&command, &path))?;
+            match result.and_then(|()| app.refresh_diffs()) {
+                Ok(()) => app.toasts.info(format!("Edited {}", path.display())),
+                Err(err) => app.report_error("Edit failed", &err),
+            }
+        }
+        ExternalAction::Shell | ExternalAction::Suspend => {
+            let root = app.workspace_root.clone();
+            let result = suspend_terminal(terminal, || match action {
+                ExternalAction::Shell => run_shell(&root),
+                _ => suspend_process(&root),
+            })?;
+            // Files may have changed while we were away
+            match result.and_then(|()| app.refresh_diffs()) {
+                Ok(()) => app.toasts.info("Resumed"),
+                Err(err) => app.report_error("Resume failed", &err),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Read the metadata of the selected entry while the info panel shows it
+fn ensure_metadata_cached(app: &mut App) {
+    if !app.show_metadata {
+        return;
+    }
+    let Some(diff) = app.selected_diff() else {
+        app.cached_metadata = None;
+        return;
+    };
+    if app.cached_metadata.as_ref().is_some_and(|(path, _)| *path == diff.source_path) {
+        return;
+    }
+    app.cached_metadata = Some((diff.source_path.clone(), EntryMetadata::read(diff)));
+}
+
 /// Ensure diff content is cached for the current selection
 fn ensure_diff_cached(app: &mut App) {
     let current_path = app.selected_diff().map(|d| d.path.clone());
@@ -60,7 +156,7 @@ fn ensure_diff_cached(app: &mut App) {
         
         if needs_reload {
             if let Some(diff) = app.selected_diff() {
-                app.cached_diff_content = crate::operations::DiffEngine::load_diff_content(diff);
+                app.cached_diff_content = app.diff_content(diff);
                 app.cached_diff_path = Some(diff_path);
                 app.diff_scroll_offset = 0;
             }
@@ -70,51 +166,3 @@ fn ensure_diff_cached(app: &mut App) {
         app.cached_diff_path = None;
     }
 }
-
-/// Handle an application event
-fn handle_event(app: &mut App, event: AppEvent) {
-    match event {
-        AppEvent::Quit => app.quit(),
-        AppEvent::SelectPrevious => {
-            if app.show_side_by_side {
-                app.scroll_up(1);
-            } else {
-                app.select_previous();
-            }
-        }
-        AppEvent::SelectNext => {
-            if app.show_side_by_side {
-                app.scroll_down(1);
-            } else {
-                app.select_next();
-            }
-        }
-        AppEvent::ToggleViewMode => app.toggle_view_mode(),
-        AppEvent::ToggleSideBySide => app.toggle_side_by_side(),
-        AppEvent::ToggleFold => app.toggle_fold(),
-        AppEvent::ScrollUp(amount) => app.scroll_up(amount),
-        AppEvent::ScrollDown(amount) => app.scroll_down(amount),
-        AppEvent::PageUp => app.scroll_up(10),
-        AppEvent::PageDown => app.scroll_down(10),
-        AppEvent::Back => {
-            if app.show_side_by_side {
-                app.show_side_by_side = false;
-                app.side_by_side_source = None;
-                app.side_by_side_dest = None;
-                app.fold_unchanged = false;
-            } else {
-                app.quit();
-            }
-        }
-        AppEvent::Refresh => {
-            let _ = app.refresh_diffs();
-        }
-        AppEvent::SyncSelected => {
-            // TODO: Implement sync selected
-        }
-        AppEvent::SyncAll => {
-            // TODO: Implement sync all
-        }
-        AppEvent::None => {}
-    }
-}
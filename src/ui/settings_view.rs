@@ -0,0 +1,64 @@
+// Settings View
+// Settings form of the Settings tab
+
+use crossterm::event::{Event, KeyEventKind};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    widgets::Paragraph,
+    Frame,
+};
+
+use tui_components::{FormResult, RectRegistry};
+
+use crate::core::{App, AppTab, FocusTarget};
+use super::input::view_event;
+
+/// Render the Settings tab
+pub fn render_settings(f: &mut Frame, app: &App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2), // Intro
+            Constraint::Min(0),    // Form
+            Constraint::Length(1), // Error
+        ])
+        .split(area);
+    
+    let intro = Paragraph::new(" Changes apply and are written to sync-manager.yaml when saved with Enter.")
+        .style(Style::default().fg(Color::Gray));
+    f.render_widget(intro, chunks[0]);
+    
+    let tab = &app.settings;
+    tab.form.render(f, chunks[1], app.focus.is_focused(FocusTarget::SettingsForm), false);
+    
+    if let Some(error) = &tab.error {
+        let error_line = Paragraph::new(format!(" {}", error))
+            .style(Style::default().fg(Color::Red));
+        f.render_widget(error_line, chunks[2]);
+    }
+}
+
+/// Handle input for the Settings tab; keys the form doesn't use fall through to the global actions
+pub fn handle_settings_event(app: &mut App, event: Event, _registry: &RectRegistry) {
+    let Event::Key(key) = event else {
+        view_event(app, event);
+        return;
+    };
+    if key.kind != KeyEventKind::Press {
+        return;
+    }
+    
+    match app.settings.form.handle_key(&key) {
+        FormResult::Submitted => app.save_settings(),
+        FormResult::Cancelled => {
+            // Esc discards unsaved edits
+            app.settings.reset(&app.config);
+            app.show_tab(AppTab::Sync);
+        }
+        FormResult::Consumed => app.settings.error = None,
+        FormResult::Ignored => {
+            view_event(app, event);
+        }
+    }
+}
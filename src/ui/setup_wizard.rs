@@ -0,0 +1,65 @@
+// Setup Wizard View
+// First-run form for creating sync-manager.yaml
+
+use crossterm::event::{Event, KeyEventKind};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    widgets::Paragraph,
+    Frame,
+};
+
+use tui_components::{FormResult, RectRegistry};
+
+use crate::core::{App, SetupWizard};
+
+/// Render the setup wizard in the main content area
+pub fn render_setup_wizard(f: &mut Frame, wizard: &SetupWizard, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2), // Intro
+            Constraint::Min(0),    // Form
+            Constraint::Length(1), // Error
+        ])
+        .split(area);
+    
+    let intro = Paragraph::new(" No sync-manager.yaml found. Describe what to sync and press Enter to save.")
+        .style(Style::default().fg(Color::Gray));
+    f.render_widget(intro, chunks[0]);
+    
+    wizard.form.render(f, chunks[1], true, false);
+    
+    if let Some(error) = &wizard.error {
+        let error_line = Paragraph::new(format!(" {}", error))
+            .style(Style::default().fg(Color::Red));
+        f.render_widget(error_line, chunks[2]);
+    }
+}
+
+/// Handle input while the wizard is active (only key presses reach the form)
+pub fn handle_setup_event(app: &mut App, event: Event, _registry: &RectRegistry) {
+    let Event::Key(key) = event else {
+        return;
+    };
+    if key.kind != KeyEventKind::Press {
+        return;
+    }
+    
+    let Some(wizard) = &mut app.setup_wizard else {
+        return;
+    };
+    
+    match wizard.form.handle_key(&key) {
+        FormResult::Cancelled => app.quit(),
+        FormResult::Submitted => {
+            if let Err(err) = app.complete_setup() {
+                if let Some(wizard) = &mut app.setup_wizard {
+                    wizard.error = Some(format!("{:#}", err));
+                }
+            }
+        }
+        FormResult::Consumed => wizard.error = None,
+        FormResult::Ignored => {}
+    }
+}
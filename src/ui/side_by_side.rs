@@ -1,22 +1,218 @@
 // Side-by-Side Diff View
 // Renders source and destination files in parallel columns
 
+use crossterm::event::{Event, KeyCode, KeyEventKind, KeyModifiers};
 use ratatui::{
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::Style,
     text::{Line, Span},
     widgets::{Block, Borders, Paragraph},
     Frame,
 };
 
-use crate::core::{App, ViewMode};
-use crate::operations::diff::{align_lines, compute_word_diff_dest, compute_word_diff_source, LineAlignment};
+use tui_components::{
+    expand_tabs, get_file_extension, long_line_window, AlignmentCache, FoldState, GutterNumbers, HighlightedLines,
+    RectRegistry, WrapMarker,
+};
+
+use crate::core::{App, AppEvent, BookmarkPrompt, ViewMode};
+use crate::operations::diff::{compute_word_diff_dest, compute_word_diff_source, LineAlignment};
+use crate::operations::{BlameLine, FileStatus};
+use crate::utilities::format_timestamp;
+use super::image_preview::{render_image_metadata, render_image_previews, METADATA_HEIGHT};
+use super::input::{handle_entry_event, review_verdict, view_event};
 use super::Styles;
 
+/// Inputs the built rows depend on; any change invalidates them
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RowsKey {
+    /// Key of the alignment the rows were built from (contents and similarity threshold)
+    alignment: Option<(u64, u64, u64)>,
+    text_width: usize,
+    max_line_digits: usize,
+    wrap_marker: WrapMarker,
+    tab_width: usize,
+    dual_line_numbers: bool,
+    context_lines: usize,
+    long_line_chars: usize,
+    folds: FoldState,
+    /// Whether the (source, destination) syntax highlighting was ready
+    syntax: (bool, bool),
+}
+
+/// Alignment and wrapped rows of the selected file pair, kept between frames
+/// Scrolling only slices the rows; they are rebuilt when the width, folds or contents change
+#[derive(Debug, Default)]
+pub struct SideBySideCache {
+    alignment: AlignmentCache,
+    key: Option<RowsKey>,
+    source_rows: Vec<Line<'static>>,
+    dest_rows: Vec<Line<'static>>,
+    /// (row, region) of every fold indicator
+    fold_rows: Vec<(usize, usize)>,
+    /// (row, aligned index) of every "line continues" marker
+    long_rows: Vec<(usize, usize)>,
+    /// Aligned entry and destination line starting each row
+    row_starts: RowStarts,
+}
+
+impl SideBySideCache {
+    /// Aligned entries of the last rendered file pair
+    pub fn aligned(&self) -> &[LineAlignment] {
+        self.alignment.aligned()
+    }
+    
+    /// Aligned entry shown at `row`: the one starting there, else the closest one above (the
+    /// row continues a wrapped line or is a fold)
+    pub fn entry_at(&self, row: usize) -> Option<usize> {
+        let row = row.min(self.row_starts.entries.len().checked_sub(1)?);
+        self.row_starts.entries[..=row].iter().rev().find_map(|entry| *entry)
+    }
+}
+
+/// What starts each row of the built panels (None for padding, wraps and folds)
+#[derive(Debug, Default)]
+struct RowStarts {
+    /// Aligned entry
+    entries: Vec<Option<usize>>,
+    /// Destination line
+    dest_lines: Vec<Option<usize>>,
+}
+
+impl RowStarts {
+    /// Record that aligned entry `index` (with destination line `dest_idx`, if any) starts at `row`
+    fn mark(&mut self, row: usize, index: usize, dest_idx: Option<usize>) {
+        self.entries.resize(row, None);
+        self.entries.push(Some(index));
+        self.dest_lines.resize(row, None);
+        self.dest_lines.push(dest_idx);
+    }
+}
+
+/// Width of the blame column, borders included: hash, author and date
+const BLAME_WIDTH: u16 = 31;
+
+/// The side of an Added or Deleted entry that exists
+enum SingleFile<'a> {
+    /// Source of a new file, which syncing creates in the destination
+    Created(&'a [String]),
+    /// Destination of a file gone from the source
+    Deleted(&'a [String]),
+}
+
+/// The existing side of the selected entry if it is Added or Deleted
+fn single_file(app: &App) -> Option<SingleFile<'_>> {
+    match (&app.selected_diff()?.status, &app.side_by_side_source, &app.side_by_side_dest) {
+        (FileStatus::Added, Some(source), None) => Some(SingleFile::Created(source)),
+        (FileStatus::Deleted, None, Some(dest)) => Some(SingleFile::Deleted(dest)),
+        _ => None,
+    }
+}
+
+/// Syntax highlighting of `lines` of the selected file if it is on and the background worker
+/// has finished it; plain text is drawn until then
+fn highlighted(app: &App, lines: &[String]) -> Option<HighlightedLines> {
+    if !app.config.ui.syntax_highlighting {
+        return None;
+    }
+    let extension = get_file_extension(&app.selected_diff()?.path.to_string_lossy())?;
+    app.highlight_cache.borrow_mut().get(lines, &extension)
+}
+
+/// Syntax highlighting of the (source, destination) files, where it is ready
+#[derive(Clone, Copy)]
+struct Syntax<'a> {
+    source: Option<&'a [Vec<Span<'static>>]>,
+    dest: Option<&'a [Vec<Span<'static>>]>,
+}
+
+impl Syntax<'_> {
+    /// Syntax style of every character of source line `idx` as drawn
+    fn source(&self, idx: usize, tab_width: usize) -> Vec<Style> {
+        char_styles(self.source.and_then(|lines| lines.get(idx)), tab_width)
+    }
+    
+    /// Syntax style of every character of destination line `idx` as drawn
+    fn dest(&self, idx: usize, tab_width: usize) -> Vec<Style> {
+        char_styles(self.dest.and_then(|lines| lines.get(idx)), tab_width)
+    }
+}
+
+/// Style of every character of a highlighted line, tabs expanded as in the rows (empty when the
+/// line isn't highlighted)
+fn char_styles(spans: Option<&Vec<Span<'static>>>, tab_width: usize) -> Vec<Style> {
+    let Some(spans) = spans else {
+        return Vec::new();
+    };
+    let texts: Vec<(String, bool)> = spans.iter().map(|span| (span.content.to_string(), false)).collect();
+    expand_tabs(&texts, tab_width)
+        .iter()
+        .zip(spans)
+        .flat_map(|((text, _), span)| std::iter::repeat_n(span.style, text.chars().count()))
+        .collect()
+}
+
+/// (left, right) panel labels of the current direction
+fn panel_labels(app: &App) -> (&'static str, &'static str) {
+    match app.view_mode {
+        ViewMode::SharedToProject => ("Shared", "Project"),
+        ViewMode::ProjectToShared => ("Project", "Shared"),
+    }
+}
+
+/// (source, destination) panel titles: label and the last components of the path, marked
+/// when the file is compared by keys
+fn panel_titles(app: &App) -> (String, String) {
+    let (left_label, right_label) = panel_labels(app);
+    match app.selected_diff() {
+        Some(diff) => {
+            let mode = if app.compares_keys(diff) { " (by keys)" } else { "" };
+            (
+                format!("{}: {}{}", left_label, short_path(&diff.source_path), mode),
+                format!("{}: {}{}", right_label, short_path(&diff.destination_path), mode),
+            )
+        }
+        None => (left_label.to_string(), right_label.to_string()),
+    }
+}
+
 /// Render side-by-side diff view
 pub fn render_side_by_side(f: &mut Frame, app: &App, area: Rect) {
-    if let (Some(source_lines), Some(dest_lines)) =
+    // Images: a metadata comparison over previews (or over the text diff of an SVG's markup)
+    let area = match &app.side_by_side_images {
+        Some(images) => {
+            let rows = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(METADATA_HEIGHT), Constraint::Min(0)])
+                .split(area);
+            render_image_metadata(f, images, panel_labels(app), rows[0]);
+            if !images.is_svg() {
+                render_image_previews(f, images, panel_titles(app), rows[1]);
+                return;
+            }
+            rows[1]
+        }
+        None => area,
+    };
+    
+    if let Some(file) = single_file(app) {
+        render_single_file(f, app, file, area);
+    } else if let (Some(source_lines), Some(dest_lines)) =
         (&app.side_by_side_source, &app.side_by_side_dest)
     {
+        // Blame goes in a column right of the destination, so both panels keep the same width
+        let blame = app.side_by_side_blame.as_deref().filter(|_| app.show_blame);
+        let (area, blame_area) = match blame {
+            Some(_) => {
+                let split = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([Constraint::Min(0), Constraint::Length(BLAME_WIDTH)])
+                    .split(area);
+                (split[0], Some(split[1]))
+            }
+            None => (area, None),
+        };
+        
         // Split area into two columns
         let columns = Layout::default()
             .direction(Direction::Horizontal)
@@ -32,7 +228,13 @@ pub fn render_side_by_side(f: &mut Frame, app: &App, area: Rect) {
         } else {
             (max_line_num as f64).log10().floor() as usize + 1
         };
-        let gutter_width = max_line_digits + 1; // +1 for the space after the number
+        // +1 for the space after the number; dual gutters hold "source dest" numbers
+        let dual_line_numbers = app.config.ui.dual_line_numbers;
+        let gutter_width = if dual_line_numbers {
+            max_line_digits * 2 + 2
+        } else {
+            max_line_digits + 1
+        };
         let right_margin = 1; // Single column gap on the right
         // Content area is inside borders: columns[0].width - 2
         // Text should wrap 1 column before right border, so available width is: columns[0].width - 2 - 1
@@ -42,48 +244,111 @@ pub fn render_side_by_side(f: &mut Frame, app: &App, area: Rect) {
         let wrap_at = content_area_width.saturating_sub(1); // 1 column before right border
         let text_width = wrap_at.saturating_sub(gutter_width + right_margin);
 
-        // Align lines
-        let aligned_lines = align_lines(source_lines, dest_lines);
+        // Align lines (realigned only when either file changes)
+        let mut cache = app.side_by_side_cache.borrow_mut();
+        let cache = &mut *cache;
+        cache
+            .alignment
+            .align(source_lines, dest_lines, app.config.ui.similarity_threshold);
 
-        // Build visible lines for both panels
-        let (mut source_visible, mut dest_visible) =
-            build_aligned_lines(&aligned_lines, source_lines, dest_lines, text_width, gutter_width, max_line_digits, app);
+        // Build the rows of both panels unless nothing they depend on has changed
+        let source_syntax = highlighted(app, source_lines);
+        let dest_syntax = highlighted(app, dest_lines);
+        let key = RowsKey {
+            alignment: cache.alignment.key(),
+            text_width,
+            max_line_digits,
+            wrap_marker: WrapMarker::named(&app.config.ui.wrap_marker),
+            tab_width: app.config.ui.tab_width,
+            dual_line_numbers,
+            context_lines: app.config.ui.context_lines,
+            long_line_chars: app.config.ui.long_line_chars,
+            folds: app.current_folds(),
+            syntax: (source_syntax.is_some(), dest_syntax.is_some()),
+        };
+        if cache.key.as_ref() != Some(&key) {
+            let mut fold_rows = Vec::new();
+            let mut long_rows = Vec::new();
+            let mut row_starts = RowStarts::default();
+            let (source_rows, dest_rows) = build_aligned_lines(
+                cache.alignment.aligned(),
+                source_lines,
+                dest_lines,
+                text_width,
+                gutter_width,
+                max_line_digits,
+                key.wrap_marker,
+                key.tab_width,
+                dual_line_numbers,
+                key.context_lines,
+                cache.alignment.ignored(),
+                cache.alignment.moved(),
+                Syntax {
+                    source: source_syntax.as_deref().map(Vec::as_slice),
+                    dest: dest_syntax.as_deref().map(Vec::as_slice),
+                },
+                &key.folds,
+                LongLines { chars: key.long_line_chars, folds: &key.folds, rows: &mut long_rows },
+                &mut fold_rows,
+                &mut row_starts,
+            );
+            cache.source_rows = source_rows;
+            cache.dest_rows = dest_rows;
+            cache.fold_rows = fold_rows;
+            cache.long_rows = long_rows;
+            cache.row_starts = row_starts;
+            cache.key = Some(key);
+        }
 
         // Apply scroll offset
-        let scroll_offset = app
-            .diff_scroll_offset
-            .min(source_visible.len().saturating_sub(1));
-        if scroll_offset > 0 {
-            source_visible.drain(..scroll_offset);
-            dest_visible.drain(..scroll_offset);
-        }
+        let total_rows = cache.source_rows.len();
+        let scroll_offset = app.diff_scroll_offset.min(total_rows.saturating_sub(1));
+        let viewport = scroll_offset..(scroll_offset + available_height).min(total_rows);
+        
+        // Remember which fold indicators and truncated lines are on screen so Enter can expand
+        // the topmost one
+        *app.visible_folds.borrow_mut() = cache
+            .fold_rows
+            .iter()
+            .filter(|(row, _)| viewport.contains(row))
+            .map(|&(_, region)| region)
+            .collect();
+        let mut visible_long_lines: Vec<usize> = cache
+            .long_rows
+            .iter()
+            .filter(|(row, _)| viewport.contains(row))
+            .map(|&(_, index)| index)
+            .collect();
+        visible_long_lines.dedup();
+        *app.visible_long_lines.borrow_mut() = visible_long_lines;
 
-        // Truncate to available height
-        source_visible.truncate(available_height);
-        dest_visible.truncate(available_height);
+        let source_visible = cache.source_rows[viewport.clone()].to_vec();
+        let dest_visible = cache.dest_rows[viewport.clone()].to_vec();
+        
+        if let (Some(blame), Some(blame_area)) = (blame, blame_area) {
+            render_blame(f, blame, &cache.row_starts.dest_lines[viewport], blame_area);
+        }
 
         // Panel titles
-        let (left_label, right_label) = match app.view_mode {
-            ViewMode::SharedToProject => ("Shared", "Project"),
-            ViewMode::ProjectToShared => ("Project", "Shared"),
-        };
-
-        let source_title = app
-            .selected_diff()
-            .map(|d| format!("{}: {}", left_label, short_path(&d.source_path)))
-            .unwrap_or_else(|| left_label.to_string());
-
-        let dest_title = app
-            .selected_diff()
-            .map(|d| format!("{}: {}", right_label, short_path(&d.destination_path)))
-            .unwrap_or_else(|| right_label.to_string());
+        let (source_title, dest_title) = panel_titles(app);
+        let mut dest_title = vec![Span::raw(dest_title)];
+        if let Some(commit) = &app.history_version {
+            dest_title.push(Span::styled(format!(" @ {} {}", commit.hash, commit.date), Styles::history_version()));
+        }
+        
+        // What syncing would change in the destination, as in the diff list
+        let stats = cache.alignment.stats();
+        if !stats.is_empty() {
+            dest_title.push(Span::styled(format!(" +{}", stats.source_changes()), Styles::status_added()));
+            dest_title.push(Span::styled(format!(" −{}", stats.dest_changes()), Styles::status_deleted()));
+        }
 
         let source_widget = Paragraph::new(source_visible)
             .block(Block::default().borders(Borders::ALL).title(source_title));
         f.render_widget(source_widget, columns[0]);
 
         let dest_widget = Paragraph::new(dest_visible)
-            .block(Block::default().borders(Borders::ALL).title(dest_title));
+            .block(Block::default().borders(Borders::ALL).title(Line::from(dest_title)));
         f.render_widget(dest_widget, columns[1]);
     } else {
         let loading = Paragraph::new("Loading files...")
@@ -96,6 +361,187 @@ pub fn render_side_by_side(f: &mut Frame, app: &App, area: Rect) {
     }
 }
 
+/// Render an Added or Deleted entry: the existing file colored as a whole beside a placeholder
+/// for the missing one, under a banner saying what syncing does with it
+/// Scrolling here moves by file line, so only the lines on screen are wrapped
+fn render_single_file(f: &mut Frame, app: &App, file: SingleFile, area: Rect) {
+    let (left_label, right_label) = panel_labels(app);
+    let propagate_deletes = app.config.defaults.propagate_deletes;
+    let (lines, banner, banner_style, line_style) = match file {
+        SingleFile::Created(lines) => (
+            lines,
+            format!(" New file: syncing creates it in {} ", right_label),
+            Styles::sync_banner_create(),
+            Styles::side_by_side_dest_modified_bg(),
+        ),
+        SingleFile::Deleted(lines) if propagate_deletes => (
+            lines,
+            format!(" Gone from {}: syncing deletes it from {} ", left_label, right_label),
+            Styles::sync_banner_delete(),
+            Styles::side_by_side_source_modified_bg(),
+        ),
+        SingleFile::Deleted(lines) => (
+            lines,
+            format!(" Gone from {}: syncing keeps it (delete propagation is off) ", left_label),
+            Styles::sync_banner_keep(),
+            Style::default(),
+        ),
+    };
+    let created = matches!(file, SingleFile::Created(_));
+    
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(0)])
+        .split(area);
+    f.render_widget(Paragraph::new(Line::styled(banner, banner_style)).alignment(Alignment::Center), rows[0]);
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(rows[1]);
+    let (file_area, missing_area) = if created { (columns[0], columns[1]) } else { (columns[1], columns[0]) };
+    
+    // Same gutter and text width as the two-sided view
+    let max_line_digits = lines.len().max(1).to_string().len();
+    let dual_line_numbers = app.config.ui.dual_line_numbers;
+    let gutter_width = if dual_line_numbers { max_line_digits * 2 + 2 } else { max_line_digits + 1 };
+    let wrap_marker = WrapMarker::named(&app.config.ui.wrap_marker);
+    let tab_width = app.config.ui.tab_width;
+    let text_width = (file_area.width.saturating_sub(2) as usize)
+        .saturating_sub(1)
+        .saturating_sub(gutter_width + 1);
+    let available_height = file_area.height.saturating_sub(2) as usize;
+    
+    let syntax = highlighted(app, lines);
+    let first = app.diff_scroll_offset.min(lines.len().saturating_sub(1));
+    let mut visible = Vec::new();
+    for (idx, line) in lines.iter().enumerate().skip(first) {
+        if visible.len() >= available_height {
+            break;
+        }
+        let row = if created { (Some(idx), None) } else { (None, Some(idx)) };
+        visible.extend(create_highlighted_lines(
+            gutter_numbers(idx, row, dual_line_numbers),
+            &[(line.clone(), false)],
+            text_width,
+            gutter_width,
+            max_line_digits,
+            wrap_marker,
+            tab_width,
+            line_style,
+            line_style,
+            &char_styles(syntax.as_ref().and_then(|lines| lines.get(idx)), tab_width),
+        ));
+    }
+    visible.truncate(available_height);
+    
+    let (source_title, dest_title) = panel_titles(app);
+    let (file_title, missing_title) = if created { (source_title, dest_title) } else { (dest_title, source_title) };
+    f.render_widget(
+        Paragraph::new(visible).block(Block::default().borders(Borders::ALL).title(file_title)),
+        file_area,
+    );
+    f.render_widget(
+        Paragraph::new(vec![Line::raw(""), Line::styled("(file does not exist)", Styles::fold_indicator())])
+            .alignment(Alignment::Center)
+            .block(Block::default().borders(Borders::ALL).title(missing_title)),
+        missing_area,
+    );
+}
+
+/// Handle input for the side-by-side view (arrows and pages scroll the diff)
+pub fn handle_side_by_side_event(app: &mut App, event: Event, _registry: &RectRegistry) {
+    if handle_bookmark_key(app, &event) {
+        return;
+    }
+    
+    // While reviewing, a verdict moves on to the next file
+    if let Some(verdict) = review_verdict(app, &event) {
+        app.review_selected(verdict);
+        return;
+    }
+    
+    let Some(event) = view_event(app, event).and_then(|event| handle_entry_event(app, event)) else {
+        return;
+    };
+    
+    match event {
+        AppEvent::SelectPrevious => app.scroll_up(1),
+        AppEvent::SelectNext => app.scroll_down(1),
+        AppEvent::PageUp => app.scroll_up(10),
+        AppEvent::PageDown => app.scroll_down(10),
+        AppEvent::ScrollUp(amount) => app.scroll_up(amount),
+        AppEvent::ScrollDown(amount) => app.scroll_down(amount),
+        // Enter first expands a folded region on screen
+        AppEvent::ToggleSideBySide if app.expand_visible_fold() => {}
+        AppEvent::ToggleSideBySide => app.toggle_side_by_side(),
+        AppEvent::ToggleFold => app.toggle_fold(),
+        AppEvent::ToggleBlame => app.toggle_blame(),
+        AppEvent::CopyDiff(what) => app.copy_diff(what),
+        AppEvent::EditLine => app.request_line_edit(),
+        AppEvent::TakeHunk(from) => app.take_hunk(from),
+        AppEvent::MoreContext => app.adjust_context_lines(true),
+        AppEvent::LessContext => app.adjust_context_lines(false),
+        // Esc acknowledges a sticky error before leaving the view
+        AppEvent::Back if app.toasts.dismiss_sticky() => {}
+        AppEvent::Back => app.close_side_by_side(),
+        _ => {}
+    }
+}
+
+/// Bookmark keys: `m` then a letter marks the scroll position, `'` then a letter scrolls back to
+/// it; `m m` opens the entry menu (as `m` does elsewhere). Returns true if the key was used
+fn handle_bookmark_key(app: &mut App, event: &Event) -> bool {
+    let Event::Key(key) = event else {
+        return false;
+    };
+    if key.kind != KeyEventKind::Press || key.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) {
+        return false;
+    }
+    match (app.bookmark_prompt.take(), key.code) {
+        (Some(BookmarkPrompt::Set), KeyCode::Char('m')) => false,
+        (Some(prompt), KeyCode::Char(letter)) if letter.is_ascii_alphabetic() => {
+            app.finish_bookmark(prompt, letter);
+            true
+        }
+        // Any other key cancels the prompt
+        (Some(_), _) => true,
+        (None, KeyCode::Char('m')) => {
+            app.start_bookmark(BookmarkPrompt::Set);
+            true
+        }
+        (None, KeyCode::Char('\'')) => {
+            app.start_bookmark(BookmarkPrompt::Jump);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Blame column beside the destination panel: commit, author and date of the line starting
+/// each row, uncommitted edits highlighted (syncing would overwrite them with nothing to restore)
+fn render_blame(f: &mut Frame, blame: &[BlameLine], dest_line_rows: &[Option<usize>], area: Rect) {
+    let width = BLAME_WIDTH.saturating_sub(2) as usize;
+    let rows: Vec<Line> = dest_line_rows
+        .iter()
+        .map(|row| match row.and_then(|line| blame.get(line)) {
+            Some(line) if line.is_uncommitted() => Line::styled(format!("{:width$}", "uncommitted edit"), Styles::blame_uncommitted()),
+            Some(line) => {
+                let date = format_timestamp(line.time);
+                let author: String = line.author.chars().take(10).collect();
+                Line::styled(format!("{} {:10} {}", line.commit, author, &date[..10]), Styles::blame())
+            }
+            None => Line::raw(""),
+        })
+        .collect();
+    
+    let uncommitted = blame.iter().filter(|line| line.is_uncommitted()).count();
+    let title = match uncommitted {
+        0 => " Blame ".to_string(),
+        n => format!(" Blame: {} uncommitted ", n),
+    };
+    f.render_widget(Paragraph::new(rows).block(Block::default().borders(Borders::ALL).title(title)), area);
+}
+
 /// Get shortened path for display
 fn short_path(path: &std::path::Path) -> String {
     let components: Vec<_> = path.components().rev().take(3).collect();
@@ -104,6 +550,11 @@ fn short_path(path: &std::path::Path) -> String {
 }
 
 /// Build aligned lines for source and destination
+/// Unchanged runs longer than the surrounding `context_lines` are folded (unless expanded)
+/// `fold_rows` receives the (row, region) of every fold indicator; a region is the aligned
+/// index where its unchanged run starts, and `row_starts` the entry and destination line of each row
+/// Lines longer than `long_lines.chars` are cut behind a marker unless expanded
+#[allow(clippy::too_many_arguments)]
 fn build_aligned_lines(
     aligned: &[LineAlignment],
     source_lines: &[String],
@@ -111,120 +562,172 @@ fn build_aligned_lines(
     text_width: usize,
     gutter_width: usize,
     max_line_digits: usize,
-    app: &App,
+    wrap_marker: WrapMarker,
+    tab_width: usize,
+    dual_line_numbers: bool,
+    context_lines: usize,
+    (source_ignored, dest_ignored): (&[bool], &[bool]),
+    (source_moved, dest_moved): (&[bool], &[bool]),
+    syntax: Syntax,
+    folds: &FoldState,
+    mut long_lines: LongLines,
+    fold_rows: &mut Vec<(usize, usize)>,
+    row_starts: &mut RowStarts,
 ) -> (Vec<Line<'static>>, Vec<Line<'static>>) {
     let mut source_visible: Vec<Line<'static>> = Vec::new();
     let mut dest_visible: Vec<Line<'static>> = Vec::new();
 
-    const CONTEXT_LINES: usize = 3;
-
+    // Lines inside ignore regions never count as changes
+    let is_same = |src_idx: usize, dest_idx: usize| -> bool {
+        source_lines[src_idx] == dest_lines[dest_idx] || (source_ignored[src_idx] && dest_ignored[dest_idx])
+    };
     let has_changes = |line_type: &LineAlignment| -> bool {
         match line_type {
-            LineAlignment::Both(src_idx, dest_idx) => source_lines[*src_idx] != dest_lines[*dest_idx],
-            LineAlignment::SourceOnly(_) | LineAlignment::DestOnly(_) => true,
+            LineAlignment::Both(src_idx, dest_idx) => !is_same(*src_idx, *dest_idx),
+            LineAlignment::SourceOnly(src_idx) => !source_ignored[*src_idx],
+            LineAlignment::DestOnly(dest_idx) => !dest_ignored[*dest_idx],
         }
     };
 
     let mut i = 0;
     while i < aligned.len() {
-        // Check for foldable unchanged regions
-        if app.fold_unchanged {
-            let mut unchanged_count = 0;
-            let mut j = i;
-            while j < aligned.len() {
-                match &aligned[j] {
-                    LineAlignment::Both(src_idx, dest_idx) => {
-                        if source_lines[*src_idx] == dest_lines[*dest_idx] {
-                            unchanged_count += 1;
-                            j += 1;
-                        } else {
-                            break;
-                        }
+        // Measure the unchanged run starting here; runs are handled whole so an expanded
+        // region can't re-fold from its middle
+        let mut unchanged_count = 0;
+        let mut j = i;
+        while j < aligned.len() {
+            match &aligned[j] {
+                LineAlignment::Both(src_idx, dest_idx) => {
+                    if is_same(*src_idx, *dest_idx) {
+                        unchanged_count += 1;
+                        j += 1;
+                    } else {
+                        break;
                     }
-                    _ => break,
                 }
+                _ => break,
             }
+        }
+
+        let has_change_before = i > 0 && has_changes(&aligned[i - 1]);
+        let has_change_after =
+            (i + unchanged_count) < aligned.len() && has_changes(&aligned[i + unchanged_count]);
+
+        let min_lines_for_fold = if has_change_before && has_change_after {
+            context_lines * 2 + 1
+        } else if has_change_before || has_change_after {
+            context_lines + 1
+        } else {
+            usize::MAX
+        };
 
-            let has_change_before = i > 0 && has_changes(&aligned[i - 1]);
-            let has_change_after =
-                (i + unchanged_count) < aligned.len() && has_changes(&aligned[i + unchanged_count]);
+        if unchanged_count > 0 && !(folds.is_folded(i) && unchanged_count > min_lines_for_fold) {
+            for (index, entry) in aligned.iter().enumerate().take(i + unchanged_count).skip(i) {
+                if let LineAlignment::Both(src_idx, dest_idx) = entry {
+                    row_starts.mark(dest_visible.len(), index, Some(*dest_idx));
+                    add_unchanged_line(
+                        &mut source_visible,
+                        &mut dest_visible,
+                        *src_idx,
+                        *dest_idx,
+                        source_lines,
+                        dest_lines,
+                        text_width,
+                        gutter_width,
+                        max_line_digits,
+                        wrap_marker,
+                        tab_width,
+                        dual_line_numbers,
+                        &mut long_lines,
+                        syntax,
+                        index,
+                    );
+                }
+            }
+            
+            i += unchanged_count;
+            continue;
+        }
 
-            let min_lines_for_fold = if has_change_before && has_change_after {
-                CONTEXT_LINES * 2 + 1
-            } else if has_change_before || has_change_after {
-                CONTEXT_LINES + 1
+        if unchanged_count > min_lines_for_fold {
+            let context_before = if has_change_before {
+                context_lines.min(unchanged_count)
             } else {
-                usize::MAX
+                0
             };
+            let context_after = if has_change_after {
+                context_lines.min(unchanged_count - context_before)
+            } else {
+                0
+            };
+            let hidden_count = unchanged_count - context_before - context_after;
 
-            if unchanged_count > min_lines_for_fold {
-                let context_before = if has_change_before {
-                    CONTEXT_LINES.min(unchanged_count)
-                } else {
-                    0
-                };
-                let context_after = if has_change_after {
-                    CONTEXT_LINES.min(unchanged_count - context_before)
-                } else {
-                    0
-                };
-                let hidden_count = unchanged_count - context_before - context_after;
-
-                // Show context before
-                for k in i..(i + context_before) {
-                    if let LineAlignment::Both(src_idx, dest_idx) = &aligned[k] {
-                        add_unchanged_line(
-                            &mut source_visible,
-                            &mut dest_visible,
-                            *src_idx,
-                            *dest_idx,
-                            source_lines,
-                            dest_lines,
-                            text_width,
-                            gutter_width,
-                            max_line_digits,
-                        );
-                    }
+            // Show context before
+            for (index, entry) in aligned.iter().enumerate().take(i + context_before).skip(i) {
+                if let LineAlignment::Both(src_idx, dest_idx) = entry {
+                    row_starts.mark(dest_visible.len(), index, Some(*dest_idx));
+                    add_unchanged_line(
+                        &mut source_visible,
+                        &mut dest_visible,
+                        *src_idx,
+                        *dest_idx,
+                        source_lines,
+                        dest_lines,
+                        text_width,
+                        gutter_width,
+                        max_line_digits,
+                        wrap_marker,
+                        tab_width,
+                        dual_line_numbers,
+                        &mut long_lines,
+                        syntax,
+                        index,
+                    );
                 }
+            }
 
-                // Show fold indicator
-                if hidden_count > 0 {
-                    let indicator = create_fold_indicator(hidden_count, text_width, gutter_width);
-                    source_visible.push(indicator.clone());
-                    dest_visible.push(indicator);
-                }
+            // Show fold indicator
+            if hidden_count > 0 {
+                fold_rows.push((source_visible.len(), i));
+                let indicator = create_fold_indicator(hidden_count, text_width, gutter_width);
+                source_visible.push(indicator.clone());
+                dest_visible.push(indicator);
+            }
 
-                // Show context after
-                let after_start = i + unchanged_count - context_after;
-                for k in after_start..(i + unchanged_count) {
-                    if let LineAlignment::Both(src_idx, dest_idx) = &aligned[k] {
-                        add_unchanged_line(
-                            &mut source_visible,
-                            &mut dest_visible,
-                            *src_idx,
-                            *dest_idx,
-                            source_lines,
-                            dest_lines,
-                            text_width,
-                            gutter_width,
-                            max_line_digits,
-                        );
-                    }
+            // Show context after
+            let after_start = i + unchanged_count - context_after;
+            for (index, entry) in aligned.iter().enumerate().take(i + unchanged_count).skip(after_start) {
+                if let LineAlignment::Both(src_idx, dest_idx) = entry {
+                    row_starts.mark(dest_visible.len(), index, Some(*dest_idx));
+                    add_unchanged_line(
+                        &mut source_visible,
+                        &mut dest_visible,
+                        *src_idx,
+                        *dest_idx,
+                        source_lines,
+                        dest_lines,
+                        text_width,
+                        gutter_width,
+                        max_line_digits,
+                        wrap_marker,
+                        tab_width,
+                        dual_line_numbers,
+                        &mut long_lines,
+                        syntax,
+                        index,
+                    );
                 }
-
-                i += unchanged_count;
-                continue;
             }
+
+            i += unchanged_count;
+            continue;
         }
 
         // Process the current line normally
         match &aligned[i] {
             LineAlignment::Both(src_idx, dest_idx) => {
-                let src_line = &source_lines[*src_idx];
-                let dest_line = &dest_lines[*dest_idx];
-                let is_same = src_line == dest_line;
-
-                if is_same {
+                if is_same(*src_idx, *dest_idx) {
+                    row_starts.mark(dest_visible.len(), i, Some(*dest_idx));
                     add_unchanged_line(
                         &mut source_visible,
                         &mut dest_visible,
@@ -235,8 +738,15 @@ fn build_aligned_lines(
                         text_width,
                         gutter_width,
                         max_line_digits,
+                        wrap_marker,
+                        tab_width,
+                        dual_line_numbers,
+                        &mut long_lines,
+                        syntax,
+                        i,
                     );
                 } else {
+                    row_starts.mark(dest_visible.len(), i, Some(*dest_idx));
                     add_modified_line(
                         &mut source_visible,
                         &mut dest_visible,
@@ -247,29 +757,51 @@ fn build_aligned_lines(
                         text_width,
                         gutter_width,
                         max_line_digits,
+                        wrap_marker,
+                        tab_width,
+                        dual_line_numbers,
+                        &mut long_lines,
+                        syntax,
+                        i,
                     );
                 }
             }
             LineAlignment::SourceOnly(src_idx) => {
+                row_starts.mark(dest_visible.len(), i, None);
                 add_source_only_line(
                     &mut source_visible,
                     &mut dest_visible,
                     *src_idx,
                     source_lines,
+                    OneSided::of(source_ignored[*src_idx], source_moved[*src_idx]),
                     text_width,
                     gutter_width,
                     max_line_digits,
+                    wrap_marker,
+                    tab_width,
+                    dual_line_numbers,
+                    &mut long_lines,
+                    syntax,
+                    i,
                 );
             }
             LineAlignment::DestOnly(dest_idx) => {
+                row_starts.mark(dest_visible.len(), i, Some(*dest_idx));
                 add_dest_only_line(
                     &mut source_visible,
                     &mut dest_visible,
                     *dest_idx,
                     dest_lines,
+                    OneSided::of(dest_ignored[*dest_idx], dest_moved[*dest_idx]),
                     text_width,
                     gutter_width,
                     max_line_digits,
+                    wrap_marker,
+                    tab_width,
+                    dual_line_numbers,
+                    &mut long_lines,
+                    syntax,
+                    i,
                 );
             }
         }
@@ -277,9 +809,12 @@ fn build_aligned_lines(
         i += 1;
     }
 
+    row_starts.entries.resize(dest_visible.len(), None);
+    row_starts.dest_lines.resize(dest_visible.len(), None);
     (source_visible, dest_visible)
 }
 
+#[allow(clippy::too_many_arguments)]
 fn add_unchanged_line(
     source_visible: &mut Vec<Line<'static>>,
     dest_visible: &mut Vec<Line<'static>>,
@@ -290,31 +825,46 @@ fn add_unchanged_line(
     text_width: usize,
     gutter_width: usize,
     max_line_digits: usize,
+    wrap_marker: WrapMarker,
+    tab_width: usize,
+    dual_line_numbers: bool,
+    long_lines: &mut LongLines,
+    syntax: Syntax,
+    index: usize,
 ) {
-    let src_line = &source_lines[src_idx];
-    let dest_line = &dest_lines[dest_idx];
+    let (src_line, src_hidden) = long_lines.window(index, &source_lines[src_idx]);
+    let (dest_line, dest_hidden) = long_lines.window(index, &dest_lines[dest_idx]);
+    let row = source_visible.len();
 
     // Create source line (may wrap to multiple lines)
-    let src_wrapped = create_highlighted_lines(
-        src_idx + 1,
-        &[(src_line.clone(), false)],
+    let mut src_wrapped = create_highlighted_lines(
+        gutter_numbers(src_idx, (Some(src_idx), Some(dest_idx)), dual_line_numbers),
+        &[(src_line.to_string(), false)],
         text_width,
         gutter_width,
         max_line_digits,
+        wrap_marker,
+        tab_width,
         ratatui::style::Style::default(),
         ratatui::style::Style::default(),
+        &syntax.source(src_idx, tab_width),
     );
     
     // Create destination line (may wrap to multiple lines)
-    let dest_wrapped = create_highlighted_lines(
-        dest_idx + 1,
-        &[(dest_line.clone(), false)],
+    let mut dest_wrapped = create_highlighted_lines(
+        gutter_numbers(dest_idx, (Some(src_idx), Some(dest_idx)), dual_line_numbers),
+        &[(dest_line.to_string(), false)],
         text_width,
         gutter_width,
         max_line_digits,
+        wrap_marker,
+        tab_width,
         ratatui::style::Style::default(),
         ratatui::style::Style::default(),
+        &syntax.dest(dest_idx, tab_width),
     );
+    long_lines.mark(index, row, &mut src_wrapped, src_hidden, text_width, gutter_width);
+    long_lines.mark(index, row, &mut dest_wrapped, dest_hidden, text_width, gutter_width);
     
     source_visible.extend(src_wrapped.clone());
     dest_visible.extend(dest_wrapped.clone());
@@ -336,6 +886,7 @@ fn add_unchanged_line(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn add_modified_line(
     source_visible: &mut Vec<Line<'static>>,
     dest_visible: &mut Vec<Line<'static>>,
@@ -346,34 +897,50 @@ fn add_modified_line(
     text_width: usize,
     gutter_width: usize,
     max_line_digits: usize,
+    wrap_marker: WrapMarker,
+    tab_width: usize,
+    dual_line_numbers: bool,
+    long_lines: &mut LongLines,
+    syntax: Syntax,
+    index: usize,
 ) {
-    let src_line = &source_lines[src_idx];
-    let dest_line = &dest_lines[dest_idx];
+    // Word diffs cover the visible windows only, so a huge line costs no more than its window
+    let (src_line, src_hidden) = long_lines.window(index, &source_lines[src_idx]);
+    let (dest_line, dest_hidden) = long_lines.window(index, &dest_lines[dest_idx]);
+    let row = source_visible.len();
 
     // Source line with word-level highlighting
     let src_diffs = compute_word_diff_source(src_line, dest_line);
-    let src_wrapped = create_highlighted_lines(
-        src_idx + 1,
+    let mut src_wrapped = create_highlighted_lines(
+        gutter_numbers(src_idx, (Some(src_idx), Some(dest_idx)), dual_line_numbers),
         &src_diffs,
         text_width,
         gutter_width,
         max_line_digits,
+        wrap_marker,
+        tab_width,
         Styles::side_by_side_source_modified_bg(),
         Styles::side_by_side_source_highlight(),
+        &syntax.source(src_idx, tab_width),
     );
+    long_lines.mark(index, row, &mut src_wrapped, src_hidden, text_width, gutter_width);
     source_visible.extend(src_wrapped.clone());
 
     // Destination line with word-level highlighting
     let dest_diffs = compute_word_diff_dest(dest_line, src_line);
-    let dest_wrapped = create_highlighted_lines(
-        dest_idx + 1,
+    let mut dest_wrapped = create_highlighted_lines(
+        gutter_numbers(dest_idx, (Some(src_idx), Some(dest_idx)), dual_line_numbers),
         &dest_diffs,
         text_width,
         gutter_width,
         max_line_digits,
+        wrap_marker,
+        tab_width,
         Styles::side_by_side_dest_modified_bg(),
         Styles::side_by_side_dest_highlight(),
+        &syntax.dest(dest_idx, tab_width),
     );
+    long_lines.mark(index, row, &mut dest_wrapped, dest_hidden, text_width, gutter_width);
     dest_visible.extend(dest_wrapped.clone());
 
     // Ensure both sides have the same number of lines by padding with blank lines
@@ -393,27 +960,110 @@ fn add_modified_line(
     }
 }
 
+/// Cuts lines longer than `chars` characters behind a "line continues" marker as rows are built
+struct LongLines<'a> {
+    /// Characters shown before a line is cut off (0 = never)
+    chars: usize,
+    /// Lines expanded with Enter are shown whole
+    folds: &'a FoldState,
+    /// (row, aligned index) of every marker
+    rows: &'a mut Vec<(usize, usize)>,
+}
+
+impl LongLines<'_> {
+    /// Visible part of the line at aligned `index` and the number of characters cut off
+    fn window<'l>(&self, index: usize, line: &'l str) -> (&'l str, usize) {
+        if self.folds.is_line_expanded(index) {
+            (line, 0)
+        } else {
+            long_line_window(line, self.chars)
+        }
+    }
+    
+    /// Append the marker of a truncated line to its wrapped rows, which start at `row`
+    fn mark(
+        &mut self,
+        index: usize,
+        row: usize,
+        wrapped: &mut Vec<Line<'static>>,
+        hidden_chars: usize,
+        text_width: usize,
+        gutter_width: usize,
+    ) {
+        if hidden_chars == 0 {
+            return;
+        }
+        self.rows.push((row + wrapped.len(), index));
+        wrapped.push(create_continuation_marker(hidden_chars, text_width, gutter_width));
+    }
+}
+
+/// How a line present on one side only is shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OneSided {
+    /// Removed or added
+    Changed,
+    /// Inside an ignore region: without highlighting
+    Ignored,
+    /// Part of a block that moved within the file: in the moved color
+    Moved,
+}
+
+impl OneSided {
+    fn of(ignored: bool, moved: bool) -> Self {
+        match (ignored, moved) {
+            (true, _) => Self::Ignored,
+            (false, true) => Self::Moved,
+            (false, false) => Self::Changed,
+        }
+    }
+    
+    /// (base, highlight) styles, given those of a changed line
+    fn styles(self, changed: (Style, Style)) -> (Style, Style) {
+        match self {
+            Self::Changed => changed,
+            Self::Ignored => (Style::default(), Style::default()),
+            Self::Moved => (Styles::side_by_side_moved_bg(), Styles::side_by_side_moved_bg()),
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 fn add_source_only_line(
     source_visible: &mut Vec<Line<'static>>,
     dest_visible: &mut Vec<Line<'static>>,
     src_idx: usize,
     source_lines: &[String],
+    kind: OneSided,
     text_width: usize,
     gutter_width: usize,
     max_line_digits: usize,
+    wrap_marker: WrapMarker,
+    tab_width: usize,
+    dual_line_numbers: bool,
+    long_lines: &mut LongLines,
+    syntax: Syntax,
+    index: usize,
 ) {
-    let src_line = &source_lines[src_idx];
+    let (src_line, src_hidden) = long_lines.window(index, &source_lines[src_idx]);
+    let row = source_visible.len();
+    let (base_style, highlight_style) =
+        kind.styles((Styles::side_by_side_source_modified_bg(), Styles::side_by_side_source_highlight()));
     
     // Create source line (may wrap to multiple lines)
-    let src_wrapped = create_highlighted_lines(
-        src_idx + 1,
-        &[(src_line.clone(), true)],
+    let mut src_wrapped = create_highlighted_lines(
+        gutter_numbers(src_idx, (Some(src_idx), None), dual_line_numbers),
+        &[(src_line.to_string(), kind == OneSided::Changed)],
         text_width,
         gutter_width,
         max_line_digits,
-        Styles::side_by_side_source_modified_bg(),
-        Styles::side_by_side_source_highlight(),
+        wrap_marker,
+        tab_width,
+        base_style,
+        highlight_style,
+        &syntax.source(src_idx, tab_width),
     );
+    long_lines.mark(index, row, &mut src_wrapped, src_hidden, text_width, gutter_width);
     
     source_visible.extend(src_wrapped.clone());
     
@@ -423,27 +1073,42 @@ fn add_source_only_line(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn add_dest_only_line(
     source_visible: &mut Vec<Line<'static>>,
     dest_visible: &mut Vec<Line<'static>>,
     dest_idx: usize,
     dest_lines: &[String],
+    kind: OneSided,
     text_width: usize,
     gutter_width: usize,
     max_line_digits: usize,
+    wrap_marker: WrapMarker,
+    tab_width: usize,
+    dual_line_numbers: bool,
+    long_lines: &mut LongLines,
+    syntax: Syntax,
+    index: usize,
 ) {
-    let dest_line = &dest_lines[dest_idx];
+    let (dest_line, dest_hidden) = long_lines.window(index, &dest_lines[dest_idx]);
+    let row = dest_visible.len();
+    let (base_style, highlight_style) =
+        kind.styles((Styles::side_by_side_dest_modified_bg(), Styles::side_by_side_dest_highlight()));
     
     // Create destination line (may wrap to multiple lines)
-    let dest_wrapped = create_highlighted_lines(
-        dest_idx + 1,
-        &[(dest_line.clone(), true)],
+    let mut dest_wrapped = create_highlighted_lines(
+        gutter_numbers(dest_idx, (None, Some(dest_idx)), dual_line_numbers),
+        &[(dest_line.to_string(), kind == OneSided::Changed)],
         text_width,
         gutter_width,
         max_line_digits,
-        Styles::side_by_side_dest_modified_bg(),
-        Styles::side_by_side_dest_highlight(),
+        wrap_marker,
+        tab_width,
+        base_style,
+        highlight_style,
+        &syntax.dest(dest_idx, tab_width),
     );
+    long_lines.mark(index, row, &mut dest_wrapped, dest_hidden, text_width, gutter_width);
     
     dest_visible.extend(dest_wrapped.clone());
     
@@ -453,6 +1118,15 @@ fn add_dest_only_line(
     }
 }
 
+/// Gutter numbers of line `idx` of one side, in the row of the (source, destination) lines `row`
+fn gutter_numbers(idx: usize, row: (Option<usize>, Option<usize>), dual_line_numbers: bool) -> GutterNumbers {
+    if dual_line_numbers {
+        GutterNumbers::Dual(row.0.map(|i| i + 1), row.1.map(|i| i + 1))
+    } else {
+        GutterNumbers::Single(idx + 1)
+    }
+}
+
 /// Split text into "word+whitespace" units where whitespace is attached to the preceding word
 fn split_into_word_units(text: &str) -> Vec<String> {
     let mut units = Vec::new();
@@ -490,24 +1164,34 @@ fn split_into_word_units(text: &str) -> Vec<String> {
     units
 }
 
+/// Wrapped, highlighted rows of one line; rows after the first carry `wrap_marker` in the gutter
+/// Tabs are expanded to stops every `tab_width` columns (0 keeps them as they are); `syntax`
+/// colors the characters as drawn (see `char_styles`)
+#[allow(clippy::too_many_arguments)]
 fn create_highlighted_lines(
-    line_num: usize,
+    numbers: GutterNumbers,
     diffs: &[(String, bool)],
     text_width: usize,
     gutter_width: usize,
     max_line_digits: usize,
+    wrap_marker: WrapMarker,
+    tab_width: usize,
     base_style: ratatui::style::Style,
     highlight_style: ratatui::style::Style,
+    syntax: &[Style],
 ) -> Vec<Line<'static>> {
     let mut lines = Vec::new();
-    let gutter = format!("{:width$} ", line_num, width = max_line_digits);
-    let continuation_gutter = " ".repeat(gutter_width);
+    let gutter = numbers.format(max_line_digits);
+    let continuation_gutter = wrap_marker.gutter(gutter_width);
     
     let mut current_line_spans: Vec<Span> = Vec::new();
     let mut current_width = 0;
     let mut is_first_line = true;
+    // Characters of the line placed so far
+    let mut column = 0;
 
-    for (text, is_changed) in diffs {
+    // Expanded before measuring, so widths and highlighted parts match what is drawn
+    for (text, is_changed) in expand_tabs(diffs, tab_width).iter() {
         let style = if *is_changed { highlight_style } else { base_style };
         
         // Split text into "word+whitespace" units
@@ -523,7 +1207,7 @@ fn create_highlighted_lines(
                 let gutter_span = if is_first_line {
                     Span::styled(gutter.clone(), Styles::gutter())
                 } else {
-                    Span::styled(continuation_gutter.clone(), Styles::gutter())
+                    Span::styled(continuation_gutter.clone(), Styles::wrap_marker())
                 };
                 
                 // Calculate content width before moving
@@ -563,7 +1247,7 @@ fn create_highlighted_lines(
                         let gutter_span = if is_first_line {
                             Span::styled(gutter.clone(), Styles::gutter())
                         } else {
-                            Span::styled(continuation_gutter.clone(), Styles::gutter())
+                            Span::styled(continuation_gutter.clone(), Styles::wrap_marker())
                         };
                         
                         let line_content_width: usize = current_line_spans.iter()
@@ -590,14 +1274,16 @@ fn create_highlighted_lines(
                     let take_count = remaining.min(unit_chars.len() - char_idx);
                     let segment: String = unit_chars[char_idx..char_idx + take_count].iter().collect();
                     
-                    current_line_spans.push(Span::styled(segment, style));
+                    current_line_spans.extend(syntax_spans(segment, style, &syntax[column.min(syntax.len())..]));
                     current_width += take_count;
+                    column += take_count;
                     char_idx += take_count;
                 }
             } else {
                 // Unit fits, add it to current line
-                current_line_spans.push(Span::styled(unit, style));
+                current_line_spans.extend(syntax_spans(unit, style, &syntax[column.min(syntax.len())..]));
                 current_width += unit_width;
+                column += unit_width;
             }
         }
     }
@@ -607,7 +1293,7 @@ fn create_highlighted_lines(
         let gutter_span = if is_first_line {
             Span::styled(gutter.clone(), Styles::gutter())
         } else {
-            Span::styled(continuation_gutter.clone(), Styles::gutter())
+            Span::styled(continuation_gutter.clone(), Styles::wrap_marker())
         };
         
         // Calculate content width before moving
@@ -642,6 +1328,23 @@ fn create_highlighted_lines(
     lines
 }
 
+/// Spans of `text` in `style`, split where the syntax colors of its characters change; the
+/// diff background of `style` stays on top
+fn syntax_spans(text: String, style: Style, syntax: &[Style]) -> Vec<Span<'static>> {
+    if syntax.is_empty() {
+        return vec![Span::styled(text, style)];
+    }
+    let mut spans: Vec<Span<'static>> = Vec::new();
+    for (i, c) in text.chars().enumerate() {
+        let char_style = syntax.get(i).map_or(style, |syntax| syntax.patch(style));
+        match spans.last_mut() {
+            Some(last) if last.style == char_style => last.content.to_mut().push(c),
+            _ => spans.push(Span::styled(c.to_string(), char_style)),
+        }
+    }
+    spans
+}
+
 fn create_blank_line(text_width: usize, gutter_width: usize) -> Line<'static> {
     Line::from(vec![
         Span::styled(" ".repeat(gutter_width), Styles::gutter()),
@@ -661,3 +1364,88 @@ fn create_fold_indicator(hidden_count: usize, text_width: usize, gutter_width: u
         Span::raw(" "), // Right margin
     ])
 }
+
+fn create_continuation_marker(hidden_chars: usize, text_width: usize, gutter_width: usize) -> Line<'static> {
+    let text = format!("… line continues ({} chars)", hidden_chars);
+    let padding_len = text_width.saturating_sub(text.chars().count());
+    
+    Line::from(vec![
+        Span::styled(" ".repeat(gutter_width), Styles::gutter()),
+        Span::styled(text, Styles::fold_indicator()),
+        Span::raw(" ".repeat(padding_len)),
+        Span::raw(" "), // Right margin
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::PathBuf;
+    use tui_components::utilities::snapshot::{assert_snapshot, render_to_text};
+    use crate::operations::DiffEntry;
+    
+    fn lines(text: &[&str]) -> Vec<String> {
+        text.iter().map(|line| line.to_string()).collect()
+    }
+    
+    /// App in an empty temporary workspace showing `source` against `dest` as a modified file
+    fn app_with_pair(name: &str, source: Vec<String>, dest: Vec<String>) -> (App, PathBuf) {
+        let root = std::env::temp_dir().join(format!("sync-manager-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        let mut app = App::open(root.clone()).unwrap();
+        app.shared_to_project_diffs = vec![DiffEntry {
+            source_path: PathBuf::from("_shared/src/lib.rs"),
+            destination_path: PathBuf::from(".project/src/lib.rs"),
+            ..DiffEntry::for_test("src/lib.rs", "")
+        }];
+        app.side_by_side_source = Some(source);
+        app.side_by_side_dest = Some(dest);
+        (app, root)
+    }
+    
+    #[test]
+    fn test_side_by_side_snapshot() {
+        let source = lines(&[
+            "fn main() {",
+            "    let name = \"shared\";",
+            "    println!(\"hello {}\", name);",
+            "}",
+            "",
+            "fn helper() -> u32 {",
+            "\t42",
+            "}",
+        ]);
+        let dest = lines(&[
+            "fn main() {",
+            "    let name = \"project\";",
+            "    println!(\"hello {}\", name);",
+            "}",
+            "",
+            "fn helper() -> u32 {",
+            "\t42",
+            "}",
+            "// added in the project, long enough to wrap in its panel",
+        ]);
+        let (app, root) = app_with_pair("side-by-side", source, dest);
+        
+        let screen = render_to_text(80, 14, |f| render_side_by_side(f, &app, f.area()));
+        assert_snapshot("side_by_side", &screen);
+        fs::remove_dir_all(&root).ok();
+    }
+    
+    #[test]
+    fn test_syntax_colors_keep_the_diff_background() {
+        let bg = Styles::side_by_side_source_modified_bg();
+        let keyword = Style::default().fg(ratatui::style::Color::Red);
+        let syntax = vec![keyword, keyword, Style::default()];
+        let spans = syntax_spans("fn x".to_string(), bg, &syntax);
+        
+        assert_eq!(spans[0].content, "fn");
+        assert_eq!(spans[0].style, keyword.bg(bg.bg.unwrap()));
+        // Characters without a syntax color, and past the highlighted ones, keep the plain style
+        assert_eq!(spans[1].content, " x");
+        assert_eq!(spans[1].style, bg);
+        assert_eq!(spans.len(), 2);
+    }
+}
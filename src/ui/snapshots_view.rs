@@ -0,0 +1,130 @@
+// Snapshots View
+// Snapshot list and the files changed since the compared snapshot
+
+use crossterm::event::Event;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    Frame,
+};
+use std::time::SystemTime;
+use tui_components::{ColumnWidth, ListCell, ListColumn, ListPanelStyles, ListRow, RectRegistry};
+
+use crate::core::{App, AppEvent, AppTab, FocusTarget};
+use crate::operations::{FileStatus, Snapshot, SnapshotChange};
+use crate::utilities::format_age;
+use super::input::view_event;
+use super::Styles;
+
+/// Sync the snapshot list panels with app state (columns, styles, titles)
+pub fn update_snapshot_lists(app: &mut App) {
+    let tab = &mut app.snapshots;
+    if tab.list.columns.is_empty() {
+        tab.list.title = "Snapshots".to_string();
+        tab.list.columns = vec![
+            ListColumn::new("Name", ColumnWidth::Fill),
+            ListColumn::new("Files", ColumnWidth::Fixed(6)).align_right(),
+            ListColumn::new("Age", ColumnWidth::Fixed(5)).align_right(),
+        ];
+        tab.list.empty_message = "No snapshots (n: create)".to_string();
+        tab.list.styles = list_styles();
+        
+        tab.changes_list.columns = vec![
+            ListColumn::new("", ColumnWidth::Fixed(1)),
+            ListColumn::new("Path", ColumnWidth::Fill),
+        ];
+        tab.changes_list.styles = list_styles();
+    }
+    
+    match &tab.comparison {
+        Some(comparison) => {
+            tab.changes_list.title = format!("Changed since {} ({})", comparison.name, comparison.changes.len());
+            tab.changes_list.empty_message = "No changes".to_string();
+        }
+        None => {
+            tab.changes_list.title = "Changes".to_string();
+            tab.changes_list.empty_message = "Enter: compare the selected snapshot".to_string();
+        }
+    }
+}
+
+fn list_styles() -> ListPanelStyles {
+    ListPanelStyles {
+        border_focused: Styles::border_focused(),
+        border_unfocused: Styles::border_unfocused(),
+        title_focused: Styles::title_focused(),
+        title_unfocused: Styles::title_unfocused(),
+        selected_focused: Styles::list_selected_focused(),
+        selected_unfocused: Styles::list_selected_unfocused(),
+        ..ListPanelStyles::default()
+    }
+}
+
+/// Render the Snapshots tab: snapshot list (left) and compared files (right)
+pub fn render_snapshots(f: &mut Frame, app: &App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(area);
+    
+    let tab = &app.snapshots;
+    let now = SystemTime::now();
+    tab.list.render(
+        f,
+        chunks[0],
+        tab.snapshots.len(),
+        |idx| snapshot_row(&tab.snapshots[idx], now),
+        app.focus.is_focused(FocusTarget::SnapshotList),
+    );
+    
+    let changes = tab.comparison.as_ref().map(|c| c.changes.as_slice()).unwrap_or_default();
+    tab.changes_list.render(f, chunks[1], changes.len(), |idx| change_row(&changes[idx]), false);
+}
+
+/// Handle input for the Snapshots tab
+pub fn handle_snapshots_event(app: &mut App, event: Event, _registry: &RectRegistry) {
+    let Some(event) = view_event(app, event) else {
+        return;
+    };
+    
+    let count = app.snapshots.snapshots.len();
+    let list = &mut app.snapshots.list;
+    match event {
+        AppEvent::SelectPrevious => list.select_previous(),
+        AppEvent::SelectNext => list.select_next(count),
+        AppEvent::PageUp => list.page_up(),
+        AppEvent::PageDown => list.page_down(count),
+        AppEvent::SelectFirst => list.select_first(),
+        AppEvent::SelectLast => list.select_last(count),
+        AppEvent::ToggleSideBySide => app.compare_selected_snapshot(),
+        AppEvent::NewSnapshot => app.request_create_snapshot(),
+        AppEvent::DeleteSnapshot => app.request_delete_snapshot(),
+        AppEvent::Refresh => match app.snapshots.reload() {
+            Ok(()) => app.toasts.info("Refreshed"),
+            Err(err) => app.report_error("Loading snapshots failed", &err),
+        },
+        // Esc acknowledges a sticky error before leaving the tab
+        AppEvent::Back if app.toasts.dismiss_sticky() => {}
+        AppEvent::Back => app.show_tab(AppTab::Sync),
+        _ => {}
+    }
+}
+
+fn snapshot_row(snapshot: &Snapshot, now: SystemTime) -> ListRow {
+    ListRow::new(vec![
+        ListCell::styled(snapshot.name.clone(), Styles::list_normal()),
+        ListCell::styled(snapshot.files.len().to_string(), Styles::status_unchanged()),
+        ListCell::styled(format_age(snapshot.created_time(), now), Styles::status_unchanged()),
+    ])
+}
+
+fn change_row(change: &SnapshotChange) -> ListRow {
+    let (icon, style) = match change.status {
+        FileStatus::Added => ("A", Styles::status_added()),
+        FileStatus::Deleted => ("D", Styles::status_deleted()),
+        _ => ("M", Styles::status_modified()),
+    };
+    ListRow::new(vec![
+        ListCell::styled(icon, style),
+        ListCell::styled(change.path.display().to_string(), Styles::list_normal()),
+    ])
+}
@@ -19,6 +19,58 @@ impl Styles {
         Style::default().fg(Color::Yellow)
     }
     
+    /// Badge shown in the header in read-only mode
+    pub fn read_only_badge() -> Style {
+        Style::default()
+            .fg(Color::White)
+            .bg(Color::Red)
+            .add_modifier(Modifier::BOLD)
+    }
+    
+    /// Badge shown in the header while the lists show the cached diff of the last run
+    pub fn stale_badge() -> Style {
+        Style::default()
+            .fg(Color::Black)
+            .bg(Color::Yellow)
+            .add_modifier(Modifier::BOLD)
+    }
+    
+    /// Note on the footer's border about a missing tool (git)
+    pub fn missing_tool_note() -> Style {
+        Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+    }
+    
+    /// Icon of a project's entries, in the project's color (cyan if the color can't be parsed)
+    pub fn project_icon(color: &str) -> Style {
+        Style::default()
+            .fg(color.parse().unwrap_or(Color::Cyan))
+            .add_modifier(Modifier::BOLD)
+    }
+    
+    /// Badge naming the project in the header
+    pub fn project_badge(color: &str) -> Style {
+        Style::default()
+            .fg(Color::Black)
+            .bg(color.parse().unwrap_or(Color::Cyan))
+            .add_modifier(Modifier::BOLD)
+    }
+    
+    /// Badge naming the project group the lists show
+    pub fn group_badge() -> Style {
+        Style::default()
+            .fg(Color::Black)
+            .bg(Color::White)
+            .add_modifier(Modifier::BOLD)
+    }
+    
+    /// Badge naming the machine profile in effect
+    pub fn profile_badge() -> Style {
+        Style::default()
+            .fg(Color::Black)
+            .bg(Color::Cyan)
+            .add_modifier(Modifier::BOLD)
+    }
+    
     // === List Items ===
     
     pub fn list_selected_focused() -> Style {
@@ -67,6 +119,21 @@ impl Styles {
         Style::default().fg(Color::Gray)
     }
     
+    /// Modified entry whose destination changed after its source
+    pub fn status_conflict() -> Style {
+        Style::default()
+            .fg(Color::LightRed)
+            .add_modifier(Modifier::BOLD)
+    }
+    
+    /// Marker of an entry that looks like a secret
+    pub fn status_secret() -> Style {
+        Style::default()
+            .fg(Color::White)
+            .bg(Color::Red)
+            .add_modifier(Modifier::BOLD)
+    }
+    
     // === Diff View Colors ===
     
     pub fn diff_added() -> Style {
@@ -120,11 +187,45 @@ impl Styles {
         Style::default().bg(Color::Rgb(r, g, b))
     }
     
+    /// Banner over a new file that syncing creates
+    pub fn sync_banner_create() -> Style {
+        Style::default()
+            .fg(Color::Black)
+            .bg(Color::Green)
+            .add_modifier(Modifier::BOLD)
+    }
+    
+    /// Banner over a file that syncing deletes
+    pub fn sync_banner_delete() -> Style {
+        Style::default()
+            .fg(Color::White)
+            .bg(Color::Red)
+            .add_modifier(Modifier::BOLD)
+    }
+    
+    /// Banner over a file gone from the source that syncing leaves in place
+    pub fn sync_banner_keep() -> Style {
+        Style::default()
+            .fg(Color::Black)
+            .bg(Color::Gray)
+    }
+    
+    /// Background for lines of a block that moved within the file (dim purple)
+    pub fn side_by_side_moved_bg() -> Style {
+        let (r, g, b) = crate::core::app_config::compiled::MOVED_BG;
+        Style::default().bg(Color::Rgb(r, g, b))
+    }
+    
     /// Gutter (line numbers) style
     pub fn gutter() -> Style {
         Style::default().fg(Color::Rgb(68, 68, 68))
     }
     
+    /// Gutter of the continuation rows of a wrapped line (dimmer than the line numbers)
+    pub fn wrap_marker() -> Style {
+        Style::default().fg(Color::Rgb(48, 48, 48))
+    }
+    
     /// Fold indicator style
     pub fn fold_indicator() -> Style {
         Style::default()
@@ -132,6 +233,21 @@ impl Styles {
             .add_modifier(Modifier::ITALIC)
     }
     
+    /// Commit of a past version shown in the side-by-side view
+    pub fn history_version() -> Style {
+        Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD)
+    }
+    
+    /// Blame of a committed destination line
+    pub fn blame() -> Style {
+        Style::default().fg(Color::Rgb(130, 130, 130))
+    }
+    
+    /// Blame of a destination line edited since the last commit
+    pub fn blame_uncommitted() -> Style {
+        Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+    }
+    
     // === Border Styles ===
     
     pub fn border_focused() -> Style {
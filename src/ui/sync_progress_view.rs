@@ -0,0 +1,196 @@
+// Sync Progress View
+// Popup shown while a sync runs, with its file count and current throughput, the startup
+// screen shown while the first scan of a launch runs, and the prompt shown when a sync or
+// background scan stops making progress
+
+use crossterm::event::{Event, KeyCode, KeyEventKind};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Gauge, Paragraph, Wrap},
+    Frame,
+};
+use tui_components::{centered_rect, RectRegistry};
+
+use crate::core::{App, AppEvent, EventHandler, ProjectScanState, StallChoice, WatchedOperation};
+use crate::utilities::{format_duration, format_size};
+use super::Styles;
+
+/// Registry handle name (HWND) of the sync progress popup
+pub const HWND_SYNC_PROGRESS: &str = "hwndSyncProgress";
+
+/// Registry handle name (HWND) of the stall prompt
+pub const HWND_STALL_PROMPT: &str = "hwndStallPrompt";
+
+/// Render the progress of the running sync over `area` and register it as an overlay layer
+pub fn render_sync_progress(f: &mut Frame, app: &App, area: Rect, registry: &mut RectRegistry) {
+    let Some(task) = &app.sync_task else {
+        return;
+    };
+    let snapshot = task.progress.snapshot();
+    let popup = centered_rect(50, 20, area);
+    
+    let title = match &app.active_group {
+        _ if task.cancel.is_cancelled() => " Cancelling ".to_string(),
+        Some(group) => format!(" Syncing {} ({} projects) ", group, task.project_count()),
+        None => " Syncing ".to_string(),
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Styles::border_focused())
+        .title(title);
+    let inner = block.inner(popup);
+    f.render_widget(Clear, popup);
+    f.render_widget(block, popup);
+    
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Length(1), Constraint::Min(0)])
+        .split(inner);
+    
+    let gauge = Gauge::default()
+        .gauge_style(Styles::border_focused())
+        .ratio(snapshot.ratio())
+        .label(format!("{} / {} files", snapshot.done, snapshot.total));
+    f.render_widget(gauge, rows[0]);
+    
+    let mut rate = format!(
+        "{} written · {}/s",
+        format_size(snapshot.bytes),
+        format_size(snapshot.throughput()),
+    );
+    if task.max_bytes_per_sec > 0 {
+        rate.push_str(&format!(" (cap {}/s)", format_size(task.max_bytes_per_sec)));
+    }
+    f.render_widget(Paragraph::new(Line::from(rate)), rows[1]);
+    
+    registry.register_layer(Some(HWND_SYNC_PROGRESS), popup, 1);
+}
+
+/// Handle input while a sync runs: Esc (or q) cancels it once the files in flight finish
+pub fn handle_sync_progress_event(app: &mut App, event: Event, _registry: &RectRegistry) {
+    if let AppEvent::Back | AppEvent::Quit = EventHandler::handle(event) {
+        app.request_cancel_sync();
+    }
+}
+
+/// Render the progress of the first scan of the launch over `area`, in place of the dashboard:
+/// the projects scanned so far, the files walked in each and the time taken
+pub fn render_startup_scan(f: &mut Frame, app: &App, area: Rect) {
+    let Some(task) = &app.scan_task else {
+        return;
+    };
+    let projects = task.progress.projects();
+    let done = projects.iter().filter(|project| project.state == ProjectScanState::Done).count();
+    let walked: usize = projects.iter().map(|project| project.walked).sum();
+    let popup = centered_rect(60, 50, area);
+    
+    let title = match &app.active_group {
+        _ if task.cancel.is_cancelled() => " Cancelling ".to_string(),
+        Some(group) => format!(" Scanning {} ", group),
+        None => format!(" Scanning {} ", app.project_name()),
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Styles::border_focused())
+        .title(title);
+    let inner = block.inner(popup);
+    f.render_widget(Clear, popup);
+    f.render_widget(block, popup);
+    
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Length(1), Constraint::Min(0), Constraint::Length(1)])
+        .split(inner);
+    
+    let ratio = if projects.is_empty() { 0.0 } else { done as f64 / projects.len() as f64 };
+    let gauge = Gauge::default()
+        .gauge_style(Styles::border_focused())
+        .ratio(ratio)
+        .label(format!("{} / {} projects", done, projects.len()));
+    f.render_widget(gauge, rows[0]);
+    
+    let summary = format!("{} files walked · {}", walked, format_duration(task.started.elapsed()));
+    f.render_widget(Paragraph::new(Line::from(summary)), rows[1]);
+    
+    let lines: Vec<Line> = projects
+        .iter()
+        .map(|project| {
+            let (icon, style) = match project.state {
+                ProjectScanState::Done => ("✓", Styles::status_added()),
+                ProjectScanState::Scanning => ("…", Styles::status_modified()),
+                ProjectScanState::Waiting => (" ", Styles::status_unchanged()),
+            };
+            let walked = match project.state {
+                ProjectScanState::Waiting => "waiting".to_string(),
+                _ => format!("{} files", project.walked),
+            };
+            Line::from(vec![
+                Span::styled(format!("{} ", icon), style),
+                Span::styled(project.name.clone(), Styles::list_normal()),
+                Span::styled(format!("  {}", walked), Styles::status_unchanged()),
+            ])
+        })
+        .collect();
+    f.render_widget(Paragraph::new(lines), rows[2]);
+    
+    let help = "Esc: Cancel and open the lists | q: Quit";
+    f.render_widget(Paragraph::new(Line::from(help)).style(Styles::status_unchanged()), rows[3]);
+}
+
+/// Handle input on the startup screen: Esc cancels the scan (the lists open empty), q quits
+pub fn handle_startup_scan_event(app: &mut App, event: Event, _registry: &RectRegistry) {
+    match EventHandler::handle(event) {
+        AppEvent::Back => app.cancel_scan(),
+        AppEvent::Quit => app.quit(),
+        _ => {}
+    }
+}
+
+/// Render the stall prompt over `area` (above the sync progress) and register it as an overlay layer
+pub fn render_stall_prompt(f: &mut Frame, app: &App, area: Rect, registry: &mut RectRegistry) {
+    let Some(prompt) = &app.stall_prompt else {
+        return;
+    };
+    let (name, cancel) = match prompt.operation {
+        WatchedOperation::Sync => ("The sync", "c: Cancel the sync"),
+        WatchedOperation::Scan => ("The background scan", "c: Cancel the scan"),
+    };
+    let waited = app
+        .watchdog_of(prompt.operation)
+        .and_then(|watchdog| watchdog.stalled(std::time::Duration::ZERO))
+        .map(|stall| format_duration(stall.elapsed))
+        .unwrap_or_default();
+    
+    let popup = centered_rect(60, 30, area);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Styles::status_modified())
+        .title(" No Progress ");
+    let text = vec![
+        Line::from(format!("{} made no progress for {} while working on:", name, waited)),
+        Line::from(Span::styled(prompt.path.display().to_string(), Styles::status_modified())),
+        Line::default(),
+        Line::from(format!("w/Esc: Keep waiting | s: Skip this path | {}", cancel)),
+    ];
+    f.render_widget(Clear, popup);
+    f.render_widget(Paragraph::new(text).wrap(Wrap { trim: true }).block(block), popup);
+    
+    registry.register_layer(Some(HWND_STALL_PROMPT), popup, 2);
+}
+
+/// Handle input while the stall prompt is open: keep waiting, skip the stuck path, or cancel
+pub fn handle_stall_prompt_event(app: &mut App, event: Event, _registry: &RectRegistry) {
+    let Event::Key(key) = event else {
+        return;
+    };
+    if key.kind != KeyEventKind::Press {
+        return;
+    }
+    match key.code {
+        KeyCode::Char('w') | KeyCode::Esc => app.resolve_stall(StallChoice::Wait),
+        KeyCode::Char('s') => app.resolve_stall(StallChoice::Skip),
+        KeyCode::Char('c') => app.resolve_stall(StallChoice::Cancel),
+        _ => {}
+    }
+}
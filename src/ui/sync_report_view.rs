@@ -0,0 +1,190 @@
+// Sync Report View
+// Popup listing each file of the last sync with its outcome, size and duration
+
+use crossterm::event::Event;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+use tui_components::{centered_rect, ColumnWidth, ListCell, ListColumn, ListPanelStyles, ListRow, RectRegistry};
+
+use crate::core::{App, AppEvent, EventHandler, ProjectIdentity};
+use crate::operations::{FailureKind, FileOutcome, OutcomeStatus};
+use crate::utilities::{format_duration, format_size};
+use super::Styles;
+
+/// Registry handle name (HWND) of the sync report popup
+pub const HWND_SYNC_REPORT: &str = "hwndSyncReport";
+
+/// Sync the report list panel with the open report (columns, styles, title)
+pub fn update_sync_report(app: &mut App) {
+    let Some(report) = app.sync_report.as_mut() else {
+        return;
+    };
+    if report.list.columns.is_empty() {
+        report.list.columns = vec![
+            ListColumn::new("", ColumnWidth::Fixed(1)),
+            ListColumn::new("", ColumnWidth::Fixed(1)),
+            ListColumn::new("Path", ColumnWidth::Fill),
+            ListColumn::new("Size", ColumnWidth::Fixed(8)).align_right(),
+            ListColumn::new("Time", ColumnWidth::Fixed(8)).align_right(),
+        ];
+        report.list.empty_message = "No files".to_string();
+        report.list.styles = ListPanelStyles {
+            border_focused: Styles::border_focused(),
+            title_focused: Styles::title_focused(),
+            selected_focused: Styles::list_selected_focused(),
+            ..ListPanelStyles::default()
+        };
+    }
+    
+    let result = &report.result;
+    report.list.title = format!(
+        "{}: {} synced, {} failed, {} skipped, {}",
+        if result.cancelled { "Sync Cancelled" } else { "Sync Results" },
+        result.synced,
+        result.failed,
+        result.skipped,
+        format_size(result.bytes()),
+    );
+}
+
+/// Render the sync report over `area` and register it as an overlay layer (dims the rest)
+/// The selected file's full error is shown under the list
+pub fn render_sync_report(f: &mut Frame, app: &App, area: Rect, registry: &mut RectRegistry) {
+    let Some(report) = &app.sync_report else {
+        return;
+    };
+    let popup = centered_rect(80, 80, area);
+    
+    // A sync across a project group gets a block of counts per project
+    let totals = report.project_totals();
+    let totals_height = if totals.len() > 1 { totals.len() as u16 + 2 } else { 0 };
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(totals_height), Constraint::Length(5)])
+        .split(popup);
+    
+    f.render_widget(Clear, popup);
+    let outcomes = &report.result.outcomes;
+    let row = |idx: usize| {
+        let project = report.entries.get(idx).map(|entry| app.project_identity(&entry.project));
+        outcome_row(&outcomes[idx], project.as_ref())
+    };
+    report.list.render(f, chunks[0], outcomes.len(), row, true);
+    
+    let detail = match report.selected() {
+        Some(FileOutcome { status: OutcomeStatus::Failed { error, kind }, retries, .. }) => {
+            let label = match kind {
+                FailureKind::Transient => format!("Still locked or busy after {} retries (r retries again): ", retries),
+                FailureKind::Permanent => "Permanent error: ".to_string(),
+            };
+            Line::from(vec![
+                Span::styled(label, Styles::status_modified()),
+                Span::styled(error.clone(), Styles::status_deleted()),
+            ])
+        }
+        Some(outcome) => {
+            let retried = match outcome.retries {
+                0 => String::new(),
+                n => format!(" after {} retries", n),
+            };
+            Line::from(format!("{}: {}{}", outcome.path.display(), status_label(&outcome.status), retried))
+        }
+        None => Line::default(),
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Styles::border_focused())
+        .title(" Details ");
+    f.render_widget(Paragraph::new(detail).wrap(Wrap { trim: true }).block(block), chunks[2]);
+    
+    if totals.len() > 1 {
+        let lines: Vec<Line> = totals
+            .iter()
+            .map(|totals| {
+                let project = app.project_identity(&totals.project);
+                Line::from(vec![
+                    Span::styled(format!("{} ", project.icon), Styles::project_icon(&project.color)),
+                    Span::raw(format!(
+                        "{}: {} synced, {} failed, {} skipped",
+                        totals.project, totals.synced, totals.failed, totals.skipped,
+                    )),
+                ])
+            })
+            .collect();
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Styles::border_focused())
+            .title(" By Project ");
+        f.render_widget(Paragraph::new(lines).block(block), chunks[1]);
+    }
+    
+    registry.register_layer(Some(HWND_SYNC_REPORT), popup, 1);
+}
+
+/// Handle input while the sync report is open: scroll, retry the failed files, or close
+pub fn handle_sync_report_event(app: &mut App, event: Event, _registry: &RectRegistry) {
+    let Some(report) = app.sync_report.as_mut() else {
+        return;
+    };
+    
+    let count = report.result.outcomes.len();
+    let list = &mut report.list;
+    match EventHandler::handle(event) {
+        AppEvent::SelectPrevious | AppEvent::ScrollUp(_) => list.select_previous(),
+        AppEvent::SelectNext | AppEvent::ScrollDown(_) => list.select_next(count),
+        AppEvent::PageUp => list.page_up(),
+        AppEvent::PageDown => list.page_down(count),
+        AppEvent::SelectFirst => list.select_first(),
+        AppEvent::SelectLast => list.select_last(count),
+        AppEvent::Refresh => app.retry_failed_sync(),
+        AppEvent::Back | AppEvent::ToggleSideBySide | AppEvent::Quit => app.close_sync_report(),
+        _ => {}
+    }
+}
+
+fn status_label(status: &OutcomeStatus) -> &'static str {
+    match status {
+        OutcomeStatus::Copied => "copied",
+        OutcomeStatus::Cloned => "cloned (copy-on-write)",
+        OutcomeStatus::Linked => "hard linked to the source",
+        OutcomeStatus::Created => "directory created",
+        OutcomeStatus::Deleted => "deleted",
+        OutcomeStatus::Skipped => "skipped (unchanged, pinned or not propagated)",
+        OutcomeStatus::Failed { .. } => "failed",
+    }
+}
+
+/// Row of one outcome, marked with the icon of the entry's project
+fn outcome_row(outcome: &FileOutcome, project: Option<&ProjectIdentity>) -> ListRow {
+    let (icon, style) = match outcome.status {
+        OutcomeStatus::Copied | OutcomeStatus::Cloned => ("✓", Styles::status_added()),
+        OutcomeStatus::Linked => ("=", Styles::status_added()),
+        OutcomeStatus::Created => ("+", Styles::status_added()),
+        OutcomeStatus::Deleted => ("D", Styles::status_modified()),
+        OutcomeStatus::Skipped => ("-", Styles::status_unchanged()),
+        OutcomeStatus::Failed { kind: FailureKind::Transient, .. } => ("!", Styles::status_modified()),
+        OutcomeStatus::Failed { kind: FailureKind::Permanent, .. } => ("✗", Styles::status_deleted()),
+    };
+    let (size, time) = match outcome.status {
+        OutcomeStatus::Skipped => (String::new(), String::new()),
+        OutcomeStatus::Copied | OutcomeStatus::Cloned | OutcomeStatus::Linked => {
+            (format_size(outcome.bytes), format_duration(outcome.duration))
+        }
+        _ => (String::new(), format_duration(outcome.duration)),
+    };
+    let project_cell = match project {
+        Some(project) => ListCell::styled(project.icon.clone(), Styles::project_icon(&project.color)),
+        None => ListCell::styled("", Styles::list_normal()),
+    };
+    ListRow::new(vec![
+        project_cell,
+        ListCell::styled(icon, style),
+        ListCell::styled(outcome.path.display().to_string(), Styles::list_normal()),
+        ListCell::styled(size, Styles::status_unchanged()),
+        ListCell::styled(time, Styles::status_unchanged()),
+    ])
+}
@@ -0,0 +1,104 @@
+// Terminal Lifecycle
+// Raw mode / alternate screen setup with guaranteed restoration
+
+use anyhow::Result;
+use crossterm::{
+    event::{DisableMouseCapture, EnableMouseCapture},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{backend::CrosstermBackend, Terminal};
+use std::io::{stdout, Stdout};
+use std::ops::{Deref, DerefMut};
+use std::sync::Once;
+
+use crate::error::SyncManagerError;
+
+/// Terminal type used by the application
+pub type AppTerminal = Terminal<CrosstermBackend<Stdout>>;
+
+static PANIC_HOOK: Once = Once::new();
+
+/// RAII guard owning the TUI terminal
+/// Raw mode, the alternate screen and mouse capture are restored when the
+/// guard is dropped, including during unwinding after a panic
+pub struct TerminalGuard {
+    terminal: AppTerminal,
+}
+
+impl TerminalGuard {
+    /// Enter raw mode and the alternate screen, and install the panic hook
+    pub fn new() -> Result<Self> {
+        install_panic_hook();
+
+        enable_raw_mode().map_err(SyncManagerError::render)?;
+        let mut stdout = stdout();
+        if let Err(err) = execute!(stdout, EnterAlternateScreen, EnableMouseCapture) {
+            restore_terminal();
+            return Err(SyncManagerError::render(err).into());
+        }
+
+        let terminal = match Terminal::new(CrosstermBackend::new(stdout)) {
+            Ok(terminal) => terminal,
+            Err(err) => {
+                restore_terminal();
+                return Err(SyncManagerError::render(err).into());
+            }
+        };
+
+        Ok(Self { terminal })
+    }
+}
+
+impl Deref for TerminalGuard {
+    type Target = AppTerminal;
+
+    fn deref(&self) -> &Self::Target {
+        &self.terminal
+    }
+}
+
+impl DerefMut for TerminalGuard {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.terminal
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        restore_terminal();
+        let _ = self.terminal.show_cursor();
+    }
+}
+
+/// Restore the terminal to its normal state
+/// Safe to call more than once; every step is best-effort
+pub fn restore_terminal() {
+    let _ = disable_raw_mode();
+    let _ = execute!(stdout(), LeaveAlternateScreen, DisableMouseCapture);
+    let _ = execute!(stdout(), crossterm::cursor::Show);
+}
+
+/// Hand the terminal back to the user while `f` runs (e.g. an external editor), then
+/// re-enter raw mode and the alternate screen and schedule a full redraw
+pub fn suspend_terminal<T>(terminal: &mut AppTerminal, f: impl FnOnce() -> T) -> Result<T> {
+    restore_terminal();
+    let result = f();
+    
+    enable_raw_mode()?;
+    execute!(stdout(), EnterAlternateScreen, EnableMouseCapture)?;
+    terminal.clear()?;
+    Ok(result)
+}
+
+/// Install a panic hook that restores the terminal before the panic message is printed
+/// Without this the message would be written to the alternate screen and lost
+pub fn install_panic_hook() {
+    PANIC_HOOK.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            restore_terminal();
+            previous(info);
+        }));
+    });
+}
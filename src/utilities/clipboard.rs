@@ -0,0 +1,30 @@
+// Clipboard
+// System clipboard access for the copy-path actions
+
+use anyhow::{Context, Result};
+use std::fmt;
+
+/// Lazily opened system clipboard
+/// The handle is kept for the life of the app: on X11 the copied text is only served while
+/// its owner is alive, so a clipboard dropped right after copying would lose it
+#[derive(Default)]
+pub struct Clipboard {
+    inner: Option<arboard::Clipboard>,
+}
+
+impl fmt::Debug for Clipboard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Clipboard").field("open", &self.inner.is_some()).finish()
+    }
+}
+
+impl Clipboard {
+    /// Put `text` on the clipboard, opening it on first use
+    pub fn copy(&mut self, text: &str) -> Result<()> {
+        let clipboard = match &mut self.inner {
+            Some(clipboard) => clipboard,
+            None => self.inner.insert(arboard::Clipboard::new().context("Clipboard is not available")?),
+        };
+        clipboard.set_text(text).context("Failed to copy to the clipboard")
+    }
+}
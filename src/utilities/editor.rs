@@ -0,0 +1,102 @@
+// External Editor
+// Resolves the editor command and runs it on a file, and rewrites lines for the edits made in
+// the side-by-side view (inline edits, hunks taken from the other side)
+
+use anyhow::{bail, Context, Result};
+use std::path::Path;
+use std::process::Command;
+
+/// Editor used when neither the config nor the environment names one
+const FALLBACK_EDITOR: &str = "vi";
+
+/// Editor command: the configured one, else $VISUAL, else $EDITOR, else vi
+pub fn editor_command(configured: &str) -> String {
+    resolve_editor(configured, std::env::var("VISUAL").ok(), std::env::var("EDITOR").ok())
+}
+
+fn resolve_editor(configured: &str, visual: Option<String>, editor: Option<String>) -> String {
+    [Some(configured.to_string()), visual, editor]
+        .into_iter()
+        .flatten()
+        .map(|command| command.trim().to_string())
+        .find(|command| !command.is_empty())
+        .unwrap_or_else(|| FALLBACK_EDITOR.to_string())
+}
+
+/// Run `command` (program plus arguments, e.g. "code --wait") on `path` and wait for it to exit
+/// The caller must hand the terminal over first (see `TerminalGuard::suspend`)
+pub fn open_in_editor(command: &str, path: &Path) -> Result<()> {
+    let mut words = command.split_whitespace();
+    let Some(program) = words.next() else {
+        bail!("No editor command configured");
+    };
+    
+    let status = Command::new(program)
+        .args(words)
+        .arg(path)
+        .status()
+        .with_context(|| format!("Failed to run `{}`", command))?;
+    if !status.success() {
+        bail!("`{}` exited with {}", command, status);
+    }
+    Ok(())
+}
+
+/// Line `index` of `content`, without its line ending
+pub fn line_at(content: &str, index: usize) -> Option<&str> {
+    content.lines().nth(index)
+}
+
+/// `content` with line `index` replaced by `line`, keeping its line ending (\n or \r\n)
+/// None if there is no such line
+pub fn replace_line(content: &str, index: usize, line: &str) -> Option<String> {
+    let mut start = 0;
+    for _ in 0..index {
+        start += content[start..].find('\n')? + 1;
+    }
+    if start >= content.len() {
+        return None;
+    }
+    let end = content[start..].find('\n').map_or(content.len(), |offset| start + offset);
+    let end = if content[..end].ends_with('\r') { end - 1 } else { end };
+    Some(format!("{}{}{}", &content[..start], line, &content[end..]))
+}
+
+/// `lines` as the text of a file in place of `original`, with its line ending (\r\n if it has
+/// any, else \n) and its final newline, if any
+pub fn join_lines(lines: &[String], original: &str) -> String {
+    let ending = if original.contains("\r\n") { "\r\n" } else { "\n" };
+    let mut text = lines.join(ending);
+    if original.ends_with('\n') && !text.is_empty() {
+        text.push_str(ending);
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_editor_precedence() {
+        let env = |s: &str| Some(s.to_string());
+        assert_eq!(resolve_editor("hx", env("code --wait"), env("nano")), "hx");
+        assert_eq!(resolve_editor("  ", env("code --wait"), env("nano")), "code --wait");
+        assert_eq!(resolve_editor("", None, env("nano")), "nano");
+        assert_eq!(resolve_editor("", env(""), None), FALLBACK_EDITOR);
+    }
+    
+    #[test]
+    fn test_replace_line_keeps_line_endings() {
+        let content = "first\r\nsecond\r\nthird";
+        assert_eq!(line_at(content, 1), Some("second"));
+        assert_eq!(replace_line(content, 1, "2nd").as_deref(), Some("first\r\n2nd\r\nthird"));
+        assert_eq!(replace_line(content, 2, "last").as_deref(), Some("first\r\nsecond\r\nlast"));
+        assert_eq!(replace_line("a\nb\n", 1, "B").as_deref(), Some("a\nB\n"));
+        assert_eq!(replace_line("a\nb\n", 2, "c"), None);
+        
+        let lines = ["one".to_string(), "two".to_string()];
+        assert_eq!(join_lines(&lines, "x\r\ny\r\n"), "one\r\ntwo\r\n");
+        assert_eq!(join_lines(&lines, "x"), "one\ntwo");
+    }
+}
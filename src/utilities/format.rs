@@ -0,0 +1,110 @@
+// Formatting Utilities
+// Human-readable sizes, ages and durations for list columns
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Format a byte count as a short human-readable size (e.g. "512 B", "1.5 KB")
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    
+    if value < 10.0 {
+        format!("{:.1} {}", value, UNITS[unit])
+    } else {
+        format!("{:.0} {}", value, UNITS[unit])
+    }
+}
+
+/// Format the time elapsed since `time` as a short age (e.g. "now", "5m", "3h", "2d")
+pub fn format_age(time: SystemTime, now: SystemTime) -> String {
+    let secs = now.duration_since(time).unwrap_or(Duration::ZERO).as_secs();
+    
+    match secs {
+        0..=59 => "now".to_string(),
+        60..=3_599 => format!("{}m", secs / 60),
+        3_600..=86_399 => format!("{}h", secs / 3_600),
+        86_400..=31_535_999 => format!("{}d", secs / 86_400),
+        _ => format!("{}y", secs / 31_536_000),
+    }
+}
+
+/// Format a point in time as a UTC timestamp (e.g. "2024-03-09 14:05:31 UTC")
+pub fn format_timestamp(time: SystemTime) -> String {
+    let secs = time.duration_since(UNIX_EPOCH).unwrap_or(Duration::ZERO).as_secs();
+    let (days, rem) = (secs / 86_400, secs % 86_400);
+    
+    // Civil date from days since 1970-01-01 (proleptic Gregorian calendar)
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC",
+        year,
+        month,
+        day,
+        rem / 3_600,
+        rem % 3_600 / 60,
+        rem % 60,
+    )
+}
+
+/// Format a short duration for list columns (e.g. "3 ms", "1.2 s")
+pub fn format_duration(duration: Duration) -> String {
+    let millis = duration.as_millis();
+    if millis < 1_000 {
+        format!("{} ms", millis)
+    } else {
+        format!("{:.1} s", duration.as_secs_f64())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KB");
+        assert_eq!(format_size(20 * 1024 * 1024), "20 MB");
+    }
+    
+    #[test]
+    fn test_format_age() {
+        let now = SystemTime::now();
+        assert_eq!(format_age(now, now), "now");
+        assert_eq!(format_age(now - Duration::from_secs(300), now), "5m");
+        assert_eq!(format_age(now - Duration::from_secs(3 * 86_400), now), "3d");
+        // Future timestamps (clock skew) read as "now"
+        assert_eq!(format_age(now + Duration::from_secs(60), now), "now");
+    }
+    
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_micros(2_500)), "2 ms");
+        assert_eq!(format_duration(Duration::from_millis(1_300)), "1.3 s");
+    }
+    
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(UNIX_EPOCH), "1970-01-01 00:00:00 UTC");
+        assert_eq!(format_timestamp(UNIX_EPOCH + Duration::from_secs(1_709_993_131)), "2024-03-09 14:05:31 UTC");
+    }
+}
@@ -0,0 +1,50 @@
+// Log File
+// Append-only diagnostics log; the TUI owns the terminal, so nothing can be printed while it runs
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Size past which the log is rotated to `<name>.old` before the next line
+const MAX_LOG_BYTES: u64 = 1024 * 1024;
+
+/// Append a timestamped line to the log at `path`
+/// Logging is best effort: failures are ignored so they never break the action being logged
+pub fn append_log(path: &Path, message: &str) {
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    if fs::metadata(path).is_ok_and(|meta| meta.len() > MAX_LOG_BYTES) {
+        let _ = fs::rename(path, path.with_extension("log.old"));
+    }
+    
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|age| age.as_secs())
+        .unwrap_or_default();
+    if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(path) {
+        let _ = writeln!(file, "[{}] {}", secs, message);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_append_log_adds_lines() {
+        let dir = std::env::temp_dir().join(format!("sync-manager-log-{}", std::process::id()));
+        let path = dir.join("test.log");
+
+        append_log(&path, "first");
+        append_log(&path, "second");
+        let content = fs::read_to_string(&path).unwrap();
+        let _ = fs::remove_dir_all(&dir);
+
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].ends_with("] first"));
+        assert!(lines[1].ends_with("] second"));
+    }
+}
@@ -1,8 +1,20 @@
 // Utilities module
 // Helper functions and tools
 
+pub mod clipboard;
+pub mod editor;
+pub mod format;
+pub mod host;
+pub mod log;
 pub mod paths;
 pub mod patterns;
+pub mod shell;
 
+pub use clipboard::Clipboard;
+pub use editor::{editor_command, join_lines, line_at, open_in_editor, replace_line};
+pub use format::{format_age, format_duration, format_size, format_timestamp};
+pub use host::hostname;
+pub use log::append_log;
 pub use paths::{normalize_path, resolve_path};
-pub use patterns::{matches_pattern, PatternMatcher};
+pub use patterns::{matches_pattern, pattern_problem, ExcludeMatcher, PatternMatcher};
+pub use shell::{run_shell, suspend_process};
@@ -1,6 +1,7 @@
 // Pattern Matching Utilities
 // Glob-like pattern matching for file exclusions
 
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
 use std::path::Path;
 
 /// Check if a path matches a pattern
@@ -8,9 +9,9 @@ pub fn matches_pattern(path: &Path, pattern: &str) -> bool {
     let path_str = path.to_string_lossy().to_lowercase();
     let pattern = pattern.to_lowercase();
     
-    if pattern.starts_with('*') {
+    if let Some(suffix) = pattern.strip_prefix('*') {
         // Wildcard at start: match suffix
-        path_str.ends_with(&pattern[1..])
+        path_str.ends_with(suffix)
     } else if pattern.ends_with('*') {
         // Wildcard at end: match prefix
         path_str.starts_with(&pattern[..pattern.len() - 1])
@@ -28,6 +29,20 @@ pub fn matches_pattern(path: &Path, pattern: &str) -> bool {
     }
 }
 
+/// Why a pattern won't match the way it reads, or None if it is fine
+/// Only a single `*` (leading, trailing or in the middle) is supported
+pub fn pattern_problem(pattern: &str) -> Option<&'static str> {
+    if pattern.trim().is_empty() {
+        Some("is empty and matches every path")
+    } else if pattern.contains(['?', '[', ']', '{', '}']) {
+        Some("uses ?, [..] or {..}, which are matched literally")
+    } else if pattern.matches('*').count() > 1 {
+        Some("has more than one *, so it is matched as a plain substring")
+    } else {
+        None
+    }
+}
+
 /// Pattern matcher for file exclusions
 pub struct PatternMatcher {
     patterns: Vec<String>,
@@ -61,6 +76,47 @@ impl Default for PatternMatcher {
     }
 }
 
+/// Exclude patterns compiled into one matcher, matching like the diff engine: `*x` matches
+/// paths ending in x, anything else matches anywhere in the path (both case-insensitive)
+#[derive(Debug, Clone)]
+pub struct ExcludeMatcher {
+    patterns: Vec<String>,
+    set: GlobSet,
+}
+
+impl ExcludeMatcher {
+    /// Compile `patterns`; the rest of each pattern after a leading `*` is literal
+    pub fn new(patterns: &[String]) -> Self {
+        let mut builder = GlobSetBuilder::new();
+        let mut compiled = Vec::new();
+        for pattern in patterns {
+            let glob = match pattern.strip_prefix('*') {
+                Some(suffix) => format!("*{}", globset::escape(suffix)),
+                None => format!("*{}*", globset::escape(pattern)),
+            };
+            // Escaped literals between single `*`s always compile; kept in step with the set's indices
+            if let Ok(glob) = GlobBuilder::new(&glob).case_insensitive(true).build() {
+                builder.add(glob);
+                compiled.push(pattern.clone());
+            }
+        }
+        Self {
+            patterns: compiled,
+            set: builder.build().unwrap_or_else(|_| GlobSet::empty()),
+        }
+    }
+    
+    pub fn is_match(&self, path: &Path) -> bool {
+        self.set.is_match(path)
+    }
+    
+    /// First pattern (in the order given) that matches the path
+    pub fn first_match(&self, path: &Path) -> Option<&str> {
+        let index = self.set.matches(path).into_iter().min()?;
+        Some(&self.patterns[index])
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -83,6 +139,15 @@ mod tests {
         assert!(!matches_pattern(Path::new("path/to/src/file"), "node_modules"));
     }
     
+    #[test]
+    fn test_pattern_problems() {
+        assert_eq!(pattern_problem("*.swp"), None);
+        assert_eq!(pattern_problem("test_*.rs"), None);
+        assert!(pattern_problem(" ").is_some());
+        assert!(pattern_problem("**/*.md").is_some());
+        assert!(pattern_problem("file?.txt").is_some());
+    }
+    
     #[test]
     fn test_pattern_matcher() {
         let matcher = PatternMatcher::new(vec![
@@ -96,4 +161,16 @@ mod tests {
         assert!(matcher.should_exclude(Path::new(".git/config")));
         assert!(!matcher.should_exclude(Path::new("src/main.rs")));
     }
+    
+    #[test]
+    fn test_exclude_matcher_matches_like_substrings() {
+        let patterns: Vec<String> = ["*.SWP", "node_modules", "[draft]", ".git"].iter().map(|p| p.to_string()).collect();
+        let matcher = ExcludeMatcher::new(&patterns);
+        
+        assert_eq!(matcher.first_match(Path::new("/ws/notes/a.swp")), Some("*.SWP"));
+        assert_eq!(matcher.first_match(Path::new("/ws/app/node_modules/x/y.js")), Some("node_modules"));
+        assert_eq!(matcher.first_match(Path::new("/ws/docs/[Draft] plan.md")), Some("[draft]"));
+        assert_eq!(matcher.first_match(Path::new("/ws/.gitignore")), Some(".git"));
+        assert!(!matcher.is_match(Path::new("/ws/docs/d.md")));
+    }
 }
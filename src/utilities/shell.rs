@@ -0,0 +1,45 @@
+// Shell Escape
+// Interactive shell and job-control suspension while the TUI is set aside
+
+use anyhow::{Context, Result};
+use std::path::Path;
+use std::process::Command;
+
+/// The user's interactive shell: $SHELL on Unix, %COMSPEC% on Windows
+pub fn shell_program() -> String {
+    let (var, fallback) = if cfg!(windows) { ("COMSPEC", "cmd.exe") } else { ("SHELL", "sh") };
+    std::env::var(var)
+        .ok()
+        .filter(|shell| !shell.trim().is_empty())
+        .unwrap_or_else(|| fallback.to_string())
+}
+
+/// Run an interactive shell in `dir` and wait for it to exit
+/// The caller must hand the terminal over first (see `suspend_terminal`)
+/// The shell's exit status is that of the last command typed in it, so it isn't an error
+pub fn run_shell(dir: &Path) -> Result<()> {
+    let shell = shell_program();
+    Command::new(&shell)
+        .current_dir(dir)
+        .status()
+        .with_context(|| format!("Failed to start {}", shell))?;
+    Ok(())
+}
+
+/// Stop the process like Ctrl+Z does in a shell; returns once it is resumed (`fg`)
+/// Without job control (Windows) an interactive shell is run instead
+#[cfg(unix)]
+pub fn suspend_process(_dir: &Path) -> Result<()> {
+    // SAFETY: raise only sends a signal to the calling process
+    if unsafe { libc::raise(libc::SIGTSTP) } != 0 {
+        anyhow::bail!("Failed to suspend: {}", std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Stop the process like Ctrl+Z does in a shell; returns once it is resumed (`fg`)
+/// Without job control (Windows) an interactive shell is run instead
+#[cfg(not(unix))]
+pub fn suspend_process(dir: &Path) -> Result<()> {
+    run_shell(dir)
+}